@@ -1,10 +1,10 @@
 //! ACP (Agent Client Protocol) integration module
 //! Handles communication with ACP-compatible agents via stdio
 
-use crate::api::ai::conversation::extract_tool_result;
+use crate::api::ai::conversation::{extract_tool_result, strip_mcp_tool_call_hints};
 use crate::api::ai::events::{ConversationEvent, MCPToolCallUpdateEvent, MessageUpdateEvent};
 use crate::db::assistant_db::AssistantModelConfig;
-use crate::db::conversation_db::ConversationDatabase;
+use crate::db::conversation_db::{ConversationDatabase, ACP_SESSION_STATUS_STALE};
 use crate::db::llm_db::LLMProviderConfig;
 use crate::db::mcp_db::MCPDatabase;
 use crate::errors::AppError;
@@ -19,11 +19,12 @@ use crate::mcp::builtin_mcp::operation::{
     },
 };
 use crate::state::activity_state::ConversationActivityManager;
-use crate::utils::window_utils::send_conversation_event_to_chat_windows;
+use crate::utils::window_utils::{
+    emit_conversation_event, send_conversation_event_to_chat_windows,
+};
 use agent_client_protocol::{
     self as acp, Agent as _, Client as AcpClient, ClientSideConnection, ToolCallLocation,
 };
-use regex::Regex;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -122,6 +123,11 @@ impl AcpSessionHandle {
             .send(AcpSessionCommand::Prompt { message_id, prompt, window })
             .map_err(|_| AppError::UnknownError("ACP session closed".to_string()))
     }
+
+    /// 会话任务已退出（Agent 进程结束或初始化失败）
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
 }
 
 /// Resolve ACP CLI command to its full path
@@ -245,6 +251,15 @@ fn permission_option_kind_to_string(kind: &acp::PermissionOptionKind) -> String
     }
 }
 
+/// 权限授权的匹配键：工具类型 + 标题（标题通常包含命令或文件路径）
+fn permission_grant_key(kind: Option<&str>, title: Option<&str>) -> Option<String> {
+    let title = title.map(str::trim).filter(|t| !t.is_empty());
+    if kind.is_none() && title.is_none() {
+        return None;
+    }
+    Some(format!("{}:{}", kind.unwrap_or("Other"), title.unwrap_or_default()))
+}
+
 fn extract_string_field(value: &serde_json::Value, keys: &[&str]) -> Option<String> {
     for key in keys {
         if let Some(v) = value.get(*key) {
@@ -349,14 +364,6 @@ fn extract_tool_response_from_meta(meta: Option<&acp::Meta>) -> Option<String> {
     response_value.map(|v| serde_json::to_string(v).unwrap_or_else(|_| v.to_string()))
 }
 
-fn strip_mcp_tool_call_hints(content: &str) -> String {
-    let re = Regex::new(r"<!--\s*MCP_TOOL_CALL:.*?-->").ok();
-    match re {
-        Some(re) => re.replace_all(content, "").to_string(),
-        None => content.to_string(),
-    }
-}
-
 fn build_acp_history_prompt(app_handle: &tauri::AppHandle, conversation_id: i64) -> Option<String> {
     let db = ConversationDatabase::new(app_handle).ok()?;
     let messages = db.message_repo().ok()?.list_by_conversation_id(conversation_id).ok()?;
@@ -808,12 +815,8 @@ impl AcpClient for AcpTauriClient {
                     .unwrap(),
                 };
 
-                let window = self.window.lock().await.clone();
-                let event_name = format!("conversation_event_{}", self.conversation_id);
-                match window.emit(&event_name, event) {
-                    Ok(_) => debug!("ACP: Emitted AgentMessageChunk event"),
-                    Err(e) => error!("ACP: Failed to emit AgentMessageChunk event: {}", e),
-                }
+                self.emit_event(event).await;
+                debug!("ACP: Emitted AgentMessageChunk event");
             }
 
             // Agent internal reasoning (thoughts) - accumulate and emit as reasoning message type
@@ -1459,6 +1462,35 @@ impl AcpClient for AcpTauriClient {
 
         let kind = args.tool_call.fields.kind.map(|k| format!("{:?}", k));
 
+        // 会话恢复后沿用用户之前"始终允许"的授权，Agent 进程重启后不会记得这些选择
+        let grant_key =
+            permission_grant_key(kind.as_deref(), args.tool_call.fields.title.as_deref());
+        if let Some(grant_key) = &grant_key {
+            let granted = ConversationDatabase::new(&self.app_handle)
+                .ok()
+                .and_then(|db| db.get_acp_session(self.conversation_id).ok().flatten())
+                .is_some_and(|record| record.permission_grants.contains(grant_key));
+            let allow_option = options
+                .iter()
+                .find(|o| o.kind == "allow_always")
+                .or_else(|| options.iter().find(|o| o.kind == "allow_once"));
+            if let (true, Some(option)) = (granted, allow_option) {
+                info!("ACP permission auto-approved by stored grant: {}", grant_key);
+                state.remove_request(&request_id).await;
+                return Ok(acp::RequestPermissionResponse::new(
+                    acp::RequestPermissionOutcome::Selected(acp::SelectedPermissionOutcome::new(
+                        acp::PermissionOptionId::new(option.option_id.clone()),
+                    )),
+                ));
+            }
+        }
+
+        let allow_always_option_ids = options
+            .iter()
+            .filter(|o| o.kind == "allow_always")
+            .map(|o| o.option_id.clone())
+            .collect::<Vec<_>>();
+
         let event = AcpPermissionRequestEvent {
             request_id: request_id.clone(),
             conversation_id: Some(self.conversation_id),
@@ -1479,6 +1511,20 @@ impl AcpClient for AcpTauriClient {
 
         match rx.await {
             Ok(AcpPermissionDecision::Selected(option_id)) => {
+                if let Some(grant_key) =
+                    grant_key.filter(|_| allow_always_option_ids.contains(&option_id))
+                {
+                    match ConversationDatabase::new(&self.app_handle) {
+                        Ok(db) => {
+                            if let Err(e) =
+                                db.add_acp_permission_grant(self.conversation_id, &grant_key)
+                            {
+                                error!(error = %e, "ACP failed to persist permission grant");
+                            }
+                        }
+                        Err(e) => error!(error = %e, "ACP failed to open conversation db"),
+                    }
+                }
                 Ok(acp::RequestPermissionResponse::new(acp::RequestPermissionOutcome::Selected(
                     acp::SelectedPermissionOutcome::new(acp::PermissionOptionId::new(option_id)),
                 )))
@@ -1571,6 +1617,15 @@ impl AcpClient for AcpTauriClient {
             run_in_background: Some(true),
         };
 
+        if let Err(e) = self
+            .permission_manager
+            .check_command(&self.operation_state, &full_command, self.get_conversation_id())
+            .await
+        {
+            tracing::warn!("Terminal command denied: {}", e);
+            return Err(acp::Error::internal_error().data(e));
+        }
+
         match BashOperations::execute_bash(&self.operation_state, request).await {
             Ok(response) => {
                 let bash_id = response.bash_id.ok_or_else(|| {
@@ -1814,8 +1869,7 @@ async fn run_acp_session(
             })
             .unwrap(),
         };
-        let event_name = format!("conversation_event_{}", conversation_id);
-        let _ = window.emit(&event_name, event);
+        emit_conversation_event(window, conversation_id, event);
     };
 
     // Create operation state and permission manager for this session
@@ -1925,6 +1979,7 @@ async fn run_acp_session(
         permission_manager,
     );
     let client_handle = client_impl.clone();
+    let cleanup_app_handle = app_handle.clone();
 
     let local_set = tokio::task::LocalSet::new();
     let session_result = local_set
@@ -2013,10 +2068,29 @@ async fn run_acp_session(
             let mut session_id: Option<String> = None;
             let mut should_build_history_fallback = false;
 
-            if let Some(stored_session_id) =
-                conversation_db.get_acp_session_id(conversation_id)?
-            {
-                if init_response.agent_capabilities.load_session {
+            let working_directory = acp_config.working_directory.display().to_string();
+            let stored_session = conversation_db.get_acp_session(conversation_id)?;
+
+            if let Some(stored) = stored_session {
+                let stored_session_id = stored.session_id;
+                let same_working_directory = stored
+                    .working_directory
+                    .as_deref()
+                    .map_or(true, |dir| dir == working_directory);
+
+                if stored.status == ACP_SESSION_STATUS_STALE {
+                    info!(
+                        "ACP: Stored session is stale, creating new session (conversation_id={}, session_id={})",
+                        conversation_id, stored_session_id
+                    );
+                    should_build_history_fallback = true;
+                } else if !same_working_directory {
+                    info!(
+                        "ACP: Working directory changed since last session, creating new session (conversation_id={})",
+                        conversation_id
+                    );
+                    should_build_history_fallback = true;
+                } else if init_response.agent_capabilities.load_session {
                     info!(
                         "ACP: Loading existing session (conversation_id={}, session_id={})",
                         conversation_id, stored_session_id
@@ -2032,16 +2106,17 @@ async fn run_acp_session(
 
                     match load_result {
                         Ok(_) => {
-                            session_id = Some(stored_session_id);
-                            conversation_db.upsert_acp_session_id(
+                            conversation_db.upsert_acp_session(
                                 conversation_id,
-                                session_id.as_deref().unwrap_or_default(),
+                                &stored_session_id,
+                                &working_directory,
+                                &acp_config.cli_command,
                             )?;
                             info!(
                                 "ACP: session/load succeeded (conversation_id={}, session_id={})",
-                                conversation_id,
-                                session_id.as_deref().unwrap_or_default()
+                                conversation_id, stored_session_id
                             );
+                            session_id = Some(stored_session_id);
                         }
                         Err(e) => {
                             error!("ACP: session/load failed: {:?}", e);
@@ -2082,7 +2157,12 @@ async fn run_acp_session(
                 let session_id = session_response.session_id.to_string();
                 info!("ACP: Session created, session_id={:?}", session_id);
 
-                conversation_db.upsert_acp_session_id(conversation_id, &session_id)?;
+                conversation_db.upsert_acp_session(
+                    conversation_id,
+                    &session_id,
+                    &working_directory,
+                    &acp_config.cli_command,
+                )?;
                 session_id
             };
 
@@ -2132,6 +2212,21 @@ async fn run_acp_session(
 
     if let Err(e) = session_result {
         error!("ACP: Session failed: {}", e);
+        // Agent 进程已退出时会话无法再 session/load，标记为 stale 而不是反复尝试恢复
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            info!(
+                "ACP: Agent process exited, marking session stale (conversation_id={})",
+                conversation_id
+            );
+            match ConversationDatabase::new(&cleanup_app_handle) {
+                Ok(db) => {
+                    if let Err(db_err) = db.mark_acp_session_stale(conversation_id) {
+                        error!("ACP: Failed to mark session stale: {}", db_err);
+                    }
+                }
+                Err(db_err) => error!("ACP: Failed to open conversation db: {}", db_err),
+            }
+        }
         if let Err(kill_err) = child.kill().await {
             debug!("ACP: Kill process result: {:?}", kill_err);
         }
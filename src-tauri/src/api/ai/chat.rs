@@ -1,5 +1,26 @@
-use crate::api::ai::config::{calculate_retry_delay, get_retry_attempts_from_config};
+use crate::api::ai::config::{
+    get_request_fallback_order_from_config, get_retry_attempts_from_config,
+    get_retry_backoff_from_config, get_stream_idle_timeout_from_config, RetryBackoffConfig,
+};
+use crate::api::ai::content_filter::{
+    build_filtered_message, content_filter_from_stop_reason, ContentFilterBlock,
+    ContentFilteredEvent, CONTENT_FILTERED_EVENT, FILTERED_MESSAGE_TYPE,
+};
 use crate::api::ai::events::{ConversationEvent, MessageAddEvent, MessageUpdateEvent};
+use crate::api::ai::generation_progress::GenerationProgress;
+use crate::api::ai::generation_status::{self, ActiveGenerationGuard};
+use crate::api::ai::model_fallback::{
+    fallback_models_for_conversation, should_fall_back, ModelFallbackEvent, MODEL_FALLBACK_EVENT,
+};
+use crate::api::ai::render_mode::apply_assistant_render_mode;
+use crate::api::ai::request_fallback::run_with_request_fallback;
+use crate::api::ai::response_language::{
+    conversation_response_language, is_response_language_mismatch, response_language_reminder,
+};
+use crate::api::ai::stream_pacer::StreamRevealPacer;
+use crate::api::ai::stream_persist::{StreamContentPersister, STREAM_PERSIST_INTERVAL};
+use crate::api::ai::title::{resolve_title_source, response_has_tool_calls, TitleTriggerConfig};
+use crate::api::ai::tool_parallel::{run_bounded, tool_concurrency_from_configs};
 use crate::api::ai::types::McpOverrideConfig;
 use crate::api::ai_api::{resolve_tool_name, sanitize_tool_name, ToolNameMapping};
 use crate::db::assistant_db::Assistant;
@@ -8,15 +29,15 @@ use crate::db::system_db::FeatureConfig;
 use crate::errors::AppError;
 use crate::state::activity_state::ConversationActivityManager;
 use crate::state::message_token::MessageTokenManager;
-use crate::utils::window_utils::send_error_to_appropriate_window;
+use crate::utils::window_utils::{emit_conversation_event, send_error_to_appropriate_window};
 use anyhow::Context as _;
 use futures::StreamExt;
 use genai::chat::ChatStreamEvent;
-use genai::chat::{ChatOptions, ChatRequest, ToolCall};
+use genai::chat::{ChatMessage, ChatOptions, ChatRequest, ToolCall};
 use genai::Client;
 use serde_json;
 use std::collections::HashMap;
-use tauri::{Emitter, Manager};
+use tauri::Manager;
 use tauri_plugin_notification::NotificationExt;
 use tokio::time::{sleep, Duration};
 use tokio_util::sync::CancellationToken;
@@ -32,7 +53,7 @@ pub struct HttpErrorDetails {
 }
 
 /// 从 genai::Error 中提取 HTTP 错误详情
-fn extract_http_error_details(error: &genai::Error) -> HttpErrorDetails {
+pub fn extract_http_error_details(error: &genai::Error) -> HttpErrorDetails {
     let mut details = HttpErrorDetails::default();
 
     match error {
@@ -351,6 +372,15 @@ async fn ensure_stream_message(
         })
         .context("failed to create stream message")?;
 
+    if message_type == "response" {
+        apply_assistant_render_mode(
+            window.app_handle(),
+            conversation_db,
+            conversation_id,
+            new_message.id,
+        );
+    }
+
     let add_event = ConversationEvent {
         r#type: "message_add".to_string(),
         data: serde_json::to_value(MessageAddEvent {
@@ -359,7 +389,7 @@ async fn ensure_stream_message(
         })
         .unwrap(),
     };
-    let _ = window.emit(format!("conversation_event_{}", conversation_id).as_str(), add_event);
+    emit_conversation_event(window, conversation_id, add_event);
 
     // 设置 Assistant 消息的活动状态（闪亮边框）
     if message_type == "response" || message_type == "reasoning" {
@@ -374,15 +404,11 @@ async fn ensure_stream_message(
     Ok(new_message.id)
 }
 
-/// 更新消息内容并发出 message_update
-fn persist_and_emit_update(
+/// 更新消息内容（不发出事件）
+fn persist_message_content(
     conversation_db: &ConversationDatabase,
-    window: &tauri::Window,
-    conversation_id: i64,
     msg_id: i64,
-    message_type: &str,
     content: &str,
-    is_done: bool,
 ) -> anyhow::Result<()> {
     if let Ok(Some(mut message)) =
         conversation_db.message_repo().context("failed to get message_repo for read")?.read(msg_id)
@@ -394,14 +420,24 @@ fn persist_and_emit_update(
             .update(&message)
             .ok();
     }
+    Ok(())
+}
 
+/// 发出流式中间态的 message_update
+fn emit_stream_update(
+    window: &tauri::Window,
+    conversation_id: i64,
+    msg_id: i64,
+    message_type: &str,
+    content: &str,
+) {
     let update_event = ConversationEvent {
         r#type: "message_update".to_string(),
         data: serde_json::to_value(MessageUpdateEvent {
             message_id: msg_id,
             message_type: message_type.to_string(),
             content: content.to_string(),
-            is_done,
+            is_done: false,
             token_count: None,
             input_token_count: None,
             output_token_count: None,
@@ -410,9 +446,41 @@ fn persist_and_emit_update(
         })
         .unwrap(),
     };
-    let _ = window.emit(format!("conversation_event_{}", conversation_id).as_str(), update_event);
+    emit_conversation_event(window, conversation_id, update_event);
+}
 
-    Ok(())
+/// 流式 chunk 到达时更新消息：写库由 persister 合并，事件在配置了匀速展示时交由 pacer 发出，否则立即发出
+fn persist_stream_update(
+    persister: &mut StreamContentPersister<impl FnMut(i64, &str)>,
+    window: &tauri::Window,
+    conversation_id: i64,
+    msg_id: i64,
+    message_type: &str,
+    content: &str,
+    reveal_chars_per_second: Option<u32>,
+    pacer: &mut Option<StreamRevealPacer>,
+) {
+    persister.update(msg_id, content);
+
+    let Some(chars_per_second) = reveal_chars_per_second else {
+        emit_stream_update(window, conversation_id, msg_id, message_type, content);
+        return;
+    };
+    let pacer = pacer.get_or_insert_with(|| {
+        let window = window.clone();
+        let message_type = message_type.to_string();
+        StreamRevealPacer::spawn(chars_per_second, move |revealed| {
+            emit_stream_update(&window, conversation_id, msg_id, &message_type, revealed);
+        })
+    });
+    pacer.push(content);
+}
+
+/// 流结束前先把 pacer 中尚未展示的内容全部发出，避免晚于 is_done 事件到达前端
+async fn flush_stream_pacer(pacer: &mut Option<StreamRevealPacer>) {
+    if let Some(pacer) = pacer.take() {
+        pacer.finish().await;
+    }
 }
 
 fn normalize_tool_arguments_json(arguments: &serde_json::Value) -> String {
@@ -522,10 +590,7 @@ async fn handle_captured_tool_calls_common(
                         })
                         .unwrap(),
                     };
-                    let _ = window.emit(
-                        format!("conversation_event_{}", conversation_id).as_str(),
-                        update_event,
-                    );
+                    emit_conversation_event(window, conversation_id, update_event);
                 }
 
                 // 自动执行（若配置）
@@ -613,10 +678,7 @@ async fn handle_captured_tool_calls_common(
                             "response_message_id": response_message_id
                         }
                     });
-                    let _ = window.emit(
-                        format!("conversation_event_{}", conversation_id).as_str(),
-                        tool_call_event,
-                    );
+                    emit_conversation_event(window, conversation_id, tool_call_event);
                 }
             }
             Err(e) => {
@@ -628,7 +690,7 @@ async fn handle_captured_tool_calls_common(
     Ok(())
 }
 
-/// 并发处理捕获到的工具调用
+/// 并发处理捕获到的工具调用，自动执行部分按助手配置的并发上限运行
 /// 返回: (所有工具调用ID, 需要执行的工具调用ID)
 async fn handle_captured_tool_calls_concurrent(
     app_handle: &tauri::AppHandle,
@@ -641,8 +703,6 @@ async fn handle_captured_tool_calls_concurrent(
     mcp_override_config: Option<&crate::api::ai::types::McpOverrideConfig>,
     tool_name_mapping: &ToolNameMapping,
 ) -> anyhow::Result<(Vec<i64>, Vec<i64>)> {
-    use futures::future::join_all;
-
     // 第一步：为所有工具调用创建 DB 记录和 UI hints（保持原有顺序）
     let mut all_tool_call_ids = Vec::new();
     let mut tool_call_records: Vec<(i64, String, String)> = Vec::new(); // (id, server_name, tool_name)
@@ -728,10 +788,7 @@ async fn handle_captured_tool_calls_concurrent(
                         })
                         .unwrap(),
                     };
-                    let _ = window.emit(
-                        format!("conversation_event_{}", conversation_id).as_str(),
-                        update_event,
-                    );
+                    emit_conversation_event(window, conversation_id, update_event);
                 }
             }
             Err(e) => {
@@ -740,14 +797,25 @@ async fn handle_captured_tool_calls_concurrent(
         }
     }
 
-    // 第二步：筛选出需要 auto_run 的工具调用 ID
+    // 第二步：筛选出需要 auto_run 的工具调用 ID，并读取助手配置的并发上限
     let mut auto_run_ids = Vec::new();
+    let mut tool_concurrency = 1;
     if let Ok(conv) = conversation_db
         .conversation_repo()
         .context("failed to get conversation_repo")?
         .read(conversation_id)
     {
         if let Some(assistant_id) = conv.and_then(|c| c.assistant_id) {
+            match crate::api::assistant_api::get_assistant(
+                app_handle.clone(),
+                assistant_id,
+                Some(true),
+            ) {
+                Ok(detail) => {
+                    tool_concurrency = tool_concurrency_from_configs(&detail.model_configs)
+                }
+                Err(e) => warn!(error = %e, assistant_id, "failed to load tool concurrency config"),
+            }
             if let Ok(mcp_info) =
                 crate::mcp::collect_mcp_info_for_assistant(app_handle, assistant_id, None, None)
                     .await
@@ -789,35 +857,36 @@ async fn handle_captured_tool_calls_concurrent(
         }
     }
 
-    // 第三步：并发执行所有 auto_run 的工具调用
+    // 第三步：按并发上限执行所有 auto_run 的工具调用，单个失败不影响其他调用
     if !auto_run_ids.is_empty() {
-        let state = app_handle.state::<crate::AppState>();
-        let feature_config_state = app_handle.state::<crate::FeatureConfigState>();
-
-        let mut execute_futures = Vec::new();
-        for call_id in &auto_run_ids {
-            let future = crate::mcp::execution_api::execute_mcp_tool_call(
-                app_handle.clone(),
-                state.clone(),
-                feature_config_state.clone(),
-                window.clone(),
-                *call_id,
-                false, // 不触发续写
-            );
-            execute_futures.push(future);
-        }
-
-        // 第四步：等待所有执行完成
-        let results = join_all(execute_futures).await;
+        info!(
+            count = auto_run_ids.len(),
+            concurrency = tool_concurrency,
+            "executing auto-run tool calls"
+        );
+        let results = run_bounded(auto_run_ids.clone(), tool_concurrency, |call_id| {
+            let app_handle = app_handle.clone();
+            let window = window.clone();
+            async move {
+                let state = app_handle.state::<crate::AppState>();
+                let feature_config_state = app_handle.state::<crate::FeatureConfigState>();
+                crate::mcp::execution_api::execute_mcp_tool_call(
+                    app_handle.clone(),
+                    state,
+                    feature_config_state,
+                    window,
+                    call_id,
+                    false, // 不触发续写
+                )
+                .await
+            }
+        })
+        .await;
 
-        // 记录执行结果
-        for (idx, result) in results.iter().enumerate() {
+        // 第四步：结果按调用顺序汇总，失败的调用已写入 failed 状态，由批量续写统一上报
+        for (call_id, result) in auto_run_ids.iter().zip(results) {
             if let Err(e) = result {
-                warn!(
-                    call_id = auto_run_ids[idx],
-                    error = %e,
-                    "Concurrent tool execution failed"
-                );
+                warn!(call_id, error = %e, "Concurrent tool execution failed");
             }
         }
     }
@@ -1121,6 +1190,8 @@ fn build_rich_error_payload(
         attempts,
         original_error,
         None,
+        Vec::new(),
+        None,
     )
 }
 
@@ -1133,6 +1204,8 @@ fn build_rich_error_payload_with_http_details(
     attempts: Option<i32>,
     original_error: String,
     http_details: Option<HttpErrorDetails>,
+    tried_models: Vec<String>,
+    partial: Option<&PartialResponseError>,
 ) -> String {
     // 根据主要信息给出建议
     let mut suggestions: Vec<&str> = Vec::new();
@@ -1176,6 +1249,9 @@ fn build_rich_error_payload_with_http_details(
     {
         suggestions.push("服务端异常，稍后重试");
     }
+    if check_str.contains("stream stalled") {
+        suggestions.push("服务端可能已卡住，稍后重试或调大流式空闲超时");
+    }
     if check_str.contains("格式") || check_str.contains("json") || check_str.contains("parse") {
         suggestions.push("检查 Base URL / 模型配置与请求参数格式");
     }
@@ -1208,7 +1284,7 @@ fn build_rich_error_payload_with_http_details(
     // 优先使用 HTTP 详情中的 response_body，否则使用 details
     let final_details = response_body.or(details);
 
-    let payload = serde_json::json!({
+    let mut payload = serde_json::json!({
         "message": main_message,
         "details": final_details,
         "model": model_name,
@@ -1221,6 +1297,15 @@ fn build_rich_error_payload_with_http_details(
         "request_id": request_id,
         "timestamp": chrono::Utc::now().to_rfc3339(),
     });
+    // 发生过模型降级时记录依次尝试过的模型
+    if tried_models.len() > 1 {
+        payload["tried_models"] = serde_json::json!(tried_models);
+    }
+    // 中断前已输出的部分回复保存在同一组的 response 消息中
+    if let Some(partial) = partial {
+        payload["partial_message_id"] = serde_json::json!(partial.message_id);
+        payload["partial_chars"] = serde_json::json!(partial.partial_chars);
+    }
     payload.to_string()
 }
 
@@ -1241,10 +1326,12 @@ fn create_structured_error_message<E: std::fmt::Display>(
 }
 
 // 将错误信息转换为用户友好的中文提示
-fn get_user_friendly_error_message<E: std::fmt::Display>(error: &E) -> String {
+pub fn get_user_friendly_error_message<E: std::fmt::Display>(error: &E) -> String {
     let error_str = error.to_string().to_lowercase();
 
-    if error_str.contains("network")
+    if error_str.contains("stream stalled") {
+        "模型长时间未返回新内容，响应已中断".to_string()
+    } else if error_str.contains("network")
         || error_str.contains("connection")
         || error_str.contains("timeout")
     {
@@ -1529,22 +1616,263 @@ pub async fn handle_stream_chat(
     llm_model_name: String,
     mcp_override_config: Option<McpOverrideConfig>,
     tool_name_mapping: ToolNameMapping,
+    reveal_chars_per_second: Option<u32>,
 ) -> Result<(), anyhow::Error> {
-    let mut main_attempts = 0;
-    let app_handle_clone = app_handle.clone();
     let cancel_token = if let Some(token_manager) = app_handle.try_state::<MessageTokenManager>() {
         token_manager.get_cancel_token(conversation_id).await
     } else {
         None
     };
 
-    // 从配置中获取最大重试次数
+    // 从配置中获取最大重试次数与持续 400 时的请求简化顺序
     let max_retry_attempts = get_retry_attempts_from_config(&config_feature_map);
+    let fallback_order = get_request_fallback_order_from_config(&config_feature_map);
+
+    let _generation_guard = ActiveGenerationGuard::enter(
+        conversation_id,
+        llm_model_id,
+        model_name,
+        &llm_model_name,
+        true,
+    );
+
+    // 提前确定 generation_group_id，重试和降级到备用模型时都归入同一组
+    let generation_group_id_override =
+        Some(generation_group_id_override.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()));
+
+    let user_prompt = &user_prompt;
+    let config_feature_map = &config_feature_map;
+    let generation_group_id_ref = &generation_group_id_override;
+    let parent_group_id_ref = &parent_group_id_override;
+    let llm_model_name_ref = &llm_model_name;
+    let mcp_override_config = &mcp_override_config;
+    let tool_name_mapping = &tool_name_mapping;
+    let cancel_token = &cancel_token;
+    // 累计原始请求与各步简化重试的总尝试次数
+    let total_attempts = std::sync::atomic::AtomicU32::new(0);
+    let total_attempts_ref = &total_attempts;
+
+    let stream_result = run_with_request_fallback(
+        chat_request,
+        chat_options,
+        &fallback_order,
+        |(e, _): &(anyhow::Error, u32)| {
+            extract_http_details_from_anyhow(e).status_code == Some(400)
+        },
+        move |request, options, is_fallback| async move {
+            // 简化后的请求只尝试一次
+            let max_attempts = if is_fallback { 1 } else { max_retry_attempts };
+            let result = stream_chat_with_retries(
+                client,
+                model_name,
+                &request,
+                &options,
+                conversation_id,
+                conversation_db,
+                window,
+                app_handle,
+                need_generate_title,
+                user_prompt,
+                config_feature_map,
+                generation_group_id_ref,
+                parent_group_id_ref,
+                llm_model_id,
+                llm_model_name_ref,
+                mcp_override_config,
+                tool_name_mapping,
+                cancel_token,
+                reveal_chars_per_second,
+                max_attempts,
+                is_fallback,
+            )
+            .await;
+            if let Err((_, attempts)) = &result {
+                total_attempts_ref.fetch_add(*attempts, std::sync::atomic::Ordering::Relaxed);
+            }
+            result
+        },
+    )
+    .await;
+
+    let (mut e, mut main_attempts) = match stream_result {
+        Ok(_) => return Ok(()),
+        Err((e, _)) => (e, total_attempts.load(std::sync::atomic::Ordering::Relaxed)),
+    };
+
+    // 主模型重试耗尽后按助手配置的降级链依次尝试备用模型
+    let mut tried_models = vec![llm_model_name.clone()];
+    let mut failed_model_id = llm_model_id;
+    let mut failed_model_name = llm_model_name.clone();
+    let is_cancelled = || cancel_token.as_ref().is_some_and(|token| token.is_cancelled());
+    if should_fall_back(extract_http_details_from_anyhow(&e).status_code)
+        && !e.is::<PartialResponseError>()
+        && !is_cancelled()
+    {
+        let fallback_models = fallback_models_for_conversation(
+            app_handle,
+            conversation_db,
+            conversation_id,
+            llm_model_id,
+            chat_options.capture_tool_calls.unwrap_or(false),
+            config_feature_map,
+        );
+        for fallback in &fallback_models {
+            if is_cancelled() {
+                break;
+            }
+            warn!(
+                from = %failed_model_name,
+                to = %fallback.llm_model_name,
+                error = %e,
+                "stream chat failed, falling back to next model"
+            );
+            generation_status::record_model_switch(
+                conversation_id,
+                fallback.llm_model_id,
+                &fallback.model_name,
+                &fallback.llm_model_name,
+            );
+            let fallback_event = ConversationEvent {
+                r#type: MODEL_FALLBACK_EVENT.to_string(),
+                data: serde_json::to_value(ModelFallbackEvent {
+                    conversation_id,
+                    generation_group_id: generation_group_id_ref.clone().unwrap_or_default(),
+                    from_model_name: failed_model_name.clone(),
+                    model_id: fallback.llm_model_id,
+                    model_name: fallback.llm_model_name.clone(),
+                })
+                .unwrap(),
+            };
+            emit_conversation_event(window, conversation_id, fallback_event);
+            tried_models.push(fallback.llm_model_name.clone());
+            let fallback_result = stream_chat_with_retries(
+                &fallback.client,
+                &fallback.model_name,
+                chat_request,
+                &fallback.chat_options,
+                conversation_id,
+                conversation_db,
+                window,
+                app_handle,
+                need_generate_title,
+                user_prompt,
+                config_feature_map,
+                generation_group_id_ref,
+                parent_group_id_ref,
+                fallback.llm_model_id,
+                &fallback.llm_model_name,
+                mcp_override_config,
+                tool_name_mapping,
+                cancel_token,
+                reveal_chars_per_second,
+                max_retry_attempts,
+                false,
+            )
+            .await;
+            match fallback_result {
+                Ok(_) => {
+                    info!(model = %fallback.llm_model_name, "stream chat completed with fallback");
+                    return Ok(());
+                }
+                Err((fallback_error, attempts)) => {
+                    e = fallback_error;
+                    main_attempts = attempts;
+                    failed_model_id = fallback.llm_model_id;
+                    failed_model_name = fallback.llm_model_name.clone();
+                    if !should_fall_back(extract_http_details_from_anyhow(&e).status_code)
+                        || e.is::<PartialResponseError>()
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    // 最终失败，提取 HTTP 错误详情并构建结构化错误
+    let http_details = extract_http_details_from_anyhow(&e);
+    let user_friendly = get_user_friendly_error_message(&e);
+
+    // 使用更友好的主消息
+    let partial = e.downcast_ref::<PartialResponseError>();
+    let final_main = match partial {
+        Some(_) => format!("AI回复中断: {}", user_friendly),
+        None => format!("AI请求失败: {}", user_friendly),
+    };
+    let payload = build_rich_error_payload_with_http_details(
+        final_main,
+        None,
+        Some(failed_model_name.clone()),
+        "stream",
+        Some(main_attempts as i32),
+        e.to_string(),
+        Some(http_details),
+        tried_models,
+        partial,
+    );
+    error!("[[final_stream_error]]: 流式聊天在{}次尝试后失败: {}", main_attempts, e);
+
+    // 发送错误通知到合适的窗口
+    send_error_to_appropriate_window(&window, &user_friendly, Some(conversation_id));
+
+    // 清除活动焦点（闪亮边框）
+    if let Some(activity_manager) = app_handle.try_state::<ConversationActivityManager>() {
+        activity_manager.clear_focus(app_handle, conversation_id).await;
+    }
+
+    // 创建错误消息
+    create_error_message(
+        conversation_db,
+        conversation_id,
+        failed_model_id,
+        failed_model_name,
+        &payload,
+        generation_group_id_override.clone(),
+        parent_group_id_override.clone(),
+        window,
+    )
+    .await;
+
+    Err(anyhow::anyhow!("AI stream failed after retries"))
+}
+
+/// 流式会话的常规重试循环，失败时返回最后一次错误与尝试次数
+async fn stream_chat_with_retries(
+    client: &Client,
+    model_name: &str,
+    chat_request: &ChatRequest,
+    chat_options: &ChatOptions,
+    conversation_id: i64,
+    conversation_db: &ConversationDatabase,
+    window: &tauri::Window,
+    app_handle: &tauri::AppHandle,
+    need_generate_title: bool,
+    user_prompt: &str,
+    config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+    generation_group_id_override: &Option<String>,
+    parent_group_id_override: &Option<String>,
+    llm_model_id: i64,
+    llm_model_name: &str,
+    mcp_override_config: &Option<McpOverrideConfig>,
+    tool_name_mapping: &ToolNameMapping,
+    cancel_token: &Option<CancellationToken>,
+    reveal_chars_per_second: Option<u32>,
+    max_retry_attempts: u32,
+    is_fallback: bool,
+) -> Result<(), (anyhow::Error, u32)> {
+    let mut main_attempts = 0;
+    let retry_backoff = get_retry_backoff_from_config(config_feature_map);
 
     // 外层重试循环，处理整个流式会话
     loop {
         main_attempts += 1;
         info!(attempt = main_attempts, max_attempts = max_retry_attempts, "stream chat attempt");
+        generation_status::record_attempt(
+            conversation_id,
+            main_attempts,
+            max_retry_attempts,
+            is_fallback,
+        );
 
         let stream_result = attempt_stream_chat(
             client,
@@ -1554,17 +1882,18 @@ pub async fn handle_stream_chat(
             conversation_id,
             conversation_db,
             window,
-            &app_handle_clone,
+            app_handle,
             need_generate_title,
-            user_prompt.clone(),
+            user_prompt.to_string(),
             config_feature_map.clone(),
             generation_group_id_override.clone(),
             parent_group_id_override.clone(),
             llm_model_id,
-            llm_model_name.clone(),
+            llm_model_name.to_string(),
             mcp_override_config.clone(),
             tool_name_mapping.clone(),
             cancel_token.clone(),
+            reveal_chars_per_second,
         )
         .await;
 
@@ -1576,65 +1905,59 @@ pub async fn handle_stream_chat(
             Err(e) => {
                 warn!(attempt = main_attempts, error = %e, "stream chat failed attempt");
 
-                if main_attempts >= max_retry_attempts {
-                    // 最终失败，提取 HTTP 错误详情并构建结构化错误
-                    let http_details = extract_http_details_from_anyhow(&e);
-                    let user_friendly = get_user_friendly_error_message(&e);
-
-                    // 使用更友好的主消息
-                    let final_main = format!("AI请求失败: {}", user_friendly);
-                    let payload = build_rich_error_payload_with_http_details(
-                        final_main,
-                        None,
-                        Some(llm_model_name.clone()),
-                        "stream",
-                        Some(main_attempts as i32),
-                        e.to_string(),
-                        Some(http_details),
-                    );
-                    error!(
-                        "[[final_stream_error]]: 流式聊天在{}次尝试后失败: {}",
-                        main_attempts, e
-                    );
-
-                    // 发送错误通知到合适的窗口
-                    send_error_to_appropriate_window(
-                        &window,
-                        &user_friendly,
-                        Some(conversation_id),
-                    );
-
-                    // 清除活动焦点（闪亮边框）
-                    if let Some(activity_manager) =
-                        app_handle.try_state::<ConversationActivityManager>()
-                    {
-                        activity_manager.clear_focus(app_handle, conversation_id).await;
-                    }
-
-                    // 创建错误消息
-                    create_error_message(
-                        conversation_db,
-                        conversation_id,
-                        llm_model_id,
-                        llm_model_name.clone(),
-                        &payload,
-                        generation_group_id_override.clone(),
-                        parent_group_id_override.clone(),
-                        window,
-                    )
-                    .await;
-
-                    return Err(anyhow::anyhow!("AI stream failed after retries"));
+                // 已保留部分回复时不自动重试，避免在同一组中重复生成
+                if main_attempts >= max_retry_attempts || e.is::<PartialResponseError>() {
+                    return Err((e, main_attempts));
                 }
 
-                let delay = calculate_retry_delay(main_attempts);
-                debug!(delay_ms = delay, "retrying stream after delay");
+                let delay = retry_backoff.delay_for(main_attempts);
+                debug!(
+                    delay_ms = delay,
+                    strategy = ?retry_backoff.strategy,
+                    "retrying stream after delay"
+                );
                 sleep(Duration::from_millis(delay)).await;
             }
         }
     }
 }
 
+/// 流式响应在空闲超时内没有收到任何事件
+#[derive(Debug, thiserror::Error)]
+#[error("Stream stalled: no data received for {}ms", .idle_timeout.as_millis())]
+pub struct StreamStalledError {
+    pub idle_timeout: Duration,
+}
+
+/// 流式生成中途出错，但已输出了部分回复。部分回复保存为 response 消息并标记为部分回复，
+/// 不再自动重试或降级，由用户决定是否重新生成
+#[derive(Debug, thiserror::Error)]
+#[error("{source}")]
+pub struct PartialResponseError {
+    pub message_id: i64,
+    pub partial_chars: usize,
+    /// 导致中断的原始错误，保留完整的错误链
+    #[source]
+    pub source: anyhow::Error,
+}
+
+/// 读取流的下一个事件，超过空闲超时仍无数据时返回 [`StreamStalledError`]；
+/// 每次调用都会重新计时，因此持续有输出的慢速流不会被中断
+pub async fn next_with_idle_timeout<S>(
+    stream: &mut S,
+    idle_timeout: Option<Duration>,
+) -> Result<Option<S::Item>, StreamStalledError>
+where
+    S: futures::Stream + Unpin,
+{
+    match idle_timeout {
+        Some(idle_timeout) => tokio::time::timeout(idle_timeout, stream.next())
+            .await
+            .map_err(|_| StreamStalledError { idle_timeout }),
+        None => Ok(stream.next().await),
+    }
+}
+
 // 单次流式聊天尝试
 async fn attempt_stream_chat(
     client: &Client,
@@ -1655,6 +1978,7 @@ async fn attempt_stream_chat(
     mcp_override_config: Option<McpOverrideConfig>,
     tool_name_mapping: ToolNameMapping,
     cancel_token: Option<CancellationToken>,
+    reveal_chars_per_second: Option<u32>,
 ) -> Result<(), anyhow::Error> {
     if let Some(token) = cancel_token.as_ref() {
         if token.is_cancelled() {
@@ -1737,12 +2061,26 @@ async fn attempt_stream_chat(
         }
     };
 
+    // 连接建立即通知前端生成已开始，不必等首个 chunk
+    let mut progress = GenerationProgress::start(conversation_id, &generation_group_id, |event| {
+        emit_conversation_event(window, conversation_id, event);
+    });
+
     let mut chat_stream = chat_stream_response.stream;
     let mut reasoning_content = String::new();
     let mut response_content = String::new();
     let mut reasoning_message_id: Option<i64> = None;
     let mut response_message_id: Option<i64> = None;
     let mut captured_tool_calls: Vec<ToolCall> = Vec::new();
+    // 匀速展示器：取消或出错提前返回时随 drop 自动发出剩余内容
+    let mut reasoning_pacer: Option<StreamRevealPacer> = None;
+    let mut response_pacer: Option<StreamRevealPacer> = None;
+    // 流式内容按间隔合并写库；收尾前需 flush，提前返回时随 drop 写入剩余内容
+    let mut persister = StreamContentPersister::new(STREAM_PERSIST_INTERVAL, |msg_id, content| {
+        if let Err(e) = persist_message_content(conversation_db, msg_id, content) {
+            warn!(error = %e, message_id = msg_id, "failed to persist stream content");
+        }
+    });
 
     // Diagnostics: counters for stream content
     let mut response_chunk_count: usize = 0;
@@ -1760,17 +2098,32 @@ async fn attempt_stream_chat(
     let mut response_first_token_time: Option<chrono::DateTime<chrono::Utc>> = None; // 首字到达时间
     let mut first_any_token_time: Option<chrono::DateTime<chrono::Utc>> = None; // 任意类型首字到达时间（用于 TPS 计算备用）
 
-    loop {
-        let stream_result = if let Some(token) = cancel_token.as_ref() {
+    let idle_timeout = get_stream_idle_timeout_from_config(&config_feature_map);
+
+    let stream_error: anyhow::Error = loop {
+        let next_result = if let Some(token) = cancel_token.as_ref() {
             tokio::select! {
                 _ = token.cancelled() => {
                     info!(conversation_id, "stream chat cancelled");
                     return Ok(());
                 }
-                result = chat_stream.next() => result,
+                result = next_with_idle_timeout(&mut chat_stream, idle_timeout) => result,
             }
         } else {
-            chat_stream.next().await
+            next_with_idle_timeout(&mut chat_stream, idle_timeout).await
+        };
+        let stream_result = match next_result {
+            Ok(result) => result,
+            Err(stalled) => {
+                warn!(
+                    conversation_id,
+                    idle_timeout_ms = stalled.idle_timeout.as_millis() as u64,
+                    response_chunks = response_chunk_count,
+                    reasoning_chunks = reasoning_chunk_count,
+                    "stream stalled, aborting attempt"
+                );
+                break stalled.into();
+            }
         };
         match stream_result {
             Some(Ok(stream_event)) => {
@@ -1779,6 +2132,8 @@ async fn attempt_stream_chat(
                     ChatStreamEvent::Chunk(chunk) => {
                         response_chunk_count += 1;
                         response_char_count += chunk.content.chars().count();
+                        progress.on_response_chunk(&chunk.content);
+                        generation_status::record_output(conversation_id, &chunk.content);
 
                         // 记录首字到达时间
                         if response_first_token_time.is_none() && !chunk.content.is_empty() {
@@ -1797,6 +2152,8 @@ async fn attempt_stream_chat(
                                 // 记录 reasoning 结束时间，用于后续 response 创建时使用
                                 let now = chrono::Utc::now();
                                 reasoning_end_time = Some(now);
+                                persister.flush(msg_id);
+                                flush_stream_pacer(&mut reasoning_pacer).await;
 
                                 if let Err(e) = super::conversation::handle_message_type_end(
                                     msg_id,
@@ -1866,9 +2223,9 @@ async fn attempt_stream_chat(
                                                 "conversation_id": conversation_id
                                             }
                                         });
-                                        let _ = window.emit(
-                                            format!("conversation_event_{}", conversation_id)
-                                                .as_str(),
+                                        emit_conversation_event(
+                                            window,
+                                            conversation_id,
                                             group_merge_event,
                                         );
                                         group_merge_event_emitted = true;
@@ -1878,20 +2235,23 @@ async fn attempt_stream_chat(
                         }
 
                         if let Some(msg_id) = response_message_id {
-                            let _ = persist_and_emit_update(
-                                &conversation_db,
+                            persist_stream_update(
+                                &mut persister,
                                 &window,
                                 conversation_id,
                                 msg_id,
                                 "response",
                                 &response_content,
-                                false,
+                                reveal_chars_per_second,
+                                &mut response_pacer,
                             );
                         }
                     }
                     ChatStreamEvent::ReasoningChunk(reasoning_chunk) => {
                         reasoning_chunk_count += 1;
                         reasoning_char_count += reasoning_chunk.content.chars().count();
+                        progress.on_reasoning_chunk(&reasoning_chunk.content);
+                        generation_status::record_output(conversation_id, &reasoning_chunk.content);
 
                         // 记录任意类型首字到达时间（用于 TPS 计算备用）
                         if first_any_token_time.is_none() && !reasoning_chunk.content.is_empty() {
@@ -1929,14 +2289,15 @@ async fn attempt_stream_chat(
                         }
 
                         if let Some(msg_id) = reasoning_message_id {
-                            let _ = persist_and_emit_update(
-                                &conversation_db,
+                            persist_stream_update(
+                                &mut persister,
                                 &window,
                                 conversation_id,
                                 msg_id,
                                 "reasoning",
                                 &reasoning_content,
-                                false,
+                                reveal_chars_per_second,
+                                &mut reasoning_pacer,
                             );
                         }
                     }
@@ -1948,6 +2309,9 @@ async fn attempt_stream_chat(
                     }
                     ChatStreamEvent::End(end_event) => {
                         debug!(?end_event, "end event");
+                        persister.flush_all();
+                        flush_stream_pacer(&mut reasoning_pacer).await;
+                        flush_stream_pacer(&mut response_pacer).await;
 
                         // Extract and store token usage data before ownership is taken
                         let token_data = end_event.captured_usage.as_ref().map(|usage| {
@@ -1958,6 +2322,11 @@ async fn attempt_stream_chat(
                             (input_tokens, output_tokens, total_tokens)
                         });
 
+                        let content_filter_block = end_event
+                            .captured_stop_reason
+                            .as_ref()
+                            .and_then(content_filter_from_stop_reason);
+
                         // Capture tool calls if they exist (this takes ownership of end_event)
                         if let Some(tool_calls) = end_event.captured_into_tool_calls() {
                             captured_tool_calls = tool_calls;
@@ -1966,6 +2335,10 @@ async fn attempt_stream_chat(
 
                         // Store the extracted token data
                         if let Some((input_tokens, output_tokens, total_tokens)) = token_data {
+                            generation_status::record_output_tokens(
+                                conversation_id,
+                                output_tokens as i64,
+                            );
                             // Update the response or reasoning message with token data
                             // 优先更新 response 消息，如果没有则更新 reasoning 消息
                             let target_msg_id = response_message_id.or(reasoning_message_id);
@@ -2072,12 +2445,9 @@ async fn attempt_stream_chat(
                                                     )
                                                     .unwrap(),
                                                 };
-                                                let _ = window.emit(
-                                                    format!(
-                                                        "conversation_event_{}",
-                                                        conversation_id
-                                                    )
-                                                    .as_str(),
+                                                emit_conversation_event(
+                                                    window,
+                                                    conversation_id,
                                                     update_event,
                                                 );
                                             }
@@ -2262,12 +2632,42 @@ async fn attempt_stream_chat(
                             }
                         }
 
+                        // 提供商审核拦截：追加 filtered 消息说明原因，而不是留下空回复
+                        if let Some(block) = content_filter_block.as_ref() {
+                            if let Err(e) = create_filtered_message(
+                                conversation_db,
+                                window,
+                                conversation_id,
+                                block,
+                                llm_model_id,
+                                &llm_model_name,
+                                &generation_group_id,
+                                parent_group_id_override.clone(),
+                            )
+                            .await
+                            {
+                                warn!(error = %e, "failed to create filtered message");
+                            }
+                        }
+
                         // 工具调用事件已在 handle_captured_tool_calls_common 中按需发出
 
-                        if need_generate_title && !response_content.is_empty() {
+                        let title_config = TitleTriggerConfig::from_config(&config_feature_map);
+                        let has_tool_calls =
+                            response_has_tool_calls(&response_content, captured_tool_calls.len());
+                        if need_generate_title
+                            && !response_content.is_empty()
+                            && !title_config.waits_for_tool_results(has_tool_calls)
+                        {
                             let app_handle_clone = app_handle.clone();
-                            let user_prompt_clone = user_prompt.clone();
-                            let content_clone = response_content.clone();
+                            // 按配置决定标题上下文：多轮或包含工具结果时从对话历史组织
+                            let (user_prompt_clone, content_clone) = resolve_title_source(
+                                conversation_db,
+                                conversation_id,
+                                &title_config,
+                                user_prompt.clone(),
+                                response_content.clone(),
+                            );
                             let config_feature_map_clone = config_feature_map.clone();
                             let window_clone = window.clone();
 
@@ -2295,6 +2695,7 @@ async fn attempt_stream_chat(
                                 if let Ok(assistant) = crate::api::assistant_api::get_assistant(
                                     app_handle.clone(),
                                     assistant_id,
+                                    None,
                                 ) {
                                     Some(assistant.assistant.name.clone())
                                 } else {
@@ -2328,10 +2729,7 @@ async fn attempt_stream_chat(
                                 "reasoning_length": reasoning_content.len(),
                             }),
                         };
-                        let _ = window.emit(
-                            format!("conversation_event_{}", conversation_id).as_str(),
-                            stream_complete_event,
-                        );
+                        emit_conversation_event(window, conversation_id, stream_complete_event);
 
                         // 清理消息焦点（保留 MCP 执行焦点）
                         let app_handle = window.app_handle();
@@ -2349,7 +2747,8 @@ async fn attempt_stream_chat(
             }
             Some(Err(e)) => {
                 let _user_friendly_error = enhanced_error_logging_v2(&e, "Stream Processing").await;
-                return Err(anyhow::anyhow!("Stream processing failed: {}", e));
+                let message = format!("Stream processing failed: {}", e);
+                break anyhow::Error::new(e).context(message);
             }
             None => {
                 error!(
@@ -2364,17 +2763,151 @@ async fn attempt_stream_chat(
                     has_reasoning_message = reasoning_message_id.is_some(),
                     "stream closed unexpectedly without End event"
                 );
-                return Err(anyhow::anyhow!(
+                break anyhow::anyhow!(
                     "Stream ended unexpectedly without End event (response_chunks={}, reasoning_chunks={}, captured_tool_calls={})",
                     response_chunk_count,
                     reasoning_chunk_count,
                     captured_tool_calls.len()
-                ));
+                );
             }
         }
+    };
+
+    // 中途出错：先把已收到的内容写库，已有回复内容时保留为完成的部分回复，不再重试
+    persister.flush_all();
+    flush_stream_pacer(&mut reasoning_pacer).await;
+    flush_stream_pacer(&mut response_pacer).await;
+    let partial_response = response_message_id
+        .zip(response_start_time)
+        .filter(|_| !response_content.trim().is_empty());
+    let Some((msg_id, start_time)) = partial_response else {
+        return Err(stream_error);
+    };
+
+    if current_output_type == OutputType::Reasoning {
+        if let (Some(reasoning_id), Some(reasoning_start)) =
+            (reasoning_message_id, reasoning_start_time)
+        {
+            if let Err(e) = super::conversation::handle_message_type_end(
+                reasoning_id,
+                "reasoning",
+                &reasoning_content,
+                reasoning_start,
+                conversation_db,
+                window,
+                conversation_id,
+                app_handle,
+                true,
+            )
+            .await
+            {
+                warn!(error = %e, "failed to finish partial reasoning");
+            }
+        }
+    }
+    // 部分回复可能在工具调用中途截断，跳过 MCP 检测
+    if let Err(e) = super::conversation::handle_message_type_end(
+        msg_id,
+        "response",
+        &response_content,
+        start_time,
+        conversation_db,
+        window,
+        conversation_id,
+        app_handle,
+        true,
+    )
+    .await
+    {
+        warn!(error = %e, "failed to finish partial response");
     }
+    if let Err(e) = conversation_db.message_repo().and_then(|repo| {
+        repo.mark_partial(msg_id, &stream_error.to_string()).map_err(AppError::from)
+    }) {
+        warn!(error = %e, "failed to mark partial response");
+    }
+    let partial_chars = response_content.chars().count();
+    warn!(
+        conversation_id,
+        message_id = msg_id,
+        partial_chars,
+        error = %stream_error,
+        "stream failed mid-generation, keeping partial response"
+    );
+    Err(PartialResponseError { message_id: msg_id, partial_chars, source: stream_error }.into())
+}
 
-    Ok(())
+/// 记录被提供商内容审核拦截的回复：创建 filtered 消息并发出 content_filtered 事件
+async fn create_filtered_message(
+    conversation_db: &ConversationDatabase,
+    window: &tauri::Window,
+    conversation_id: i64,
+    block: &ContentFilterBlock,
+    llm_model_id: i64,
+    llm_model_name: &str,
+    generation_group_id: &str,
+    parent_group_id_override: Option<String>,
+) -> anyhow::Result<i64> {
+    let filtered_message = conversation_db
+        .message_repo()
+        .context("failed to get message_repo")?
+        .create(&build_filtered_message(
+            conversation_id,
+            block,
+            llm_model_id,
+            llm_model_name,
+            generation_group_id,
+            parent_group_id_override,
+        ))
+        .context("failed to create filtered message")?;
+    warn!(
+        conversation_id,
+        message_id = filtered_message.id,
+        reason = %block.reason,
+        category = ?block.category,
+        "response blocked by provider content filter"
+    );
+
+    let add_event = ConversationEvent {
+        r#type: "message_add".to_string(),
+        data: serde_json::to_value(MessageAddEvent {
+            message_id: filtered_message.id,
+            message_type: FILTERED_MESSAGE_TYPE.to_string(),
+        })
+        .unwrap(),
+    };
+    emit_conversation_event(window, conversation_id, add_event);
+
+    let update_event = ConversationEvent {
+        r#type: "message_update".to_string(),
+        data: serde_json::to_value(MessageUpdateEvent {
+            message_id: filtered_message.id,
+            message_type: FILTERED_MESSAGE_TYPE.to_string(),
+            content: filtered_message.content.clone(),
+            is_done: true,
+            token_count: None,
+            input_token_count: None,
+            output_token_count: None,
+            ttft_ms: None,
+            tps: None,
+        })
+        .unwrap(),
+    };
+    emit_conversation_event(window, conversation_id, update_event);
+
+    let filtered_event = ConversationEvent {
+        r#type: CONTENT_FILTERED_EVENT.to_string(),
+        data: serde_json::to_value(ContentFilteredEvent {
+            conversation_id,
+            message_id: filtered_message.id,
+            reason: block.reason.clone(),
+            category: block.category.clone(),
+        })
+        .unwrap(),
+    };
+    emit_conversation_event(window, conversation_id, filtered_event);
+
+    Ok(filtered_message.id)
 }
 
 // 辅助函数：创建错误消息
@@ -2420,8 +2953,7 @@ async fn create_error_message(
             })
             .unwrap(),
         };
-        let _ =
-            window.emit(format!("conversation_event_{}", conversation_id).as_str(), error_event);
+        emit_conversation_event(window, conversation_id, error_event);
 
         let update_event = ConversationEvent {
             r#type: "message_update".to_string(),
@@ -2438,8 +2970,127 @@ async fn create_error_message(
             })
             .unwrap(),
         };
-        let _ =
-            window.emit(format!("conversation_event_{}", conversation_id).as_str(), update_event);
+        emit_conversation_event(window, conversation_id, update_event);
+    }
+}
+
+/// 非流式请求的常规重试循环，被取消时返回 `Ok(None)`
+async fn exec_non_stream_chat_with_retries(
+    client: &Client,
+    model_name: &str,
+    chat_request: ChatRequest,
+    chat_options: ChatOptions,
+    conversation_id: i64,
+    cancel_token: Option<&CancellationToken>,
+    max_retry_attempts: u32,
+    retry_backoff: RetryBackoffConfig,
+    is_fallback: bool,
+) -> Result<Option<genai::chat::ChatResponse>, genai::Error> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+
+        info!(attempts, max_retry_attempts, "non stream chat attempt");
+        generation_status::record_attempt(
+            conversation_id,
+            attempts,
+            max_retry_attempts,
+            is_fallback,
+        );
+
+        let exec_result = if let Some(token) = cancel_token {
+            tokio::select! {
+                _ = token.cancelled() => {
+                    info!("non-stream chat cancelled");
+                    return Ok(None);
+                }
+                res = client.exec_chat(model_name, chat_request.clone(), Some(&chat_options)) => res,
+            }
+        } else {
+            client.exec_chat(model_name, chat_request.clone(), Some(&chat_options)).await
+        };
+
+        match exec_result {
+            Ok(response) => {
+                info!(attempts, "non stream chat succeeded attempt");
+                return Ok(Some(response));
+            }
+            Err(e) => {
+                let _ = enhanced_error_logging_v2(
+                    &e,
+                    &format!("Non-Stream Chat (attempt {}/{})", attempts, max_retry_attempts),
+                )
+                .await;
+                if attempts >= max_retry_attempts {
+                    return Err(e);
+                }
+
+                let delay = retry_backoff.delay_for(attempts);
+                debug!(
+                    delay_ms = delay,
+                    strategy = ?retry_backoff.strategy,
+                    "retrying non-stream after delay"
+                );
+                sleep(Duration::from_millis(delay)).await;
+            }
+        }
+    }
+}
+
+/// 助手要求固定回复语言并开启校验时，非流式回复的主要语言不符则带提醒重试一次；
+/// 重试失败或被取消时沿用原回复
+async fn retry_on_response_language_mismatch(
+    client: &Client,
+    model_name: &str,
+    chat_request: &ChatRequest,
+    chat_options: &ChatOptions,
+    conversation_id: i64,
+    conversation_db: &ConversationDatabase,
+    app_handle: &tauri::AppHandle,
+    cancel_token: Option<&CancellationToken>,
+    chat_response: genai::chat::ChatResponse,
+) -> genai::chat::ChatResponse {
+    let Some((language, true)) =
+        conversation_response_language(app_handle, conversation_db, conversation_id)
+    else {
+        return chat_response;
+    };
+    if !chat_response.tool_calls().is_empty() {
+        return chat_response;
+    }
+    let Some(content) = chat_response.first_text().map(str::to_string) else {
+        return chat_response;
+    };
+    if !is_response_language_mismatch(&content, &language) {
+        return chat_response;
+    }
+
+    warn!(conversation_id, language = %language, "response language mismatch, retrying once");
+    let mut retry_request = chat_request.clone();
+    retry_request.messages.push(ChatMessage::assistant(content));
+    retry_request.messages.push(ChatMessage::user(response_language_reminder(&language)));
+    match exec_non_stream_chat_with_retries(
+        client,
+        model_name,
+        retry_request,
+        chat_options.clone(),
+        conversation_id,
+        cancel_token,
+        1,
+        false,
+    )
+    .await
+    {
+        Ok(Some(retry_response))
+            if retry_response.first_text().is_some_and(|text| !text.trim().is_empty()) =>
+        {
+            retry_response
+        }
+        Ok(_) => chat_response,
+        Err(e) => {
+            warn!(conversation_id, error = %e, "response language retry failed");
+            chat_response
+        }
     }
 }
 
@@ -2481,6 +3132,15 @@ pub async fn handle_non_stream_chat(
 
     // 从配置中获取最大重试次数
     let max_retry_attempts = get_retry_attempts_from_config(&config_feature_map);
+    let retry_backoff = get_retry_backoff_from_config(&config_feature_map);
+
+    let _generation_guard = ActiveGenerationGuard::enter(
+        conversation_id,
+        llm_model_id,
+        model_name,
+        &llm_model_name,
+        false,
+    );
 
     // 非流式：强制捕获工具调用，便于将工具以 UI 注释形式插入
     let non_stream_options = chat_options.clone().with_capture_tool_calls(true);
@@ -2538,81 +3198,108 @@ pub async fn handle_non_stream_chat(
         }
     }
 
-    let chat_result = {
-        let mut attempts = 0;
-        loop {
-            attempts += 1;
-
-            info!(attempts, max_retry_attempts, "non stream chat attempt");
-
-            let exec_result = if let Some(token) = cancel_token.as_ref() {
-                tokio::select! {
-                    _ = token.cancelled() => {
-                        info!(conversation_id, "non-stream chat cancelled");
-                        return Ok(());
-                    }
-                    res = client.exec_chat(model_name, chat_request.clone(), Some(&non_stream_options)) => res,
-                }
-            } else {
-                client.exec_chat(model_name, chat_request.clone(), Some(&non_stream_options)).await
-            };
-
-            match exec_result {
-                Ok(response) => {
-                    info!(attempts, "non stream chat succeeded attempt");
-                    break Ok(response);
-                }
-                Err(e) => {
-                    let _ = enhanced_error_logging_v2(
-                        &e,
-                        &format!("Non-Stream Chat (attempt {}/{})", attempts, max_retry_attempts),
-                    )
-                    .await;
-                    if attempts >= max_retry_attempts {
-                        let http_details = extract_http_error_details(&e);
-                        let raw_error = e.to_string();
-                        let final_error =
-                            http_details.response_body.clone().unwrap_or_else(|| raw_error.clone());
-
-                        error!(attempts, error = %e, final_error, "final non stream chat error");
-
-                        // 发送错误通知到合适的窗口
-                        send_error_to_appropriate_window(
-                            &window,
-                            &final_error,
-                            Some(conversation_id),
-                        );
+    let fallback_order = get_request_fallback_order_from_config(&config_feature_map);
+    let cancel_token_ref = cancel_token.as_ref();
+    let chat_result = run_with_request_fallback(
+        chat_request,
+        &non_stream_options,
+        &fallback_order,
+        |e: &genai::Error| extract_http_error_details(e).status_code == Some(400),
+        move |request, options, is_fallback| {
+            // 简化后的请求只尝试一次
+            let max_attempts = if is_fallback { 1 } else { max_retry_attempts };
+            exec_non_stream_chat_with_retries(
+                client,
+                model_name,
+                request,
+                options,
+                conversation_id,
+                cancel_token_ref,
+                max_attempts,
+                retry_backoff,
+                is_fallback,
+            )
+        },
+    )
+    .await;
+
+    let chat_result = match chat_result {
+        Ok(outcome) => match outcome.value {
+            Some(response) => Ok(response),
+            // 请求过程中被取消
+            None => return Ok(()),
+        },
+        Err(e) => {
+            let http_details = extract_http_error_details(&e);
+            let raw_error = e.to_string();
+            let final_error =
+                http_details.response_body.clone().unwrap_or_else(|| raw_error.clone());
 
-                        // 清除活动焦点（闪亮边框）
-                        if let Some(activity_manager) =
-                            app_handle.try_state::<ConversationActivityManager>()
-                        {
-                            activity_manager.clear_focus(app_handle, conversation_id).await;
-                        }
+            error!(error = %e, final_error, "final non stream chat error");
 
-                        break Err(anyhow::anyhow!("{}", final_error));
-                    }
+            // 发送错误通知到合适的窗口
+            send_error_to_appropriate_window(&window, &final_error, Some(conversation_id));
 
-                    let delay = calculate_retry_delay(attempts);
-                    debug!(delay_ms = delay, "retrying non-stream after delay");
-                    sleep(Duration::from_millis(delay)).await;
-                }
+            // 清除活动焦点（闪亮边框）
+            if let Some(activity_manager) = app_handle.try_state::<ConversationActivityManager>() {
+                activity_manager.clear_focus(app_handle, conversation_id).await;
             }
+
+            Err(anyhow::anyhow!("{}", final_error))
         }
     };
 
     match chat_result {
         Ok(chat_response) => {
+            let chat_response = retry_on_response_language_mismatch(
+                client,
+                model_name,
+                chat_request,
+                &non_stream_options,
+                conversation_id,
+                conversation_db,
+                app_handle,
+                cancel_token_ref,
+                chat_response,
+            )
+            .await;
+
             // 在创建新的 response 消息前，如果上一条是错误消息，则清理
             let _ = cleanup_last_error_message(conversation_db, conversation_id).await;
 
             let mut content = chat_response.first_text().unwrap_or("").to_string();
 
+            // 提供商审核拦截且没有任何内容时，只创建 filtered 消息，不再生成空的回复卡片
+            let content_filter_block =
+                chat_response.stop_reason.as_ref().and_then(content_filter_from_stop_reason);
+            if let Some(block) = content_filter_block.as_ref() {
+                if content.trim().is_empty() && chat_response.tool_calls().is_empty() {
+                    create_filtered_message(
+                        conversation_db,
+                        window,
+                        conversation_id,
+                        block,
+                        llm_model_id,
+                        &llm_model_name,
+                        &generation_group_id,
+                        parent_group_id_override.clone(),
+                    )
+                    .await?;
+                    if let Some(activity_manager) =
+                        app_handle.try_state::<ConversationActivityManager>()
+                    {
+                        activity_manager.clear_focus(app_handle, conversation_id).await;
+                    }
+                    return Ok(());
+                }
+            }
+
             // Extract token usage data
             let usage = &chat_response.usage;
             let input_tokens = usage.prompt_tokens.unwrap_or(0);
             let output_tokens = usage.completion_tokens.unwrap_or(0);
             let total_tokens = usage.total_tokens.unwrap_or(input_tokens + output_tokens);
+            generation_status::record_output_tokens(conversation_id, output_tokens as i64);
 
             // 现在才创建响应消息（在有实际内容后）
             let now = chrono::Utc::now();
@@ -2665,6 +3352,12 @@ pub async fn handle_non_stream_chat(
                 "Token usage captured for non-streaming response"
             );
             let response_message_id = response_message.id;
+            apply_assistant_render_mode(
+                app_handle,
+                conversation_db,
+                conversation_id,
+                response_message_id,
+            );
 
             // 现在才发送 message_add 事件（消息有内容时）
             let add_event = ConversationEvent {
@@ -2675,8 +3368,7 @@ pub async fn handle_non_stream_chat(
                 })
                 .unwrap(),
             };
-            let _ =
-                window.emit(format!("conversation_event_{}", conversation_id).as_str(), add_event);
+            emit_conversation_event(window, conversation_id, add_event);
 
             // 立即发送一个 is_done: false 的 message_update 事件，触发前端清理用户消息的 shine-border
             // 这与流式模式的行为保持一致
@@ -2695,10 +3387,7 @@ pub async fn handle_non_stream_chat(
                 })
                 .unwrap(),
             };
-            let _ = window.emit(
-                format!("conversation_event_{}", conversation_id).as_str(),
-                initial_update_event,
-            );
+            emit_conversation_event(window, conversation_id, initial_update_event);
 
             // 非流式：捕获原生 ToolCall 并处理（创建DB、UI注释、自动执行）
             let tool_calls: Vec<ToolCall> =
@@ -2791,13 +3480,23 @@ pub async fn handle_non_stream_chat(
                 })
                 .unwrap(),
             };
-            let _ = window
-                .emit(format!("conversation_event_{}", conversation_id).as_str(), update_event);
+            emit_conversation_event(window, conversation_id, update_event);
 
-            if need_generate_title && !content.is_empty() {
+            let title_config = TitleTriggerConfig::from_config(&config_feature_map);
+            let has_tool_calls = response_has_tool_calls(&content, tool_calls.len());
+            if need_generate_title
+                && !content.is_empty()
+                && !title_config.waits_for_tool_results(has_tool_calls)
+            {
                 let app_handle_clone = app_handle.clone();
-                let user_prompt_clone = user_prompt.clone();
-                let content_clone = content.clone();
+                // 按配置决定标题上下文：多轮或包含工具结果时从对话历史组织
+                let (user_prompt_clone, content_clone) = resolve_title_source(
+                    conversation_db,
+                    conversation_id,
+                    &title_config,
+                    user_prompt.clone(),
+                    content.clone(),
+                );
                 let config_feature_map_clone = config_feature_map.clone();
                 let window_clone = window.clone();
 
@@ -2822,9 +3521,11 @@ pub async fn handle_non_stream_chat(
                 conversation_db.conversation_repo().unwrap().read(conversation_id)
             {
                 if let Some(assistant_id) = conv.assistant_id {
-                    if let Ok(assistant) =
-                        crate::api::assistant_api::get_assistant(app_handle.clone(), assistant_id)
-                    {
+                    if let Ok(assistant) = crate::api::assistant_api::get_assistant(
+                        app_handle.clone(),
+                        assistant_id,
+                        None,
+                    ) {
                         Some(assistant.assistant.name.clone())
                     } else {
                         None
@@ -2854,6 +3555,8 @@ pub async fn handle_non_stream_chat(
                 None,
                 e.to_string(),
                 Some(http_details),
+                Vec::new(),
+                None,
             );
             let now = chrono::Utc::now();
             send_error_to_appropriate_window(&window, &user_friendly_error, Some(conversation_id));
@@ -2900,8 +3603,7 @@ pub async fn handle_non_stream_chat(
                 })
                 .unwrap(),
             };
-            let _ = window
-                .emit(format!("conversation_event_{}", conversation_id).as_str(), error_event);
+            emit_conversation_event(window, conversation_id, error_event);
 
             let update_event = ConversationEvent {
                 r#type: "message_update".to_string(),
@@ -2918,8 +3620,7 @@ pub async fn handle_non_stream_chat(
                 })
                 .unwrap(),
             };
-            let _ = window
-                .emit(format!("conversation_event_{}", conversation_id).as_str(), update_event);
+            emit_conversation_event(window, conversation_id, update_event);
 
             error!(error = %e, "chat error");
             Err(anyhow::anyhow!("Chat error: {}", e))
@@ -2,6 +2,7 @@ use crate::db::assistant_db::AssistantModelConfig;
 use genai::chat::ChatOptions;
 use genai::Client;
 use std::collections::HashMap;
+use tracing::warn;
 
 #[derive(Debug, Clone)]
 pub struct ChatConfig {
@@ -14,27 +15,23 @@ pub struct ChatConfig {
 pub struct ConfigBuilder;
 
 impl ConfigBuilder {
+    /// 按助手配置构建 ChatOptions，未配置的参数交由提供商使用默认值
     pub fn build_chat_options(config_map: &HashMap<String, String>) -> ChatOptions {
+        let params = SamplingParams::from_config_map(config_map);
         let mut chat_options = ChatOptions::default();
-        if let Some(temp_str) = config_map.get("temperature") {
-            if let Ok(temp) = temp_str.parse::<f64>() {
-                chat_options = chat_options.with_temperature(temp);
-            }
+        if let Some(temperature) = params.temperature {
+            chat_options = chat_options.with_temperature(temperature);
         }
-        if let Some(max_tokens_str) = config_map.get("max_tokens") {
-            if let Ok(max_tokens) = max_tokens_str.parse::<u32>() {
-                chat_options = chat_options.with_max_tokens(max_tokens);
-            }
+        if let Some(max_tokens) = params.max_tokens {
+            chat_options = chat_options.with_max_tokens(max_tokens);
         }
-        if let Some(top_p_str) = config_map.get("top_p") {
-            if let Ok(top_p) = top_p_str.parse::<f64>() {
-                chat_options = chat_options.with_top_p(top_p);
-            }
+        if let Some(top_p) = params.top_p {
+            chat_options = chat_options.with_top_p(top_p);
         }
-        if let Some(reasoning_str) = config_map.get("reasoning_effort") {
-            if let Some(effort) = genai::chat::ReasoningEffort::from_keyword(reasoning_str) {
-                chat_options = chat_options.with_reasoning_effort(effort);
-            }
+        if let Some(effort) =
+            params.reasoning_effort.as_deref().and_then(genai::chat::ReasoningEffort::from_keyword)
+        {
+            chat_options = chat_options.with_reasoning_effort(effort);
         }
         chat_options
     }
@@ -91,9 +88,113 @@ impl ConfigBuilder {
     }
 }
 
+/// 助手配置中的采样参数，未配置或取值无效的项为 None
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SamplingParams {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub max_tokens: Option<u32>,
+    /// 推理强度关键字，如 low / medium / high
+    pub reasoning_effort: Option<String>,
+}
+
+impl SamplingParams {
+    /// 解析并校验采样参数，超出范围的值会被忽略，与推理强度冲突的参数会被移除
+    pub fn from_config_map(config_map: &HashMap<String, String>) -> Self {
+        let value = |key: &str| {
+            config_map.get(key).map(|value| value.trim()).filter(|value| !value.is_empty())
+        };
+
+        let temperature = value("temperature").and_then(|raw| match raw.parse::<f64>() {
+            Ok(temperature) if (0.0..=2.0).contains(&temperature) => Some(temperature),
+            _ => {
+                warn!(value = raw, "ignoring invalid temperature, expected 0.0 ~ 2.0");
+                None
+            }
+        });
+        let top_p = value("top_p").and_then(|raw| match raw.parse::<f64>() {
+            Ok(top_p) if (0.0..=1.0).contains(&top_p) => Some(top_p),
+            _ => {
+                warn!(value = raw, "ignoring invalid top_p, expected 0.0 ~ 1.0");
+                None
+            }
+        });
+        let max_tokens = value("max_tokens").and_then(|raw| match raw.parse::<u32>() {
+            Ok(max_tokens) if max_tokens > 0 => Some(max_tokens),
+            _ => {
+                warn!(value = raw, "ignoring invalid max_tokens");
+                None
+            }
+        });
+        let reasoning_effort = value("reasoning_effort").and_then(|raw| {
+            if genai::chat::ReasoningEffort::from_keyword(raw).is_some() {
+                Some(raw.to_string())
+            } else {
+                warn!(value = raw, "ignoring unknown reasoning_effort");
+                None
+            }
+        });
+
+        let params = SamplingParams { temperature, top_p, max_tokens, reasoning_effort };
+        params.drop_incompatible(config_map.get("model").map(String::as_str).unwrap_or(""))
+    }
+
+    /// 部分模型在开启推理时不接受 temperature / top_p，发送前移除以免请求被拒绝
+    fn drop_incompatible(mut self, model: &str) -> Self {
+        let reasoning_enabled = self
+            .reasoning_effort
+            .as_deref()
+            .is_some_and(|effort| !effort.eq_ignore_ascii_case("none"));
+        if !reasoning_enabled || !rejects_sampling_with_reasoning(model) {
+            return self;
+        }
+        if let Some(temperature) = self.temperature.take() {
+            warn!(model, temperature, "dropping temperature: not supported with reasoning_effort");
+        }
+        if let Some(top_p) = self.top_p.take() {
+            warn!(model, top_p, "dropping top_p: not supported with reasoning_effort");
+        }
+        self
+    }
+}
+
+/// 开启推理后拒绝 temperature / top_p 的模型：OpenAI o 系列、GPT-5 系列以及 Claude 扩展思考
+pub fn rejects_sampling_with_reasoning(model: &str) -> bool {
+    let model = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    ["o1", "o3", "o4", "gpt-5"].iter().any(|prefix| model.starts_with(prefix))
+        || model.contains("claude")
+}
+
+/// 读取助手模型配置项的值（去除首尾空白），未配置或为空时返回 None
+pub fn config_value<'a>(configs: &'a [AssistantModelConfig], name: &str) -> Option<&'a str> {
+    configs
+        .iter()
+        .find(|config| config.name == name)
+        .and_then(|config| config.value.as_deref())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// 解析开关类配置值，不区分大小写；无法识别时返回 None，由调用方决定默认值
+pub fn parse_config_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// 读取助手模型配置中的开关项，未配置或无法识别时返回 None
+pub fn config_bool(configs: &[AssistantModelConfig], name: &str) -> Option<bool> {
+    config_value(configs, name).and_then(parse_config_bool)
+}
+
 pub const MAX_RETRY_ATTEMPTS: u32 = 3;
 pub const RETRY_DELAY_BASE_MS: u64 = 2000;
 pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 180; // 3分钟默认超时
+pub const DEFAULT_STREAM_IDLE_TIMEOUT_MS: u64 = 0; // 默认不检测流式空闲，避免中断长时间思考的模型
+pub const DEFAULT_MODEL_LIST_CACHE_TTL_SECS: u64 = 600; // 提供商模型列表默认缓存 10 分钟
+pub const DEFAULT_MCP_HEALTH_CHECK_INTERVAL_SECS: u64 = 300; // MCP 服务器默认每 5 分钟检查一次
 
 /// 从网络配置中获取重试次数，如果没有配置则使用默认值
 pub fn get_retry_attempts_from_config(
@@ -109,6 +210,74 @@ pub fn get_retry_attempts_from_config(
     MAX_RETRY_ATTEMPTS
 }
 
+/// 请求失败后的重试退避策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetryBackoffStrategy {
+    /// 每次重试间隔固定
+    Fixed,
+    /// 重试间隔随次数线性增长
+    Linear,
+    /// 重试间隔随次数指数增长（默认，保持原有行为）
+    #[default]
+    Exponential,
+    /// 指数增长并叠加随机抖动，避免多个请求同时重试
+    ExponentialJitter,
+}
+
+impl RetryBackoffStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "fixed" => Some(Self::Fixed),
+            "linear" => Some(Self::Linear),
+            "exponential" => Some(Self::Exponential),
+            "exponential_jitter" => Some(Self::ExponentialJitter),
+            _ => None,
+        }
+    }
+}
+
+/// 重试退避配置，max_delay_ms 为 None 时不限制单次延迟
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetryBackoffConfig {
+    pub strategy: RetryBackoffStrategy,
+    pub max_delay_ms: Option<u64>,
+}
+
+impl RetryBackoffConfig {
+    /// 计算第 attempt 次失败后的重试延迟
+    pub fn delay_for(&self, attempt: u32) -> u64 {
+        calculate_retry_delay(attempt, self.strategy, self.max_delay_ms)
+    }
+}
+
+/// 从网络配置中获取重试退避策略与单次延迟上限，未配置或无效时保持原有的指数退避
+pub fn get_retry_backoff_from_config(
+    config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
+) -> RetryBackoffConfig {
+    let network_config = config_feature_map.get("network_config");
+    let strategy = network_config
+        .and_then(|network_config| network_config.get("retry_backoff"))
+        .and_then(|config| RetryBackoffStrategy::parse(&config.value))
+        .unwrap_or_default();
+    let max_delay_ms = network_config
+        .and_then(|network_config| network_config.get("retry_max_delay_ms"))
+        .and_then(|config| config.value.trim().parse::<u64>().ok())
+        .filter(|max_delay_ms| *max_delay_ms > 0);
+    RetryBackoffConfig { strategy, max_delay_ms }
+}
+
+/// 从网络配置中获取持续 400 时的请求简化顺序，如果没有配置则使用默认顺序；配置为空表示关闭
+pub fn get_request_fallback_order_from_config(
+    config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
+) -> Vec<crate::api::ai::request_fallback::RequestSimplification> {
+    let value = config_feature_map
+        .get("network_config")
+        .and_then(|network_config| network_config.get("bad_request_fallback"))
+        .map(|config| config.value.as_str())
+        .unwrap_or(crate::api::ai::request_fallback::DEFAULT_FALLBACK_ORDER);
+    crate::api::ai::request_fallback::parse_fallback_order(value)
+}
+
 /// 从网络配置中获取请求超时时间（秒），如果没有配置则使用默认值
 pub fn get_request_timeout_from_config(
     config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
@@ -123,6 +292,53 @@ pub fn get_request_timeout_from_config(
     DEFAULT_REQUEST_TIMEOUT_SECS
 }
 
+/// 从网络配置中获取流式响应的空闲超时，配置为 0 表示不检测
+pub fn get_stream_idle_timeout_from_config(
+    config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
+) -> Option<std::time::Duration> {
+    let timeout_ms = config_feature_map
+        .get("network_config")
+        .and_then(|network_config| network_config.get("stream_idle_timeout_ms"))
+        .and_then(|config| config.value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_STREAM_IDLE_TIMEOUT_MS);
+    (timeout_ms > 0).then(|| std::time::Duration::from_millis(timeout_ms))
+}
+
+/// 从网络配置中获取提供商模型列表的缓存时间，配置为 0 表示不缓存
+pub fn get_model_list_cache_ttl_from_config(
+    config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
+) -> std::time::Duration {
+    let ttl_secs = config_feature_map
+        .get("network_config")
+        .and_then(|network_config| network_config.get("model_list_cache_ttl_secs"))
+        .and_then(|config| config.value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MODEL_LIST_CACHE_TTL_SECS);
+    std::time::Duration::from_secs(ttl_secs)
+}
+
+/// 从网络配置中获取 MCP 服务器健康检查间隔，配置为 0 表示关闭健康检查
+pub fn get_mcp_health_check_interval_from_config(
+    config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
+) -> Option<std::time::Duration> {
+    let interval_secs = config_feature_map
+        .get("network_config")
+        .and_then(|network_config| network_config.get("mcp_health_check_interval_secs"))
+        .and_then(|config| config.value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MCP_HEALTH_CHECK_INTERVAL_SECS);
+    (interval_secs > 0).then(|| std::time::Duration::from_secs(interval_secs))
+}
+
+/// 从网络配置中获取 MCP 工具执行超时（毫秒），未配置或为 0 时返回 None，沿用服务器的请求超时
+pub fn get_mcp_tool_timeout_from_config(
+    config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
+) -> Option<u64> {
+    config_feature_map
+        .get("network_config")
+        .and_then(|network_config| network_config.get("mcp_tool_timeout_ms"))
+        .and_then(|config| config.value.trim().parse::<u64>().ok())
+        .filter(|timeout_ms| *timeout_ms > 0)
+}
+
 /// 从网络配置中获取网络代理URL
 pub fn get_network_proxy_from_config(
     config_feature_map: &HashMap<String, HashMap<String, crate::db::system_db::FeatureConfig>>,
@@ -170,7 +386,24 @@ pub fn get_custom_headers_from_config(
     HashMap::new()
 }
 
-/// 计算重试延迟，使用指数退避策略
-pub fn calculate_retry_delay(attempt: u32) -> u64 {
-    RETRY_DELAY_BASE_MS * (2_u64.pow(attempt.saturating_sub(1)))
+/// 按退避策略计算重试延迟，超过上限时截断；带抖动时在 [延迟/2, 延迟] 区间内随机
+pub fn calculate_retry_delay(
+    attempt: u32,
+    strategy: RetryBackoffStrategy,
+    max_delay_ms: Option<u64>,
+) -> u64 {
+    let delay = match strategy {
+        RetryBackoffStrategy::Fixed => RETRY_DELAY_BASE_MS,
+        RetryBackoffStrategy::Linear => RETRY_DELAY_BASE_MS.saturating_mul(attempt.max(1) as u64),
+        RetryBackoffStrategy::Exponential | RetryBackoffStrategy::ExponentialJitter => {
+            RETRY_DELAY_BASE_MS.saturating_mul(2_u64.saturating_pow(attempt.saturating_sub(1)))
+        }
+    };
+    let delay = max_delay_ms.map_or(delay, |max_delay_ms| delay.min(max_delay_ms));
+    if strategy == RetryBackoffStrategy::ExponentialJitter {
+        let half = delay / 2;
+        half + rand::random::<u64>() % (delay - half + 1)
+    } else {
+        delay
+    }
 }
@@ -0,0 +1,161 @@
+//! 提供商内容审核拦截（content filter / moderation）的识别与消息构建
+//!
+//! 拦截既不是模型拒答也不是请求错误：提供商以特定的 finish reason 结束生成，回复通常为空或被截断。
+//! 识别后生成一条 `filtered` 类型的消息说明原因，避免前端只看到空回复或通用错误卡片。
+
+use crate::db::conversation_db::Message;
+use genai::chat::StopReason;
+use serde::{Deserialize, Serialize};
+
+/// 内容被审核拦截时创建的消息类型
+pub const FILTERED_MESSAGE_TYPE: &str = "filtered";
+
+/// 内容被审核拦截时发出的对话事件
+pub const CONTENT_FILTERED_EVENT: &str = "content_filtered";
+
+/// 一次审核拦截的信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentFilterBlock {
+    /// 提供商返回的原始 finish reason
+    pub reason: String,
+    /// 拦截类别（提供商给出时才有）
+    pub category: Option<String>,
+}
+
+/// 内容审核拦截事件的 payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentFilteredEvent {
+    pub conversation_id: i64,
+    pub message_id: i64,
+    pub reason: String,
+    pub category: Option<String>,
+}
+
+/// 根据原始 finish reason 判断是否为审核拦截
+///
+/// - OpenAI / Azure / DeepSeek 等：`content_filter`
+/// - Gemini：`SAFETY`、`PROHIBITED_CONTENT`、`BLOCKLIST`、`SPII`、`IMAGE_SAFETY` 等，本身即为类别
+/// - 智谱：`sensitive`
+pub fn content_filter_from_raw_reason(raw: &str) -> Option<ContentFilterBlock> {
+    let normalized = raw.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+    let category = match normalized.as_str() {
+        "content_filter" | "content_filtered" | "contentfilter" => None,
+        "safety"
+        | "prohibited_content"
+        | "blocklist"
+        | "spii"
+        | "image_safety"
+        | "image_prohibited_content"
+        | "sensitive" => Some(raw.trim().to_string()),
+        _ => return None,
+    };
+    Some(ContentFilterBlock { reason: raw.trim().to_string(), category })
+}
+
+/// 从 genai 的 StopReason 中识别审核拦截
+pub fn content_filter_from_stop_reason(stop_reason: &StopReason) -> Option<ContentFilterBlock> {
+    match stop_reason {
+        StopReason::ContentFilter(raw) => Some(
+            content_filter_from_raw_reason(raw)
+                .unwrap_or_else(|| ContentFilterBlock { reason: raw.clone(), category: None }),
+        ),
+        // 部分适配器未归类的拦截原因（如 Gemini 的 PROHIBITED_CONTENT）落在 Other 中
+        StopReason::Other(raw) => content_filter_from_raw_reason(raw),
+        _ => None,
+    }
+}
+
+/// 面向用户的拦截说明
+pub fn filtered_message_content(block: &ContentFilterBlock) -> String {
+    let mut content = String::from("该回复已被模型提供商的内容审核拦截，未能生成完整内容。");
+    if let Some(category) = &block.category {
+        content.push_str(&format!("\n\n拦截类别：{}", category));
+    }
+    content.push_str(&format!("\n\n结束原因：{}", block.reason));
+    content
+}
+
+/// 构建 filtered 类型的消息，与被拦截的回复属于同一生成组
+pub fn build_filtered_message(
+    conversation_id: i64,
+    block: &ContentFilterBlock,
+    llm_model_id: i64,
+    llm_model_name: &str,
+    generation_group_id: &str,
+    parent_group_id: Option<String>,
+) -> Message {
+    let now = chrono::Utc::now();
+    Message {
+        id: 0,
+        parent_id: None,
+        conversation_id,
+        message_type: FILTERED_MESSAGE_TYPE.to_string(),
+        content: filtered_message_content(block),
+        llm_model_id: Some(llm_model_id),
+        llm_model_name: Some(llm_model_name.to_string()),
+        created_time: now,
+        start_time: Some(now),
+        finish_time: Some(now),
+        token_count: 0,
+        input_token_count: 0,
+        output_token_count: 0,
+        generation_group_id: Some(generation_group_id.to_string()),
+        parent_group_id,
+        tool_calls_json: None,
+        first_token_time: None,
+        ttft_ms: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试模拟的 content_filter 结束原因生成带原因的 filtered 消息
+    #[test]
+    fn test_content_filter_response_creates_filtered_message() {
+        let stop_reason = StopReason::ContentFilter("content_filter".to_string());
+
+        let block = content_filter_from_stop_reason(&stop_reason).expect("should detect filter");
+        assert_eq!(
+            block,
+            ContentFilterBlock { reason: "content_filter".to_string(), category: None }
+        );
+
+        let message = build_filtered_message(3, &block, 1, "gpt-4o", "group-1", None);
+        assert_eq!(message.message_type, FILTERED_MESSAGE_TYPE);
+        assert_eq!(message.conversation_id, 3);
+        assert_eq!(message.generation_group_id.as_deref(), Some("group-1"));
+        assert!(message.content.contains("内容审核拦截"));
+        assert!(message.content.contains("结束原因：content_filter"));
+        assert!(!message.content.contains("拦截类别"));
+    }
+
+    #[test]
+    fn test_provider_specific_reasons_carry_category() {
+        let block = content_filter_from_stop_reason(&StopReason::Other("SAFETY".to_string()))
+            .expect("Gemini SAFETY should be treated as a filter block");
+        assert_eq!(block.category.as_deref(), Some("SAFETY"));
+        assert!(filtered_message_content(&block).contains("拦截类别：SAFETY"));
+
+        assert!(content_filter_from_raw_reason("sensitive").is_some());
+        assert!(content_filter_from_raw_reason("PROHIBITED_CONTENT").is_some());
+    }
+
+    #[test]
+    fn test_normal_stop_reasons_are_not_filtered() {
+        assert_eq!(
+            content_filter_from_stop_reason(&StopReason::Completed("stop".to_string())),
+            None
+        );
+        assert_eq!(
+            content_filter_from_stop_reason(&StopReason::MaxTokens("length".to_string())),
+            None
+        );
+        assert_eq!(
+            content_filter_from_stop_reason(&StopReason::Other("refusal".to_string())),
+            None
+        );
+        assert_eq!(content_filter_from_raw_reason("tool_calls"), None);
+    }
+}
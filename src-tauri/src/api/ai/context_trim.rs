@@ -0,0 +1,341 @@
+//! 上下文窗口裁剪：对话的估算 token 超过助手配置的上限时，按策略裁剪较早的消息，
+//! 避免请求超出模型的上下文窗口。system 消息、置顶消息与当前用户轮次始终保留
+
+use crate::api::ai::config::config_value;
+use crate::db::assistant_db::AssistantModelConfig;
+use crate::db::conversation_db::{ConversationSummary, Message};
+use crate::utils::token_utils::estimate_tokens;
+use std::collections::HashSet;
+use tracing::{debug, info};
+
+/// 助手配置项：裁剪策略（drop_oldest / summarize_oldest / keep_last_n）
+pub const CONTEXT_TRIM_STRATEGY_CONFIG_KEY: &str = "context_trim_strategy";
+/// 助手配置项：上下文 token 上限，未配置或为 0 时不裁剪
+pub const CONTEXT_MAX_TOKENS_CONFIG_KEY: &str = "context_max_tokens";
+/// 助手配置项：keep_last_n 策略保留的最近消息数量
+pub const CONTEXT_KEEP_LAST_N_CONFIG_KEY: &str = "context_keep_last_n";
+
+const DEFAULT_KEEP_LAST_N: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContextTrimStrategy {
+    /// 从最早的轮次开始丢弃，直到不超过上限
+    #[default]
+    DropOldest,
+    /// 与 DropOldest 相同，并把对话总结附加到系统提示词中，没有总结时等同 DropOldest
+    SummarizeOldest,
+    /// 只保留 system 与最近 N 条消息（按整轮丢弃），仍超出时继续丢弃最早的轮次
+    KeepLastN,
+}
+
+impl ContextTrimStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "drop_oldest" => Some(Self::DropOldest),
+            "summarize_oldest" => Some(Self::SummarizeOldest),
+            "keep_last_n" => Some(Self::KeepLastN),
+            _ => None,
+        }
+    }
+}
+
+/// 上下文裁剪配置
+#[derive(Clone, Debug, Default)]
+pub struct ContextTrim {
+    pub strategy: ContextTrimStrategy,
+    pub max_tokens: usize,
+    pub keep_last_n: usize,
+    /// SummarizeOldest 使用的对话总结
+    pub summary: Option<String>,
+    /// 尚未写入历史的当前提问（含附件上下文）的估算 token，计入预算；
+    /// 大于 0 时当前轮次就是这条提问，历史消息都可以被裁剪
+    pub pending_tokens: usize,
+}
+
+/// 从助手模型配置中读取裁剪配置，未配置 token 上限时返回 None
+pub fn context_trim_from_configs(configs: &[AssistantModelConfig]) -> Option<ContextTrim> {
+    let max_tokens = config_value(configs, CONTEXT_MAX_TOKENS_CONFIG_KEY)
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|value| *value > 0)?;
+    let strategy = config_value(configs, CONTEXT_TRIM_STRATEGY_CONFIG_KEY)
+        .and_then(ContextTrimStrategy::parse)
+        .unwrap_or_default();
+    let keep_last_n = config_value(configs, CONTEXT_KEEP_LAST_N_CONFIG_KEY)
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_KEEP_LAST_N);
+    Some(ContextTrim { strategy, max_tokens, keep_last_n, ..Default::default() })
+}
+
+/// 把对话总结整理为附加到系统提示词的文本
+pub fn format_summary_for_context(summary: &ConversationSummary) -> Option<String> {
+    let mut parts = Vec::new();
+    if !summary.summary.trim().is_empty() {
+        parts.push(summary.summary.trim().to_string());
+    }
+    if !summary.key_outcomes.trim().is_empty() {
+        parts.push(format!("关键成果：{}", summary.key_outcomes.trim()));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("\n"))
+    }
+}
+
+fn message_tokens(message: &Message) -> usize {
+    estimate_tokens(&message.content)
+}
+
+/// 按 token 上限裁剪消息（保持原有顺序）。最后一条 user 消息及其之后的内容属于当前轮次，不会被裁剪；
+/// 丢弃以轮次为单位，停在 user 消息处，避免留下没有提问的回复
+pub fn trim_messages_to_token_budget(
+    mut messages: Vec<Message>,
+    trim: &ContextTrim,
+    pinned_message_ids: &HashSet<i64>,
+) -> Vec<Message> {
+    let mut total: usize = messages.iter().map(message_tokens).sum::<usize>() + trim.pending_tokens;
+    if trim.max_tokens == 0 || total <= trim.max_tokens {
+        return messages;
+    }
+
+    let current_turn_start = if trim.pending_tokens > 0 {
+        messages.len()
+    } else {
+        messages
+            .iter()
+            .rposition(|message| message.message_type == "user")
+            .unwrap_or(messages.len())
+    };
+    let candidates: Vec<usize> = (0..current_turn_start)
+        .filter(|&index| {
+            messages[index].message_type != "system"
+                && !pinned_message_ids.contains(&messages[index].id)
+        })
+        .collect();
+
+    let mut dropped: HashSet<usize> = HashSet::new();
+    if trim.strategy == ContextTrimStrategy::KeepLastN {
+        let conversation_count =
+            messages.iter().filter(|message| message.message_type != "system").count()
+                + usize::from(trim.pending_tokens > 0);
+        let mut drop_count =
+            conversation_count.saturating_sub(trim.keep_last_n).min(candidates.len());
+        // 截断点落在轮次中间时把整轮一起丢弃，避免留下没有提问的回复或工具结果
+        while drop_count > 0
+            && drop_count < candidates.len()
+            && messages[candidates[drop_count]].message_type != "user"
+        {
+            drop_count += 1;
+        }
+        for &index in &candidates[..drop_count] {
+            total -= message_tokens(&messages[index]);
+            dropped.insert(index);
+        }
+    }
+    for &index in &candidates {
+        if dropped.contains(&index) {
+            continue;
+        }
+        if total <= trim.max_tokens && messages[index].message_type == "user" {
+            break;
+        }
+        total -= message_tokens(&messages[index]);
+        dropped.insert(index);
+    }
+    if dropped.is_empty() {
+        debug!(estimated_tokens = total, "context over budget but nothing can be trimmed");
+        return messages;
+    }
+
+    let original_count = messages.len();
+    let mut index = 0;
+    messages.retain(|_| {
+        let keep = !dropped.contains(&index);
+        index += 1;
+        keep
+    });
+
+    if trim.strategy == ContextTrimStrategy::SummarizeOldest {
+        if let Some(summary) = trim.summary.as_deref() {
+            let note = format!("以下是本对话较早内容的摘要（原始消息已省略）：\n{}", summary);
+            match messages.iter_mut().find(|message| message.message_type == "system") {
+                Some(system) => {
+                    system.content = format!("{}\n\n{}", system.content, note);
+                }
+                None => {
+                    if let Some(mut system) = messages.first().cloned() {
+                        system.id = 0;
+                        system.message_type = "system".to_string();
+                        system.content = note;
+                        system.tool_calls_json = None;
+                        messages.insert(0, system);
+                    }
+                }
+            }
+        }
+    }
+
+    info!(
+        strategy = ?trim.strategy,
+        original_messages = original_count,
+        dropped_messages = dropped.len(),
+        estimated_tokens = total,
+        max_tokens = trim.max_tokens,
+        "trimmed context to token budget"
+    );
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tests::test_helpers::{create_test_message, create_test_model_config};
+    use chrono::{Duration, Utc};
+
+    fn message(id: i64, message_type: &str, content: &str) -> Message {
+        Message {
+            id,
+            created_time: Utc::now() + Duration::seconds(id),
+            ..create_test_message(1, message_type, content, None, None)
+        }
+    }
+
+    /// system + 3 轮历史（每条约 100 tokens）+ 当前提问
+    fn conversation() -> Vec<Message> {
+        let long = "a".repeat(400);
+        vec![
+            message(1, "system", "sys"),
+            message(2, "user", &long),
+            message(3, "response", &long),
+            message(4, "user", &long),
+            message(5, "response", &long),
+            message(6, "user", &long),
+            message(7, "response", &long),
+            message(8, "user", "current question"),
+        ]
+    }
+
+    fn ids(messages: &[Message]) -> Vec<i64> {
+        messages.iter().map(|message| message.id).collect()
+    }
+
+    fn trim(strategy: ContextTrimStrategy, max_tokens: usize) -> ContextTrim {
+        ContextTrim { strategy, max_tokens, keep_last_n: 3, ..Default::default() }
+    }
+
+    #[test]
+    fn test_drop_oldest_trims_whole_turns() {
+        let trimmed = trim_messages_to_token_budget(
+            conversation(),
+            &trim(ContextTrimStrategy::DropOldest, 350),
+            &HashSet::new(),
+        );
+        assert_eq!(ids(&trimmed), vec![1, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_trim_never_drops_system_or_current_turn() {
+        let trimmed = trim_messages_to_token_budget(
+            conversation(),
+            &trim(ContextTrimStrategy::DropOldest, 1),
+            &HashSet::new(),
+        );
+        assert_eq!(ids(&trimmed), vec![1, 8]);
+
+        // 置顶消息同样保留
+        let trimmed = trim_messages_to_token_budget(
+            conversation(),
+            &trim(ContextTrimStrategy::DropOldest, 1),
+            &HashSet::from([3]),
+        );
+        assert_eq!(ids(&trimmed), vec![1, 3, 8]);
+    }
+
+    /// 尚未写入历史的当前提问计入预算，历史中最后一轮不再被当作当前轮次保护
+    #[test]
+    fn test_pending_prompt_counts_against_budget() {
+        let mut history = conversation();
+        history.pop();
+
+        let config = trim(ContextTrimStrategy::DropOldest, 450);
+        assert_eq!(
+            ids(&trim_messages_to_token_budget(history.clone(), &config, &HashSet::new())),
+            vec![1, 4, 5, 6, 7]
+        );
+
+        let config = ContextTrim { pending_tokens: 100, ..config };
+        let trimmed = trim_messages_to_token_budget(history.clone(), &config, &HashSet::new());
+        assert_eq!(ids(&trimmed), vec![1, 6, 7]);
+
+        // 提问本身就超出上限时，历史全部丢弃，只保留 system
+        let config = ContextTrim { pending_tokens: 1000, ..config };
+        assert_eq!(ids(&trim_messages_to_token_budget(history, &config, &HashSet::new())), vec![1]);
+    }
+
+    #[test]
+    fn test_within_budget_is_untouched() {
+        let trimmed = trim_messages_to_token_budget(
+            conversation(),
+            &trim(ContextTrimStrategy::KeepLastN, 10_000),
+            &HashSet::new(),
+        );
+        assert_eq!(trimmed.len(), 8);
+    }
+
+    #[test]
+    fn test_keep_last_n() {
+        let trimmed = trim_messages_to_token_budget(
+            conversation(),
+            &trim(ContextTrimStrategy::KeepLastN, 600),
+            &HashSet::new(),
+        );
+        assert_eq!(ids(&trimmed), vec![1, 6, 7, 8]);
+    }
+
+    /// N 截在工具调用轮次中间时，整轮（提问、工具调用、工具结果、回复）一起丢弃
+    #[test]
+    fn test_keep_last_n_drops_whole_tool_call_turn() {
+        let long = "a".repeat(400);
+        let messages = vec![
+            message(1, "system", "sys"),
+            message(2, "user", &long),
+            message(3, "response", &long),
+            message(4, "tool_result", &long),
+            message(5, "response", &long),
+            message(6, "user", &long),
+            message(7, "response", &long),
+            message(8, "user", "current question"),
+        ];
+        let config = ContextTrim { keep_last_n: 4, ..trim(ContextTrimStrategy::KeepLastN, 600) };
+        let trimmed = trim_messages_to_token_budget(messages, &config, &HashSet::new());
+        assert_eq!(ids(&trimmed), vec![1, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_summarize_oldest_appends_summary_to_system_prompt() {
+        let mut config = trim(ContextTrimStrategy::SummarizeOldest, 350);
+        config.summary = Some("用户在调试 Rust 生命周期问题".to_string());
+        let trimmed = trim_messages_to_token_budget(conversation(), &config, &HashSet::new());
+        assert_eq!(ids(&trimmed), vec![1, 6, 7, 8]);
+        assert!(trimmed[0].content.starts_with("sys\n\n"));
+        assert!(trimmed[0].content.contains("用户在调试 Rust 生命周期问题"));
+    }
+
+    #[test]
+    fn test_context_trim_from_configs() {
+        assert!(context_trim_from_configs(&[create_test_model_config(
+            CONTEXT_TRIM_STRATEGY_CONFIG_KEY,
+            "keep_last_n"
+        )])
+        .is_none());
+
+        let trim = context_trim_from_configs(&[
+            create_test_model_config(CONTEXT_MAX_TOKENS_CONFIG_KEY, "32000"),
+            create_test_model_config(CONTEXT_TRIM_STRATEGY_CONFIG_KEY, "summarize_oldest"),
+        ])
+        .unwrap();
+        assert_eq!(trim.max_tokens, 32000);
+        assert_eq!(trim.strategy, ContextTrimStrategy::SummarizeOldest);
+        assert_eq!(trim.keep_last_n, DEFAULT_KEEP_LAST_N);
+    }
+}
@@ -1,17 +1,18 @@
+use crate::api::ai::context_trim::{trim_messages_to_token_budget, ContextTrim};
 use crate::api::ai::summary::get_latest_branch_messages;
 use crate::api::ai_api::{build_tool_name, ToolNameMapping};
 use crate::db::conversation_db::AttachmentType;
 use crate::db::conversation_db::Repository;
 use crate::db::conversation_db::{Conversation, ConversationDatabase, Message, MessageAttachment};
 use crate::errors::AppError;
+use crate::utils::window_utils::emit_conversation_event;
 use base64::Engine;
 use genai::chat::{
     ChatMessage, ChatRequest, ContentPart, MessageContent, Tool, ToolCall, ToolResponse,
 };
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use tauri::Emitter;
+use std::sync::{Arc, LazyLock};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 
@@ -140,10 +141,8 @@ fn filter_tool_results_for_branch(
     selection: &str,
     messages: &mut Vec<Message>,
 ) {
+    // 没有任何工具调用时也要过滤，截断后遗留的工具结果不能单独发送
     let valid_tool_call_ids = collect_valid_tool_call_ids(messages);
-    if valid_tool_call_ids.is_empty() {
-        return;
-    }
 
     let before = messages.len();
     messages.retain(|message| {
@@ -315,6 +314,8 @@ pub fn build_chat_messages_with_context(
             "reasoning" => {
                 append_reasoning_content(&mut pending_reasoning_content, content);
             }
+            // 审核拦截说明只面向用户，不回传给模型
+            "filtered" => {}
             "tool_result" => {
                 debug!("processing tool_result message");
                 let tool_call_id =
@@ -365,16 +366,34 @@ pub fn build_chat_messages_with_context(
     chat_messages
 }
 
+static MCP_HINT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<!--\s*MCP_TOOL_CALL:(.*?)-->").unwrap());
+
+/// 解析内容中的 MCP 工具调用 UI 注释，返回去除注释后的内容以及每个注释中的工具调用数据
+pub fn extract_mcp_tool_call_hints(content: &str) -> (String, Vec<serde_json::Value>) {
+    let hints = MCP_HINT_REGEX
+        .captures_iter(content)
+        .map(|capture| {
+            serde_json::from_str::<serde_json::Value>(capture[1].trim())
+                .unwrap_or(serde_json::Value::Null)
+        })
+        .collect();
+    (MCP_HINT_REGEX.replace_all(content, "").to_string(), hints)
+}
+
+/// 移除内容中的 MCP 工具调用 UI 注释
+pub fn strip_mcp_tool_call_hints(content: &str) -> String {
+    extract_mcp_tool_call_hints(content).0
+}
+
 /// 在非原生 toolcall 场景下，移除 MCP 注释并将 tool_result 转换为 user 消息，避免重新构建原生 tool_calls
 pub fn sanitize_messages_for_non_native(
     init_message_list: &[(String, String, Vec<MessageAttachment>)],
 ) -> Vec<(String, String, Vec<MessageAttachment>)> {
-    let mcp_hint_regex = Regex::new(r"<!--\s*MCP_TOOL_CALL:.*?-->").unwrap();
-
     init_message_list
         .iter()
         .map(|(message_type, content, attachments)| {
-            let sanitized_content = mcp_hint_regex.replace_all(content, "").to_string();
+            let sanitized_content = strip_mcp_tool_call_hints(content);
             if message_type == "tool_result" {
                 (String::from("user"), sanitized_content, Vec::new())
             } else {
@@ -508,6 +527,126 @@ fn build_native_toolcall_paired_messages(
     chat_messages
 }
 
+/// 部分回复在上下文中附加的说明，避免模型把中断的回复当作完整答案
+pub const PARTIAL_RESPONSE_NOTE: &str = "[此回复在生成过程中中断，内容不完整]";
+
+/// 为生成中途中断的部分回复附加说明，仅影响本次组装的上下文，不修改已保存的消息
+pub fn annotate_partial_responses(
+    messages: &mut [(Message, Option<MessageAttachment>)],
+    partial_message_ids: &HashSet<i64>,
+) {
+    if partial_message_ids.is_empty() {
+        return;
+    }
+    for (message, _) in messages.iter_mut() {
+        if partial_message_ids.contains(&message.id) {
+            message.content = format!("{}\n\n{}", message.content, PARTIAL_RESPONSE_NOTE);
+        }
+    }
+}
+
+/// 上下文截断时最多额外保留的置顶消息数量，避免置顶过多导致上下文失控
+pub const MAX_PINNED_MESSAGES_IN_CONTEXT: usize = 20;
+
+/// 上下文截断配置
+#[derive(Clone, Debug, Default)]
+pub struct ContextTruncation {
+    /// 保留最近的非 system 消息数量，0 表示不截断
+    pub max_messages: usize,
+    /// 置顶消息 ID，截断时无论新旧都会保留
+    pub pinned_message_ids: HashSet<i64>,
+    /// 按 token 上限裁剪，在按数量截断之后执行
+    pub token_trim: Option<ContextTrim>,
+}
+
+/// 按最近消息数量截断上下文，system 消息与置顶消息始终保留，保持原有顺序
+///
+/// 以轮次（一条 user 消息及其后的回复、工具结果）为单位截断，避免工具调用与工具结果被拆开；
+/// 最近一轮即使超过上限也会完整保留
+pub fn truncate_messages_keep_pinned(
+    messages: Vec<Message>,
+    truncation: &ContextTruncation,
+) -> Vec<Message> {
+    let conversation_indices: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.message_type != "system")
+        .map(|(index, _)| index)
+        .collect();
+    if truncation.max_messages == 0 || conversation_indices.len() <= truncation.max_messages {
+        return messages;
+    }
+
+    let mut turns: Vec<Vec<usize>> = Vec::new();
+    for index in conversation_indices {
+        match turns.last_mut() {
+            Some(turn) if messages[index].message_type != "user" => turn.push(index),
+            _ => turns.push(vec![index]),
+        }
+    }
+
+    let mut keep: HashSet<usize> = HashSet::new();
+    let mut kept_count = 0;
+    let mut older_turns = turns.len();
+    for turn in turns.iter().rev() {
+        if kept_count > 0 && kept_count + turn.len() > truncation.max_messages {
+            break;
+        }
+        kept_count += turn.len();
+        keep.extend(turn.iter().copied());
+        older_turns -= 1;
+    }
+
+    // 较旧的置顶消息优先保留离当前最近的，数量受上限约束
+    let pinned_older: Vec<usize> = turns[..older_turns]
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|&index| truncation.pinned_message_ids.contains(&messages[index].id))
+        .collect();
+    let pinned_skip = pinned_older.len().saturating_sub(MAX_PINNED_MESSAGES_IN_CONTEXT);
+    if pinned_skip > 0 {
+        warn!(
+            pinned = pinned_older.len(),
+            limit = MAX_PINNED_MESSAGES_IN_CONTEXT,
+            "too many pinned messages, dropping the oldest ones from context"
+        );
+    }
+    // 置顶的回复若包含工具调用，一并保留同一轮内对应的工具结果
+    for &index in pinned_older.iter().skip(pinned_skip) {
+        keep.insert(index);
+        if messages[index].message_type != "response" {
+            continue;
+        }
+        let call_ids = extract_tool_call_ids_from_mcp_comments(&messages[index].content);
+        if call_ids.is_empty() {
+            continue;
+        }
+        if let Some(turn) = turns[..older_turns].iter().find(|turn| turn.contains(&index)) {
+            keep.extend(turn.iter().copied().filter(|&other| {
+                messages[other].message_type == "tool_result"
+                    && extract_tool_call_id(&messages[other].content)
+                        .is_some_and(|id| call_ids.contains(&id))
+            }));
+        }
+    }
+
+    let total = messages.len();
+    let kept: Vec<Message> = messages
+        .into_iter()
+        .enumerate()
+        .filter(|(index, m)| m.message_type == "system" || keep.contains(index))
+        .map(|(_, m)| m)
+        .collect();
+    debug!(
+        total_messages = total,
+        kept_messages = kept.len(),
+        max_messages = truncation.max_messages,
+        "truncated context messages"
+    );
+    kept
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum BranchSelection {
     All,
@@ -562,6 +701,15 @@ fn log_selected_messages(conversation_id: i64, selection: &str, messages: &[Mess
 pub fn build_message_list_from_db(
     all_messages: &[(Message, Option<MessageAttachment>)],
     branch_selection: BranchSelection,
+) -> Vec<(String, String, Vec<MessageAttachment>)> {
+    build_message_list_from_db_with_truncation(all_messages, branch_selection, None)
+}
+
+/// 与 `build_message_list_from_db` 相同，但在选出最新分支后按 `truncation` 截断上下文
+pub fn build_message_list_from_db_with_truncation(
+    all_messages: &[(Message, Option<MessageAttachment>)],
+    branch_selection: BranchSelection,
+    truncation: Option<&ContextTruncation>,
 ) -> Vec<(String, String, Vec<MessageAttachment>)> {
     let conversation_id =
         all_messages.first().map(|(msg, _)| msg.conversation_id).unwrap_or_default();
@@ -584,6 +732,16 @@ pub fn build_message_list_from_db(
                     }
                 }
             }
+            if let Some(truncation) = truncation {
+                latest_branch = truncate_messages_keep_pinned(latest_branch, truncation);
+                if let Some(token_trim) = &truncation.token_trim {
+                    latest_branch = trim_messages_to_token_budget(
+                        latest_branch,
+                        token_trim,
+                        &truncation.pinned_message_ids,
+                    );
+                }
+            }
             filter_tool_results_for_branch(conversation_id, "latest_branch", &mut latest_branch);
             log_selected_messages(conversation_id, "latest_branch", &latest_branch);
             build_message_list_from_selected_messages(&latest_branch, all_messages)
@@ -605,6 +763,45 @@ pub fn build_message_list_from_db(
     }
 }
 
+/// 仅使用指定消息作为本轮上下文（一次性提问），不影响对话的默认上下文组装
+///
+/// system 消息始终保留；选中的回复若包含工具调用，会带上对应的工具结果；
+/// 结果按消息创建时间排序，与选择顺序无关
+pub fn build_message_list_from_context_ids(
+    all_messages: &[(Message, Option<MessageAttachment>)],
+    context_message_ids: &[i64],
+) -> Vec<(String, String, Vec<MessageAttachment>)> {
+    let selected_ids: HashSet<i64> = context_message_ids.iter().copied().collect();
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<Message> = all_messages
+        .iter()
+        .filter(|(message, _)| seen.insert(message.id))
+        .map(|(message, _)| message.clone())
+        .collect();
+    candidates.sort_by_key(|message| (message.created_time, message.id));
+
+    let selected_tool_call_ids: HashSet<String> = candidates
+        .iter()
+        .filter(|message| message.message_type == "response" && selected_ids.contains(&message.id))
+        .flat_map(|message| extract_tool_call_ids_from_mcp_comments(&message.content))
+        .collect();
+
+    let selected: Vec<Message> = candidates
+        .into_iter()
+        .filter(|message| {
+            message.message_type == "system"
+                || selected_ids.contains(&message.id)
+                || (message.message_type == "tool_result"
+                    && extract_tool_call_id(&message.content)
+                        .is_some_and(|id| selected_tool_call_ids.contains(&id)))
+        })
+        .collect();
+    let conversation_id =
+        all_messages.first().map(|(msg, _)| msg.conversation_id).unwrap_or_default();
+    log_selected_messages(conversation_id, "context_ids", &selected);
+    build_message_list_from_selected_messages(&selected, all_messages)
+}
+
 // Helper function to extract tool call ID from tool result content
 pub fn extract_tool_call_id(content: &str) -> Option<String> {
     // Expected format: "Tool execution completed:\n\nTool Call ID: {id}\nResult:\n{result}"
@@ -832,7 +1029,7 @@ pub async fn handle_message_type_end(
         })
         .unwrap(),
     };
-    let _ = window.emit(format!("conversation_event_{}", conversation_id).as_str(), type_end_event);
+    emit_conversation_event(window, conversation_id, type_end_event);
 
     let final_update_event = crate::api::ai::events::ConversationEvent {
         r#type: "message_update".to_string(),
@@ -849,8 +1046,7 @@ pub async fn handle_message_type_end(
         })
         .unwrap(),
     };
-    let _ =
-        window.emit(format!("conversation_event_{}", conversation_id).as_str(), final_update_event);
+    emit_conversation_event(window, conversation_id, final_update_event);
 
     Ok(())
 }
@@ -882,8 +1078,7 @@ pub fn finish_stream_messages(
                 })
                 .unwrap(),
             };
-            let _ = window
-                .emit(format!("conversation_event_{}", conversation_id).as_str(), complete_event);
+            emit_conversation_event(window, conversation_id, complete_event);
         }
     }
 
@@ -904,8 +1099,7 @@ pub fn finish_stream_messages(
             })
             .unwrap(),
         };
-        let _ =
-            window.emit(format!("conversation_event_{}", conversation_id).as_str(), complete_event);
+        emit_conversation_event(window, conversation_id, complete_event);
     }
     Ok(())
 }
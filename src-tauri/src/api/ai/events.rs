@@ -49,6 +49,23 @@ pub struct MCPToolCallUpdateEvent {
     pub finished_time: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// 工具调用达到 `max_tool_calls_before_answer` 上限，接下来的回复为强制回答轮次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForcedFinalAnswerEvent {
+    pub conversation_id: i64,
+    pub tool_call_count: usize,
+    pub max_tool_calls: usize,
+}
+
+/// 生成进度事件（generation_started / reasoning_started / response_started），
+/// 在首条消息创建前即可驱动前端的状态提示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationProgressEvent {
+    pub conversation_id: i64,
+    pub generation_group_id: String,
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationCancelEvent {
     pub conversation_id: i64,
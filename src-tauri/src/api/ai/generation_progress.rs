@@ -0,0 +1,144 @@
+//! 流式生成的进度事件：连接建立后立即发出 generation_started，
+//! 首个 reasoning / response chunk 到达时各发出一次对应的 started 事件
+//!
+//! 推理模型静默思考时首个 chunk 可能很久才到，前端据此显示准确的状态，而不必等到首条消息创建。
+
+use crate::api::ai::events::{ConversationEvent, GenerationProgressEvent};
+
+pub const GENERATION_STARTED_EVENT: &str = "generation_started";
+pub const REASONING_STARTED_EVENT: &str = "reasoning_started";
+pub const RESPONSE_STARTED_EVENT: &str = "response_started";
+
+/// 记录一次生成已发出的进度事件，保证每种事件只发一次
+pub struct GenerationProgress<E: FnMut(ConversationEvent)> {
+    conversation_id: i64,
+    generation_group_id: String,
+    emit: E,
+    reasoning_started: bool,
+    response_started: bool,
+}
+
+impl<E: FnMut(ConversationEvent)> GenerationProgress<E> {
+    /// 流式连接建立后创建，并立即发出 generation_started
+    pub fn start(conversation_id: i64, generation_group_id: &str, emit: E) -> Self {
+        let mut progress = Self {
+            conversation_id,
+            generation_group_id: generation_group_id.to_string(),
+            emit,
+            reasoning_started: false,
+            response_started: false,
+        };
+        progress.emit_event(GENERATION_STARTED_EVENT);
+        progress
+    }
+
+    /// 收到 reasoning chunk，首个非空 chunk 时发出 reasoning_started
+    pub fn on_reasoning_chunk(&mut self, content: &str) {
+        if !self.reasoning_started && !content.is_empty() {
+            self.reasoning_started = true;
+            self.emit_event(REASONING_STARTED_EVENT);
+        }
+    }
+
+    /// 收到 response chunk，首个非空 chunk 时发出 response_started
+    pub fn on_response_chunk(&mut self, content: &str) {
+        if !self.response_started && !content.is_empty() {
+            self.response_started = true;
+            self.emit_event(RESPONSE_STARTED_EVENT);
+        }
+    }
+
+    fn emit_event(&mut self, event_type: &str) {
+        let event = ConversationEvent {
+            r#type: event_type.to_string(),
+            data: serde_json::to_value(GenerationProgressEvent {
+                conversation_id: self.conversation_id,
+                generation_group_id: self.generation_group_id.clone(),
+                occurred_at: chrono::Utc::now(),
+            })
+            .unwrap(),
+        };
+        (self.emit)(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    enum Chunk {
+        Reasoning(&'static str),
+        Response(&'static str),
+    }
+
+    /// 测试首个 chunk 迟迟不到时，generation_started 先于任何内容发出，
+    /// reasoning_started / response_started 各在对应首个 chunk 时发出一次
+    #[tokio::test]
+    async fn test_started_event_precedes_content_for_slow_first_chunk() {
+        let log: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = log.clone();
+
+        let chunks = futures::stream::iter(vec![
+            Chunk::Reasoning("let me think"),
+            Chunk::Reasoning(" more"),
+            Chunk::Response(""),
+            Chunk::Response("answer"),
+            Chunk::Response(" done"),
+        ])
+        .then(|chunk| async move {
+            // 模拟推理模型静默思考：首个 chunk 到达前有明显延迟
+            if matches!(chunk, Chunk::Reasoning("let me think")) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            chunk
+        });
+        futures::pin_mut!(chunks);
+
+        let mut progress = GenerationProgress::start(7, "group-1", |event| {
+            sink.lock().unwrap().push(event.r#type)
+        });
+        // 首个 chunk 到达前，前端已经收到 generation_started
+        assert_eq!(*log.lock().unwrap(), vec![GENERATION_STARTED_EVENT.to_string()]);
+
+        while let Some(chunk) = chunks.next().await {
+            match chunk {
+                Chunk::Reasoning(content) => {
+                    progress.on_reasoning_chunk(content);
+                    log.lock().unwrap().push(format!("reasoning:{}", content));
+                }
+                Chunk::Response(content) => {
+                    progress.on_response_chunk(content);
+                    log.lock().unwrap().push(format!("response:{}", content));
+                }
+            }
+        }
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "generation_started",
+                "reasoning_started",
+                "reasoning:let me think",
+                "reasoning: more",
+                "response:",
+                "response_started",
+                "response:answer",
+                "response: done",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_payload_carries_conversation_and_group() {
+        let mut events = Vec::new();
+        GenerationProgress::start(42, "group-x", |event| events.push(event));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].r#type, GENERATION_STARTED_EVENT);
+        assert_eq!(events[0].data["conversation_id"], 42);
+        assert_eq!(events[0].data["generation_group_id"], "group-x");
+    }
+}
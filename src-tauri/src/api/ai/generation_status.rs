@@ -0,0 +1,214 @@
+//! 生成状态：记录每个对话当前正在进行的模型请求（模型、流式/非流式、重试次数、输出进度等），
+//! 供前端查询展示。生成开始时通过 [`ActiveGenerationGuard`] 登记，结束（含出错、取消）时自动移除。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+static NEXT_GENERATION_TOKEN: AtomicU64 = AtomicU64::new(1);
+static ACTIVE_GENERATIONS: OnceLock<Mutex<HashMap<i64, ActiveGeneration>>> = OnceLock::new();
+
+fn active_generations() -> &'static Mutex<HashMap<i64, ActiveGeneration>> {
+    ACTIVE_GENERATIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct ActiveGeneration {
+    status: ActiveGenerationStatus,
+    started_at: Instant,
+    /// 登记序号，防止旧的 guard 在 drop 时移除同一对话新登记的状态
+    token: u64,
+}
+
+/// 对话当前生成的状态快照
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ActiveGenerationStatus {
+    pub conversation_id: i64,
+    pub model_id: i64,
+    /// 请求使用的模型代码
+    pub model_code: String,
+    /// 模型显示名称
+    pub model_name: String,
+    /// 提供商名称，查询时按 model_id 补全
+    pub provider_name: Option<String>,
+    pub stream: bool,
+    /// 当前尝试次数，从 1 开始
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// 是否为 400 错误后简化过的请求
+    pub is_fallback: bool,
+    /// 已收到的输出字符数（含推理内容）
+    pub output_chars: usize,
+    /// 服务端返回的输出 token 数，流式结束或非流式响应后才可用
+    pub output_tokens: Option<i64>,
+    pub elapsed_ms: u64,
+}
+
+/// 生成中的作用域标记，drop 时自动移除对话的生成状态
+pub struct ActiveGenerationGuard {
+    conversation_id: i64,
+    token: u64,
+}
+
+impl ActiveGenerationGuard {
+    pub fn enter(
+        conversation_id: i64,
+        model_id: i64,
+        model_code: &str,
+        model_name: &str,
+        stream: bool,
+    ) -> Self {
+        let token = NEXT_GENERATION_TOKEN.fetch_add(1, Ordering::Relaxed);
+        let status = ActiveGenerationStatus {
+            conversation_id,
+            model_id,
+            model_code: model_code.to_string(),
+            model_name: model_name.to_string(),
+            provider_name: None,
+            stream,
+            attempt: 0,
+            max_attempts: 0,
+            is_fallback: false,
+            output_chars: 0,
+            output_tokens: None,
+            elapsed_ms: 0,
+        };
+        active_generations().lock().unwrap().insert(
+            conversation_id,
+            ActiveGeneration { status, started_at: Instant::now(), token },
+        );
+        Self { conversation_id, token }
+    }
+}
+
+impl Drop for ActiveGenerationGuard {
+    fn drop(&mut self) {
+        let mut generations = active_generations().lock().unwrap();
+        if generations.get(&self.conversation_id).is_some_and(|g| g.token == self.token) {
+            generations.remove(&self.conversation_id);
+        }
+    }
+}
+
+fn update_status(conversation_id: i64, update: impl FnOnce(&mut ActiveGenerationStatus)) {
+    if let Some(generation) = active_generations().lock().unwrap().get_mut(&conversation_id) {
+        update(&mut generation.status);
+    }
+}
+
+/// 记录新一次请求尝试，重置本次尝试的输出进度
+pub fn record_attempt(conversation_id: i64, attempt: u32, max_attempts: u32, is_fallback: bool) {
+    update_status(conversation_id, |status| {
+        status.attempt = attempt;
+        status.max_attempts = max_attempts;
+        status.is_fallback = is_fallback;
+        status.output_chars = 0;
+        status.output_tokens = None;
+    });
+}
+
+/// 降级到备用模型时替换当前生成的模型，提供商名称在查询时按新的 model_id 补全
+pub fn record_model_switch(
+    conversation_id: i64,
+    model_id: i64,
+    model_code: &str,
+    model_name: &str,
+) {
+    update_status(conversation_id, |status| {
+        status.model_id = model_id;
+        status.model_code = model_code.to_string();
+        status.model_name = model_name.to_string();
+        status.provider_name = None;
+        status.attempt = 0;
+        status.max_attempts = 0;
+        status.is_fallback = false;
+        status.output_chars = 0;
+        status.output_tokens = None;
+    });
+}
+
+/// 累计已收到的输出内容
+pub fn record_output(conversation_id: i64, content: &str) {
+    update_status(conversation_id, |status| status.output_chars += content.chars().count());
+}
+
+/// 记录服务端返回的输出 token 数
+pub fn record_output_tokens(conversation_id: i64, output_tokens: i64) {
+    update_status(conversation_id, |status| status.output_tokens = Some(output_tokens));
+}
+
+/// 获取对话当前生成的状态，未在生成时返回 None
+pub fn get_active_generation(conversation_id: i64) -> Option<ActiveGenerationStatus> {
+    active_generations().lock().unwrap().get(&conversation_id).map(|generation| {
+        let mut status = generation.status.clone();
+        status.elapsed_ms = generation.started_at.elapsed().as_millis() as u64;
+        status
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_tracks_model_and_attempt_while_generating() {
+        let conversation_id = 9_100_001;
+        {
+            let _guard = ActiveGenerationGuard::enter(conversation_id, 7, "gpt-4o", "GPT-4o", true);
+            record_attempt(conversation_id, 1, 3, false);
+            record_output(conversation_id, "部分输出");
+
+            let status = get_active_generation(conversation_id).unwrap();
+            assert_eq!(status.model_id, 7);
+            assert_eq!(status.model_code, "gpt-4o");
+            assert!(status.stream);
+            assert_eq!((status.attempt, status.max_attempts), (1, 3));
+            assert_eq!(status.output_chars, 4);
+
+            // 重试时进度重置
+            record_attempt(conversation_id, 2, 3, false);
+            record_output_tokens(conversation_id, 12);
+            let status = get_active_generation(conversation_id).unwrap();
+            assert_eq!(status.attempt, 2);
+            assert_eq!(status.output_chars, 0);
+            assert_eq!(status.output_tokens, Some(12));
+        }
+        assert!(get_active_generation(conversation_id).is_none());
+    }
+
+    #[test]
+    fn test_model_switch_replaces_model_and_resets_progress() {
+        let conversation_id = 9_100_004;
+        let _guard = ActiveGenerationGuard::enter(conversation_id, 7, "gpt-4o", "GPT-4o", true);
+        record_attempt(conversation_id, 3, 3, false);
+        record_output(conversation_id, "partial");
+
+        record_model_switch(conversation_id, 8, "claude-sonnet", "Claude Sonnet");
+        let status = get_active_generation(conversation_id).unwrap();
+        assert_eq!(status.model_id, 8);
+        assert_eq!(status.model_code, "claude-sonnet");
+        assert_eq!(status.model_name, "Claude Sonnet");
+        assert_eq!((status.attempt, status.output_chars), (0, 0));
+        assert!(status.stream);
+    }
+
+    #[test]
+    fn test_stale_guard_does_not_clear_newer_generation() {
+        let conversation_id = 9_100_002;
+        let old_guard = ActiveGenerationGuard::enter(conversation_id, 1, "a", "A", false);
+        let _new_guard = ActiveGenerationGuard::enter(conversation_id, 2, "b", "B", true);
+        drop(old_guard);
+
+        let status = get_active_generation(conversation_id).unwrap();
+        assert_eq!(status.model_id, 2);
+    }
+
+    #[test]
+    fn test_updates_without_active_generation_are_ignored() {
+        let conversation_id = 9_100_003;
+        record_attempt(conversation_id, 1, 1, false);
+        record_output(conversation_id, "x");
+        assert!(get_active_generation(conversation_id).is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! 提供商连接保活：云端提供商每次空闲后的首个请求都要重新进行 TLS 握手，
+//! 开启后为该提供商复用同一个 HTTP 客户端，并定期发送轻量请求让连接池中的连接保持可用。
+//!
+//! 保活会长期占用一个连接，因此需要在提供商配置中显式开启；本地提供商不会启用。
+//! 提供商配置修改或被停用、删除时，预热任务随之停止。
+
+use crate::db::llm_db::LLMProviderConfig;
+use genai::adapter::AdapterKind;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+/// 提供商配置项：是否开启连接保活
+pub const KEEP_ALIVE_CONFIG_KEY: &str = "keep_alive";
+
+/// 预热请求间隔，需要小于连接池空闲超时
+const WARMUP_INTERVAL_SECS: u64 = 45;
+/// 连接池中空闲连接的保留时间
+const POOL_IDLE_TIMEOUT_SECS: u64 = 120;
+/// 每个主机保留的空闲连接数
+const POOL_MAX_IDLE_PER_HOST: usize = 2;
+/// TCP keepalive 探测间隔
+const TCP_KEEPALIVE_SECS: u64 = 30;
+
+/// 保活开启时 HTTP 客户端使用的连接池设置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeepAliveSettings {
+    pub pool_idle_timeout: Duration,
+    pub pool_max_idle_per_host: usize,
+    pub tcp_keepalive: Duration,
+    pub warmup_interval: Duration,
+}
+
+impl Default for KeepAliveSettings {
+    fn default() -> Self {
+        Self {
+            pool_idle_timeout: Duration::from_secs(POOL_IDLE_TIMEOUT_SECS),
+            pool_max_idle_per_host: POOL_MAX_IDLE_PER_HOST,
+            tcp_keepalive: Duration::from_secs(TCP_KEEPALIVE_SECS),
+            warmup_interval: Duration::from_secs(WARMUP_INTERVAL_SECS),
+        }
+    }
+}
+
+impl KeepAliveSettings {
+    /// 将连接池与 TCP keepalive 设置应用到 reqwest 客户端
+    pub fn apply(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        builder
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .tcp_keepalive(self.tcp_keepalive)
+    }
+}
+
+/// 提供商是否开启了连接保活
+pub fn keep_alive_enabled(configs: &[LLMProviderConfig]) -> bool {
+    configs
+        .iter()
+        .find(|config| config.name == KEEP_ALIVE_CONFIG_KEY)
+        .map(|config| matches!(config.value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// 是否为本地端点（本机、私有网段地址或局域网主机名），本地服务没有 TLS 握手开销，不需要保活
+pub fn is_local_endpoint(endpoint: &str) -> bool {
+    let without_scheme = endpoint.split("://").nth(1).unwrap_or(endpoint);
+    let authority = without_scheme.split('/').next().unwrap_or_default();
+    let host = if authority.starts_with('[') {
+        authority.split(']').next().unwrap_or_default().trim_start_matches('[')
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+    let host = host.to_lowercase();
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        Ok(IpAddr::V6(ip)) => ip.is_loopback() || ip.is_unspecified(),
+        Err(_) => host == "localhost" || host.ends_with(".local"),
+    }
+}
+
+/// 计算提供商的保活设置，未开启或本地提供商时返回 None
+pub fn keep_alive_settings(
+    configs: &[LLMProviderConfig],
+    adapter_kind: AdapterKind,
+    endpoint: &str,
+) -> Option<KeepAliveSettings> {
+    if !keep_alive_enabled(configs) {
+        return None;
+    }
+    if adapter_kind == AdapterKind::Ollama || is_local_endpoint(endpoint) {
+        return None;
+    }
+    Some(KeepAliveSettings::default())
+}
+
+struct WarmClient {
+    /// 客户端相关配置（端点、代理、超时等）的指纹，变化后重建客户端
+    fingerprint: String,
+    client: reqwest::Client,
+    /// 客户端被替换或停止保活时取消，预热任务随即退出
+    cancel: CancellationToken,
+}
+
+/// key 为（提供商 ID，是否流式），流式与非流式请求的超时设置不同
+static WARM_CLIENTS: OnceLock<Mutex<HashMap<(i64, bool), WarmClient>>> = OnceLock::new();
+
+fn warm_clients() -> &'static Mutex<HashMap<(i64, bool), WarmClient>> {
+    WARM_CLIENTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 获取提供商的保活客户端：配置未变化时复用已有客户端（连接池随之复用），
+/// 否则调用 `build` 新建客户端并启动预热任务
+pub fn get_or_create_warm_client<F>(
+    provider_id: i64,
+    is_stream: bool,
+    fingerprint: &str,
+    endpoint: &str,
+    settings: &KeepAliveSettings,
+    build: F,
+) -> Result<reqwest::Client, String>
+where
+    F: FnOnce() -> Result<reqwest::Client, String>,
+{
+    let key = (provider_id, is_stream);
+    let mut clients = warm_clients().lock().unwrap();
+    if let Some(existing) = clients.get(&key).filter(|c| c.fingerprint == fingerprint) {
+        return Ok(existing.client.clone());
+    }
+
+    let client = build()?;
+    let cancel = CancellationToken::new();
+    let previous = clients.insert(
+        key,
+        WarmClient {
+            fingerprint: fingerprint.to_string(),
+            client: client.clone(),
+            cancel: cancel.clone(),
+        },
+    );
+    drop(clients);
+    if let Some(previous) = previous {
+        previous.cancel.cancel();
+    }
+    info!(provider_id, is_stream, %endpoint, "provider keep-alive client created");
+    spawn_warmup(
+        provider_id,
+        client.clone(),
+        endpoint.to_string(),
+        settings.warmup_interval,
+        cancel,
+    );
+    Ok(client)
+}
+
+/// 定期向端点发送 HEAD 请求保持连接；客户端被替换或停止保活后立即退出
+fn spawn_warmup(
+    provider_id: i64,
+    client: reqwest::Client,
+    endpoint: String,
+    interval: Duration,
+    cancel: CancellationToken,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // 端点根路径通常返回 404/401，只要能建立连接即可，不关心状态码
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                result = client.head(&endpoint).send() => {
+                    if let Err(e) = result {
+                        debug!(provider_id, error = %e, "provider keep-alive warm-up failed");
+                    }
+                }
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+        debug!(provider_id, "provider keep-alive warm-up stopped");
+    });
+}
+
+/// 停止提供商的保活（保活关闭、提供商配置修改、停用或删除时调用）
+pub fn stop_keep_alive(provider_id: i64) {
+    let mut clients = warm_clients().lock().unwrap();
+    clients.retain(|(id, _), client| {
+        let keep = *id != provider_id;
+        if !keep {
+            client.cancel.cancel();
+        }
+        keep
+    });
+}
+
+/// 提供商当前是否有保活连接
+pub fn is_keep_alive_active(provider_id: i64) -> bool {
+    warm_clients().lock().unwrap().keys().any(|(id, _)| *id == provider_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str, value: &str) -> LLMProviderConfig {
+        LLMProviderConfig {
+            id: 0,
+            name: name.to_string(),
+            llm_provider_id: 1,
+            value: value.to_string(),
+            append_location: "header".to_string(),
+            is_addition: false,
+        }
+    }
+
+    #[test]
+    fn test_keep_alive_settings_when_enabled() {
+        let configs = vec![config(KEEP_ALIVE_CONFIG_KEY, "true")];
+        let settings =
+            keep_alive_settings(&configs, AdapterKind::OpenAI, "https://api.openai.com/v1/")
+                .expect("keep-alive should be enabled");
+        assert_eq!(settings.pool_idle_timeout, Duration::from_secs(POOL_IDLE_TIMEOUT_SECS));
+        assert_eq!(settings.pool_max_idle_per_host, POOL_MAX_IDLE_PER_HOST);
+        assert_eq!(settings.tcp_keepalive, Duration::from_secs(TCP_KEEPALIVE_SECS));
+        // 预热间隔必须短于空闲超时，否则连接会在两次预热之间被回收
+        assert!(settings.warmup_interval < settings.pool_idle_timeout);
+        assert!(settings.apply(reqwest::Client::builder()).build().is_ok());
+    }
+
+    #[test]
+    fn test_keep_alive_disabled_for_local_or_unconfigured() {
+        let enabled = vec![config(KEEP_ALIVE_CONFIG_KEY, "1")];
+        let endpoint = "https://api.anthropic.com/";
+        assert!(keep_alive_settings(&[], AdapterKind::Anthropic, endpoint).is_none());
+        assert!(keep_alive_settings(
+            &[config(KEEP_ALIVE_CONFIG_KEY, "false")],
+            AdapterKind::Anthropic,
+            endpoint
+        )
+        .is_none());
+        assert!(keep_alive_settings(&enabled, AdapterKind::Ollama, "https://ollama.example.com/")
+            .is_none());
+        assert!(keep_alive_settings(&enabled, AdapterKind::OpenAI, "http://127.0.0.1:1234/v1/")
+            .is_none());
+    }
+
+    #[test]
+    fn test_is_local_endpoint() {
+        assert!(is_local_endpoint("http://localhost:11434/v1/"));
+        assert!(is_local_endpoint("http://[::1]:8080/"));
+        assert!(is_local_endpoint("http://192.168.1.20:8000"));
+        assert!(is_local_endpoint("http://10.0.0.5/v1"));
+        assert!(is_local_endpoint("http://172.16.0.10:8000/v1"));
+        assert!(is_local_endpoint("http://172.31.255.1/"));
+        assert!(!is_local_endpoint("http://172.32.0.1/"));
+        assert!(is_local_endpoint("http://my-box.local/v1"));
+        assert!(!is_local_endpoint("https://api.deepseek.com/"));
+        assert!(!is_local_endpoint("https://10.example.com/"));
+    }
+}
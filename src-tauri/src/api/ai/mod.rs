@@ -1,8 +1,23 @@
 pub mod acp;
 pub mod chat;
 pub mod config;
+pub mod content_filter;
+pub mod context_trim;
 pub mod conversation;
 pub mod events;
+pub mod generation_progress;
+pub mod generation_status;
+pub mod keep_alive;
+pub mod model_fallback;
+pub mod render_mode;
+pub mod request_fallback;
+pub mod response_language;
+pub mod send_confirmation;
+pub mod stream_pacer;
+pub mod stream_persist;
 pub mod summary;
 pub mod title;
+pub mod tool_budget;
+pub mod tool_parallel;
+pub mod tool_replay;
 pub mod types;
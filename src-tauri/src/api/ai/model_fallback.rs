@@ -0,0 +1,238 @@
+//! 模型降级链：主模型在重试耗尽后仍失败（如 429 限流或服务暂时不可用）时，
+//! 按助手配置的顺序依次改用备用模型生成，复用同一个 generation_group_id
+
+use crate::api::ai::config::{
+    config_value, get_network_proxy_from_config, get_request_timeout_from_config, ConfigBuilder,
+};
+use crate::api::genai_client;
+use crate::db::assistant_db::AssistantModelConfig;
+use crate::db::conversation_db::{ConversationDatabase, Repository};
+use crate::db::llm_db::{LLMDatabase, ModelDetail};
+use crate::db::system_db::FeatureConfig;
+use genai::chat::ChatOptions;
+use genai::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// 助手配置项：备用模型列表，按顺序尝试。
+/// 支持 JSON 数组或逗号/换行分隔，每项为模型 id 或 `model_code%%provider_id`
+pub const MODEL_FALLBACK_CONFIG_KEY: &str = "model_fallback_chain";
+
+/// 切换到备用模型时发出的对话事件
+pub const MODEL_FALLBACK_EVENT: &str = "model_fallback";
+
+/// 切换备用模型事件的 payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFallbackEvent {
+    pub conversation_id: i64,
+    pub generation_group_id: String,
+    /// 失败的模型显示名称
+    pub from_model_name: String,
+    pub model_id: i64,
+    pub model_name: String,
+}
+
+/// 降级链中的一项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FallbackModelRef {
+    /// llm_model 表中的模型 id
+    Id(i64),
+    /// 与 override_model_id 相同的 `model_code%%provider_id` 格式
+    Code { model_code: String, provider_id: i64 },
+}
+
+impl FallbackModelRef {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(id) = value.parse::<i64>() {
+            return Some(FallbackModelRef::Id(id));
+        }
+        let (model_code, provider_id) = value.split_once("%%")?;
+        let provider_id = provider_id.trim().parse::<i64>().ok()?;
+        let model_code = model_code.trim();
+        (!model_code.is_empty())
+            .then(|| FallbackModelRef::Code { model_code: model_code.to_string(), provider_id })
+    }
+}
+
+/// 从助手模型配置中读取降级链，忽略无法解析的条目和重复项
+pub fn fallback_chain_from_configs(configs: &[AssistantModelConfig]) -> Vec<FallbackModelRef> {
+    let Some(raw) = config_value(configs, MODEL_FALLBACK_CONFIG_KEY) else {
+        return Vec::new();
+    };
+
+    let entries: Vec<String> = match serde_json::from_str::<Vec<serde_json::Value>>(raw) {
+        Ok(values) => values
+            .into_iter()
+            .map(|value| match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            })
+            .collect(),
+        Err(_) => raw.split([',', '\n']).map(str::to_string).collect(),
+    };
+
+    let mut chain = Vec::new();
+    for entry in entries.iter().filter(|entry| !entry.trim().is_empty()) {
+        match FallbackModelRef::parse(entry) {
+            Some(model_ref) if !chain.contains(&model_ref) => chain.push(model_ref),
+            Some(_) => {}
+            None => warn!(entry = entry.as_str(), "ignoring invalid model fallback entry"),
+        }
+    }
+    chain
+}
+
+/// 只有限流（429）、服务端错误（5xx）和没有状态码的网络错误才降级；
+/// 其余 4xx 是请求或权限问题，换模型也无法恢复，此时直接报错
+pub fn should_fall_back(status_code: Option<u16>) -> bool {
+    matches!(status_code, None | Some(429) | Some(500..=599))
+}
+
+/// OpenAI 兼容接口下的 Gemini 模型返回的 usage 格式与 genai 不兼容，不捕获 usage
+pub fn capture_usage_supported(provider_api_type: &str, model_code: &str) -> bool {
+    let provider_api_type = provider_api_type.to_lowercase();
+    let is_openai_like = provider_api_type == "openai" || provider_api_type == "openai_api";
+    !(is_openai_like && model_code.to_lowercase().contains("gemini"))
+}
+
+/// 已创建好客户端的备用模型
+pub struct FallbackModel {
+    pub client: Client,
+    pub model_name: String,
+    /// 按助手配置为该模型单独构建的请求参数，不沿用主模型的参数
+    pub chat_options: ChatOptions,
+    pub llm_model_id: i64,
+    pub llm_model_name: String,
+}
+
+/// 按助手配置为备用模型构建请求参数，usage 捕获按备用模型的提供商判断
+pub fn fallback_chat_options(
+    assistant_configs: &[AssistantModelConfig],
+    detail: &ModelDetail,
+    capture_tool_calls: bool,
+) -> ChatOptions {
+    let config_map: HashMap<String, String> =
+        ConfigBuilder::merge_model_configs(assistant_configs.to_vec(), detail, None)
+            .into_iter()
+            .filter_map(|config| config.value.map(|value| (config.name, value)))
+            .collect();
+    ConfigBuilder::build_chat_options(&config_map)
+        .with_normalize_reasoning_content(true)
+        .with_capture_usage(capture_usage_supported(&detail.provider.api_type, &detail.model.code))
+        .with_capture_tool_calls(capture_tool_calls)
+}
+
+/// 按降级链为备用模型创建客户端，跳过主模型本身以及无法加载的条目
+pub fn resolve_fallback_models(
+    app_handle: &tauri::AppHandle,
+    chain: &[FallbackModelRef],
+    assistant_configs: &[AssistantModelConfig],
+    primary_model_id: i64,
+    capture_tool_calls: bool,
+    config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+) -> Vec<FallbackModel> {
+    if chain.is_empty() {
+        return Vec::new();
+    }
+    let llm_db = match LLMDatabase::new(app_handle) {
+        Ok(db) => db,
+        Err(e) => {
+            warn!(error = %e, "failed to open llm database for model fallback");
+            return Vec::new();
+        }
+    };
+    let network_proxy = get_network_proxy_from_config(config_feature_map);
+    let request_timeout = get_request_timeout_from_config(config_feature_map);
+
+    let mut models = Vec::new();
+    for model_ref in chain {
+        let detail: rusqlite::Result<ModelDetail> = match model_ref {
+            FallbackModelRef::Id(id) => llm_db.get_llm_model_detail_by_id(id),
+            FallbackModelRef::Code { model_code, provider_id } => {
+                llm_db.get_llm_model_detail(provider_id, model_code)
+            }
+        };
+        let detail = match detail {
+            Ok(detail) => detail,
+            Err(e) => {
+                warn!(?model_ref, error = %e, "fallback model not found, skipping");
+                continue;
+            }
+        };
+        if !detail.provider.is_enabled {
+            warn!(model = %detail.model.code, "fallback model provider is disabled, skipping");
+            continue;
+        }
+        if detail.model.id == primary_model_id
+            || models.iter().any(|m: &FallbackModel| m.llm_model_id == detail.model.id)
+        {
+            continue;
+        }
+
+        let proxy_enabled = detail
+            .configs
+            .iter()
+            .find(|config| config.name == "proxy_enabled")
+            .and_then(|config| config.value.parse::<bool>().ok())
+            .unwrap_or(false);
+        match genai_client::create_client_with_config(
+            &detail.configs,
+            &detail.model.code,
+            &detail.provider.api_type,
+            network_proxy.as_deref(),
+            proxy_enabled,
+            Some(request_timeout),
+            true,
+            config_feature_map,
+        ) {
+            Ok(client) => models.push(FallbackModel {
+                client,
+                model_name: detail.model.code.clone(),
+                chat_options: fallback_chat_options(assistant_configs, &detail, capture_tool_calls),
+                llm_model_id: detail.model.id,
+                llm_model_name: detail.model.code,
+            }),
+            Err(e) => {
+                warn!(model = %detail.model.code, error = %e, "failed to create fallback client")
+            }
+        }
+    }
+    models
+}
+
+/// 读取对话所属助手的降级链并创建备用模型客户端
+///
+/// `capture_tool_calls` 沿用主模型请求的设置，保证备用模型同样能返回原生工具调用
+pub fn fallback_models_for_conversation(
+    app_handle: &tauri::AppHandle,
+    conversation_db: &ConversationDatabase,
+    conversation_id: i64,
+    primary_model_id: i64,
+    capture_tool_calls: bool,
+    config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+) -> Vec<FallbackModel> {
+    let assistant_id = conversation_db
+        .conversation_repo()
+        .ok()
+        .and_then(|repo| repo.read(conversation_id).ok().flatten())
+        .and_then(|conversation| conversation.assistant_id);
+    let Some(assistant_id) = assistant_id else {
+        return Vec::new();
+    };
+    match crate::api::assistant_api::get_assistant(app_handle.clone(), assistant_id, Some(true)) {
+        Ok(detail) => resolve_fallback_models(
+            app_handle,
+            &fallback_chain_from_configs(&detail.model_configs),
+            &detail.model_configs,
+            primary_model_id,
+            capture_tool_calls,
+            config_feature_map,
+        ),
+        Err(e) => {
+            warn!(error = %e, assistant_id, "failed to load model fallback config");
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! 回复渲染模式：助手可声明其回复按纯文本或原始代码展示，而不是按 Markdown 渲染
+
+use crate::api::ai::config::config_value;
+use crate::api::assistant_api::resolve_assistant_detail;
+use crate::db::assistant_db::{AssistantDatabase, AssistantModelConfig};
+use crate::db::conversation_db::{ConversationDatabase, MessageRepository, Repository};
+use tracing::warn;
+
+/// 助手配置项：回复渲染模式，可选 markdown / plaintext / code，未配置时为 markdown
+pub const RENDER_MODE_CONFIG_KEY: &str = "response_render_mode";
+
+pub const RENDER_MODE_MARKDOWN: &str = "markdown";
+pub const RENDER_MODE_PLAINTEXT: &str = "plaintext";
+pub const RENDER_MODE_CODE: &str = "code";
+
+/// 从助手模型配置中读取回复渲染模式，非法值按 markdown 处理
+pub fn render_mode_from_configs(configs: &[AssistantModelConfig]) -> &'static str {
+    let value =
+        config_value(configs, RENDER_MODE_CONFIG_KEY).map(|value| value.to_ascii_lowercase());
+    match value.as_deref() {
+        Some(RENDER_MODE_PLAINTEXT) => RENDER_MODE_PLAINTEXT,
+        Some(RENDER_MODE_CODE) => RENDER_MODE_CODE,
+        _ => RENDER_MODE_MARKDOWN,
+    }
+}
+
+/// 按助手配置记录回复消息的渲染模式，markdown 为默认值不落库
+pub fn record_response_render_mode(
+    message_repo: &MessageRepository,
+    message_id: i64,
+    configs: &[AssistantModelConfig],
+) -> rusqlite::Result<()> {
+    let render_mode = render_mode_from_configs(configs);
+    if render_mode == RENDER_MODE_MARKDOWN {
+        return Ok(());
+    }
+    message_repo.set_render_mode(message_id, render_mode)
+}
+
+/// 根据对话所属助手的配置，为新创建的回复消息记录渲染模式
+///
+/// 失败只记录日志，不影响回复本身。
+pub fn apply_assistant_render_mode(
+    app_handle: &tauri::AppHandle,
+    conversation_db: &ConversationDatabase,
+    conversation_id: i64,
+    message_id: i64,
+) {
+    let result = (|| -> Result<(), String> {
+        let assistant_id = conversation_db
+            .conversation_repo()
+            .map_err(|e| e.to_string())?
+            .read(conversation_id)
+            .map_err(|e| e.to_string())?
+            .and_then(|conversation| conversation.assistant_id);
+        let Some(assistant_id) = assistant_id else {
+            return Ok(());
+        };
+        // 读取合并了基础助手的生效配置
+        let assistant_db = AssistantDatabase::new(app_handle).map_err(|e| e.to_string())?;
+        let configs = resolve_assistant_detail(&assistant_db, assistant_id)?.model_configs;
+        let message_repo = conversation_db.message_repo().map_err(|e| e.to_string())?;
+        record_response_render_mode(&message_repo, message_id, &configs).map_err(|e| e.to_string())
+    })();
+
+    if let Err(e) = result {
+        warn!(conversation_id, message_id, error = %e, "failed to record response render mode");
+    }
+}
@@ -0,0 +1,246 @@
+//! 持续 400 时的降级重试：依次去掉请求中提供商可能不支持的特性后再试
+//!
+//! 不少 400 来自提供商不支持的请求特性（tools、response_format、部分采样参数）。
+//! 常规重试全部返回 400 后，按配置顺序逐步简化请求（累积生效）各重试一次，
+//! 并记录是哪一步简化让请求成功，方便用户据此调整配置。
+
+use genai::chat::{ChatOptions, ChatRequest};
+use std::future::Future;
+use tracing::{info, warn};
+
+/// 默认的简化顺序
+pub const DEFAULT_FALLBACK_ORDER: &str = "remove_tools,strip_extra_params";
+
+/// 一步请求简化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestSimplification {
+    /// 移除 tools 定义
+    RemoveTools,
+    /// 移除 response_format、top_p、reasoning_effort 等附加参数
+    StripExtraParams,
+}
+
+impl RequestSimplification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestSimplification::RemoveTools => "remove_tools",
+            RequestSimplification::StripExtraParams => "strip_extra_params",
+        }
+    }
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.trim() {
+            "remove_tools" => Some(RequestSimplification::RemoveTools),
+            "strip_extra_params" => Some(RequestSimplification::StripExtraParams),
+            _ => None,
+        }
+    }
+
+    /// 在请求上应用本步简化，请求中没有可简化的内容时返回 false
+    pub fn apply(&self, chat_request: &mut ChatRequest, chat_options: &mut ChatOptions) -> bool {
+        match self {
+            RequestSimplification::RemoveTools => {
+                let has_tools = chat_request.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+                chat_request.tools = None;
+                has_tools
+            }
+            RequestSimplification::StripExtraParams => {
+                let has_extra = chat_options.response_format.is_some()
+                    || chat_options.top_p.is_some()
+                    || chat_options.reasoning_effort.is_some();
+                chat_options.response_format = None;
+                chat_options.top_p = None;
+                chat_options.reasoning_effort = None;
+                has_extra
+            }
+        }
+    }
+}
+
+/// 解析简化顺序配置（逗号分隔），忽略未知项与重复项；空字符串表示关闭降级
+pub fn parse_fallback_order(value: &str) -> Vec<RequestSimplification> {
+    let mut order = Vec::new();
+    for keyword in value.split(',') {
+        match RequestSimplification::from_keyword(keyword) {
+            Some(step) if !order.contains(&step) => order.push(step),
+            Some(_) => {}
+            None if keyword.trim().is_empty() => {}
+            None => warn!(keyword = %keyword.trim(), "unknown request fallback step"),
+        }
+    }
+    order
+}
+
+/// 降级重试成功的结果
+#[derive(Debug)]
+pub struct FallbackOutcome<T> {
+    pub value: T,
+    /// 让请求成功的最后一步简化，原始请求即成功时为 None
+    pub applied: Option<RequestSimplification>,
+}
+
+/// 先以原始请求执行 `run`；若最终错误被 `is_bad_request` 判定为 400，
+/// 则按 `order` 逐步（累积）简化请求并各执行一次，直到成功或简化步骤用尽
+///
+/// `run` 的第三个参数表示是否为降级重试，调用方可据此只尝试一次而不走常规重试。
+pub async fn run_with_request_fallback<T, E, F, Fut>(
+    chat_request: &ChatRequest,
+    chat_options: &ChatOptions,
+    order: &[RequestSimplification],
+    is_bad_request: impl Fn(&E) -> bool,
+    mut run: F,
+) -> Result<FallbackOutcome<T>, E>
+where
+    F: FnMut(ChatRequest, ChatOptions, bool) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut error = match run(chat_request.clone(), chat_options.clone(), false).await {
+        Ok(value) => return Ok(FallbackOutcome { value, applied: None }),
+        Err(error) => error,
+    };
+
+    let mut request = chat_request.clone();
+    let mut options = chat_options.clone();
+    for step in order {
+        if !is_bad_request(&error) {
+            break;
+        }
+        if !step.apply(&mut request, &mut options) {
+            continue;
+        }
+
+        warn!(
+            step = step.as_str(),
+            "request keeps failing with 400, retrying with simplified request"
+        );
+        match run(request.clone(), options.clone(), true).await {
+            Ok(value) => {
+                info!(
+                    step = step.as_str(),
+                    "simplified request succeeded; consider adjusting the model or assistant config"
+                );
+                return Ok(FallbackOutcome { value, applied: Some(*step) });
+            }
+            Err(next_error) => error = next_error,
+        }
+    }
+
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genai::chat::{ChatMessage, ChatResponseFormat, JsonSpec, Tool};
+    use std::sync::{Arc, Mutex};
+
+    fn request_with_tools() -> ChatRequest {
+        ChatRequest::new(vec![ChatMessage::user("hi")]).with_tools(vec![Tool::new("search")])
+    }
+
+    /// 测试提供商在带 tools 时返回 400、去掉 tools 后成功，降级重试生效
+    #[tokio::test]
+    async fn test_fallback_removes_tools_after_persistent_400() {
+        let calls: Arc<Mutex<Vec<(bool, bool)>>> = Arc::new(Mutex::new(Vec::new()));
+        let order = parse_fallback_order(DEFAULT_FALLBACK_ORDER);
+
+        let outcome = run_with_request_fallback(
+            &request_with_tools(),
+            &ChatOptions::default(),
+            &order,
+            |status: &u16| *status == 400,
+            |request, _options, is_fallback| {
+                let calls = calls.clone();
+                async move {
+                    let has_tools = request.tools.as_ref().is_some_and(|t| !t.is_empty());
+                    calls.lock().unwrap().push((has_tools, is_fallback));
+                    // 模拟提供商：不支持 tools
+                    if has_tools {
+                        Err(400u16)
+                    } else {
+                        Ok("degraded response")
+                    }
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.value, "degraded response");
+        assert_eq!(outcome.applied, Some(RequestSimplification::RemoveTools));
+        assert_eq!(*calls.lock().unwrap(), vec![(true, false), (false, true)]);
+    }
+
+    /// 测试简化步骤累积生效，且请求中没有可简化内容的步骤会被跳过
+    #[tokio::test]
+    async fn test_fallback_escalates_and_skips_noop_steps() {
+        let options = ChatOptions::default()
+            .with_response_format(ChatResponseFormat::JsonSpec(JsonSpec::new(
+                "answer",
+                serde_json::json!({"type": "object"}),
+            )))
+            .with_top_p(0.5);
+        let attempts = Arc::new(Mutex::new(0));
+
+        // 请求没有 tools：remove_tools 不产生新请求，直接进入 strip_extra_params
+        let outcome = run_with_request_fallback(
+            &ChatRequest::new(vec![ChatMessage::user("hi")]),
+            &options,
+            &parse_fallback_order(DEFAULT_FALLBACK_ORDER),
+            |status: &u16| *status == 400,
+            |_request, options, _is_fallback| {
+                let attempts = attempts.clone();
+                async move {
+                    *attempts.lock().unwrap() += 1;
+                    if options.response_format.is_some() || options.top_p.is_some() {
+                        Err(400u16)
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.applied, Some(RequestSimplification::StripExtraParams));
+        assert_eq!(*attempts.lock().unwrap(), 2);
+    }
+
+    /// 测试非 400 错误与关闭降级时不做简化重试
+    #[tokio::test]
+    async fn test_fallback_only_for_bad_request_and_when_enabled() {
+        for (status, order) in [
+            (500u16, parse_fallback_order(DEFAULT_FALLBACK_ORDER)),
+            (400, parse_fallback_order("")),
+        ] {
+            let attempts = Arc::new(Mutex::new(0));
+            let result = run_with_request_fallback(
+                &request_with_tools(),
+                &ChatOptions::default(),
+                &order,
+                |status: &u16| *status == 400,
+                |_request, _options, _is_fallback| {
+                    let attempts = attempts.clone();
+                    async move {
+                        *attempts.lock().unwrap() += 1;
+                        Err::<(), u16>(status)
+                    }
+                },
+            )
+            .await;
+
+            assert_eq!(result.unwrap_err(), status);
+            assert_eq!(*attempts.lock().unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn test_parse_fallback_order() {
+        assert_eq!(
+            parse_fallback_order(" strip_extra_params , remove_tools,unknown,remove_tools"),
+            vec![RequestSimplification::StripExtraParams, RequestSimplification::RemoveTools]
+        );
+        assert!(parse_fallback_order("").is_empty());
+    }
+}
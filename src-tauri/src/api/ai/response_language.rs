@@ -0,0 +1,248 @@
+//! 助手回复语言：助手可要求始终使用指定语言回复，而不是跟随用户的输入语言
+//!
+//! 配置后会在系统提示词末尾追加语言指令；开启校验时，非流式回复会按文字系统粗略判断主要语言，
+//! 不符时带提醒重试一次。流式回复已实时展示给用户，不做重试。
+
+use crate::api::ai::config::{config_bool, config_value};
+use crate::api::assistant_api::resolve_assistant_detail;
+use crate::db::assistant_db::{AssistantDatabase, AssistantModelConfig};
+use crate::db::conversation_db::{ConversationDatabase, Repository};
+use tracing::warn;
+
+/// 助手配置项：回复语言（如 zh、English、日本語），未配置时跟随用户语言
+pub const RESPONSE_LANGUAGE_CONFIG_KEY: &str = "response_language";
+/// 助手配置项：是否校验回复语言并在不符时重试一次
+pub const RESPONSE_LANGUAGE_CHECK_CONFIG_KEY: &str = "response_language_check";
+
+/// 判断主要语言所需的最少文字字符数，过短的回复不做校验
+const MIN_LETTERS_FOR_DETECTION: usize = 20;
+
+/// 文字系统，用于粗略判断回复的主要语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Chinese,
+    Japanese,
+    Korean,
+    Cyrillic,
+    Arabic,
+    Latin,
+}
+
+/// 从助手模型配置中读取回复语言
+pub fn response_language_from_configs(configs: &[AssistantModelConfig]) -> Option<String> {
+    config_value(configs, RESPONSE_LANGUAGE_CONFIG_KEY).map(str::to_string)
+}
+
+/// 是否开启回复语言校验
+pub fn response_language_check_enabled(configs: &[AssistantModelConfig]) -> bool {
+    config_bool(configs, RESPONSE_LANGUAGE_CHECK_CONFIG_KEY).unwrap_or(false)
+}
+
+/// 追加到系统提示词末尾的语言指令
+pub fn response_language_instruction(language: &str) -> String {
+    format!(
+        "You must always respond in {language}, regardless of the language of the user's \
+messages, attachments or tool results. Translate any content you quote or summarize into {language}. \
+Keep code, commands and proper nouns unchanged."
+    )
+}
+
+/// 语言不符重试时追加的用户提醒
+pub fn response_language_reminder(language: &str) -> String {
+    format!("Your previous reply was not written in {language}. Rewrite it entirely in {language}.")
+}
+
+/// 按助手配置在系统提示词末尾追加语言指令，未配置时原样返回
+pub fn apply_response_language(prompt: String, configs: &[AssistantModelConfig]) -> String {
+    match response_language_from_configs(configs) {
+        Some(language) => {
+            let instruction = response_language_instruction(&language);
+            if prompt.trim().is_empty() {
+                instruction
+            } else {
+                format!("{}\n\n{}", prompt, instruction)
+            }
+        }
+        None => prompt,
+    }
+}
+
+/// 将配置的语言映射到文字系统，无法识别时返回 None（不做校验）
+pub fn expected_script(language: &str) -> Option<Script> {
+    let language = language.trim().to_lowercase();
+    let primary = language.split(['-', '_']).next().unwrap_or_default();
+    match primary {
+        "zh" | "chinese" | "中文" | "简体中文" | "繁體中文" | "繁体中文" | "汉语" | "普通话" => {
+            Some(Script::Chinese)
+        }
+        "ja" | "japanese" | "日本語" | "日语" | "日文" => Some(Script::Japanese),
+        "ko" | "korean" | "한국어" | "韩语" | "韩文" => Some(Script::Korean),
+        "ru" | "uk" | "russian" | "ukrainian" | "русский" | "俄语" => {
+            Some(Script::Cyrillic)
+        }
+        "ar" | "arabic" | "العربية" | "阿拉伯语" => Some(Script::Arabic),
+        "en" | "fr" | "de" | "es" | "it" | "pt" | "nl" | "english" | "french" | "german"
+        | "spanish" | "italian" | "portuguese" | "dutch" | "英语" | "英文" | "法语" | "德语"
+        | "西班牙语" => Some(Script::Latin),
+        _ => None,
+    }
+}
+
+/// 去除代码块，避免代码中的英文影响主要语言判断
+fn strip_code_blocks(text: &str) -> String {
+    let mut in_code = false;
+    text.lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                return false;
+            }
+            !in_code
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 统计各文字系统的字符数，返回占比最高的文字系统；文字过少时返回 None
+pub fn dominant_script(text: &str) -> Option<Script> {
+    let (mut han, mut kana, mut hangul, mut cyrillic, mut arabic, mut latin) =
+        (0usize, 0usize, 0usize, 0usize, 0usize, 0usize);
+    for c in strip_code_blocks(text).chars() {
+        match c as u32 {
+            0x3040..=0x30FF => kana += 1,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => han += 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => hangul += 1,
+            0x0400..=0x04FF => cyrillic += 1,
+            0x0600..=0x06FF => arabic += 1,
+            _ if c.is_ascii_alphabetic() || matches!(c as u32, 0x00C0..=0x024F) => latin += 1,
+            _ => {}
+        }
+    }
+    if han + kana + hangul + cyrillic + arabic + latin < MIN_LETTERS_FOR_DETECTION {
+        return None;
+    }
+    // 日文同时包含汉字与假名，出现一定比例假名时按日文统计
+    let (cjk_script, cjk_count) = if kana * 10 >= han + kana && kana > 0 {
+        (Script::Japanese, han + kana)
+    } else {
+        (Script::Chinese, han)
+    };
+    [
+        (cjk_script, cjk_count),
+        (Script::Korean, hangul),
+        (Script::Cyrillic, cyrillic),
+        (Script::Arabic, arabic),
+        (Script::Latin, latin),
+    ]
+    .into_iter()
+    .max_by_key(|(_, count)| *count)
+    .map(|(script, _)| script)
+}
+
+/// 回复的主要语言是否明显不符合配置的语言；语言无法识别或文字过少时视为符合
+pub fn is_response_language_mismatch(text: &str, language: &str) -> bool {
+    match (expected_script(language), dominant_script(text)) {
+        (Some(expected), Some(actual)) => expected != actual,
+        _ => false,
+    }
+}
+
+/// 读取对话所属助手的回复语言配置：返回（语言, 是否校验），未配置或读取失败时返回 None
+pub fn conversation_response_language(
+    app_handle: &tauri::AppHandle,
+    conversation_db: &ConversationDatabase,
+    conversation_id: i64,
+) -> Option<(String, bool)> {
+    let result = (|| -> Result<Option<(String, bool)>, String> {
+        let assistant_id = conversation_db
+            .conversation_repo()
+            .map_err(|e| e.to_string())?
+            .read(conversation_id)
+            .map_err(|e| e.to_string())?
+            .and_then(|conversation| conversation.assistant_id);
+        let Some(assistant_id) = assistant_id else {
+            return Ok(None);
+        };
+        // 读取合并了基础助手的生效配置
+        let assistant_db = AssistantDatabase::new(app_handle).map_err(|e| e.to_string())?;
+        let configs = resolve_assistant_detail(&assistant_db, assistant_id)?.model_configs;
+        Ok(response_language_from_configs(&configs)
+            .map(|language| (language, response_language_check_enabled(&configs))))
+    })();
+
+    result.unwrap_or_else(|e| {
+        warn!(conversation_id, error = %e, "failed to read response language config");
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tests::test_helpers::create_test_model_config;
+
+    #[test]
+    fn test_apply_response_language_appends_instruction() {
+        let configs = vec![create_test_model_config(RESPONSE_LANGUAGE_CONFIG_KEY, " Japanese ")];
+        let prompt = apply_response_language("You are a helpful assistant.".to_string(), &configs);
+        assert!(prompt.starts_with("You are a helpful assistant.\n\n"));
+        assert!(prompt.ends_with(&response_language_instruction("Japanese")));
+
+        // 未配置或为空时保持原样
+        let prompt = "You are a helpful assistant.".to_string();
+        assert_eq!(apply_response_language(prompt.clone(), &[]), prompt);
+        assert_eq!(
+            apply_response_language(
+                prompt.clone(),
+                &[create_test_model_config(RESPONSE_LANGUAGE_CONFIG_KEY, "  ")]
+            ),
+            prompt
+        );
+    }
+
+    #[test]
+    fn test_response_language_check_enabled() {
+        assert!(response_language_check_enabled(&[create_test_model_config(
+            RESPONSE_LANGUAGE_CHECK_CONFIG_KEY,
+            "true"
+        )]));
+        assert!(!response_language_check_enabled(&[create_test_model_config(
+            RESPONSE_LANGUAGE_CHECK_CONFIG_KEY,
+            "false"
+        )]));
+        assert!(!response_language_check_enabled(&[]));
+    }
+
+    #[test]
+    fn test_dominant_script() {
+        assert_eq!(
+            dominant_script("今天天气很好，我们一起去公园散步吧，顺便买点水果回来。"),
+            Some(Script::Chinese)
+        );
+        assert_eq!(
+            dominant_script("今日はとても良い天気ですね。一緒に公園へ散歩に行きましょう。"),
+            Some(Script::Japanese)
+        );
+        assert_eq!(
+            dominant_script("The weather is nice today, let's take a walk in the park."),
+            Some(Script::Latin)
+        );
+        assert_eq!(
+            dominant_script("Сегодня хорошая погода, пойдём гулять в парк."),
+            Some(Script::Cyrillic)
+        );
+        assert_eq!(dominant_script("ok"), None);
+    }
+
+    #[test]
+    fn test_response_language_mismatch_ignores_code_blocks() {
+        let reply = "下面是示例代码，请参考：\n```rust\nfn main() { println!(\"hello world from rust code\"); }\n```\n这段代码会输出一行问候语。";
+        assert!(!is_response_language_mismatch(reply, "zh-CN"));
+        assert!(is_response_language_mismatch(
+            "This reply is written entirely in English.",
+            "中文"
+        ));
+        // 无法识别的语言不做校验
+        assert!(!is_response_language_mismatch("This reply is in English.", "Klingon"));
+    }
+}
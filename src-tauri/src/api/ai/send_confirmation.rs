@@ -0,0 +1,245 @@
+//! 发送前确认：为指定助手开启后，ask_ai 不会立即生成，而是先返回本次请求的模型、预估用量与可用工具，
+//! 用户确认后再通过令牌继续发送
+
+use crate::api::ai::config::{config_bool, config_value};
+use crate::api::ai::types::{AiRequest, McpOverrideConfig};
+use crate::api::assistant_api::{AssistantDetail, MCPServerWithTools};
+use crate::db::assistant_db::AssistantModelConfig;
+use crate::db::llm_db::LLMModelPrice;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 助手配置项：发送前是否需要用户确认
+pub const REQUIRE_SEND_CONFIRMATION_CONFIG_KEY: &str = "require_send_confirmation";
+
+/// 待确认请求的有效期，过期后需要重新发送
+pub const PENDING_SEND_TTL: Duration = Duration::from_secs(600);
+
+/// 返回给前端的确认信息
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SendConfirmation {
+    pub token: String,
+    pub assistant_id: i64,
+    pub assistant_name: String,
+    pub model_name: String,
+    pub estimated_input_tokens: usize,
+    pub max_output_tokens: Option<u32>,
+    pub estimated_cost: Option<f64>,
+    /// 本次可用的工具，格式为 "server/tool"
+    pub tools: Vec<String>,
+    pub expires_in_secs: u64,
+}
+
+/// 确认前暂存的原始请求参数
+#[derive(Clone, Debug)]
+pub struct PendingSend {
+    pub request: AiRequest,
+    pub override_model_config: Option<HashMap<String, serde_json::Value>>,
+    pub override_prompt: Option<String>,
+    pub override_mcp_config: Option<McpOverrideConfig>,
+}
+
+struct PendingEntry {
+    send: PendingSend,
+    created_at: Instant,
+}
+
+static PENDING_SENDS: OnceLock<Mutex<HashMap<String, PendingEntry>>> = OnceLock::new();
+
+fn pending_sends() -> &'static Mutex<HashMap<String, PendingEntry>> {
+    PENDING_SENDS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 从助手模型配置中读取是否开启发送前确认
+pub fn send_confirmation_required_from_configs(configs: &[AssistantModelConfig]) -> bool {
+    config_bool(configs, REQUIRE_SEND_CONFIRMATION_CONFIG_KEY).unwrap_or(false)
+}
+
+/// 本次发送是否需要先返回确认信息：助手开启了发送前确认，且请求不是确认后的继续发送
+pub fn needs_send_confirmation(confirmed: bool, configs: &[AssistantModelConfig]) -> bool {
+    !confirmed && send_confirmation_required_from_configs(configs)
+}
+
+/// 根据组装完成的请求生成确认信息
+///
+/// `price` 为模型单价设置中该模型的单价，未配置时不估算费用；未设置输出上限时只计算输入部分
+pub fn build_send_confirmation(
+    token: String,
+    assistant_detail: &AssistantDetail,
+    model_name: String,
+    price: Option<&LLMModelPrice>,
+    enabled_servers: &[MCPServerWithTools],
+    estimated_input_tokens: usize,
+    request_max_tokens: Option<u32>,
+) -> SendConfirmation {
+    let configs = &assistant_detail.model_configs;
+    let max_output_tokens = config_value(configs, "max_tokens")
+        .and_then(|value| value.parse::<u32>().ok())
+        .or(request_max_tokens);
+    let estimated_cost = price.map(|price| {
+        price.estimate_cost(estimated_input_tokens as i64, max_output_tokens.unwrap_or(0) as i64)
+    });
+    let tools = enabled_servers
+        .iter()
+        .flat_map(|server| {
+            server
+                .tools
+                .iter()
+                .filter(|tool| tool.is_enabled)
+                .map(move |tool| format!("{}/{}", server.name, tool.name))
+        })
+        .collect();
+
+    SendConfirmation {
+        token,
+        assistant_id: assistant_detail.assistant.id,
+        assistant_name: assistant_detail.assistant.name.clone(),
+        model_name,
+        estimated_input_tokens,
+        max_output_tokens,
+        estimated_cost,
+        tools,
+        expires_in_secs: PENDING_SEND_TTL.as_secs(),
+    }
+}
+
+/// 暂存请求并生成确认令牌，同时清理已过期的请求
+pub fn register_pending_send(send: PendingSend) -> String {
+    let token = uuid::Uuid::new_v4().to_string();
+    let mut pending = pending_sends().lock().unwrap();
+    pending.retain(|_, entry| entry.created_at.elapsed() < PENDING_SEND_TTL);
+    pending.insert(token.clone(), PendingEntry { send, created_at: Instant::now() });
+    token
+}
+
+/// 取出令牌对应的请求，令牌只能使用一次
+pub fn take_pending_send(token: &str) -> Result<PendingSend, String> {
+    let entry = pending_sends()
+        .lock()
+        .unwrap()
+        .remove(token)
+        .ok_or_else(|| "确认请求不存在或已被使用".to_string())?;
+    if entry.created_at.elapsed() >= PENDING_SEND_TTL {
+        return Err("确认请求已过期，请重新发送".to_string());
+    }
+    Ok(entry.send)
+}
+
+/// 丢弃令牌对应的请求（用户取消发送）
+pub fn discard_pending_send(token: &str) -> bool {
+    pending_sends().lock().unwrap().remove(token).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::assistant_db::Assistant;
+    use crate::db::tests::test_helpers::create_test_model_config;
+
+    fn detail(model_configs: Vec<AssistantModelConfig>) -> AssistantDetail {
+        AssistantDetail {
+            assistant: Assistant {
+                id: 1,
+                name: "测试助手".to_string(),
+                description: None,
+                assistant_type: Some(0),
+                is_addition: false,
+                created_time: String::new(),
+            },
+            prompts: vec![],
+            model: vec![],
+            model_configs,
+            prompt_params: vec![],
+            mcp_configs: vec![],
+            mcp_tool_configs: vec![],
+            render_mode: String::new(),
+            starters: vec![],
+            base_assistant_id: None,
+        }
+    }
+
+    fn pending(prompt: &str) -> PendingSend {
+        PendingSend {
+            request: AiRequest {
+                conversation_id: String::new(),
+                assistant_id: 1,
+                prompt: prompt.to_string(),
+                model: None,
+                override_model_id: None,
+                temperature: None,
+                top_p: None,
+                max_tokens: None,
+                stream: None,
+                attachment_list: None,
+                context_message_ids: None,
+            },
+            override_model_config: None,
+            override_prompt: None,
+            override_mcp_config: None,
+        }
+    }
+
+    #[test]
+    fn test_send_confirmation_config_and_cost() {
+        assert!(!send_confirmation_required_from_configs(&[]));
+        assert!(send_confirmation_required_from_configs(&[create_test_model_config(
+            REQUIRE_SEND_CONFIRMATION_CONFIG_KEY,
+            "true"
+        )]));
+        assert!(!send_confirmation_required_from_configs(&[create_test_model_config(
+            REQUIRE_SEND_CONFIRMATION_CONFIG_KEY,
+            "false"
+        )]));
+
+        let price = LLMModelPrice {
+            model_code: "test-model".to_string(),
+            input_price_per_1k: 0.003,
+            output_price_per_1k: 0.015,
+        };
+        let confirm = |configs: Vec<AssistantModelConfig>, price: Option<&LLMModelPrice>| {
+            build_send_confirmation(
+                "token".to_string(),
+                &detail(configs),
+                "test-model".to_string(),
+                price,
+                &[],
+                1_000_000,
+                None,
+            )
+        };
+        assert_eq!(confirm(vec![], None).estimated_cost, None);
+        let cost = confirm(vec![create_test_model_config("max_tokens", "100000")], Some(&price))
+            .estimated_cost;
+        assert!((cost.unwrap() - 4.5).abs() < 1e-9);
+        // 未设置输出上限时只计算输入部分
+        let cost = confirm(vec![], Some(&price)).estimated_cost;
+        assert!((cost.unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generation_runs_only_after_confirmation() {
+        let guarded = vec![create_test_model_config(REQUIRE_SEND_CONFIRMATION_CONFIG_KEY, "true")];
+        let unguarded =
+            vec![create_test_model_config(REQUIRE_SEND_CONFIRMATION_CONFIG_KEY, "false")];
+
+        // ask_ai：开启确认的助手首次发送被拦截，未开启的助手直接生成
+        assert!(needs_send_confirmation(false, &guarded));
+        assert!(!needs_send_confirmation(false, &unguarded));
+        assert!(!needs_send_confirmation(false, &[]));
+
+        // 被拦截的请求暂存后，confirm_and_send 取出原始请求并以 confirmed 重新进入，不再被拦截
+        let token = register_pending_send(pending("hello"));
+        let send = take_pending_send(&token).unwrap();
+        assert_eq!(send.request.prompt, "hello");
+        assert!(!needs_send_confirmation(true, &guarded));
+
+        // 令牌只能使用一次，重复确认不会再次生成
+        assert!(take_pending_send(&token).is_err());
+
+        let cancelled = register_pending_send(pending("cancel me"));
+        assert!(discard_pending_send(&cancelled));
+        assert!(take_pending_send(&cancelled).is_err());
+    }
+}
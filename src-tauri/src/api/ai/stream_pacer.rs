@@ -0,0 +1,165 @@
+//! 流式输出匀速展示：按助手配置的目标字符/秒节奏发出 message_update，与供应商实际流速解耦
+//!
+//! 仅影响前端展示节奏，消息内容仍按收到的 chunk 实时持久化。
+
+use crate::api::ai::config::config_value;
+use crate::db::assistant_db::AssistantModelConfig;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+
+/// 助手配置项：流式输出展示速度（字符/秒），未配置或为 0 表示收到即发出
+pub const REVEAL_SPEED_CONFIG_KEY: &str = "stream_reveal_chars_per_second";
+
+/// 匀速展示的发送间隔
+const REVEAL_TICK: Duration = Duration::from_millis(50);
+
+/// 从助手模型配置中读取流式展示速度
+pub fn reveal_speed_from_configs(configs: &[AssistantModelConfig]) -> Option<u32> {
+    config_value(configs, REVEAL_SPEED_CONFIG_KEY)
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|value| *value > 0)
+}
+
+/// 单条流式消息的匀速展示器
+///
+/// `push` 传入当前完整内容，后台任务按速度逐步发出内容前缀；
+/// `finish` 或直接 drop（如取消、出错）时都会把剩余内容一次性发出。
+pub struct StreamRevealPacer {
+    content_tx: watch::Sender<String>,
+    task: JoinHandle<()>,
+}
+
+impl StreamRevealPacer {
+    pub fn spawn(chars_per_second: u32, emit: impl Fn(&str) + Send + 'static) -> Self {
+        let (content_tx, content_rx) = watch::channel(String::new());
+        let task = tokio::spawn(run_reveal_loop(chars_per_second, content_rx, emit));
+        Self { content_tx, task }
+    }
+
+    /// 更新待展示的完整内容
+    pub fn push(&self, full_content: &str) {
+        self.content_tx.send_replace(full_content.to_string());
+    }
+
+    /// 流结束：立即发出剩余内容并等待后台任务退出
+    pub async fn finish(self) {
+        let Self { content_tx, task } = self;
+        drop(content_tx);
+        let _ = task.await;
+    }
+}
+
+async fn run_reveal_loop(
+    chars_per_second: u32,
+    mut content_rx: watch::Receiver<String>,
+    emit: impl Fn(&str) + Send + 'static,
+) {
+    let mut ticker = interval(REVEAL_TICK);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut revealed_chars = 0usize;
+    let mut budget = 0f64;
+    let mut last_tick = Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            changed = content_rx.changed() => {
+                if changed.is_err() {
+                    // 发送端已关闭（流结束/取消/出错），发出全部剩余内容
+                    let content = content_rx.borrow().clone();
+                    if content.chars().count() > revealed_chars {
+                        emit(&content);
+                    }
+                    return;
+                }
+                continue;
+            }
+        }
+
+        let now = Instant::now();
+        budget += now.duration_since(last_tick).as_secs_f64() * chars_per_second as f64;
+        last_tick = now;
+
+        let content = content_rx.borrow().clone();
+        let total_chars = content.chars().count();
+        if revealed_chars >= total_chars {
+            // 没有待展示内容时不累积额度，避免新内容到达后突然整段出现
+            budget = 0.0;
+            continue;
+        }
+
+        let step = budget.floor() as usize;
+        if step == 0 {
+            continue;
+        }
+        budget -= step as f64;
+        revealed_chars = (revealed_chars + step).min(total_chars);
+        let end = content.char_indices().nth(revealed_chars).map_or(content.len(), |(i, _)| i);
+        emit(&content[..end]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tests::test_helpers::create_test_model_config;
+    use std::sync::{Arc, Mutex};
+
+    fn collecting_pacer(chars_per_second: u32) -> (StreamRevealPacer, Arc<Mutex<Vec<String>>>) {
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let sink = emitted.clone();
+        let pacer = StreamRevealPacer::spawn(chars_per_second, move |content| {
+            sink.lock().unwrap().push(content.to_string());
+        });
+        (pacer, emitted)
+    }
+
+    /// 测试配置展示速度后按节奏分批发出，结束时内容完整
+    #[tokio::test]
+    async fn test_reveal_is_paced_and_final_content_complete() {
+        let full = "流式输出匀速展示".repeat(10);
+        let (pacer, emitted) = collecting_pacer(200);
+
+        pacer.push(&full[..full.len() / 2]);
+        pacer.push(&full);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        pacer.finish().await;
+
+        let emitted = emitted.lock().unwrap();
+        assert!(emitted.len() > 2, "expected several paced emissions, got {}", emitted.len());
+        assert!(emitted[0].chars().count() < full.chars().count());
+        for window in emitted.windows(2) {
+            assert!(window[1].starts_with(window[0].as_str()));
+            assert!(window[1].len() > window[0].len());
+        }
+        assert_eq!(emitted.last().unwrap(), &full);
+    }
+
+    /// 测试 drop（取消场景）时也会发出剩余内容
+    #[tokio::test]
+    async fn test_drop_flushes_buffer() {
+        let (pacer, emitted) = collecting_pacer(1);
+        pacer.push("cancelled before fully revealed");
+        drop(pacer);
+
+        for _ in 0..50 {
+            if !emitted.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            emitted.lock().unwrap().last().map(String::as_str),
+            Some("cancelled before fully revealed")
+        );
+    }
+
+    #[test]
+    fn test_reveal_speed_from_configs() {
+        let config = |value: &str| create_test_model_config(REVEAL_SPEED_CONFIG_KEY, value);
+        assert_eq!(reveal_speed_from_configs(&[config("60")]), Some(60));
+        assert_eq!(reveal_speed_from_configs(&[config("0")]), None);
+        assert_eq!(reveal_speed_from_configs(&[]), None);
+    }
+}
@@ -0,0 +1,126 @@
+//! 流式内容持久化批处理：流式过程中按时间间隔合并消息内容写入，减少数据库往返
+//!
+//! 只影响写库频率，message_update 事件仍按 chunk 到达节奏发出。
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 流式过程中同一条消息两次写库的最小间隔
+pub const STREAM_PERSIST_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 按消息合并流式内容写入
+///
+/// 间隔内的更新只保留最新内容，`flush`/`flush_all` 或 drop（取消、出错提前返回）时写入剩余内容，
+/// 保证最终落库的是完整内容。
+pub struct StreamContentPersister<W: FnMut(i64, &str)> {
+    interval: Duration,
+    write: W,
+    last_write: HashMap<i64, Instant>,
+    pending: HashMap<i64, String>,
+}
+
+impl<W: FnMut(i64, &str)> StreamContentPersister<W> {
+    pub fn new(interval: Duration, write: W) -> Self {
+        Self { interval, write, last_write: HashMap::new(), pending: HashMap::new() }
+    }
+
+    /// 记录消息最新的完整内容，距上次写入已超过间隔时立即写库
+    pub fn update(&mut self, msg_id: i64, content: &str) {
+        let now = Instant::now();
+        let due = match self.last_write.get(&msg_id) {
+            Some(last_write) => now.duration_since(*last_write) >= self.interval,
+            None => true,
+        };
+        if due {
+            self.pending.remove(&msg_id);
+            self.write_now(msg_id, content, now);
+        } else {
+            self.pending.insert(msg_id, content.to_string());
+        }
+    }
+
+    /// 写入指定消息尚未落库的内容
+    pub fn flush(&mut self, msg_id: i64) {
+        if let Some(content) = self.pending.remove(&msg_id) {
+            self.write_now(msg_id, &content, Instant::now());
+        }
+    }
+
+    /// 写入所有消息尚未落库的内容
+    pub fn flush_all(&mut self) {
+        let pending: Vec<i64> = self.pending.keys().copied().collect();
+        for msg_id in pending {
+            self.flush(msg_id);
+        }
+    }
+
+    fn write_now(&mut self, msg_id: i64, content: &str, now: Instant) {
+        (self.write)(msg_id, content);
+        self.last_write.insert(msg_id, now);
+    }
+}
+
+impl<W: FnMut(i64, &str)> Drop for StreamContentPersister<W> {
+    fn drop(&mut self) {
+        self.flush_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::thread::sleep;
+
+    /// 测试慢速存储下写库被合并，而事件按每个 chunk 发出，最终内容完整
+    #[test]
+    fn test_writes_are_batched_while_events_flow() {
+        let writes: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let sink = writes.clone();
+        let mut events = Vec::new();
+        let mut content = String::new();
+        {
+            let mut persister = StreamContentPersister::new(Duration::from_millis(20), |_, c| {
+                // 模拟远程数据库的往返延迟
+                sleep(Duration::from_millis(5));
+                sink.borrow_mut().push(c.to_string());
+            });
+            for i in 0..50 {
+                content.push_str(&format!("chunk{} ", i));
+                persister.update(1, &content);
+                events.push(content.clone());
+                sleep(Duration::from_millis(1));
+            }
+            persister.flush(1);
+        }
+
+        let writes = writes.borrow();
+        assert_eq!(events.len(), 50, "every chunk should still produce an event");
+        assert!(writes.len() < 25, "expected batched writes, got {}", writes.len());
+        assert_eq!(writes.last().unwrap(), &content, "final write must persist full content");
+    }
+
+    /// 测试 drop（取消/出错提前返回）时写入剩余内容，且不会重复写入已落库的内容
+    #[test]
+    fn test_drop_flushes_pending_content() {
+        let writes: Rc<RefCell<Vec<(i64, String)>>> = Rc::new(RefCell::new(Vec::new()));
+        let sink = writes.clone();
+        {
+            let mut persister = StreamContentPersister::new(Duration::from_secs(60), |id, c| {
+                sink.borrow_mut().push((id, c.to_string()))
+            });
+            persister.update(1, "a");
+            persister.update(1, "ab");
+            persister.update(2, "x");
+            persister.update(1, "abc");
+        }
+
+        let mut writes = writes.borrow().clone();
+        writes.sort();
+        assert_eq!(
+            writes,
+            vec![(1, "a".to_string()), (1, "abc".to_string()), (2, "x".to_string())]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::api::ai::config::{
-    calculate_retry_delay, get_network_proxy_from_config, get_request_timeout_from_config,
-    get_retry_attempts_from_config,
+    get_network_proxy_from_config, get_request_timeout_from_config, get_retry_attempts_from_config,
+    get_retry_backoff_from_config,
 };
 use crate::api::genai_client;
 use crate::db::conversation_db::{ConversationDatabase, ConversationSummary, Message};
@@ -86,10 +86,13 @@ fn resolve_group(replacements: &HashMap<String, String>, group_id: &str) -> Stri
 }
 
 /// 生成对话总结
+///
+/// `force` 为 true 时（手动触发）忽略功能开关和已有总结，生成后覆盖旧的总结
 pub async fn generate_conversation_summary(
     app_handle: &tauri::AppHandle,
     conversation_id: i64,
     config_feature_map: HashMap<String, HashMap<String, FeatureConfig>>,
+    force: bool,
 ) -> Result<(), AppError> {
     // 0) 检查对话总结功能是否启用
     let feature_config_opt = config_feature_map.get("conversation_summary");
@@ -98,17 +101,19 @@ pub async fn generate_conversation_summary(
         .map(|c| c.value.clone())
         .unwrap_or_else(|| "true".to_string());
 
-    if summary_enabled != "true" && summary_enabled != "1" {
+    if !force && summary_enabled != "true" && summary_enabled != "1" {
         debug!("对话总结功能已禁用，跳过总结生成");
         return Ok(());
     }
 
     // 检查是否已经总结过
     let conversation_db = ConversationDatabase::new(app_handle).map_err(AppError::from)?;
-    if let Ok(repo) = conversation_db.conversation_summary_repo() {
-        if repo.exists(conversation_id)? {
-            debug!(conversation_id, "对话已经总结过，跳过");
-            return Ok(());
+    if !force {
+        if let Ok(repo) = conversation_db.conversation_summary_repo() {
+            if repo.exists(conversation_id)? {
+                debug!(conversation_id, "对话已经总结过，跳过");
+                return Ok(());
+            }
         }
     }
 
@@ -296,6 +301,7 @@ pub async fn generate_conversation_summary(
 
     // 5) 调用 AI 生成总结
     let max_retry_attempts = get_retry_attempts_from_config(&config_feature_map);
+    let retry_backoff = get_retry_backoff_from_config(&config_feature_map);
 
     let mut attempts = 0;
     let response = loop {
@@ -323,7 +329,7 @@ pub async fn generate_conversation_summary(
                     break Err(e.to_string());
                 }
                 warn!(attempts, error = %e, conversation_id, "对话总结生成失败，正在重试");
-                let delay = calculate_retry_delay(attempts);
+                let delay = retry_backoff.delay_for(attempts);
                 sleep(Duration::from_millis(delay)).await;
             }
         }
@@ -551,11 +557,10 @@ fn save_summary_to_db(
         created_time: chrono::Utc::now(),
     };
 
-    conversation_db
-        .conversation_summary_repo()
-        .map_err(AppError::from)?
-        .create(&conversation_summary)
-        .map_err(AppError::from)?;
+    // 手动重新总结时覆盖旧记录，保证每个对话只有一条总结
+    let repo = conversation_db.conversation_summary_repo().map_err(AppError::from)?;
+    repo.delete_by_conversation_id(conversation_id).map_err(AppError::from)?;
+    repo.create(&conversation_summary).map_err(AppError::from)?;
 
     info!(conversation_id, "对话总结已保存");
     Ok(())
@@ -575,11 +580,9 @@ fn save_empty_summary_to_db(
         created_time: chrono::Utc::now(),
     };
 
-    conversation_db
-        .conversation_summary_repo()
-        .map_err(AppError::from)?
-        .create(&conversation_summary)
-        .map_err(AppError::from)?;
+    let repo = conversation_db.conversation_summary_repo().map_err(AppError::from)?;
+    repo.delete_by_conversation_id(conversation_id).map_err(AppError::from)?;
+    repo.create(&conversation_summary).map_err(AppError::from)?;
 
     info!(conversation_id, "已为空对话创建占位总结记录");
     Ok(())
@@ -1,19 +1,179 @@
 use crate::api::ai::config::{
-    calculate_retry_delay, get_network_proxy_from_config, get_request_timeout_from_config,
-    get_retry_attempts_from_config,
+    get_network_proxy_from_config, get_request_timeout_from_config, get_retry_attempts_from_config,
+    get_retry_backoff_from_config,
 };
 use crate::api::ai::events::TITLE_CHANGE_EVENT;
+use crate::api::ai::summary::get_latest_branch_messages;
 use crate::api::genai_client;
-use crate::db::conversation_db::{Conversation, ConversationDatabase};
+use crate::db::conversation_db::{Conversation, ConversationDatabase, Message};
 use crate::db::llm_db::LLMDatabase;
 use crate::db::system_db::FeatureConfig;
 use crate::errors::AppError;
 use crate::utils::window_utils::send_error_to_appropriate_window;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tauri::Emitter;
 use tokio::time::{sleep, Duration};
 use tracing::{debug, error, warn};
 
+/// 自动生成标题的触发时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleTrigger {
+    /// 第一轮回复后生成（默认）
+    FirstResponse,
+    /// 第 N 轮回复后生成，适合首轮以工具调用或推理为主的对话
+    AfterTurns(usize),
+    /// 仅在用户手动请求时生成
+    Manual,
+}
+
+/// 标题生成的触发配置，来自 conversation_summary 的 title_trigger 等配置项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TitleTriggerConfig {
+    pub trigger: TitleTrigger,
+    /// 生成标题时是否把工具调用结果一并作为上下文
+    pub include_tool_results: bool,
+}
+
+impl Default for TitleTriggerConfig {
+    fn default() -> Self {
+        Self { trigger: TitleTrigger::FirstResponse, include_tool_results: false }
+    }
+}
+
+impl TitleTriggerConfig {
+    pub fn from_config(
+        config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+    ) -> Self {
+        let Some(feature_config) = config_feature_map.get("conversation_summary") else {
+            return Self::default();
+        };
+        let value = |key: &str| feature_config.get(key).map(|c| c.value.trim().to_string());
+        let trigger = match value("title_trigger").as_deref() {
+            Some("after_turns") => {
+                let turns = value("title_trigger_turns")
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .filter(|turns| *turns > 0)
+                    .unwrap_or(1);
+                TitleTrigger::AfterTurns(turns)
+            }
+            Some("manual") => TitleTrigger::Manual,
+            _ => TitleTrigger::FirstResponse,
+        };
+        let include_tool_results =
+            matches!(value("title_include_tool_results").as_deref(), Some("true") | Some("1"));
+        Self { trigger, include_tool_results }
+    }
+
+    /// 对话第 `user_turn` 轮（从 1 开始计数的用户消息数）的回复完成后是否需要生成标题
+    pub fn should_generate(&self, user_turn: usize) -> bool {
+        match self.trigger {
+            TitleTrigger::FirstResponse => user_turn == 1,
+            TitleTrigger::AfterTurns(turns) => user_turn == turns,
+            TitleTrigger::Manual => false,
+        }
+    }
+
+    /// 是否需要从对话历史重新组织标题上下文，而不是只用当前这一轮的问答
+    pub fn uses_conversation_history(&self) -> bool {
+        self.include_tool_results
+            || matches!(self.trigger, TitleTrigger::AfterTurns(turns) if turns > 1)
+    }
+
+    /// 包含工具结果时，发起工具调用的回复先不生成标题，等工具结果返回后的最终回复再生成
+    pub fn waits_for_tool_results(&self, has_tool_calls: bool) -> bool {
+        self.include_tool_results && has_tool_calls
+    }
+
+    /// 工具结果续写的回复完成后是否需要生成标题：只有包含工具结果时，触发轮次的标题才会推迟到这里
+    pub fn should_generate_after_tool_results(&self, user_turn: usize) -> bool {
+        self.include_tool_results && self.should_generate(user_turn)
+    }
+}
+
+/// 回复是否发起了工具调用：原生工具调用，或内容中的 MCP 工具调用（提示词模式的 XML 与界面注释）
+pub fn response_has_tool_calls(content: &str, native_tool_calls: usize) -> bool {
+    native_tool_calls > 0
+        || content.contains("<mcp_tool_call>")
+        || content.contains("MCP_TOOL_CALL:")
+}
+
+/// 对话的最新分支，编辑或重新生成留下的旧版本不在其中
+fn latest_branch(messages: &[Message]) -> Vec<Message> {
+    let rows: Vec<_> = messages.iter().map(|m| (m.clone(), None)).collect();
+    get_latest_branch_messages(&rows)
+}
+
+/// 统计对话最新分支上的用户轮数
+pub fn count_user_turns(messages: &[Message]) -> usize {
+    latest_branch(messages).iter().filter(|m| m.message_type == "user").count()
+}
+
+/// 从对话消息中组织标题生成的上下文：返回（用户消息, 助手回复），
+/// 多轮内容按顺序以空行拼接，开启 `include_tool_results` 时工具结果并入助手回复
+pub fn title_source_from_messages(
+    messages: &[Message],
+    include_tool_results: bool,
+) -> (String, String) {
+    let join = |types: &[&str]| {
+        messages
+            .iter()
+            .filter(|m| types.contains(&m.message_type.as_str()))
+            .map(|m| m.content.trim())
+            .filter(|content| !content.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+    let content =
+        if include_tool_results { join(&["response", "tool_result"]) } else { join(&["response"]) };
+    (join(&["user"]), content)
+}
+
+/// 读取对话消息，带多个附件的消息会出现多行，按 id 去重
+fn load_conversation_messages(
+    conversation_db: &ConversationDatabase,
+    conversation_id: i64,
+) -> Result<Vec<Message>, String> {
+    let rows = conversation_db
+        .message_repo()
+        .map_err(|e| e.to_string())?
+        .list_by_conversation_id(conversation_id)
+        .map_err(|e| e.to_string())?;
+    let mut seen_ids = HashSet::new();
+    Ok(rows.into_iter().map(|(m, _)| m).filter(|m| seen_ids.insert(m.id)).collect())
+}
+
+/// 统计已有对话的用户轮数（含刚发送的用户消息）
+pub fn count_conversation_user_turns(
+    conversation_db: &ConversationDatabase,
+    conversation_id: i64,
+) -> Result<usize, String> {
+    load_conversation_messages(conversation_db, conversation_id)
+        .map(|messages| count_user_turns(&messages))
+}
+
+/// 按触发配置确定标题生成使用的上下文；无需对话历史或读取失败时沿用当前轮的问答
+pub fn resolve_title_source(
+    conversation_db: &ConversationDatabase,
+    conversation_id: i64,
+    trigger_config: &TitleTriggerConfig,
+    user_prompt: String,
+    content: String,
+) -> (String, String) {
+    if !trigger_config.uses_conversation_history() {
+        return (user_prompt, content);
+    }
+    match load_conversation_messages(conversation_db, conversation_id) {
+        Ok(messages) => title_source_from_messages(
+            &latest_branch(&messages),
+            trigger_config.include_tool_results,
+        ),
+        Err(e) => {
+            warn!(error = %e, conversation_id, "failed to read messages for title generation");
+            (user_prompt, content)
+        }
+    }
+}
+
 pub async fn generate_title(
     app_handle: &tauri::AppHandle,
     conversation_id: i64,
@@ -221,6 +381,7 @@ pub async fn generate_title(
 
     // 从配置中获取最大重试次数
     let max_retry_attempts = get_retry_attempts_from_config(&config_feature_map);
+    let retry_backoff = get_retry_backoff_from_config(&config_feature_map);
 
     let mut attempts = 0;
     let response = loop {
@@ -233,7 +394,7 @@ pub async fn generate_title(
                     break Err(e.to_string());
                 }
                 warn!(attempts, error = %e, "Title generation attempt failed, retrying");
-                let delay = calculate_retry_delay(attempts);
+                let delay = retry_backoff.delay_for(attempts);
                 sleep(Duration::from_millis(delay)).await;
             }
         }
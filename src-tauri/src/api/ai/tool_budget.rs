@@ -0,0 +1,62 @@
+//! 工具调用预算：限制单轮对话中的工具调用次数，达到上限后强制模型基于已有信息直接回答
+
+use crate::api::ai::config::config_value;
+use crate::db::assistant_db::AssistantModelConfig;
+use crate::db::conversation_db::Message;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+/// 助手配置项：单轮对话中允许的最大工具调用次数，未配置或为 0 表示不限制
+pub const MAX_TOOL_CALLS_CONFIG_KEY: &str = "max_tool_calls_before_answer";
+
+/// 强制回答轮次追加给模型的指令
+pub const FORCED_FINAL_ANSWER_PROMPT: &str = "You have reached the tool call limit for this turn. \
+Tools are no longer available. Do not request any more tool calls; answer the user's request now \
+using only the information you have already gathered, and clearly state anything you could not verify.";
+
+static FORCED_ANSWER_CONVERSATIONS: OnceLock<Mutex<HashSet<i64>>> = OnceLock::new();
+
+fn forced_answer_conversations() -> &'static Mutex<HashSet<i64>> {
+    FORCED_ANSWER_CONVERSATIONS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 从助手模型配置中读取工具调用上限
+pub fn max_tool_calls_from_configs(configs: &[AssistantModelConfig]) -> Option<usize> {
+    config_value(configs, MAX_TOOL_CALLS_CONFIG_KEY)
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|value| *value > 0)
+}
+
+/// 统计当前轮次（最后一条用户消息之后）已执行的工具调用次数
+pub fn count_tool_calls_in_current_turn(messages: &[Message]) -> usize {
+    let turn_start = messages.iter().rposition(|m| m.message_type == "user").map_or(0, |i| i + 1);
+    messages[turn_start..].iter().filter(|m| m.message_type == "tool_result").count()
+}
+
+/// 当前轮次的工具调用次数达到上限时，下一轮需要禁用工具并强制回答
+pub fn should_force_final_answer(messages: &[Message], max_tool_calls: Option<usize>) -> bool {
+    max_tool_calls.is_some_and(|max| count_tool_calls_in_current_turn(messages) >= max)
+}
+
+/// 对话是否处于强制回答轮次（该轮次中检测到的工具调用不会被执行）
+pub fn is_forced_answer_turn(conversation_id: i64) -> bool {
+    forced_answer_conversations().lock().unwrap().contains(&conversation_id)
+}
+
+/// 强制回答轮次的作用域标记，drop 时自动清除
+pub struct ForcedAnswerGuard {
+    conversation_id: i64,
+}
+
+impl ForcedAnswerGuard {
+    pub fn enter(conversation_id: i64) -> Self {
+        forced_answer_conversations().lock().unwrap().insert(conversation_id);
+        Self { conversation_id }
+    }
+}
+
+impl Drop for ForcedAnswerGuard {
+    fn drop(&mut self) {
+        forced_answer_conversations().lock().unwrap().remove(&self.conversation_id);
+    }
+}
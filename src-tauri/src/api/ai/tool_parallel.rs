@@ -0,0 +1,67 @@
+//! 工具并行执行：同一轮中捕获到的多个自动执行工具调用按有限并发同时执行，结果按调用顺序汇总
+
+use crate::api::ai::config::{config_bool, config_value};
+use crate::db::assistant_db::AssistantModelConfig;
+use futures::FutureExt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use tokio::task::JoinSet;
+
+/// 助手配置项：是否并行执行同一轮中的多个工具调用，未配置时关闭
+pub const PARALLEL_TOOL_EXECUTION_CONFIG_KEY: &str = "parallel_tool_execution";
+/// 助手配置项：并行执行时同时运行的最大工具调用数
+pub const MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY: &str = "max_parallel_tool_calls";
+/// 未配置并发上限时的默认值
+pub const DEFAULT_MAX_PARALLEL_TOOL_CALLS: usize = 4;
+
+/// 从助手模型配置中读取工具调用的并发数，未开启并行执行时为 1（逐个执行）
+pub fn tool_concurrency_from_configs(configs: &[AssistantModelConfig]) -> usize {
+    if !config_bool(configs, PARALLEL_TOOL_EXECUTION_CONFIG_KEY).unwrap_or(false) {
+        return 1;
+    }
+    config_value(configs, MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY)
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_PARALLEL_TOOL_CALLS)
+}
+
+/// 以有限并发执行任务，结果按输入顺序返回；单个任务失败或 panic 不影响其他任务
+pub async fn run_bounded<I, T, F, Fut>(
+    items: Vec<I>,
+    concurrency: usize,
+    task: F,
+) -> Vec<Result<T, String>>
+where
+    T: Send + 'static,
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = Result<T, String>> + Send + 'static,
+{
+    let concurrency = concurrency.max(1);
+    let mut results: Vec<Option<Result<T, String>>> = items.iter().map(|_| None).collect();
+    let mut pending = items.into_iter().enumerate();
+    let mut join_set = JoinSet::new();
+
+    loop {
+        while join_set.len() < concurrency {
+            let Some((index, item)) = pending.next() else {
+                break;
+            };
+            let future = AssertUnwindSafe(task(item)).catch_unwind();
+            join_set.spawn(async move {
+                let result = future.await.unwrap_or_else(|_| Err("工具执行异常退出".to_string()));
+                (index, result)
+            });
+        }
+        match join_set.join_next().await {
+            Some(Ok((index, result))) => results[index] = Some(result),
+            // 任务内部已捕获 panic，这里只会是运行时被关闭导致的取消
+            Some(Err(e)) => tracing::warn!(error = %e, "parallel tool task aborted"),
+            None => break,
+        }
+    }
+
+    results
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Err("工具执行被取消".to_string())))
+        .collect()
+}
@@ -0,0 +1,74 @@
+//! 复用工具结果的重新生成
+//!
+//! 默认的重新生成会让模型从头回答，并可能再次执行工具。复用模式下，被重新生成的回复若发起过工具调用，
+//! 直接把已存储的工具调用记录作为结果回填给模型，并在该轮禁用工具，让模型基于同一份工具数据给出新的回答。
+//! 对于昂贵或非幂等的工具，这样更省也更安全。
+
+use crate::db::conversation_db::{Message, MessageAttachment};
+use crate::db::mcp_db::MCPToolCall;
+use crate::mcp::execution_api::{build_tool_result_message_content, tool_call_history_id};
+use crate::mcp::tool_result_integrity::{declared_tool_call_ids, validate_tool_result_correlation};
+
+/// `regenerate_ai` 的 mode 取值：复用工具结果
+pub const REUSE_TOOL_RESULTS_MODE: &str = "reuse_tool_results";
+
+/// 复用工具结果时追加的回答指令
+pub const REUSED_TOOL_RESULTS_PROMPT: &str =
+    "The tool results above were produced earlier in this turn. \
+Answer the previous request again based only on these results. Do not call any tools.";
+
+/// 重新生成模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegenerateMode {
+    /// 从头重新生成，模型可以再次调用工具（默认）
+    #[default]
+    Rerun,
+    /// 复用上一轮已存储的工具结果，不再执行工具
+    ReuseToolResults,
+}
+
+impl RegenerateMode {
+    pub fn from_value(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(REUSE_TOOL_RESULTS_MODE) => RegenerateMode::ReuseToolResults,
+            _ => RegenerateMode::Rerun,
+        }
+    }
+
+    /// 该模式下是否允许模型调用工具
+    pub fn allows_tool_calls(self) -> bool {
+        self == RegenerateMode::Rerun
+    }
+}
+
+/// 构建复用工具结果时追加在历史消息之后的内容：原回复（含工具调用）、已存储的工具结果与回答指令
+///
+/// `tool_calls` 为原回复关联的工具调用记录；只使用已完成（成功或失败）的记录，
+/// 并校验它们与原回复声明的工具调用一一对应。
+pub fn build_reused_tool_turn(
+    response: &Message,
+    tool_calls: &[MCPToolCall],
+) -> Result<Vec<(String, String, Vec<MessageAttachment>)>, String> {
+    let results: Vec<(String, String)> = tool_calls
+        .iter()
+        .filter_map(|tool_call| {
+            build_tool_result_message_content(tool_call)
+                .map(|content| (tool_call_history_id(tool_call), content))
+        })
+        .collect();
+    if results.is_empty() {
+        return Err("该回复没有可复用的工具调用结果".to_string());
+    }
+
+    let declared_call_ids = declared_tool_call_ids(response.tool_calls_json.as_deref())
+        .unwrap_or_else(|| tool_calls.iter().map(tool_call_history_id).collect());
+    let result_call_ids: Vec<String> = results.iter().map(|(id, _)| id.clone()).collect();
+    validate_tool_result_correlation(&declared_call_ids, &result_call_ids)?;
+
+    let mut turn = vec![("response".to_string(), response.content.clone(), Vec::new())];
+    turn.extend(
+        results.into_iter().map(|(_, content)| ("tool_result".to_string(), content, Vec::new())),
+    );
+    turn.push(("user".to_string(), REUSED_TOOL_RESULTS_PROMPT.to_string(), Vec::new()));
+    Ok(turn)
+}
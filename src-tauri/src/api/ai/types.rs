@@ -1,3 +1,4 @@
+use crate::api::ai::send_confirmation::SendConfirmation;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -26,10 +27,37 @@ pub struct AiRequest {
     pub max_tokens: Option<u32>,
     pub stream: Option<bool>,
     pub attachment_list: Option<Vec<i64>>,
+    /// 仅使用这些消息作为本轮上下文（一次性提问），为空时使用完整对话历史
+    #[serde(default)]
+    pub context_message_ids: Option<Vec<i64>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AiResponse {
     pub conversation_id: i64,
     pub request_prompt_result_with_context: String,
+    /// 助手开启发送前确认时返回，此时尚未开始生成
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<SendConfirmation>,
+}
+
+/// 助手冒烟测试中模型发起的工具调用（仅记录，不会实际执行）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AssistantTestToolCall {
+    pub server_name: String,
+    pub tool_name: String,
+    pub parameters: String,
+}
+
+/// 助手冒烟测试结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AssistantTestResult {
+    pub assistant_id: i64,
+    pub model_code: String,
+    pub content: String,
+    pub tool_calls: Vec<AssistantTestToolCall>,
+    pub input_token_count: i32,
+    pub output_token_count: i32,
+    pub token_count: i32,
+    pub duration_ms: i64,
 }
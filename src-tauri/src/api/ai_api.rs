@@ -7,20 +7,41 @@ use crate::api::ai::chat::{
 use crate::api::ai::config::{
     get_network_proxy_from_config, get_request_timeout_from_config, ChatConfig, ConfigBuilder,
 };
+use crate::api::ai::context_trim::{
+    context_trim_from_configs, format_summary_for_context, ContextTrimStrategy,
+};
 use crate::api::ai::conversation::{
-    build_chat_request_from_messages, build_message_list_from_db, filter_messages_for_parent_group,
-    init_conversation, BranchSelection, ChatRequestBuildResult, ToolCallStrategy, ToolConfig,
+    annotate_partial_responses, build_chat_request_from_messages,
+    build_message_list_from_context_ids, build_message_list_from_db_with_truncation,
+    filter_messages_for_parent_group, init_conversation, BranchSelection, ChatRequestBuildResult,
+    ContextTruncation, ToolCallStrategy, ToolConfig,
 };
 use crate::api::ai::events::{
     ActivityFocus, ConversationEvent, ConversationRuntimeState, ConversationShineState,
-    MessageAddEvent, MessageUpdateEvent,
+    ForcedFinalAnswerEvent, MessageAddEvent, MessageUpdateEvent,
+};
+use crate::api::ai::generation_status::{self, ActiveGenerationStatus};
+use crate::api::ai::response_language::apply_response_language;
+use crate::api::ai::send_confirmation::{
+    build_send_confirmation, discard_pending_send, needs_send_confirmation, register_pending_send,
+    take_pending_send, PendingSend,
+};
+use crate::api::ai::stream_pacer::reveal_speed_from_configs;
+use crate::api::ai::title::{count_conversation_user_turns, generate_title, TitleTriggerConfig};
+use crate::api::ai::tool_budget::{
+    count_tool_calls_in_current_turn, max_tool_calls_from_configs, should_force_final_answer,
+    ForcedAnswerGuard, FORCED_FINAL_ANSWER_PROMPT,
+};
+use crate::api::ai::tool_replay::{build_reused_tool_turn, RegenerateMode};
+use crate::api::ai::types::{
+    AiRequest, AiResponse, AssistantTestResult, AssistantTestToolCall, McpOverrideConfig,
 };
-use crate::api::ai::title::generate_title;
-use crate::api::ai::types::{AiRequest, AiResponse, McpOverrideConfig};
 use crate::api::assistant_api::{get_assistant, get_assistants};
+use crate::api::attachment_api::{build_attachment_context, wait_for_pending_ocr};
+use crate::artifacts::preview_console::take_preview_console_context;
 
 use crate::api::genai_client;
-use crate::db::conversation_db::{AttachmentType, Repository};
+use crate::db::conversation_db::Repository;
 use crate::db::conversation_db::{ConversationDatabase, Message, MessageAttachment};
 use crate::db::llm_db::LLMDatabase;
 use crate::db::mcp_db::MCPDatabase;
@@ -31,12 +52,16 @@ use crate::skills::{collect_skills_info_for_assistant, format_skills_prompt};
 use crate::state::activity_state::ConversationActivityManager;
 use crate::state::message_token::MessageTokenManager;
 use crate::template_engine::build_template_engine;
-use crate::utils::window_utils::send_conversation_event_to_chat_windows;
+use crate::template_engine::{prompt_placeholder_variables, render_placeholders};
+use crate::utils::token_utils::estimate_tokens;
+use crate::utils::window_utils::{
+    emit_conversation_event, send_conversation_event_to_chat_windows,
+};
 use crate::{AcpSessionState, AppState, FeatureConfigState};
 use anyhow::Context;
 use genai::chat::Tool;
 use std::collections::{HashMap, HashSet};
-use tauri::Emitter;
+use std::sync::OnceLock;
 use tauri::Manager;
 use tauri::State;
 use tracing::{debug, error, info, instrument, warn};
@@ -190,6 +215,103 @@ pub fn build_tools_with_mapping(
     (tools, mapping)
 }
 
+/// 读取对话的全部消息用于组装上下文，部分回复会附加中断说明
+fn list_context_messages(
+    db: &ConversationDatabase,
+    conversation_id: i64,
+) -> Result<Vec<(Message, Option<MessageAttachment>)>, AppError> {
+    let repo = db.message_repo()?;
+    let mut messages = repo.list_by_conversation_id(conversation_id)?;
+    match repo.list_partial_ids(conversation_id) {
+        Ok(partial_message_ids) => annotate_partial_responses(&mut messages, &partial_message_ids),
+        Err(e) => warn!(conversation_id, error = %e, "failed to load partial responses"),
+    }
+    Ok(messages)
+}
+
+/// 根据助手配置 `max_context_messages`、`context_max_tokens` 与对话中的置顶消息构建上下文截断配置，
+/// 均未配置时返回 None
+fn load_context_truncation(
+    db: &ConversationDatabase,
+    conversation_id: i64,
+    assistant_model_configs: &[crate::db::assistant_db::AssistantModelConfig],
+) -> Option<ContextTruncation> {
+    let max_messages = assistant_model_configs
+        .iter()
+        .find(|config| config.name == "max_context_messages")
+        .and_then(|config| config.value.as_ref())
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut token_trim = context_trim_from_configs(assistant_model_configs);
+    if max_messages == 0 && token_trim.is_none() {
+        return None;
+    }
+    if let Some(trim) =
+        token_trim.as_mut().filter(|trim| trim.strategy == ContextTrimStrategy::SummarizeOldest)
+    {
+        // 复用对话总结任务生成的总结；尚未生成时退化为直接丢弃最早的消息
+        trim.summary = match db
+            .conversation_summary_repo()
+            .and_then(|repo| repo.get_by_conversation_id(conversation_id).map_err(AppError::from))
+        {
+            Ok(summary) => summary.as_ref().and_then(format_summary_for_context),
+            Err(e) => {
+                warn!(conversation_id, error = %e, "failed to load conversation summary");
+                None
+            }
+        };
+    }
+    let pinned_message_ids = match db
+        .message_repo()
+        .and_then(|repo| repo.list_pinned_ids(conversation_id).map_err(AppError::from))
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            warn!(conversation_id, error = %e, "failed to load pinned messages");
+            HashSet::new()
+        }
+    };
+    Some(ContextTruncation { max_messages, pinned_message_ids, token_trim })
+}
+
+/// 当前轮次的工具调用次数达到 `max_tool_calls_before_answer` 时，追加强制回答指令并通知前端，
+/// 返回的 guard 在本轮回复结束前禁止执行新的工具调用
+fn prepare_forced_final_answer(
+    app_handle: &tauri::AppHandle,
+    conversation_id: i64,
+    latest_branch: &[Message],
+    assistant_model_configs: &[crate::db::assistant_db::AssistantModelConfig],
+    init_message_list: &mut Vec<(String, String, Vec<MessageAttachment>)>,
+) -> Option<ForcedAnswerGuard> {
+    let max_tool_calls = max_tool_calls_from_configs(assistant_model_configs)?;
+    if !should_force_final_answer(latest_branch, Some(max_tool_calls)) {
+        return None;
+    }
+    let tool_call_count = count_tool_calls_in_current_turn(latest_branch);
+    info!(
+        conversation_id,
+        tool_call_count, max_tool_calls, "tool call budget reached, forcing final answer"
+    );
+
+    init_message_list.push((
+        "user".to_string(),
+        FORCED_FINAL_ANSWER_PROMPT.to_string(),
+        Vec::new(),
+    ));
+    let event = ConversationEvent {
+        r#type: "forced_final_answer".to_string(),
+        data: serde_json::to_value(ForcedFinalAnswerEvent {
+            conversation_id,
+            tool_call_count,
+            max_tool_calls,
+        })
+        .unwrap(),
+    };
+    send_conversation_event_to_chat_windows(app_handle, conversation_id, event);
+
+    Some(ForcedAnswerGuard::enter(conversation_id))
+}
+
 fn build_tool_config(
     app_handle: &tauri::AppHandle,
     mcp_info: &crate::mcp::MCPInfoForAssistant,
@@ -256,6 +378,84 @@ pub async fn ask_ai(
     override_model_config: Option<HashMap<String, serde_json::Value>>,
     override_prompt: Option<String>,
     override_mcp_config: Option<McpOverrideConfig>,
+) -> Result<AiResponse, AppError> {
+    ask_ai_impl(
+        app_handle,
+        state,
+        acp_session_state,
+        feature_config_state,
+        message_token_manager,
+        activity_manager,
+        window,
+        request,
+        override_model_config,
+        override_prompt,
+        override_mcp_config,
+        false,
+    )
+    .await
+}
+
+/// 用户确认后继续发送此前被发送前确认拦截的请求
+#[tauri::command]
+#[instrument(skip(
+    app_handle,
+    state,
+    acp_session_state,
+    feature_config_state,
+    message_token_manager,
+    activity_manager,
+    window
+))]
+pub async fn confirm_and_send(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    acp_session_state: State<'_, AcpSessionState>,
+    feature_config_state: State<'_, FeatureConfigState>,
+    message_token_manager: State<'_, MessageTokenManager>,
+    activity_manager: State<'_, ConversationActivityManager>,
+    window: tauri::Window,
+    token: String,
+) -> Result<AiResponse, AppError> {
+    let pending = take_pending_send(&token).map_err(AppError::UnknownError)?;
+    info!(assistant_id = pending.request.assistant_id, "send confirmed");
+    ask_ai_impl(
+        app_handle,
+        state,
+        acp_session_state,
+        feature_config_state,
+        message_token_manager,
+        activity_manager,
+        window,
+        pending.request,
+        pending.override_model_config,
+        pending.override_prompt,
+        pending.override_mcp_config,
+        true,
+    )
+    .await
+}
+
+/// 用户取消发送时丢弃待确认的请求
+#[tauri::command]
+pub fn cancel_send_confirmation(token: String) -> bool {
+    discard_pending_send(&token)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn ask_ai_impl(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    acp_session_state: State<'_, AcpSessionState>,
+    feature_config_state: State<'_, FeatureConfigState>,
+    message_token_manager: State<'_, MessageTokenManager>,
+    activity_manager: State<'_, ConversationActivityManager>,
+    window: tauri::Window,
+    request: AiRequest,
+    override_model_config: Option<HashMap<String, serde_json::Value>>,
+    override_prompt: Option<String>,
+    override_mcp_config: Option<McpOverrideConfig>,
+    confirmed: bool,
 ) -> Result<AiResponse, AppError> {
     info!("Ask AI start");
     debug!(
@@ -285,17 +485,22 @@ pub async fn ask_ai(
     let mut template_context = HashMap::new();
 
     let selected_text = state.inner().selected_text.lock().await.clone();
-    template_context.insert("selected_text".to_string(), selected_text);
+    template_context.insert("selected_text".to_string(), selected_text.clone());
     if !processed_request.conversation_id.trim().is_empty() {
         template_context
             .insert("conversation_id".to_string(), processed_request.conversation_id.trim().to_string());
     }
 
     let app_handle_clone = app_handle.clone();
-    let assistant_detail = get_assistant(app_handle_clone, processed_request.assistant_id).unwrap();
+    let assistant_detail =
+        get_assistant(app_handle_clone, processed_request.assistant_id, Some(true)).unwrap();
     let assistant_prompt_origin = &assistant_detail.prompts[0].prompt;
     let assistant_prompt_result =
         template_engine.parse(&assistant_prompt_origin, &template_context).await;
+    let assistant_prompt_result = render_placeholders(
+        &assistant_prompt_result,
+        &prompt_placeholder_variables(&selected_text, &assistant_detail.prompt_params),
+    );
     debug!(
         assistant_prompt_result = assistant_prompt_result.as_str(),
         "assistant prompt after template"
@@ -352,11 +557,80 @@ pub async fn ask_ai(
     } else {
         assistant_prompt_result
     };
+    // 助手配置了回复语言时追加语言指令
+    let assistant_prompt_result =
+        apply_response_language(assistant_prompt_result, &assistant_detail.model_configs);
 
-    let _need_generate_title = processed_request.conversation_id.is_empty();
     let request_prompt_result =
         template_engine.parse(&processed_request.prompt, &template_context).await;
 
+    // 助手开启发送前确认时，先返回模型、预估用量与可用工具，确认后再创建消息并生成
+    if needs_send_confirmation(confirmed, &assistant_detail.model_configs) {
+        let existing_conversation_id =
+            processed_request.conversation_id.trim().parse::<i64>().unwrap_or(0);
+        let history_tokens: usize = if existing_conversation_id > 0 {
+            ConversationDatabase::new(&app_handle)
+                .map_err(AppError::from)
+                .and_then(|db| db.message_repo())
+                .and_then(|repo| {
+                    repo.list_by_conversation_id(existing_conversation_id).map_err(AppError::from)
+                })
+                .map(|rows| {
+                    let mut seen_ids = HashSet::new();
+                    rows.iter()
+                        .filter(|(message, _)| seen_ids.insert(message.id))
+                        .map(|(message, _)| estimate_tokens(&message.content))
+                        .sum()
+                })
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, "failed to load history for send confirmation");
+                    0
+                })
+        } else {
+            0
+        };
+        let model_name = match &processed_request.override_model_id {
+            Some(override_model_id) => {
+                override_model_id.split("%%").next().unwrap_or_default().to_string()
+            }
+            None => assistant_detail.model[0].model_code.clone(),
+        };
+        let estimated_input_tokens = estimate_tokens(&assistant_prompt_result)
+            + estimate_tokens(&request_prompt_result)
+            + history_tokens;
+        let token = register_pending_send(PendingSend {
+            request,
+            override_model_config,
+            override_prompt,
+            override_mcp_config,
+        });
+        let price = LLMDatabase::new(&app_handle)
+            .and_then(|db| db.get_model_price(&model_name))
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "failed to load model price for send confirmation");
+                None
+            });
+        let confirmation = build_send_confirmation(
+            token,
+            &assistant_detail,
+            model_name,
+            price.as_ref(),
+            &mcp_info.enabled_servers,
+            estimated_input_tokens,
+            processed_request.max_tokens,
+        );
+        info!(
+            estimated_input_tokens,
+            tools = confirmation.tools.len(),
+            "send confirmation required"
+        );
+        return Ok(AiResponse {
+            conversation_id: existing_conversation_id,
+            request_prompt_result_with_context: request_prompt_result,
+            confirmation: Some(confirmation),
+        });
+    }
+
     let app_handle_clone = app_handle.clone();
     let (
         conversation_id,
@@ -381,6 +655,21 @@ pub async fn ask_ai(
 
     // 总是启动流式处理，即使没有预先创建消息
     let _config_feature_map = feature_config_state.config_feature_map.lock().await.clone();
+    // 按配置的触发时机决定本轮回复后是否生成标题，新对话即第 1 轮
+    let _need_generate_title = {
+        let user_turn = if processed_request.conversation_id.is_empty() {
+            1
+        } else {
+            ConversationDatabase::new(&app_handle)
+                .map_err(|e| e.to_string())
+                .and_then(|db| count_conversation_user_turns(&db, conversation_id))
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, conversation_id, "failed to count user turns for title");
+                    0
+                })
+        };
+        TitleTriggerConfig::from_config(&_config_feature_map).should_generate(user_turn)
+    };
     let _request_prompt_result_with_context_clone = request_prompt_result_with_context.clone();
 
     let app_handle_clone = app_handle.clone();
@@ -447,14 +736,15 @@ pub async fn ask_ai(
             })
             .unwrap(),
         };
-        let _ = window.emit(format!("conversation_event_{}", conversation_id).as_str(), add_event);
+        emit_conversation_event(&window, conversation_id, add_event);
 
         // Clone prompt before moving into session dispatcher
         let prompt_clone = processed_request.prompt.clone();
 
         let session_handle = {
             let mut sessions = acp_session_state.sessions.lock().await;
-            if let Some(handle) = sessions.get(&conversation_id) {
+            // 会话任务已退出（如 Agent 进程崩溃）时丢弃旧句柄，重新启动并按持久化的会话信息恢复
+            if let Some(handle) = sessions.get(&conversation_id).filter(|h| !h.is_closed()) {
                 handle.clone()
             } else {
                 let (handle, join_handle) =
@@ -473,6 +763,7 @@ pub async fn ask_ai(
         return Ok(AiResponse {
             conversation_id,
             request_prompt_result_with_context: processed_request.prompt,
+            confirmation: None,
         });
     }
 
@@ -511,6 +802,7 @@ pub async fn ask_ai(
     let model_configs = model_detail.configs.clone(); // 提前获取模型配置
     let provider_api_type = model_detail.provider.api_type.clone(); // 提前获取API类型
     let assistant_model_configs = assistant_detail.model_configs.clone(); // 提前获取助手模型配置
+    let reveal_chars_per_second = reveal_speed_from_configs(&assistant_model_configs);
 
     info!(
         "ask_ai: provider_api_type={}, conversation_id={}, assistant_id={}",
@@ -665,6 +957,7 @@ pub async fn ask_ai(
                 model_code.clone(),        // 传递模型名称
                 override_mcp_config,       // MCP override配置
                 tool_name_mapping.clone(), // 工具名称映射表
+                reveal_chars_per_second,   // 流式输出展示速度
             )
             .await?;
         } else {
@@ -699,7 +992,7 @@ pub async fn ask_ai(
 
     info!("Ask AI end");
 
-    Ok(AiResponse { conversation_id, request_prompt_result_with_context })
+    Ok(AiResponse { conversation_id, request_prompt_result_with_context, confirmation: None })
 }
 
 #[instrument(skip(app_handle, feature_config_state, window, tool_result), fields(conversation_id = %conversation_id, assistant_id, tool_call_id))]
@@ -733,7 +1026,7 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
         .ok_or_else(|| AppError::DatabaseError("对话未找到".to_string()))?;
 
     // Get assistant details
-    let assistant_detail = get_assistant(app_handle.clone(), assistant_id).unwrap();
+    let assistant_detail = get_assistant(app_handle.clone(), assistant_id, Some(true)).unwrap();
     if assistant_detail.model.is_empty() {
         return Err(AppError::NoModelFound);
     }
@@ -787,8 +1080,7 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
             })
             .unwrap(),
         };
-        let _ =
-            window.emit(format!("conversation_event_{}", conversation_id_i64).as_str(), add_event);
+        emit_conversation_event(&window, conversation_id_i64, add_event);
         tool_result_message
     };
 
@@ -808,11 +1100,10 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
         })
         .unwrap(),
     };
-    let _ =
-        window.emit(format!("conversation_event_{}", conversation_id_i64).as_str(), update_event);
+    emit_conversation_event(&window, conversation_id_i64, update_event);
 
     // Get all existing messages
-    let all_messages = db.message_repo().unwrap().list_by_conversation_id(conversation_id_i64)?;
+    let all_messages = list_context_messages(&db, conversation_id_i64)?;
 
     // 使用 get_latest_branch_messages 获取最新分支的消息（正确过滤掉废弃分支）
     let latest_branch = crate::api::ai::summary::get_latest_branch_messages(&all_messages);
@@ -830,8 +1121,20 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
         .max_by_key(|msg| msg.id)
         .and_then(|m| m.generation_group_id.clone());
 
-    let init_message_list =
-        build_message_list_from_db(&all_messages, BranchSelection::LatestBranch);
+    let truncation =
+        load_context_truncation(&db, conversation_id_i64, &assistant_detail.model_configs);
+    let mut init_message_list = build_message_list_from_db_with_truncation(
+        &all_messages,
+        BranchSelection::LatestBranch,
+        truncation.as_ref(),
+    );
+    let forced_answer_guard = prepare_forced_final_answer(
+        &app_handle,
+        conversation_id_i64,
+        &latest_branch,
+        &assistant_detail.model_configs,
+        &mut init_message_list,
+    );
 
     // 收集 MCP 信息
     let mcp_info = collect_mcp_info_for_assistant(&app_handle, assistant_id, None, None).await?;
@@ -851,6 +1154,7 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
     let model_configs = model_detail.configs.clone();
     let provider_api_type = model_detail.provider.api_type.clone();
     let assistant_model_configs = assistant_detail.model_configs.clone();
+    let reveal_chars_per_second = reveal_speed_from_configs(&assistant_model_configs);
 
     // 获取配置
     let config_feature_map = feature_config_state.config_feature_map.lock().await.clone();
@@ -926,7 +1230,8 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
     let has_available_tools = is_native_toolcall
         && !mcp_info.enabled_servers.is_empty()
         && !force_non_native_for_gemini_toolresult
-        && !force_non_native_for_invalid_tool_args;
+        && !force_non_native_for_invalid_tool_args
+        && forced_answer_guard.is_none();
 
     // 同 ask_ai：避免 OpenAI 兼容通道 + Gemini 模型导致的 usage 反序列化报错日志
     let provider_api_type_lc = provider_api_type.to_lowercase();
@@ -972,6 +1277,13 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
     let ChatRequestBuildResult { chat_request, tool_name_mapping } =
         build_chat_request_from_messages(&init_message_list, tool_call_strategy, tool_config);
 
+    // 标题包含工具结果时，触发轮次的标题推迟到工具结果返回后的回复生成
+    let need_generate_title = count_conversation_user_turns(&conversation_db, conversation_id_i64)
+        .map(|user_turn| {
+            TitleTriggerConfig::from_config(&config_feature_map).should_generate_after_tool_results(user_turn)
+        })
+        .unwrap_or(false);
+
     if chat_config.stream {
         ai_handle_stream_chat(
             &chat_config.client,
@@ -982,15 +1294,16 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
             &conversation_db,
             &window_clone,
             &app_handle,
-            false,                             // no title generation needed
+            need_generate_title,
             String::new(),                     // no user prompt
-            HashMap::new(),                    // no feature config needed
+            config_feature_map.clone(),
             reuse_generation_group_id.clone(), // 复用上一条assistant响应的generation_group_id
             None,                              // no parent_group_id
             model_id,
             model_code.clone(),
             None,                      // no MCP override config
             tool_name_mapping.clone(), // 工具名称映射表
+            reveal_chars_per_second,
         )
         .await?;
     } else {
@@ -1003,9 +1316,9 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
             &conversation_db,
             &window_clone,
             &app_handle,
-            false,                             // no title generation needed
+            need_generate_title,
             String::new(),                     // no user prompt
-            HashMap::new(),                    // no feature config needed
+            config_feature_map.clone(),
             reuse_generation_group_id.clone(), // 复用上一条assistant响应的generation_group_id
             None,                              // no parent_group_id
             model_id,
@@ -1021,6 +1334,7 @@ pub(crate) async fn tool_result_continue_ask_ai_impl(
     Ok(AiResponse {
         conversation_id: conversation_id_i64,
         request_prompt_result_with_context: format!("Tool result: {}", tool_result),
+        confirmation: None,
     })
 }
 
@@ -1050,13 +1364,13 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
         .ok_or_else(|| AppError::DatabaseError("对话未找到".to_string()))?;
 
     // Get assistant details
-    let assistant_detail = get_assistant(app_handle.clone(), assistant_id).unwrap();
+    let assistant_detail = get_assistant(app_handle.clone(), assistant_id, Some(true)).unwrap();
     if assistant_detail.model.is_empty() {
         return Err(AppError::NoModelFound);
     }
 
     // Get all existing messages (including the just-created tool_result messages)
-    let all_messages = db.message_repo().unwrap().list_by_conversation_id(conversation_id)?;
+    let all_messages = list_context_messages(&db, conversation_id)?;
 
     // 使用 get_latest_branch_messages 获取最新分支的消息（正确过滤掉废弃分支）
     let latest_branch = crate::api::ai::summary::get_latest_branch_messages(&all_messages);
@@ -1075,8 +1389,19 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
             .and_then(|m| m.generation_group_id.clone())
     };
 
-    let init_message_list =
-        build_message_list_from_db(&all_messages, BranchSelection::LatestBranch);
+    let truncation = load_context_truncation(&db, conversation_id, &assistant_detail.model_configs);
+    let mut init_message_list = build_message_list_from_db_with_truncation(
+        &all_messages,
+        BranchSelection::LatestBranch,
+        truncation.as_ref(),
+    );
+    let forced_answer_guard = prepare_forced_final_answer(
+        &app_handle,
+        conversation_id,
+        &latest_branch,
+        &assistant_detail.model_configs,
+        &mut init_message_list,
+    );
 
     // 收集 MCP 信息
     let mcp_info = collect_mcp_info_for_assistant(&app_handle, assistant_id, None, None).await?;
@@ -1096,6 +1421,7 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
     let model_configs = model_detail.configs.clone();
     let provider_api_type = model_detail.provider.api_type.clone();
     let assistant_model_configs = assistant_detail.model_configs.clone();
+    let reveal_chars_per_second = reveal_speed_from_configs(&assistant_model_configs);
 
     // 获取配置
     let config_feature_map = feature_config_state.config_feature_map.lock().await.clone();
@@ -1171,7 +1497,8 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
     let has_available_tools = is_native_toolcall
         && !mcp_info.enabled_servers.is_empty()
         && !force_non_native_for_gemini_toolresult
-        && !force_non_native_for_invalid_tool_args;
+        && !force_non_native_for_invalid_tool_args
+        && forced_answer_guard.is_none();
 
     let provider_api_type_lc = provider_api_type.to_lowercase();
     let model_code_lc = model_code.to_lowercase();
@@ -1206,6 +1533,13 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
     let ChatRequestBuildResult { chat_request, tool_name_mapping } =
         build_chat_request_from_messages(&init_message_list, tool_call_strategy, tool_config);
 
+    // 标题包含工具结果时，触发轮次的标题推迟到工具结果返回后的回复生成
+    let need_generate_title = count_conversation_user_turns(&conversation_db, conversation_id)
+        .map(|user_turn| {
+            TitleTriggerConfig::from_config(&config_feature_map).should_generate_after_tool_results(user_turn)
+        })
+        .unwrap_or(false);
+
     if chat_config.stream {
         Box::pin(ai_handle_stream_chat(
             &chat_config.client,
@@ -1216,15 +1550,16 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
             &conversation_db,
             &window_clone,
             &app_handle,
-            false,
+            need_generate_title,
             String::new(),
-            HashMap::new(),
+            config_feature_map.clone(),
             reuse_generation_group_id.clone(),
             None,
             model_id,
             model_code.clone(),
             None,
             tool_name_mapping.clone(),
+            reveal_chars_per_second,
         ))
         .await?;
     } else {
@@ -1237,9 +1572,9 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
             &conversation_db,
             &window_clone,
             &app_handle,
-            false,
+            need_generate_title,
             String::new(),
-            HashMap::new(),
+            config_feature_map.clone(),
             reuse_generation_group_id,
             None,
             model_id,
@@ -1255,6 +1590,7 @@ pub(crate) async fn batch_tool_result_continue_ask_ai_impl(
     Ok(AiResponse {
         conversation_id,
         request_prompt_result_with_context: "Batch tool results sent".to_string(),
+        confirmation: None,
     })
 }
 
@@ -1328,6 +1664,52 @@ pub async fn cancel_ai(
     Ok(())
 }
 
+/// 构建重新生成的初始消息列表
+///
+/// - 用户消息重发：包含当前用户消息和之前的所有消息
+/// - AI 消息重新生成：仅保留在待重新生成消息之前的历史消息
+/// - 复用工具结果：被重新生成的回复发起过工具调用时，回填已存储的结果而不是重新执行
+pub(crate) fn build_regenerate_message_list(
+    db: &ConversationDatabase,
+    mcp_db: &MCPDatabase,
+    message: &Message,
+    mode: RegenerateMode,
+    parent_group_id: Option<&str>,
+    truncation: Option<&ContextTruncation>,
+) -> Result<Vec<(String, String, Vec<MessageAttachment>)>, AppError> {
+    let messages = list_context_messages(db, message.conversation_id)?;
+    let filtered_messages: Vec<(Message, Option<MessageAttachment>)> =
+        if message.message_type == "user" {
+            messages.into_iter().filter(|m| m.0.id <= message.id).collect()
+        } else {
+            messages.into_iter().filter(|m| m.0.id < message.id).collect()
+        };
+    let filtered_messages = filter_messages_for_parent_group(filtered_messages, parent_group_id);
+
+    let mut init_message_list = build_message_list_from_db_with_truncation(
+        &filtered_messages,
+        BranchSelection::LatestBranch,
+        truncation,
+    );
+
+    if mode == RegenerateMode::ReuseToolResults {
+        let tool_calls =
+            mcp_db.get_mcp_tool_calls_by_message(message.id).map_err(AppError::from)?;
+        if !tool_calls.is_empty() {
+            let reused_turn =
+                build_reused_tool_turn(message, &tool_calls).map_err(AppError::UnknownError)?;
+            info!(
+                message_id = message.id,
+                reused_tool_calls = tool_calls.len(),
+                "regenerating with reused tool results"
+            );
+            init_message_list.extend(reused_turn);
+        }
+    }
+
+    Ok(init_message_list)
+}
+
 #[tauri::command]
 #[instrument(
     skip(app_handle, feature_config_state, activity_manager, message_token_manager, window),
@@ -1340,14 +1722,19 @@ pub async fn regenerate_ai(
     message_token_manager: State<'_, MessageTokenManager>,
     window: tauri::Window,
     message_id: i64,
+    mode: Option<String>,
 ) -> Result<AiResponse, AppError> {
     info!("Regenerate AI start");
+    let regenerate_mode = RegenerateMode::from_value(mode.as_deref());
     let db = ConversationDatabase::new(&app_handle).map_err(AppError::from)?;
     let message = db
         .message_repo()
         .unwrap()
         .read(message_id)?
         .ok_or(AppError::DatabaseError("未找到消息".to_string()))?;
+    if regenerate_mode == RegenerateMode::ReuseToolResults && message.message_type == "user" {
+        return Err(AppError::UnknownError("仅支持重新生成 AI 回复时复用工具结果".to_string()));
+    }
 
     let conversation_id = message.conversation_id;
     let conversation = db
@@ -1355,7 +1742,6 @@ pub async fn regenerate_ai(
         .unwrap()
         .read(conversation_id)?
         .ok_or(AppError::DatabaseError("未找到对话".to_string()))?;
-    let messages = db.message_repo().unwrap().list_by_conversation_id(conversation_id)?;
 
     // 重新生成开始时，优先让被点击的消息闪亮（可被后续 streaming 覆盖）
     if message.message_type == "user" {
@@ -1366,21 +1752,6 @@ pub async fn regenerate_ai(
 
     message_token_manager.reset_cancel_token(conversation_id).await;
 
-    // 根据消息类型决定处理逻辑
-    let (filtered_messages, _parent_message_id) = if message.message_type == "user" {
-        // 用户消息重发：包含当前用户消息和之前的所有消息，新生成的assistant消息没有parent（新一轮对话）
-        let filtered_messages: Vec<(Message, Option<MessageAttachment>)> = messages
-            .into_iter()
-            .filter(|m| m.0.id <= message_id) // 包含当前消息
-            .collect();
-        (filtered_messages, None) // 用户消息重发时，新的AI回复没有parent_id
-    } else {
-        // AI消息重新生成：仅保留在待重新生成消息之前的历史消息，新消息以被重发的原消息为parent
-        let filtered_messages: Vec<(Message, Option<MessageAttachment>)> =
-            messages.into_iter().filter(|m| m.0.id < message_id).collect();
-        (filtered_messages, Some(message_id)) // 使用被重发消息的ID作为parent_id表示这是它的一个版本
-    };
-
     // 确定要使用的generation_group_id和parent_group_id
     let (regenerate_generation_group_id, regenerate_parent_group_id) = if message.message_type
         == "user"
@@ -1414,22 +1785,27 @@ pub async fn regenerate_ai(
         (Some(uuid::Uuid::new_v4().to_string()), original_group_id)
     };
 
-    let filtered_messages =
-        filter_messages_for_parent_group(filtered_messages, regenerate_parent_group_id.as_deref());
-
-    let init_message_list =
-        build_message_list_from_db(&filtered_messages, BranchSelection::LatestBranch);
-
-    debug!(?init_message_list, "initial message list for regenerate");
-
-    // 获取助手信息（在构建消息列表之后，以确保对话已确定）
+    // 获取助手信息
     let assistant_id = conversation.assistant_id.unwrap();
-    let assistant_detail = get_assistant(app_handle.clone(), assistant_id).unwrap();
+    let assistant_detail = get_assistant(app_handle.clone(), assistant_id, Some(true)).unwrap();
 
     if assistant_detail.model.is_empty() {
         return Err(AppError::NoModelFound);
     }
 
+    let truncation = load_context_truncation(&db, conversation_id, &assistant_detail.model_configs);
+    let mcp_db = MCPDatabase::new(&app_handle).map_err(AppError::from)?;
+    let init_message_list = build_regenerate_message_list(
+        &db,
+        &mcp_db,
+        &message,
+        regenerate_mode,
+        regenerate_parent_group_id.as_deref(),
+        truncation.as_ref(),
+    )?;
+
+    debug!(?init_message_list, "initial message list for regenerate");
+
     // 兼容 MCP：根据助手配置判断是否使用提供商原生 toolcall
     let mcp_info =
         crate::mcp::collect_mcp_info_for_assistant(&app_handle, assistant_id, None, None).await?;
@@ -1450,10 +1826,15 @@ pub async fn regenerate_ai(
     let regenerate_model_configs = model_detail.configs.clone(); // 提前获取模型配置
     let regenerate_provider_api_type = model_detail.provider.api_type.clone(); // 提前获取API类型
     let regenerate_assistant_model_configs = assistant_detail.model_configs.clone(); // 提前获取助手模型配置
+    let reveal_chars_per_second = reveal_speed_from_configs(&regenerate_assistant_model_configs);
 
     // 获取网络配置
     let _config_feature_map = feature_config_state.config_feature_map.lock().await.clone();
     let regenerate_task_handle = tokio::spawn(async move {
+        // 复用工具结果时整轮禁用工具，输出中的工具调用也不会被执行
+        let _forced_answer_guard = (!regenerate_mode.allows_tool_calls())
+            .then(|| ForcedAnswerGuard::enter(conversation_id));
+
         // 直接创建数据库连接（避免线程安全问题）
         let conversation_db = ConversationDatabase::new(&app_handle_clone).unwrap();
 
@@ -1535,7 +1916,8 @@ pub async fn regenerate_ai(
         // 动态判断是否有可用的工具
         let has_available_tools = is_native_toolcall
             && !mcp_info.enabled_servers.is_empty()
-            && !force_non_native_for_invalid_tool_args;
+            && !force_non_native_for_invalid_tool_args
+            && regenerate_mode.allows_tool_calls();
 
         // 同 ask_ai：避免 OpenAI 兼容通道 + Gemini 模型导致的 usage 反序列化报错日志
         let provider_api_type_lc = regenerate_provider_api_type.to_lowercase();
@@ -1611,6 +1993,7 @@ pub async fn regenerate_ai(
                 regenerate_model_code.clone(),          // 传递模型名称
                 None,                                   // regenerate 不使用 MCP override
                 tool_name_mapping.clone(),              // 工具名称映射表
+                reveal_chars_per_second,                // 流式输出展示速度
             )
             .await?;
         } else {
@@ -1645,7 +2028,11 @@ pub async fn regenerate_ai(
 
     info!("Regenerate AI dispatched (background task started)");
 
-    Ok(AiResponse { conversation_id, request_prompt_result_with_context: String::new() })
+    Ok(AiResponse {
+        conversation_id,
+        request_prompt_result_with_context: String::new(),
+        confirmation: None,
+    })
 }
 
 pub(crate) fn add_message(
@@ -1709,6 +2096,8 @@ async fn initialize_conversation(
 {
     // 返回值：(conversation_id, add_message_id, user_message_id, request_prompt_with_context, init_message_list)
     let db = ConversationDatabase::new(app_handle).map_err(AppError::from)?;
+    // 刚添加的截图可能仍在识别，等待 OCR 完成（有超时），否则识别出的文字不会进入本次提问
+    wait_for_pending_ocr(&request.attachment_list.clone().unwrap_or_default()).await;
 
     let (
         conversation_id,
@@ -1721,19 +2110,12 @@ async fn initialize_conversation(
             .attachment_repo()
             .unwrap()
             .list_by_id(&request.attachment_list.clone().unwrap_or(vec![]))?;
+        let ocr_texts = db
+            .attachment_repo()
+            .unwrap()
+            .list_ocr_texts(&request.attachment_list.clone().unwrap_or(vec![]))?;
         // 新对话逻辑
-        let text_attachments: Vec<String> = message_attachment_list
-            .iter()
-            .filter(|a| matches!(a.attachment_type, AttachmentType::Text))
-            .filter_map(|a| {
-                Some(format!(
-                    r#"<fileattachment name="{}">{}</fileattachment>"#,
-                    a.attachment_url.clone().unwrap(),
-                    a.attachment_content.clone().unwrap().as_str()
-                ))
-            })
-            .collect();
-        let context = text_attachments.join("\n");
+        let context = build_attachment_context(&message_attachment_list, &ocr_texts);
         let request_prompt_result_with_context = format!("{}\n{}", request_prompt_result, context);
         let init_message_list = vec![
             (String::from("system"), override_prompt.unwrap_or(assistant_prompt_result), vec![]),
@@ -1768,30 +2150,46 @@ async fn initialize_conversation(
     } else {
         // 已存在对话逻辑
         let conversation_id = request.conversation_id.parse::<i64>()?;
-        let all_messages = db.message_repo().unwrap().list_by_conversation_id(conversation_id)?;
-
-        let message_list = build_message_list_from_db(&all_messages, BranchSelection::LatestBranch);
+        let all_messages = list_context_messages(&db, conversation_id)?;
 
         // 获取到消息的附件列表
         let message_attachment_list = db
             .attachment_repo()
             .unwrap()
             .list_by_id(&request.attachment_list.clone().unwrap_or(vec![]))?;
-        // 过滤出文本附件
-        let text_attachments: Vec<String> = message_attachment_list
-            .iter()
-            .filter(|a| matches!(a.attachment_type, AttachmentType::Text))
-            .filter_map(|a| {
-                Some(format!(
-                    r#"<fileattachment name="{}">{}</fileattachment>"#,
-                    a.attachment_url.clone().unwrap(),
-                    a.attachment_content.clone().unwrap().as_str()
-                ))
-            })
-            .collect();
-        let context = text_attachments.join("\n");
+        let ocr_texts = db
+            .attachment_repo()
+            .unwrap()
+            .list_ocr_texts(&request.attachment_list.clone().unwrap_or(vec![]))?;
+        // 文本附件内容与图片 OCR 文字作为附加上下文
+        let context = build_attachment_context(&message_attachment_list, &ocr_texts);
+        // 对话中预览产生的新错误一并附加，便于助手据此修正代码
+        let context = match take_preview_console_context(app_handle, conversation_id) {
+            Some(console) => format!("{}\n{}", context, console),
+            None => context,
+        };
 
         let request_prompt_result_with_context = format!("{}\n{}", request_prompt_result, context);
+
+        // 指定了上下文消息时只使用这些消息，仅对本轮生效
+        let message_list = match request.context_message_ids.as_deref() {
+            Some(ids) if !ids.is_empty() => build_message_list_from_context_ids(&all_messages, ids),
+            _ => {
+                let mut truncation =
+                    load_context_truncation(&db, conversation_id, &assistant_detail.model_configs);
+                // 当前提问尚未写入历史，按估算 token 计入裁剪预算
+                if let Some(token_trim) =
+                    truncation.as_mut().and_then(|truncation| truncation.token_trim.as_mut())
+                {
+                    token_trim.pending_tokens = estimate_tokens(&request_prompt_result_with_context);
+                }
+                build_message_list_from_db_with_truncation(
+                    &all_messages,
+                    BranchSelection::LatestBranch,
+                    truncation.as_ref(),
+                )
+            }
+        };
         // 添加用户消息
         let user_message = add_message(
             app_handle,
@@ -1826,8 +2224,7 @@ async fn initialize_conversation(
             .unwrap(),
         };
 
-        let _ =
-            app_handle.emit(format!("conversation_event_{}", conversation_id).as_str(), add_event);
+        emit_conversation_event(app_handle, conversation_id, add_event);
 
         let update_event = ConversationEvent {
             r#type: "message_update".to_string(),
@@ -1844,8 +2241,7 @@ async fn initialize_conversation(
             })
             .unwrap(),
         };
-        let _ = app_handle
-            .emit(format!("conversation_event_{}", conversation_id).as_str(), update_event);
+        emit_conversation_event(app_handle, conversation_id, update_event);
 
         let mut updated_message_list = message_list;
         updated_message_list.push((
@@ -1898,6 +2294,21 @@ pub async fn get_conversation_runtime_state(
     Ok(activity_manager.get_runtime_state(conversation_id).await)
 }
 
+/// 获取指定对话当前生成的模型、重试次数与输出进度，未在生成时返回 None
+#[tauri::command]
+pub async fn get_active_generation_status(
+    app_handle: tauri::AppHandle,
+    conversation_id: i64,
+) -> Result<Option<ActiveGenerationStatus>, String> {
+    let Some(mut status) = generation_status::get_active_generation(conversation_id) else {
+        return Ok(None);
+    };
+    let llm_db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    status.provider_name =
+        llm_db.get_llm_model_detail_by_id(&status.model_id).ok().map(|detail| detail.provider.name);
+    Ok(Some(status))
+}
+
 /// 重新生成对话标题
 #[tauri::command]
 pub async fn regenerate_conversation_title(
@@ -1948,3 +2359,225 @@ pub async fn regenerate_conversation_title(
 
     Ok(())
 }
+
+/// 在临时对话中执行 `run`，无论成功与否执行完毕后都会调用 `cleanup` 删除该对话
+pub async fn run_in_ephemeral_conversation<T, F, Fut>(
+    create: impl FnOnce() -> Result<i64, AppError>,
+    cleanup: impl FnOnce(i64) -> Result<(), AppError>,
+    run: F,
+) -> Result<T, AppError>
+where
+    F: FnOnce(i64) -> Fut,
+    Fut: std::future::Future<Output = Result<T, AppError>>,
+{
+    let conversation_id = create()?;
+    let result = run(conversation_id).await;
+    if let Err(e) = cleanup(conversation_id) {
+        warn!(conversation_id, error = %e, "failed to clean up ephemeral conversation");
+    }
+    result
+}
+
+/// 从非原生工具调用（XML 提示词模式）的回复内容中解析工具调用，仅用于展示
+fn parse_prompt_tool_calls(content: &str) -> Vec<AssistantTestToolCall> {
+    static MCP_TOOL_CALL_REGEX: OnceLock<regex::Regex> = OnceLock::new();
+    MCP_TOOL_CALL_REGEX
+        .get_or_init(|| {
+            regex::Regex::new(r"<mcp_tool_call>\s*<server_name>([^<]*)</server_name>\s*<tool_name>([^<]*)</tool_name>\s*<parameters>([\s\S]*?)</parameters>\s*</mcp_tool_call>").unwrap()
+        })
+        .captures_iter(content)
+        .map(|cap| AssistantTestToolCall {
+            server_name: cap[1].trim().to_string(),
+            tool_name: cap[2].trim().to_string(),
+            parameters: cap[3].trim().to_string(),
+        })
+        .collect()
+}
+
+/// 使用示例提示词对助手进行一次完整的冒烟测试
+///
+/// 走完整的系统提示词组装（模板、MCP、Skills）与工具注入流程，在临时对话中执行一次非流式生成，
+/// 结束后删除临时对话。模型发起的工具调用只会被记录返回，不会执行。
+#[tauri::command]
+#[instrument(skip(app_handle, feature_config_state, sample_prompt))]
+pub async fn test_assistant(
+    app_handle: tauri::AppHandle,
+    feature_config_state: State<'_, FeatureConfigState>,
+    assistant_id: i64,
+    sample_prompt: String,
+) -> Result<AssistantTestResult, AppError> {
+    let assistant_detail = get_assistant(app_handle.clone(), assistant_id, Some(true))
+        .map_err(|e| AppError::UnknownError(format!("Failed to get assistant: {}", e)))?;
+    if assistant_detail.assistant.assistant_type == Some(4) {
+        return Err(AppError::UnknownError("ACP 助手不支持冒烟测试".to_string()));
+    }
+    if assistant_detail.model.is_empty() {
+        return Err(AppError::NoModelFound);
+    }
+    let Some(assistant_prompt) = assistant_detail.prompts.first().map(|p| p.prompt.clone()) else {
+        return Err(AppError::UnknownError("助手未配置提示词".to_string()));
+    };
+    let config_feature_map = feature_config_state.config_feature_map.lock().await.clone();
+    let conversation_db = ConversationDatabase::new(&app_handle).map_err(AppError::from)?;
+    let conversation_name = format!("[assistant test] {}", assistant_detail.assistant.name);
+
+    run_in_ephemeral_conversation(
+        || {
+            let conversation = conversation_db.conversation_repo()?.create(
+                &crate::db::conversation_db::Conversation {
+                    id: 0,
+                    name: conversation_name,
+                    assistant_id: Some(assistant_id),
+                    created_time: chrono::Utc::now(),
+                },
+            )?;
+            Ok(conversation.id)
+        },
+        |conversation_id| {
+            conversation_db.conversation_repo()?.delete(conversation_id)?;
+            Ok(())
+        },
+        |conversation_id| async move {
+            let template_engine = build_template_engine(&app_handle).map_err(|e| {
+                AppError::UnknownError(format!("Failed to build template engine: {}", e))
+            })?;
+            let mut template_context = HashMap::new();
+            template_context.insert("conversation_id".to_string(), conversation_id.to_string());
+
+            let assistant_prompt =
+                template_engine.parse(&assistant_prompt, &template_context).await;
+            let assistant_prompt = render_placeholders(
+                &assistant_prompt,
+                &prompt_placeholder_variables("", &assistant_detail.prompt_params),
+            );
+            let mcp_info =
+                collect_mcp_info_for_assistant(&app_handle, assistant_id, None, None).await?;
+            let assistant_prompt = if !mcp_info.enabled_servers.is_empty()
+                && (mcp_info.dynamic_loading_enabled || !mcp_info.use_native_toolcall)
+            {
+                format_mcp_prompt(assistant_prompt, &mcp_info).await
+            } else {
+                assistant_prompt
+            };
+            let skills_info = collect_skills_info_for_assistant(&app_handle, assistant_id).await?;
+            let assistant_prompt = if !skills_info.enabled_skills.is_empty() {
+                format_skills_prompt(&app_handle, assistant_prompt, &skills_info).await
+            } else {
+                assistant_prompt
+            };
+            let assistant_prompt =
+                apply_response_language(assistant_prompt, &assistant_detail.model_configs);
+            let user_prompt = template_engine.parse(&sample_prompt, &template_context).await;
+
+            let llm_db = LLMDatabase::new(&app_handle).map_err(AppError::from)?;
+            let model_detail = llm_db
+                .get_llm_model_detail(
+                    &assistant_detail.model[0].provider_id,
+                    &assistant_detail.model[0].model_code,
+                )
+                .context("Failed to get LLM model detail")?;
+            let model_config = ConfigBuilder::merge_model_configs(
+                assistant_detail.model_configs.clone(),
+                &model_detail,
+                None,
+            );
+            let config_map = model_config
+                .iter()
+                .filter_map(|config| {
+                    config.value.as_ref().map(|value| (config.name.clone(), value.clone()))
+                })
+                .collect::<HashMap<String, String>>();
+            let model_name =
+                config_map.get("model").cloned().unwrap_or_else(|| model_detail.model.code.clone());
+            let proxy_enabled = model_detail
+                .configs
+                .iter()
+                .find(|config| config.name == "proxy_enabled")
+                .and_then(|config| config.value.parse::<bool>().ok())
+                .unwrap_or(false);
+            let network_proxy = get_network_proxy_from_config(&config_feature_map);
+            let client = genai_client::create_client_with_config(
+                &model_detail.configs,
+                &model_detail.model.code,
+                &model_detail.provider.api_type,
+                network_proxy.as_deref(),
+                proxy_enabled,
+                Some(get_request_timeout_from_config(&config_feature_map)),
+                false,
+                &config_feature_map,
+            )?;
+
+            let has_available_tools =
+                mcp_info.use_native_toolcall && !mcp_info.enabled_servers.is_empty();
+            let chat_options = ConfigBuilder::build_chat_options(&config_map)
+                .with_normalize_reasoning_content(true)
+                .with_capture_usage(true)
+                .with_capture_tool_calls(has_available_tools);
+            let tool_call_strategy = if has_available_tools {
+                ToolCallStrategy::Native
+            } else {
+                ToolCallStrategy::NonNative
+            };
+            let tool_config = build_tool_config(
+                &app_handle,
+                &mcp_info,
+                has_available_tools,
+                Some(conversation_id),
+            );
+            let ChatRequestBuildResult { chat_request, tool_name_mapping } =
+                build_chat_request_from_messages(
+                    &[
+                        ("system".to_string(), assistant_prompt, Vec::new()),
+                        ("user".to_string(), user_prompt, Vec::new()),
+                    ],
+                    tool_call_strategy,
+                    tool_config,
+                );
+
+            let start = std::time::Instant::now();
+            let chat_response = client
+                .exec_chat(&model_name, chat_request, Some(&chat_options))
+                .await
+                .map_err(|e| AppError::ProviderError(e.to_string()))?;
+            let duration_ms = start.elapsed().as_millis() as i64;
+
+            let content = chat_response.first_text().unwrap_or("").to_string();
+            let mut tool_calls: Vec<AssistantTestToolCall> = chat_response
+                .tool_calls()
+                .into_iter()
+                .map(|tc| {
+                    let (server_name, tool_name) =
+                        resolve_tool_name(&tc.fn_name, &tool_name_mapping);
+                    AssistantTestToolCall {
+                        server_name,
+                        tool_name,
+                        parameters: tc.fn_arguments.to_string(),
+                    }
+                })
+                .collect();
+            tool_calls.extend(parse_prompt_tool_calls(&content));
+
+            let usage = &chat_response.usage;
+            let input_token_count = usage.prompt_tokens.unwrap_or(0);
+            let output_token_count = usage.completion_tokens.unwrap_or(0);
+            info!(
+                assistant_id,
+                duration_ms,
+                tool_calls = tool_calls.len(),
+                "assistant smoke test finished"
+            );
+
+            Ok(AssistantTestResult {
+                assistant_id,
+                model_code: model_detail.model.code.clone(),
+                content,
+                tool_calls,
+                input_token_count,
+                output_token_count,
+                token_count: usage.total_tokens.unwrap_or(input_token_count + output_token_count),
+                duration_ms,
+            })
+        },
+    )
+    .await
+}
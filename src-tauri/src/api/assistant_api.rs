@@ -1,4 +1,5 @@
 use crate::{
+    api::ai::render_mode::{render_mode_from_configs, RENDER_MODE_MARKDOWN},
     db::{
         assistant_db::{
             Assistant, AssistantDatabase, AssistantMCPConfig, AssistantMCPToolConfig,
@@ -6,6 +7,13 @@ use crate::{
         },
         conversation_db::ConversationDatabase,
         llm_db::LLMDatabase,
+        mcp_db::MCPDatabase,
+        skill_db::SkillDatabase,
+    },
+    mcp::tool_defaults::validate_default_arguments,
+    template_engine::{
+        build_template_engine,
+        lint::{lint_prompt, PromptLintIssue},
     },
     utils::share_utils::{
         compress_assistant_data, decompress_assistant_data, AssistantShareData, ModelConfigShare,
@@ -13,6 +21,7 @@ use crate::{
     },
     NameCacheState,
 };
+use std::collections::HashMap;
 use tauri::Emitter;
 use tracing::{debug, info, instrument, warn};
 
@@ -25,6 +34,19 @@ pub struct AssistantDetail {
     pub prompt_params: Vec<AssistantPromptParam>,
     pub mcp_configs: Vec<AssistantMCPConfig>,
     pub mcp_tool_configs: Vec<AssistantMCPToolConfig>,
+    /// 回复渲染模式（markdown / plaintext / code），由 model_configs 中的 `response_render_mode` 派生，保存时忽略
+    #[serde(default = "default_render_mode")]
+    pub render_mode: String,
+    /// 对话开场建议（未解析模板变量），通过 `update_assistant_starters` 编辑，保存助手时忽略
+    #[serde(default)]
+    pub starters: Vec<String>,
+    /// 继承的基础助手，通过 `set_assistant_base` 设置，保存助手时忽略
+    #[serde(default)]
+    pub base_assistant_id: Option<i64>,
+}
+
+fn default_render_mode() -> String {
+    RENDER_MODE_MARKDOWN.to_string()
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -62,14 +84,30 @@ pub fn get_assistants(app_handle: tauri::AppHandle) -> Result<Vec<Assistant>, St
     assistant_db.get_assistants().map(|assistants| assistants.into()).map_err(|e| e.to_string())
 }
 
+/// 获取助手配置
+///
+/// `resolve_inheritance` 为 true 时返回合并了基础助手后的生效配置（组装请求时使用），
+/// 否则返回助手自身保存的配置（编辑助手时使用）
 #[tauri::command]
 #[instrument(skip(app_handle), fields(assistant_id))]
 pub fn get_assistant(
     app_handle: tauri::AppHandle,
     assistant_id: i64,
+    resolve_inheritance: Option<bool>,
 ) -> Result<AssistantDetail, String> {
     let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    if resolve_inheritance.unwrap_or(false) {
+        resolve_assistant_detail(&assistant_db, assistant_id)
+    } else {
+        load_assistant_detail(&assistant_db, assistant_id)
+    }
+}
 
+/// 读取助手自身保存的配置，不合并基础助手
+pub fn load_assistant_detail(
+    assistant_db: &AssistantDatabase,
+    assistant_id: i64,
+) -> Result<AssistantDetail, String> {
     // 获取 Assistant 基本信息
     let assistant = assistant_db.get_assistant(assistant_id).map_err(|e| e.to_string())?;
     info!(name = ?assistant.name, id = assistant.id, "loaded assistant");
@@ -102,20 +140,124 @@ pub fn get_assistant(
         assistant_db.get_assistant_mcp_tool_configs(assistant_id).map_err(|e| e.to_string())?;
     debug!(mcp_tool_config_count = mcp_tool_configs.len(), "assistant mcp tool configs loaded");
 
+    let starters = assistant_db.get_assistant_starters(assistant_id).map_err(|e| e.to_string())?;
+    let base_assistant_id =
+        assistant_db.get_base_assistant_id(assistant_id).map_err(|e| e.to_string())?;
+
     // 构建 AssistantDetail 对象
     let assistant_detail = AssistantDetail {
         assistant,
         prompts,
         model,
+        render_mode: render_mode_from_configs(&model_configs).to_string(),
         model_configs,
         prompt_params,
         mcp_configs,
         mcp_tool_configs,
+        starters,
+        base_assistant_id,
     };
 
     Ok(assistant_detail)
 }
 
+/// 读取助手的生效配置：从最顶层的基础助手开始，逐级把子助手的配置合并上去
+pub fn resolve_assistant_detail(
+    assistant_db: &AssistantDatabase,
+    assistant_id: i64,
+) -> Result<AssistantDetail, String> {
+    let chain = assistant_db.get_inheritance_chain(assistant_id).map_err(|e| e.to_string())?;
+    let mut details = Vec::with_capacity(chain.len());
+    for id in &chain {
+        details.push(load_assistant_detail(assistant_db, *id)?);
+    }
+    if chain.len() > 1 {
+        debug!(?chain, "resolving assistant inheritance");
+    }
+
+    // chain 中子助手在前，反向折叠
+    let mut details = details.into_iter().rev();
+    let root = details.next().ok_or_else(|| format!("助手 {} 不存在", assistant_id))?;
+    Ok(details.fold(root, merge_assistant_detail))
+}
+
+/// 将子助手的配置合并到基础助手上
+///
+/// - 提示词：基础助手的提示词在前，子助手的提示词追加在后
+/// - 模型：子助手选择了模型时覆盖，否则沿用基础助手的模型
+/// - 模型配置、提示词参数、MCP 服务器与工具配置：按名称/ID 合并，子助手的同名项覆盖基础助手（空值视为未设置）
+/// - 工具默认参数：子助手的工具配置未设置默认参数时沿用基础助手的
+/// - 开场建议：子助手有则覆盖
+pub fn merge_assistant_detail(base: AssistantDetail, child: AssistantDetail) -> AssistantDetail {
+    let prompts = merge_prompts(base.prompts, child.prompts);
+
+    let model =
+        if child.model.iter().any(|m| !m.model_code.is_empty()) { child.model } else { base.model };
+
+    let mut model_configs = base.model_configs;
+    for config in child.model_configs {
+        if config.value.as_deref().is_some_and(|v| !v.is_empty()) {
+            model_configs.retain(|c| c.name != config.name);
+            model_configs.push(config);
+        }
+    }
+
+    let mut prompt_params = base.prompt_params;
+    for param in child.prompt_params {
+        prompt_params.retain(|p| p.param_name != param.param_name);
+        prompt_params.push(param);
+    }
+
+    let mut mcp_configs = base.mcp_configs;
+    for config in child.mcp_configs {
+        mcp_configs.retain(|c| c.mcp_server_id != config.mcp_server_id);
+        mcp_configs.push(config);
+    }
+
+    let mut mcp_tool_configs = base.mcp_tool_configs;
+    for mut config in child.mcp_tool_configs {
+        if let Some(index) =
+            mcp_tool_configs.iter().position(|c| c.mcp_tool_id == config.mcp_tool_id)
+        {
+            let base_config = mcp_tool_configs.remove(index);
+            if !config.default_arguments.as_deref().is_some_and(|v| !v.trim().is_empty()) {
+                config.default_arguments = base_config.default_arguments;
+            }
+        }
+        mcp_tool_configs.push(config);
+    }
+
+    let starters = if child.starters.is_empty() { base.starters } else { child.starters };
+
+    AssistantDetail {
+        assistant: child.assistant,
+        prompts,
+        model,
+        render_mode: render_mode_from_configs(&model_configs).to_string(),
+        model_configs,
+        prompt_params,
+        mcp_configs,
+        mcp_tool_configs,
+        starters,
+        base_assistant_id: child.base_assistant_id,
+    }
+}
+
+/// 合并提示词：双方都有内容时拼接到子助手的提示词上，否则取有内容的一方
+fn merge_prompts(base: Vec<AssistantPrompt>, child: Vec<AssistantPrompt>) -> Vec<AssistantPrompt> {
+    let has_content =
+        |prompts: &[AssistantPrompt]| prompts.first().is_some_and(|p| !p.prompt.trim().is_empty());
+    match (has_content(&base[..]), has_content(&child[..])) {
+        (true, true) => {
+            let mut prompts = child;
+            prompts[0].prompt = format!("{}\n\n{}", base[0].prompt.trim_end(), prompts[0].prompt);
+            prompts
+        }
+        (true, false) => base,
+        _ => child,
+    }
+}
+
 #[tauri::command]
 #[instrument(skip(app_handle, name_cache_state, assistant_detail), fields(assistant_id = assistant_detail.assistant.id))]
 pub async fn save_assistant(
@@ -349,10 +491,13 @@ pub fn add_assistant(
         assistant,
         prompts,
         model,
+        render_mode: render_mode_from_configs(&model_configs).to_string(),
         model_configs,
         prompt_params,
         mcp_configs: Vec::new(),
         mcp_tool_configs: Vec::new(),
+        starters: Vec::new(),
+        base_assistant_id: None,
     };
 
     // 广播助手列表更新事件
@@ -446,6 +591,17 @@ pub fn copy_assistant(
         }
     }
 
+    // Copy starters
+    let starters = assistant_db.get_assistant_starters(assistant_id).map_err(|e| e.to_string())?;
+    assistant_db.set_assistant_starters(new_assistant_id, &starters).map_err(|e| e.to_string())?;
+
+    // 副本沿用原助手的基础助手
+    let base_assistant_id =
+        assistant_db.get_base_assistant_id(assistant_id).map_err(|e| e.to_string())?;
+    if base_assistant_id.is_some() {
+        assistant_db.set_base_assistant(new_assistant_id, base_assistant_id)?;
+    }
+
     // Get the newly created assistant
     let new_assistant = assistant_db.get_assistant(new_assistant_id).map_err(|e| e.to_string())?;
 
@@ -454,10 +610,13 @@ pub fn copy_assistant(
         assistant: new_assistant,
         prompts: new_prompts,
         model: new_models,
+        render_mode: render_mode_from_configs(&new_model_configs).to_string(),
         model_configs: new_model_configs,
         prompt_params: Vec::new(), // Assuming prompt_params are not copied
         mcp_configs: Vec::new(),
         mcp_tool_configs: Vec::new(),
+        starters,
+        base_assistant_id,
     };
 
     info!(new_assistant_id, "assistant copied");
@@ -476,6 +635,14 @@ pub fn delete_assistant(app_handle: tauri::AppHandle, assistant_id: i64) -> Resu
     if assistant_id == 1 {
         return Err("快速使用助手不能删除".to_string());
     }
+    let child_ids =
+        assistant_db.get_child_assistant_ids(assistant_id).map_err(|e| e.to_string())?;
+    if !child_ids.is_empty() {
+        return Err(format!(
+            "该助手是 {} 个助手的基础助手，请先解除它们的继承关系后再删除",
+            child_ids.len()
+        ));
+    }
 
     let _ = assistant_db
         .delete_assistant_model_config_by_assistant_id(assistant_id)
@@ -486,6 +653,8 @@ pub fn delete_assistant(app_handle: tauri::AppHandle, assistant_id: i64) -> Resu
     let _ = assistant_db
         .delete_assistant_prompt_param_by_assistant_id(assistant_id)
         .map_err(|e| e.to_string());
+    let _ = assistant_db.set_assistant_starters(assistant_id, &[]).map_err(|e| e.to_string());
+    let _ = assistant_db.set_base_assistant(assistant_id, None);
 
     let conversation_db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     let _ = conversation_db
@@ -510,24 +679,21 @@ pub fn get_assistant_field_value(
     field_name: &str,
 ) -> Result<String, String> {
     let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    // 读取生效配置，继承自基础助手的字段同样可以取到
+    let detail = resolve_assistant_detail(&assistant_db, assistant_id)?;
 
     if field_name == "prompt" {
-        // Get prompts for this assistant
-        let prompts = assistant_db.get_assistant_prompt(assistant_id).map_err(|e| e.to_string())?;
-
-        debug!(prompt_count = prompts.len(), "prompts fetched for assistant");
+        debug!(prompt_count = detail.prompts.len(), "prompts fetched for assistant");
 
         // Return first prompt's content
-        return prompts
+        return detail
+            .prompts
             .first()
             .map(|p| p.prompt.clone())
             .ok_or_else(|| "No prompt found".to_string());
     }
 
-    // Get all model configs for this assistant
-    let configs =
-        assistant_db.get_assistant_model_configs(assistant_id).map_err(|e| e.to_string())?;
-
+    let configs = detail.model_configs;
     debug!(config_count = configs.len(), "model configs fetched for assistant");
 
     // Find config with matching name
@@ -599,6 +765,99 @@ pub async fn update_assistant_mcp_tool_config(
         .map_err(|e| e.to_string())
 }
 
+/// 保存助手对某个工具的默认参数，保存前按工具的参数 schema 校验；传空表示清空
+#[tauri::command]
+#[instrument(skip(app_handle, default_arguments), fields(assistant_id, mcp_tool_id))]
+pub async fn update_assistant_mcp_tool_default_arguments(
+    app_handle: tauri::AppHandle,
+    assistant_id: i64,
+    mcp_tool_id: i64,
+    default_arguments: Option<String>,
+) -> Result<(), String> {
+    let mcp_db = MCPDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let tool = mcp_db.get_mcp_server_tool(mcp_tool_id).map_err(|e| e.to_string())?;
+    let normalized = validate_default_arguments(
+        tool.parameters.as_deref(),
+        default_arguments.as_deref().unwrap_or_default(),
+    )?;
+
+    let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    assistant_db
+        .set_assistant_mcp_tool_default_arguments(assistant_id, mcp_tool_id, normalized.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// 获取助手的对话开场建议，模板变量（如 `!current_date`、`!assistant_name`）在此时解析
+#[tauri::command]
+#[instrument(skip(app_handle), fields(assistant_id))]
+pub async fn get_assistant_starters(
+    app_handle: tauri::AppHandle,
+    assistant_id: i64,
+) -> Result<Vec<String>, String> {
+    let (starters, assistant) = {
+        let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        let starters =
+            assistant_db.get_assistant_starters(assistant_id).map_err(|e| e.to_string())?;
+        if starters.is_empty() {
+            return Ok(starters);
+        }
+        let assistant = assistant_db.get_assistant(assistant_id).map_err(|e| e.to_string())?;
+        (starters, assistant)
+    };
+
+    let template_engine = build_template_engine(&app_handle)?;
+    let template_context = HashMap::from([("assistant_name".to_string(), assistant.name)]);
+    let mut resolved = Vec::with_capacity(starters.len());
+    for starter in starters {
+        resolved.push(template_engine.parse(&starter, &template_context).await);
+    }
+    Ok(resolved)
+}
+
+/// 检查助手系统提示词：未定义的模板命令与参数、过长提示词、可能矛盾的指令
+#[tauri::command]
+#[instrument(skip(app_handle, prompt, params), fields(prompt_len = prompt.len()))]
+pub fn lint_assistant_prompt(
+    app_handle: tauri::AppHandle,
+    prompt: String,
+    params: Vec<String>,
+) -> Result<Vec<PromptLintIssue>, String> {
+    let template_engine = build_template_engine(&app_handle)?;
+    Ok(lint_prompt(&template_engine, &prompt, &params))
+}
+
+/// 整体替换助手的对话开场建议
+#[tauri::command]
+#[instrument(skip(app_handle, starters), fields(assistant_id, count = starters.len()))]
+pub fn update_assistant_starters(
+    app_handle: tauri::AppHandle,
+    assistant_id: i64,
+    starters: Vec<String>,
+) -> Result<(), String> {
+    let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    assistant_db.set_assistant_starters(assistant_id, &starters).map_err(|e| e.to_string())
+}
+
+/// 设置或清除助手继承的基础助手，会形成循环继承时拒绝
+#[tauri::command]
+#[instrument(skip(app_handle), fields(assistant_id, base_assistant_id))]
+pub fn set_assistant_base(
+    app_handle: tauri::AppHandle,
+    assistant_id: i64,
+    base_assistant_id: Option<i64>,
+) -> Result<(), String> {
+    let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    if let Some(base_id) = base_assistant_id {
+        assistant_db.get_assistant(base_id).map_err(|_| format!("基础助手 {} 不存在", base_id))?;
+    }
+    assistant_db.set_base_assistant(assistant_id, base_assistant_id)?;
+    info!(assistant_id, ?base_assistant_id, "assistant base updated");
+
+    // 广播助手列表更新事件
+    let _ = app_handle.emit("assistant_list_changed", ());
+    Ok(())
+}
+
 #[tauri::command]
 #[instrument(skip(app_handle), fields(assistant_id))]
 pub async fn get_assistant_mcp_servers_with_tools(
@@ -606,8 +865,17 @@ pub async fn get_assistant_mcp_servers_with_tools(
     assistant_id: i64,
 ) -> Result<Vec<MCPServerWithTools>, String> {
     let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let detail = resolve_assistant_detail(&assistant_db, assistant_id)?;
+    mcp_servers_with_tools_for_detail(&assistant_db, &detail)
+}
+
+/// 按助手详情中的 MCP 配置列出服务器与工具；传入生效配置时包含继承自基础助手的启用状态
+pub fn mcp_servers_with_tools_for_detail(
+    assistant_db: &AssistantDatabase,
+    detail: &AssistantDetail,
+) -> Result<Vec<MCPServerWithTools>, String> {
     let servers_data = assistant_db
-        .get_assistant_mcp_servers_with_tools(assistant_id)
+        .get_mcp_servers_with_tools_for_configs(&detail.mcp_configs, &detail.mcp_tool_configs)
         .map_err(|e| e.to_string())?;
 
     let servers = servers_data
@@ -738,7 +1006,7 @@ pub async fn export_assistant(
     app_handle: tauri::AppHandle,
     assistant_id: i64,
 ) -> Result<String, String> {
-    let assistant_detail = get_assistant(app_handle, assistant_id)?;
+    let assistant_detail = get_assistant(app_handle, assistant_id, None)?;
 
     // Convert to share format (exclude model information)
     let share_data = AssistantShareData {
@@ -822,5 +1090,112 @@ pub async fn import_assistant(
     let _ = app_handle.emit("assistant_list_changed", ());
 
     // Return the created assistant detail
-    get_assistant(app_handle, new_assistant_id)
+    get_assistant(app_handle, new_assistant_id, None)
+}
+
+// Assistant Templates
+
+/// 内置助手模板，数据来自 `assets/assistant_templates.json`，新增模板只需修改该文件
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct AssistantTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub assistant_type: i64,
+    pub prompt: String,
+    #[serde(default)]
+    pub model_configs: Vec<ModelConfigShare>,
+    /// 推荐启用的 MCP 服务器，按 command 匹配（如 `aipp:search`）
+    #[serde(default)]
+    pub mcp_servers: Vec<String>,
+    /// 推荐启用的技能 identifier
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub starters: Vec<String>,
+}
+
+pub fn builtin_assistant_templates() -> Result<Vec<AssistantTemplate>, String> {
+    serde_json::from_str(include_str!("../../assets/assistant_templates.json"))
+        .map_err(|e| format!("Invalid assistant templates: {}", e))
+}
+
+#[tauri::command]
+pub fn list_assistant_templates() -> Result<Vec<AssistantTemplate>, String> {
+    builtin_assistant_templates()
+}
+
+/// 按模板创建助手：写入提示词、模型配置和开场建议，并启用模板推荐的 MCP 服务器与技能。
+/// 模型留空由用户自行选择；推荐的 MCP 服务器或技能不存在时跳过
+#[tauri::command]
+#[instrument(skip(app_handle), fields(template_id = %template_id))]
+pub async fn create_assistant_from_template(
+    app_handle: tauri::AppHandle,
+    template_id: String,
+) -> Result<AssistantDetail, String> {
+    let template = builtin_assistant_templates()?
+        .into_iter()
+        .find(|template| template.id == template_id)
+        .ok_or_else(|| format!("Assistant template not found: {}", template_id))?;
+
+    let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let assistant_id = assistant_db
+        .add_assistant(&template.name, &template.description, Some(template.assistant_type), false)
+        .map_err(|e| e.to_string())?;
+    assistant_db.add_assistant_prompt(assistant_id, &template.prompt).map_err(|e| e.to_string())?;
+
+    let model_id =
+        assistant_db.add_assistant_model(assistant_id, 0, "", "").map_err(|e| e.to_string())?;
+    for config in &template.model_configs {
+        assistant_db
+            .add_assistant_model_config(
+                assistant_id,
+                model_id,
+                &config.name,
+                &config.value,
+                &config.value_type,
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    if !template.starters.is_empty() {
+        assistant_db
+            .set_assistant_starters(assistant_id, &template.starters)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if !template.mcp_servers.is_empty() {
+        let mcp_db = MCPDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        let servers = mcp_db.get_mcp_servers().map_err(|e| e.to_string())?;
+        for command in &template.mcp_servers {
+            let Some(server) =
+                servers.iter().find(|server| server.command.as_deref() == Some(command.as_str()))
+            else {
+                warn!(command = %command, "recommended MCP server not found, skipping");
+                continue;
+            };
+            assistant_db
+                .upsert_assistant_mcp_config(assistant_id, server.id, true)
+                .map_err(|e| e.to_string())?;
+            for tool in mcp_db.get_mcp_server_tools(server.id).map_err(|e| e.to_string())? {
+                assistant_db
+                    .upsert_assistant_mcp_tool_config(assistant_id, tool.id, true, tool.is_auto_run)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    if !template.skills.is_empty() {
+        let skill_db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        for (priority, identifier) in template.skills.iter().enumerate() {
+            skill_db
+                .upsert_assistant_skill_config(assistant_id, identifier, true, priority as i32)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    info!(assistant_id, "assistant created from template");
+    let _ = app_handle.emit("assistant_list_changed", ());
+
+    get_assistant(app_handle, assistant_id, None)
 }
@@ -1,19 +1,39 @@
-use crate::db::conversation_db::{AttachmentType, Repository};
+use crate::api::ai::conversation::parse_data_url;
+use crate::db::conversation_db::{
+    AttachmentType, Repository, ATTACHMENT_OCR_STATUS_DONE, ATTACHMENT_OCR_STATUS_FAILED,
+};
+use crate::db::system_db::FeatureConfig;
+use crate::FeatureConfigState;
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use mime_guess::from_path;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::Manager;
 use tauri_plugin_opener::OpenerExt;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
 
 use crate::{
     db::conversation_db::{ConversationDatabase, MessageAttachment},
     errors::AppError,
 };
-use tracing::{debug, info, warn};
+use tracing::{debug, info, instrument, warn};
+
+const OCR_FEATURE_CODE: &str = "attachment_ocr";
+const DEFAULT_OCR_LANGUAGES: &str = "eng";
+const OCR_TIMEOUT: Duration = Duration::from_secs(60);
+/// 发送消息时等待仍在识别的附件的最长时间，超时后不带 OCR 文字继续
+const OCR_PROMPT_WAIT_TIMEOUT: Duration = Duration::from_secs(20);
+/// PDF 附件转为文本后保留的最大字符数，超出部分截断并附说明
+pub const PDF_ATTACHMENT_MAX_CHARS: usize = 200_000;
 
 #[derive(Serialize)]
 pub struct AttachmentResult {
@@ -68,6 +88,8 @@ pub async fn add_attachment_by_url(
         file_type_classify = "text".to_string();
     } else if file_type.starts_with("image/") {
         file_type_classify = "image".to_string();
+    } else if file_type == "application/pdf" {
+        file_type_classify = "pdf".to_string();
     }
     debug!(file_type_classify, "file type classify");
 
@@ -94,6 +116,7 @@ pub async fn add_attachment_by_url(
             file.read_to_string(&mut content)?;
             content
         }
+        "pdf" => extract_pdf_attachment_text_blocking(std::fs::read(&file_path)?).await?,
         _ => return Err(AppError::Anyhow(anyhow!("Unsupported file type").to_string())),
     };
 
@@ -123,11 +146,12 @@ pub async fn add_attachment_by_url(
                             message_id: -1,
                             attachment_type: AttachmentType::Image,
                             attachment_url: Some(file_url),
-                            attachment_content: Some(reader),
+                            attachment_content: Some(reader.clone()),
                             attachment_hash: Some(hash_str),
                             use_vector: false,
                             token_count: Some(0),
                         })?;
+                    spawn_attachment_ocr(app_handle.clone(), message_attachment.id, reader);
                     message_attachment.id
                 }
                 "text" => {
@@ -145,6 +169,20 @@ pub async fn add_attachment_by_url(
                         })?;
                     message_attachment.id
                 }
+                "pdf" => {
+                    let message_attachment =
+                        db.attachment_repo().unwrap().create(&MessageAttachment {
+                            id: 0,
+                            message_id: -1,
+                            attachment_type: AttachmentType::PDF,
+                            attachment_url: Some(file_url),
+                            attachment_content: Some(reader),
+                            attachment_hash: Some(hash_str),
+                            use_vector: false,
+                            token_count: Some(0),
+                        })?;
+                    message_attachment.id
+                }
                 _ => return Err(AppError::Anyhow(anyhow!("Unsupported file type").to_string())),
             };
 
@@ -163,6 +201,15 @@ pub async fn add_attachment_content(
     info!(file_name, "add_attachment_content called");
     let db = ConversationDatabase::new(&app_handle).map_err(AppError::from)?;
 
+    // 前端以 data URL 传入的 PDF 先转为文本，保存的是可直接放入提示词的内容
+    let file_content = match parse_data_url(&file_content) {
+        Some((_, b64)) if attachment_type == AttachmentType::PDF as i64 => {
+            let bytes = STANDARD.decode(b64).map_err(|e| AppError::Anyhow(e.to_string()))?;
+            extract_pdf_attachment_text_blocking(bytes).await?
+        }
+        _ => file_content,
+    };
+
     let mut hasher = Sha256::new();
     hasher.update(file_content.clone());
     let hash_str = hex::encode(hasher.finalize());
@@ -182,12 +229,13 @@ pub async fn add_attachment_content(
             return Ok(AttachmentResult { attachment_id: attachment.id });
         }
         None => {
+            let attachment_type = AttachmentType::try_from(attachment_type).unwrap();
             let message_attachment = db.attachment_repo().unwrap().create(&MessageAttachment {
                 id: 0,
                 message_id: -1,
-                attachment_type: AttachmentType::try_from(attachment_type).unwrap(),
+                attachment_type,
                 attachment_url: Some(file_name),
-                attachment_content: Some(file_content),
+                attachment_content: Some(file_content.clone()),
                 attachment_hash: Some(hash_str),
                 use_vector: false,
                 token_count: Some(0),
@@ -196,6 +244,9 @@ pub async fn add_attachment_content(
                 Ok(t) => t.id,
                 Err(e) => return Err(AppError::from(e)),
             };
+            if attachment_type == AttachmentType::Image {
+                spawn_attachment_ocr(app_handle.clone(), attachment_id, file_content);
+            }
             Ok(AttachmentResult { attachment_id })
         }
     }
@@ -229,3 +280,242 @@ fn read_image_as_base64(file_path: &str) -> Result<String> {
     let base64_string = STANDARD.encode(&buffer);
     Ok(base64_string)
 }
+
+/// 图片附件 OCR 配置，来自 `attachment_ocr` 功能配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrSettings {
+    pub enabled: bool,
+    /// 传给 tesseract `-l` 的语言，如 `eng+chi_sim`
+    pub languages: String,
+    /// tesseract 可执行文件路径，未配置时从 PATH 查找
+    pub tesseract_path: Option<String>,
+}
+
+impl OcrSettings {
+    pub fn from_config(
+        config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+    ) -> Self {
+        let ocr_config = config_feature_map.get(OCR_FEATURE_CODE);
+        let value_of = |key: &str| {
+            ocr_config
+                .and_then(|config| config.get(key))
+                .map(|config| config.value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        OcrSettings {
+            enabled: value_of("ocr_enabled").is_some_and(|v| v == "true" || v == "1"),
+            languages: value_of("ocr_languages")
+                .unwrap_or_else(|| DEFAULT_OCR_LANGUAGES.to_string()),
+            tesseract_path: value_of("tesseract_path"),
+        }
+    }
+}
+
+async fn load_ocr_settings(app_handle: &tauri::AppHandle) -> Option<OcrSettings> {
+    let feature_state = app_handle.try_state::<FeatureConfigState>()?;
+    let config_map = feature_state.config_feature_map.lock().await;
+    Some(OcrSettings::from_config(&config_map))
+}
+
+/// 调用 tesseract CLI 识别图片中的文字，图片通过 stdin 传入
+#[instrument(level = "debug", skip(settings, image), fields(image_size = image.len()), err)]
+pub async fn run_tesseract(settings: &OcrSettings, image: Vec<u8>) -> Result<String, String> {
+    let program = match &settings.tesseract_path {
+        Some(path) => path.into(),
+        None => which::which("tesseract")
+            .map_err(|_| "未找到 tesseract，请安装后重试或在 OCR 配置中指定路径".to_string())?,
+    };
+
+    let mut child = tokio::process::Command::new(&program)
+        .args(["stdin", "stdout", "-l", settings.languages.as_str()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("无法启动 tesseract ({}): {}", program.display(), e))?;
+
+    let mut stdin = child.stdin.take().ok_or_else(|| "无法写入 tesseract 输入".to_string())?;
+    let writer = tokio::spawn(async move {
+        let _ = stdin.write_all(&image).await;
+    });
+    let output = tokio::time::timeout(OCR_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("tesseract 识别超时（{} 秒）", OCR_TIMEOUT.as_secs()))?
+        .map_err(|e| e.to_string())?;
+    let _ = writer.await;
+
+    if !output.status.success() {
+        return Err(format!(
+            "tesseract 识别失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 正在识别的附件：识别任务持有 Sender，结束（无论成功与否）时随之 drop，等待方的 `changed()` 随即返回
+static PENDING_OCR: OnceLock<Mutex<HashMap<i64, watch::Receiver<()>>>> = OnceLock::new();
+
+fn pending_ocr() -> &'static Mutex<HashMap<i64, watch::Receiver<()>>> {
+    PENDING_OCR.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 识别任务结束时从等待列表中移除，并通过 drop Sender 通知等待方
+pub(crate) struct PendingOcrGuard {
+    attachment_id: i64,
+    _done: watch::Sender<()>,
+}
+
+impl PendingOcrGuard {
+    pub(crate) fn register(attachment_id: i64) -> Self {
+        let (done, receiver) = watch::channel(());
+        pending_ocr().lock().unwrap().insert(attachment_id, receiver);
+        Self { attachment_id, _done: done }
+    }
+}
+
+impl Drop for PendingOcrGuard {
+    fn drop(&mut self) {
+        pending_ocr().lock().unwrap().remove(&self.attachment_id);
+    }
+}
+
+/// 等待附件中仍在识别的图片完成 OCR，最多等待 `OCR_PROMPT_WAIT_TIMEOUT`
+pub async fn wait_for_pending_ocr(attachment_ids: &[i64]) {
+    let receivers: Vec<watch::Receiver<()>> = {
+        let pending = pending_ocr().lock().unwrap();
+        attachment_ids.iter().filter_map(|id| pending.get(id).cloned()).collect()
+    };
+    if receivers.is_empty() {
+        return;
+    }
+
+    let pending_count = receivers.len();
+    let wait = futures::future::join_all(receivers.into_iter().map(|mut receiver| async move {
+        // Sender 被 drop 时返回错误，表示识别已结束
+        let _ = receiver.changed().await;
+    }));
+    match tokio::time::timeout(OCR_PROMPT_WAIT_TIMEOUT, wait).await {
+        Ok(_) => debug!(pending_count, "Waited for pending attachment OCR"),
+        Err(_) => warn!(
+            pending_count,
+            timeout_secs = OCR_PROMPT_WAIT_TIMEOUT.as_secs(),
+            "Attachment OCR still running, sending without OCR text"
+        ),
+    }
+}
+
+/// 附件创建后在后台识别图片文字；识别失败只记录到 OCR 结果中，不影响附件本身
+fn spawn_attachment_ocr(app_handle: tauri::AppHandle, attachment_id: i64, image_content: String) {
+    // 在启动任务前登记，保证紧接着发送的消息能等到识别结果
+    let guard = PendingOcrGuard::register(attachment_id);
+    tauri::async_runtime::spawn(async move {
+        let _guard = guard;
+        let Some(settings) = load_ocr_settings(&app_handle).await.filter(|s| s.enabled) else {
+            return;
+        };
+        let image = parse_data_url(&image_content)
+            .and_then(|(_, b64)| STANDARD.decode(b64).ok())
+            .ok_or_else(|| "无法解析图片数据".to_string());
+        let result = match image {
+            Ok(image) => run_tesseract(&settings, image).await,
+            Err(e) => Err(e),
+        };
+
+        let save = ConversationDatabase::new(&app_handle)
+            .map_err(AppError::from)
+            .and_then(|db| db.attachment_repo())
+            .and_then(|repo| {
+                match &result {
+                    Ok(text) => repo.save_ocr_result(
+                        attachment_id,
+                        ATTACHMENT_OCR_STATUS_DONE,
+                        Some(text),
+                        None,
+                    ),
+                    Err(e) => repo.save_ocr_result(
+                        attachment_id,
+                        ATTACHMENT_OCR_STATUS_FAILED,
+                        None,
+                        Some(e),
+                    ),
+                }
+                .map_err(AppError::from)
+            });
+        match (&result, save) {
+            (_, Err(e)) => warn!(attachment_id, error = %e, "Failed to save attachment OCR result"),
+            (Ok(text), Ok(_)) => {
+                info!(attachment_id, text_len = text.len(), "Attachment OCR finished")
+            }
+            (Err(e), Ok(_)) => warn!(attachment_id, error = %e, "Attachment OCR failed"),
+        }
+    });
+}
+
+/// 把文本附件内容与图片附件的 OCR 文字拼接为用户消息的附加上下文
+pub fn build_attachment_context(
+    attachments: &[MessageAttachment],
+    ocr_texts: &HashMap<i64, String>,
+) -> String {
+    attachments
+        .iter()
+        .filter_map(|a| match a.attachment_type {
+            AttachmentType::Text => Some(format!(
+                r#"<fileattachment name="{}">{}</fileattachment>"#,
+                a.attachment_url.clone().unwrap_or_default(),
+                a.attachment_content.clone().unwrap_or_default()
+            )),
+            AttachmentType::Image => ocr_texts.get(&a.id).map(|text| {
+                format!(
+                    r#"<imageattachment name="{}" source="ocr">{}</imageattachment>"#,
+                    a.attachment_url.clone().unwrap_or_default(),
+                    text
+                )
+            }),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 把 PDF 各页文本拼接为附件内容：每页前加页码标记，超过 `max_chars` 时截断并注明截断位置
+pub fn format_pdf_attachment_text(pages: &[String], max_chars: usize) -> String {
+    let mut text = String::new();
+    let mut used = 0usize;
+    for (index, page) in pages.iter().enumerate() {
+        let separator = if index == 0 { "" } else { "\n\n" };
+        let section = format!("{}[第 {} 页]\n{}", separator, index + 1, page.trim());
+        let section_chars = section.chars().count();
+        if used + section_chars > max_chars {
+            text.extend(section.chars().take(max_chars - used));
+            text.push_str(&format!(
+                "\n\n[PDF 内容过长，已在第 {} 页处截断（共 {} 页）]",
+                index + 1,
+                pages.len()
+            ));
+            return text;
+        }
+        text.push_str(&section);
+        used += section_chars;
+    }
+    text
+}
+
+/// 从 PDF 字节中提取带页码标记的文本；没有可提取的文字（如扫描件）时返回说明
+pub fn extract_pdf_attachment_text(bytes: &[u8]) -> Result<String, String> {
+    let pages = pdf_extract::extract_text_from_mem_by_pages(bytes)
+        .map_err(|e| format!("无法解析 PDF: {}", e))?;
+    if pages.iter().all(|page| page.trim().is_empty()) {
+        return Ok(format!("[PDF 共 {} 页，未能提取到文字，可能是扫描件]", pages.len()));
+    }
+    Ok(format_pdf_attachment_text(&pages, PDF_ATTACHMENT_MAX_CHARS))
+}
+
+/// 在阻塞线程中解析 PDF，解析器在异常文件上 panic 时也只返回错误
+async fn extract_pdf_attachment_text_blocking(bytes: Vec<u8>) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || extract_pdf_attachment_text(&bytes))
+        .await
+        .map_err(|e| AppError::Anyhow(format!("解析 PDF 失败: {}", e)))?
+        .map_err(AppError::Anyhow)
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Manager;
+use tracing::{debug, info, instrument, warn};
+
+use crate::db::encryption::{open_connection, ENCRYPTION_STATE_FILE};
+use crate::db::get_db_dir;
+use crate::db::system_db::FeatureConfig;
+use crate::FeatureConfigState;
+
+const BACKUP_FEATURE_CODE: &str = "data_backup";
+const BACKUP_DIR_PREFIX: &str = "aipp-backup-";
+const BACKUP_NAME_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+const DEFAULT_BACKUP_INTERVAL_HOURS: u64 = 24;
+const DEFAULT_BACKUP_KEEP_COUNT: usize = 7;
+
+/// 自动备份配置，来自 `data_backup` 功能配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval: Duration,
+    pub keep_count: usize,
+    pub directory: PathBuf,
+}
+
+impl BackupSettings {
+    /// 读取备份配置，未配置目录时使用 `default_directory`，保留份数至少为 1
+    pub fn from_config(
+        config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+        default_directory: PathBuf,
+    ) -> Self {
+        let backup_config = config_feature_map.get(BACKUP_FEATURE_CODE);
+        let value_of = |key: &str| {
+            backup_config
+                .and_then(|config| config.get(key))
+                .map(|config| config.value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let interval_hours = value_of("backup_interval_hours")
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_BACKUP_INTERVAL_HOURS);
+        BackupSettings {
+            enabled: value_of("backup_enabled").is_some_and(|v| v == "true" || v == "1"),
+            interval: Duration::from_secs(interval_hours * 3600),
+            keep_count: value_of("backup_keep_count")
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(DEFAULT_BACKUP_KEEP_COUNT)
+                .max(1),
+            directory: value_of("backup_directory").map(PathBuf::from).unwrap_or(default_directory),
+        }
+    }
+}
+
+/// 一次备份的信息
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: String,
+    pub created_at: DateTime<Local>,
+    pub size_bytes: u64,
+    pub file_count: usize,
+}
+
+/// 将 db 目录下的所有 SQLite 数据库快照到 `backup_root` 下的新目录
+///
+/// 使用 `VACUUM INTO` 生成一致的快照，数据库正在写入时也不会得到半截文件；
+/// 已加密的数据库以相同密钥写出，同时复制加密状态文件以便恢复。
+#[instrument(level = "info", skip_all, fields(backup_root = %backup_root.display()), err)]
+pub fn create_backup(
+    db_dir: &Path,
+    backup_root: &Path,
+    now: DateTime<Local>,
+) -> Result<BackupInfo, String> {
+    let name = format!("{}{}", BACKUP_DIR_PREFIX, now.format(BACKUP_NAME_TIME_FORMAT));
+    let backup_dir = backup_root.join(&name);
+    if backup_dir.exists() {
+        return Err(format!("备份目录已存在: {}", backup_dir.display()));
+    }
+    fs::create_dir_all(&backup_dir).map_err(|e| format!("无法创建备份目录: {}", e))?;
+
+    let result = snapshot_databases(db_dir, &backup_dir);
+    if let Err(e) = result {
+        // 半成品备份会干扰轮转与恢复，失败时整体删除
+        let _ = fs::remove_dir_all(&backup_dir);
+        return Err(e);
+    }
+
+    let info = read_backup_info(&backup_dir)
+        .ok_or_else(|| format!("无法读取备份信息: {}", backup_dir.display()))?;
+    info!(name = %info.name, file_count = info.file_count, size_bytes = info.size_bytes, "Backup created");
+    Ok(info)
+}
+
+fn snapshot_databases(db_dir: &Path, backup_dir: &Path) -> Result<(), String> {
+    let entries = fs::read_dir(db_dir).map_err(|e| e.to_string())?;
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let target = backup_dir.join(file_name);
+        if path.extension().is_some_and(|ext| ext == "db") && path.is_file() {
+            let conn = open_connection(&path)
+                .map_err(|e| format!("无法打开数据库 {}: {}", path.display(), e))?;
+            conn.execute("VACUUM INTO ?1", [target.to_string_lossy()])
+                .map_err(|e| format!("备份数据库 {} 失败: {}", path.display(), e))?;
+            debug!(path = %path.display(), "Database snapshot written");
+        } else if file_name == ENCRYPTION_STATE_FILE {
+            fs::copy(&path, &target).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// 列出 `backup_root` 下的备份，按时间从新到旧排序
+pub fn list_backups_in(backup_root: &Path) -> Result<Vec<BackupInfo>, String> {
+    if !backup_root.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(backup_root).map_err(|e| e.to_string())?;
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .filter_map(|path| read_backup_info(&path))
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+/// 只保留最新的 `keep_count` 份备份，返回被删除的备份名称
+pub fn rotate_backups(backup_root: &Path, keep_count: usize) -> Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+    for backup in list_backups_in(backup_root)?.into_iter().skip(keep_count) {
+        match fs::remove_dir_all(&backup.path) {
+            Ok(_) => removed.push(backup.name),
+            Err(e) => warn!(name = %backup.name, error = %e, "Failed to remove old backup"),
+        }
+    }
+    Ok(removed)
+}
+
+fn read_backup_info(path: &Path) -> Option<BackupInfo> {
+    let name = path.file_name()?.to_str()?.to_string();
+    let timestamp = name.strip_prefix(BACKUP_DIR_PREFIX)?;
+    let naive = NaiveDateTime::parse_from_str(timestamp, BACKUP_NAME_TIME_FORMAT).ok()?;
+    let created_at = Local.from_local_datetime(&naive).earliest()?;
+
+    let files: Vec<fs::Metadata> = fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .collect();
+    Some(BackupInfo {
+        name,
+        path: path.to_string_lossy().to_string(),
+        created_at,
+        size_bytes: files.iter().map(|metadata| metadata.len()).sum(),
+        file_count: files.len(),
+    })
+}
+
+/// 距离最近一次备份已超过备份间隔时需要执行自动备份
+pub fn is_backup_due(
+    latest_backup: Option<DateTime<Local>>,
+    interval: Duration,
+    now: DateTime<Local>,
+) -> bool {
+    match latest_backup {
+        Some(latest) => match chrono::Duration::from_std(interval) {
+            Ok(interval) => now - latest >= interval,
+            Err(_) => false,
+        },
+        None => true,
+    }
+}
+
+pub async fn load_backup_settings(app_handle: &tauri::AppHandle) -> Result<BackupSettings, String> {
+    let default_directory =
+        app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join("backups");
+    let feature_state = app_handle
+        .try_state::<FeatureConfigState>()
+        .ok_or_else(|| "无法获取功能配置状态".to_string())?;
+    let config_map = feature_state.config_feature_map.lock().await;
+    Ok(BackupSettings::from_config(&config_map, default_directory))
+}
+
+/// 执行一次备份并按配置轮转旧备份
+pub async fn run_backup(
+    app_handle: &tauri::AppHandle,
+    settings: &BackupSettings,
+) -> Result<BackupInfo, String> {
+    let db_dir = get_db_dir(app_handle)?;
+    let backup_root = settings.directory.clone();
+    let keep_count = settings.keep_count;
+    tauri::async_runtime::spawn_blocking(move || {
+        let info = create_backup(&db_dir, &backup_root, Local::now())?;
+        let removed = rotate_backups(&backup_root, keep_count)?;
+        if !removed.is_empty() {
+            info!(removed = ?removed, "Old backups rotated");
+        }
+        Ok(info)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 立即备份数据目录中的数据库
+#[tauri::command]
+pub async fn create_backup_now(app_handle: tauri::AppHandle) -> Result<BackupInfo, String> {
+    let settings = load_backup_settings(&app_handle).await?;
+    run_backup(&app_handle, &settings).await
+}
+
+/// 列出已有的备份，按时间从新到旧排序
+#[tauri::command]
+pub async fn list_backups(app_handle: tauri::AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let settings = load_backup_settings(&app_handle).await?;
+    list_backups_in(&settings.directory)
+}
@@ -3,14 +3,20 @@ use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use regex;
 use serde::{Deserialize, Serialize};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 
 use crate::{
+    api::ai::config::config_value,
+    api::ai::conversation::extract_mcp_tool_call_hints,
+    api::assistant_api::resolve_assistant_detail,
+    db::assistant_db::{AssistantDatabase, AssistantModelConfig},
     db::conversation_db::{
-        ConversationDatabase, Message, MessageAttachment, MessageDetail, Repository,
+        ConversationDatabase, ConversationRepository, ConversationSummary, ConversationTag,
+        Message, MessageAttachment, MessageDetail, MessageSearchHit, Repository,
     },
     errors::AppError,
-    NameCacheState,
+    scheduler::SchedulerState,
+    FeatureConfigState, NameCacheState,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -74,12 +80,16 @@ pub struct ConversationResult {
     pub assistant_id: i64,
     pub assistant_name: String,
     pub created_time: DateTime<Utc>,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConversationWithMessages {
     pub conversation: ConversationResult,
     pub messages: Vec<MessageDetail>,
+    /// 分页加载时是否还有更早的消息，不分页时恒为 false
+    #[serde(default)]
+    pub has_more: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -186,15 +196,22 @@ pub async fn list_conversations(
     name_cache_state: tauri::State<'_, NameCacheState>,
     page: u32,
     page_size: u32,
+    tag: Option<String>,
 ) -> Result<Vec<ConversationResult>, AppError> {
     let db = ConversationDatabase::new(&app_handle).map_err(AppError::from)?;
 
-    let conversations =
-        db.conversation_repo().unwrap().list(page, page_size).map_err(|e| e.to_string());
+    let conversation_repo = db.conversation_repo().unwrap();
+    let conversations = match tag.as_deref().map(str::trim).filter(|tag| !tag.is_empty()) {
+        Some(tag) => conversation_repo.list_by_tag(page, page_size, tag),
+        None => conversation_repo.list(page, page_size),
+    }
+    .map_err(|e| e.to_string());
 
     let mut conversation_results = Vec::new();
     let assistant_name_cache = name_cache_state.assistant_names.lock().await.clone();
     if let Ok(conversations) = &conversations {
+        let ids: Vec<i64> = conversations.iter().map(|c| c.id).collect();
+        let mut tags = conversation_repo.list_tags_by_conversation_ids(&ids)?;
         for conversation in conversations {
             let assistant_name = assistant_name_cache.get(&conversation.assistant_id.unwrap());
             conversation_results.push(ConversationResult {
@@ -203,17 +220,77 @@ pub async fn list_conversations(
                 assistant_id: conversation.assistant_id.unwrap_or(0),
                 assistant_name: assistant_name.unwrap_or(&"未知".to_string()).clone(),
                 created_time: conversation.created_time,
+                tags: tags.remove(&conversation.id).unwrap_or_default(),
             });
         }
     }
     Ok(conversation_results)
 }
 
+/// 给对话添加标签，返回对话当前的全部标签
+#[tauri::command]
+pub fn add_conversation_tag(
+    app_handle: tauri::AppHandle,
+    conversation_id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("标签名称不能为空".to_string());
+    }
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let repo = db.conversation_repo().map_err(|e| e.to_string())?;
+    repo.add_tag(conversation_id, tag).map_err(|e| e.to_string())?;
+    conversation_tags(&repo, conversation_id)
+}
+
+/// 移除对话上的标签，返回对话当前的全部标签
+#[tauri::command]
+pub fn remove_conversation_tag(
+    app_handle: tauri::AppHandle,
+    conversation_id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let repo = db.conversation_repo().map_err(|e| e.to_string())?;
+    repo.remove_tag(conversation_id, tag.trim()).map_err(|e| e.to_string())?;
+    conversation_tags(&repo, conversation_id)
+}
+
+/// 获取全部对话标签
+#[tauri::command]
+pub fn list_conversation_tags(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<ConversationTag>, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.conversation_repo().map_err(|e| e.to_string())?.list_tags().map_err(|e| e.to_string())
+}
+
+/// 删除标签，只移除与对话的关联，不删除对话
+#[tauri::command]
+pub fn delete_conversation_tag(app_handle: tauri::AppHandle, tag_id: i64) -> Result<(), String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.conversation_repo().map_err(|e| e.to_string())?.delete_tag(tag_id).map_err(|e| e.to_string())
+}
+
+fn conversation_tags(
+    repo: &ConversationRepository,
+    conversation_id: i64,
+) -> Result<Vec<String>, String> {
+    let mut tags =
+        repo.list_tags_by_conversation_ids(&[conversation_id]).map_err(|e| e.to_string())?;
+    Ok(tags.remove(&conversation_id).unwrap_or_default())
+}
+
+/// 获取对话及其消息。传入 `limit` 时只返回 `before_message_id`（不传则为最新一条）之前最近的
+/// `limit` 条消息，并通过 `has_more` 标记是否还有更早的消息；不传 `limit` 时返回全部消息
 #[tauri::command]
 pub async fn get_conversation_with_messages(
     app_handle: tauri::AppHandle,
     name_cache_state: tauri::State<'_, NameCacheState>,
     conversation_id: i64,
+    limit: Option<usize>,
+    before_message_id: Option<i64>,
 ) -> Result<ConversationWithMessages, String> {
     use std::time::Instant;
     let start_time = Instant::now();
@@ -233,14 +310,26 @@ pub async fn get_conversation_with_messages(
 
     // 查询 messages
     let msg_query_start = Instant::now();
-    let messages = db
-        .message_repo()
-        .unwrap()
-        .list_by_conversation_id(conversation_id)
-        .map_err(|e| e.to_string())?;
+    let message_repo = db.message_repo().unwrap();
+    let (messages, has_more) = match limit {
+        Some(limit) => message_repo
+            .list_page_by_conversation_id(conversation_id, before_message_id, limit)
+            .map_err(|e| e.to_string())?,
+        None => (
+            message_repo.list_by_conversation_id(conversation_id).map_err(|e| e.to_string())?,
+            false,
+        ),
+    };
     let msg_query_duration = msg_query_start.elapsed();
     println!("[PERF] 查询 messages 耗时: {:?}, 消息数量: {}", msg_query_duration, messages.len());
 
+    let pinned_message_ids =
+        db.message_repo().unwrap().list_pinned_ids(conversation_id).map_err(|e| e.to_string())?;
+    let render_modes =
+        db.message_repo().unwrap().list_render_modes(conversation_id).map_err(|e| e.to_string())?;
+    let partial_message_ids =
+        db.message_repo().unwrap().list_partial_ids(conversation_id).map_err(|e| e.to_string())?;
+
     let mut message_details: Vec<MessageDetail> = Vec::new();
     let mut attachment_map: HashMap<i64, Vec<MessageAttachment>> = HashMap::new();
 
@@ -276,6 +365,9 @@ pub async fn get_conversation_with_messages(
             tool_calls_json: message.tool_calls_json,
             first_token_time: message.first_token_time,
             ttft_ms: message.ttft_ms,
+            is_pinned: pinned_message_ids.contains(&message.id),
+            render_mode: render_modes.get(&message.id).cloned(),
+            is_partial: partial_message_ids.contains(&message.id),
             attachment_list,
             regenerate: Vec::new(),
         });
@@ -295,6 +387,14 @@ pub async fn get_conversation_with_messages(
         .cloned()
         .unwrap_or_else(|| "未知".to_string());
 
+    let tags = db
+        .conversation_repo()
+        .unwrap()
+        .list_tags_by_conversation_ids(&[conversation.id])
+        .map_err(|e| e.to_string())?
+        .remove(&conversation.id)
+        .unwrap_or_default();
+
     let total_duration = start_time.elapsed();
     println!("[PERF] get_conversation_with_messages 总耗时: {:?}", total_duration);
 
@@ -305,11 +405,78 @@ pub async fn get_conversation_with_messages(
             assistant_id: conversation.assistant_id.unwrap_or(0),
             assistant_name,
             created_time: conversation.created_time,
+            tags,
         },
         messages: final_messages,
+        has_more,
     })
 }
 
+/// 专注模式下的消息清理（纯函数）：
+/// - 移除 reasoning 消息
+/// - 去除 MCP 工具调用 UI 注释，改为在回复末尾追加简短的工具调用标记
+/// - 将连续的 tool_result 消息折叠为一条简短标记
+pub fn build_clean_messages(messages: Vec<MessageDetail>) -> Vec<MessageDetail> {
+    let mut cleaned: Vec<MessageDetail> = Vec::new();
+    let mut collapsed_tool_results = 0usize;
+
+    for mut message in messages {
+        match message.message_type.as_str() {
+            "reasoning" => continue,
+            "tool_result" => {
+                let previous_is_tool_result =
+                    cleaned.last().is_some_and(|m| m.message_type == "tool_result");
+                collapsed_tool_results =
+                    if previous_is_tool_result { collapsed_tool_results + 1 } else { 1 };
+                let marker = format!("[已折叠 {} 条工具结果]", collapsed_tool_results);
+                if previous_is_tool_result {
+                    cleaned.last_mut().unwrap().content = marker;
+                } else {
+                    message.content = marker;
+                    message.attachment_list = Vec::new();
+                    message.regenerate = Vec::new();
+                    cleaned.push(message);
+                }
+            }
+            _ => {
+                let (content, hints) = extract_mcp_tool_call_hints(&message.content);
+                let mut content = content.trim().to_string();
+                if !hints.is_empty() {
+                    let tool_names: Vec<&str> =
+                        hints.iter().filter_map(|hint| hint["tool_name"].as_str()).collect();
+                    let marker = if tool_names.is_empty() {
+                        format!("[调用了 {} 个工具]", hints.len())
+                    } else {
+                        format!("[调用了 {} 个工具: {}]", hints.len(), tool_names.join(", "))
+                    };
+                    if !content.is_empty() {
+                        content.push_str("\n\n");
+                    }
+                    content.push_str(&marker);
+                }
+                message.content = content;
+                message.regenerate = build_clean_messages(message.regenerate);
+                cleaned.push(message);
+            }
+        }
+    }
+    cleaned
+}
+
+/// 获取专注模式下的对话内容：隐藏思考过程与工具调用细节，只保留对话本身
+#[tauri::command]
+pub async fn get_conversation_clean(
+    app_handle: tauri::AppHandle,
+    name_cache_state: tauri::State<'_, NameCacheState>,
+    conversation_id: i64,
+) -> Result<ConversationWithMessages, String> {
+    let mut conversation =
+        get_conversation_with_messages(app_handle, name_cache_state, conversation_id, None, None)
+            .await?;
+    conversation.messages = build_clean_messages(conversation.messages);
+    Ok(conversation)
+}
+
 #[tauri::command]
 pub fn delete_conversation(
     app_handle: tauri::AppHandle,
@@ -317,6 +484,11 @@ pub fn delete_conversation(
 ) -> Result<(), String> {
     let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     db.conversation_repo().unwrap().delete(conversation_id).map_err(|e| e.to_string())?;
+    let message_repo = db.message_repo().unwrap();
+    message_repo.clear_edit_history(conversation_id).map_err(|e| e.to_string())?;
+    message_repo
+        .remove_conversation_from_search_index(conversation_id)
+        .map_err(|e| e.to_string())?;
 
     // 发送删除事件通知前端更新列表
     let _ = app_handle.emit("conversation_deleted", conversation_id);
@@ -351,7 +523,160 @@ pub fn update_message_content(
     content: String,
 ) -> Result<(), String> {
     let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
-    db.message_repo().unwrap().update_content(message_id, &content).map_err(|e| e.to_string())
+    db.message_repo()
+        .unwrap()
+        .update_content_with_history(message_id, &content)
+        .map_err(|e| e.to_string())
+}
+
+/// 撤销消息的最近一次内容编辑，返回恢复后的内容，没有可撤销的编辑时返回 None
+#[tauri::command]
+pub fn undo_message_edit(
+    app_handle: tauri::AppHandle,
+    message_id: i64,
+) -> Result<Option<String>, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.message_repo().unwrap().undo_edit(message_id).map_err(|e| e.to_string())
+}
+
+/// 重做消息最近一次被撤销的编辑，返回恢复后的内容，没有可重做的编辑时返回 None
+#[tauri::command]
+pub fn redo_message_edit(
+    app_handle: tauri::AppHandle,
+    message_id: i64,
+) -> Result<Option<String>, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.message_repo().unwrap().redo_edit(message_id).map_err(|e| e.to_string())
+}
+
+/// 助手配置项 / 全局 display 配置项：编辑历史消息的方式
+pub const EDIT_BEHAVIOR_CONFIG_KEY: &str = "edit_behavior";
+
+/// 编辑历史消息并重新生成时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditBehavior {
+    /// 原地修改消息，重新生成时丢弃其后的内容
+    Truncate,
+    /// 复制编辑点之前的消息到新对话，在新对话中修改并重新生成，原对话保持不变
+    Fork,
+}
+
+impl EditBehavior {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value.trim() {
+            "truncate" => Some(EditBehavior::Truncate),
+            "fork" => Some(EditBehavior::Fork),
+            _ => None,
+        }
+    }
+}
+
+/// 解析编辑方式：助手配置优先，未配置（或为 default）时使用全局配置，都没有时原地修改
+pub fn resolve_edit_behavior(
+    assistant_configs: &[AssistantModelConfig],
+    global_value: Option<&str>,
+) -> EditBehavior {
+    config_value(assistant_configs, EDIT_BEHAVIOR_CONFIG_KEY)
+        .and_then(EditBehavior::from_value)
+        .or_else(|| global_value.and_then(EditBehavior::from_value))
+        .unwrap_or(EditBehavior::Truncate)
+}
+
+/// 编辑消息的结果，前端据此决定在哪个对话、对哪条消息重新生成
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EditMessageResult {
+    pub conversation_id: i64,
+    pub message_id: i64,
+    pub forked: bool,
+}
+
+/// 按编辑方式修改消息内容：Fork 时在新对话中修改复制出的消息，原对话不受影响
+pub fn apply_message_edit(
+    db: &ConversationDatabase,
+    message_id: i64,
+    content: &str,
+    behavior: EditBehavior,
+) -> Result<EditMessageResult, String> {
+    let message_repo = db.message_repo().map_err(|e| e.to_string())?;
+    let message = message_repo
+        .read(message_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Message not found".to_string())?;
+
+    let (conversation_id, target_message_id) = match behavior {
+        EditBehavior::Truncate => (message.conversation_id, message_id),
+        EditBehavior::Fork => {
+            let forked = fork_conversation_until(db, message.conversation_id, message_id)?;
+            let new_message_id = *forked
+                .message_id_map
+                .get(&message_id)
+                .ok_or_else(|| "Forked message not found".to_string())?;
+            (forked.conversation_id, new_message_id)
+        }
+    };
+
+    message_repo
+        .update_content_with_history(target_message_id, content)
+        .map_err(|e| e.to_string())?;
+    Ok(EditMessageResult {
+        conversation_id,
+        message_id: target_message_id,
+        forked: behavior == EditBehavior::Fork,
+    })
+}
+
+/// 编辑消息（用于“保存并重新生成”），根据助手 / 全局配置决定原地修改还是自动分支
+#[tauri::command]
+pub async fn edit_message(
+    app_handle: tauri::AppHandle,
+    feature_config_state: tauri::State<'_, FeatureConfigState>,
+    message_id: i64,
+    content: String,
+) -> Result<EditMessageResult, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+
+    let assistant_id = db
+        .message_repo()
+        .map_err(|e| e.to_string())?
+        .read(message_id)
+        .map_err(|e| e.to_string())?
+        .map(|message| message.conversation_id)
+        .and_then(|conversation_id| {
+            db.conversation_repo().ok()?.read(conversation_id).ok().flatten()
+        })
+        .and_then(|conversation| conversation.assistant_id);
+    // 读取合并了基础助手的生效配置
+    let assistant_configs = match assistant_id {
+        Some(assistant_id) => {
+            let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+            resolve_assistant_detail(&assistant_db, assistant_id)?.model_configs
+        }
+        None => Vec::new(),
+    };
+    let global_value = feature_config_state
+        .config_feature_map
+        .lock()
+        .await
+        .get("display")
+        .and_then(|display| display.get(EDIT_BEHAVIOR_CONFIG_KEY))
+        .map(|config| config.value.clone());
+
+    let behavior = resolve_edit_behavior(&assistant_configs, global_value.as_deref());
+    apply_message_edit(&db, message_id, &content, behavior)
+}
+
+/// 置顶消息，置顶后该消息在上下文截断时始终保留
+#[tauri::command]
+pub fn pin_message(app_handle: tauri::AppHandle, message_id: i64) -> Result<(), String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.message_repo().unwrap().pin(message_id).map_err(|e| e.to_string())
+}
+
+/// 取消消息置顶
+#[tauri::command]
+pub fn unpin_message(app_handle: tauri::AppHandle, message_id: i64) -> Result<(), String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.message_repo().unwrap().unpin(message_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -361,7 +686,60 @@ pub async fn fork_conversation(
     message_id: i64,
 ) -> Result<i64, String> {
     let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    fork_conversation_until(&db, conversation_id, message_id).map(|forked| forked.conversation_id)
+}
+
+/// 立即总结指定对话，忽略自动总结的开关和触发阈值，已有总结会被覆盖
+#[tauri::command]
+pub async fn summarize_conversation_now(
+    app_handle: tauri::AppHandle,
+    feature_config_state: tauri::State<'_, FeatureConfigState>,
+    conversation_id: i64,
+) -> Result<ConversationSummary, String> {
+    // 与定时任务共用正在总结的集合，避免同一对话被重复总结
+    let scheduler_state = app_handle.try_state::<SchedulerState>().map(|s| s.inner().clone());
+    if let Some(state) = &scheduler_state {
+        if !state.summarizing_conversations.lock().await.insert(conversation_id) {
+            return Err("该对话正在总结中，请稍后再试".to_string());
+        }
+    }
+
+    let config_map = feature_config_state.config_feature_map.lock().await.clone();
+    let result = crate::api::ai::summary::generate_conversation_summary(
+        &app_handle,
+        conversation_id,
+        config_map,
+        true,
+    )
+    .await;
+
+    if let Some(state) = &scheduler_state {
+        state.summarizing_conversations.lock().await.remove(&conversation_id);
+    }
+    result.map_err(|e| e.to_string())?;
+
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.conversation_summary_repo()
+        .map_err(|e| e.to_string())?
+        .get_by_conversation_id(conversation_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "对话总结生成失败".to_string())
+}
 
+/// 对话分支的结果
+#[derive(Debug, Clone)]
+pub struct ForkedConversation {
+    pub conversation_id: i64,
+    /// 原对话消息 ID -> 新对话中对应消息 ID
+    pub message_id_map: HashMap<i64, i64>,
+}
+
+/// 将对话中截至 `message_id`（含）的消息及附件复制到一个新对话
+pub fn fork_conversation_until(
+    db: &ConversationDatabase,
+    conversation_id: i64,
+    message_id: i64,
+) -> Result<ForkedConversation, String> {
     // 获取原对话信息
     let conversation_repo = db.conversation_repo().unwrap();
     let original_conversation = conversation_repo
@@ -428,11 +806,15 @@ pub async fn fork_conversation(
 
     let created_conversation =
         conversation_repo.create(&new_conversation).map_err(|e| e.to_string())?;
+    conversation_repo
+        .copy_tags(conversation_id, created_conversation.id)
+        .map_err(|e| e.to_string())?;
 
     // 获取附件仓库
     let attachment_repo = db.attachment_repo().map_err(|e| e.to_string())?;
 
     // 复制消息到新对话，并复制对应的附件
+    let mut message_id_map = HashMap::new();
     for message in messages_to_copy {
         let old_message_id = message.id;
 
@@ -442,6 +824,7 @@ pub async fn fork_conversation(
         new_message.created_time = chrono::Utc::now();
 
         let created_message = message_repo.create(&new_message).map_err(|e| e.to_string())?;
+        message_id_map.insert(old_message_id, created_message.id);
 
         // 复制该消息的所有附件
         if let Some(attachments) = attachment_map.get(&old_message_id) {
@@ -454,7 +837,7 @@ pub async fn fork_conversation(
         }
     }
 
-    Ok(created_conversation.id)
+    Ok(ForkedConversation { conversation_id: created_conversation.id, message_id_map })
 }
 
 #[tauri::command]
@@ -620,6 +1003,21 @@ pub async fn search_conversations(
     Ok(hits)
 }
 
+/// 在全部消息内容中全文搜索，返回消息 ID、对话 ID 与高亮摘要
+#[tauri::command]
+pub async fn search_messages(
+    app_handle: tauri::AppHandle,
+    query: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<MessageSearchHit>, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.message_repo()
+        .map_err(|e| e.to_string())?
+        .search_content(query.trim(), limit.unwrap_or(50).min(200), offset.unwrap_or(0))
+        .map_err(|e| e.to_string())
+}
+
 fn build_snippet(text: &str, query: &str, max_len: usize) -> String {
     if text.is_empty() {
         return String::new();
@@ -0,0 +1,465 @@
+//! 对话的 JSON 导出与导入，用于在不同设备之间迁移完整对话
+//!
+//! 导出包保留消息的 generation_group_id / parent_group_id，导入后重新生成的版本树可以正常展示；
+//! 本机不存在的助手会改用本机的默认助手，不存在的模型会被置空，而不是导致导入失败。
+//! 导入在一个事务中完成，中途失败不会留下不完整的对话。
+
+use crate::db::assistant_db::AssistantDatabase;
+use crate::db::conversation_db::{
+    insert_attachment, insert_conversation, insert_message, Conversation, ConversationDatabase,
+    Message, MessageAttachment, Repository,
+};
+use crate::db::llm_db::LLMDatabase;
+use crate::errors::AppError;
+use crate::NameCacheState;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::Emitter;
+use tracing::info;
+
+/// 导出包格式版本，格式不兼容变更时递增
+pub const CONVERSATION_BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub conversation: Conversation,
+    /// 导出时的助手名称，导入时用于在本机按名称匹配助手
+    pub assistant_name: Option<String>,
+    pub messages: Vec<BundleMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    #[serde(default)]
+    pub attachments: Vec<MessageAttachment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportConversationResult {
+    pub conversation_id: i64,
+    pub assistant_id: i64,
+    pub message_count: usize,
+    pub attachment_count: usize,
+    /// 原助手在本机不存在，已改用其他助手
+    pub assistant_remapped: bool,
+    /// 模型在本机不存在、已置为占位的消息数
+    pub remapped_model_count: usize,
+}
+
+/// 本机已有的助手和模型，用于把导入包中的 ID 映射到本机
+pub struct LocalIdCatalog {
+    assistant_ids: HashSet<i64>,
+    assistant_names: HashMap<String, i64>,
+    /// 匹配不到原助手时使用的本机助手（最早创建的内置助手，即快速使用助手）
+    fallback_assistant_id: Option<i64>,
+    /// (id, name, code)
+    models: Vec<(i64, String, String)>,
+}
+
+impl LocalIdCatalog {
+    pub fn new(assistants: Vec<(i64, String)>, models: Vec<(i64, String, String)>) -> Self {
+        let assistant_ids: HashSet<i64> = assistants.iter().map(|(id, _)| *id).collect();
+        let fallback_assistant_id = assistant_ids.iter().min().copied();
+        let assistant_names = assistants.into_iter().map(|(id, name)| (name, id)).collect();
+        Self { assistant_ids, assistant_names, fallback_assistant_id, models }
+    }
+
+    /// 按 ID（且名称一致）或名称匹配本机助手，都找不到时使用本机默认助手，第二个值表示是否发生了映射；
+    /// 本机没有任何助手时返回 None
+    pub fn resolve_assistant(&self, id: Option<i64>, name: Option<&str>) -> Option<(i64, bool)> {
+        let by_name = name.and_then(|name| self.assistant_names.get(name).copied());
+        match (id, by_name) {
+            (Some(id), Some(named)) if id == named => Some((id, false)),
+            (_, Some(named)) => Some((named, true)),
+            (Some(id), None) if name.is_none() && self.assistant_ids.contains(&id) => {
+                Some((id, false))
+            }
+            _ => self.fallback_assistant_id.map(|fallback| (fallback, true)),
+        }
+    }
+
+    /// 消息记录的模型名称是模型 code 或名称；ID 在本机对应的模型名称一致时保留，
+    /// 否则按名称查找，找不到时返回 None，消息上保留原模型名称用于展示
+    pub fn resolve_model(&self, id: Option<i64>, name: Option<&str>) -> Option<i64> {
+        let matches_name = |model: &&(i64, String, String)| {
+            name.is_some_and(|name| model.1 == name || model.2 == name)
+        };
+        if let Some(id) = id {
+            if let Some(model) = self.models.iter().find(|model| model.0 == id) {
+                if name.is_none() || matches_name(&model) {
+                    return Some(id);
+                }
+            }
+        }
+        self.models.iter().find(matches_name).map(|model| model.0)
+    }
+}
+
+/// 读取对话生成导出包，消息按附件合并并保持原始顺序
+pub fn build_conversation_bundle(
+    db: &ConversationDatabase,
+    conversation_id: i64,
+    assistant_name: Option<String>,
+) -> Result<ConversationBundle, AppError> {
+    let conversation = db
+        .conversation_repo()?
+        .read(conversation_id)?
+        .ok_or(AppError::ConversationNotFound(conversation_id))?;
+    let rows = db.message_repo()?.list_by_conversation_id(conversation_id)?;
+
+    let mut messages: Vec<BundleMessage> = Vec::new();
+    let mut index_by_id: HashMap<i64, usize> = HashMap::new();
+    for (message, attachment) in rows {
+        let index = *index_by_id.entry(message.id).or_insert_with(|| {
+            messages.push(BundleMessage { message, attachments: Vec::new() });
+            messages.len() - 1
+        });
+        if let Some(attachment) = attachment {
+            messages[index].attachments.push(attachment);
+        }
+    }
+
+    Ok(ConversationBundle {
+        version: CONVERSATION_BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        conversation,
+        assistant_name,
+        messages,
+    })
+}
+
+/// 解析导出包并检查格式版本
+pub fn parse_conversation_bundle(json: &str) -> Result<ConversationBundle, AppError> {
+    let bundle: ConversationBundle = serde_json::from_str(json)
+        .map_err(|e| AppError::ParseError(format!("对话导出包格式错误: {}", e)))?;
+    if bundle.version > CONVERSATION_BUNDLE_VERSION {
+        return Err(AppError::ParseError(format!(
+            "不支持的导出包版本 {}，请升级应用后再导入",
+            bundle.version
+        )));
+    }
+    Ok(bundle)
+}
+
+/// 将导出包写入为新对话，消息与附件获得新的 ID，分组关系保持不变；
+/// 所有写入在同一事务中完成，任何一步失败都会整体回滚
+pub fn import_conversation_bundle(
+    conn: &mut Connection,
+    bundle: ConversationBundle,
+    catalog: &LocalIdCatalog,
+) -> Result<ImportConversationResult, AppError> {
+    let (assistant_id, assistant_remapped) = catalog
+        .resolve_assistant(bundle.conversation.assistant_id, bundle.assistant_name.as_deref())
+        .ok_or_else(|| AppError::NoConfigError("本机没有可用的助手，无法导入对话".to_string()))?;
+
+    let tx = conn.transaction()?;
+    let conversation = insert_conversation(
+        &tx,
+        &Conversation {
+            id: 0,
+            name: bundle.conversation.name,
+            assistant_id: Some(assistant_id),
+            created_time: bundle.conversation.created_time,
+        },
+    )?;
+
+    let mut messages = bundle.messages;
+    messages.sort_by_key(|bundle_message| bundle_message.message.id);
+
+    let mut message_id_map: HashMap<i64, i64> = HashMap::new();
+    let mut attachment_count = 0;
+    let mut remapped_model_count = 0;
+    for BundleMessage { message, attachments } in messages {
+        let old_message_id = message.id;
+        let llm_model_id =
+            catalog.resolve_model(message.llm_model_id, message.llm_model_name.as_deref());
+        if message.llm_model_id.is_some() && llm_model_id.is_none() {
+            remapped_model_count += 1;
+        }
+
+        let created = insert_message(
+            &tx,
+            &Message {
+                id: 0,
+                conversation_id: conversation.id,
+                parent_id: message.parent_id.and_then(|id| message_id_map.get(&id).copied()),
+                llm_model_id,
+                ..message
+            },
+        )?;
+        message_id_map.insert(old_message_id, created.id);
+
+        for attachment in attachments {
+            insert_attachment(
+                &tx,
+                &MessageAttachment { id: 0, message_id: created.id, ..attachment },
+            )?;
+            attachment_count += 1;
+        }
+    }
+    tx.commit()?;
+
+    Ok(ImportConversationResult {
+        conversation_id: conversation.id,
+        assistant_id,
+        message_count: message_id_map.len(),
+        attachment_count,
+        assistant_remapped,
+        remapped_model_count,
+    })
+}
+
+#[tauri::command]
+pub async fn export_conversation_json(
+    app_handle: tauri::AppHandle,
+    conversation_id: i64,
+    file_path: String,
+) -> Result<(), AppError> {
+    let db = ConversationDatabase::new(&app_handle)?;
+    let assistant_id = db.conversation_repo()?.read(conversation_id)?.and_then(|c| c.assistant_id);
+    let assistant_name = match assistant_id {
+        Some(id) => AssistantDatabase::new(&app_handle)?.get_assistant(id).ok().map(|a| a.name),
+        None => None,
+    };
+
+    let bundle = build_conversation_bundle(&db, conversation_id, assistant_name)?;
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::ParseError(format!("对话序列化失败: {}", e)))?;
+    std::fs::write(&file_path, json)?;
+
+    info!(conversation_id, message_count = bundle.messages.len(), file_path = %file_path, "Exported conversation as JSON");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn import_conversation(
+    app_handle: tauri::AppHandle,
+    name_cache_state: tauri::State<'_, NameCacheState>,
+    json: String,
+) -> Result<ImportConversationResult, AppError> {
+    let bundle = parse_conversation_bundle(&json)?;
+
+    let assistants: Vec<(i64, String)> = AssistantDatabase::new(&app_handle)?
+        .get_assistants()?
+        .into_iter()
+        .map(|assistant| (assistant.id, assistant.name))
+        .collect();
+    let models = LLMDatabase::new(&app_handle)?
+        .get_all_llm_models()?
+        .into_iter()
+        .map(|(id, name, _, code, ..)| (id, name, code))
+        .collect();
+    let catalog = LocalIdCatalog::new(assistants.clone(), models);
+
+    let mut conn = ConversationDatabase::new(&app_handle)?.get_connection()?;
+    let result = import_conversation_bundle(&mut conn, bundle, &catalog)?;
+
+    // 导入的对话使用的助手名称写入缓存，确保列表展示正确
+    if let Some((id, name)) = assistants.into_iter().find(|(id, _)| *id == result.assistant_id) {
+        name_cache_state.assistant_names.lock().await.insert(id, name);
+    }
+
+    let _ = app_handle.emit("conversation_created", result.conversation_id);
+    info!(
+        conversation_id = result.conversation_id,
+        message_count = result.message_count,
+        assistant_remapped = result.assistant_remapped,
+        remapped_model_count = result.remapped_model_count,
+        "Imported conversation"
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::conversation_db::{AttachmentType, MessageRepository};
+    use crate::db::tests::test_helpers::{create_test_db, create_test_message};
+
+    fn catalog() -> LocalIdCatalog {
+        LocalIdCatalog::new(
+            vec![(1, "快速使用助手".to_string()), (7, "翻译助手".to_string())],
+            vec![(3, "GPT-4o".to_string(), "gpt-4o".to_string())],
+        )
+    }
+
+    #[test]
+    fn test_resolve_assistant() {
+        let catalog = catalog();
+        assert_eq!(catalog.resolve_assistant(Some(7), Some("翻译助手")), Some((7, false)));
+        // 其他设备上 ID 不同但名称相同
+        assert_eq!(catalog.resolve_assistant(Some(42), Some("翻译助手")), Some((7, true)));
+        // ID 相同但本机是另一个助手时不能直接复用
+        assert_eq!(catalog.resolve_assistant(Some(7), Some("写作助手")), Some((1, true)));
+        assert_eq!(catalog.resolve_assistant(Some(99), None), Some((1, true)));
+        assert_eq!(catalog.resolve_assistant(Some(7), None), Some((7, false)));
+        // 本机没有任何助手时无法导入
+        assert_eq!(
+            LocalIdCatalog::new(Vec::new(), Vec::new()).resolve_assistant(Some(7), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_model() {
+        let catalog = catalog();
+        assert_eq!(catalog.resolve_model(Some(3), Some("gpt-4o")), Some(3));
+        assert_eq!(catalog.resolve_model(Some(10), Some("gpt-4o")), Some(3));
+        assert_eq!(catalog.resolve_model(Some(3), Some("claude-3")), None);
+        assert_eq!(catalog.resolve_model(Some(10), None), None);
+    }
+
+    #[test]
+    fn test_parse_bundle_round_trip() {
+        let now = Utc::now();
+        let message = Message {
+            id: 5,
+            parent_id: None,
+            conversation_id: 2,
+            message_type: "response".to_string(),
+            content: "hello".to_string(),
+            llm_model_id: Some(3),
+            llm_model_name: Some("gpt-4o".to_string()),
+            created_time: now,
+            start_time: Some(now),
+            finish_time: None,
+            token_count: 1,
+            input_token_count: 0,
+            output_token_count: 1,
+            generation_group_id: Some("group-b".to_string()),
+            parent_group_id: Some("group-a".to_string()),
+            tool_calls_json: None,
+            first_token_time: None,
+            ttft_ms: None,
+        };
+        let bundle = ConversationBundle {
+            version: CONVERSATION_BUNDLE_VERSION,
+            exported_at: now,
+            conversation: Conversation {
+                id: 2,
+                name: "测试".to_string(),
+                assistant_id: Some(7),
+                created_time: now,
+            },
+            assistant_name: Some("翻译助手".to_string()),
+            messages: vec![BundleMessage { message, attachments: Vec::new() }],
+        };
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let parsed = parse_conversation_bundle(&json).unwrap();
+        let message = &parsed.messages[0].message;
+        assert_eq!(message.generation_group_id.as_deref(), Some("group-b"));
+        assert_eq!(message.parent_group_id.as_deref(), Some("group-a"));
+        assert_eq!(message.content, "hello");
+
+        let newer = json.replacen("\"version\":1", "\"version\":99", 1);
+        assert!(parse_conversation_bundle(&newer).is_err());
+        assert!(parse_conversation_bundle("not json").is_err());
+    }
+
+    fn import_bundle() -> ConversationBundle {
+        let message =
+            |id, message_type, parent_id, group: &str, parent_group: Option<&str>| Message {
+                id,
+                llm_model_id: Some(3),
+                llm_model_name: Some("gpt-4o".to_string()),
+                parent_group_id: parent_group.map(str::to_string),
+                ..create_test_message(2, message_type, "内容", parent_id, Some(group.to_string()))
+            };
+        let user = message(20, "user", None, "group-user", None);
+        let response = message(21, "response", None, "group-a", None);
+        // 重新生成的版本指向原回复，并使用本机不存在的模型
+        let regenerated = Message {
+            llm_model_id: Some(99),
+            llm_model_name: Some("unknown-model".to_string()),
+            ..message(22, "response", Some(21), "group-b", Some("group-a"))
+        };
+        let attachment = MessageAttachment {
+            id: 8,
+            message_id: 20,
+            attachment_type: AttachmentType::Text,
+            attachment_url: None,
+            attachment_content: Some("附件内容".to_string()),
+            attachment_hash: None,
+            use_vector: false,
+            token_count: None,
+        };
+
+        ConversationBundle {
+            version: CONVERSATION_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            conversation: Conversation {
+                id: 2,
+                name: "导入测试".to_string(),
+                assistant_id: Some(42),
+                created_time: Utc::now(),
+            },
+            assistant_name: Some("翻译助手".to_string()),
+            // 导出包中的顺序被打乱，导入时需按原 ID 排序后再映射 parent_id
+            messages: vec![
+                BundleMessage { message: regenerated, attachments: Vec::new() },
+                BundleMessage { message: user, attachments: vec![attachment] },
+                BundleMessage { message: response, attachments: Vec::new() },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_import_bundle_remaps_ids() {
+        let mut conn = create_test_db();
+        let json = serde_json::to_string(&import_bundle()).unwrap();
+        let bundle = parse_conversation_bundle(&json).unwrap();
+
+        let result = import_conversation_bundle(&mut conn, bundle, &catalog()).unwrap();
+        assert_eq!(result.assistant_id, 7);
+        assert!(result.assistant_remapped);
+        assert_eq!(result.message_count, 3);
+        assert_eq!(result.attachment_count, 1);
+        assert_eq!(result.remapped_model_count, 1);
+
+        let rows =
+            MessageRepository::new(conn).list_by_conversation_id(result.conversation_id).unwrap();
+        let find = |group: &str| {
+            rows.iter()
+                .find(|(message, _)| message.generation_group_id.as_deref() == Some(group))
+                .unwrap()
+        };
+        let (user, attachment) = find("group-user");
+        let (response, _) = find("group-a");
+        let (regenerated, _) = find("group-b");
+
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|(message, _)| message.conversation_id == result.conversation_id));
+        assert_eq!(regenerated.parent_id, Some(response.id));
+        assert_eq!(regenerated.parent_group_id.as_deref(), Some("group-a"));
+        assert_eq!(regenerated.llm_model_id, None);
+        assert_eq!(regenerated.llm_model_name.as_deref(), Some("unknown-model"));
+        assert_eq!(response.llm_model_id, Some(3));
+
+        let attachment = attachment.as_ref().unwrap();
+        assert_eq!(attachment.message_id, user.id);
+        assert_eq!(attachment.attachment_content.as_deref(), Some("附件内容"));
+    }
+
+    #[test]
+    fn test_import_bundle_rolls_back_on_failure() {
+        let mut conn = create_test_db();
+        // 附件写入失败时，已写入的对话和消息也不能保留
+        conn.execute("DROP TABLE message_attachment", []).unwrap();
+
+        assert!(import_conversation_bundle(&mut conn, import_bundle(), &catalog()).is_err());
+
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count("conversation"), 0);
+        assert_eq!(count("message"), 0);
+    }
+}
@@ -0,0 +1,365 @@
+//! 对话导出为独立的只读 HTML 文件
+//!
+//! 样式全部内联、代码块在后端预先高亮，生成的文件不依赖应用即可离线查看和分享。
+//! 导出按消息逐条写入文件，长对话不会在内存中拼接整份 HTML。
+
+use crate::api::ai::conversation::extract_mcp_tool_call_hints;
+use crate::api::ai::render_mode::{RENDER_MODE_CODE, RENDER_MODE_PLAINTEXT};
+use crate::api::highlight_api::highlight_code_for_export;
+use crate::db::conversation_db::{ConversationDatabase, Message, Repository};
+use crate::errors::AppError;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use tracing::info;
+
+/// 代码高亮使用的浅色主题，与 PDF 导出保持一致
+const EXPORT_CODE_THEME: &str = "InspiredGitHub";
+
+const EXPORT_STYLE: &str = r#"
+    html, body { margin: 0; padding: 0; background: #f6f7f9; color: #1f2328; }
+    body {
+      font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, "Helvetica Neue", Arial, "Microsoft YaHei", sans-serif;
+      font-size: 15px;
+      line-height: 1.65;
+      word-break: break-word;
+    }
+    main { max-width: 860px; margin: 0 auto; padding: 32px 20px 48px; }
+    header.conversation { margin-bottom: 24px; }
+    header.conversation h1 { font-size: 1.5em; margin: 0 0 4px; }
+    header.conversation .meta { color: #6b7280; font-size: 13px; }
+    .message { background: #fff; border: 1px solid #e5e7eb; border-radius: 10px; padding: 12px 16px; margin: 12px 0; }
+    .message > .role { font-size: 12px; font-weight: 600; color: #6b7280; margin-bottom: 6px; }
+    .message > .role time { font-weight: 400; margin-left: 8px; }
+    .message.user { background: #eef4ff; border-color: #c7d7fe; }
+    .message.reasoning { background: #fafafa; border-style: dashed; color: #6b7280; font-size: 14px; }
+    .message.error { background: #fef2f2; border-color: #fca5a5; color: #991b1b; }
+    .message.filtered { background: #fff7ed; border-color: #fdba74; color: #9a3412; }
+    .message.system { background: #fffbeb; border-color: #fde68a; }
+    .plaintext { white-space: pre-wrap; }
+    pre { border: 1px solid #e5e7eb; border-radius: 6px; padding: 10px 12px; overflow-x: auto; font-size: 13px; line-height: 1.5; }
+    code { font-family: Consolas, Monaco, "Courier New", monospace; }
+    p > code, li > code { background: #f3f4f6; border-radius: 4px; padding: 1px 4px; font-size: 0.9em; }
+    blockquote { margin: 0.75em 0; padding: 0.25em 0 0.25em 0.8em; border-left: 3px solid #d4d4d4; color: #555; }
+    table { border-collapse: collapse; margin: 0.75em 0; }
+    th, td { border: 1px solid #e5e7eb; padding: 6px 8px; text-align: left; vertical-align: top; }
+    th { background: #f8f8f8; }
+    img { max-width: 100%; }
+    details.tool { border: 1px solid #e5e7eb; border-radius: 6px; margin: 8px 0; padding: 4px 10px; background: #f9fafb; font-size: 13px; }
+    details.tool > summary { cursor: pointer; color: #374151; }
+    details.tool pre { white-space: pre-wrap; background: #fff; }
+"#;
+
+#[tauri::command]
+pub async fn export_conversation_html(
+    app_handle: tauri::AppHandle,
+    conversation_id: i64,
+    file_path: String,
+) -> Result<(), AppError> {
+    let db = ConversationDatabase::new(&app_handle)?;
+    let conversation = db
+        .conversation_repo()?
+        .read(conversation_id)?
+        .ok_or(AppError::ConversationNotFound(conversation_id))?;
+    let message_repo = db.message_repo()?;
+    let messages = message_repo.list_by_conversation_id(conversation_id)?;
+    let render_modes = message_repo.list_render_modes(conversation_id)?;
+
+    let messages = select_export_messages(messages.into_iter().map(|(message, _)| message));
+    let mut writer = BufWriter::new(File::create(&file_path)?);
+    write_conversation_html(&mut writer, &conversation.name, &messages, &render_modes)?;
+    writer.flush()?;
+
+    info!(conversation_id, message_count = messages.len(), file_path = %file_path, "Exported conversation as HTML");
+    Ok(())
+}
+
+/// 整理待导出的消息：按附件去重、只保留每组重新生成的最新版本，并按时间排序
+fn select_export_messages(messages: impl IntoIterator<Item = Message>) -> Vec<Message> {
+    let mut seen = HashSet::new();
+    let messages: Vec<Message> = messages.into_iter().filter(|m| seen.insert(m.id)).collect();
+
+    let superseded_groups: HashSet<&str> =
+        messages.iter().filter_map(|m| m.parent_group_id.as_deref()).collect();
+    let superseded_messages: HashSet<i64> = messages.iter().filter_map(|m| m.parent_id).collect();
+    let mut selected: Vec<Message> = messages
+        .iter()
+        .filter(|m| !superseded_messages.contains(&m.id))
+        .filter(|m| {
+            !m.generation_group_id.as_deref().is_some_and(|group| superseded_groups.contains(group))
+        })
+        .cloned()
+        .collect();
+    selected.sort_by(|a, b| a.created_time.cmp(&b.created_time).then(a.id.cmp(&b.id)));
+    selected
+}
+
+/// 将对话逐条写出为独立 HTML
+pub fn write_conversation_html<W: Write>(
+    writer: &mut W,
+    title: &str,
+    messages: &[Message],
+    render_modes: &HashMap<i64, String>,
+) -> std::io::Result<()> {
+    let title = escape_html(title);
+    writeln!(
+        writer,
+        "<!doctype html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\" />\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />\n\
+         <title>{title}</title>\n<style>{EXPORT_STYLE}</style>\n</head>\n<body>\n<main>\n\
+         <header class=\"conversation\"><h1>{title}</h1><div class=\"meta\">{} 条消息</div></header>",
+        messages.len()
+    )?;
+
+    for message in messages {
+        write_message_html(writer, message, render_modes.get(&message.id).map(String::as_str))?;
+    }
+
+    writer.write_all(b"</main>\n</body>\n</html>\n")
+}
+
+fn write_message_html<W: Write>(
+    writer: &mut W,
+    message: &Message,
+    render_mode: Option<&str>,
+) -> std::io::Result<()> {
+    let time = message.created_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S");
+
+    if message.message_type == "tool_result" {
+        return writeln!(
+            writer,
+            "<details class=\"tool\"><summary>工具执行结果 · {time}</summary><pre>{}</pre></details>",
+            escape_html(&message.content)
+        );
+    }
+
+    let role = match message.message_type.as_str() {
+        "system" => "系统提示词".to_string(),
+        "user" => "用户".to_string(),
+        "reasoning" => "思考过程".to_string(),
+        "error" => "错误".to_string(),
+        "filtered" => "内容审核拦截".to_string(),
+        "response" => message.llm_model_name.clone().unwrap_or_else(|| "助手".to_string()),
+        other => other.to_string(),
+    };
+    writeln!(
+        writer,
+        "<section class=\"message {}\">\n<div class=\"role\">{}<time>{time}</time></div>",
+        escape_html(&message.message_type),
+        escape_html(&role)
+    )?;
+
+    let (content, tool_calls) = extract_mcp_tool_call_hints(&message.content);
+    let body = match (message.message_type.as_str(), render_mode) {
+        ("error" | "filtered", _) | (_, Some(RENDER_MODE_PLAINTEXT)) => {
+            format!("<div class=\"plaintext\">{}</div>", escape_html(content.trim()))
+        }
+        (_, Some(RENDER_MODE_CODE)) => render_code_block_html("", content.trim_end()),
+        _ => render_markdown_html(&content),
+    };
+    writer.write_all(body.as_bytes())?;
+
+    for tool_call in tool_calls {
+        let server_name = tool_call["server_name"].as_str().unwrap_or_default();
+        let tool_name = tool_call["tool_name"].as_str().unwrap_or_default();
+        let parameters = tool_call["parameters"].as_str().unwrap_or_default();
+        let parameters = serde_json::from_str::<serde_json::Value>(parameters)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_else(|_| parameters.to_string());
+        write!(
+            writer,
+            "\n<details class=\"tool\"><summary>工具调用：{} / {}</summary><pre>{}</pre></details>",
+            escape_html(server_name),
+            escape_html(tool_name),
+            escape_html(&parameters)
+        )?;
+    }
+
+    writer.write_all(b"\n</section>\n")
+}
+
+/// 渲染 Markdown，代码块使用 syntect 高亮；原始 HTML 按文本转义，链接与图片地址只保留
+/// http(s)、mailto 与相对地址，避免导出文件执行消息中的脚本
+fn render_markdown_html(markdown: &str) -> String {
+    let parser_options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut html = String::new();
+    let mut code_block: Option<(String, String)> = None;
+
+    for event in Parser::new_ext(markdown, parser_options) {
+        if let Some((lang, code)) = code_block.as_mut() {
+            match event {
+                Event::End(TagEnd::CodeBlock) => {
+                    html.push_str(&render_code_block_html(lang, code));
+                    code_block = None;
+                }
+                Event::Text(text) | Event::Code(text) => code.push_str(&text),
+                Event::SoftBreak | Event::HardBreak => code.push('\n'),
+                _ => {}
+            }
+            continue;
+        }
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((lang, String::new()));
+            }
+            Event::Html(raw) | Event::InlineHtml(raw) => html.push_str(&escape_html(&raw)),
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+                let dest_url = if is_safe_url(&dest_url) { dest_url } else { "#".into() };
+                let link = Tag::Link { link_type, dest_url, title, id };
+                pulldown_cmark::html::push_html(&mut html, std::iter::once(Event::Start(link)));
+            }
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+                let dest_url = if is_safe_url(&dest_url) { dest_url } else { "".into() };
+                let image = Tag::Image { link_type, dest_url, title, id };
+                pulldown_cmark::html::push_html(&mut html, std::iter::once(Event::Start(image)));
+            }
+            event => pulldown_cmark::html::push_html(&mut html, std::iter::once(event)),
+        }
+    }
+
+    html
+}
+
+/// 只允许 http(s)、mailto 与不带协议的相对地址；浏览器会忽略协议名中的空白和控制字符，判断前先去掉
+fn is_safe_url(url: &str) -> bool {
+    let normalized: String =
+        url.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect();
+    let scheme_end = normalized.find(|c| matches!(c, ':' | '/' | '?' | '#'));
+    match scheme_end {
+        Some(index) if normalized[index..].starts_with(':') => {
+            let scheme = normalized[..index].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+fn render_code_block_html(lang: &str, code: &str) -> String {
+    match highlight_code_for_export(lang, code, false, Some(EXPORT_CODE_THEME)) {
+        Ok(html) => html,
+        Err(_) => format!("<pre><code>{}</code></pre>", escape_html(code)),
+    }
+}
+
+fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tests::test_helpers::create_test_message;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn message(id: i64, message_type: &str, content: &str) -> Message {
+        Message {
+            id,
+            created_time: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+                + Duration::seconds(id),
+            ..create_test_message(1, message_type, content, None, None)
+        }
+    }
+
+    fn export(messages: &[Message]) -> String {
+        let mut output = Vec::new();
+        write_conversation_html(&mut output, "Rust <问答>", messages, &HashMap::new()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_export_contains_messages_and_highlighted_code() {
+        let messages = vec![
+            message(1, "user", "如何在 Rust 中打印 hello？"),
+            message(2, "reasoning", "用户想要一个最小示例"),
+            message(
+                3,
+                "response",
+                "可以这样写：\n\n```rust\nfn main() {\n    println!(\"hello\");\n}\n```\n\
+                 <!-- MCP_TOOL_CALL:{\"server_name\":\"search\",\"tool_name\":\"web_search\",\"parameters\":\"{\\\"query\\\":\\\"rust\\\"}\",\"call_id\":7} -->",
+            ),
+            message(4, "tool_result", "<result>ok</result>"),
+            message(5, "error", "请求超时 <timeout>"),
+        ];
+
+        let html = export(&messages);
+
+        assert!(html.starts_with("<!doctype html>"));
+        assert!(html.contains("<title>Rust &lt;问答&gt;</title>"));
+        assert!(html.contains("如何在 Rust 中打印 hello？"));
+        assert!(html.contains("<section class=\"message reasoning\">"));
+        assert!(html.contains("用户想要一个最小示例"));
+        // 代码块经过 syntect 高亮为内联样式，而不是原样输出
+        assert!(html.contains("<pre style=\"background-color:"));
+        assert!(html.contains("<span style=\"color:"));
+        assert!(html.contains("println!"));
+        assert!(!html.contains("```rust"));
+        // 工具调用与结果折叠展示，注释本身不会出现在导出内容中
+        assert!(html.contains("<details class=\"tool\"><summary>工具调用：search / web_search"));
+        assert!(!html.contains("MCP_TOOL_CALL"));
+        assert!(html.contains("&lt;result&gt;ok&lt;/result&gt;"));
+        assert!(html.contains("<section class=\"message error\">"));
+        assert!(html.contains("请求超时 &lt;timeout&gt;"));
+    }
+
+    #[test]
+    fn test_export_escapes_raw_html_and_honors_render_mode() {
+        let messages = vec![
+            message(1, "response", "<script>alert(1)</script>\n\n**bold**"),
+            message(2, "response", "# not a heading"),
+        ];
+        let render_modes = HashMap::from([(2, RENDER_MODE_PLAINTEXT.to_string())]);
+        let mut output = Vec::new();
+        write_conversation_html(&mut output, "t", &messages, &render_modes).unwrap();
+        let html = String::from_utf8(output).unwrap();
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<div class=\"plaintext\"># not a heading</div>"));
+    }
+
+    #[test]
+    fn test_export_drops_unsafe_link_and_image_urls() {
+        let html = render_markdown_html(
+            "[a](javascript:alert(1)) [b](JavaScript&#58;alert(2)) [c](<java\tscript:alert(3)>) \
+             ![d](data:text/html;base64,PHNjcmlwdD4=) [e](https://example.com/?q=1) \
+             [f](mailto:me@example.com) [g](docs/readme.md) ![h](/img/logo.png)",
+        );
+
+        assert!(!html.to_lowercase().contains("javascript"), "{}", html);
+        assert!(!html.contains("data:"), "{}", html);
+        assert!(html.contains("<a href=\"#\">a</a>"));
+        assert!(html.contains("<a href=\"#\">b</a>"));
+        assert!(html.contains("<a href=\"#\">c</a>"));
+        assert!(html.contains("<img src=\"\""));
+        assert!(html.contains("href=\"https://example.com/?q=1\""));
+        assert!(html.contains("href=\"mailto:me@example.com\""));
+        assert!(html.contains("href=\"docs/readme.md\""));
+        assert!(html.contains("src=\"/img/logo.png\""));
+    }
+
+    #[test]
+    fn test_select_export_messages_keeps_latest_versions_in_order() {
+        let mut original = message(2, "response", "old answer");
+        original.generation_group_id = Some("g1".to_string());
+        let mut regenerated = message(3, "response", "new answer");
+        regenerated.generation_group_id = Some("g2".to_string());
+        regenerated.parent_group_id = Some("g1".to_string());
+        let user = message(1, "user", "question");
+
+        let selected =
+            select_export_messages(vec![regenerated.clone(), user.clone(), original, regenerated]);
+
+        let ids: Vec<i64> = selected.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+}
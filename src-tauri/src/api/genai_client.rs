@@ -1,9 +1,11 @@
 use crate::api::ai::config::get_custom_headers_from_config;
+use crate::api::ai::keep_alive::{get_or_create_warm_client, keep_alive_settings, stop_keep_alive};
 use crate::errors::AppError;
 use genai::resolver::{AuthData, Endpoint, ServiceTargetResolver};
 use genai::{adapter::AdapterKind, ModelIden, ServiceTarget};
 use genai::{Client, WebConfig};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::HashMap;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
@@ -34,6 +36,7 @@ pub fn infer_adapter_kind(model_name: &str, api_type: &str) -> AdapterKind {
         "deepseek" => AdapterKind::DeepSeek,
         "ollama" => AdapterKind::Ollama,
         "github_copilot" => AdapterKind::Copilot, // Copilot 使用 OpenAI 兼容 API
+        AZURE_API_TYPE => AdapterKind::OpenAI,    // Azure OpenAI 的 v1 接口与 OpenAI 兼容
         _ => {
             // 根据模型名称推断
             let model_lower = model_name.to_lowercase();
@@ -52,20 +55,168 @@ pub fn infer_adapter_kind(model_name: &str, api_type: &str) -> AdapterKind {
     }
 }
 
-/// 仅根据API类型推断适配器类型（用于llm_api.rs）
-pub fn infer_adapter_kind_simple(api_type: &str) -> AdapterKind {
-    match api_type.to_lowercase().as_str() {
-        "openai" => AdapterKind::OpenAI,
-        "openai_api" => AdapterKind::OpenAI,
-        "anthropic" => AdapterKind::Anthropic,
-        "cohere" => AdapterKind::Cohere,
-        "gemini" => AdapterKind::Gemini,
-        "groq" => AdapterKind::Groq,
-        "xai" => AdapterKind::Xai,
-        "deepseek" => AdapterKind::DeepSeek,
-        "ollama" => AdapterKind::Ollama,
-        "github_copilot" => AdapterKind::Copilot, // Copilot 使用 OpenAI 兼容 API
-        _ => AdapterKind::OpenAI,                 // 默认
+/// Azure OpenAI 提供商的 API 类型
+pub const AZURE_API_TYPE: &str = "azure";
+/// Azure OpenAI 的 v1 API 版本
+pub const AZURE_API_VERSION_V1: &str = "v1";
+
+/// Azure OpenAI 提供商配置，存储在 llm_provider_config 中：
+/// `resource_name`、`api_version` 以及 JSON 格式的 `deployment_names`（模型代码 -> 部署名）
+///
+/// - `v1`：请求发往 `https://{resource_name}.openai.azure.com/openai/v1/`，部署名作为 model 传递
+/// - 日期版本（如 `2024-10-21`、`2025-01-01-preview`）：请求发往经典的
+///   `/openai/deployments/{deployment}/chat/completions?api-version=...` 路径
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AzureConfig {
+    pub resource_name: String,
+    pub api_version: String,
+    pub deployments: HashMap<String, String>,
+}
+
+impl AzureConfig {
+    pub fn from_configs(
+        configs: &[crate::db::llm_db::LLMProviderConfig],
+    ) -> Result<Self, AppError> {
+        let value = |name: &str| {
+            configs
+                .iter()
+                .find(|config| config.name == name)
+                .map(|config| config.value.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let api_version = match value("api_version") {
+            version if version.is_empty() => AZURE_API_VERSION_V1.to_string(),
+            version => version,
+        };
+        if api_version != AZURE_API_VERSION_V1 && !is_dated_azure_api_version(&api_version) {
+            return Err(AppError::ProviderError(format!(
+                "不支持的 Azure API 版本 \"{}\"，请使用 {} 或日期版本（如 2024-10-21）",
+                api_version, AZURE_API_VERSION_V1
+            )));
+        }
+
+        let deployments_json = value("deployment_names");
+        let deployments = if deployments_json.is_empty() {
+            HashMap::new()
+        } else {
+            serde_json::from_str::<HashMap<String, String>>(&deployments_json).map_err(|e| {
+                AppError::ProviderError(format!("Azure 部署名映射不是有效的 JSON 对象: {}", e))
+            })?
+        };
+
+        Ok(AzureConfig { resource_name: value("resource_name"), api_version, deployments })
+    }
+
+    /// 是否使用经典的按部署路由的接口（日期版本）
+    pub fn uses_deployment_urls(&self) -> bool {
+        self.api_version != AZURE_API_VERSION_V1
+    }
+
+    /// 资源名对应的接口地址，未配置资源名时返回 None（使用自定义 endpoint）
+    ///
+    /// v1 返回 `/openai/v1/` 接口地址；日期版本返回资源根地址，具体部署地址见 [`Self::deployment_url`]
+    pub fn base_url(&self) -> Option<String> {
+        if self.resource_name.is_empty() {
+            return None;
+        }
+        Some(if self.uses_deployment_urls() {
+            format!("https://{}.openai.azure.com/", self.resource_name)
+        } else {
+            format!("https://{}.openai.azure.com/openai/v1/", self.resource_name)
+        })
+    }
+
+    /// 经典接口下部署的 chat/completions 地址，`resource_url` 为资源根地址
+    ///
+    /// genai 的 OpenAI 适配器会在 endpoint 后直接拼接 `chat/completions`，无法在其后追加查询参数。
+    /// 这里把完整地址（含 `api-version`）作为 endpoint，并以 `#` 结尾，
+    /// 使适配器拼接的后缀落在 URL 片段中，而片段不会随 HTTP 请求发送。
+    pub fn deployment_url(&self, resource_url: &str, deployment: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}#",
+            resource_url.trim().trim_end_matches('/'),
+            deployment,
+            self.api_version
+        )
+    }
+
+    /// 模型对应的部署名，未配置映射时直接使用模型代码
+    pub fn deployment_for(&self, model_code: &str) -> String {
+        self.deployments
+            .get(model_code)
+            .filter(|deployment| !deployment.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| model_code.to_string())
+    }
+
+    /// 已配置的部署，按模型代码排序
+    pub fn deployment_list(&self) -> Vec<(String, String)> {
+        let mut deployments: Vec<(String, String)> =
+            self.deployments.iter().map(|(code, name)| (code.clone(), name.clone())).collect();
+        deployments.sort();
+        deployments
+    }
+}
+
+/// 日期形式的 Azure API 版本：`YYYY-MM-DD`，可带 `-preview` 后缀
+fn is_dated_azure_api_version(version: &str) -> bool {
+    let date = version.strip_suffix("-preview").unwrap_or(version);
+    date.len() == 10 && chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+}
+
+pub fn is_azure_api_type(api_type: &str) -> bool {
+    api_type.eq_ignore_ascii_case(AZURE_API_TYPE)
+}
+
+/// 提供商配置中显式指定 API 协议（dialect）的配置项
+pub const API_DIALECT_CONFIG_KEY: &str = "api_dialect";
+
+/// 可显式选择的 API 协议，决定使用哪个 genai 适配器构建请求和解析响应
+pub const API_DIALECTS: &[(&str, AdapterKind)] = &[
+    ("openai", AdapterKind::OpenAI),
+    ("anthropic", AdapterKind::Anthropic),
+    ("gemini", AdapterKind::Gemini),
+    ("cohere", AdapterKind::Cohere),
+    ("ollama", AdapterKind::Ollama),
+];
+
+/// 解析 API 协议配置，空值或 `auto` 表示按 API 类型/模型名自动推断
+pub fn parse_api_dialect(value: &str) -> Result<Option<AdapterKind>, AppError> {
+    let dialect = value.trim().to_lowercase();
+    if dialect.is_empty() || dialect == "auto" {
+        return Ok(None);
+    }
+    API_DIALECTS.iter().find(|(name, _)| *name == dialect).map(|(_, kind)| Some(*kind)).ok_or_else(
+        || {
+            let supported: Vec<&str> = API_DIALECTS.iter().map(|(name, _)| *name).collect();
+            AppError::ProviderError(format!(
+                "不支持的 API 协议 \"{}\"，可选值: auto, {}",
+                value.trim(),
+                supported.join(", ")
+            ))
+        },
+    )
+}
+
+/// 根据提供商配置选择适配器：显式配置的 API 协议优先，否则回退到自动推断
+pub fn resolve_adapter_kind(
+    configs: &[crate::db::llm_db::LLMProviderConfig],
+    model_name: &str,
+    api_type: &str,
+) -> Result<AdapterKind, AppError> {
+    let dialect = configs
+        .iter()
+        .find(|config| config.name == API_DIALECT_CONFIG_KEY)
+        .map(|config| parse_api_dialect(&config.value))
+        .transpose()?
+        .flatten();
+    match dialect {
+        Some(adapter_kind) => {
+            debug!(?adapter_kind, api_type = %api_type, "adapter selected by api dialect");
+            Ok(adapter_kind)
+        }
+        None => Ok(infer_adapter_kind(model_name, api_type)),
     }
 }
 
@@ -92,7 +243,9 @@ pub fn create_client_with_config(
         std::collections::HashMap<String, crate::db::system_db::FeatureConfig>,
     >,
 ) -> Result<Client, AppError> {
-    let adapter_kind = infer_adapter_kind(model_name, api_type);
+    let adapter_kind = resolve_adapter_kind(configs, model_name, api_type)?;
+    let azure =
+        is_azure_api_type(api_type).then(|| AzureConfig::from_configs(configs)).transpose()?;
 
     let mut api_key = String::new();
     let mut endpoint_opt: Option<String> = None;
@@ -111,14 +264,30 @@ pub fn create_client_with_config(
             _ => {}
         }
     }
+    if let Some(azure) = azure.as_ref() {
+        debug!(
+            resource_name = %azure.resource_name,
+            api_version = %azure.api_version,
+            deployments = azure.deployments.len(),
+            "azure provider configured"
+        );
+        if let Some(base_url) = azure.base_url() {
+            endpoint_opt = Some(base_url);
+        }
+    }
 
     // 构建 WebConfig 配置代理和超时
     let mut web_config = WebConfig::default();
+    // 开启连接保活时需要用相同的超时、代理和 headers 构建 reqwest 客户端
+    let mut applied_timeout = None;
+    let mut applied_proxy = None;
+    let mut applied_headers = HeaderMap::new();
 
     // 配置超时
     if let Some(timeout_secs) = request_timeout {
         if timeout_secs > 0 {
             let timeout = Duration::from_secs(timeout_secs);
+            applied_timeout = Some(timeout);
             if is_stream {
                 web_config = web_config.with_read_timeout(timeout);
                 info!(timeout_secs, "stream read timeout configured");
@@ -136,6 +305,7 @@ pub fn create_client_with_config(
                 match web_config.clone().with_all_proxy_url(proxy_url) {
                     Ok(config_with_proxy) => {
                         web_config = config_with_proxy;
+                        applied_proxy = Some(proxy_url.to_string());
                         info!(proxy_url = %proxy_url, "proxy configured");
                     }
                     Err(e) => {
@@ -146,8 +316,11 @@ pub fn create_client_with_config(
         }
     }
 
-    // 配置自定义 headers
-    let custom_headers = get_custom_headers_from_config(config_feature_map);
+    // 配置自定义 headers；Azure 使用 api-key header 认证
+    let mut custom_headers = get_custom_headers_from_config(config_feature_map);
+    if azure.is_some() && !api_key.is_empty() {
+        custom_headers.insert("api-key".to_string(), api_key.clone());
+    }
     if !custom_headers.is_empty() {
         let mut headers = HeaderMap::new();
         for (key, value) in custom_headers {
@@ -157,10 +330,18 @@ pub fn create_client_with_config(
                 }
             }
         }
+        applied_headers = headers.clone();
         web_config = web_config.with_default_headers(headers);
         info!("custom headers configured");
     }
 
+    let provider_id = configs.first().map(|config| config.llm_provider_id);
+    let keep_alive_endpoint = endpoint_opt
+        .clone()
+        .filter(|ep| ep.starts_with("http"))
+        .unwrap_or_else(|| get_default_endpoint(adapter_kind).to_string());
+    let keep_alive = keep_alive_settings(configs, adapter_kind, &keep_alive_endpoint);
+
     // 克隆值以便在闭包中使用
     let api_key_clone = api_key.clone();
     let endpoint_clone = endpoint_opt.clone();
@@ -185,7 +366,19 @@ pub fn create_client_with_config(
             };
 
             let auth = AuthData::from_single(api_key_clone.clone());
-            let model = ModelIden::new(adapter_kind, model.model_name);
+            // Azure 按部署名路由，将模型代码映射为部署名；经典接口的部署名还要写进地址
+            let (endpoint, model) = match azure.as_ref() {
+                Some(azure) => {
+                    let deployment = azure.deployment_for(&model.model_name);
+                    let endpoint = if azure.uses_deployment_urls() {
+                        Endpoint::from_owned(azure.deployment_url(endpoint.base_url(), &deployment))
+                    } else {
+                        endpoint
+                    };
+                    (endpoint, ModelIden::new(adapter_kind, deployment))
+                }
+                None => (endpoint, ModelIden::new(adapter_kind, model.model_name)),
+            };
 
             debug!(?endpoint, ?model, "resolved service target");
 
@@ -193,10 +386,57 @@ pub fn create_client_with_config(
         },
     );
 
-    let client = Client::builder()
-        .with_service_target_resolver(target_resolver)
-        .with_web_config(web_config)
-        .build();
+    let client_builder = Client::builder().with_service_target_resolver(target_resolver);
+    let client = match (provider_id, keep_alive) {
+        (Some(provider_id), Some(settings)) => {
+            let mut header_fingerprint: Vec<String> = applied_headers
+                .iter()
+                .map(|(name, value)| format!("{}={:?}", name, value))
+                .collect();
+            header_fingerprint.sort();
+            let fingerprint = format!(
+                "{}|{:?}|{:?}|{}",
+                keep_alive_endpoint,
+                applied_proxy,
+                applied_timeout,
+                header_fingerprint.join(",")
+            );
+            let reqwest_client = get_or_create_warm_client(
+                provider_id,
+                is_stream,
+                &fingerprint,
+                &keep_alive_endpoint,
+                &settings,
+                || {
+                    let mut builder = settings.apply(reqwest::Client::builder());
+                    if let Some(timeout) = applied_timeout {
+                        builder = if is_stream {
+                            builder.read_timeout(timeout)
+                        } else {
+                            builder.timeout(timeout)
+                        };
+                    }
+                    if let Some(proxy_url) = applied_proxy.as_deref() {
+                        builder = builder
+                            .proxy(reqwest::Proxy::all(proxy_url).map_err(|e| e.to_string())?);
+                    }
+                    if !applied_headers.is_empty() {
+                        builder = builder.default_headers(applied_headers.clone());
+                    }
+                    builder.build().map_err(|e| e.to_string())
+                },
+            )
+            .map_err(AppError::ProviderError)?;
+            debug!(provider_id, "using keep-alive client");
+            client_builder.with_reqwest(reqwest_client).build()
+        }
+        (provider_id, _) => {
+            if let Some(provider_id) = provider_id {
+                stop_keep_alive(provider_id);
+            }
+            client_builder.with_web_config(web_config).build()
+        }
+    };
 
     Ok(client)
 }
@@ -1,18 +1,34 @@
 use std::collections::BTreeMap;
+use std::fs;
 use std::io::{BufReader, Cursor};
-use std::sync::OnceLock;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
 
 use crate::FeatureConfigState;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use syntect::dumps::{from_reader, from_uncompressed_data};
+use syntect::easy::HighlightLines;
+use syntect::escape::Escape;
 use syntect::highlighting::{Color, Theme, ThemeSet};
-use syntect::html::highlighted_html_for_string;
-use syntect::parsing::{Scope, SyntaxSet};
-use tracing::debug;
+use syntect::html::{
+    highlighted_html_for_string, start_highlighted_html_snippet, styled_line_to_highlighted_html,
+    IncludeBackground,
+};
+use syntect::parsing::{Scope, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+use tauri::Manager;
+use tracing::{debug, info, warn};
 
 static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
 static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+/// 用户导入的 .tmTheme 主题，与内置主题分开存放，解析失败的文件不会进入这里。
+/// 以 Arc 存放，高亮时只增加引用计数，不必每次克隆整个主题
+static CUSTOM_THEMES: RwLock<BTreeMap<String, Arc<Theme>>> = RwLock::new(BTreeMap::new());
+
+const CUSTOM_THEME_DIR: &str = "syntect_themes";
+const CUSTOM_THEME_EXTENSION: &str = "tmTheme";
 
 #[derive(Debug, Deserialize)]
 struct BatLazyThemeSet {
@@ -26,8 +42,8 @@ struct BatLazyTheme {
 
 #[derive(Debug, Serialize)]
 pub struct SyntectThemeInfo {
-    name: String,
-    is_dark: bool,
+    pub name: String,
+    pub is_dark: bool,
 }
 
 fn is_zlib_compressed(data: &[u8]) -> bool {
@@ -192,9 +208,98 @@ fn is_blocked_theme_name(name: &str) -> bool {
     lower == "ansi" || lower.starts_with("ansi-") || lower.starts_with("base16")
 }
 
-fn pick_theme_by_name(name: &str) -> Option<&'static syntect::highlighting::Theme> {
+/// 选中的主题：内置主题直接借用，自定义主题共享 Arc
+enum ThemeRef {
+    Builtin(&'static Theme),
+    Custom(Arc<Theme>),
+}
+
+impl Deref for ThemeRef {
+    type Target = Theme;
+
+    fn deref(&self) -> &Theme {
+        match self {
+            ThemeRef::Builtin(theme) => *theme,
+            ThemeRef::Custom(theme) => theme.as_ref(),
+        }
+    }
+}
+
+fn pick_theme_by_name(name: &str) -> Option<ThemeRef> {
     let ts = theme_set();
-    ts.themes.get(name)
+    if let Some(theme) = ts.themes.get(name) {
+        return Some(ThemeRef::Builtin(theme));
+    }
+    let custom = CUSTOM_THEMES.read().unwrap_or_else(|e| e.into_inner());
+    custom.get(name).cloned().map(ThemeRef::Custom)
+}
+
+/// 解析 .tmTheme 文件，主题名取文件中的 name，缺失时使用文件名
+pub fn parse_tm_theme(path: &Path) -> Result<(String, Theme), String> {
+    let theme = ThemeSet::get_theme(path)
+        .map_err(|e| format!("主题文件解析失败 {}: {}", path.display(), e))?;
+    let name = theme
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .or_else(|| path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string))
+        .ok_or_else(|| format!("无法确定主题名称: {}", path.display()))?;
+    Ok((name, theme))
+}
+
+/// 把主题加入自定义主题列表，同名的自定义主题会被替换，内置主题不允许覆盖
+pub fn register_custom_theme(name: String, theme: Theme) -> Result<(), String> {
+    if theme_set().themes.contains_key(&name) {
+        return Err(format!("主题名称与内置主题重复: {}", name));
+    }
+    CUSTOM_THEMES.write().unwrap_or_else(|e| e.into_inner()).insert(name, Arc::new(theme));
+    Ok(())
+}
+
+/// 导入 .tmTheme 文件：先完整解析，成功后再复制到 `theme_dir` 并加入主题列表
+pub fn import_theme_file(source: &Path, theme_dir: &Path) -> Result<SyntectThemeInfo, String> {
+    let (name, theme) = parse_tm_theme(source)?;
+    if theme_set().themes.contains_key(&name) {
+        return Err(format!("主题名称与内置主题重复: {}", name));
+    }
+
+    fs::create_dir_all(theme_dir).map_err(|e| format!("无法创建主题目录: {}", e))?;
+    let file_name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.') { c } else { '_' })
+        .collect();
+    let target = theme_dir.join(format!("{}.{}", file_name, CUSTOM_THEME_EXTENSION));
+    fs::copy(source, &target).map_err(|e| format!("无法保存主题文件: {}", e))?;
+
+    let info = SyntectThemeInfo { name: name.clone(), is_dark: theme_is_dark(&name, &theme) };
+    register_custom_theme(name, theme)?;
+    info!(theme_name = %info.name, path = %target.display(), "Custom syntect theme imported");
+    Ok(info)
+}
+
+/// 启动时加载 `theme_dir` 中已导入的主题，无法解析的文件只记录日志并跳过
+pub fn load_custom_themes(theme_dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(theme_dir) else {
+        return 0;
+    };
+    let mut loaded = 0usize;
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        if !path.extension().is_some_and(|ext| ext == CUSTOM_THEME_EXTENSION) {
+            continue;
+        }
+        match parse_tm_theme(&path).and_then(|(name, theme)| register_custom_theme(name, theme)) {
+            Ok(_) => loaded += 1,
+            Err(error) => warn!(path = %path.display(), %error, "Failed to load custom theme"),
+        }
+    }
+    debug!(loaded, "Custom syntect themes loaded");
+    loaded
+}
+
+pub fn custom_theme_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join(CUSTOM_THEME_DIR))
 }
 
 fn map_ui_theme_to_syntect(ui: &str, _is_dark: bool) -> Option<&'static str> {
@@ -281,24 +386,10 @@ fn map_lang_alias(token: &str) -> String {
     }
 }
 
-pub fn highlight_code_for_export(
-    lang: &str,
-    code: &str,
-    is_dark: bool,
-    theme_hint: Option<&str>,
-) -> Result<String, String> {
+/// 按语言标记查找语法：依次尝试 scope、token、扩展名、名称，都找不到时使用纯文本
+fn resolve_syntax(lang: &str) -> &'static SyntaxReference {
     let ss = syntax_set();
-
-    let mut theme_ref: Option<&'static syntect::highlighting::Theme> = None;
-    if let Some(hint) = theme_hint {
-        if let Some(theme) = pick_theme_by_name(hint) {
-            theme_ref = Some(theme);
-        } else if let Some(mapped_name) = map_ui_theme_to_syntect(hint, is_dark) {
-            theme_ref = pick_theme_by_name(mapped_name);
-        }
-    }
-    let theme = theme_ref.unwrap_or_else(|| pick_theme(is_dark));
-
+    // Try by token, then by extension, else plain text
     let raw_token = normalize_lang_token(lang);
     let mapped = map_lang_alias(raw_token);
     let token_lower = mapped.to_lowercase();
@@ -318,8 +409,177 @@ pub fn highlight_code_for_export(
         syntax_scope = %syntax.scope,
         "Highlight syntax resolved"
     );
+    syntax
+}
+
+/// 识别 diff 语言标记：`diff` / `patch` 返回 `Some(None)`，`diff-rust` 这类带内部语言的返回 `Some(Some("rust"))`
+fn parse_diff_lang(lang: &str) -> Option<Option<String>> {
+    let token = normalize_lang_token(lang).to_lowercase();
+    match token.as_str() {
+        "diff" | "patch" | "udiff" => Some(None),
+        _ => token
+            .strip_prefix("diff-")
+            .or_else(|| token.strip_prefix("diff:"))
+            .filter(|inner| !inner.is_empty())
+            .map(|inner| Some(inner.to_string())),
+    }
+}
+
+fn render_code_html(lang: &str, code: &str, theme: &Theme) -> Result<String, String> {
+    if let Some(inner_lang) = parse_diff_lang(lang) {
+        return highlight_diff_html(code, inner_lang.as_deref(), theme);
+    }
+    let syntax = resolve_syntax(lang);
+    highlighted_html_for_string(code, syntax_set(), syntax, theme).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffLineKind {
+    Header,
+    Hunk,
+    Added,
+    Removed,
+    Context,
+}
+
+impl DiffLineKind {
+    fn class_name(self) -> &'static str {
+        match self {
+            DiffLineKind::Header => "diff-header",
+            DiffLineKind::Hunk => "diff-hunk",
+            DiffLineKind::Added => "diff-added",
+            DiffLineKind::Removed => "diff-removed",
+            DiffLineKind::Context => "diff-context",
+        }
+    }
+
+    /// 行背景与标记颜色，半透明背景在深浅色主题上都能看清
+    fn colors(self) -> (Option<&'static str>, Option<&'static str>) {
+        match self {
+            DiffLineKind::Added => (Some("rgba(46,160,67,0.18)"), Some("#2ea043")),
+            DiffLineKind::Removed => (Some("rgba(248,81,73,0.18)"), Some("#f85149")),
+            DiffLineKind::Hunk => (Some("rgba(56,139,253,0.12)"), Some("#388bfd")),
+            DiffLineKind::Header => (None, Some("#8b949e")),
+            DiffLineKind::Context => (None, None),
+        }
+    }
+}
+
+/// 从 `+++ b/src/main.rs` 这类文件头推断内部语言
+fn syntax_from_diff_header(line: &str) -> Option<&'static SyntaxReference> {
+    let path = line.strip_prefix("+++ ")?.split('\t').next()?.trim();
+    let path = path.strip_prefix("b/").unwrap_or(path);
+    if path == "/dev/null" {
+        return None;
+    }
+    let extension = Path::new(path).extension()?.to_str()?;
+    syntax_set().find_syntax_by_extension(extension)
+}
+
+/// 渲染 unified diff：新增、删除、上下文行分别着色，非标记部分按内部语言高亮
+///
+/// `inner_lang` 为空时根据 `+++` 文件头推断语言，推断不到时按纯文本处理
+pub fn highlight_diff_html(
+    code: &str,
+    inner_lang: Option<&str>,
+    theme: &Theme,
+) -> Result<String, String> {
+    let ss = syntax_set();
+    let explicit_syntax = inner_lang.map(resolve_syntax);
+    let mut syntax = explicit_syntax.unwrap_or_else(|| ss.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut in_hunk = false;
+
+    let (mut html, _) = start_highlighted_html_snippet(theme);
+    html.push_str("<code class=\"diff\">");
+    for line in LinesWithEndings::from(code) {
+        let kind = if line.starts_with("@@") {
+            in_hunk = true;
+            // 每个 hunk 重新开始高亮，避免上一段未闭合的字符串、注释影响后续内容
+            highlighter = HighlightLines::new(syntax, theme);
+            DiffLineKind::Hunk
+        } else if !in_hunk || line.starts_with("diff ") {
+            in_hunk = false;
+            if explicit_syntax.is_none() && line.starts_with("+++ ") {
+                syntax =
+                    syntax_from_diff_header(line).unwrap_or_else(|| ss.find_syntax_plain_text());
+            }
+            DiffLineKind::Header
+        } else if line.starts_with('+') {
+            DiffLineKind::Added
+        } else if line.starts_with('-') {
+            DiffLineKind::Removed
+        } else if line.starts_with('\\') {
+            DiffLineKind::Header
+        } else {
+            DiffLineKind::Context
+        };
+
+        let (background, marker_color) = kind.colors();
+        html.push_str("<span class=\"diff-line ");
+        html.push_str(kind.class_name());
+        html.push_str("\" style=\"display:block;");
+        if let Some(background) = background {
+            html.push_str("background-color:");
+            html.push_str(background);
+            html.push(';');
+        }
+        html.push_str("\">");
+
+        match kind {
+            DiffLineKind::Added | DiffLineKind::Removed | DiffLineKind::Context => {
+                let (marker, content) = match line.chars().next() {
+                    Some(c @ ('+' | '-' | ' ')) => line.split_at(c.len_utf8()),
+                    _ => ("", line),
+                };
+                push_marker(&mut html, marker, marker_color);
+                let regions = highlighter.highlight_line(content, ss).map_err(|e| e.to_string())?;
+                html.push_str(
+                    &styled_line_to_highlighted_html(&regions, IncludeBackground::No)
+                        .map_err(|e| e.to_string())?,
+                );
+            }
+            DiffLineKind::Header | DiffLineKind::Hunk => push_marker(&mut html, line, marker_color),
+        }
+        html.push_str("</span>");
+    }
+    html.push_str("</code></pre>\n");
+    Ok(html)
+}
+
+fn push_marker(html: &mut String, text: &str, color: Option<&str>) {
+    if text.is_empty() {
+        return;
+    }
+    match color {
+        Some(color) => {
+            html.push_str("<span style=\"color:");
+            html.push_str(color);
+            html.push_str(";\">");
+            html.push_str(&Escape(text).to_string());
+            html.push_str("</span>");
+        }
+        None => html.push_str(&Escape(text).to_string()),
+    }
+}
+
+pub fn highlight_code_for_export(
+    lang: &str,
+    code: &str,
+    is_dark: bool,
+    theme_hint: Option<&str>,
+) -> Result<String, String> {
+    let mut theme_ref: Option<ThemeRef> = None;
+    if let Some(hint) = theme_hint {
+        if let Some(theme) = pick_theme_by_name(hint) {
+            theme_ref = Some(theme);
+        } else if let Some(mapped_name) = map_ui_theme_to_syntect(hint, is_dark) {
+            theme_ref = pick_theme_by_name(mapped_name);
+        }
+    }
+    let theme = theme_ref.unwrap_or_else(|| ThemeRef::Builtin(pick_theme(is_dark)));
 
-    highlighted_html_for_string(code, ss, syntax, theme).map_err(|e| e.to_string())
+    render_code_html(lang, code, &theme)
 }
 
 #[tauri::command]
@@ -330,7 +590,6 @@ pub fn highlight_code(
     theme_hint: Option<String>,
     feature_config_state: tauri::State<'_, FeatureConfigState>,
 ) -> Result<String, String> {
-    let ss = syntax_set();
     // Determine theme in priority:
     // 1) Explicit theme_hint that directly matches syntect theme name
     // 2) Map theme_hint UI id -> syntect theme
@@ -338,7 +597,7 @@ pub fn highlight_code(
     // 4) Fallback candidates by dark/light
 
     // Try theme_hint
-    let mut theme_ref: Option<&'static syntect::highlighting::Theme> = None;
+    let mut theme_ref: Option<ThemeRef> = None;
     if let Some(ref hint) = theme_hint {
         if let Some(t) = pick_theme_by_name(hint) {
             theme_ref = Some(t);
@@ -353,54 +612,49 @@ pub fn highlight_code(
             let key = if is_dark { "code_theme_dark" } else { "code_theme_light" };
             if let Some(fc) = display_map.get(key) {
                 let ui_id = fc.value.as_str();
-                if let Some(mapped_name) = map_ui_theme_to_syntect(ui_id, is_dark) {
+                if let Some(t) = pick_theme_by_name(ui_id) {
+                    theme_ref = Some(t);
+                } else if let Some(mapped_name) = map_ui_theme_to_syntect(ui_id, is_dark) {
                     theme_ref = pick_theme_by_name(mapped_name);
                 }
             }
         }
         drop(config_map_guard);
     }
-    let theme = theme_ref.unwrap_or_else(|| pick_theme(is_dark));
-
-    // Figure out theme name for logging
-    let ts = theme_set();
-
-    // Try by token, then by extension, else plain text
-    let raw_token = normalize_lang_token(&lang);
-    let mapped = map_lang_alias(raw_token);
-    let token_lower = mapped.to_lowercase();
-    let scope_match = Scope::new(&mapped).ok().and_then(|scope| ss.find_syntax_by_scope(scope));
-    let syntax = scope_match
-        .or_else(|| ss.find_syntax_by_token(&mapped))
-        .or_else(|| ss.find_syntax_by_token(&token_lower))
-        .or_else(|| ss.find_syntax_by_extension(&mapped))
-        .or_else(|| ss.find_syntax_by_extension(&token_lower))
-        .or_else(|| ss.find_syntax_by_name(&mapped))
-        .or_else(|| ss.find_syntax_by_name(&token_lower))
-        .unwrap_or_else(|| ss.find_syntax_plain_text());
-    debug!(
-        raw_lang = %lang,
-        mapped_lang = %mapped,
-        syntax_name = %syntax.name,
-        syntax_scope = %syntax.scope,
-        "Highlight syntax resolved"
-    );
+    let theme = theme_ref.unwrap_or_else(|| ThemeRef::Builtin(pick_theme(is_dark)));
 
     // Use helper to generate inline-styled HTML within <pre><code> ... </code></pre>
-    let html = highlighted_html_for_string(&code, ss, syntax, theme).map_err(|e| e.to_string())?;
-
-    Ok(html)
+    render_code_html(&lang, &code, &theme)
 }
 
 #[tauri::command]
 pub fn list_syntect_themes() -> Vec<SyntectThemeInfo> {
     let ts = theme_set();
+    let custom = CUSTOM_THEMES.read().unwrap_or_else(|e| e.into_inner());
     ts.themes
         .iter()
         .filter(|(name, _)| !is_blocked_theme_name(name))
+        .chain(
+            custom
+                .iter()
+                .filter(|(name, _)| !ts.themes.contains_key(*name))
+                .map(|(name, theme)| (name, theme.as_ref())),
+        )
         .map(|(name, theme)| SyntectThemeInfo {
             name: name.clone(),
             is_dark: theme_is_dark(name, theme),
         })
         .collect()
 }
+
+/// 导入用户的 .tmTheme 主题文件，保存到数据目录后可在 `list_syntect_themes` 与 `highlight_code` 中使用
+#[tauri::command]
+pub async fn import_syntect_theme(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<SyntectThemeInfo, String> {
+    let theme_dir = custom_theme_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || import_theme_file(Path::new(&path), &theme_dir))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -1,13 +1,22 @@
-use crate::api::ai::config::get_network_proxy_from_config;
+use crate::api::ai::chat::{extract_http_error_details, get_user_friendly_error_message};
+use crate::api::ai::config::{
+    get_model_list_cache_ttl_from_config, get_network_proxy_from_config,
+    get_request_timeout_from_config,
+};
+use crate::api::ai::keep_alive::{is_keep_alive_active, stop_keep_alive};
 use crate::api::genai_client;
-use crate::db::llm_db::LLMDatabase;
+use crate::db::assistant_db::AssistantDatabase;
+use crate::db::llm_db::{LLMDatabase, LLMEnvironmentProfile, LLMModelPrice};
+use crate::state::model_list_cache::ModelListCacheState;
+use crate::state::model_select_cache::ModelSelectCacheState;
 use crate::utils::share_utils::{decrypt_provider_data, encrypt_provider_data, ProviderShareData};
-use crate::FeatureConfigState;
+use crate::{FeatureConfigState, NameCacheState};
+use genai::chat::{ChatMessage, ChatOptions, ChatRequest};
 use genai::Modality;
 use serde::{Deserialize, Serialize};
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LlmProvider {
     pub id: i64,
     pub name: String,
@@ -15,6 +24,9 @@ pub struct LlmProvider {
     pub description: String,
     pub is_official: bool,
     pub is_enabled: bool,
+    /// 是否有保活连接（提供商开启了 keep_alive 且已发起过请求）
+    #[serde(default)]
+    pub keep_alive_active: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,13 +51,42 @@ pub struct LlmProviderConfig {
     pub is_addition: Option<bool>,
 }
 
+/// 提供商或模型发生变更后清空选择列表缓存
+async fn invalidate_model_select_cache(app_handle: &tauri::AppHandle) {
+    if let Some(cache_state) = app_handle.try_state::<ModelSelectCacheState>() {
+        cache_state.invalidate().await;
+    }
+}
+
+/// 修改后需要重新获取远程模型列表的提供商配置项
+const MODEL_LIST_CACHE_KEYS: [&str; 3] = ["endpoint", "api_key", "api_dialect"];
+
+/// 提供商远程模型列表中的一项，缓存在 `ModelListCacheState` 中
+#[derive(Clone, Debug)]
+pub struct RemoteModel {
+    pub name: String,
+    pub code: String,
+    pub description: String,
+    pub vision_support: bool,
+    pub audio_support: bool,
+    pub video_support: bool,
+}
+
 #[tauri::command]
 pub async fn get_llm_providers(app_handle: tauri::AppHandle) -> Result<Vec<LlmProvider>, String> {
     let db = LLMDatabase::new(&app_handle).map_err(|e: rusqlite::Error| e.to_string())?;
     let providers = db.get_llm_providers().map_err(|e| e.to_string())?;
     let mut result = Vec::new();
     for (id, name, api_type, description, is_official, is_enabled) in providers {
-        result.push(LlmProvider { id, name, api_type, description, is_official, is_enabled });
+        result.push(LlmProvider {
+            id,
+            name,
+            api_type,
+            description,
+            is_official,
+            is_enabled,
+            keep_alive_active: is_keep_alive_active(id),
+        });
     }
     Ok(result)
 }
@@ -56,14 +97,31 @@ pub async fn get_llm_providers(app_handle: tauri::AppHandle) -> Result<Vec<LlmPr
 #[tauri::command]
 pub async fn get_filtered_providers(
     app_handle: tauri::AppHandle,
+    cache_state: tauri::State<'_, ModelSelectCacheState>,
     assistant_type: i64,
 ) -> Result<Vec<LlmProvider>, String> {
+    if let Some(mut cached) = cache_state.providers.get(&assistant_type).await {
+        // 保活状态随运行时变化，不能使用缓存中的值
+        for provider in cached.iter_mut() {
+            provider.keep_alive_active = is_keep_alive_active(provider.id);
+        }
+        return Ok(cached);
+    }
     let db = LLMDatabase::new(&app_handle).map_err(|e: rusqlite::Error| e.to_string())?;
     let providers = db.get_filtered_providers(assistant_type).map_err(|e| e.to_string())?;
     let mut result = Vec::new();
     for (id, name, api_type, description, is_official, is_enabled) in providers {
-        result.push(LlmProvider { id, name, api_type, description, is_official, is_enabled });
+        result.push(LlmProvider {
+            id,
+            name,
+            api_type,
+            description,
+            is_official,
+            is_enabled,
+            keep_alive_active: is_keep_alive_active(id),
+        });
     }
+    cache_state.providers.insert(assistant_type, result.clone()).await;
     Ok(result)
 }
 
@@ -75,6 +133,7 @@ pub async fn add_llm_provider(
 ) -> Result<(), String> {
     let db = LLMDatabase::new(&app).map_err(|e| e.to_string())?;
     db.add_llm_provider(&*name, &*api_type, "", false, false).map_err(|e| e.to_string())?;
+    invalidate_model_select_cache(&app).await;
     Ok(())
 }
 
@@ -90,6 +149,8 @@ pub async fn update_llm_provider(
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     db.update_llm_provider(id, &*name, &*api_type, &*description, is_enabled)
         .map_err(|e| e.to_string())?;
+    stop_keep_alive(id);
+    invalidate_model_select_cache(&app_handle).await;
     Ok(())
 }
 
@@ -100,6 +161,8 @@ pub async fn delete_llm_provider(
 ) -> Result<(), String> {
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     db.delete_llm_provider(llm_provider_id).map_err(|e| e.to_string())?;
+    stop_keep_alive(llm_provider_id);
+    invalidate_model_select_cache(&app_handle).await;
     Ok(())
 }
 
@@ -133,6 +196,13 @@ pub async fn update_llm_provider_config(
 ) -> Result<(), String> {
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     db.update_llm_provider_config(llm_provider_id, &*name, &*value).map_err(|e| e.to_string())?;
+    // 配置变化后停止旧的保活任务，下次请求按新配置决定是否重新建立
+    stop_keep_alive(llm_provider_id);
+    if MODEL_LIST_CACHE_KEYS.contains(&name.as_str()) {
+        if let Some(cache_state) = app_handle.try_state::<ModelListCacheState>() {
+            cache_state.invalidate(llm_provider_id).await;
+        }
+    }
     Ok(())
 }
 
@@ -169,27 +239,122 @@ pub async fn get_llm_models(
     Ok(result)
 }
 
+/// Azure 提供商已配置的部署，返回 (模型代码, 描述)
+fn azure_deployment_models(
+    configs: &[crate::db::llm_db::LLMProviderConfig],
+) -> Result<Vec<(String, String)>, String> {
+    let azure = genai_client::AzureConfig::from_configs(configs).map_err(|e| e.to_string())?;
+    Ok(azure
+        .deployment_list()
+        .into_iter()
+        .map(|(code, deployment)| (code, format!("Azure deployment: {}", deployment)))
+        .collect())
+}
+
 #[tauri::command]
 pub async fn fetch_model_list(
     app_handle: tauri::AppHandle,
     llm_provider_id: i64,
+    force_refresh: Option<bool>,
 ) -> Result<Vec<LlmModel>, String> {
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     let llm_provider = db.get_llm_provider(llm_provider_id).map_err(|e| e.to_string())?;
     let llm_provider_config =
         db.get_llm_provider_config(llm_provider_id).map_err(|e| e.to_string())?;
 
-    // 获取代理配置
+    // Azure 没有可用的模型列表接口，使用已配置的部署
+    if genai_client::is_azure_api_type(&llm_provider.api_type) {
+        db.delete_llm_model_by_provider(llm_provider_id).map_err(|e| e.to_string())?;
+        let mut result = Vec::new();
+        for (code, description) in azure_deployment_models(&llm_provider_config)? {
+            db.add_llm_model(&code, llm_provider_id, &code, &description, false, false, false)
+                .map_err(|e| e.to_string())?;
+            result.push(LlmModel {
+                id: 0,
+                name: code.clone(),
+                llm_provider_id,
+                code,
+                description,
+                vision_support: false,
+                audio_support: false,
+                video_support: false,
+            });
+        }
+        invalidate_model_select_cache(&app_handle).await;
+        return Ok(result);
+    }
+
+    let models = list_remote_models(
+        &app_handle,
+        llm_provider_id,
+        &llm_provider.api_type,
+        &llm_provider_config,
+        force_refresh.unwrap_or(false),
+    )
+    .await?;
+
+    db.delete_llm_model_by_provider(llm_provider_id).map_err(|e| e.to_string())?;
+    let mut result = Vec::new();
+    for model in models {
+        db.add_llm_model(
+            &model.name,
+            llm_provider_id,
+            &model.code,
+            &model.description,
+            model.vision_support,
+            model.audio_support,
+            model.video_support,
+        )
+        .map_err(|e| e.to_string())?;
+
+        result.push(LlmModel {
+            id: 0,
+            name: model.name,
+            llm_provider_id,
+            code: model.code,
+            description: model.description,
+            vision_support: model.vision_support,
+            audio_support: model.audio_support,
+            video_support: model.video_support,
+        });
+    }
+
+    invalidate_model_select_cache(&app_handle).await;
+    Ok(result)
+}
+
+/// 获取提供商的远程模型列表，缓存未过期且未要求强制刷新时直接返回缓存
+async fn list_remote_models(
+    app_handle: &tauri::AppHandle,
+    llm_provider_id: i64,
+    api_type: &str,
+    llm_provider_config: &[crate::db::llm_db::LLMProviderConfig],
+    force_refresh: bool,
+) -> Result<Vec<RemoteModel>, String> {
+    let cache_state = app_handle.try_state::<ModelListCacheState>();
     let feature_config_state = app_handle.state::<FeatureConfigState>();
     let config_feature_map = feature_config_state.config_feature_map.lock().await;
+    let cache_ttl = get_model_list_cache_ttl_from_config(&config_feature_map);
+
+    if !force_refresh {
+        if let Some(cache_state) = &cache_state {
+            if let Some(models) = cache_state.get(llm_provider_id, cache_ttl).await {
+                tracing::debug!(llm_provider_id, count = models.len(), "model list cache hit");
+                return Ok(models);
+            }
+        }
+    }
+
+    // 获取代理配置
     let network_proxy = get_network_proxy_from_config(&config_feature_map);
     let proxy_enabled = network_proxy.is_some();
+    tracing::info!(llm_provider_id, ?network_proxy, proxy_enabled, "fetching remote model list");
 
     // 使用共用的客户端创建函数
     let client = genai_client::create_client_with_config(
-        &llm_provider_config,
+        llm_provider_config,
         "",
-        &llm_provider.api_type,
+        api_type,
         network_proxy.as_deref(),
         proxy_enabled,
         None,
@@ -197,47 +362,196 @@ pub async fn fetch_model_list(
         &config_feature_map,
     )
     .map_err(|e| e.to_string())?;
+    drop(config_feature_map);
 
-    let adapter_kind = genai_client::infer_adapter_kind_simple(&llm_provider.api_type);
-
-    match client.all_models(adapter_kind).await {
-        Ok(models) => {
-            db.delete_llm_model_by_provider(llm_provider_id).map_err(|e| e.to_string())?;
-
-            let mut result = Vec::new();
-            for model in &models {
-                let model = LlmModel {
-                    id: 0,
-                    name: model.name.to_string(),
-                    llm_provider_id,
-                    code: model.id.to_string(),
-                    description: format!("Model: {}", model.name),
-                    vision_support: model.supports_input_modality(&Modality::Image),
-                    audio_support: model.supports_input_modality(&Modality::Audio),
-                    video_support: model.supports_input_modality(&Modality::Video),
-                };
-
-                db.add_llm_model(
-                    &model.name,
-                    llm_provider_id,
-                    &model.code,
-                    &model.description,
-                    model.vision_support,
-                    model.audio_support,
-                    model.video_support,
-                )
-                .map_err(|e| e.to_string())?;
+    let adapter_kind = genai_client::resolve_adapter_kind(llm_provider_config, "", api_type)
+        .map_err(|e| e.to_string())?;
+    tracing::info!(llm_provider_id, "list remote models with adapter_kind: {:?}", adapter_kind);
+
+    let models = client.all_models(adapter_kind).await.map_err(|e| {
+        tracing::error!(error = ?e, "获取模型列表错误（详细）");
+        tracing::error!(error = %e, "获取模型列表错误");
+        e.to_string()
+    })?;
+    let models: Vec<RemoteModel> = models
+        .iter()
+        .map(|model| RemoteModel {
+            name: model.name.to_string(),
+            code: model.id.to_string(),
+            description: format!("Model: {}", model.name),
+            vision_support: model.supports_input_modality(&Modality::Image),
+            audio_support: model.supports_input_modality(&Modality::Audio),
+            video_support: model.supports_input_modality(&Modality::Video),
+        })
+        .collect();
 
-                result.push(model);
-            }
+    if let Some(cache_state) = &cache_state {
+        cache_state.insert(llm_provider_id, models.clone()).await;
+        // 选择列表中带有最近获取时间，需要一并刷新
+        invalidate_model_select_cache(app_handle).await;
+    }
+    Ok(models)
+}
+
+/// 提供商连通性测试失败的原因分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderErrorCategory {
+    /// 401：API Key 无效或过期
+    Auth,
+    /// 403：密钥没有对应权限
+    Permission,
+    /// 404：Base URL 或模型不存在
+    NotFound,
+    /// 429：请求过于频繁或额度不足
+    RateLimit,
+    /// 5xx：服务端异常
+    Server,
+    /// 其他 4xx：请求参数或配置有误
+    InvalidRequest,
+    /// 未收到 HTTP 响应：网络、DNS、代理或 TLS 问题
+    Network,
+    Unknown,
+}
+
+/// 根据 HTTP 状态码（优先）和错误文本归类失败原因
+pub fn categorize_provider_error(status: Option<u16>, error_text: &str) -> ProviderErrorCategory {
+    match status {
+        Some(401) => return ProviderErrorCategory::Auth,
+        Some(403) => return ProviderErrorCategory::Permission,
+        Some(404) => return ProviderErrorCategory::NotFound,
+        Some(429) => return ProviderErrorCategory::RateLimit,
+        Some(code) if code >= 500 => return ProviderErrorCategory::Server,
+        Some(code) if code >= 400 => return ProviderErrorCategory::InvalidRequest,
+        _ => {}
+    }
+    let lower = error_text.to_lowercase();
+    let network_markers = [
+        "connect",
+        "connection",
+        "dns",
+        "proxy",
+        "timed out",
+        "timeout",
+        "tls",
+        "certificate",
+        "network",
+        "unreachable",
+        "resolve",
+    ];
+    if network_markers.iter().any(|marker| lower.contains(marker)) {
+        ProviderErrorCategory::Network
+    } else {
+        ProviderErrorCategory::Unknown
+    }
+}
+
+/// 提供商连通性测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderTestResult {
+    pub success: bool,
+    pub latency_ms: u64,
+    pub status: Option<u16>,
+    pub category: Option<ProviderErrorCategory>,
+    /// 面向用户的提示
+    pub message: String,
+    /// 原始错误信息
+    pub error: Option<String>,
+    /// 测试方式：chat（1 token 补全）或 models（获取模型列表）
+    pub probe: String,
+    pub model_code: Option<String>,
+}
 
-            Ok(result)
+/// 测试提供商的凭据与网络是否可用：已配置模型时发送 1 token 的补全请求，否则请求模型列表
+#[tauri::command]
+pub async fn test_llm_provider(
+    app_handle: tauri::AppHandle,
+    provider_id: i64,
+) -> Result<ProviderTestResult, String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let llm_provider = db.get_llm_provider(provider_id).map_err(|e| e.to_string())?;
+    let llm_provider_config = db.get_llm_provider_config(provider_id).map_err(|e| e.to_string())?;
+    let model_code = db
+        .get_llm_models(provider_id.to_string())
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(_, _, _, code, _, _, _, _)| code)
+        .next();
+
+    let feature_config_state = app_handle.state::<FeatureConfigState>();
+    let config_feature_map = feature_config_state.config_feature_map.lock().await.clone();
+    let network_proxy = get_network_proxy_from_config(&config_feature_map);
+    let proxy_enabled = network_proxy.is_some();
+    let request_timeout = get_request_timeout_from_config(&config_feature_map);
+
+    let client = genai_client::create_client_with_config(
+        &llm_provider_config,
+        model_code.as_deref().unwrap_or(""),
+        &llm_provider.api_type,
+        network_proxy.as_deref(),
+        proxy_enabled,
+        Some(request_timeout),
+        false,
+        &config_feature_map,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let start = std::time::Instant::now();
+    let (probe, outcome) = match model_code.as_deref() {
+        Some(code) => {
+            let request = ChatRequest::new(vec![ChatMessage::user("ping")]);
+            let options = ChatOptions::default().with_max_tokens(1);
+            ("chat", client.exec_chat(code, request, Some(&options)).await.map(|_| ()))
+        }
+        None => {
+            let adapter_kind = genai_client::resolve_adapter_kind(
+                &llm_provider_config,
+                "",
+                &llm_provider.api_type,
+            )
+            .map_err(|e| e.to_string())?;
+            ("models", client.all_models(adapter_kind).await.map(|_| ()))
         }
+    };
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let result = match outcome {
+        Ok(()) => ProviderTestResult {
+            success: true,
+            latency_ms,
+            status: None,
+            category: None,
+            message: "连接成功".to_string(),
+            error: None,
+            probe: probe.to_string(),
+            model_code,
+        },
         Err(e) => {
-            tracing::error!(error = %e, "获取模型列表错误");
-            Err(e.to_string())
+            let status = extract_http_error_details(&e).status_code;
+            let error_text = e.to_string();
+            let category = categorize_provider_error(status, &error_text);
+            // 带上状态码再归一化，避免错误文本中缺少状态码时提示不准确
+            let mut message = get_user_friendly_error_message(&match status {
+                Some(code) => format!("{} {}", code, error_text),
+                None => error_text.clone(),
+            });
+            if category == ProviderErrorCategory::Network && proxy_enabled {
+                message.push_str("（已启用网络代理，请确认代理可用）");
+            }
+            tracing::warn!(provider_id, ?status, ?category, error = %e, "provider test failed");
+            ProviderTestResult {
+                success: false,
+                latency_ms,
+                status,
+                category: Some(category),
+                message,
+                error: Some(error_text),
+                probe: probe.to_string(),
+                model_code,
+            }
         }
-    }
+    };
+    Ok(result)
 }
 
 #[tauri::command]
@@ -250,6 +564,7 @@ pub async fn add_llm_model(
     let code_str = code.as_str();
     db.add_llm_model(code_str, llm_provider_id, code_str, code_str, false, false, false)
         .map_err(|e| e.to_string())?;
+    invalidate_model_select_cache(&app_handle).await;
     Ok(())
 }
 
@@ -261,15 +576,18 @@ pub async fn delete_llm_model(
 ) -> Result<(), String> {
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     let _ = db.delete_llm_model(llm_provider_id, code);
+    invalidate_model_select_cache(&app_handle).await;
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ModelForSelect {
-    name: String,
-    code: String,
-    id: i64,
-    llm_provider_id: i64,
+    pub name: String,
+    pub code: String,
+    pub id: i64,
+    pub llm_provider_id: i64,
+    /// 所属提供商最近一次从远程获取模型列表的时间（毫秒时间戳），本次运行未获取过时为空
+    pub models_fetched_at: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -293,6 +611,7 @@ pub struct ModelSelectionResponse {
 pub async fn preview_model_list(
     app_handle: tauri::AppHandle,
     llm_provider_id: i64,
+    force_refresh: Option<bool>,
 ) -> Result<ModelSelectionResponse, String> {
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     let llm_provider = db.get_llm_provider(llm_provider_id).map_err(|e| e.to_string())?;
@@ -305,84 +624,91 @@ pub async fn preview_model_list(
     let existing_model_codes: std::collections::HashSet<String> =
         existing_models.iter().map(|(_, _, _, code, _, _, _, _)| code.clone()).collect();
 
-    // 获取代理配置
-    let feature_config_state = app_handle.state::<FeatureConfigState>();
-    let config_feature_map = feature_config_state.config_feature_map.lock().await;
-    let network_proxy = get_network_proxy_from_config(&config_feature_map);
-    let proxy_enabled = network_proxy.is_some();
-    tracing::info!(
-        llm_provider_id,
-        ?network_proxy,
-        proxy_enabled,
-        "preview_model_list proxy config"
-    );
+    if genai_client::is_azure_api_type(&llm_provider.api_type) {
+        let available_models: Vec<ModelForSelection> =
+            azure_deployment_models(&llm_provider_config)?
+                .into_iter()
+                .map(|(code, description)| ModelForSelection {
+                    name: code.clone(),
+                    is_selected: existing_model_codes.contains(&code),
+                    code,
+                    description,
+                    vision_support: false,
+                    audio_support: false,
+                    video_support: false,
+                })
+                .collect();
+        let deployed: std::collections::HashSet<&String> =
+            available_models.iter().map(|model| &model.code).collect();
+        let missing_models =
+            existing_model_codes.iter().filter(|code| !deployed.contains(code)).cloned().collect();
+        return Ok(ModelSelectionResponse { available_models, missing_models });
+    }
 
-    // 使用共用的客户端创建函数
-    let client = genai_client::create_client_with_config(
-        &llm_provider_config,
-        "",
+    let models = list_remote_models(
+        &app_handle,
+        llm_provider_id,
         &llm_provider.api_type,
-        network_proxy.as_deref(),
-        proxy_enabled,
-        None,
-        false,
-        &config_feature_map,
+        &llm_provider_config,
+        force_refresh.unwrap_or(false),
     )
-    .map_err(|e| e.to_string())?;
-    tracing::info!(llm_provider_id, "created client for preview_model_list: {:?}", client);
-
-    let adapter_kind = genai_client::infer_adapter_kind_simple(&llm_provider.api_type);
-    tracing::info!(llm_provider_id, "preview_model_list with adapter_kind: {:?}", adapter_kind);
-
-    match client.all_models(adapter_kind).await {
-        Ok(models) => {
-            let mut available_models = Vec::new();
-            let remote_model_codes: std::collections::HashSet<String> =
-                models.iter().map(|model| model.id.to_string()).collect();
-
-            // 构建可选择的模型列表
-            for model in &models {
-                let model_code = model.id.to_string();
-                let is_selected = existing_model_codes.contains(&model_code);
-
-                available_models.push(ModelForSelection {
-                    name: model.name.to_string(),
-                    code: model_code,
-                    description: format!("Model: {}", model.name),
-                    vision_support: model.supports_input_modality(&Modality::Image),
-                    audio_support: model.supports_input_modality(&Modality::Audio),
-                    video_support: model.supports_input_modality(&Modality::Video),
-                    is_selected,
-                });
-            }
+    .await?;
+    let remote_model_codes: std::collections::HashSet<String> =
+        models.iter().map(|model| model.code.clone()).collect();
+
+    // 构建可选择的模型列表
+    let available_models = models
+        .into_iter()
+        .map(|model| ModelForSelection {
+            is_selected: existing_model_codes.contains(&model.code),
+            name: model.name,
+            code: model.code,
+            description: model.description,
+            vision_support: model.vision_support,
+            audio_support: model.audio_support,
+            video_support: model.video_support,
+        })
+        .collect();
 
-            // 找出在数据库中但远程不存在的模型
-            let missing_models: Vec<String> =
-                existing_model_codes.difference(&remote_model_codes).cloned().collect();
+    // 找出在数据库中但远程不存在的模型
+    let missing_models: Vec<String> =
+        existing_model_codes.difference(&remote_model_codes).cloned().collect();
 
-            Ok(ModelSelectionResponse { available_models, missing_models })
-        }
-        Err(e) => {
-            tracing::error!(error = ?e, "获取模型列表错误（详细）");
-            tracing::error!(error = %e, "获取模型列表错误");
-            Err(e.to_string())
-        }
-    }
+    Ok(ModelSelectionResponse { available_models, missing_models })
 }
 
-#[tauri::command]
-pub fn get_models_for_select(app_handle: tauri::AppHandle) -> Result<Vec<ModelForSelect>, String> {
-    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
-    let result = db.get_models_for_select().unwrap();
-    let models = result
-        .iter()
+/// 将数据库查询结果转换为选择列表项，并附带提供商最近一次获取远程模型列表的时间
+async fn to_models_for_select(
+    app_handle: &tauri::AppHandle,
+    rows: &[(String, String, i64, i64)],
+) -> Vec<ModelForSelect> {
+    let last_fetched = match app_handle.try_state::<ModelListCacheState>() {
+        Some(cache_state) => cache_state.last_fetched_at().await,
+        None => Default::default(),
+    };
+    rows.iter()
         .map(|(name, code, id, llm_provider_id)| ModelForSelect {
             name: name.clone(),
             code: code.clone(),
             id: *id,
             llm_provider_id: *llm_provider_id,
+            models_fetched_at: last_fetched.get(llm_provider_id).copied(),
         })
-        .collect();
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_models_for_select(
+    app_handle: tauri::AppHandle,
+    cache_state: tauri::State<'_, ModelSelectCacheState>,
+) -> Result<Vec<ModelForSelect>, String> {
+    if let Some(cached) = cache_state.models.get(&None).await {
+        return Ok(cached);
+    }
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let result = db.get_models_for_select().unwrap();
+    let models = to_models_for_select(&app_handle, &result).await;
+    cache_state.models.insert(None, models.clone()).await;
     Ok(models)
 }
 
@@ -390,21 +716,18 @@ pub fn get_models_for_select(app_handle: tauri::AppHandle) -> Result<Vec<ModelFo
 /// ACP 助手 (assistant_type = 4): 只返回 ACP 提供商的模型
 /// 普通助手: 排除 ACP 提供商的模型
 #[tauri::command]
-pub fn get_filtered_models_for_select(
+pub async fn get_filtered_models_for_select(
     app_handle: tauri::AppHandle,
+    cache_state: tauri::State<'_, ModelSelectCacheState>,
     assistant_type: i64,
 ) -> Result<Vec<ModelForSelect>, String> {
+    if let Some(cached) = cache_state.models.get(&Some(assistant_type)).await {
+        return Ok(cached);
+    }
     let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
     let result = db.get_filtered_models_for_select(assistant_type).map_err(|e| e.to_string())?;
-    let models = result
-        .iter()
-        .map(|(name, code, id, llm_provider_id)| ModelForSelect {
-            name: name.clone(),
-            code: code.clone(),
-            id: *id,
-            llm_provider_id: *llm_provider_id,
-        })
-        .collect();
+    let models = to_models_for_select(&app_handle, &result).await;
+    cache_state.models.insert(Some(assistant_type), models.clone()).await;
     Ok(models)
 }
 
@@ -433,6 +756,7 @@ pub async fn update_selected_models(
         .map_err(|e| e.to_string())?;
     }
 
+    invalidate_model_select_cache(&app_handle).await;
     Ok(())
 }
 
@@ -517,6 +841,8 @@ pub async fn import_llm_provider(
     db.add_llm_provider_config(provider_id, "api_key", &provider_data.api_key, "header", false)
         .map_err(|e| e.to_string())?;
 
+    invalidate_model_select_cache(&app_handle).await;
+
     // Return the created provider
     Ok(LlmProvider {
         id: provider_id,
@@ -525,5 +851,150 @@ pub async fn import_llm_provider(
         description: "Imported provider".to_string(),
         is_official: false,
         is_enabled: true,
+        keep_alive_active: false,
     })
 }
+
+#[tauri::command]
+pub async fn get_environment_profiles(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<LLMEnvironmentProfile>, String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.get_environment_profiles().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_environment_profile(
+    app_handle: tauri::AppHandle,
+    name: String,
+    enabled_provider_ids: Vec<i64>,
+    default_provider_id: Option<i64>,
+    default_model_code: Option<String>,
+) -> Result<i64, String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.add_environment_profile(
+        &name,
+        &enabled_provider_ids,
+        default_provider_id,
+        default_model_code.as_deref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_environment_profile(
+    app_handle: tauri::AppHandle,
+    id: i64,
+    name: String,
+    enabled_provider_ids: Vec<i64>,
+    default_provider_id: Option<i64>,
+    default_model_code: Option<String>,
+) -> Result<(), String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.update_environment_profile(
+        id,
+        &name,
+        &enabled_provider_ids,
+        default_provider_id,
+        default_model_code.as_deref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_environment_profile(
+    app_handle: tauri::AppHandle,
+    id: i64,
+) -> Result<(), String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.delete_environment_profile(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_model_prices(app_handle: tauri::AppHandle) -> Result<Vec<LLMModelPrice>, String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.get_model_prices().map_err(|e| e.to_string())
+}
+
+/// 设置模型单价（每 1K token），用于 token 统计中的费用估算
+#[tauri::command]
+pub async fn set_model_price(
+    app_handle: tauri::AppHandle,
+    model_code: String,
+    input_price_per_1k: f64,
+    output_price_per_1k: f64,
+) -> Result<LLMModelPrice, String> {
+    let model_code = model_code.trim();
+    if model_code.is_empty() {
+        return Err("模型编码不能为空".to_string());
+    }
+    for price in [input_price_per_1k, output_price_per_1k] {
+        if !price.is_finite() || price < 0.0 {
+            return Err(format!("无效的单价: {}", price));
+        }
+    }
+
+    let price = LLMModelPrice {
+        model_code: model_code.to_string(),
+        input_price_per_1k,
+        output_price_per_1k,
+    };
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.upsert_model_price(&price).map_err(|e| e.to_string())?;
+    Ok(price)
+}
+
+#[tauri::command]
+pub async fn delete_model_price(
+    app_handle: tauri::AppHandle,
+    model_code: String,
+) -> Result<(), String> {
+    let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.delete_model_price(&model_code).map_err(|e| e.to_string())
+}
+
+/// 切换当前环境：按环境启用提供商、更新默认助手的模型，刷新缓存并通知前端
+#[tauri::command]
+pub async fn set_active_environment(
+    app_handle: tauri::AppHandle,
+    name_cache_state: tauri::State<'_, NameCacheState>,
+    profile_id: i64,
+) -> Result<LLMEnvironmentProfile, String> {
+    let (profile, models) = {
+        let db = LLMDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        let profile = db.apply_environment_profile(profile_id).map_err(|e| e.to_string())?;
+        (profile, db.get_models_for_select()?)
+    };
+
+    if let (Some(provider_id), Some(model_code)) =
+        (profile.default_provider_id, profile.default_model_code.as_deref())
+    {
+        let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        apply_default_assistant_model(&assistant_db, provider_id, model_code)
+            .map_err(|e| e.to_string())?;
+    }
+
+    invalidate_model_select_cache(&app_handle).await;
+    *name_cache_state.model_names.lock().await =
+        models.into_iter().map(|(name, _, id, _)| (id, name)).collect();
+
+    let _ = app_handle.emit("environment_changed", &profile);
+    Ok(profile)
+}
+
+/// 将默认助手（快速使用助手）的模型切换为指定模型
+pub fn apply_default_assistant_model(
+    assistant_db: &AssistantDatabase,
+    provider_id: i64,
+    model_code: &str,
+) -> rusqlite::Result<()> {
+    const DEFAULT_ASSISTANT_ID: i64 = 1;
+    match assistant_db.get_assistant_model(DEFAULT_ASSISTANT_ID)?.first() {
+        Some(model) => {
+            assistant_db.update_assistant_model(model.id, provider_id, model_code, &model.alias)
+        }
+        None => assistant_db
+            .add_assistant_model(DEFAULT_ASSISTANT_ID, provider_id, model_code, "")
+            .map(|_| ()),
+    }
+}
@@ -2,7 +2,10 @@ pub mod ai;
 pub mod ai_api;
 pub mod assistant_api;
 pub mod attachment_api;
+pub mod backup_api;
 pub mod conversation_api;
+pub mod conversation_bundle_api;
+pub mod conversation_export_api;
 pub mod copilot_api;
 #[cfg(desktop)]
 pub mod copilot_lsp;
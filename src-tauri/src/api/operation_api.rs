@@ -2,7 +2,9 @@ use tauri::{AppHandle, Manager};
 use tracing::{info, instrument, warn};
 
 use crate::api::ai::acp::{AcpPermissionDecision, AcpPermissionState};
-use crate::mcp::builtin_mcp::operation::types::PermissionDecision;
+use crate::mcp::builtin_mcp::operation::types::{
+    DeniedCommandRecord, PermissionConfirmRequest, PermissionDecision,
+};
 use crate::mcp::builtin_mcp::OperationState;
 
 /// 确认操作权限
@@ -15,15 +17,7 @@ pub async fn confirm_operation_permission(
 ) -> Result<bool, String> {
     info!(request_id = %request_id, decision = %decision, "Processing permission confirmation");
 
-    let decision = match decision.as_str() {
-        "allow" => PermissionDecision::Allow,
-        "allow_and_save" => PermissionDecision::AllowAndSave,
-        "deny" => PermissionDecision::Deny,
-        _ => {
-            warn!(decision = %decision, "Invalid permission decision");
-            return Err(format!("Invalid decision: {}", decision));
-        }
-    };
+    let decision = parse_permission_decision(&decision)?;
 
     // 获取 OperationState
     let state = app_handle
@@ -42,6 +36,59 @@ pub async fn confirm_operation_permission(
     }
 }
 
+fn parse_permission_decision(decision: &str) -> Result<PermissionDecision, String> {
+    match decision {
+        "allow" => Ok(PermissionDecision::Allow),
+        "allow_and_save" => Ok(PermissionDecision::AllowAndSave),
+        "deny" => Ok(PermissionDecision::Deny),
+        _ => {
+            warn!(decision = %decision, "Invalid permission decision");
+            Err(format!("Invalid decision: {}", decision))
+        }
+    }
+}
+
+/// 批量确认操作权限，返回成功解决的请求 ID
+///
+/// 各项独立生效：拒绝某一项不会阻塞同批次中已允许的操作
+#[tauri::command]
+#[instrument(skip(app_handle, decisions), fields(count = decisions.len()))]
+pub async fn confirm_operation_permission_batch(
+    app_handle: AppHandle,
+    decisions: Vec<PermissionConfirmRequest>,
+) -> Result<Vec<String>, String> {
+    info!("Processing batched permission confirmation");
+
+    let state = app_handle
+        .try_state::<OperationState>()
+        .ok_or_else(|| "OperationState not found".to_string())?;
+
+    let requested = decisions.len();
+    let resolved = state.resolve_permission_batch(decisions).await;
+    if resolved.len() < requested {
+        warn!(
+            requested,
+            resolved = resolved.len(),
+            "Some batched permission requests were not found or already resolved"
+        );
+    }
+    Ok(resolved)
+}
+
+/// 获取被命令策略拒绝的 Bash 命令记录
+#[tauri::command]
+#[instrument(skip(app_handle))]
+pub async fn get_denied_operation_commands(
+    app_handle: AppHandle,
+    conversation_id: Option<i64>,
+) -> Result<Vec<DeniedCommandRecord>, String> {
+    let state = app_handle
+        .try_state::<OperationState>()
+        .ok_or_else(|| "OperationState not found".to_string())?;
+
+    Ok(state.get_denied_commands(conversation_id).await)
+}
+
 /// 确认 ACP 工具调用权限
 #[tauri::command]
 #[instrument(skip(app_handle))]
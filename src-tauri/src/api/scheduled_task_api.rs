@@ -1,4 +1,4 @@
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -16,6 +16,7 @@ use crate::api::ai::config::{
 use crate::api::ai::conversation::{
     build_chat_request_from_messages, ToolCallStrategy, ToolConfig,
 };
+use crate::api::ai::response_language::apply_response_language;
 use crate::api::ai::summary::extract_json_from_response;
 use crate::api::ai_api::{build_tools_with_mapping, resolve_tool_name, ToolNameMapping};
 use crate::api::assistant_api::get_assistant;
@@ -33,6 +34,7 @@ use crate::db::system_db::FeatureConfig;
 use crate::mcp::{collect_mcp_info_for_assistant, format_mcp_prompt, MCPInfoForAssistant};
 use crate::skills::{collect_skills_info_for_assistant, format_skills_prompt};
 use crate::template_engine::build_template_engine;
+use crate::utils::cron_utils::{resolve_local_datetime, CronSchedule};
 use crate::{AppState, FeatureConfigState, NameCacheState};
 use genai::chat::{ChatOptions, ToolCall};
 use tauri::Manager;
@@ -51,6 +53,11 @@ pub struct ScheduledTaskDTO {
     pub start_time: Option<String>,
     pub week_days: Option<Vec<i32>>,
     pub month_days: Option<Vec<i32>>,
+    pub cron: Option<String>,
+    pub timezone: Option<String>,
+    pub max_retries: i64,
+    pub retry_delay_secs: i64,
+    pub retry_attempt: i64,
     pub run_at: Option<String>,
     pub next_run_at: Option<String>,
     pub last_run_at: Option<String>,
@@ -66,12 +73,20 @@ pub struct ScheduledTaskDTO {
 pub struct CreateScheduledTaskRequest {
     pub name: String,
     pub is_enabled: bool,
-    pub schedule_type: String, // 'once' | 'interval'
+    pub schedule_type: String, // 'once' | 'interval' | 'cron'
     pub interval_value: Option<i64>,
     pub interval_unit: Option<String>, // minute/hour/day/week/month
     pub start_time: Option<String>,    // HH:mm for day/week/month
     pub week_days: Option<Vec<i32>>,   // [0-6] for week
     pub month_days: Option<Vec<i32>>,  // [1-31] for month
+    #[serde(default)]
+    pub cron: Option<String>, // 5-field cron expression for cron schedules
+    #[serde(default)]
+    pub timezone: Option<String>, // IANA timezone, e.g. Asia/Shanghai
+    #[serde(default)]
+    pub max_retries: Option<i64>, // retries after a failed run, default 0
+    #[serde(default)]
+    pub retry_delay_secs: Option<i64>, // delay before the first retry, default 60s
     pub run_at: Option<String>,
     pub assistant_id: i64,
     pub task_prompt: String,
@@ -90,6 +105,14 @@ pub struct UpdateScheduledTaskRequest {
     pub start_time: Option<String>,
     pub week_days: Option<Vec<i32>>,
     pub month_days: Option<Vec<i32>>,
+    #[serde(default)]
+    pub cron: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub max_retries: Option<i64>,
+    #[serde(default)]
+    pub retry_delay_secs: Option<i64>,
     pub run_at: Option<String>,
     pub assistant_id: i64,
     pub task_prompt: String,
@@ -133,8 +156,16 @@ pub struct ScheduledTaskRunDTO {
     pub notify: bool,
     pub summary: Option<String>,
     pub error_message: Option<String>,
+    /// 实际开始执行的 UTC 时间
     pub started_time: String,
     pub finished_time: Option<String>,
+    /// 计划执行时间（UTC），手动执行时为空
+    pub scheduled_time: Option<String>,
+    /// 计划执行时间在任务时区中的本地时间
+    pub scheduled_local_time: Option<String>,
+    pub timezone: Option<String>,
+    /// 第几次尝试，失败重试时递增
+    pub attempt: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -160,6 +191,10 @@ const AGENTIC_LOOP_TOTAL_TIMEOUT_SECS: u64 = 3000;
 const SCHEDULED_TASK_LOG_ADDED_EVENT: &str = "scheduled_task_log_added";
 const SCHEDULED_TASK_RUN_CREATED_EVENT: &str = "scheduled_task_run_created";
 const SCHEDULED_TASK_RUN_UPDATED_EVENT: &str = "scheduled_task_run_updated";
+const MAX_TASK_RETRIES: i64 = 10;
+const DEFAULT_RETRY_DELAY_SECS: i64 = 60;
+const MIN_RETRY_DELAY_SECS: i64 = 10;
+const MAX_RETRY_DELAY_SECS: i64 = 24 * 60 * 60;
 type ScheduledRunCancelRegistry = Arc<Mutex<HashMap<String, CancellationToken>>>;
 static SCHEDULED_RUN_CANCEL_REGISTRY: OnceLock<ScheduledRunCancelRegistry> = OnceLock::new();
 
@@ -460,6 +495,7 @@ async fn execute_single_tool(
             &record.parameters,
             Some(record.conversation_id),
             cancel_token,
+            None,
         ),
     )
     .await;
@@ -1103,6 +1139,8 @@ pub struct ScheduleConfig<'a> {
     pub start_time: Option<&'a str>,  // HH:mm
     pub week_days: Option<Vec<i32>>,  // 0=Sun, 1=Mon, ..., 6=Sat
     pub month_days: Option<Vec<i32>>, // 1-31
+    pub cron: Option<&'a str>,        // 5-field cron expression
+    pub timezone: Option<&'a str>,    // IANA timezone, None = system local time
     pub run_at: Option<DateTime<Utc>>,
 }
 
@@ -1119,36 +1157,125 @@ fn parse_start_time(start_time: Option<&str>) -> Option<(u32, u32)> {
     })
 }
 
-pub fn compute_next_run_at(
-    schedule_type: &str,
-    interval_value: Option<i64>,
-    interval_unit: Option<&str>,
-    run_at: Option<DateTime<Utc>>,
+/// 任务执行后计算下一次执行时间，一次性任务执行后不再调度
+pub fn compute_task_next_run_at(
+    task: &ScheduledTask,
     base_time: DateTime<Utc>,
 ) -> Result<Option<DateTime<Utc>>, String> {
+    if task.schedule_type == "once" {
+        return Ok(None);
+    }
     compute_next_run_at_with_config(
         ScheduleConfig {
-            schedule_type,
-            interval_value,
-            interval_unit,
-            start_time: None,
-            week_days: None,
-            month_days: None,
-            run_at,
+            schedule_type: &task.schedule_type,
+            interval_value: task.interval_value,
+            interval_unit: task.interval_unit.as_deref(),
+            start_time: task.start_time.as_deref(),
+            week_days: parse_json_array(&task.week_days),
+            month_days: parse_json_array(&task.month_days),
+            cron: task.cron.as_deref(),
+            timezone: task.timezone.as_deref(),
+            run_at: task.run_at,
         },
         base_time,
     )
 }
 
+/// 单次调度执行的计划：本次所属的计划时间与执行后的下一次常规执行时间
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledRunPlan {
+    pub scheduled_time: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+/// 沿调度网格查找下一次执行时最多前进的步数，防止极短间隔的任务长期停摆后循环过久
+const MAX_SCHEDULE_GRID_STEPS: usize = 10_000;
+
+/// 计划到期任务的本次执行。重试执行归属于被替代的原计划时间，
+/// 下一次常规执行从该时间沿调度网格推算，间隔任务不会因重试而整体后移
+pub fn plan_scheduled_run(
+    task: &ScheduledTask,
+    now: DateTime<Utc>,
+) -> Result<ScheduledRunPlan, String> {
+    let retry_anchor = task.retry_scheduled_time.filter(|_| task.retry_attempt > 0);
+    let Some(anchor) = retry_anchor else {
+        // cron 任务按表达式从当前时间往后找下一个触发点，错过的触发不会补跑
+        return Ok(ScheduledRunPlan {
+            scheduled_time: task.next_run_at,
+            next_run_at: compute_task_next_run_at(task, now)?,
+        });
+    };
+
+    let mut next_run_at = compute_task_next_run_at(task, anchor)?;
+    let mut steps = 0;
+    while let Some(next) = next_run_at.filter(|next| *next <= now) {
+        steps += 1;
+        if steps > MAX_SCHEDULE_GRID_STEPS {
+            next_run_at = compute_task_next_run_at(task, now)?;
+            break;
+        }
+        next_run_at = compute_task_next_run_at(task, next)?;
+    }
+    Ok(ScheduledRunPlan { scheduled_time: Some(anchor), next_run_at })
+}
+
+/// 把 UTC 时间格式化为时区中的本地时间（RFC 3339，带偏移），未设置时区时使用系统本地时区
+fn format_in_timezone(time: DateTime<Utc>, timezone: Option<&str>) -> String {
+    match timezone.and_then(|tz| parse_timezone(tz).ok()) {
+        Some(tz) => time.with_timezone(&tz).to_rfc3339(),
+        None => time.with_timezone(&Local).to_rfc3339(),
+    }
+}
+
+/// 解析 IANA 时区名，如 Asia/Shanghai、America/New_York
+pub fn parse_timezone(name: &str) -> Result<chrono_tz::Tz, String> {
+    name.trim().parse::<chrono_tz::Tz>().map_err(|_| format!("无效的时区: {}", name))
+}
+
+/// 按任务时区计算下一次执行时间，未设置时区时使用系统本地时区
 pub fn compute_next_run_at_with_config(
     config: ScheduleConfig,
     base_time: DateTime<Utc>,
 ) -> Result<Option<DateTime<Utc>>, String> {
-    use chrono::{Datelike, Timelike, Weekday};
+    match config.timezone.filter(|tz| !tz.trim().is_empty()) {
+        Some(name) => {
+            let timezone = parse_timezone(name)?;
+            compute_next_run_at_in_zone(&config, base_time, &timezone)
+        }
+        None => compute_next_run_at_in_zone(&config, base_time, &Local),
+    }
+}
+
+/// 构造时区中的本地时刻，夏令时跳过的时刻顺延到之后第一个有效时刻
+fn local_at<Tz: TimeZone>(
+    timezone: &Tz,
+    date: NaiveDate,
+    hour: u32,
+    minute: u32,
+) -> Option<DateTime<Tz>> {
+    resolve_local_datetime(timezone, date.and_hms_opt(hour, minute, 0)?)
+}
+
+fn compute_next_run_at_in_zone<Tz: TimeZone>(
+    config: &ScheduleConfig,
+    base_time: DateTime<Utc>,
+    timezone: &Tz,
+) -> Result<Option<DateTime<Utc>>, String> {
+    use chrono::{Datelike, Timelike};
 
     if config.schedule_type == "once" {
         return Ok(config.run_at);
     }
+    if config.schedule_type == "cron" {
+        let expression = config
+            .cron
+            .filter(|c| !c.trim().is_empty())
+            .ok_or_else(|| "缺少 cron 表达式".to_string())?;
+        let next = CronSchedule::parse(expression)?
+            .next_after(&base_time.with_timezone(timezone))
+            .ok_or_else(|| "无法计算下次执行时间".to_string())?;
+        return Ok(Some(next.with_timezone(&Utc)));
+    }
     if config.schedule_type != "interval" {
         return Err("不支持的 schedule_type".to_string());
     }
@@ -1159,7 +1286,8 @@ pub fn compute_next_run_at_with_config(
         return Err("interval_value 需要大于 0".to_string());
     }
 
-    let local_base = base_time.with_timezone(&Local);
+    let local_base = base_time.with_timezone(timezone);
+    let base_date = local_base.date_naive();
     let (target_hour, target_minute) =
         parse_start_time(config.start_time).unwrap_or((local_base.hour(), local_base.minute()));
 
@@ -1173,21 +1301,19 @@ pub fn compute_next_run_at_with_config(
             Ok(Some(next))
         }
         "day" => {
-            // Every N days at start_time
-            let mut candidate = Local
-                .with_ymd_and_hms(
-                    local_base.year(),
-                    local_base.month(),
-                    local_base.day(),
+            // Every N days at start_time; days are added on the local calendar so the
+            // wall-clock time stays the same across DST changes
+            let mut candidate = local_at(timezone, base_date, target_hour, target_minute)
+                .ok_or_else(|| "无法构造日期".to_string())?;
+
+            if candidate <= local_base {
+                candidate = local_at(
+                    timezone,
+                    base_date + chrono::Duration::days(value),
                     target_hour,
                     target_minute,
-                    0,
                 )
-                .single()
                 .ok_or_else(|| "无法构造日期".to_string())?;
-
-            if candidate <= local_base {
-                candidate = candidate + chrono::Duration::days(value);
             }
             Ok(Some(candidate.with_timezone(&Utc)))
         }
@@ -1212,33 +1338,19 @@ pub fn compute_next_run_at_with_config(
                 return Err("无效的星期几配置".to_string());
             }
 
-            let current_weekday = local_base.weekday().num_days_from_sunday();
-
             // Find next valid day in this week or next weeks
-            let mut candidate: Option<DateTime<Local>> = None;
+            let mut candidate: Option<DateTime<Tz>> = None;
             for week_offset in 0..=(value as i64 * 2) {
-                let week_start = local_base + chrono::Duration::weeks(week_offset);
+                let week_start = base_date + chrono::Duration::weeks(week_offset);
                 for &wd in &week_days_sorted {
                     let days_from_week_start =
                         (wd as i64 + 7 - week_start.weekday().num_days_from_sunday() as i64) % 7;
-                    let target_date =
-                        week_start.date_naive() + chrono::Duration::days(days_from_week_start);
-                    let target_dt = Local
-                        .with_ymd_and_hms(
-                            target_date.year(),
-                            target_date.month(),
-                            target_date.day(),
-                            target_hour,
-                            target_minute,
-                            0,
-                        )
-                        .single();
+                    let target_date = week_start + chrono::Duration::days(days_from_week_start);
+                    let target_dt = local_at(timezone, target_date, target_hour, target_minute);
 
                     if let Some(dt) = target_dt {
-                        if dt > local_base {
-                            if candidate.is_none() || dt < candidate.unwrap() {
-                                candidate = Some(dt);
-                            }
+                        if dt > local_base && candidate.as_ref().map_or(true, |c| dt < *c) {
+                            candidate = Some(dt);
                         }
                     }
                 }
@@ -1272,28 +1384,18 @@ pub fn compute_next_run_at_with_config(
             }
 
             // Find next valid day in current month or future months
-            let mut candidate: Option<DateTime<Local>> = None;
+            let mut candidate: Option<DateTime<Tz>> = None;
             let mut check_year = local_base.year();
             let mut check_month = local_base.month();
 
             for _ in 0..24 {
                 for &day in &month_days_sorted {
-                    let target_dt = Local
-                        .with_ymd_and_hms(
-                            check_year,
-                            check_month,
-                            day,
-                            target_hour,
-                            target_minute,
-                            0,
-                        )
-                        .single();
+                    let target_dt = NaiveDate::from_ymd_opt(check_year, check_month, day)
+                        .and_then(|date| local_at(timezone, date, target_hour, target_minute));
 
                     if let Some(dt) = target_dt {
-                        if dt > local_base {
-                            if candidate.is_none() || dt < candidate.unwrap() {
-                                candidate = Some(dt);
-                            }
+                        if dt > local_base && candidate.as_ref().map_or(true, |c| dt < *c) {
+                            candidate = Some(dt);
                         }
                     }
                 }
@@ -1332,6 +1434,11 @@ fn to_dto(task: ScheduledTask) -> ScheduledTaskDTO {
         start_time: task.start_time,
         week_days: parse_json_array(&task.week_days),
         month_days: parse_json_array(&task.month_days),
+        cron: task.cron,
+        timezone: task.timezone,
+        max_retries: task.max_retries,
+        retry_delay_secs: task.retry_delay_secs,
+        retry_attempt: task.retry_attempt,
         run_at: format_dt(task.run_at),
         next_run_at: format_dt(task.next_run_at),
         last_run_at: format_dt(task.last_run_at),
@@ -1365,6 +1472,12 @@ fn run_to_dto(run: ScheduledTaskRun) -> ScheduledTaskRunDTO {
         error_message: run.error_message,
         started_time: run.started_time.to_rfc3339(),
         finished_time: run.finished_time.map(|v| v.to_rfc3339()),
+        scheduled_time: run.scheduled_time.map(|v| v.to_rfc3339()),
+        scheduled_local_time: run
+            .scheduled_time
+            .map(|v| format_in_timezone(v, run.timezone.as_deref())),
+        timezone: run.timezone,
+        attempt: run.attempt,
     }
 }
 
@@ -1407,6 +1520,95 @@ pub async fn list_scheduled_task_runs(
     Ok(ListScheduledTaskRunsResponse { runs: runs.into_iter().map(run_to_dto).collect() })
 }
 
+/// 校验失败重试配置，返回 (max_retries, retry_delay_secs)
+fn validate_retry_config(
+    max_retries: Option<i64>,
+    retry_delay_secs: Option<i64>,
+) -> Result<(i64, i64), String> {
+    let max_retries = max_retries.unwrap_or(0);
+    let retry_delay_secs = retry_delay_secs.unwrap_or(DEFAULT_RETRY_DELAY_SECS);
+    if !(0..=MAX_TASK_RETRIES).contains(&max_retries) {
+        return Err(format!("最大重试次数需要在 0 到 {} 之间", MAX_TASK_RETRIES));
+    }
+    if !(MIN_RETRY_DELAY_SECS..=MAX_RETRY_DELAY_SECS).contains(&retry_delay_secs) {
+        return Err(format!(
+            "重试间隔需要在 {} 到 {} 秒之间",
+            MIN_RETRY_DELAY_SECS, MAX_RETRY_DELAY_SECS
+        ));
+    }
+    Ok((max_retries, retry_delay_secs))
+}
+
+/// 第 attempt 次执行失败后等待的秒数：从 retry_delay_secs 开始每次翻倍，最长一天
+pub(crate) fn retry_delay_for_attempt(retry_delay_secs: i64, attempt: i64) -> i64 {
+    let exponent = attempt.saturating_sub(1).clamp(0, 20) as u32;
+    retry_delay_secs
+        .max(MIN_RETRY_DELAY_SECS)
+        .saturating_mul(1 << exponent)
+        .min(MAX_RETRY_DELAY_SECS)
+}
+
+/// 执行结束后更新重试进度：失败且未超过 max_retries 时安排下一次重试，
+/// 成功或用尽重试次数时清零
+fn update_task_retry_state(
+    app_handle: &tauri::AppHandle,
+    task: &ScheduledTask,
+    run_id: &str,
+    scheduled_time: Option<DateTime<Utc>>,
+    attempt: i64,
+    failed: bool,
+) {
+    let db = match ScheduledTaskDatabase::new(app_handle) {
+        Ok(db) => db,
+        Err(e) => {
+            warn!(task_id = task.id, error = %e, "无法更新定时任务重试状态");
+            return;
+        }
+    };
+
+    let result = if failed && attempt <= task.max_retries {
+        let delay_secs = retry_delay_for_attempt(task.retry_delay_secs, attempt);
+        let retry_at = Utc::now() + chrono::Duration::seconds(delay_secs);
+        log_task_message(
+            app_handle,
+            task.id,
+            run_id,
+            "task_retry",
+            format!(
+                "第 {} 次执行失败，{} 秒后重试（{}/{}）",
+                attempt, delay_secs, attempt, task.max_retries
+            ),
+        );
+        info!(task_id = task.id, attempt, delay_secs, "定时任务执行失败，已安排重试");
+        db.set_retry_state(task.id, attempt, Some(retry_at), scheduled_time)
+    } else {
+        if failed && task.max_retries > 0 {
+            log_task_message(
+                app_handle,
+                task.id,
+                run_id,
+                "task_retry",
+                format!("已重试 {} 次仍然失败，停止重试", task.max_retries),
+            );
+        }
+        if task.retry_attempt == 0 {
+            return;
+        }
+        db.set_retry_state(task.id, 0, None, None)
+    };
+    if let Err(e) = result {
+        warn!(task_id = task.id, error = %e, "无法更新定时任务重试状态");
+    }
+}
+
+/// 只有 cron 类型的任务保存表达式
+fn normalize_cron(schedule_type: &str, cron: Option<String>) -> Option<String> {
+    if schedule_type != "cron" {
+        return None;
+    }
+    cron.map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
 fn serialize_json_array(arr: &Option<Vec<i32>>) -> Option<String> {
     arr.as_ref().map(|v| serde_json::to_string(v).unwrap_or_else(|_| "[]".to_string()))
 }
@@ -1434,11 +1636,17 @@ pub async fn create_scheduled_task(
             start_time: request.start_time.as_deref(),
             week_days: request.week_days.clone(),
             month_days: request.month_days.clone(),
+            cron: request.cron.as_deref(),
+            timezone: request.timezone.as_deref(),
             run_at,
         },
         now,
     )?;
 
+    let cron = normalize_cron(&request.schedule_type, request.cron);
+    let timezone = request.timezone.map(|tz| tz.trim().to_string()).filter(|tz| !tz.is_empty());
+    let (max_retries, retry_delay_secs) =
+        validate_retry_config(request.max_retries, request.retry_delay_secs)?;
     let task = ScheduledTask {
         id: 0,
         name: request.name,
@@ -1457,6 +1665,12 @@ pub async fn create_scheduled_task(
         notify_prompt: request.notify_prompt,
         created_time: now,
         updated_time: now,
+        cron,
+        timezone,
+        max_retries,
+        retry_delay_secs,
+        retry_attempt: 0,
+        retry_scheduled_time: None,
     };
     let created = db.create_task(&task).map_err(|e| e.to_string())?;
     Ok(to_dto(created))
@@ -1489,10 +1703,16 @@ pub async fn update_scheduled_task(
             start_time: request.start_time.as_deref(),
             week_days: request.week_days.clone(),
             month_days: request.month_days.clone(),
+            cron: request.cron.as_deref(),
+            timezone: request.timezone.as_deref(),
             run_at,
         },
         now,
     )?;
+    let cron = normalize_cron(&request.schedule_type, request.cron);
+    let timezone = request.timezone.map(|tz| tz.trim().to_string()).filter(|tz| !tz.is_empty());
+    let (max_retries, retry_delay_secs) =
+        validate_retry_config(request.max_retries, request.retry_delay_secs)?;
     let updated = ScheduledTask {
         id: existing.id,
         name: request.name,
@@ -1511,6 +1731,12 @@ pub async fn update_scheduled_task(
         notify_prompt: request.notify_prompt,
         created_time: existing.created_time,
         updated_time: now,
+        cron,
+        timezone,
+        max_retries,
+        retry_delay_secs,
+        retry_attempt: 0,
+        retry_scheduled_time: None,
     };
     db.update_task(&updated).map_err(|e| e.to_string())?;
     Ok(to_dto(updated))
@@ -1538,17 +1764,7 @@ pub async fn run_scheduled_task_now(
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "任务不存在".to_string())?;
     let now = Utc::now();
-    let next_run_at = if task.schedule_type == "once" {
-        None
-    } else {
-        compute_next_run_at(
-            &task.schedule_type,
-            task.interval_value,
-            task.interval_unit.as_deref(),
-            task.run_at,
-            now,
-        )?
-    };
+    let next_run_at = compute_task_next_run_at(&task, now)?;
     let updated = ScheduledTask {
         is_enabled: if task.schedule_type == "once" { false } else { task.is_enabled },
         last_run_at: Some(now),
@@ -1558,7 +1774,8 @@ pub async fn run_scheduled_task_now(
     };
     db.update_task(&updated).map_err(|e| e.to_string())?;
 
-    match execute_scheduled_task(&app_handle, &feature_config_state, &updated).await {
+    // 手动执行开始新的一轮尝试
+    match execute_scheduled_task(&app_handle, &feature_config_state, &updated, None, 1).await {
         Ok(result) => Ok(result),
         Err(e) => Ok(RunScheduledTaskResult {
             task_id,
@@ -1603,10 +1820,14 @@ pub async fn stop_scheduled_task_run(
     Ok(true)
 }
 
+/// scheduled_time 为触发本次执行的计划时间，手动执行时传 None；
+/// attempt 为第几次尝试，失败后按任务的重试配置安排下一次尝试
 pub async fn execute_scheduled_task(
     app_handle: &tauri::AppHandle,
     feature_config_state: &FeatureConfigState,
     task: &ScheduledTask,
+    scheduled_time: Option<DateTime<Utc>>,
+    attempt: i64,
 ) -> Result<RunScheduledTaskResult, String> {
     let run_id = Uuid::new_v4().to_string();
     let run_cancel_token = register_scheduled_run_cancel_token(&run_id).await;
@@ -1623,24 +1844,40 @@ pub async fn execute_scheduled_task(
             error_message: None,
             started_time: run_started_at,
             finished_time: None,
+            scheduled_time,
+            timezone: task.timezone.clone(),
+            attempt,
         };
         if let Ok(created_run) = log_db.create_run(&running) {
             let _ = app_handle.emit(SCHEDULED_TASK_RUN_CREATED_EVENT, run_to_dto(created_run));
         }
     }
-    log_task_message(app_handle, task.id, &run_id, "start", "开始执行定时任务");
+    if attempt > 1 {
+        log_task_message(
+            app_handle,
+            task.id,
+            &run_id,
+            "start",
+            format!("开始第 {} 次尝试执行定时任务", attempt),
+        );
+    } else {
+        log_task_message(app_handle, task.id, &run_id, "start", "开始执行定时任务");
+    }
     let run_result = execute_scheduled_task_inner(
         app_handle,
         feature_config_state,
         task,
         &run_id,
-        run_cancel_token,
+        run_cancel_token.clone(),
     )
     .await;
 
     if let Err(err) = &run_result {
         update_task_run(app_handle, &run_id, "failed", false, None, Some(err), Some(Utc::now()));
     }
+    // 手动停止的执行不重试
+    let failed = run_result.is_err() && !run_cancel_token.is_cancelled();
+    update_task_retry_state(app_handle, task, &run_id, scheduled_time, attempt, failed);
     unregister_scheduled_run_cancel_token(&run_id).await;
     run_result
 }
@@ -1653,7 +1890,7 @@ async fn execute_scheduled_task_inner(
     run_cancel_token: CancellationToken,
 ) -> Result<RunScheduledTaskResult, String> {
     // ── 1. 验证助手配置 ──────────────────────────────────────────────
-    let assistant_detail = get_assistant(app_handle.clone(), task.assistant_id)
+    let assistant_detail = get_assistant(app_handle.clone(), task.assistant_id, Some(true))
         .map_err(|e| format!("Failed to get assistant: {}", e))?;
     if assistant_detail.assistant.assistant_type.unwrap_or(0) != 0 {
         log_task_message(app_handle, task.id, run_id, "error", "只能选择普通对话助手");
@@ -1715,6 +1952,8 @@ async fn execute_scheduled_task_inner(
         assistant_prompt_result =
             format_skills_prompt(app_handle, assistant_prompt_result, &skills_info).await;
     }
+    let assistant_prompt_result =
+        apply_response_language(assistant_prompt_result, &assistant_detail.model_configs);
 
     // ── 4. 构建 LLM 客户端 & 选项 ─────────────────────────────────
     let llm_db = LLMDatabase::new(app_handle).map_err(|e| e.to_string())?;
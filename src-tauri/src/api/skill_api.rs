@@ -1,10 +1,21 @@
 //! Skill API - Tauri commands for skill management
 
+use crate::db::assistant_db::AssistantDatabase;
 use crate::db::skill_db::SkillDatabase;
+use crate::skills::git_source::{
+    checkout_dir, ensure_token_transport, source_config, sync_git_source, validate_repo_url,
+};
+use crate::skills::params::{
+    effective_skill_parameters, validate_skill_parameters, SkillParameterValidationError,
+};
 use crate::skills::parser::SkillParser;
 use crate::skills::scanner::SkillScanner;
-use crate::skills::types::{ScannedSkill, SkillContent, SkillSourceConfig, SkillWithConfig};
+use crate::skills::types::{
+    GitSkillSource, ScannedSkill, SkillContent, SkillSourceConfig, SkillWithConfig,
+};
+use crate::skills::watcher::{ensure_skill_watcher, start_skill_watcher};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::path::PathBuf;
 use tauri::Manager;
 use tracing::{debug, error, info, warn};
@@ -33,9 +44,24 @@ fn get_app_data_dir(app_handle: &tauri::AppHandle) -> PathBuf {
     app_handle.path().app_data_dir().unwrap_or_else(|_| PathBuf::from("."))
 }
 
-/// Create a skill scanner with proper paths
-fn create_scanner(app_handle: &tauri::AppHandle) -> SkillScanner {
-    SkillScanner::new(get_home_dir(), get_app_data_dir(app_handle))
+/// Create a skill scanner with proper paths, including subscribed git sources
+pub fn create_scanner(app_handle: &tauri::AppHandle) -> SkillScanner {
+    let app_data_dir = get_app_data_dir(app_handle);
+    let mut scanner = SkillScanner::new(get_home_dir(), app_data_dir.clone());
+    match SkillDatabase::new(app_handle).and_then(|db| db.list_git_sources()) {
+        Ok(sources) => {
+            for source in &sources {
+                scanner.add_source(source_config(source, &app_data_dir));
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to load git skill sources"),
+    }
+    scanner
+}
+
+/// Start (or restart) watching skill sources for hot-reload
+pub fn start_skills_hot_reload(app_handle: &tauri::AppHandle) {
+    start_skill_watcher(app_handle, &create_scanner(app_handle));
 }
 
 /// Migrate skills from old {app_data}/skills to new ~/.agents/skills
@@ -170,6 +196,8 @@ pub fn migrate_skills_to_agents_dir(app_handle: &tauri::AppHandle) -> Result<(),
 #[tauri::command]
 pub async fn scan_skills(app_handle: tauri::AppHandle) -> Result<Vec<ScannedSkill>, String> {
     let scanner = create_scanner(&app_handle);
+    // A source directory may have appeared since the watcher started
+    ensure_skill_watcher(&app_handle, &scanner);
     let skills = scanner.scan_all();
     info!("Scanned {} skills", skills.len());
     Ok(skills)
@@ -214,7 +242,7 @@ pub async fn get_skill_content_internal(
     Ok(content)
 }
 
-/// Get a single skill by identifier
+/// Get a single skill by identifier (metadata includes the parameter schema)
 #[tauri::command]
 pub async fn get_skill(
     app_handle: tauri::AppHandle,
@@ -276,9 +304,27 @@ pub async fn get_enabled_assistant_skills_internal(
     app_handle: &tauri::AppHandle,
     assistant_id: i64,
 ) -> Result<Vec<ScannedSkill>, crate::errors::AppError> {
+    Ok(get_enabled_assistant_skills_with_parameters_internal(app_handle, assistant_id)
+        .await?
+        .into_iter()
+        .map(|(skill, _)| skill)
+        .collect())
+}
+
+/// Enabled skills paired with their effective parameter values
+/// (declared defaults overridden by the assistant's valid configured values)
+pub async fn get_enabled_assistant_skills_with_parameters_internal(
+    app_handle: &tauri::AppHandle,
+    assistant_id: i64,
+) -> Result<Vec<(ScannedSkill, Map<String, Value>)>, crate::errors::AppError> {
     let db = SkillDatabase::new(app_handle).map_err(crate::errors::AppError::from)?;
-    let configs =
-        db.get_enabled_skill_configs(assistant_id).map_err(crate::errors::AppError::from)?;
+    // Skills configured on base assistants are inherited
+    let inheritance_chain = AssistantDatabase::new(app_handle)
+        .and_then(|assistant_db| assistant_db.get_inheritance_chain(assistant_id))
+        .map_err(crate::errors::AppError::from)?;
+    let configs = db
+        .get_effective_skill_configs(&inheritance_chain)
+        .map_err(crate::errors::AppError::from)?;
 
     if configs.is_empty() {
         return Ok(Vec::new());
@@ -289,9 +335,14 @@ pub async fn get_enabled_assistant_skills_internal(
     let existing_skills = scanner.scan_all_as_map();
 
     // Filter to only existing skills, maintaining priority order
-    let result: Vec<ScannedSkill> = configs
+    let result = configs
         .into_iter()
-        .filter_map(|config| existing_skills.get(&config.skill_identifier).cloned())
+        .filter_map(|config| {
+            let skill = existing_skills.get(&config.skill_identifier)?.clone();
+            let parameters =
+                effective_skill_parameters(&skill.metadata.parameters, &config.parameters);
+            Some((skill, parameters))
+        })
         .collect();
 
     Ok(result)
@@ -305,7 +356,9 @@ fn check_agent_load_skill_ready(
     crate::mcp::registry_api::is_agent_load_skill_ready(app_handle, assistant_id)
 }
 
-/// Update skill config for an assistant
+/// Update skill config for an assistant.
+/// `parameters` replaces the stored values after validation against the skill's declared
+/// parameters; on failure the error is a JSON `SkillParameterValidationError`.
 #[tauri::command]
 pub async fn update_assistant_skill_config(
     app_handle: tauri::AppHandle,
@@ -313,9 +366,20 @@ pub async fn update_assistant_skill_config(
     skill_identifier: String,
     is_enabled: bool,
     priority: i32,
+    parameters: Option<Map<String, Value>>,
 ) -> Result<i64, String> {
     let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
 
+    if let Some(parameters) = &parameters {
+        let skill = create_scanner(&app_handle)
+            .get_skill(&skill_identifier)
+            .ok_or_else(|| format!("Skill not found: {}", skill_identifier))?;
+        validate_skill_parameters(&skill.metadata.parameters, parameters).map_err(|errors| {
+            warn!(skill = %skill_identifier, ?errors, "Rejected invalid skill parameters");
+            SkillParameterValidationError::new(&skill_identifier, errors).to_json_string()
+        })?;
+    }
+
     // 后端校验：启用 skill 时需要 Agent load_skill 可用
     if is_enabled {
         let agent_ready = check_agent_load_skill_ready(&app_handle, assistant_id)?;
@@ -327,6 +391,9 @@ pub async fn update_assistant_skill_config(
     let id = db
         .upsert_assistant_skill_config(assistant_id, &skill_identifier, is_enabled, priority)
         .map_err(|e| e.to_string())?;
+    if let Some(parameters) = &parameters {
+        db.update_skill_config_parameters(id, parameters).map_err(|e| e.to_string())?;
+    }
 
     info!(
         "Updated skill config: assistant={}, skill={}, enabled={}",
@@ -422,6 +489,7 @@ pub async fn open_skills_folder(app_handle: tauri::AppHandle) -> Result<(), Stri
 
     // Create if not exists
     std::fs::create_dir_all(&skills_dir).map_err(|e| e.to_string())?;
+    ensure_skill_watcher(&app_handle, &create_scanner(&app_handle));
 
     // Open with system file manager
     #[cfg(target_os = "macos")]
@@ -661,6 +729,8 @@ pub async fn install_official_skill(
     let _ = std::fs::remove_dir_all(&temp_extract_dir);
 
     info!("Skill installed successfully to {}", skills_dir.display());
+    // 首次安装时技能目录可能刚被创建，重新建立监听
+    start_skills_hot_reload(&app_handle);
     Ok(())
 }
 
@@ -725,3 +795,99 @@ pub async fn delete_skill(app_handle: tauri::AppHandle, identifier: String) -> R
     info!("Deleted skill folder: {}", skill_folder.display());
     Ok(())
 }
+
+/// Get all subscribed git skill sources (tokens are not included)
+#[tauri::command]
+pub async fn list_git_skill_sources(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<GitSkillSource>, String> {
+    let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.list_git_sources().map_err(|e| e.to_string())
+}
+
+/// Subscribe to a git repository of skills and clone it.
+/// The source is kept even if the first clone fails; the error is returned in `last_error`
+/// so the user can fix the token/URL and refresh.
+#[tauri::command]
+pub async fn add_git_skill_source(
+    app_handle: tauri::AppHandle,
+    name: String,
+    repo_url: String,
+    branch: Option<String>,
+    auth_token: Option<String>,
+) -> Result<GitSkillSource, String> {
+    validate_repo_url(&repo_url)?;
+    let name = name.trim();
+    let name = if name.is_empty() { repo_url.trim() } else { name };
+    let branch = branch.as_deref().map(str::trim).filter(|branch| !branch.is_empty());
+    if branch.is_some_and(|branch| branch.starts_with('-')) {
+        return Err("Invalid branch name".to_string());
+    }
+    let auth_token = auth_token.as_deref().map(str::trim).filter(|token| !token.is_empty());
+    ensure_token_transport(&repo_url, auth_token)?;
+
+    let id = {
+        let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        db.add_git_source(name, repo_url.trim(), branch, auth_token).map_err(|e| e.to_string())?
+    };
+    info!(id, "Added git skill source {}", repo_url.trim());
+
+    if let Err(e) = refresh_skill_source(app_handle.clone(), id).await {
+        warn!(id, error = %e, "Initial clone of git skill source failed");
+    }
+    let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.get_git_source(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Git skill source not found: {}", id))
+}
+
+/// Clone or pull a git skill source. On failure the last good checkout is kept
+/// and the error is both recorded on the source and returned.
+#[tauri::command]
+pub async fn refresh_skill_source(
+    app_handle: tauri::AppHandle,
+    id: i64,
+) -> Result<GitSkillSource, String> {
+    let source = {
+        let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+        db.get_git_source(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Git skill source not found: {}", id))?
+    };
+
+    let checkout = checkout_dir(&get_app_data_dir(&app_handle), id);
+    let result = sync_git_source(&source, &checkout).await;
+
+    let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    match result {
+        Ok(()) => {
+            db.mark_git_source_synced(id).map_err(|e| e.to_string())?;
+            // A first clone creates the checkout directory, so re-register the watcher
+            start_skills_hot_reload(&app_handle);
+            db.get_git_source(id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Git skill source not found: {}", id))
+        }
+        Err(e) => {
+            error!(id, error = %e, "Failed to refresh git skill source");
+            db.mark_git_source_failed(id, &e).map_err(|e| e.to_string())?;
+            Err(e)
+        }
+    }
+}
+
+/// Unsubscribe from a git skill source and delete its checkout
+#[tauri::command]
+pub async fn remove_git_skill_source(app_handle: tauri::AppHandle, id: i64) -> Result<(), String> {
+    let db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    db.delete_git_source(id).map_err(|e| e.to_string())?;
+
+    let checkout = checkout_dir(&get_app_data_dir(&app_handle), id);
+    if checkout.exists() {
+        std::fs::remove_dir_all(&checkout)
+            .map_err(|e| format!("Failed to delete checkout directory: {}", e))?;
+    }
+    info!(id, "Removed git skill source");
+    start_skills_hot_reload(&app_handle);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use base64::Engine;
+use serde::Serialize;
 use std::cmp::Ord;
 use std::collections::HashMap;
 use tauri::{Emitter, Manager, State};
@@ -7,6 +8,8 @@ use crate::template_engine::{build_template_engine, BangType};
 use crate::AppState;
 use crate::FeatureConfigState;
 
+use crate::db::encryption::{self, DatabaseEncryptionStatus, KeySource};
+use crate::db::get_db_dir;
 use crate::db::system_db::{FeatureConfig, SystemDatabase};
 
 #[tauri::command]
@@ -87,6 +90,25 @@ pub async fn open_data_folder(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_database_encryption_status(
+    app_handle: tauri::AppHandle,
+) -> Result<DatabaseEncryptionStatus, String> {
+    encryption::status(&get_db_dir(&app_handle)?)
+}
+
+/// 启用本地数据库加密，已有的明文数据库在下次启动时加密；口令模式可直接传入用户输入的口令
+#[tauri::command]
+pub async fn enable_database_encryption(
+    app_handle: tauri::AppHandle,
+    key_source: KeySource,
+    passphrase: Option<String>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    let db_dir = get_db_dir(&app_handle)?;
+    encryption::enable(&db_dir, key_source, passphrase.as_deref())?;
+    encryption::status(&db_dir)
+}
+
 #[tauri::command]
 pub async fn get_bang_list(
     app_handle: tauri::AppHandle,
@@ -120,6 +142,24 @@ pub async fn set_shortcut_recording(
     Ok(())
 }
 
+/// 单个全局快捷键的注册结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutRegistrationStatus {
+    /// "ask" 或 "chat"
+    pub action: String,
+    pub shortcut: String,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+/// 获取最近一次全局快捷键注册的结果，每次重新注册都会整体替换
+#[tauri::command]
+pub async fn get_shortcut_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ShortcutRegistrationStatus>, String> {
+    Ok(state.shortcut_status.lock().await.clone())
+}
+
 #[tauri::command]
 pub async fn suspend_global_shortcut(app: tauri::AppHandle) -> Result<(), String> {
     #[cfg(desktop)]
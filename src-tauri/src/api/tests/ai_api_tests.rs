@@ -732,4 +732,91 @@ mod ai_api_tests {
             );
         }
     }
+
+    fn assistant_test_result_fixture(
+        assistant_id: i64,
+    ) -> crate::api::ai::types::AssistantTestResult {
+        crate::api::ai::types::AssistantTestResult {
+            assistant_id,
+            model_code: "gpt-4o".to_string(),
+            content: "pong".to_string(),
+            tool_calls: vec![crate::api::ai::types::AssistantTestToolCall {
+                server_name: "aipp_search".to_string(),
+                tool_name: "search_web".to_string(),
+                parameters: "{\"query\":\"rust\"}".to_string(),
+            }],
+            input_token_count: 12,
+            output_token_count: 3,
+            token_count: 15,
+            duration_ms: 42,
+        }
+    }
+
+    /// 测试助手冒烟测试：临时对话在执行后被删除，并返回生成结果
+    #[tokio::test]
+    async fn test_run_in_ephemeral_conversation_cleans_up_and_returns_response() {
+        let repo = ConversationRepository::new(create_ai_api_test_db());
+        let mut created_id = None;
+
+        let result = run_in_ephemeral_conversation(
+            || {
+                let conversation = repo.create(&Conversation {
+                    id: 0,
+                    name: "[assistant test] demo".to_string(),
+                    assistant_id: Some(1),
+                    created_time: Utc::now(),
+                })?;
+                created_id = Some(conversation.id);
+                Ok(conversation.id)
+            },
+            |conversation_id| {
+                repo.delete(conversation_id)?;
+                Ok(())
+            },
+            |conversation_id| async move {
+                assert!(conversation_id > 0);
+                Ok(assistant_test_result_fixture(1))
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.content, "pong");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.token_count, 15);
+
+        let conversation_id = created_id.expect("ephemeral conversation should be created");
+        assert!(repo.read(conversation_id).unwrap().is_none(), "临时对话应该被删除");
+    }
+
+    /// 测试助手冒烟测试：生成失败时同样会删除临时对话并透传错误
+    #[tokio::test]
+    async fn test_run_in_ephemeral_conversation_cleans_up_on_error() {
+        let repo = ConversationRepository::new(create_ai_api_test_db());
+        let mut created_id = None;
+
+        let result: Result<crate::api::ai::types::AssistantTestResult, _> =
+            run_in_ephemeral_conversation(
+                || {
+                    let conversation = repo.create(&Conversation {
+                        id: 0,
+                        name: "[assistant test] demo".to_string(),
+                        assistant_id: Some(1),
+                        created_time: Utc::now(),
+                    })?;
+                    created_id = Some(conversation.id);
+                    Ok(conversation.id)
+                },
+                |conversation_id| {
+                    repo.delete(conversation_id)?;
+                    Ok(())
+                },
+                |_| async { Err(crate::errors::AppError::ProviderError("boom".to_string())) },
+            )
+            .await;
+
+        assert!(matches!(result, Err(crate::errors::AppError::ProviderError(_))));
+        let conversation_id = created_id.expect("ephemeral conversation should be created");
+        assert!(repo.read(conversation_id).unwrap().is_none(), "失败后临时对话也应该被删除");
+    }
 }
@@ -6,11 +6,22 @@
 //! - 模型配置合并
 //! - 网络配置获取
 //! - 重试延迟计算
+//! - API 协议（dialect）选择
 
 use crate::api::ai::config::{
-    calculate_retry_delay, get_network_proxy_from_config, get_request_timeout_from_config,
-    get_retry_attempts_from_config, ConfigBuilder, DEFAULT_REQUEST_TIMEOUT_SECS,
-    MAX_RETRY_ATTEMPTS, RETRY_DELAY_BASE_MS,
+    calculate_retry_delay, get_mcp_health_check_interval_from_config,
+    get_mcp_tool_timeout_from_config, get_model_list_cache_ttl_from_config,
+    get_network_proxy_from_config, get_request_fallback_order_from_config,
+    get_request_timeout_from_config, get_retry_attempts_from_config, get_retry_backoff_from_config,
+    get_stream_idle_timeout_from_config, parse_config_bool, ConfigBuilder, RetryBackoffConfig,
+    RetryBackoffStrategy, SamplingParams, DEFAULT_MCP_HEALTH_CHECK_INTERVAL_SECS,
+    DEFAULT_MODEL_LIST_CACHE_TTL_SECS, DEFAULT_REQUEST_TIMEOUT_SECS,
+    DEFAULT_STREAM_IDLE_TIMEOUT_MS, MAX_RETRY_ATTEMPTS, RETRY_DELAY_BASE_MS,
+};
+use crate::api::ai::request_fallback::RequestSimplification;
+use crate::api::genai_client::{
+    create_client_with_config, get_default_endpoint, parse_api_dialect, resolve_adapter_kind,
+    AzureConfig,
 };
 use crate::db::assistant_db::AssistantModelConfig;
 use crate::db::llm_db::{LLMModel, LLMProvider, LLMProviderConfig, ModelDetail};
@@ -104,6 +115,80 @@ fn test_build_chat_options_invalid_max_tokens() {
     assert!(format!("{:?}", options).contains("ChatOptions"));
 }
 
+fn sampling_config_map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+/// 测试助手的采样参数解析
+/// 验证内容：
+/// - 不同助手的温度（包括 0.0）按各自配置生效
+/// - 未配置或为空时保持 None，由提供商使用默认值
+/// - 超出范围的值被忽略
+#[test]
+fn test_sampling_params_per_assistant() {
+    let cold = SamplingParams::from_config_map(&sampling_config_map(&[
+        ("model", "gpt-4o"),
+        ("temperature", "0.0"),
+    ]));
+    let hot = SamplingParams::from_config_map(&sampling_config_map(&[
+        ("model", "gpt-4o"),
+        ("temperature", "1.2"),
+        ("top_p", "0.9"),
+        ("max_tokens", "1024"),
+    ]));
+    assert_eq!(cold.temperature, Some(0.0));
+    assert_eq!(hot.temperature, Some(1.2));
+    assert_eq!(hot.top_p, Some(0.9));
+    assert_eq!(hot.max_tokens, Some(1024));
+
+    let unset = SamplingParams::from_config_map(&sampling_config_map(&[
+        ("model", "gpt-4o"),
+        ("temperature", " "),
+    ]));
+    assert_eq!(unset, SamplingParams::default());
+
+    let out_of_range = SamplingParams::from_config_map(&sampling_config_map(&[
+        ("temperature", "3.5"),
+        ("top_p", "1.5"),
+        ("max_tokens", "0"),
+    ]));
+    assert_eq!(out_of_range, SamplingParams::default());
+}
+
+/// 测试与推理强度冲突的采样参数被移除
+/// 验证内容：
+/// - OpenAI 推理模型和 Claude 开启推理后移除 temperature / top_p
+/// - 其他模型或未开启推理时保留
+#[test]
+fn test_sampling_params_drop_incompatible_with_reasoning() {
+    for model in ["o3-mini", "gpt-5", "openai/o4-mini", "claude-sonnet-4-5"] {
+        let params = SamplingParams::from_config_map(&sampling_config_map(&[
+            ("model", model),
+            ("temperature", "0.7"),
+            ("top_p", "0.9"),
+            ("max_tokens", "2048"),
+            ("reasoning_effort", "high"),
+        ]));
+        assert_eq!(params.temperature, None, "{}", model);
+        assert_eq!(params.top_p, None, "{}", model);
+        assert_eq!(params.max_tokens, Some(2048));
+        assert_eq!(params.reasoning_effort.as_deref(), Some("high"));
+    }
+
+    let other_model = SamplingParams::from_config_map(&sampling_config_map(&[
+        ("model", "deepseek-chat"),
+        ("temperature", "0.7"),
+        ("reasoning_effort", "high"),
+    ]));
+    assert_eq!(other_model.temperature, Some(0.7));
+
+    let no_reasoning = SamplingParams::from_config_map(&sampling_config_map(&[
+        ("model", "claude-sonnet-4-5"),
+        ("temperature", "0.7"),
+    ]));
+    assert_eq!(no_reasoning.temperature, Some(0.7));
+}
+
 // ============================================================================
 // 模型配置合并测试
 // ============================================================================
@@ -300,6 +385,22 @@ fn test_get_retry_attempts_invalid_value() {
     assert_eq!(attempts, MAX_RETRY_ATTEMPTS);
 }
 
+/// 测试获取 400 降级顺序 - 无配置使用默认顺序，配置为空则关闭
+#[test]
+fn test_get_request_fallback_order_from_config() {
+    let config_map: HashMap<String, HashMap<String, FeatureConfig>> = HashMap::new();
+    assert_eq!(
+        get_request_fallback_order_from_config(&config_map),
+        vec![RequestSimplification::RemoveTools, RequestSimplification::StripExtraParams]
+    );
+
+    let mut network_config = HashMap::new();
+    network_config.insert("bad_request_fallback".to_string(), create_feature_config(""));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+    assert!(get_request_fallback_order_from_config(&config_map).is_empty());
+}
+
 /// 测试获取请求超时 - 有配置
 #[test]
 fn test_get_request_timeout_with_config() {
@@ -322,6 +423,105 @@ fn test_get_request_timeout_no_config() {
     assert_eq!(timeout, DEFAULT_REQUEST_TIMEOUT_SECS);
 }
 
+/// 测试获取流式空闲超时 - 无配置时默认不检测，配置为 0 关闭检测
+#[test]
+fn test_get_stream_idle_timeout_from_config() {
+    let config_map: HashMap<String, HashMap<String, FeatureConfig>> = HashMap::new();
+    assert_eq!(DEFAULT_STREAM_IDLE_TIMEOUT_MS, 0);
+    assert_eq!(get_stream_idle_timeout_from_config(&config_map), None);
+
+    let mut network_config = HashMap::new();
+    network_config.insert("stream_idle_timeout_ms".to_string(), create_feature_config("30000"));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+    assert_eq!(
+        get_stream_idle_timeout_from_config(&config_map),
+        Some(std::time::Duration::from_millis(30_000))
+    );
+
+    config_map
+        .get_mut("network_config")
+        .unwrap()
+        .insert("stream_idle_timeout_ms".to_string(), create_feature_config("0"));
+    assert_eq!(get_stream_idle_timeout_from_config(&config_map), None);
+}
+
+/// 测试获取模型列表缓存时间 - 无配置默认 10 分钟，配置值按秒解析
+#[test]
+fn test_get_model_list_cache_ttl_from_config() {
+    let config_map: HashMap<String, HashMap<String, FeatureConfig>> = HashMap::new();
+    assert_eq!(
+        get_model_list_cache_ttl_from_config(&config_map),
+        std::time::Duration::from_secs(DEFAULT_MODEL_LIST_CACHE_TTL_SECS)
+    );
+    assert_eq!(DEFAULT_MODEL_LIST_CACHE_TTL_SECS, 600);
+
+    let mut network_config = HashMap::new();
+    network_config.insert("model_list_cache_ttl_secs".to_string(), create_feature_config("60"));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+    assert_eq!(
+        get_model_list_cache_ttl_from_config(&config_map),
+        std::time::Duration::from_secs(60)
+    );
+
+    config_map
+        .get_mut("network_config")
+        .unwrap()
+        .insert("model_list_cache_ttl_secs".to_string(), create_feature_config("abc"));
+    assert_eq!(
+        get_model_list_cache_ttl_from_config(&config_map),
+        std::time::Duration::from_secs(DEFAULT_MODEL_LIST_CACHE_TTL_SECS)
+    );
+}
+
+/// 测试获取 MCP 健康检查间隔 - 无配置默认 5 分钟，配置为 0 时关闭
+#[test]
+fn test_get_mcp_health_check_interval_from_config() {
+    let config_map: HashMap<String, HashMap<String, FeatureConfig>> = HashMap::new();
+    assert_eq!(
+        get_mcp_health_check_interval_from_config(&config_map),
+        Some(std::time::Duration::from_secs(DEFAULT_MCP_HEALTH_CHECK_INTERVAL_SECS))
+    );
+
+    let mut network_config = HashMap::new();
+    network_config
+        .insert("mcp_health_check_interval_secs".to_string(), create_feature_config("120"));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+    assert_eq!(
+        get_mcp_health_check_interval_from_config(&config_map),
+        Some(std::time::Duration::from_secs(120))
+    );
+
+    config_map
+        .get_mut("network_config")
+        .unwrap()
+        .insert("mcp_health_check_interval_secs".to_string(), create_feature_config("0"));
+    assert_eq!(get_mcp_health_check_interval_from_config(&config_map), None);
+}
+
+/// 测试获取 MCP 工具执行超时 - 未配置或为 0 时沿用服务器超时
+#[test]
+fn test_get_mcp_tool_timeout_from_config() {
+    let config_map: HashMap<String, HashMap<String, FeatureConfig>> = HashMap::new();
+    assert_eq!(get_mcp_tool_timeout_from_config(&config_map), None);
+
+    let mut network_config = HashMap::new();
+    network_config.insert("mcp_tool_timeout_ms".to_string(), create_feature_config("90000"));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+    assert_eq!(get_mcp_tool_timeout_from_config(&config_map), Some(90_000));
+
+    for value in ["", "0", "abc"] {
+        config_map
+            .get_mut("network_config")
+            .unwrap()
+            .insert("mcp_tool_timeout_ms".to_string(), create_feature_config(value));
+        assert_eq!(get_mcp_tool_timeout_from_config(&config_map), None);
+    }
+}
+
 /// 测试获取网络代理 - 有配置
 #[test]
 fn test_get_network_proxy_with_config() {
@@ -367,21 +567,21 @@ fn test_get_network_proxy_no_config() {
 /// 测试第一次重试的延迟
 #[test]
 fn test_calculate_retry_delay_first_attempt() {
-    let delay = calculate_retry_delay(1);
+    let delay = calculate_retry_delay(1, RetryBackoffStrategy::Exponential, None);
     assert_eq!(delay, RETRY_DELAY_BASE_MS); // 2000ms
 }
 
 /// 测试第二次重试的延迟（指数退避）
 #[test]
 fn test_calculate_retry_delay_second_attempt() {
-    let delay = calculate_retry_delay(2);
+    let delay = calculate_retry_delay(2, RetryBackoffStrategy::Exponential, None);
     assert_eq!(delay, RETRY_DELAY_BASE_MS * 2); // 4000ms
 }
 
 /// 测试第三次重试的延迟
 #[test]
 fn test_calculate_retry_delay_third_attempt() {
-    let delay = calculate_retry_delay(3);
+    let delay = calculate_retry_delay(3, RetryBackoffStrategy::Exponential, None);
     assert_eq!(delay, RETRY_DELAY_BASE_MS * 4); // 8000ms
 }
 
@@ -389,7 +589,7 @@ fn test_calculate_retry_delay_third_attempt() {
 #[test]
 fn test_calculate_retry_delay_zero_attempt() {
     // attempt = 0 时，2^(0-1) 会因为 saturating_sub 变成 2^0 = 1
-    let delay = calculate_retry_delay(0);
+    let delay = calculate_retry_delay(0, RetryBackoffStrategy::Exponential, None);
     assert_eq!(delay, RETRY_DELAY_BASE_MS);
 }
 
@@ -397,7 +597,281 @@ fn test_calculate_retry_delay_zero_attempt() {
 #[test]
 fn test_calculate_retry_delay_large_attempt() {
     // 测试不会溢出
-    let delay = calculate_retry_delay(10);
+    let delay = calculate_retry_delay(10, RetryBackoffStrategy::Exponential, None);
     assert!(delay > 0);
     assert_eq!(delay, RETRY_DELAY_BASE_MS * 512); // 2^9 = 512
 }
+
+/// 测试获取重试退避配置 - 无配置或无效值时保持原有指数退避且不限制上限
+#[test]
+fn test_get_retry_backoff_default() {
+    let config_map: HashMap<String, HashMap<String, FeatureConfig>> = HashMap::new();
+    assert_eq!(get_retry_backoff_from_config(&config_map), RetryBackoffConfig::default());
+    assert_eq!(
+        RetryBackoffConfig::default(),
+        RetryBackoffConfig { strategy: RetryBackoffStrategy::Exponential, max_delay_ms: None }
+    );
+
+    let mut network_config = HashMap::new();
+    network_config.insert("retry_backoff".to_string(), create_feature_config("quadratic"));
+    network_config.insert("retry_max_delay_ms".to_string(), create_feature_config("0"));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+    assert_eq!(get_retry_backoff_from_config(&config_map), RetryBackoffConfig::default());
+}
+
+/// 测试获取重试退避配置 - 读取策略与延迟上限
+#[test]
+fn test_get_retry_backoff_with_config() {
+    let mut network_config = HashMap::new();
+    network_config.insert("retry_backoff".to_string(), create_feature_config(" Linear "));
+    network_config.insert("retry_max_delay_ms".to_string(), create_feature_config("5000"));
+    let mut config_map = HashMap::new();
+    config_map.insert("network_config".to_string(), network_config);
+
+    let backoff = get_retry_backoff_from_config(&config_map);
+    assert_eq!(backoff.strategy, RetryBackoffStrategy::Linear);
+    assert_eq!(backoff.max_delay_ms, Some(5000));
+    assert_eq!(backoff.delay_for(2), RETRY_DELAY_BASE_MS * 2);
+    assert_eq!(backoff.delay_for(5), 5000);
+}
+
+/// 测试固定与线性退避
+#[test]
+fn test_calculate_retry_delay_fixed_and_linear() {
+    for attempt in 1..=5 {
+        assert_eq!(
+            calculate_retry_delay(attempt, RetryBackoffStrategy::Fixed, None),
+            RETRY_DELAY_BASE_MS
+        );
+        assert_eq!(
+            calculate_retry_delay(attempt, RetryBackoffStrategy::Linear, None),
+            RETRY_DELAY_BASE_MS * attempt as u64
+        );
+    }
+}
+
+/// 测试延迟上限与带抖动的指数退避
+#[test]
+fn test_calculate_retry_delay_cap_and_jitter() {
+    assert_eq!(calculate_retry_delay(10, RetryBackoffStrategy::Exponential, Some(30_000)), 30_000);
+    // 大数值重试次数不会溢出
+    assert_eq!(calculate_retry_delay(200, RetryBackoffStrategy::Exponential, None), u64::MAX);
+
+    for attempt in 1..=4 {
+        let full = RETRY_DELAY_BASE_MS * 2_u64.pow(attempt - 1);
+        for _ in 0..20 {
+            let delay =
+                calculate_retry_delay(attempt, RetryBackoffStrategy::ExponentialJitter, None);
+            assert!(delay >= full / 2 && delay <= full, "delay {} out of range", delay);
+        }
+    }
+    let capped = calculate_retry_delay(10, RetryBackoffStrategy::ExponentialJitter, Some(4000));
+    assert!((2000..=4000).contains(&capped));
+}
+
+// ============================================================================
+// API 协议（dialect）选择测试
+// ============================================================================
+
+fn provider_config(name: &str, value: &str) -> LLMProviderConfig {
+    LLMProviderConfig {
+        id: 0,
+        name: name.to_string(),
+        llm_provider_id: 1,
+        value: value.to_string(),
+        append_location: "header".to_string(),
+        is_addition: false,
+    }
+}
+
+/// 测试显式配置的 API 协议优先于 API 类型和模型名推断
+#[test]
+fn test_api_dialect_routes_to_selected_adapter() {
+    use genai::adapter::AdapterKind;
+
+    // OpenAI 兼容类型的提供商代理 Anthropic Messages API 的 Claude 模型
+    let configs = vec![
+        provider_config("endpoint", "https://proxy.example.com/"),
+        provider_config("api_dialect", "Anthropic"),
+    ];
+    let kind = resolve_adapter_kind(&configs, "gpt-4o", "openai_api").unwrap();
+    assert_eq!(kind, AdapterKind::Anthropic);
+    assert_eq!(get_default_endpoint(kind), "https://api.anthropic.com/");
+
+    let gemini = vec![provider_config("api_dialect", "gemini")];
+    assert_eq!(resolve_adapter_kind(&gemini, "my-model", "openai").unwrap(), AdapterKind::Gemini);
+
+    // 未配置或 auto 时保持原有推断逻辑
+    let auto = vec![provider_config("api_dialect", "auto")];
+    assert_eq!(resolve_adapter_kind(&auto, "claude-3", "unknown").unwrap(), AdapterKind::Anthropic);
+    assert_eq!(resolve_adapter_kind(&[], "gpt-4o", "openai").unwrap(), AdapterKind::OpenAI);
+}
+
+/// 测试错误的 API 协议配置返回清晰的错误信息
+#[test]
+fn test_api_dialect_misconfigured_returns_clear_error() {
+    let err = parse_api_dialect("anthropics").unwrap_err().to_string();
+    assert!(err.contains("anthropics"));
+    assert!(err.contains("anthropic, gemini"));
+
+    let configs =
+        vec![provider_config("api_key", "sk-test"), provider_config("api_dialect", "soap")];
+    let result = create_client_with_config(
+        &configs,
+        "gpt-4o",
+        "openai_api",
+        None,
+        false,
+        None,
+        false,
+        &HashMap::new(),
+    );
+    assert!(result.is_err(), "misconfigured dialect should fail before any request is sent");
+}
+
+/// 测试 Azure OpenAI 配置解析与部署名映射
+/// 验证内容：
+/// - 资源名生成 v1 接口地址，未配置资源名时不覆盖 endpoint
+/// - v1 不使用经典的部署地址
+/// - 已映射的模型使用部署名，未映射的模型沿用模型代码
+/// - 部署列表按模型代码排序
+#[test]
+fn test_azure_config_deployment_mapping() {
+    let configs = vec![
+        provider_config("api_key", "azure-key"),
+        provider_config("resource_name", "my-resource"),
+        provider_config("deployment_names", r#"{"gpt-4o": "prod-gpt4o", "gpt-4o-mini": "mini"}"#),
+    ];
+    let azure = AzureConfig::from_configs(&configs).unwrap();
+    assert_eq!(azure.api_version, "v1");
+    assert!(!azure.uses_deployment_urls());
+    assert_eq!(
+        azure.base_url().as_deref(),
+        Some("https://my-resource.openai.azure.com/openai/v1/")
+    );
+    assert_eq!(azure.deployment_for("gpt-4o"), "prod-gpt4o");
+    assert_eq!(azure.deployment_for("o3"), "o3");
+    assert_eq!(
+        azure.deployment_list(),
+        vec![
+            ("gpt-4o".to_string(), "prod-gpt4o".to_string()),
+            ("gpt-4o-mini".to_string(), "mini".to_string()),
+        ]
+    );
+
+    let no_resource = AzureConfig::from_configs(&[]).unwrap();
+    assert!(no_resource.base_url().is_none());
+    assert!(no_resource.deployment_list().is_empty());
+}
+
+/// 测试 Azure OpenAI 日期版本使用经典的部署地址
+/// 验证内容：
+/// - 日期版本（含 -preview）可通过校验
+/// - 资源名生成资源根地址，部署地址带部署名和 api-version
+/// - 自定义 endpoint 作为资源根地址时同样生成部署地址，客户端可正常创建
+#[test]
+fn test_azure_config_classic_deployment_urls() {
+    let configs = vec![
+        provider_config("api_key", "azure-key"),
+        provider_config("resource_name", "my-resource"),
+        provider_config("api_version", "2024-10-21"),
+        provider_config("deployment_names", r#"{"gpt-4o": "prod-gpt4o"}"#),
+    ];
+    let azure = AzureConfig::from_configs(&configs).unwrap();
+    assert!(azure.uses_deployment_urls());
+    let resource_url = azure.base_url().unwrap();
+    assert_eq!(resource_url, "https://my-resource.openai.azure.com/");
+    assert_eq!(
+        azure.deployment_url(&resource_url, &azure.deployment_for("gpt-4o")),
+        "https://my-resource.openai.azure.com/openai/deployments/prod-gpt4o/chat/completions?api-version=2024-10-21#"
+    );
+
+    let preview = AzureConfig::from_configs(&[
+        provider_config("api_version", "2025-01-01-preview"),
+        provider_config("endpoint", "https://proxy.example.com"),
+    ])
+    .unwrap();
+    assert!(preview.base_url().is_none());
+    assert_eq!(
+        preview.deployment_url("https://proxy.example.com", "o3"),
+        "https://proxy.example.com/openai/deployments/o3/chat/completions?api-version=2025-01-01-preview#"
+    );
+
+    let result = create_client_with_config(
+        &configs,
+        "gpt-4o",
+        "azure",
+        None,
+        false,
+        None,
+        false,
+        &HashMap::new(),
+    );
+    assert!(result.is_ok());
+}
+
+/// 测试 Azure OpenAI 错误配置返回清晰的错误信息
+/// 验证内容：
+/// - 不支持的 api_version 报错并提示可用版本
+/// - 部署名映射不是合法 JSON 时报错
+/// - azure 类型路由到 OpenAI 适配器，客户端可正常创建
+#[test]
+fn test_azure_config_errors_and_client() {
+    use genai::adapter::AdapterKind;
+
+    let bad_version = vec![provider_config("api_version", "2024-13-01")];
+    let err = AzureConfig::from_configs(&bad_version).unwrap_err().to_string();
+    assert!(err.contains("2024-13-01"));
+    assert!(err.contains("v1"));
+    assert!(AzureConfig::from_configs(&[provider_config("api_version", "latest")]).is_err());
+
+    let bad_json = vec![provider_config("deployment_names", "gpt-4o=prod")];
+    assert!(AzureConfig::from_configs(&bad_json).is_err());
+
+    assert_eq!(resolve_adapter_kind(&[], "my-deployment", "azure").unwrap(), AdapterKind::OpenAI);
+
+    let configs = vec![
+        provider_config("api_key", "azure-key"),
+        provider_config("resource_name", "my-resource"),
+        provider_config("deployment_names", r#"{"gpt-4o": "prod-gpt4o"}"#),
+    ];
+    let result = create_client_with_config(
+        &configs,
+        "gpt-4o",
+        "azure",
+        None,
+        false,
+        None,
+        false,
+        &HashMap::new(),
+    );
+    assert!(result.is_ok());
+    assert!(create_client_with_config(
+        &bad_version,
+        "gpt-4o",
+        "azure",
+        None,
+        false,
+        None,
+        false,
+        &HashMap::new()
+    )
+    .is_err());
+}
+
+/// 测试开关类配置值解析
+/// 验证内容：
+/// - 1/true/yes/on 与 0/false/no/off 不区分大小写、忽略首尾空白
+/// - 无法识别的值返回 None，由调用方决定默认值
+#[test]
+fn test_parse_config_bool() {
+    for value in ["1", "true", "YES", " on "] {
+        assert_eq!(parse_config_bool(value), Some(true), "{}", value);
+    }
+    for value in ["0", "False", "no", "OFF"] {
+        assert_eq!(parse_config_bool(value), Some(false), "{}", value);
+    }
+    assert_eq!(parse_config_bool(""), None);
+    assert_eq!(parse_config_bool("enabled"), None);
+}
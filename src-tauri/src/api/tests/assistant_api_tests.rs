@@ -0,0 +1,30 @@
+use crate::api::assistant_api::builtin_assistant_templates;
+use std::collections::HashSet;
+
+/// 测试内置助手模板可以解析，且 id 唯一、内容完整
+#[test]
+fn test_builtin_assistant_templates_are_valid() {
+    let templates = builtin_assistant_templates().unwrap();
+    let ids: HashSet<&str> = templates.iter().map(|template| template.id.as_str()).collect();
+    assert_eq!(ids.len(), templates.len(), "template ids must be unique");
+    for id in ["coder", "researcher", "writer", "translator"] {
+        assert!(ids.contains(id), "missing builtin template {}", id);
+    }
+
+    for template in &templates {
+        assert!(!template.name.trim().is_empty());
+        assert!(!template.prompt.trim().is_empty());
+        let config_names: Vec<&str> =
+            template.model_configs.iter().map(|config| config.name.as_str()).collect();
+        assert!(config_names.contains(&"temperature"), "{} has no temperature", template.id);
+        assert!(config_names.contains(&"stream"), "{} has no stream", template.id);
+    }
+}
+
+/// 测试研究助手模板默认启用内置搜索工具
+#[test]
+fn test_researcher_template_enables_search() {
+    let templates = builtin_assistant_templates().unwrap();
+    let researcher = templates.iter().find(|template| template.id == "researcher").unwrap();
+    assert_eq!(researcher.mcp_servers, vec!["aipp:search".to_string()]);
+}
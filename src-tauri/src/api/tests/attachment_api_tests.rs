@@ -0,0 +1,97 @@
+use crate::api::attachment_api::{
+    build_attachment_context, extract_pdf_attachment_text, format_pdf_attachment_text,
+    wait_for_pending_ocr, PendingOcrGuard,
+};
+use crate::db::conversation_db::{AttachmentType, MessageAttachment};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+fn attachment(
+    id: i64,
+    attachment_type: AttachmentType,
+    name: &str,
+    content: &str,
+) -> MessageAttachment {
+    MessageAttachment {
+        id,
+        message_id: -1,
+        attachment_type,
+        attachment_url: Some(name.to_string()),
+        attachment_content: Some(content.to_string()),
+        attachment_hash: None,
+        use_vector: false,
+        token_count: Some(0),
+    }
+}
+
+#[test]
+fn test_format_pdf_attachment_text_adds_page_markers() {
+    let pages = vec![
+        "  1. Introduction\n".to_string(),
+        "2. Scope".to_string(),
+        "3. Requirements\nThe system shall...".to_string(),
+    ];
+    let text = format_pdf_attachment_text(&pages, 10_000);
+    assert_eq!(
+        text,
+        "[第 1 页]\n1. Introduction\n\n[第 2 页]\n2. Scope\n\n[第 3 页]\n3. Requirements\nThe system shall..."
+    );
+}
+
+#[test]
+fn test_format_pdf_attachment_text_truncates_with_notice() {
+    let pages: Vec<String> =
+        (1..=50).map(|i| format!("第 {} 节内容 {}", i, "文字".repeat(20))).collect();
+    let text = format_pdf_attachment_text(&pages, 200);
+    assert!(text.starts_with("[第 1 页]\n"));
+    assert!(text.contains("（共 50 页）"), "truncation notice missing: {}", text);
+    let (kept, notice) = text.rsplit_once("\n\n[PDF 内容过长").unwrap();
+    assert_eq!(kept.chars().count(), 200);
+    assert!(notice.contains("页处截断"));
+    assert!(!text.contains("第 50 节"));
+}
+
+#[test]
+fn test_extract_pdf_attachment_text_rejects_invalid_pdf() {
+    let error = extract_pdf_attachment_text(b"not a pdf").unwrap_err();
+    assert!(error.contains("无法解析 PDF"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_build_attachment_context_includes_text_and_ocr() {
+    let attachments = vec![
+        attachment(1, AttachmentType::Text, "notes.txt", "hello"),
+        attachment(2, AttachmentType::Image, "error.png", "data:image/png;base64,AAAA"),
+        attachment(3, AttachmentType::Image, "photo.png", "data:image/png;base64,BBBB"),
+        attachment(4, AttachmentType::PDF, "spec.pdf", "[第 1 页]\nspec"),
+    ];
+    let ocr_texts = HashMap::from([(2, "Error: disk full".to_string())]);
+
+    let context = build_attachment_context(&attachments, &ocr_texts);
+    assert_eq!(
+        context,
+        "<fileattachment name=\"notes.txt\">hello</fileattachment>\n<imageattachment name=\"error.png\" source=\"ocr\">Error: disk full</imageattachment>"
+    );
+}
+
+/// 测试发送消息时会等待仍在识别的附件，识别任务结束（guard 释放）后立即继续
+#[tokio::test]
+async fn test_wait_for_pending_ocr_until_recognition_finishes() {
+    let attachment_id = 9_001;
+    let guard = PendingOcrGuard::register(attachment_id);
+    let started = Instant::now();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        drop(guard);
+    });
+
+    wait_for_pending_ocr(&[attachment_id, 9_002]).await;
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_secs(5));
+
+    // 已结束的识别不再等待
+    let started = Instant::now();
+    wait_for_pending_ocr(&[attachment_id]).await;
+    assert!(started.elapsed() < Duration::from_millis(50));
+}
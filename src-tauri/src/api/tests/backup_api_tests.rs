@@ -0,0 +1,123 @@
+use crate::api::backup_api::{
+    create_backup, is_backup_due, list_backups_in, rotate_backups, BackupSettings,
+};
+use crate::db::system_db::FeatureConfig;
+use chrono::{Duration as ChronoDuration, Local, TimeZone};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn backup_config(entries: &[(&str, &str)]) -> HashMap<String, HashMap<String, FeatureConfig>> {
+    let config = entries
+        .iter()
+        .map(|(key, value)| {
+            let config = FeatureConfig {
+                id: None,
+                feature_code: "data_backup".to_string(),
+                key: key.to_string(),
+                value: value.to_string(),
+                data_type: "string".to_string(),
+                description: None,
+            };
+            (key.to_string(), config)
+        })
+        .collect();
+    HashMap::from([("data_backup".to_string(), config)])
+}
+
+fn create_sample_db(db_dir: &Path, name: &str, rows: i64) {
+    let conn = Connection::open(db_dir.join(name)).unwrap();
+    conn.execute_batch("CREATE TABLE item (id INTEGER PRIMARY KEY);").unwrap();
+    for id in 0..rows {
+        conn.execute("INSERT INTO item (id) VALUES (?1)", [id]).unwrap();
+    }
+}
+
+/// 测试备份配置的默认值与解析
+#[test]
+fn test_backup_settings_from_config() {
+    let default_dir = PathBuf::from("/tmp/aipp-backups");
+    let settings = BackupSettings::from_config(&HashMap::new(), default_dir.clone());
+    assert!(!settings.enabled);
+    assert_eq!(settings.interval, Duration::from_secs(24 * 3600));
+    assert_eq!(settings.keep_count, 7);
+    assert_eq!(settings.directory, default_dir);
+
+    let config = backup_config(&[
+        ("backup_enabled", "true"),
+        ("backup_interval_hours", "6"),
+        ("backup_keep_count", "0"),
+        ("backup_directory", "/data/backups"),
+    ]);
+    let settings = BackupSettings::from_config(&config, default_dir);
+    assert!(settings.enabled);
+    assert_eq!(settings.interval, Duration::from_secs(6 * 3600));
+    // 至少保留一份
+    assert_eq!(settings.keep_count, 1);
+    assert_eq!(settings.directory, PathBuf::from("/data/backups"));
+}
+
+/// 测试备份会快照所有数据库文件，忽略其他文件，快照内容可读
+#[test]
+fn test_create_backup_snapshots_databases() {
+    let db_dir = tempdir().unwrap();
+    let backup_root = tempdir().unwrap();
+    create_sample_db(db_dir.path(), "conversation.db", 3);
+    create_sample_db(db_dir.path(), "llm.db", 1);
+    std::fs::write(db_dir.path().join("notes.txt"), "ignored").unwrap();
+
+    let now = Local.with_ymd_and_hms(2024, 6, 1, 3, 0, 0).unwrap();
+    let backup = create_backup(db_dir.path(), backup_root.path(), now).unwrap();
+    assert_eq!(backup.name, "aipp-backup-20240601-030000");
+    assert_eq!(backup.file_count, 2);
+    assert_eq!(backup.created_at, now);
+
+    let conn = Connection::open(Path::new(&backup.path).join("conversation.db")).unwrap();
+    let count: i64 = conn.query_row("SELECT count(*) FROM item", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 3);
+
+    // 同一秒内重复备份不会覆盖已有备份
+    assert!(create_backup(db_dir.path(), backup_root.path(), now).is_err());
+}
+
+/// 测试轮转只保留最新的 N 份，列表按时间从新到旧排序
+#[test]
+fn test_rotate_backups_keeps_latest() {
+    let db_dir = tempdir().unwrap();
+    let backup_root = tempdir().unwrap();
+    create_sample_db(db_dir.path(), "system.db", 1);
+    std::fs::create_dir(backup_root.path().join("unrelated")).unwrap();
+
+    let start = Local.with_ymd_and_hms(2024, 6, 1, 3, 0, 0).unwrap();
+    for day in 0..4 {
+        create_backup(db_dir.path(), backup_root.path(), start + ChronoDuration::days(day))
+            .unwrap();
+    }
+
+    let removed = rotate_backups(backup_root.path(), 3).unwrap();
+    assert_eq!(removed, vec!["aipp-backup-20240601-030000".to_string()]);
+
+    let backups = list_backups_in(backup_root.path()).unwrap();
+    let names: Vec<&str> = backups.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "aipp-backup-20240604-030000",
+            "aipp-backup-20240603-030000",
+            "aipp-backup-20240602-030000"
+        ]
+    );
+    assert!(backup_root.path().join("unrelated").exists());
+}
+
+/// 测试距最近一次备份超过间隔才需要备份
+#[test]
+fn test_is_backup_due() {
+    let now = Local.with_ymd_and_hms(2024, 6, 2, 3, 0, 0).unwrap();
+    let interval = Duration::from_secs(24 * 3600);
+    assert!(is_backup_due(None, interval, now));
+    assert!(!is_backup_due(Some(now - ChronoDuration::hours(23)), interval, now));
+    assert!(is_backup_due(Some(now - ChronoDuration::hours(24)), interval, now));
+}
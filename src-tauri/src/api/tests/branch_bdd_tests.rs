@@ -1,6 +1,8 @@
 use crate::api::ai::conversation::{
-    build_chat_request_from_messages, build_message_list_from_db, filter_messages_for_parent_group,
-    BranchSelection, ToolCallStrategy,
+    annotate_partial_responses, build_chat_request_from_messages,
+    build_message_list_from_context_ids, build_message_list_from_db,
+    build_message_list_from_db_with_truncation, filter_messages_for_parent_group, BranchSelection,
+    ContextTruncation, ToolCallStrategy, PARTIAL_RESPONSE_NOTE,
 };
 use crate::api::ai::summary::get_latest_branch_messages;
 use crate::db::conversation_db::{Message, MessageAttachment};
@@ -451,3 +453,185 @@ fn given_regeneration_then_continue_when_latest_branch_then_keeps_new_tail() {
     let ids: Vec<i64> = result.iter().map(|msg| msg.id).collect();
     assert_eq!(ids, vec![1, 2, 3, 4, 6, 7, 8]);
 }
+
+#[test]
+fn given_pinned_old_message_when_truncating_context_then_pinned_message_survives() {
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let at = |secs: i64| base + Duration::seconds(secs);
+
+    let messages = vec![
+        wrap(make_message(1, "system", at(0), None, None, "system")),
+        wrap(make_message(2, "user", at(1), None, None, "spec: use snake_case")),
+        wrap(make_message(3, "response", at(2), Some("g1"), None, "ack spec")),
+        wrap(make_message(4, "user", at(3), None, None, "q2")),
+        wrap(make_message(5, "response", at(4), Some("g2"), None, "r2")),
+        wrap(make_message(6, "user", at(5), None, None, "q3")),
+        wrap(make_message(7, "response", at(6), Some("g3"), None, "r3")),
+    ];
+
+    let truncation = ContextTruncation {
+        max_messages: 2,
+        pinned_message_ids: [2].into_iter().collect(),
+        ..Default::default()
+    };
+    let list = build_message_list_from_db_with_truncation(
+        &messages,
+        BranchSelection::LatestBranch,
+        Some(&truncation),
+    );
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    assert_eq!(contents, vec!["system", "spec: use snake_case", "q3", "r3"]);
+
+    // 未置顶时同样截断，旧的 spec 被丢弃
+    let unpinned = ContextTruncation { max_messages: 2, ..Default::default() };
+    let list = build_message_list_from_db_with_truncation(
+        &messages,
+        BranchSelection::LatestBranch,
+        Some(&unpinned),
+    );
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    assert_eq!(contents, vec!["system", "q3", "r3"]);
+}
+
+#[test]
+fn given_tool_call_turn_when_truncating_context_then_keeps_whole_turns_without_orphans() {
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let at = |secs: i64| base + Duration::seconds(secs);
+
+    let messages = vec![
+        wrap(make_message(1, "system", at(0), None, None, "system")),
+        wrap(make_message(2, "user", at(1), None, None, "q1")),
+        wrap(make_message(
+            3,
+            "response",
+            at(2),
+            Some("g1"),
+            None,
+            &response_with_tool_call(1, "call tool"),
+        )),
+        wrap(make_message(
+            4,
+            "tool_result",
+            at(3),
+            Some("g1"),
+            None,
+            &tool_result_content("call_1", "ok"),
+        )),
+        wrap(make_message(5, "response", at(4), Some("g1"), None, "r1")),
+        wrap(make_message(6, "user", at(5), None, None, "q2")),
+        wrap(make_message(7, "response", at(6), Some("g2"), None, "r2")),
+    ];
+
+    // 按条数截断会留下孤立的 tool_result，按轮次截断则整轮丢弃
+    let truncation = ContextTruncation { max_messages: 4, ..Default::default() };
+    let list = build_message_list_from_db_with_truncation(
+        &messages,
+        BranchSelection::LatestBranch,
+        Some(&truncation),
+    );
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    assert_eq!(contents, vec!["system", "q2", "r2"]);
+
+    // 最近一轮超过上限时仍完整保留
+    let truncation = ContextTruncation { max_messages: 1, ..Default::default() };
+    let list = build_message_list_from_db_with_truncation(
+        &messages,
+        BranchSelection::LatestBranch,
+        Some(&truncation),
+    );
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    assert_eq!(contents, vec!["system", "q2", "r2"]);
+
+    // 置顶的工具调用回复会带上对应的工具结果
+    let truncation = ContextTruncation {
+        max_messages: 2,
+        pinned_message_ids: [3].into_iter().collect(),
+        ..Default::default()
+    };
+    let list = build_message_list_from_db_with_truncation(
+        &messages,
+        BranchSelection::LatestBranch,
+        Some(&truncation),
+    );
+    let types: Vec<&str> = list.iter().map(|(message_type, _, _)| message_type.as_str()).collect();
+    assert_eq!(types, vec!["system", "response", "tool_result", "user", "response"]);
+}
+
+#[test]
+fn given_partial_response_when_building_context_then_marks_it_incomplete() {
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let at = |secs: i64| base + Duration::seconds(secs);
+
+    let mut messages = vec![
+        wrap(make_message(1, "user", at(0), None, None, "q1")),
+        wrap(make_message(2, "response", at(1), Some("g1"), None, "half an answer")),
+        wrap(make_message(3, "user", at(2), None, None, "q2")),
+        wrap(make_message(4, "response", at(3), Some("g2"), None, "r2")),
+    ];
+    annotate_partial_responses(&mut messages, &[2].into_iter().collect());
+
+    let list = build_message_list_from_db(&messages, BranchSelection::LatestBranch);
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    let partial = format!("half an answer\n\n{}", PARTIAL_RESPONSE_NOTE);
+    assert_eq!(contents, vec!["q1", partial.as_str(), "q2", "r2"]);
+}
+
+#[test]
+fn given_context_message_ids_when_building_one_off_request_then_only_selected_messages_assembled() {
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let at = |secs: i64| base + Duration::seconds(secs);
+
+    let messages = vec![
+        wrap(make_message(1, "system", at(0), None, None, "system")),
+        wrap(make_message(2, "user", at(1), None, None, "q1")),
+        wrap(make_message(3, "response", at(2), Some("g1"), None, "r1")),
+        wrap(make_message(4, "user", at(3), None, None, "q2")),
+        wrap(make_message(
+            5,
+            "response",
+            at(4),
+            Some("g2"),
+            None,
+            &response_with_tool_call(1, "r2"),
+        )),
+        wrap(make_message(
+            6,
+            "tool_result",
+            at(5),
+            None,
+            None,
+            &tool_result_content("call_1", "ok"),
+        )),
+        wrap(make_message(7, "user", at(6), None, None, "q3")),
+        wrap(make_message(8, "response", at(7), Some("g3"), None, "r3")),
+    ];
+
+    // 选择顺序与创建顺序不同，组装结果仍按对话顺序排列
+    let mut list = build_message_list_from_context_ids(&messages, &[5, 2]);
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    assert_eq!(
+        contents,
+        vec![
+            "system",
+            "q1",
+            response_with_tool_call(1, "r2").as_str(),
+            tool_result_content("call_1", "ok").as_str(),
+        ]
+    );
+
+    list.push(("user".to_string(), "new question".to_string(), vec![]));
+    let result = build_chat_request_from_messages(&list, ToolCallStrategy::Native, None);
+    let request_messages = result.chat_request.messages;
+    assert_eq!(request_messages.len(), 5);
+    assert!(matches!(&request_messages[0].role, ChatRole::System));
+    assert!(matches!(&request_messages[1].role, ChatRole::User));
+    assert!(matches!(&request_messages[2].role, ChatRole::Assistant));
+    assert_eq!(request_messages[2].content.tool_calls().len(), 1);
+    assert!(matches!(&request_messages[3].role, ChatRole::Tool));
+    assert!(matches!(&request_messages[4].role, ChatRole::User));
+
+    // 未选择带工具调用的回复时，工具结果不会被带入
+    let list = build_message_list_from_context_ids(&messages, &[7]);
+    let contents: Vec<&str> = list.iter().map(|(_, content, _)| content.as_str()).collect();
+    assert_eq!(contents, vec!["system", "q3"]);
+}
@@ -1,7 +1,10 @@
 use crate::api::ai::chat::{
-    extract_assistant_from_message, parse_assistant_mentions, ParseOptions, PositionRestriction,
+    extract_assistant_from_message, get_user_friendly_error_message, next_with_idle_timeout,
+    parse_assistant_mentions, ParseOptions, PartialResponseError, PositionRestriction,
 };
 use crate::db::assistant_db::Assistant;
+use futures::stream::{self, StreamExt};
+use std::time::Duration;
 
 /// 创建测试用的助手列表
 fn create_test_assistants() -> Vec<Assistant> {
@@ -669,3 +672,75 @@ async fn test_parse_assistant_mentions_performance_with_remove_mentions() {
     // 性能断言：移除@提及操作也应该在合理时间内完成（比如250ms内）
     assert!(elapsed.as_millis() < 250, "解析和清理时间过长: {:?}", elapsed);
 }
+
+/// 测试流式空闲超时：发送一个 chunk 后挂起的流会在空闲超时后报错
+#[tokio::test]
+async fn test_next_with_idle_timeout_detects_stalled_stream() {
+    let mut stalled = stream::iter(vec!["first chunk"]).chain(stream::pending());
+    let idle_timeout = Some(Duration::from_millis(50));
+
+    let first = next_with_idle_timeout(&mut stalled, idle_timeout).await;
+    assert_eq!(first.unwrap(), Some("first chunk"));
+
+    let second = next_with_idle_timeout(&mut stalled, idle_timeout).await;
+    let error = second.unwrap_err();
+    assert_eq!(error.idle_timeout, Duration::from_millis(50));
+    assert!(error.to_string().contains("Stream stalled"));
+}
+
+/// 测试流式空闲超时：持续输出的慢速流每个 chunk 都会重新计时，不会被中断
+#[tokio::test]
+async fn test_next_with_idle_timeout_allows_slow_progressing_stream() {
+    let mut slow = Box::pin(stream::unfold(0, |n| async move {
+        if n < 4 {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            Some((n, n + 1))
+        } else {
+            None
+        }
+    }));
+    let idle_timeout = Some(Duration::from_millis(100));
+
+    let mut received = Vec::new();
+    while let Some(n) = next_with_idle_timeout(&mut slow, idle_timeout).await.unwrap() {
+        received.push(n);
+    }
+    // 总耗时超过空闲超时，但每个 chunk 间隔都在超时之内
+    assert_eq!(received, vec![0, 1, 2, 3]);
+}
+
+/// 测试流式中途出错保留部分回复时的错误
+/// 验证内容：
+/// - 经过 anyhow 传递后仍能识别为部分回复错误（重试循环据此停止重试）
+/// - 错误文本保留原始原因，友好提示按原始原因分类
+/// - 原始错误保留在错误链中，可以取回底层错误
+#[test]
+fn test_partial_response_error_is_detectable() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset");
+    let stream_error =
+        anyhow::Error::new(io_error).context("Stream processing failed: connection reset");
+    let error: anyhow::Error =
+        PartialResponseError { message_id: 42, partial_chars: 500, source: stream_error }.into();
+
+    assert!(error.is::<PartialResponseError>());
+    let partial = error.downcast_ref::<PartialResponseError>().unwrap();
+    assert_eq!(partial.message_id, 42);
+    assert_eq!(partial.partial_chars, 500);
+    assert_eq!(error.to_string(), "Stream processing failed: connection reset");
+    assert_eq!(get_user_friendly_error_message(&error), "网络连接异常，请检查网络设置");
+
+    let chain: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+    assert_eq!(
+        chain,
+        vec![
+            "Stream processing failed: connection reset",
+            "Stream processing failed: connection reset",
+            "connection reset",
+        ]
+    );
+    let root = error.root_cause().downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(root.kind(), std::io::ErrorKind::ConnectionReset);
+
+    let plain = anyhow::anyhow!("Stream processing failed: connection reset");
+    assert!(!plain.is::<PartialResponseError>());
+}
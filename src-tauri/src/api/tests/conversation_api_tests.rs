@@ -1,4 +1,4 @@
-use crate::api::conversation_api::process_message_versions;
+use crate::api::conversation_api::{build_clean_messages, process_message_versions};
 use crate::db::conversation_db::MessageDetail;
 use chrono::Utc;
 use uuid::Uuid;
@@ -38,6 +38,9 @@ fn create_message_detail(
         tool_calls_json: None,
         first_token_time: None,
         ttft_ms: None,
+        is_pinned: false,
+        render_mode: None,
+        is_partial: false,
     }
 }
 
@@ -711,3 +714,135 @@ async fn test_reasoning_response_with_regeneration() {
     assert_eq!(result[1].content, "Correct reasoning");
     assert_eq!(result[2].content, "Correct answer: 4");
 }
+
+// ============================================================================
+// 专注模式测试
+// ============================================================================
+
+/// 测试专注模式：移除 reasoning、去除 MCP 注释并折叠工具调用结果
+#[test]
+fn test_build_clean_messages_hides_reasoning_and_tool_calls() {
+    let hint = r#"<!-- MCP_TOOL_CALL:{"server_name":"search","tool_name":"web_search","parameters":"{}","call_id":7,"llm_call_id":"call_7"} -->"#;
+    let messages = vec![
+        quick_message(1, "user", "今天天气怎么样？", None, 0),
+        quick_message(2, "reasoning", "需要先查询天气", None, 1),
+        quick_message(3, "response", &format!("我来查一下。\n\n{}\n", hint), None, 2),
+        quick_message(4, "tool_result", "Tool execution completed:\n晴，25°C", None, 3),
+        quick_message(5, "tool_result", "Tool execution completed:\n湿度 40%", None, 4),
+        quick_message(6, "reasoning", "根据结果回答", None, 5),
+        quick_message(7, "response", "今天晴，25°C。", None, 6),
+    ];
+
+    let result = build_clean_messages(process_message_versions(messages));
+
+    let types: Vec<&str> = result.iter().map(|m| m.message_type.as_str()).collect();
+    assert_eq!(types, vec!["user", "response", "tool_result", "response"]);
+    assert_eq!(result[1].content, "我来查一下。\n\n[调用了 1 个工具: web_search]");
+    assert!(!result[1].content.contains("MCP_TOOL_CALL"));
+    assert_eq!(result[2].id, 4);
+    assert_eq!(result[2].content, "[已折叠 2 条工具结果]");
+    assert_eq!(result[3].content, "今天晴，25°C。");
+}
+
+// ============================================================================
+// 编辑自动分支测试
+// ============================================================================
+
+/// 测试编辑方式解析：助手配置优先于全局配置，均未配置时原地修改
+#[test]
+fn test_resolve_edit_behavior_prefers_assistant_config() {
+    use crate::api::conversation_api::{resolve_edit_behavior, EditBehavior};
+    use crate::db::tests::test_helpers::create_test_model_config;
+
+    let config = |value: &str| create_test_model_config("edit_behavior", value);
+
+    assert_eq!(resolve_edit_behavior(&[], None), EditBehavior::Truncate);
+    assert_eq!(resolve_edit_behavior(&[], Some("fork")), EditBehavior::Fork);
+    assert_eq!(resolve_edit_behavior(&[config("truncate")], Some("fork")), EditBehavior::Truncate);
+    assert_eq!(resolve_edit_behavior(&[config("default")], Some("fork")), EditBehavior::Fork);
+}
+
+/// 测试开启自动分支后编辑消息会创建新对话，原对话保持不变
+#[test]
+fn test_edit_with_auto_fork_keeps_original_conversation() {
+    use crate::api::conversation_api::{apply_message_edit, EditBehavior};
+    use crate::db::conversation_db::{Conversation, ConversationDatabase, Message, Repository};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = ConversationDatabase::from_path(temp_dir.path().join("conversation.db"));
+    db.create_tables().unwrap();
+
+    let conversation = db
+        .conversation_repo()
+        .unwrap()
+        .create(&Conversation {
+            id: 0,
+            name: "旅行计划".to_string(),
+            assistant_id: None,
+            created_time: Utc::now(),
+        })
+        .unwrap();
+
+    let base_time = Utc::now();
+    let message_repo = db.message_repo().unwrap();
+    let mut message_ids = Vec::new();
+    for (offset, (message_type, content)) in [
+        ("user", "去哪玩？"),
+        ("response", "去杭州"),
+        ("user", "预算 1000"),
+        ("response", "住青旅"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let message = message_repo
+            .create(&Message {
+                id: 0,
+                parent_id: None,
+                conversation_id: conversation.id,
+                message_type: message_type.to_string(),
+                content: content.to_string(),
+                llm_model_id: None,
+                llm_model_name: None,
+                created_time: base_time + chrono::Duration::seconds(offset as i64),
+                start_time: None,
+                finish_time: None,
+                token_count: 0,
+                input_token_count: 0,
+                output_token_count: 0,
+                generation_group_id: None,
+                parent_group_id: None,
+                tool_calls_json: None,
+                first_token_time: None,
+                ttft_ms: None,
+            })
+            .unwrap();
+        message_ids.push(message.id);
+    }
+
+    let result = apply_message_edit(&db, message_ids[2], "预算 5000", EditBehavior::Fork).unwrap();
+
+    assert!(result.forked);
+    assert_ne!(result.conversation_id, conversation.id);
+    assert!(!message_ids.contains(&result.message_id));
+
+    // 新对话只包含编辑点及之前的消息，且编辑点为修改后的内容
+    let forked: Vec<Message> = message_repo
+        .list_by_conversation_id(result.conversation_id)
+        .unwrap()
+        .into_iter()
+        .map(|(message, _)| message)
+        .collect();
+    let forked_contents: Vec<&str> = forked.iter().map(|m| m.content.as_str()).collect();
+    assert_eq!(forked_contents, vec!["去哪玩？", "去杭州", "预算 5000"]);
+    assert_eq!(forked[2].id, result.message_id);
+
+    // 原对话完整保留
+    let original: Vec<String> = message_repo
+        .list_by_conversation_id(conversation.id)
+        .unwrap()
+        .into_iter()
+        .map(|(message, _)| message.content)
+        .collect();
+    assert_eq!(original, vec!["去哪玩？", "去杭州", "预算 1000", "住青旅"]);
+}
@@ -0,0 +1,141 @@
+use crate::api::highlight_api::{
+    highlight_code_for_export, highlight_diff_html, import_theme_file, list_syntect_themes,
+    load_custom_themes, parse_tm_theme,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn write_dark_theme(dir: &Path, file_name: &str, theme_name: &str) -> PathBuf {
+    let content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>{}</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>background</key>
+                <string>#101418</string>
+                <key>foreground</key>
+                <string>#D8DEE9</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>keyword, storage</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#FF3366</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#,
+        theme_name
+    );
+    let path = dir.join(file_name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_import_custom_theme_and_highlight_rust() {
+    let source_dir = tempdir().unwrap();
+    let theme_dir = tempdir().unwrap();
+    let source = write_dark_theme(source_dir.path(), "my-dark.tmTheme", "Test Import Dark");
+
+    let info = import_theme_file(&source, theme_dir.path()).unwrap();
+    assert_eq!(info.name, "Test Import Dark");
+    assert!(info.is_dark);
+    assert!(theme_dir.path().join("Test Import Dark.tmTheme").exists());
+
+    let listed = list_syntect_themes();
+    assert!(listed.iter().any(|theme| theme.name == "Test Import Dark" && theme.is_dark));
+
+    let html =
+        highlight_code_for_export("rust", "fn main() { return; }", false, Some("Test Import Dark"))
+            .unwrap();
+    assert!(html.contains("#101418"), "background of the imported theme should be used: {}", html);
+    assert!(
+        html.contains("#ff3366"),
+        "keyword color of the imported theme should be used: {}",
+        html
+    );
+}
+
+#[test]
+fn test_import_invalid_theme_returns_parse_error() {
+    let source_dir = tempdir().unwrap();
+    let theme_dir = tempdir().unwrap();
+    let source = source_dir.path().join("broken.tmTheme");
+    fs::write(&source, "this is not a plist").unwrap();
+
+    let before = list_syntect_themes().len();
+    let error = import_theme_file(&source, theme_dir.path()).unwrap_err();
+    assert!(error.contains("主题文件解析失败"), "unexpected error: {}", error);
+    assert_eq!(list_syntect_themes().len(), before);
+    assert_eq!(fs::read_dir(theme_dir.path()).unwrap().count(), 0);
+    assert!(list_syntect_themes().iter().all(|theme| theme.name != "broken"));
+}
+
+#[test]
+fn test_load_custom_themes_skips_invalid_files() {
+    let theme_dir = tempdir().unwrap();
+    write_dark_theme(theme_dir.path(), "persisted.tmTheme", "Test Persisted Dark");
+    fs::write(theme_dir.path().join("broken.tmTheme"), "<plist>").unwrap();
+    fs::write(theme_dir.path().join("notes.txt"), "ignored").unwrap();
+
+    assert_eq!(load_custom_themes(theme_dir.path()), 1);
+    assert!(list_syntect_themes().iter().any(|theme| theme.name == "Test Persisted Dark"));
+}
+
+const RUST_PATCH: &str = "diff --git a/src/main.rs b/src/main.rs
+index 83db48f..bf269f4 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    println!(\"old\");
++    println!(\"new <value>\");
+ }
+";
+
+#[test]
+fn test_highlight_diff_marks_added_removed_and_context_lines() {
+    let html = highlight_code_for_export("diff", RUST_PATCH, true, None).unwrap();
+    assert_eq!(html.matches("diff-line diff-added").count(), 1);
+    assert_eq!(html.matches("diff-line diff-removed").count(), 1);
+    assert_eq!(html.matches("diff-line diff-context").count(), 2);
+    assert_eq!(html.matches("diff-line diff-hunk").count(), 1);
+    assert_eq!(html.matches("diff-line diff-header").count(), 4);
+    assert!(html.contains("rgba(46,160,67,0.18)"));
+    assert!(html.contains("rgba(248,81,73,0.18)"));
+    // 内容被转义，不会被当作 HTML 标签
+    assert!(html.contains("&lt;value&gt;"));
+    assert!(!html.contains("<value>"));
+}
+
+#[test]
+fn test_highlight_diff_applies_inner_language() {
+    let source_dir = tempdir().unwrap();
+    let path = write_dark_theme(source_dir.path(), "diff.tmTheme", "Test Diff Dark");
+    let (_, theme) = parse_tm_theme(&path).unwrap();
+
+    // 语言由 +++ 文件头推断，也可以通过 diff-rust 显式指定
+    for inner_lang in [None, Some("rust")] {
+        let html = highlight_diff_html(RUST_PATCH, inner_lang, &theme).unwrap();
+        assert!(html.contains("#ff3366"), "rust keywords should be highlighted: {}", html);
+    }
+    let plain = highlight_diff_html(RUST_PATCH, Some("text"), &theme).unwrap();
+    assert!(!plain.contains("#ff3366"));
+
+    let html = highlight_code_for_export("diff-rust", RUST_PATCH, true, None).unwrap();
+    assert!(html.contains("diff-added"));
+}
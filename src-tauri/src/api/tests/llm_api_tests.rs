@@ -0,0 +1,40 @@
+use crate::api::llm_api::{categorize_provider_error, ProviderErrorCategory};
+
+/// 测试提供商连通性测试的失败分类
+/// 验证内容：
+/// - 有状态码时按状态码区分认证、权限、限流、服务端等错误
+/// - 无状态码时按错误文本识别网络 / 代理问题
+#[test]
+fn test_categorize_provider_error() {
+    assert_eq!(
+        categorize_provider_error(Some(401), "invalid api key"),
+        ProviderErrorCategory::Auth
+    );
+    assert_eq!(categorize_provider_error(Some(403), ""), ProviderErrorCategory::Permission);
+    assert_eq!(categorize_provider_error(Some(404), ""), ProviderErrorCategory::NotFound);
+    assert_eq!(categorize_provider_error(Some(429), ""), ProviderErrorCategory::RateLimit);
+    assert_eq!(categorize_provider_error(Some(502), ""), ProviderErrorCategory::Server);
+    assert_eq!(categorize_provider_error(Some(400), ""), ProviderErrorCategory::InvalidRequest);
+    // 状态码优先于错误文本
+    assert_eq!(
+        categorize_provider_error(Some(401), "connection closed"),
+        ProviderErrorCategory::Auth
+    );
+
+    assert_eq!(
+        categorize_provider_error(None, "error sending request: Connection refused"),
+        ProviderErrorCategory::Network
+    );
+    assert_eq!(
+        categorize_provider_error(None, "proxy tunnel error: 407"),
+        ProviderErrorCategory::Network
+    );
+    assert_eq!(
+        categorize_provider_error(None, "dns error: failed to lookup address"),
+        ProviderErrorCategory::Network
+    );
+    assert_eq!(
+        categorize_provider_error(None, "unexpected payload"),
+        ProviderErrorCategory::Unknown
+    );
+}
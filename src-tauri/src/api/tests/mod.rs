@@ -1,12 +1,22 @@
 pub mod ai_api_tests;
 pub mod ai_config_tests;
+pub mod assistant_api_tests;
+pub mod attachment_api_tests;
+pub mod backup_api_tests;
 pub mod branch_bdd_tests;
 pub mod chat_tests;
 pub mod conversation_api_tests;
 pub mod copilot_api_tests;
+pub mod highlight_api_tests;
 pub mod integration_tests;
+pub mod llm_api_tests;
 pub mod mcp_detection_tests;
 pub mod mcp_registry_tests;
+pub mod model_fallback_tests;
 pub mod regenerate_tests;
 pub mod scheduled_task_api_tests;
 pub mod summary_tests;
+pub mod title_tests;
+pub mod token_statistics_api_tests;
+pub mod tool_budget_tests;
+pub mod tool_parallel_tests;
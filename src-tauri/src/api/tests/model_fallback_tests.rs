@@ -0,0 +1,113 @@
+use crate::api::ai::model_fallback::{
+    capture_usage_supported, fallback_chain_from_configs, fallback_chat_options, should_fall_back,
+    FallbackModelRef, MODEL_FALLBACK_CONFIG_KEY,
+};
+use crate::db::llm_db::{LLMModel, LLMProvider, ModelDetail};
+use crate::db::tests::test_helpers::create_test_model_config;
+
+fn model_detail(api_type: &str, model_code: &str) -> ModelDetail {
+    ModelDetail {
+        model: LLMModel {
+            id: 7,
+            name: model_code.to_string(),
+            llm_provider_id: 2,
+            code: model_code.to_string(),
+            description: String::new(),
+            vision_support: false,
+            audio_support: false,
+            video_support: false,
+        },
+        provider: LLMProvider {
+            id: 2,
+            name: "provider".to_string(),
+            api_type: api_type.to_string(),
+            description: String::new(),
+            is_official: false,
+            is_enabled: true,
+        },
+        configs: vec![],
+    }
+}
+
+fn code(model_code: &str, provider_id: i64) -> FallbackModelRef {
+    FallbackModelRef::Code { model_code: model_code.to_string(), provider_id }
+}
+
+/// 测试降级链配置解析
+/// 验证内容：
+/// - 支持 JSON 数组（字符串或数字）和逗号/换行分隔两种写法
+/// - 保持配置顺序，去掉重复项和无法解析的条目
+/// - 未配置时为空
+#[test]
+fn test_fallback_chain_from_configs() {
+    assert!(fallback_chain_from_configs(&[]).is_empty());
+    assert!(fallback_chain_from_configs(&[create_test_model_config(
+        MODEL_FALLBACK_CONFIG_KEY,
+        " "
+    )])
+    .is_empty());
+
+    let json = create_test_model_config(
+        MODEL_FALLBACK_CONFIG_KEY,
+        r#"["gpt-4o-mini%%2", 15, "bad-entry"]"#,
+    );
+    assert_eq!(
+        fallback_chain_from_configs(&[json]),
+        vec![code("gpt-4o-mini", 2), FallbackModelRef::Id(15)]
+    );
+
+    let plain = create_test_model_config(
+        MODEL_FALLBACK_CONFIG_KEY,
+        "claude-sonnet-4-5%%3, gpt-4o-mini%%2\ngpt-4o-mini%%2\ndeepseek-chat%%x",
+    );
+    assert_eq!(
+        fallback_chain_from_configs(&[plain]),
+        vec![code("claude-sonnet-4-5", 3), code("gpt-4o-mini", 2)]
+    );
+}
+
+/// 测试是否降级的判断
+/// 验证内容：
+/// - 认证失败、权限不足、请求错误等 4xx 不降级
+/// - 限流、服务端错误以及无状态码的网络错误会降级
+#[test]
+fn test_should_fall_back() {
+    assert!(!should_fall_back(Some(400)));
+    assert!(!should_fall_back(Some(401)));
+    assert!(!should_fall_back(Some(403)));
+    assert!(!should_fall_back(Some(404)));
+    assert!(should_fall_back(Some(429)));
+    assert!(should_fall_back(Some(500)));
+    assert!(should_fall_back(Some(503)));
+    assert!(should_fall_back(None));
+}
+
+/// 测试备用模型的请求参数
+/// 验证内容：
+/// - 采样参数来自助手配置，usage 捕获按备用模型自身的提供商判断，不沿用主模型的设置
+/// - 工具调用捕获沿用主模型请求的设置
+#[test]
+fn test_fallback_chat_options_are_built_per_model() {
+    let assistant_configs = vec![
+        create_test_model_config("temperature", "0.3"),
+        create_test_model_config("max_tokens", "2048"),
+    ];
+
+    let options =
+        fallback_chat_options(&assistant_configs, &model_detail("openai", "gemini-2.5-pro"), true);
+    assert_eq!(options.temperature, Some(0.3));
+    assert_eq!(options.max_tokens, Some(2048));
+    assert_eq!(options.capture_usage, Some(false));
+    assert_eq!(options.capture_tool_calls, Some(true));
+
+    let options = fallback_chat_options(
+        &assistant_configs,
+        &model_detail("anthropic", "claude-haiku"),
+        false,
+    );
+    assert_eq!(options.capture_usage, Some(true));
+    assert_eq!(options.capture_tool_calls, Some(false));
+
+    assert!(!capture_usage_supported("OpenAI_API", "Gemini-2.0-flash"));
+    assert!(capture_usage_supported("gemini", "gemini-2.0-flash"));
+}
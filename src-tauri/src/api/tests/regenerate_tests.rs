@@ -614,3 +614,196 @@ async fn test_message_filtering_for_regenerate() {
     assert_eq!(filtered_for_user[0].message_type, "system");
     assert_eq!(filtered_for_user[1].message_type, "user");
 }
+
+#[tokio::test]
+async fn test_regenerate_reusing_tool_results_does_not_rerun_tools() {
+    // 测试复用工具结果的重新生成：回填已存储的工具结果，不再执行工具
+    use crate::api::ai::conversation::{build_chat_request_from_messages, ToolCallStrategy};
+    use crate::api::ai::tool_budget::{is_forced_answer_turn, ForcedAnswerGuard};
+    use crate::api::ai::tool_replay::{RegenerateMode, REUSED_TOOL_RESULTS_PROMPT};
+    use crate::api::ai_api::build_regenerate_message_list;
+    use crate::db::conversation_db::{ConversationDatabase, Repository};
+    use crate::db::mcp_db::MCPDatabase;
+    use rusqlite::Connection;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = ConversationDatabase::from_path(temp_dir.path().join("conversation.db"));
+    db.create_tables().unwrap();
+    let conversation =
+        db.conversation_repo().unwrap().create(&create_test_conversation(0, Some(1))).unwrap();
+    let conversation_id = conversation.id;
+
+    let base_time = Utc::now();
+    let message_repo = db.message_repo().unwrap();
+    for (offset, (message_type, content)) in
+        [("system", "You are helpful"), ("user", "Search rust")].iter().enumerate()
+    {
+        message_repo
+            .create(&create_test_message(
+                0,
+                None,
+                conversation_id,
+                message_type,
+                content,
+                None,
+                None,
+                None,
+                None,
+                base_time + chrono::Duration::seconds(offset as i64),
+            ))
+            .unwrap();
+    }
+    let mut tool_response = create_test_message(
+        0,
+        None,
+        conversation_id,
+        "response",
+        "Let me look it up.\n<!-- MCP_TOOL_CALL:{\"server_name\":\"search\",\"tool_name\":\"web_search\",\"parameters\":\"{\\\"query\\\":\\\"rust\\\"}\",\"llm_call_id\":\"call_1\"} -->",
+        Some(1),
+        Some("gpt-4".to_string()),
+        Some(Uuid::new_v4().to_string()),
+        None,
+        base_time + chrono::Duration::seconds(2),
+    );
+    tool_response.tool_calls_json = Some(
+        r#"[{"call_id":"call_1","fn_name":"search__web_search","fn_arguments":{}}]"#.to_string(),
+    );
+    let tool_response = message_repo.create(&tool_response).unwrap();
+
+    // 上一轮已经执行完成的工具调用记录
+    let mcp_db = MCPDatabase { conn: Connection::open_in_memory().unwrap() };
+    mcp_db.create_tables().unwrap();
+    let server_id = mcp_db
+        .upsert_mcp_server_with_builtin(
+            "search",
+            None,
+            "stdio",
+            Some("npx search"),
+            None,
+            None,
+            None,
+            None,
+            false,
+            true,
+            false,
+            true,
+            false,
+        )
+        .unwrap();
+    let call = mcp_db
+        .create_mcp_tool_call_with_llm_id(
+            conversation_id,
+            Some(tool_response.id),
+            server_id,
+            "search",
+            "web_search",
+            r#"{"query":"rust"}"#,
+            Some("call_1"),
+            Some(tool_response.id),
+        )
+        .unwrap();
+    mcp_db.update_mcp_tool_call_status(call.id, "success", Some("rust results"), None).unwrap();
+    let stored_before = mcp_db.get_mcp_tool_call(call.id).unwrap();
+
+    let mode = RegenerateMode::from_value(Some("reuse_tool_results"));
+    assert_eq!(mode, RegenerateMode::ReuseToolResults);
+    assert!(!mode.allows_tool_calls());
+    assert_eq!(RegenerateMode::from_value(None), RegenerateMode::Rerun);
+
+    // 普通重新生成只保留被重新生成回复之前的上下文
+    let rerun_list = build_regenerate_message_list(
+        &db,
+        &mcp_db,
+        &tool_response,
+        RegenerateMode::Rerun,
+        tool_response.generation_group_id.as_deref(),
+        None,
+    )
+    .unwrap();
+    let rerun_types: Vec<&str> = rerun_list.iter().map(|(t, _, _)| t.as_str()).collect();
+    assert_eq!(rerun_types, vec!["system", "user"]);
+
+    // 复用工具结果：已存储的结果被原样回填，并追加回答指令
+    let init_message_list = build_regenerate_message_list(
+        &db,
+        &mcp_db,
+        &tool_response,
+        mode,
+        tool_response.generation_group_id.as_deref(),
+        None,
+    )
+    .unwrap();
+    let types: Vec<&str> = init_message_list.iter().map(|(t, _, _)| t.as_str()).collect();
+    assert_eq!(types, vec!["system", "user", "response", "tool_result", "user"]);
+    assert_eq!(init_message_list[2].1, tool_response.content);
+    assert!(init_message_list[3].1.contains("Tool Call ID: call_1"));
+    assert!(init_message_list[3].1.contains("rust results"));
+    assert_eq!(init_message_list[4].1, REUSED_TOOL_RESULTS_PROMPT);
+
+    // 该轮不向模型提供工具，输出中的工具调用也不会被执行
+    let request =
+        build_chat_request_from_messages(&init_message_list, ToolCallStrategy::NonNative, None);
+    assert!(request.chat_request.tools.is_none());
+    {
+        let _guard = ForcedAnswerGuard::enter(conversation_id);
+        assert!(is_forced_answer_turn(conversation_id));
+    }
+
+    // 工具没有被重新执行：记录数量与状态保持不变
+    let stored_after = mcp_db.get_mcp_tool_calls_by_conversation(conversation_id).unwrap();
+    assert_eq!(stored_after.len(), 1);
+    assert_eq!(stored_after[0].status, "success");
+    assert_eq!(stored_after[0].result, stored_before.result);
+    assert_eq!(stored_after[0].started_time, stored_before.started_time);
+    assert_eq!(stored_after[0].finished_time, stored_before.finished_time);
+}
+
+#[test]
+fn test_reuse_tool_results_requires_completed_results() {
+    use crate::api::ai::tool_replay::build_reused_tool_turn;
+    use crate::db::mcp_db::MCPToolCall;
+
+    let response = create_test_message(
+        4,
+        None,
+        1,
+        "response",
+        "calling tool",
+        None,
+        None,
+        None,
+        None,
+        Utc::now(),
+    );
+    let pending = MCPToolCall {
+        id: 1,
+        conversation_id: 1,
+        message_id: Some(4),
+        subtask_id: None,
+        server_id: 1,
+        server_name: "search".to_string(),
+        tool_name: "web_search".to_string(),
+        parameters: "{}".to_string(),
+        status: "pending".to_string(),
+        result: None,
+        error: None,
+        created_time: String::new(),
+        started_time: None,
+        finished_time: None,
+        llm_call_id: Some("call_1".to_string()),
+        assistant_message_id: Some(4),
+        timeout_ms: None,
+    };
+    assert!(build_reused_tool_turn(&response, &[pending.clone()]).is_err());
+
+    // 部分调用尚未完成时同样拒绝复用
+    let done = MCPToolCall {
+        id: 2,
+        status: "failed".to_string(),
+        error: Some("timeout".to_string()),
+        llm_call_id: Some("call_2".to_string()),
+        ..pending.clone()
+    };
+    let err = build_reused_tool_turn(&response, &[pending, done]).unwrap_err();
+    assert!(err.contains("call_1"), "{}", err);
+}
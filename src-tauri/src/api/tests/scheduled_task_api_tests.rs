@@ -273,4 +273,171 @@ mod tests {
         assert!(parse_local_datetime("not a date").is_err());
         assert!(parse_local_datetime("").is_err());
     }
+
+    // ── cron schedule ────────────────────────────────────────────────
+
+    fn cron_config(cron: Option<&str>) -> ScheduleConfig<'_> {
+        ScheduleConfig {
+            schedule_type: "cron",
+            interval_value: None,
+            interval_unit: None,
+            start_time: None,
+            week_days: None,
+            month_days: None,
+            cron,
+            timezone: None,
+            run_at: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_next_run_at_cron_weekdays() {
+        use chrono::{Datelike, Local, Timelike, Utc, Weekday};
+
+        let base = Utc::now();
+        let next = compute_next_run_at_with_config(cron_config(Some("0 9 * * 1-5")), base)
+            .unwrap()
+            .expect("cron schedule should have a next run");
+        let local = next.with_timezone(&Local);
+
+        assert!(next > base);
+        assert_eq!((local.hour(), local.minute(), local.second()), (9, 0, 0));
+        assert!(!matches!(local.weekday(), Weekday::Sat | Weekday::Sun));
+        assert!(next - base <= chrono::Duration::days(4));
+    }
+
+    #[test]
+    fn test_compute_next_run_at_cron_rejects_malformed() {
+        let base = chrono::Utc::now();
+        assert!(compute_next_run_at_with_config(cron_config(None), base).is_err());
+        assert!(compute_next_run_at_with_config(cron_config(Some("  ")), base).is_err());
+        assert!(compute_next_run_at_with_config(cron_config(Some("0 25 * * *")), base).is_err());
+        assert!(compute_next_run_at_with_config(cron_config(Some("0 9 * *")), base).is_err());
+    }
+
+    fn daily_config(timezone: &str) -> ScheduleConfig<'_> {
+        ScheduleConfig {
+            schedule_type: "interval",
+            interval_value: Some(1),
+            interval_unit: Some("day"),
+            start_time: Some("02:30"),
+            week_days: None,
+            month_days: None,
+            cron: None,
+            timezone: Some(timezone),
+            run_at: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_next_run_at_in_timezone_across_dst() {
+        let utc =
+            |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&chrono::Utc);
+
+        // 2026-03-08 02:30 不存在，顺延到 03:00 EDT（07:00 UTC）
+        let next = compute_next_run_at_with_config(
+            daily_config("America/New_York"),
+            utc("2026-03-07T12:00:00Z"),
+        )
+        .unwrap();
+        assert_eq!(next, Some(utc("2026-03-08T07:00:00Z")));
+
+        // 夏令时开始后仍按本地 02:30 执行
+        let next = compute_next_run_at_with_config(
+            daily_config("America/New_York"),
+            utc("2026-03-08T08:00:00Z"),
+        )
+        .unwrap();
+        assert_eq!(next, Some(utc("2026-03-09T06:30:00Z")));
+
+        let eight_am =
+            ScheduleConfig { timezone: Some("Asia/Shanghai"), ..cron_config(Some("0 8 * * *")) };
+        let next = compute_next_run_at_with_config(eight_am, utc("2026-10-17T01:00:00Z")).unwrap();
+        assert_eq!(next, Some(utc("2026-10-18T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_compute_next_run_at_rejects_unknown_timezone() {
+        let config =
+            ScheduleConfig { timezone: Some("Mars/Olympus"), ..cron_config(Some("0 8 * * *")) };
+        assert!(compute_next_run_at_with_config(config, chrono::Utc::now()).is_err());
+        assert!(parse_timezone("America/New_York").is_ok());
+    }
+
+    // ── retry backoff ────────────────────────────────────────────────
+
+    #[test]
+    fn test_retry_delay_for_attempt_backs_off() {
+        assert_eq!(retry_delay_for_attempt(60, 1), 60);
+        assert_eq!(retry_delay_for_attempt(60, 2), 120);
+        assert_eq!(retry_delay_for_attempt(60, 4), 480);
+        // 过短的间隔按下限计算，过长的间隔不超过一天
+        assert_eq!(retry_delay_for_attempt(1, 1), 10);
+        assert_eq!(retry_delay_for_attempt(3600, 10), 24 * 60 * 60);
+    }
+
+    /// 间隔任务整点失败、几分钟后重试，重试之后的常规执行仍落在原来的整点网格上
+    #[test]
+    fn test_interval_retry_keeps_next_run_on_original_grid() {
+        use crate::db::scheduled_task_db::{ScheduledTask, ScheduledTaskDatabase};
+
+        let db = ScheduledTaskDatabase {
+            conn: rusqlite::Connection::open_in_memory().unwrap(),
+            db_path: std::path::PathBuf::new(),
+        };
+        db.create_tables().unwrap();
+        let utc =
+            |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&chrono::Utc);
+        let slot = utc("2026-03-02T10:00:00Z");
+        let task = db
+            .create_task(&ScheduledTask {
+                id: 0,
+                name: "每小时巡检".to_string(),
+                is_enabled: true,
+                schedule_type: "interval".to_string(),
+                interval_value: Some(1),
+                interval_unit: Some("hour".to_string()),
+                start_time: None,
+                week_days: None,
+                month_days: None,
+                run_at: None,
+                next_run_at: Some(slot),
+                last_run_at: None,
+                assistant_id: 1,
+                task_prompt: "检查服务状态".to_string(),
+                notify_prompt: "异常时通知".to_string(),
+                created_time: slot,
+                updated_time: slot,
+                cron: None,
+                timezone: Some("UTC".to_string()),
+                max_retries: 2,
+                retry_delay_secs: 300,
+                retry_attempt: 0,
+                retry_scheduled_time: None,
+            })
+            .unwrap();
+
+        // 首次执行：计划时间即原定时间，下一次为一小时后
+        let first_run = slot + chrono::Duration::seconds(2);
+        let plan = plan_scheduled_run(&task, first_run).unwrap();
+        assert_eq!(plan.scheduled_time, Some(slot));
+        assert_eq!(plan.next_run_at, Some(utc("2026-03-02T11:00:00Z")));
+        db.update_task(&ScheduledTask { next_run_at: plan.next_run_at, ..task.clone() }).unwrap();
+
+        // 执行失败，5 分钟后重试
+        let retry_at = first_run + chrono::Duration::seconds(300);
+        db.set_retry_state(task.id, 1, Some(retry_at), plan.scheduled_time).unwrap();
+        let retrying = db.read_task(task.id).unwrap().unwrap();
+        assert_eq!(retrying.next_run_at, Some(retry_at));
+
+        // 重试执行仍归属原计划时间，下一次常规执行不随重试后移
+        let plan = plan_scheduled_run(&retrying, retry_at + chrono::Duration::seconds(1)).unwrap();
+        assert_eq!(plan.scheduled_time, Some(slot));
+        assert_eq!(plan.next_run_at, Some(utc("2026-03-02T11:00:00Z")));
+
+        // 重试拖过了下一个整点时跳到之后的整点，而不是从当前时间再加一小时
+        let late = utc("2026-03-02T11:20:00Z");
+        let plan = plan_scheduled_run(&retrying, late).unwrap();
+        assert_eq!(plan.next_run_at, Some(utc("2026-03-02T12:00:00Z")));
+    }
 }
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::api::ai::title::{
+    count_user_turns, response_has_tool_calls, title_source_from_messages, TitleTrigger,
+    TitleTriggerConfig,
+};
+use crate::db::conversation_db::Message;
+use crate::db::system_db::FeatureConfig;
+use crate::db::tests::test_helpers::create_test_message;
+
+/// 辅助函数：按 id 依次排列创建时间的消息
+fn make_message(id: i64, message_type: &str, content: &str) -> Message {
+    Message {
+        id,
+        created_time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::seconds(id),
+        ..create_test_message(1, message_type, content, None, None)
+    }
+}
+
+fn summary_config(entries: &[(&str, &str)]) -> HashMap<String, HashMap<String, FeatureConfig>> {
+    let summary = entries
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.to_string(),
+                FeatureConfig {
+                    id: None,
+                    feature_code: "conversation_summary".to_string(),
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    data_type: "string".to_string(),
+                    description: None,
+                },
+            )
+        })
+        .collect();
+    HashMap::from([("conversation_summary".to_string(), summary)])
+}
+
+/// 测试未配置时保持原有行为：第一轮回复后生成标题
+#[test]
+fn test_title_trigger_defaults_to_first_response() {
+    let config = TitleTriggerConfig::from_config(&HashMap::new());
+    assert_eq!(config.trigger, TitleTrigger::FirstResponse);
+    assert!(!config.include_tool_results);
+    assert!(config.should_generate(1));
+    assert!(!config.should_generate(2));
+    assert!(!config.uses_conversation_history());
+}
+
+/// 测试配置为第 N 轮后生成时，标题推迟到该轮
+#[test]
+fn test_title_trigger_defers_to_configured_turn_count() {
+    let config = TitleTriggerConfig::from_config(&summary_config(&[
+        ("title_trigger", "after_turns"),
+        ("title_trigger_turns", "3"),
+    ]));
+    assert_eq!(config.trigger, TitleTrigger::AfterTurns(3));
+    assert!(!config.should_generate(1));
+    assert!(!config.should_generate(2));
+    assert!(config.should_generate(3));
+    assert!(!config.should_generate(4));
+    assert!(config.uses_conversation_history());
+
+    // 非法轮数按 1 处理
+    let config = TitleTriggerConfig::from_config(&summary_config(&[
+        ("title_trigger", "after_turns"),
+        ("title_trigger_turns", "0"),
+    ]));
+    assert_eq!(config.trigger, TitleTrigger::AfterTurns(1));
+}
+
+/// 测试仅手动生成时不会自动触发
+#[test]
+fn test_title_trigger_manual_never_generates() {
+    let config = TitleTriggerConfig::from_config(&summary_config(&[("title_trigger", "manual")]));
+    assert!((1..=5).all(|turn| !config.should_generate(turn)));
+}
+
+/// 测试标题上下文按需包含工具结果
+#[test]
+fn test_title_source_includes_tool_results_when_enabled() {
+    let messages = vec![
+        make_message(1, "system", "You are helpful"),
+        make_message(2, "user", "查一下北京天气"),
+        make_message(3, "response", "<!-- MCP_TOOL_CALL:{} -->"),
+        make_message(4, "tool_result", "北京 晴 25℃"),
+        make_message(5, "response", "北京今天晴"),
+        make_message(6, "user", "明天呢"),
+    ];
+    assert_eq!(count_user_turns(&messages), 2);
+
+    let (user_prompt, content) = title_source_from_messages(&messages, false);
+    assert_eq!(user_prompt, "查一下北京天气\n\n明天呢");
+    assert!(!content.contains("25℃"));
+
+    let (_, content) = title_source_from_messages(&messages, true);
+    assert!(content.contains("北京 晴 25℃"));
+    assert!(content.ends_with("北京今天晴"));
+}
+
+/// 测试编辑和重新生成留下的旧版本不计入用户轮数
+#[test]
+fn test_count_user_turns_uses_latest_branch() {
+    let grouped = |id: i64, message_type: &str, group: &str, parent_group: Option<&str>| Message {
+        generation_group_id: Some(group.to_string()),
+        parent_group_id: parent_group.map(str::to_string),
+        ..make_message(id, message_type, "content")
+    };
+    let messages = vec![
+        grouped(1, "user", "u1", None),
+        grouped(2, "response", "r1", None),
+        // 编辑第一条用户消息
+        grouped(3, "user", "u2", Some("u1")),
+        grouped(4, "response", "r2", None),
+        // 重新生成回复
+        grouped(5, "response", "r3", Some("r2")),
+    ];
+    assert_eq!(count_user_turns(&messages), 1);
+
+    let config = TitleTriggerConfig::default();
+    assert!(config.should_generate(count_user_turns(&messages)));
+}
+
+/// 测试包含工具结果时，首轮发起工具调用的回复推迟到工具结果返回后再生成标题
+#[test]
+fn test_include_tool_results_defers_first_response_title() {
+    let config =
+        TitleTriggerConfig::from_config(&summary_config(&[("title_include_tool_results", "true")]));
+    assert_eq!(config.trigger, TitleTrigger::FirstResponse);
+
+    assert!(response_has_tool_calls("", 1));
+    assert!(response_has_tool_calls(
+        "<mcp_tool_call><server_name>s</server_name></mcp_tool_call>",
+        0
+    ));
+    assert!(!response_has_tool_calls("北京今天晴", 0));
+
+    // 发起工具调用的回复等待工具结果，续写出的最终回复生成标题
+    assert!(config.waits_for_tool_results(true));
+    assert!(!config.waits_for_tool_results(false));
+    assert!(config.should_generate_after_tool_results(1));
+    assert!(!config.should_generate_after_tool_results(2));
+
+    // 未开启时保持在首轮回复后立即生成，续写不再生成
+    let config = TitleTriggerConfig::default();
+    assert!(!config.waits_for_tool_results(true));
+    assert!(!config.should_generate_after_tool_results(1));
+}
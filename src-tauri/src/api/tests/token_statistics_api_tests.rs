@@ -0,0 +1,200 @@
+use crate::api::token_statistics_api::{
+    build_usage_summary, compute_conversation_stats, count_words, usage_summary_window,
+    UsageSummaryRange,
+};
+use crate::db::conversation_db::{Message, UsageReportRow};
+use crate::db::llm_db::LLMModelPrice;
+use crate::db::tests::test_helpers::create_test_message;
+use chrono::{Duration, FixedOffset, TimeZone, Utc};
+use std::collections::HashMap;
+
+/// 辅助函数：按 id 依次排列创建时间的消息
+fn make_message(id: i64, message_type: &str, content: &str) -> Message {
+    Message {
+        id,
+        created_time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::seconds(id),
+        ..create_test_message(1, message_type, content, None, None)
+    }
+}
+
+fn mixed_conversation() -> Vec<Message> {
+    vec![
+        make_message(1, "system", "You are a helpful assistant."),
+        make_message(2, "user", "Hello there"),
+        make_message(3, "reasoning", "think"),
+        make_message(
+            4,
+            "response",
+            "Hi, how can I help?\n\n<!-- MCP_TOOL_CALL:{\"tool_name\":\"search\",\"call_id\":1} -->",
+        ),
+        make_message(5, "tool_result", "Tool execution completed: ok"),
+        make_message(6, "user", "写一首诗"),
+        make_message(7, "response", "床前明月光"),
+        make_message(8, "error", "AI请求失败"),
+    ]
+}
+
+/// 测试字数统计：英文按单词，中文按字
+#[test]
+fn test_count_words_mixed_languages() {
+    assert_eq!(count_words("Hello, world! It's fine."), 4);
+    assert_eq!(count_words("写一首诗"), 4);
+    assert_eq!(count_words("用 Rust 写代码"), 5);
+    assert_eq!(count_words("  "), 0);
+}
+
+/// 测试混合消息类型的对话统计，默认排除 reasoning 与 error
+#[test]
+fn test_conversation_stats_excludes_reasoning_and_errors_by_default() {
+    let messages = mixed_conversation();
+    let stats = compute_conversation_stats(&messages, false);
+
+    assert_eq!(stats.message_count, 8);
+    assert_eq!(stats.message_count_by_type["user"], 2);
+    assert_eq!(stats.message_count_by_type["response"], 2);
+    assert_eq!(stats.message_count_by_type["reasoning"], 1);
+    assert_eq!(stats.message_count_by_type["tool_result"], 1);
+    assert_eq!(stats.message_count_by_type["error"], 1);
+
+    assert_eq!(stats.user_message_count, 2);
+    assert_eq!(stats.user_word_count, 2 + 4);
+    assert_eq!(stats.user_char_count, 11 + 4);
+
+    // MCP 注释不计入 AI 输出
+    assert_eq!(stats.ai_output_message_count, 2);
+    assert_eq!(stats.ai_output_word_count, 5 + 5);
+    assert_eq!(stats.ai_output_char_count, 19 + 5);
+    assert_eq!(stats.avg_response_char_count, 12.0);
+    assert!(!stats.include_reasoning_and_errors);
+}
+
+/// 测试开启开关后 reasoning 与 error 计入 AI 输出
+#[test]
+fn test_conversation_stats_can_include_reasoning_and_errors() {
+    let messages = mixed_conversation();
+    let stats = compute_conversation_stats(&messages, true);
+
+    assert_eq!(stats.ai_output_message_count, 4);
+    assert_eq!(stats.ai_output_char_count, 19 + 5 + 5 + 6);
+    assert_eq!(stats.ai_output_word_count, 5 + 5 + 1 + 5);
+}
+
+/// 测试带多个附件导致的重复消息行只统计一次
+#[test]
+fn test_conversation_stats_deduplicates_message_rows() {
+    let user = make_message(1, "user", "one two");
+    let messages = vec![user.clone(), user];
+    let stats = compute_conversation_stats(&messages, false);
+
+    assert_eq!(stats.message_count, 1);
+    assert_eq!(stats.user_word_count, 2);
+}
+
+fn report_row(
+    period: &str,
+    model_id: i64,
+    model: &str,
+    assistant_id: i64,
+    tokens: (i64, i64),
+) -> UsageReportRow {
+    UsageReportRow {
+        period: period.to_string(),
+        llm_model_id: Some(model_id),
+        model_name: model.to_string(),
+        assistant_id: Some(assistant_id),
+        response_count: 1,
+        input_tokens: tokens.0,
+        output_tokens: tokens.1,
+        total_tokens: tokens.0 + tokens.1,
+    }
+}
+
+/// 测试用量窗口从起始日本地零点开始，日期列表包含今天
+#[test]
+fn test_usage_summary_window_starts_at_local_midnight() {
+    let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    let now = offset.with_ymd_and_hms(2024, 6, 10, 1, 30, 0).unwrap();
+
+    let (since, dates) = usage_summary_window(UsageSummaryRange::Last7Days, now);
+    assert_eq!(since, Utc.with_ymd_and_hms(2024, 6, 3, 16, 0, 0).unwrap());
+    assert_eq!(dates.len(), 7);
+    assert_eq!(dates.first().unwrap(), "2024-06-04");
+    assert_eq!(dates.last().unwrap(), "2024-06-10");
+
+    let (_, dates) = usage_summary_window(UsageSummaryRange::Last30Days, now);
+    assert_eq!(dates.len(), 30);
+}
+
+/// 测试按天、模型合并不同助手的用量，并补齐无用量的日期
+///
+/// 验证内容：
+/// - 同一天同一模型的多个助手行合并为一项
+/// - 同名模型挂在不同提供商下时分开统计
+/// - 整个窗口的模型排行按 total_tokens 从高到低
+#[test]
+fn test_build_usage_summary_groups_by_day_and_model() {
+    let dates: Vec<String> =
+        ["2024-06-01", "2024-06-02", "2024-06-03"].iter().map(|d| d.to_string()).collect();
+    let providers = HashMap::from([(1, "OpenAI".to_string()), (2, "OpenRouter".to_string())]);
+    let rows = vec![
+        report_row("2024-06-01", 1, "gpt-4o", 1, (100, 50)),
+        report_row("2024-06-01", 1, "gpt-4o", 2, (200, 100)),
+        report_row("2024-06-01", 2, "gpt-4o", 1, (10, 5)),
+        report_row("2024-06-03", 3, "claude", 1, (1000, 500)),
+    ];
+
+    let summary =
+        build_usage_summary(UsageSummaryRange::Last7Days, dates, rows, &providers, &HashMap::new());
+
+    assert_eq!(summary.start_date, "2024-06-01");
+    assert_eq!(summary.end_date, "2024-06-03");
+    assert_eq!(summary.days.len(), 3);
+    assert_eq!(summary.total_tokens, 1965);
+
+    let first = &summary.days[0];
+    assert_eq!(first.total_tokens, 465);
+    assert_eq!(first.models.len(), 2);
+    assert_eq!(first.models[0].provider.as_deref(), Some("OpenAI"));
+    assert_eq!(first.models[0].input_tokens, 300);
+    assert_eq!(first.models[0].output_tokens, 150);
+    assert_eq!(first.models[0].response_count, 2);
+
+    assert_eq!(summary.days[1].total_tokens, 0);
+    assert!(summary.days[1].models.is_empty());
+
+    assert_eq!(summary.by_model[0].model, "claude");
+    assert_eq!(summary.by_model[0].provider, None);
+    assert_eq!(summary.by_model.len(), 3);
+}
+
+/// 测试按配置单价估算费用，未配置单价的模型费用为空而不是 0
+#[test]
+fn test_build_usage_summary_estimates_cost_for_priced_models() {
+    let dates: Vec<String> = ["2024-06-01", "2024-06-02"].iter().map(|d| d.to_string()).collect();
+    let prices = HashMap::from([(
+        "gpt-4o".to_string(),
+        LLMModelPrice {
+            model_code: "gpt-4o".to_string(),
+            input_price_per_1k: 0.002,
+            output_price_per_1k: 0.008,
+        },
+    )]);
+    let rows = vec![
+        report_row("2024-06-01", 1, "gpt-4o", 1, (1000, 500)),
+        report_row("2024-06-01", 2, "local-llama", 1, (300, 200)),
+        report_row("2024-06-02", 2, "local-llama", 1, (100, 100)),
+    ];
+
+    let summary =
+        build_usage_summary(UsageSummaryRange::Last7Days, dates, rows, &HashMap::new(), &prices);
+
+    let cost =
+        |model: &str| summary.by_model.iter().find(|m| m.model == model).unwrap().estimated_cost;
+    assert!((cost("gpt-4o").unwrap() - 0.006).abs() < 1e-12);
+    assert_eq!(cost("local-llama"), None);
+    assert!((summary.estimated_cost.unwrap() - 0.006).abs() < 1e-12);
+    assert_eq!(summary.unpriced_tokens, 700);
+
+    assert!((summary.days[0].estimated_cost.unwrap() - 0.006).abs() < 1e-12);
+    assert_eq!(summary.days[1].estimated_cost, None);
+}
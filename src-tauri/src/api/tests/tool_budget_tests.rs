@@ -0,0 +1,98 @@
+use crate::api::ai::tool_budget::{
+    count_tool_calls_in_current_turn, is_forced_answer_turn, max_tool_calls_from_configs,
+    should_force_final_answer, ForcedAnswerGuard,
+};
+use crate::db::conversation_db::Message;
+use crate::db::tests::test_helpers::{create_test_message, create_test_model_config};
+use chrono::{Duration, TimeZone, Utc};
+
+/// 辅助函数：按 id 依次排列创建时间的消息
+fn make_message(id: i64, message_type: &str, content: &str) -> Message {
+    Message {
+        id,
+        created_time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::seconds(id),
+        ..create_test_message(1, message_type, content, None, Some("g1".to_string()))
+    }
+}
+
+/// 测试读取 max_tool_calls_before_answer 配置，0 或非法值视为不限制
+#[test]
+fn test_max_tool_calls_from_configs() {
+    assert_eq!(
+        max_tool_calls_from_configs(&[create_test_model_config(
+            "max_tool_calls_before_answer",
+            "3"
+        )]),
+        Some(3)
+    );
+    assert_eq!(
+        max_tool_calls_from_configs(&[create_test_model_config(
+            "max_tool_calls_before_answer",
+            "0"
+        )]),
+        None
+    );
+    assert_eq!(
+        max_tool_calls_from_configs(&[create_test_model_config(
+            "max_tool_calls_before_answer",
+            "abc"
+        )]),
+        None
+    );
+    assert_eq!(max_tool_calls_from_configs(&[create_test_model_config("max_tokens", "3")]), None);
+}
+
+/// 测试只统计最后一条用户消息之后的工具调用
+#[test]
+fn test_count_tool_calls_only_in_current_turn() {
+    let messages = vec![
+        make_message(1, "system", "system"),
+        make_message(2, "user", "q1"),
+        make_message(3, "response", "call"),
+        make_message(4, "tool_result", "r"),
+        make_message(5, "response", "a1"),
+        make_message(6, "user", "q2"),
+        make_message(7, "response", "call"),
+        make_message(8, "tool_result", "r"),
+    ];
+    assert_eq!(count_tool_calls_in_current_turn(&messages), 1);
+}
+
+/// 测试模型持续调用工具时，在第 N 次工具调用后被强制回答
+#[test]
+fn test_model_that_keeps_calling_tools_is_forced_to_answer_after_n_calls() {
+    let max_tool_calls = max_tool_calls_from_configs(&[create_test_model_config(
+        "max_tool_calls_before_answer",
+        "3",
+    )]);
+    let mut messages =
+        vec![make_message(1, "system", "system"), make_message(2, "user", "research this")];
+    let mut next_id = 3;
+    let mut executed_tool_calls = 0;
+
+    // 模拟 tool_result_continue 循环：模型每轮都请求工具，直到被强制回答
+    let forced_at = loop {
+        if should_force_final_answer(&messages, max_tool_calls) {
+            break executed_tool_calls;
+        }
+        assert!(executed_tool_calls < 10, "model was never forced to answer");
+        messages.push(make_message(next_id, "response", "<mcp_tool_call>...</mcp_tool_call>"));
+        messages.push(make_message(next_id + 1, "tool_result", "more data"));
+        next_id += 2;
+        executed_tool_calls += 1;
+    };
+    assert_eq!(forced_at, 3);
+
+    // 强制回答轮次期间，检测到的工具调用不会被执行；轮次结束后恢复
+    {
+        let _guard = ForcedAnswerGuard::enter(42);
+        assert!(is_forced_answer_turn(42));
+        assert!(!is_forced_answer_turn(43));
+    }
+    assert!(!is_forced_answer_turn(42));
+
+    // 新的用户消息开启新一轮，预算重新计算
+    messages.push(make_message(next_id, "response", "final answer"));
+    messages.push(make_message(next_id + 1, "user", "follow up"));
+    assert!(!should_force_final_answer(&messages, max_tool_calls));
+}
@@ -0,0 +1,90 @@
+use crate::api::ai::tool_parallel::{
+    run_bounded, tool_concurrency_from_configs, DEFAULT_MAX_PARALLEL_TOOL_CALLS,
+    MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY, PARALLEL_TOOL_EXECUTION_CONFIG_KEY,
+};
+use crate::db::tests::test_helpers::create_test_model_config;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 测试并发数配置读取
+/// 验证内容：
+/// - 未开启并行执行时逐个执行，即使配置了并发上限
+/// - 开启后使用配置的并发上限，未配置或无效时回退到默认值
+/// - 开关值与其他开关类配置一致，接受 yes/on 等写法
+#[test]
+fn test_tool_concurrency_from_configs() {
+    assert_eq!(tool_concurrency_from_configs(&[]), 1);
+    assert_eq!(
+        tool_concurrency_from_configs(&[create_test_model_config(
+            MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY,
+            "8"
+        )]),
+        1
+    );
+    assert_eq!(
+        tool_concurrency_from_configs(&[
+            create_test_model_config(PARALLEL_TOOL_EXECUTION_CONFIG_KEY, "false"),
+            create_test_model_config(MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY, "8"),
+        ]),
+        1
+    );
+    assert_eq!(
+        tool_concurrency_from_configs(&[
+            create_test_model_config(PARALLEL_TOOL_EXECUTION_CONFIG_KEY, "true"),
+            create_test_model_config(MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY, "2"),
+        ]),
+        2
+    );
+    assert_eq!(
+        tool_concurrency_from_configs(&[create_test_model_config(
+            PARALLEL_TOOL_EXECUTION_CONFIG_KEY,
+            "on"
+        )]),
+        DEFAULT_MAX_PARALLEL_TOOL_CALLS
+    );
+    assert_eq!(
+        tool_concurrency_from_configs(&[
+            create_test_model_config(PARALLEL_TOOL_EXECUTION_CONFIG_KEY, "yes"),
+            create_test_model_config(MAX_PARALLEL_TOOL_CALLS_CONFIG_KEY, "0"),
+        ]),
+        DEFAULT_MAX_PARALLEL_TOOL_CALLS
+    );
+}
+
+/// 测试有限并发执行
+/// 验证内容：
+/// - 同时运行的任务数不超过并发上限
+/// - 结果按输入顺序返回，与完成顺序无关
+/// - 单个任务失败或 panic 时其他任务仍正常完成
+#[tokio::test]
+async fn test_run_bounded_respects_limit_and_isolates_failures() {
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let results = run_bounded(vec![1u64, 2, 3, 4, 5], 2, |n| {
+        let running = running.clone();
+        let peak = peak.clone();
+        async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            // 先开始的任务后完成，验证结果顺序不依赖完成顺序
+            tokio::time::sleep(Duration::from_millis(60 - n * 10)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            match n {
+                2 => Err("search failed".to_string()),
+                4 => panic!("tool crashed"),
+                _ => Ok(n * 10),
+            }
+        }
+    })
+    .await;
+
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0], Ok(10));
+    assert_eq!(results[1], Err("search failed".to_string()));
+    assert_eq!(results[2], Ok(30));
+    assert!(results[3].is_err());
+    assert_eq!(results[4], Ok(50));
+}
@@ -1,5 +1,124 @@
-use crate::db::conversation_db::{ConversationDatabase, ConversationTokenStats, MessageTokenStats};
+use crate::api::ai::conversation::strip_mcp_tool_call_hints;
+use crate::db::assistant_db::AssistantDatabase;
+use crate::db::conversation_db::{
+    ConversationDatabase, ConversationTokenStats, Message, MessageTokenStats, UsageInsights,
+    UsageReportGroupBy, UsageReportRow,
+};
+use crate::db::llm_db::{LLMDatabase, LLMModelPrice};
+use crate::errors::AppError;
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use tauri::{AppHandle, Manager};
+use tracing::info;
+
+/// 对话的文字统计（消息数、字数、字符数），与 token 统计互补
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationStats {
+    pub message_count: usize,
+    pub message_count_by_type: BTreeMap<String, usize>,
+    pub user_message_count: usize,
+    pub user_word_count: usize,
+    pub user_char_count: usize,
+    pub ai_output_message_count: usize,
+    pub ai_output_word_count: usize,
+    pub ai_output_char_count: usize,
+    /// AI 输出的平均字符数
+    pub avg_response_char_count: f64,
+    /// AI 输出是否包含 reasoning 与 error 消息
+    pub include_reasoning_and_errors: bool,
+}
+
+/// 统计字数：中日韩文字每个字计一个词，其余按连续的字母数字计一个词
+pub fn count_words(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk_char(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                count += 1;
+                in_word = true;
+            }
+        } else if !(in_word && (c == '\'' || c == '-')) {
+            in_word = false;
+        }
+    }
+    count
+}
+
+fn is_cjk_char(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF // 平假名、片假名
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF // 韩文
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x2FA1F)
+}
+
+/// 单次遍历对话消息计算文字统计（按消息 ID 去重，兼容带附件的重复行）
+pub fn compute_conversation_stats<'a>(
+    messages: impl IntoIterator<Item = &'a Message>,
+    include_reasoning_and_errors: bool,
+) -> ConversationStats {
+    let mut stats = ConversationStats { include_reasoning_and_errors, ..Default::default() };
+    let mut seen_ids = HashSet::new();
+
+    for message in messages {
+        if !seen_ids.insert(message.id) {
+            continue;
+        }
+        stats.message_count += 1;
+        *stats.message_count_by_type.entry(message.message_type.clone()).or_default() += 1;
+
+        let is_ai_output = match message.message_type.as_str() {
+            "response" | "assistant" => true,
+            "reasoning" | "error" => include_reasoning_and_errors,
+            _ => false,
+        };
+        if message.message_type == "user" {
+            stats.user_message_count += 1;
+            stats.user_word_count += count_words(&message.content);
+            stats.user_char_count += message.content.chars().count();
+        } else if is_ai_output {
+            let content = strip_mcp_tool_call_hints(&message.content);
+            let content = content.trim();
+            stats.ai_output_message_count += 1;
+            stats.ai_output_word_count += count_words(content);
+            stats.ai_output_char_count += content.chars().count();
+        }
+    }
+
+    if stats.ai_output_message_count > 0 {
+        stats.avg_response_char_count =
+            stats.ai_output_char_count as f64 / stats.ai_output_message_count as f64;
+    }
+    stats
+}
+
+/// 获取对话的文字统计信息（消息数、字数、字符数）
+#[tauri::command]
+pub async fn get_conversation_stats(
+    app_handle: AppHandle,
+    conversation_id: i64,
+    include_reasoning_and_errors: Option<bool>,
+) -> Result<ConversationStats, String> {
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let messages = db
+        .message_repo()
+        .map_err(|e| e.to_string())?
+        .list_by_conversation_id(conversation_id)
+        .map_err(|e| e.to_string())?;
+    Ok(compute_conversation_stats(
+        messages.iter().map(|(message, _)| message),
+        include_reasoning_and_errors.unwrap_or(false),
+    ))
+}
 
 /// 获取对话的token统计信息
 #[tauri::command]
@@ -18,5 +137,489 @@ pub async fn get_message_token_stats(
     message_id: i64,
 ) -> Result<MessageTokenStats, String> {
     let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
-    db.get_message_token_stats(message_id).map_err(|e| e.to_string())
+    let mut stats = db.get_message_token_stats(message_id).map_err(|e| e.to_string())?;
+    let prices = load_model_prices(&app_handle)?;
+    stats.estimated_cost =
+        stats.model_name.as_ref().and_then(|model| prices.get(model)).map(|price| {
+            price.estimate_cost(stats.input_tokens as i64, stats.output_tokens as i64)
+        });
+    Ok(stats)
+}
+
+/// 模型编码 -> 配置的单价
+fn load_model_prices(app_handle: &AppHandle) -> Result<HashMap<String, LLMModelPrice>, String> {
+    let llm_db = LLMDatabase::new(app_handle).map_err(|e| e.to_string())?;
+    Ok(llm_db
+        .get_model_prices()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|price| (price.model_code.clone(), price))
+        .collect())
+}
+
+/// 获取使用情况汇总（常用助手、模型、活跃时段、平均对话长度）
+///
+/// `window_days` 为空时统计全部历史；排行默认取前 10 项
+#[tauri::command]
+pub async fn get_usage_insights(
+    app_handle: AppHandle,
+    window_days: Option<u32>,
+    limit: Option<usize>,
+) -> Result<UsageInsights, String> {
+    let since = window_days.map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64));
+    let utc_offset_minutes = chrono::Local::now().offset().local_minus_utc() / 60;
+
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let mut insights = db
+        .get_usage_insights(since, utc_offset_minutes, limit.unwrap_or(10))
+        .map_err(|e| e.to_string())?;
+
+    // 助手名称存放在 assistant 库中，统一查一次后补全
+    let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let names: HashMap<i64, String> = assistant_db
+        .get_assistants()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|assistant| (assistant.id, assistant.name))
+        .collect();
+    for usage in &mut insights.top_assistants {
+        usage.assistant_name = usage.assistant_id.and_then(|id| names.get(&id).cloned());
+    }
+
+    Ok(insights)
+}
+
+/// 用量报表的时间范围，起止均为空时导出全部历史；结束时间不包含在内
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UsageReportRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageReportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// 导出的报表行，补全了提供商、助手名称与估算费用
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageReportEntry {
+    pub date: String,
+    pub provider: Option<String>,
+    pub model: String,
+    pub assistant: Option<String>,
+    pub response_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    /// 未配置该模型单价时为空
+    pub estimated_cost: Option<f64>,
+}
+
+/// 报表中用于补全名称与费用的查找表
+#[derive(Debug, Clone, Default)]
+pub struct UsageReportLabels {
+    /// 模型 ID -> 提供商名称
+    pub providers_by_model: HashMap<i64, String>,
+    /// 助手 ID -> 助手名称
+    pub assistants: HashMap<i64, String>,
+    /// 模型编码 -> 单价
+    pub pricing: HashMap<String, LLMModelPrice>,
+}
+
+impl UsageReportLabels {
+    pub fn entry(&self, row: UsageReportRow) -> UsageReportEntry {
+        let estimated_cost = self
+            .pricing
+            .get(&row.model_name)
+            .map(|price| price.estimate_cost(row.input_tokens, row.output_tokens));
+        UsageReportEntry {
+            date: row.period,
+            provider: row.llm_model_id.and_then(|id| self.providers_by_model.get(&id).cloned()),
+            model: row.model_name,
+            assistant: row.assistant_id.and_then(|id| self.assistants.get(&id).cloned()),
+            response_count: row.response_count,
+            input_tokens: row.input_tokens,
+            output_tokens: row.output_tokens,
+            total_tokens: row.total_tokens,
+            estimated_cost,
+        }
+    }
+}
+
+const USAGE_REPORT_CSV_HEADER: &str = "date,provider,model,assistant,response_count,input_tokens,output_tokens,total_tokens,estimated_cost";
+
+/// 转义 CSV 字段：包含分隔符、引号或换行时加引号；
+/// 以公式字符开头的文本前加单引号，避免表格软件把名称当作公式执行
+pub fn escape_csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// 逐行写出用量报表：CSV 带 UTF-8 BOM，便于表格软件正确识别中文；JSON 为对象数组
+pub struct UsageReportWriter<W: Write> {
+    writer: W,
+    format: UsageReportFormat,
+    rows: usize,
+}
+
+impl<W: Write> UsageReportWriter<W> {
+    pub fn new(mut writer: W, format: UsageReportFormat) -> std::io::Result<Self> {
+        match format {
+            UsageReportFormat::Csv => {
+                writer.write_all("\u{feff}".as_bytes())?;
+                writeln!(writer, "{}", USAGE_REPORT_CSV_HEADER)?;
+            }
+            UsageReportFormat::Json => writer.write_all(b"[")?,
+        }
+        Ok(Self { writer, format, rows: 0 })
+    }
+
+    pub fn write_entry(&mut self, entry: &UsageReportEntry) -> std::io::Result<()> {
+        match self.format {
+            UsageReportFormat::Csv => {
+                let text =
+                    |value: &Option<String>| escape_csv_field(value.as_deref().unwrap_or(""));
+                writeln!(
+                    self.writer,
+                    "{},{},{},{},{},{},{},{},{}",
+                    escape_csv_field(&entry.date),
+                    text(&entry.provider),
+                    escape_csv_field(&entry.model),
+                    text(&entry.assistant),
+                    entry.response_count,
+                    entry.input_tokens,
+                    entry.output_tokens,
+                    entry.total_tokens,
+                    entry.estimated_cost.map(|cost| format!("{:.6}", cost)).unwrap_or_default()
+                )?;
+            }
+            UsageReportFormat::Json => {
+                if self.rows > 0 {
+                    self.writer.write_all(b",")?;
+                }
+                self.writer.write_all(b"\n  ")?;
+                serde_json::to_writer(&mut self.writer, entry)?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// 写出结尾并刷新，返回写入的行数
+    pub fn finish(mut self) -> std::io::Result<usize> {
+        if self.format == UsageReportFormat::Json {
+            let tail = if self.rows > 0 { "\n]\n" } else { "]\n" };
+            self.writer.write_all(tail.as_bytes())?;
+        }
+        self.writer.flush()?;
+        Ok(self.rows)
+    }
+}
+
+/// 模型 ID -> 提供商名称
+fn load_providers_by_model(app_handle: &AppHandle) -> Result<HashMap<i64, String>, String> {
+    let llm_db = LLMDatabase::new(app_handle).map_err(|e| e.to_string())?;
+    let provider_names: HashMap<i64, String> = llm_db
+        .get_llm_providers()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(id, name, ..)| (id, name))
+        .collect();
+    Ok(llm_db
+        .get_all_llm_models()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|(id, _, provider_id, ..)| {
+            provider_names.get(&provider_id).map(|name| (id, name.clone()))
+        })
+        .collect())
+}
+
+/// 导出按时间段、提供商、模型、助手汇总的 token 用量与估算费用，返回导出的行数
+///
+/// `pricing` 按模型编码匹配，未传入时使用已保存的模型单价；未配置单价的模型费用列留空；
+/// 报表逐行写入文件，不在内存中拼接。
+#[tauri::command]
+pub async fn export_usage_report(
+    app_handle: AppHandle,
+    range: UsageReportRange,
+    group_by: Option<UsageReportGroupBy>,
+    format: Option<UsageReportFormat>,
+    file_path: String,
+    pricing: Option<Vec<LLMModelPrice>>,
+) -> Result<usize, String> {
+    let utc_offset_minutes = chrono::Local::now().offset().local_minus_utc() / 60;
+
+    let providers_by_model = load_providers_by_model(&app_handle)?;
+    let assistants = AssistantDatabase::new(&app_handle)
+        .map_err(|e| e.to_string())?
+        .get_assistants()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|assistant| (assistant.id, assistant.name))
+        .collect();
+    let pricing = match pricing {
+        Some(pricing) => {
+            pricing.into_iter().map(|price| (price.model_code.clone(), price)).collect()
+        }
+        None => load_model_prices(&app_handle)?,
+    };
+    let labels = UsageReportLabels { providers_by_model, assistants, pricing };
+
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let file = File::create(&file_path).map_err(|e| e.to_string())?;
+    let mut writer = UsageReportWriter::new(BufWriter::new(file), format.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+    db.for_each_usage_report_row::<AppError, _>(
+        range.start,
+        range.end,
+        group_by.unwrap_or_default(),
+        utc_offset_minutes,
+        |row| Ok(writer.write_entry(&labels.entry(row))?),
+    )
+    .map_err(|e| e.to_string())?;
+    let rows = writer.finish().map_err(|e| e.to_string())?;
+
+    info!(rows, file_path = %file_path, "Exported usage report");
+    Ok(rows)
+}
+
+/// 用量汇总的时间窗口（按本地自然日计算，包含今天）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum UsageSummaryRange {
+    #[default]
+    #[serde(rename = "last_7_days")]
+    Last7Days,
+    #[serde(rename = "last_30_days")]
+    Last30Days,
+}
+
+impl UsageSummaryRange {
+    pub fn days(self) -> i64 {
+        match self {
+            UsageSummaryRange::Last7Days => 7,
+            UsageSummaryRange::Last30Days => 30,
+        }
+    }
+}
+
+/// 某个模型在一段时间内的 token 用量
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ModelUsageTotals {
+    pub model: String,
+    pub provider: Option<String>,
+    pub response_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    /// 未配置该模型单价时为空
+    pub estimated_cost: Option<f64>,
+}
+
+/// 某一天的 token 用量，`models` 按 total_tokens 从高到低排序
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DailyUsage {
+    pub date: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    /// 已定价模型的费用合计，没有任何已定价模型时为空
+    pub estimated_cost: Option<f64>,
+    pub models: Vec<ModelUsageTotals>,
+}
+
+/// 最近 N 天的用量汇总，`days` 覆盖窗口内的每一天（无用量的日期为 0）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageSummary {
+    pub range: UsageSummaryRange,
+    pub start_date: String,
+    pub end_date: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    /// 已定价模型的费用合计，没有任何已定价模型时为空
+    pub estimated_cost: Option<f64>,
+    /// 未配置单价的模型消耗的 token，用于提示费用估算不完整
+    pub unpriced_tokens: i64,
+    pub days: Vec<DailyUsage>,
+    /// 整个窗口内按模型汇总，按 total_tokens 从高到低排序
+    pub by_model: Vec<ModelUsageTotals>,
+}
+
+/// 计算窗口起点（起始日本地零点）与窗口内的日期列表
+pub fn usage_summary_window(
+    range: UsageSummaryRange,
+    now: DateTime<FixedOffset>,
+) -> (DateTime<Utc>, Vec<String>) {
+    let today = now.date_naive();
+    let start_date = today - chrono::Duration::days(range.days() - 1);
+    let since = start_date
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(*now.offset())
+        .single()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| now.with_timezone(&Utc) - chrono::Duration::days(range.days()));
+    let dates = start_date
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .collect();
+    (since, dates)
+}
+
+fn add_model_usage(
+    totals: &mut Vec<ModelUsageTotals>,
+    row: &UsageReportRow,
+    provider: Option<&String>,
+) {
+    let provider = provider.cloned();
+    match totals.iter_mut().find(|t| t.model == row.model_name && t.provider == provider) {
+        Some(total) => {
+            total.response_count += row.response_count;
+            total.input_tokens += row.input_tokens;
+            total.output_tokens += row.output_tokens;
+            total.total_tokens += row.total_tokens;
+        }
+        None => totals.push(ModelUsageTotals {
+            model: row.model_name.clone(),
+            provider,
+            response_count: row.response_count,
+            input_tokens: row.input_tokens,
+            output_tokens: row.output_tokens,
+            total_tokens: row.total_tokens,
+            estimated_cost: None,
+        }),
+    }
+}
+
+/// 为每个模型填入估算费用，返回已定价模型的费用合计（没有已定价模型时为空）
+fn apply_model_prices(
+    totals: &mut [ModelUsageTotals],
+    prices: &HashMap<String, LLMModelPrice>,
+) -> Option<f64> {
+    let mut total_cost = None;
+    for total in totals {
+        total.estimated_cost = prices
+            .get(&total.model)
+            .map(|price| price.estimate_cost(total.input_tokens, total.output_tokens));
+        if let Some(cost) = total.estimated_cost {
+            *total_cost.get_or_insert(0.0) += cost;
+        }
+    }
+    total_cost
+}
+
+fn sort_by_total_tokens(totals: &mut [ModelUsageTotals]) {
+    totals.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens).then_with(|| a.model.cmp(&b.model)));
+}
+
+/// 把按天、模型、助手分组的报表行合并为按天、模型（含提供商）的汇总
+pub fn build_usage_summary(
+    range: UsageSummaryRange,
+    dates: Vec<String>,
+    rows: impl IntoIterator<Item = UsageReportRow>,
+    providers_by_model: &HashMap<i64, String>,
+    prices: &HashMap<String, LLMModelPrice>,
+) -> UsageSummary {
+    let start_date = dates.first().cloned().unwrap_or_default();
+    let end_date = dates.last().cloned().unwrap_or_default();
+    let mut days: BTreeMap<String, DailyUsage> = dates
+        .into_iter()
+        .map(|date| {
+            let day = DailyUsage {
+                date: date.clone(),
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                estimated_cost: None,
+                models: Vec::new(),
+            };
+            (date, day)
+        })
+        .collect();
+    let mut by_model = Vec::new();
+
+    for row in rows {
+        let provider = row.llm_model_id.and_then(|id| providers_by_model.get(&id));
+        let day = days.entry(row.period.clone()).or_insert_with(|| DailyUsage {
+            date: row.period.clone(),
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            estimated_cost: None,
+            models: Vec::new(),
+        });
+        day.input_tokens += row.input_tokens;
+        day.output_tokens += row.output_tokens;
+        day.total_tokens += row.total_tokens;
+        add_model_usage(&mut day.models, &row, provider);
+        add_model_usage(&mut by_model, &row, provider);
+    }
+
+    let mut days: Vec<DailyUsage> = days.into_values().collect();
+    for day in &mut days {
+        day.estimated_cost = apply_model_prices(&mut day.models, prices);
+        sort_by_total_tokens(&mut day.models);
+    }
+    let estimated_cost = apply_model_prices(&mut by_model, prices);
+    sort_by_total_tokens(&mut by_model);
+    let unpriced_tokens =
+        by_model.iter().filter(|m| m.estimated_cost.is_none()).map(|m| m.total_tokens).sum();
+
+    UsageSummary {
+        range,
+        start_date,
+        end_date,
+        input_tokens: days.iter().map(|d| d.input_tokens).sum(),
+        output_tokens: days.iter().map(|d| d.output_tokens).sum(),
+        total_tokens: days.iter().map(|d| d.total_tokens).sum(),
+        estimated_cost,
+        unpriced_tokens,
+        days,
+        by_model,
+    }
+}
+
+/// 获取最近 7/30 天按天、模型、提供商汇总的 token 用量与估算费用
+///
+/// 按消息时间统计，跨越窗口边界的对话只计入窗口内的消息
+#[tauri::command]
+pub async fn get_usage_summary(
+    app_handle: AppHandle,
+    range: Option<UsageSummaryRange>,
+) -> Result<UsageSummary, String> {
+    let range = range.unwrap_or_default();
+    let now = chrono::Local::now().fixed_offset();
+    let utc_offset_minutes = now.offset().local_minus_utc() / 60;
+    let (since, dates) = usage_summary_window(range, now);
+
+    let providers_by_model = load_providers_by_model(&app_handle)?;
+    let prices = load_model_prices(&app_handle)?;
+    let db = ConversationDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let mut rows = Vec::new();
+    db.for_each_usage_report_row::<AppError, _>(
+        Some(since),
+        None,
+        UsageReportGroupBy::Day,
+        utc_offset_minutes,
+        |row| {
+            rows.push(row);
+            Ok(())
+        },
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(build_usage_summary(range, dates, rows, &providers_by_model, &prices))
 }
@@ -200,9 +200,12 @@ pub async fn artifact_ai_ask(
     request: AiAskRequest,
 ) -> Result<AiAskResponse, String> {
     // 使用 assistant_api::get_assistant 获取完整的助手信息
-    let assistant_detail =
-        crate::api::assistant_api::get_assistant(app_handle.clone(), request.assistant_id)
-            .map_err(|e| format!("Failed to get assistant: {}", e))?;
+    let assistant_detail = crate::api::assistant_api::get_assistant(
+        app_handle.clone(),
+        request.assistant_id,
+        Some(true),
+    )
+    .map_err(|e| format!("Failed to get assistant: {}", e))?;
 
     // 获取模型信息
     let model = assistant_detail.model.first().ok_or("Assistant has no model configured")?;
@@ -1,3 +1,4 @@
+use crate::db::encryption;
 use rusqlite::{params_from_iter, Connection, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -62,8 +63,10 @@ impl ArtifactDataDatabase {
         let data_dir = Self::get_artifact_data_dir(app_handle)?;
         let db_path = data_dir.join(format!("{}.db", db_id));
 
-        let conn =
-            Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+        // 启用数据库加密前创建的 artifact 数据库在首次打开时迁移为加密库
+        encryption::encrypt_if_plaintext(&db_path)?;
+        let conn = encryption::open_connection(&db_path)
+            .map_err(|e| format!("Failed to open database: {}", e))?;
 
         // 启用 WAL 模式以提高并发性能
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA busy_timeout=5000;")
@@ -1,3 +1,4 @@
+use crate::db::encryption::open_connection;
 use crate::db::get_db_path;
 use rusqlite::{params, Connection, Result};
 use serde::{Deserialize, Serialize};
@@ -39,6 +40,17 @@ pub struct UpdateArtifactCollection {
     pub tags: Option<String>,
     pub db_id: Option<String>,
     pub assistant_id: Option<i64>,
+    pub code: Option<String>, // 代码变更时追加新版本
+}
+
+/// Artifact 的一个历史版本，version 从 1 开始递增
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArtifactVersion {
+    pub id: i64,
+    pub artifact_id: i64,
+    pub version: i64,
+    pub code: String,
+    pub created_time: String,
 }
 
 pub struct ArtifactsDatabase {
@@ -48,7 +60,7 @@ pub struct ArtifactsDatabase {
 impl ArtifactsDatabase {
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "artifacts.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
 
         Ok(ArtifactsDatabase { conn })
     }
@@ -96,6 +108,23 @@ impl ArtifactsDatabase {
             .conn
             .execute("ALTER TABLE artifacts_collection ADD COLUMN assistant_id INTEGER", []);
 
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS artifact_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                artifact_id INTEGER NOT NULL,
+                version INTEGER NOT NULL,
+                code TEXT NOT NULL,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE (artifact_id, version)
+            );",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_artifact_versions_artifact_id ON artifact_versions(artifact_id);",
+            [],
+        )?;
+
         Ok(())
     }
 
@@ -117,7 +146,10 @@ impl ArtifactsDatabase {
             artifact.assistant_id
         ])?;
 
-        Ok(self.conn.last_insert_rowid())
+        let id = self.conn.last_insert_rowid();
+        self.append_version(id, &artifact.code)?;
+
+        Ok(id)
     }
 
     /// Get all artifacts with optional type filter
@@ -235,9 +267,27 @@ impl ArtifactsDatabase {
     }
 
     /// Update artifact metadata (name, icon, description, tags, db_id, assistant_id)
+    /// 传入的 code 与当前代码不同时，追加一个新版本而不是直接覆盖历史
     pub fn update_artifact(&self, update: UpdateArtifactCollection) -> Result<()> {
         let mut set_clauses = Vec::new();
 
+        let mut code_changed = false;
+        if let Some(ref code) = update.code {
+            if let Some(current) = self.get_artifact_by_id(update.id)? {
+                if &current.code != code {
+                    // 早于版本表创建的 artifact 没有历史记录，先把当前代码补记为第一个版本
+                    if self.get_latest_version_number(update.id)?.is_none() {
+                        self.append_version(update.id, &current.code)?;
+                    }
+                    self.append_version(update.id, code)?;
+                    code_changed = true;
+                }
+            }
+        }
+        if code_changed {
+            set_clauses.push("code = ?");
+        }
+
         if update.name.is_some() {
             set_clauses.push("name = ?");
         }
@@ -291,6 +341,12 @@ impl ArtifactsDatabase {
             stmt.raw_bind_parameter(idx, assistant_id)?;
             idx += 1;
         }
+        if code_changed {
+            if let Some(ref code) = update.code {
+                stmt.raw_bind_parameter(idx, code)?;
+                idx += 1;
+            }
+        }
         stmt.raw_bind_parameter(idx, update.id)?;
 
         stmt.raw_execute()?;
@@ -299,6 +355,7 @@ impl ArtifactsDatabase {
 
     /// Delete artifact by ID
     pub fn delete_artifact(&self, id: i64) -> Result<bool> {
+        self.conn.execute("DELETE FROM artifact_versions WHERE artifact_id = ?", [id])?;
         let rows_affected =
             self.conn.execute("DELETE FROM artifacts_collection WHERE id = ?", [id])?;
 
@@ -317,6 +374,68 @@ impl ArtifactsDatabase {
         Ok(())
     }
 
+    /// 获取最新版本号，没有任何版本记录时返回 None
+    pub fn get_latest_version_number(&self, artifact_id: i64) -> Result<Option<i64>> {
+        self.conn.query_row(
+            "SELECT MAX(version) FROM artifact_versions WHERE artifact_id = ?",
+            [artifact_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// 追加一个新版本，返回新版本号
+    pub fn append_version(&self, artifact_id: i64, code: &str) -> Result<i64> {
+        let version = self.get_latest_version_number(artifact_id)?.unwrap_or(0) + 1;
+        self.conn.execute(
+            "INSERT INTO artifact_versions (artifact_id, version, code) VALUES (?, ?, ?)",
+            params![artifact_id, version, code],
+        )?;
+        Ok(version)
+    }
+
+    /// 获取 artifact 的全部历史版本，按版本号升序
+    pub fn get_versions(&self, artifact_id: i64) -> Result<Vec<ArtifactVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, artifact_id, version, code, created_time
+             FROM artifact_versions
+             WHERE artifact_id = ?
+             ORDER BY version ASC",
+        )?;
+
+        let rows = stmt.query_map([artifact_id], |row| {
+            Ok(ArtifactVersion {
+                id: row.get(0)?,
+                artifact_id: row.get(1)?,
+                version: row.get(2)?,
+                code: row.get(3)?,
+                created_time: row.get(4)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// 获取指定版本
+    pub fn get_version(&self, artifact_id: i64, version: i64) -> Result<Option<ArtifactVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, artifact_id, version, code, created_time
+             FROM artifact_versions
+             WHERE artifact_id = ? AND version = ?",
+        )?;
+
+        let mut rows = stmt.query_map([artifact_id, version], |row| {
+            Ok(ArtifactVersion {
+                id: row.get(0)?,
+                artifact_id: row.get(1)?,
+                version: row.get(2)?,
+                code: row.get(3)?,
+                created_time: row.get(4)?,
+            })
+        })?;
+
+        rows.next().transpose()
+    }
+
     /// Get artifacts statistics
     pub fn get_statistics(&self) -> Result<(i64, i64)> {
         let total_count: i64 =
@@ -629,6 +748,7 @@ mod tests {
             tags: None,
             db_id: None,
             assistant_id: None,
+            code: None,
         };
 
         let result = db.update_artifact(update);
@@ -651,6 +771,7 @@ mod tests {
             tags: Some(r#"["new", "tags"]"#.to_string()),
             db_id: None,
             assistant_id: None,
+            code: None,
         };
 
         let result = db.update_artifact(update);
@@ -676,6 +797,7 @@ mod tests {
             tags: None,
             db_id: None,
             assistant_id: None,
+            code: None,
         };
 
         let result = db.update_artifact(update);
@@ -750,6 +872,76 @@ mod tests {
         assert!(fetched.last_used_time.is_some());
     }
 
+    // ============================================
+    // Version History Tests
+    // ============================================
+
+    fn code_update(id: i64, code: &str) -> UpdateArtifactCollection {
+        UpdateArtifactCollection {
+            id,
+            name: None,
+            icon: None,
+            description: None,
+            tags: None,
+            db_id: None,
+            assistant_id: None,
+            code: Some(code.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_save_artifact_creates_first_version() {
+        let db = setup_test_db();
+        let id = db.save_artifact(create_sample_artifact("Versioned", "react")).unwrap();
+
+        let versions = db.get_versions(id).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, 1);
+        assert_eq!(versions[0].code, "<div>Versioned</div>");
+    }
+
+    #[test]
+    fn test_update_code_appends_versions() {
+        let db = setup_test_db();
+        let id = db.save_artifact(create_sample_artifact("Versioned", "react")).unwrap();
+
+        db.update_artifact(code_update(id, "<div>v2</div>")).unwrap();
+        db.update_artifact(code_update(id, "<div>v3</div>")).unwrap();
+        // 代码未变化时不产生新版本
+        db.update_artifact(code_update(id, "<div>v3</div>")).unwrap();
+
+        let versions = db.get_versions(id).unwrap();
+        assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(db.get_artifact_by_id(id).unwrap().unwrap().code, "<div>v3</div>");
+        assert_eq!(db.get_version(id, 1).unwrap().unwrap().code, "<div>Versioned</div>");
+        assert!(db.get_version(id, 4).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_update_code_backfills_legacy_artifact() {
+        let db = setup_test_db();
+        let id = db.save_artifact(create_sample_artifact("Legacy", "vue")).unwrap();
+        // 模拟版本表出现之前保存的 artifact
+        db.conn.execute("DELETE FROM artifact_versions WHERE artifact_id = ?", [id]).unwrap();
+
+        db.update_artifact(code_update(id, "<div>new</div>")).unwrap();
+
+        let versions = db.get_versions(id).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].code, "<div>Legacy</div>");
+        assert_eq!(versions[1].code, "<div>new</div>");
+    }
+
+    #[test]
+    fn test_delete_artifact_removes_versions() {
+        let db = setup_test_db();
+        let id = db.save_artifact(create_sample_artifact("Gone", "react")).unwrap();
+        db.update_artifact(code_update(id, "<div>v2</div>")).unwrap();
+
+        db.delete_artifact(id).unwrap();
+        assert!(db.get_versions(id).unwrap().is_empty());
+    }
+
     // ============================================
     // Statistics Tests
     // ============================================
@@ -71,6 +71,64 @@ pub fn extract_vue_component_name(code: &str) -> Option<String> {
     None
 }
 
+// A single line of a line-based diff between two artifact versions
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CodeDiffLine {
+    pub kind: String, // equal, added, removed
+    pub content: String,
+}
+
+// Above this many LCS cells the diff degrades to "all removed, all added"
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+// Compute a line-based diff (LCS) from `old` to `new`
+pub fn diff_lines(old: &str, new: &str) -> Vec<CodeDiffLine> {
+    let line = |kind: &str, content: &str| CodeDiffLine {
+        kind: kind.to_string(),
+        content: content.to_string(),
+    };
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        let mut result: Vec<CodeDiffLine> = old_lines.iter().map(|l| line("removed", l)).collect();
+        result.extend(new_lines.iter().map(|l| line("added", l)));
+        return result;
+    }
+
+    // lcs[i][j] = LCS length of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            result.push(line("equal", old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(line("removed", old_lines[i]));
+            i += 1;
+        } else {
+            result.push(line("added", new_lines[j]));
+            j += 1;
+        }
+    }
+    result.extend(old_lines[i..].iter().map(|l| line("removed", l)));
+    result.extend(new_lines[j..].iter().map(|l| line("added", l)));
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,4 +484,27 @@ mod tests {
         "#;
         assert!(is_vue_component(code));
     }
+
+    // ============================================================================
+    // diff_lines Tests
+    // ============================================================================
+
+    #[test]
+    fn test_diff_lines_marks_changes() {
+        let diff = diff_lines("a\nb\nc", "a\nx\nc\nd");
+        let kinds: Vec<(&str, &str)> =
+            diff.iter().map(|l| (l.kind.as_str(), l.content.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![("equal", "a"), ("removed", "b"), ("added", "x"), ("equal", "c"), ("added", "d")]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_from_empty() {
+        let diff = diff_lines("", "one\ntwo");
+        assert!(diff.iter().all(|l| l.kind == "added"));
+        assert_eq!(diff.len(), 2);
+        assert!(diff_lines("same", "same").iter().all(|l| l.kind == "equal"));
+    }
 }
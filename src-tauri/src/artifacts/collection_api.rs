@@ -4,7 +4,8 @@ use tauri::{Emitter, Manager, State};
 use crate::api::ai::config::{get_network_proxy_from_config, get_request_timeout_from_config};
 use crate::api::genai_client;
 use crate::artifacts::code_utils::{
-    extract_component_name, extract_vue_component_name, is_react_component, is_vue_component,
+    diff_lines, extract_component_name, extract_vue_component_name, is_react_component,
+    is_vue_component, CodeDiffLine,
 };
 use crate::artifacts::react_runner::run_react_artifact;
 use crate::artifacts::vue_runner::run_vue_artifact;
@@ -12,7 +13,8 @@ use crate::db::llm_db::LLMDatabase;
 use crate::FeatureConfigState;
 
 use super::artifacts_db::{
-    ArtifactCollection, ArtifactsDatabase, NewArtifactCollection, UpdateArtifactCollection,
+    ArtifactCollection, ArtifactVersion, ArtifactsDatabase, NewArtifactCollection,
+    UpdateArtifactCollection,
 };
 use crate::utils::bun_utils::BunUtils;
 
@@ -52,6 +54,15 @@ pub struct UpdateArtifactRequest {
     pub tags: Option<String>,
     pub db_id: Option<String>,
     pub assistant_id: Option<i64>,
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArtifactVersionDetail {
+    #[serde(flatten)]
+    pub version: ArtifactVersion,
+    /// 相对上一版本的逐行差异，第一个版本相对空内容
+    pub diff: Vec<CodeDiffLine>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -193,6 +204,7 @@ pub fn update_artifact_collection(
         tags: request.tags,
         db_id: request.db_id,
         assistant_id: request.assistant_id,
+        code: request.code,
     };
 
     db.update_artifact(update).map_err(|e| format!("Failed to update artifact: {}", e))?;
@@ -207,6 +219,43 @@ pub fn update_artifact_collection(
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_artifact_versions(
+    app_handle: tauri::AppHandle,
+    id: i64,
+) -> Result<Vec<ArtifactVersion>, String> {
+    let db = ArtifactsDatabase::new(&app_handle)
+        .map_err(|e| format!("Database connection failed: {}", e))?;
+
+    db.get_versions(id).map_err(|e| format!("Failed to get artifact versions: {}", e))
+}
+
+#[tauri::command]
+pub fn get_artifact_version(
+    app_handle: tauri::AppHandle,
+    id: i64,
+    version: i64,
+) -> Result<Option<ArtifactVersionDetail>, String> {
+    let db = ArtifactsDatabase::new(&app_handle)
+        .map_err(|e| format!("Database connection failed: {}", e))?;
+
+    let Some(current) = db
+        .get_version(id, version)
+        .map_err(|e| format!("Failed to get artifact version: {}", e))?
+    else {
+        return Ok(None);
+    };
+    let previous = if version > 1 {
+        db.get_version(id, version - 1)
+            .map_err(|e| format!("Failed to get artifact version: {}", e))?
+    } else {
+        None
+    };
+
+    let diff = diff_lines(previous.as_ref().map(|v| v.code.as_str()).unwrap_or(""), &current.code);
+    Ok(Some(ArtifactVersionDetail { version: current, diff }))
+}
+
 #[tauri::command]
 pub fn delete_artifact_collection(app_handle: tauri::AppHandle, id: i64) -> Result<bool, String> {
     let db = ArtifactsDatabase::new(&app_handle)
@@ -231,15 +280,25 @@ pub fn delete_artifact_collection(app_handle: tauri::AppHandle, id: i64) -> Resu
 pub async fn open_artifact_window(
     app_handle: tauri::AppHandle,
     artifact_id: i64,
+    version: Option<i64>,
 ) -> Result<(), String> {
     let db = ArtifactsDatabase::new(&app_handle)
         .map_err(|e| format!("Database connection failed: {}", e))?;
 
-    let artifact = db
+    let mut artifact = db
         .get_artifact_by_id(artifact_id)
         .map_err(|e| format!("Failed to get artifact: {}", e))?
         .ok_or_else(|| "Artifact not found".to_string())?;
 
+    // 指定版本时用该版本的代码替换当前代码
+    if let Some(version) = version {
+        let artifact_version = db
+            .get_version(artifact_id, version)
+            .map_err(|e| format!("Failed to get artifact version: {}", e))?
+            .ok_or_else(|| format!("Artifact version {} not found", version))?;
+        artifact.code = artifact_version.code;
+    }
+
     db.increment_use_count(artifact_id)
         .map_err(|e| format!("Failed to increment use count: {}", e))?;
 
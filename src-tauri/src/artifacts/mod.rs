@@ -6,6 +6,7 @@ pub mod code_utils;
 pub mod collection_api;
 pub mod env_installer;
 pub mod powershell;
+pub mod preview_console;
 pub mod preview_router;
 pub mod react_preview;
 pub mod react_runner;
@@ -0,0 +1,435 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
+
+/// 每个预览最多保留的日志条数，超出后丢弃最早的普通日志
+const MAX_CONSOLE_ENTRIES: usize = 200;
+/// 单条日志消息的最大字符数
+const MAX_MESSAGE_CHARS: usize = 4000;
+/// 注入脚本的标记，用于避免重复注入
+const BRIDGE_MARKER: &str = "data-aipp-console-bridge";
+/// 附加到提问中的错误条数上限
+const MAX_CONTEXT_ERRORS: usize = 5;
+
+/// 注入到 React/Vue 预览页面的桥接脚本：拦截 console 输出与未捕获异常，
+/// 通过 postMessage 发给承载预览的 iframe 父窗口，由前端转发到后端
+const CONSOLE_BRIDGE_SCRIPT: &str = r#"(function () {
+  if (window.__aippConsoleBridge) return;
+  window.__aippConsoleBridge = true;
+  var previewId = __PREVIEW_ID__;
+  function format(arg) {
+    if (arg instanceof Error) return arg.stack || String(arg);
+    if (typeof arg === 'string') return arg;
+    try { return JSON.stringify(arg); } catch (e) { return String(arg); }
+  }
+  function send(level, args, stack) {
+    if (window.parent === window) return;
+    try {
+      window.parent.postMessage({
+        type: 'aipp_preview_console',
+        preview_id: previewId,
+        entry: {
+          level: level,
+          message: Array.prototype.map.call(args, format).join(' '),
+          stack: stack || null,
+          timestamp: Date.now()
+        }
+      }, '*');
+    } catch (e) {}
+  }
+  ['log', 'info', 'warn', 'error'].forEach(function (level) {
+    var original = console[level];
+    console[level] = function () {
+      send(level, arguments, null);
+      return original.apply(console, arguments);
+    };
+  });
+  window.addEventListener('error', function (event) {
+    var error = event.error;
+    var location = event.filename ? event.filename + ':' + event.lineno + ':' + event.colno : null;
+    send('uncaught', [event.message || String(error)], error && error.stack ? error.stack : location);
+  });
+  window.addEventListener('unhandledrejection', function (event) {
+    var reason = event.reason;
+    var message = reason instanceof Error ? reason.message : format(reason);
+    send('unhandledrejection', [message], reason && reason.stack ? reason.stack : null);
+  });
+})();"#;
+
+/// 预览页面上报的一条控制台输出
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PreviewConsoleEntry {
+    /// log / info / warn / error / uncaught / unhandledrejection
+    pub level: String,
+    pub message: String,
+    pub stack: Option<String>,
+    pub timestamp: i64,
+}
+
+impl PreviewConsoleEntry {
+    /// 是否为未捕获的异常（包括未处理的 Promise 拒绝）
+    pub fn is_uncaught(&self) -> bool {
+        matches!(self.level.as_str(), "uncaught" | "unhandledrejection")
+    }
+
+    /// 是否计入错误数：console.error 与未捕获的异常
+    pub fn is_error(&self) -> bool {
+        self.is_uncaught() || self.level == "error"
+    }
+}
+
+/// 一次预览的控制台日志，first_error 单独保留，供助手据此修正代码
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PreviewConsoleLog {
+    pub preview_id: String,
+    pub conversation_id: Option<i64>,
+    pub entries: Vec<PreviewConsoleEntry>,
+    pub first_error: Option<PreviewConsoleEntry>,
+    pub error_count: usize,
+    /// 因超出上限被丢弃的条数
+    pub dropped: usize,
+    /// 已附加到助手上下文中的错误条数，只有新增的错误才会再次附加
+    #[serde(default)]
+    pub reported_error_count: usize,
+}
+
+impl PreviewConsoleLog {
+    pub fn new(preview_id: &str, conversation_id: Option<i64>) -> Self {
+        Self { preview_id: preview_id.to_string(), conversation_id, ..Default::default() }
+    }
+
+    pub fn push(&mut self, mut entry: PreviewConsoleEntry) {
+        if entry.message.chars().count() > MAX_MESSAGE_CHARS {
+            entry.message = entry.message.chars().take(MAX_MESSAGE_CHARS).collect::<String>() + "…";
+        }
+        if entry.is_error() {
+            self.error_count += 1;
+        }
+        if entry.is_uncaught() && self.first_error.is_none() {
+            self.first_error = Some(entry.clone());
+        }
+        self.entries.push(entry);
+        if self.entries.len() > MAX_CONSOLE_ENTRIES {
+            self.entries.remove(0);
+            self.dropped += 1;
+        }
+    }
+
+    /// 有尚未附加到上下文的新错误时，生成给助手的上下文块并标记为已附加
+    pub fn take_context_block(&mut self) -> Option<String> {
+        if self.error_count <= self.reported_error_count {
+            return None;
+        }
+        let new_errors = self.error_count - self.reported_error_count;
+        self.reported_error_count = self.error_count;
+
+        let errors: Vec<&PreviewConsoleEntry> =
+            self.entries.iter().filter(|entry| entry.is_error()).collect();
+        let recent = &errors[errors.len().saturating_sub(new_errors.min(MAX_CONTEXT_ERRORS))..];
+        let mut lines = Vec::new();
+        if let Some(first) = self.first_error.as_ref().filter(|first| !recent.contains(first)) {
+            lines.push(format_context_entry(first));
+        }
+        lines.extend(recent.iter().map(|entry| format_context_entry(entry)));
+        Some(format!(
+            "<preview_console preview=\"{}\" error_count=\"{}\">\n{}\n</preview_console>",
+            self.preview_id,
+            self.error_count,
+            lines.join("\n")
+        ))
+    }
+}
+
+fn format_context_entry(entry: &PreviewConsoleEntry) -> String {
+    match entry.stack.as_deref().filter(|stack| !stack.trim().is_empty()) {
+        Some(stack) => format!("[{}] {}\n{}", entry.level, entry.message, stack),
+        None => format!("[{}] {}", entry.level, entry.message),
+    }
+}
+
+/// 内存中的日志缓存，首次访问时从磁盘加载
+static PREVIEW_CONSOLE_LOGS: Mutex<Option<HashMap<String, PreviewConsoleLog>>> = Mutex::new(None);
+
+/// 预览控制台日志的保存目录，随预览项目一起放在应用数据目录的 preview 下
+fn console_log_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join("preview").join("console"))
+}
+
+fn load_logs(dir: &Path) -> HashMap<String, PreviewConsoleLog> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let text = fs::read_to_string(entry.path()).ok()?;
+            match serde_json::from_str::<PreviewConsoleLog>(&text) {
+                Ok(log) => Some((log.preview_id.clone(), log)),
+                Err(e) => {
+                    warn!(path = %entry.path().display(), error = %e, "Ignoring invalid preview console log");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn save_log(dir: &Path, log: &PreviewConsoleLog) {
+    let result = fs::create_dir_all(dir).and_then(|_| {
+        let json = serde_json::to_string(log).map_err(std::io::Error::other)?;
+        fs::write(dir.join(format!("{}.json", log.preview_id)), json)
+    });
+    if let Err(e) = result {
+        warn!(preview_id = %log.preview_id, error = %e, "Failed to save preview console log");
+    }
+}
+
+/// 在已加载的日志上执行操作，`f` 返回的预览 id 对应的日志会写回磁盘（已移除的删除文件）
+fn with_logs<T>(
+    app_handle: &AppHandle,
+    f: impl FnOnce(&mut HashMap<String, PreviewConsoleLog>) -> (T, Vec<String>),
+) -> Result<T, String> {
+    let dir = console_log_dir(app_handle)?;
+    let mut guard = PREVIEW_CONSOLE_LOGS.lock().map_err(|e| e.to_string())?;
+    let logs = guard.get_or_insert_with(|| load_logs(&dir));
+    let (result, changed) = f(logs);
+    for preview_id in changed {
+        match logs.get(&preview_id) {
+            Some(log) => save_log(&dir, log),
+            None => {
+                let _ = fs::remove_file(dir.join(format!("{}.json", preview_id)));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// 新建预览时清空上一次预览的日志
+pub fn clear_preview_console(app_handle: &AppHandle, preview_id: &str) {
+    if let Err(e) = with_logs(app_handle, |logs| {
+        logs.remove(preview_id);
+        ((), vec![preview_id.to_string()])
+    }) {
+        warn!(preview_id, error = %e, "Failed to clear preview console log");
+    }
+}
+
+/// 取出对话中预览产生的新错误，作为附加到用户提问中的上下文块
+pub fn take_preview_console_context(
+    app_handle: &AppHandle,
+    conversation_id: i64,
+) -> Option<String> {
+    with_logs(app_handle, |logs| {
+        let mut blocks = Vec::new();
+        let mut changed = Vec::new();
+        for log in logs.values_mut().filter(|log| log.conversation_id == Some(conversation_id)) {
+            if let Some(block) = log.take_context_block() {
+                blocks.push(block);
+                changed.push(log.preview_id.clone());
+            }
+        }
+        ((!blocks.is_empty()).then(|| blocks.join("\n")), changed)
+    })
+    .unwrap_or_else(|e| {
+        warn!(conversation_id, error = %e, "Failed to read preview console logs");
+        None
+    })
+}
+
+/// 生成指定预览使用的桥接脚本
+pub fn console_bridge_script(preview_id: &str) -> String {
+    let preview_id = serde_json::to_string(preview_id).unwrap_or_else(|_| "\"\"".to_string());
+    CONSOLE_BRIDGE_SCRIPT.replace("__PREVIEW_ID__", &preview_id)
+}
+
+/// 在 index.html 的 head 开头注入桥接脚本，保证在应用代码之前执行；已注入的旧脚本会被替换
+pub fn inject_console_bridge(index_html: &str, preview_id: &str) -> String {
+    let mut html = index_html.to_string();
+    if let Some(start) = html.find(&format!("<script {}>", BRIDGE_MARKER)) {
+        if let Some(end) = html[start..].find("</script>") {
+            let mut end = start + end + "</script>".len();
+            if html[end..].starts_with('\n') {
+                end += 1;
+            }
+            html.replace_range(start..end, "");
+        }
+    }
+
+    let tag = format!("<script {}>{}</script>\n", BRIDGE_MARKER, console_bridge_script(preview_id));
+    let insert_at = html
+        .find("<head")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1))
+        .map(|pos| if html[pos..].starts_with('\n') { pos + 1 } else { pos })
+        .unwrap_or(0);
+    html.insert_str(insert_at, &tag);
+    html
+}
+
+/// 向预览项目的 index.html 注入桥接脚本
+pub fn inject_console_bridge_file(index_path: &Path, preview_id: &str) -> std::io::Result<()> {
+    let html = fs::read_to_string(index_path)?;
+    fs::write(index_path, inject_console_bridge(&html, preview_id))
+}
+
+/// 推送给各窗口的控制台事件
+#[derive(Debug, Clone, Serialize)]
+struct PreviewConsoleEvent {
+    preview_id: String,
+    conversation_id: Option<i64>,
+    entries: Vec<PreviewConsoleEntry>,
+    first_error: Option<PreviewConsoleEntry>,
+    error_count: usize,
+}
+
+/// 前端收到预览页面的控制台消息后批量上报，记录、保存并广播 artifact-preview-console 事件
+#[tauri::command]
+pub fn report_preview_console(
+    app_handle: AppHandle,
+    preview_id: String,
+    conversation_id: Option<i64>,
+    entries: Vec<PreviewConsoleEntry>,
+) -> Result<PreviewConsoleLog, String> {
+    let log = with_logs(&app_handle, |logs| {
+        let log = logs
+            .entry(preview_id.clone())
+            .or_insert_with(|| PreviewConsoleLog::new(&preview_id, conversation_id));
+        if conversation_id.is_some() {
+            log.conversation_id = conversation_id;
+        }
+        for entry in entries.iter().cloned() {
+            log.push(entry);
+        }
+        (log.clone(), vec![preview_id.clone()])
+    })?;
+
+    let _ = app_handle.emit(
+        "artifact-preview-console",
+        PreviewConsoleEvent {
+            preview_id,
+            conversation_id: log.conversation_id,
+            entries,
+            first_error: log.first_error.clone(),
+            error_count: log.error_count,
+        },
+    );
+    Ok(log)
+}
+
+/// 获取预览的控制台日志，尚无日志时返回 None
+#[tauri::command]
+pub fn get_preview_console_logs(
+    app_handle: AppHandle,
+    preview_id: String,
+) -> Result<Option<PreviewConsoleLog>, String> {
+    with_logs(&app_handle, |logs| (logs.get(&preview_id).cloned(), Vec::new()))
+}
+
+/// 清空预览的控制台日志
+#[tauri::command]
+pub fn clear_preview_console_logs(app_handle: AppHandle, preview_id: String) -> Result<(), String> {
+    clear_preview_console(&app_handle, &preview_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: &str, message: &str) -> PreviewConsoleEntry {
+        PreviewConsoleEntry {
+            level: level.to_string(),
+            message: message.to_string(),
+            stack: None,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_first_uncaught_error_is_kept() {
+        let mut log = PreviewConsoleLog::new("react", Some(1));
+        log.push(entry("log", "mounted"));
+        log.push(entry("error", "Warning: each child should have a key"));
+        log.push(entry("uncaught", "TypeError: x is undefined"));
+        log.push(entry("unhandledrejection", "fetch failed"));
+
+        assert_eq!(log.entries.len(), 4);
+        assert_eq!(log.error_count, 3);
+        assert_eq!(log.first_error.unwrap().message, "TypeError: x is undefined");
+    }
+
+    #[test]
+    fn test_entries_are_capped() {
+        let mut log = PreviewConsoleLog::new("vue", None);
+        log.push(entry("uncaught", "boom"));
+        for i in 0..MAX_CONSOLE_ENTRIES + 5 {
+            log.push(entry("log", &format!("line {}", i)));
+        }
+
+        assert_eq!(log.entries.len(), MAX_CONSOLE_ENTRIES);
+        assert_eq!(log.dropped, 6);
+        // 最早的异常被挤出日志列表后仍保留在 first_error 中
+        assert_eq!(log.first_error.unwrap().message, "boom");
+
+        let mut long = PreviewConsoleLog::new("vue", None);
+        long.push(entry("log", &"a".repeat(MAX_MESSAGE_CHARS + 10)));
+        assert_eq!(long.entries[0].message.chars().count(), MAX_MESSAGE_CHARS + 1);
+    }
+
+    #[test]
+    fn test_context_block_only_includes_new_errors() {
+        let mut log = PreviewConsoleLog::new("react", Some(1));
+        log.push(entry("log", "mounted"));
+        assert!(log.take_context_block().is_none());
+
+        log.push(PreviewConsoleEntry {
+            stack: Some("at App (App.tsx:3:5)".to_string()),
+            ..entry("uncaught", "TypeError: x is undefined")
+        });
+        let block = log.take_context_block().unwrap();
+        assert!(block.starts_with("<preview_console preview=\"react\" error_count=\"1\">"));
+        assert!(block.contains("[uncaught] TypeError: x is undefined\nat App (App.tsx:3:5)"));
+        assert!(!block.contains("mounted"));
+        assert!(log.take_context_block().is_none());
+
+        // 新错误附加时同时带上最早的异常
+        log.push(entry("error", "Failed to fetch"));
+        let block = log.take_context_block().unwrap();
+        assert!(block.contains("TypeError: x is undefined"));
+        assert!(block.contains("[error] Failed to fetch"));
+    }
+
+    #[test]
+    fn test_logs_persist_across_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = PreviewConsoleLog::new("vue", Some(7));
+        log.push(entry("uncaught", "boom"));
+        save_log(dir.path(), &log);
+
+        let loaded = load_logs(dir.path());
+        assert_eq!(loaded.get("vue"), Some(&log));
+        assert!(load_logs(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_inject_console_bridge_is_idempotent() {
+        let html = "<!DOCTYPE html>\n<html>\n  <head>\n    <title>Preview</title>\n  </head>\n  <body><div id=\"root\"></div></body>\n</html>";
+        let injected = inject_console_bridge(html, "react");
+
+        assert!(injected.contains("var previewId = \"react\";"));
+        let script_pos = injected.find(BRIDGE_MARKER).unwrap();
+        assert!(script_pos > injected.find("<head>").unwrap());
+        assert!(script_pos < injected.find("<title>").unwrap());
+
+        let twice = inject_console_bridge(&injected, "react");
+        assert_eq!(twice, injected);
+        assert_eq!(twice.matches(BRIDGE_MARKER).count(), 1);
+
+        // 没有 head 时插到最前面
+        assert!(inject_console_bridge("<div></div>", "vue").starts_with("<script"));
+    }
+}
@@ -6,6 +6,7 @@ use std::sync::LazyLock;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
 
+use crate::artifacts::preview_console::{clear_preview_console, inject_console_bridge_file};
 use crate::artifacts::shared_components::{
     kill_process_by_pid, kill_process_group_by_pid, kill_processes_by_port, SharedPreviewUtils,
     TemplateCache,
@@ -101,6 +102,7 @@ impl ReactPreviewManager {
 
         // 关闭已存在的预览实例
         let _ = self.close_preview(&preview_id);
+        clear_preview_console(&self.app_handle, &preview_id);
 
         let (template_path, need_install_deps) = self.setup_template_project(
             &preview_id,
@@ -357,6 +359,11 @@ impl ReactPreviewManager {
         fs::write(&component_file, component_code)?;
         println!("🛠️ [Setup] 组件文件写入完成");
 
+        // 注入控制台桥接脚本，把预览中的日志和未捕获异常回传给应用
+        if let Err(e) = inject_console_bridge_file(&preview_dir.join("index.html"), preview_id) {
+            println!("⚠️ [Setup] 注入控制台桥接脚本失败: {}", e);
+        }
+
         // 返回预览目录和是否需要安装依赖的标志
         Ok((preview_dir, need_install_deps))
     }
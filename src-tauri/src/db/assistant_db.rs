@@ -1,7 +1,8 @@
+use super::encryption::open_connection;
 use super::get_db_path;
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, instrument};
+use tracing::{debug, error, instrument, warn};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Assistant {
@@ -65,6 +66,9 @@ pub struct AssistantMCPToolConfig {
     pub mcp_tool_id: i64,
     pub is_enabled: bool,
     pub is_auto_run: bool,
+    /// 工具默认参数（JSON 对象），执行前与模型提供的参数合并
+    #[serde(default)]
+    pub default_arguments: Option<String>,
 }
 
 pub struct AssistantDatabase {
@@ -75,10 +79,10 @@ pub struct AssistantDatabase {
 impl AssistantDatabase {
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "assistant.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
 
         let mcp_db_path = get_db_path(app_handle, "mcp.db");
-        let mcp_conn = Connection::open(mcp_db_path.unwrap())?;
+        let mcp_conn = open_connection(mcp_db_path.unwrap())?;
 
         Ok(AssistantDatabase { conn, mcp_conn })
     }
@@ -177,12 +181,46 @@ impl AssistantDatabase {
                 mcp_tool_id INTEGER NOT NULL,
                 is_enabled BOOLEAN NOT NULL DEFAULT 1,
                 is_auto_run BOOLEAN NOT NULL DEFAULT 0,
+                default_arguments TEXT,
                 created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
                 FOREIGN KEY (assistant_id) REFERENCES assistant(id) ON DELETE CASCADE,
                 UNIQUE(assistant_id, mcp_tool_id)
             );",
             [],
         )?;
+        self.migrate_assistant_mcp_tool_config_table()?;
+
+        // 助手的对话开场建议，按 sort_order 展示在空对话中
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS assistant_starter (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                assistant_id INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (assistant_id) REFERENCES assistant(id) ON DELETE CASCADE
+            );",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_assistant_starter_assistant ON assistant_starter(assistant_id, sort_order);",
+            [],
+        )?;
+
+        // 助手继承关系：子助手在组装时合并基础助手的提示词、模型配置、工具与技能
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS assistant_inheritance (
+                assistant_id INTEGER PRIMARY KEY,
+                base_assistant_id INTEGER NOT NULL,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (assistant_id) REFERENCES assistant(id) ON DELETE CASCADE
+            );",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_assistant_inheritance_base ON assistant_inheritance(base_assistant_id);",
+            [],
+        )?;
 
         if let Err(err) = self.init_assistant() {
             error!(error = ?err, "init_assistant failed during create_tables");
@@ -192,6 +230,19 @@ impl AssistantDatabase {
         Ok(())
     }
 
+    fn migrate_assistant_mcp_tool_config_table(&self) -> rusqlite::Result<()> {
+        let mut stmt = self.conn.prepare("PRAGMA table_info(assistant_mcp_tool_config)")?;
+        let columns =
+            stmt.query_map([], |row| row.get::<_, String>(1))?.collect::<Result<Vec<_>>>()?;
+        if !columns.iter().any(|c| c == "default_arguments") {
+            self.conn.execute(
+                "ALTER TABLE assistant_mcp_tool_config ADD COLUMN default_arguments TEXT",
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
     #[instrument(level = "debug", skip(self), fields(name = name, assistant_type = assistant_type))]
     pub fn add_assistant(
         &self,
@@ -637,7 +688,7 @@ impl AssistantDatabase {
         assistant_id: i64,
     ) -> Result<Vec<AssistantMCPToolConfig>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, assistant_id, mcp_tool_id, is_enabled, is_auto_run FROM assistant_mcp_tool_config WHERE assistant_id = ?"
+            "SELECT id, assistant_id, mcp_tool_id, is_enabled, is_auto_run, default_arguments FROM assistant_mcp_tool_config WHERE assistant_id = ?"
         )?;
         let mcp_tool_config_iter = stmt.query_map(params![assistant_id], |row| {
             Ok(AssistantMCPToolConfig {
@@ -646,6 +697,7 @@ impl AssistantDatabase {
                 mcp_tool_id: row.get(2)?,
                 is_enabled: row.get(3)?,
                 is_auto_run: row.get(4)?,
+                default_arguments: row.get(5)?,
             })
         })?;
 
@@ -664,20 +716,208 @@ impl AssistantDatabase {
         is_enabled: bool,
         is_auto_run: bool,
     ) -> Result<()> {
+        // 使用 ON CONFLICT 更新而非 REPLACE，避免切换开关时丢失默认参数
         self.conn.execute(
-            "INSERT OR REPLACE INTO assistant_mcp_tool_config (assistant_id, mcp_tool_id, is_enabled, is_auto_run) VALUES (?, ?, ?, ?)",
+            "INSERT INTO assistant_mcp_tool_config (assistant_id, mcp_tool_id, is_enabled, is_auto_run) VALUES (?, ?, ?, ?)
+             ON CONFLICT(assistant_id, mcp_tool_id) DO UPDATE SET is_enabled = excluded.is_enabled, is_auto_run = excluded.is_auto_run",
             params![assistant_id, mcp_tool_id, is_enabled, is_auto_run],
         )?;
         debug!("assistant mcp tool config upserted");
         Ok(())
     }
 
+    /// 设置助手对某个工具的默认参数，None 表示清空；未配置过的工具按默认开关状态新建配置
+    #[instrument(level = "debug", skip(self, default_arguments), fields(assistant_id = assistant_id, mcp_tool_id = mcp_tool_id))]
+    pub fn set_assistant_mcp_tool_default_arguments(
+        &self,
+        assistant_id: i64,
+        mcp_tool_id: i64,
+        default_arguments: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO assistant_mcp_tool_config (assistant_id, mcp_tool_id, default_arguments) VALUES (?, ?, ?)
+             ON CONFLICT(assistant_id, mcp_tool_id) DO UPDATE SET default_arguments = excluded.default_arguments",
+            params![assistant_id, mcp_tool_id, default_arguments],
+        )?;
+        debug!("assistant mcp tool default arguments updated");
+        Ok(())
+    }
+
+    /// 获取助手对某个工具配置的默认参数
+    #[instrument(level = "debug", skip(self), fields(assistant_id = assistant_id, mcp_tool_id = mcp_tool_id))]
+    pub fn get_assistant_mcp_tool_default_arguments(
+        &self,
+        assistant_id: i64,
+        mcp_tool_id: i64,
+    ) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT default_arguments FROM assistant_mcp_tool_config WHERE assistant_id = ? AND mcp_tool_id = ?",
+                params![assistant_id, mcp_tool_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map(Option::flatten)
+    }
+
+    /// 获取助手的对话开场建议（未解析模板变量），按配置顺序返回
+    #[instrument(level = "debug", skip(self), fields(assistant_id = assistant_id))]
+    pub fn get_assistant_starters(&self, assistant_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT content FROM assistant_starter WHERE assistant_id = ? ORDER BY sort_order, id",
+        )?;
+        let starters = stmt
+            .query_map(params![assistant_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(starters)
+    }
+
+    /// 整体替换助手的对话开场建议，空白项会被忽略
+    #[instrument(level = "debug", skip(self, starters), fields(assistant_id = assistant_id, count = starters.len()))]
+    pub fn set_assistant_starters(&self, assistant_id: i64, starters: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM assistant_starter WHERE assistant_id = ?", params![assistant_id])?;
+        let starters = starters.iter().map(|s| s.trim()).filter(|s| !s.is_empty());
+        for (sort_order, content) in starters.enumerate() {
+            tx.execute(
+                "INSERT INTO assistant_starter (assistant_id, content, sort_order) VALUES (?, ?, ?)",
+                params![assistant_id, content, sort_order as i64],
+            )?;
+        }
+        tx.commit()?;
+        debug!("assistant starters replaced");
+        Ok(())
+    }
+
+    /// 获取助手直接继承的基础助手
+    #[instrument(level = "debug", skip(self), fields(assistant_id = assistant_id))]
+    pub fn get_base_assistant_id(&self, assistant_id: i64) -> Result<Option<i64>> {
+        self.conn
+            .query_row(
+                "SELECT base_assistant_id FROM assistant_inheritance WHERE assistant_id = ?",
+                params![assistant_id],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// 直接继承了指定助手的子助手
+    #[instrument(level = "debug", skip(self), fields(base_assistant_id = base_assistant_id))]
+    pub fn get_child_assistant_ids(&self, base_assistant_id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT assistant_id FROM assistant_inheritance WHERE base_assistant_id = ? ORDER BY assistant_id",
+        )?;
+        let ids = stmt
+            .query_map(params![base_assistant_id], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ids)
+    }
+
+    /// 从助手自身开始沿继承关系向上的助手 ID 链（子助手在前，最顶层的基础助手在后）
+    ///
+    /// 写入时已拒绝循环继承，这里遇到环（例如手动改库）时截断，避免死循环
+    #[instrument(level = "debug", skip(self), fields(assistant_id = assistant_id))]
+    pub fn get_inheritance_chain(&self, assistant_id: i64) -> Result<Vec<i64>> {
+        let mut chain = vec![assistant_id];
+        let mut current = assistant_id;
+        while let Some(base_id) = self.get_base_assistant_id(current)? {
+            if chain.contains(&base_id) {
+                warn!(assistant_id, base_id, "assistant inheritance cycle detected, truncated");
+                break;
+            }
+            chain.push(base_id);
+            current = base_id;
+        }
+        Ok(chain)
+    }
+
+    /// 设置或清除助手的基础助手；继承自身或形成循环继承时返回错误
+    #[instrument(level = "debug", skip(self), fields(assistant_id = assistant_id, base_assistant_id = ?base_assistant_id))]
+    pub fn set_base_assistant(
+        &self,
+        assistant_id: i64,
+        base_assistant_id: Option<i64>,
+    ) -> std::result::Result<(), String> {
+        let Some(base_id) = base_assistant_id else {
+            self.conn
+                .execute(
+                    "DELETE FROM assistant_inheritance WHERE assistant_id = ?",
+                    params![assistant_id],
+                )
+                .map_err(|e| e.to_string())?;
+            debug!("assistant base cleared");
+            return Ok(());
+        };
+
+        if base_id == assistant_id {
+            return Err("助手不能继承自身".to_string());
+        }
+        let base_chain = self.get_inheritance_chain(base_id).map_err(|e| e.to_string())?;
+        if base_chain.contains(&assistant_id) {
+            return Err(format!("助手 {} 已直接或间接继承自当前助手，不能形成循环继承", base_id));
+        }
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO assistant_inheritance (assistant_id, base_assistant_id) VALUES (?, ?)",
+                params![assistant_id, base_id],
+            )
+            .map_err(|e| e.to_string())?;
+        debug!("assistant base set");
+        Ok(())
+    }
+
+    /// 删除引用了指定 MCP 服务器/工具的助手配置（MCP 服务器位于 mcp.db，无法依赖外键级联）
+    #[instrument(level = "debug", skip(self, server_ids, tool_ids), fields(server_count = server_ids.len(), tool_count = tool_ids.len()))]
+    pub fn delete_assistant_mcp_bindings(
+        &self,
+        server_ids: &[i64],
+        tool_ids: &[i64],
+    ) -> Result<()> {
+        if !server_ids.is_empty() {
+            let placeholders = vec!["?"; server_ids.len()].join(",");
+            self.conn.execute(
+                &format!(
+                    "DELETE FROM assistant_mcp_config WHERE mcp_server_id IN ({})",
+                    placeholders
+                ),
+                rusqlite::params_from_iter(server_ids),
+            )?;
+        }
+        if !tool_ids.is_empty() {
+            let placeholders = vec!["?"; tool_ids.len()].join(",");
+            self.conn.execute(
+                &format!(
+                    "DELETE FROM assistant_mcp_tool_config WHERE mcp_tool_id IN ({})",
+                    placeholders
+                ),
+                rusqlite::params_from_iter(tool_ids),
+            )?;
+        }
+        debug!("assistant mcp bindings deleted");
+        Ok(())
+    }
+
+    /// 助手自身保存的 MCP 服务器与工具启用状态（不合并基础助手）
     #[instrument(level = "debug", skip(self), fields(assistant_id = assistant_id))]
     pub fn get_assistant_mcp_servers_with_tools(
         &self,
         assistant_id: i64,
     ) -> Result<
         Vec<(i64, String, Option<String>, bool, Vec<(i64, String, String, bool, bool, String)>)>,
+    > {
+        let mcp_configs = self.get_assistant_mcp_configs(assistant_id)?;
+        let mcp_tool_configs = self.get_assistant_mcp_tool_configs(assistant_id)?;
+        self.get_mcp_servers_with_tools_for_configs(&mcp_configs, &mcp_tool_configs)
+    }
+
+    /// 按给定的助手 MCP 配置（例如合并了基础助手的生效配置）列出服务器与工具的启用状态
+    #[instrument(level = "debug", skip(self, mcp_configs, mcp_tool_configs), fields(server_config_count = mcp_configs.len(), tool_config_count = mcp_tool_configs.len()))]
+    pub fn get_mcp_servers_with_tools_for_configs(
+        &self,
+        mcp_configs: &[AssistantMCPConfig],
+        mcp_tool_configs: &[AssistantMCPToolConfig],
+    ) -> Result<
+        Vec<(i64, String, Option<String>, bool, Vec<(i64, String, String, bool, bool, String)>)>,
     > {
         // 使用一条 SQL 语句获取所有需要的数据，避免 N+1 查询问题
         // 注意：由于涉及两个数据库（assistant.db 和 mcp.db），我们需要分两步查询，但可以优化为批量查询
@@ -705,24 +945,10 @@ impl AssistantDatabase {
             return Ok(Vec::new());
         }
 
-        // 2. 批量获取所有服务器的配置状态
-        let server_ids: Vec<String> = servers.iter().map(|(id, _, _)| id.to_string()).collect();
-        let server_ids_placeholder = vec!["?"; server_ids.len()].join(",");
-        let server_config_sql = format!(
-            "SELECT mcp_server_id, is_enabled FROM assistant_mcp_config 
-             WHERE assistant_id = ? AND mcp_server_id IN ({})",
-            server_ids_placeholder
-        );
-
-        let mut server_config_stmt = self.conn.prepare(&server_config_sql)?;
-        let mut server_config_params = vec![assistant_id];
-        server_config_params.extend(servers.iter().map(|(id, _, _)| *id));
-
-        let server_configs: std::collections::HashMap<i64, bool> = server_config_stmt
-            .query_map(rusqlite::params_from_iter(server_config_params), |row| {
-                Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?))
-            })?
-            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+        // 2. 服务器的配置状态
+        let server_configs: std::collections::HashMap<i64, bool> =
+            mcp_configs.iter().map(|config| (config.mcp_server_id, config.is_enabled)).collect();
+        let server_ids_placeholder = vec!["?"; servers.len()].join(",");
 
         // 3. 获取所有工具信息（一次性获取所有服务器的工具）
         let tools_sql = format!(
@@ -746,29 +972,11 @@ impl AssistantDatabase {
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // 4. 批量获取工具配置状态
-        let mut tool_configs: std::collections::HashMap<i64, (bool, bool)> =
-            std::collections::HashMap::new();
-        if !all_tools.is_empty() {
-            let tool_ids: Vec<String> =
-                all_tools.iter().map(|(id, _, _, _, _)| id.to_string()).collect();
-            let tool_ids_placeholder = vec!["?"; tool_ids.len()].join(",");
-            let tool_config_sql = format!(
-                "SELECT mcp_tool_id, is_enabled, is_auto_run FROM assistant_mcp_tool_config 
-                 WHERE assistant_id = ? AND mcp_tool_id IN ({})",
-                tool_ids_placeholder
-            );
-
-            let mut tool_config_stmt = self.conn.prepare(&tool_config_sql)?;
-            let mut tool_config_params = vec![assistant_id];
-            tool_config_params.extend(all_tools.iter().map(|(id, _, _, _, _)| *id));
-
-            tool_configs = tool_config_stmt
-                .query_map(rusqlite::params_from_iter(tool_config_params), |row| {
-                    Ok((row.get::<_, i64>(0)?, (row.get::<_, bool>(1)?, row.get::<_, bool>(2)?)))
-                })?
-                .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
-        }
+        // 4. 工具配置状态
+        let tool_configs: std::collections::HashMap<i64, (bool, bool)> = mcp_tool_configs
+            .iter()
+            .map(|config| (config.mcp_tool_id, (config.is_enabled, config.is_auto_run)))
+            .collect();
 
         // 5. 组织数据结构
         let mut result = Vec::new();
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 use chrono::{prelude::*, SecondsFormat};
@@ -9,8 +9,21 @@ use tracing::{debug, instrument};
 use crate::errors::AppError;
 use crate::utils::db_utils::{get_datetime_from_row, get_required_datetime_from_row};
 
+use super::encryption::open_connection;
 use super::get_db_path;
 
+/// 每条消息最多保留的编辑历史版本数
+pub const MAX_MESSAGE_EDIT_HISTORY: usize = 20;
+
+/// 消息全文索引使用 trigram 分词（兼容中文等无空格分词的语言），每个搜索词至少需要 3 个字符
+const MESSAGE_SEARCH_MIN_TERM_CHARS: usize = 3;
+/// 搜索结果摘要中命中部分的标记
+pub const SEARCH_HIGHLIGHT_START: &str = "<mark>";
+pub const SEARCH_HIGHLIGHT_END: &str = "</mark>";
+/// FTS snippet() 使用的临时标记（Unicode 私有区字符），转义消息内容后再替换为 HTML 标记
+const FTS_HIGHLIGHT_START: &str = "\u{E000}";
+const FTS_HIGHLIGHT_END: &str = "\u{E001}";
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum AttachmentType {
     Image = 1,
@@ -124,6 +137,12 @@ pub struct MessageDetail {
     #[serde(serialize_with = "serialize_option_datetime_millis")]
     pub first_token_time: Option<DateTime<Utc>>, // 首个 token 到达时间
     pub ttft_ms: Option<i64>, // Time to First Token (毫秒)
+    #[serde(default)]
+    pub is_pinned: bool, // 是否置顶（置顶消息在上下文截断时始终保留）
+    #[serde(default)]
+    pub render_mode: Option<String>, // 渲染模式（plaintext / code），为空时按 markdown 渲染
+    #[serde(default)]
+    pub is_partial: bool, // 是否为生成中途中断的部分回复
     pub attachment_list: Vec<MessageAttachment>,
     pub regenerate: Vec<MessageDetail>,
 }
@@ -140,6 +159,92 @@ pub struct MessageAttachment {
     pub token_count: Option<i32>,
 }
 
+/// 插入对话，仓库的 create 与需要在同一事务中写入多张表的场景（如导入对话）共用
+pub fn insert_conversation(conn: &Connection, conversation: &Conversation) -> Result<Conversation> {
+    conn.execute(
+        "INSERT INTO conversation (name, assistant_id, created_time) VALUES (?1, ?2, ?3)",
+        (&conversation.name, &conversation.assistant_id, &conversation.created_time),
+    )?;
+    let id = conn.last_insert_rowid();
+    debug!(conversation_id = id, "conversation inserted");
+    Ok(Conversation {
+        id,
+        name: conversation.name.clone(),
+        assistant_id: conversation.assistant_id,
+        created_time: conversation.created_time,
+    })
+}
+
+/// 插入消息，仓库的 create 与需要在同一事务中写入多张表的场景（如导入对话）共用
+pub fn insert_message(conn: &Connection, message: &Message) -> Result<Message> {
+    // rusqlite Params trait only supports up to 16 parameters, use named params for 17+ fields
+    conn.execute(
+        "INSERT INTO message (parent_id, conversation_id, message_type, content, llm_model_id, llm_model_name, created_time, start_time, finish_time, token_count, input_token_count, output_token_count, generation_group_id, parent_group_id, tool_calls_json, first_token_time, ttft_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        rusqlite::params![
+            &message.parent_id,
+            &message.conversation_id,
+            &message.message_type,
+            &message.content,
+            &message.llm_model_id,
+            &message.llm_model_name,
+            &message.created_time,
+            &message.start_time,
+            &message.finish_time,
+            &message.token_count,
+            &message.input_token_count,
+            &message.output_token_count,
+            &message.generation_group_id,
+            &message.parent_group_id,
+            &message.tool_calls_json,
+            &message.first_token_time,
+            &message.ttft_ms,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    Ok(Message {
+        id,
+        parent_id: message.parent_id,
+        conversation_id: message.conversation_id,
+        message_type: message.message_type.clone(),
+        content: message.content.clone(),
+        llm_model_id: message.llm_model_id,
+        llm_model_name: message.llm_model_name.clone(),
+        created_time: message.created_time,
+        start_time: message.start_time,
+        finish_time: message.finish_time,
+        token_count: message.token_count,
+        input_token_count: message.input_token_count,
+        output_token_count: message.output_token_count,
+        generation_group_id: message.generation_group_id.clone(),
+        parent_group_id: message.parent_group_id.clone(),
+        tool_calls_json: message.tool_calls_json.clone(),
+        first_token_time: message.first_token_time,
+        ttft_ms: message.ttft_ms,
+    })
+}
+
+/// 插入消息附件，仓库的 create 与需要在同一事务中写入多张表的场景（如导入对话）共用
+pub fn insert_attachment(
+    conn: &Connection,
+    attachment: &MessageAttachment,
+) -> Result<MessageAttachment> {
+    conn.execute(
+        "INSERT INTO message_attachment (message_id, attachment_type, attachment_url, attachment_content, attachment_hash, use_vector, token_count) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        (&attachment.message_id, &(attachment.attachment_type as i64), &attachment.attachment_url, &attachment.attachment_content, &attachment.attachment_hash, &attachment.use_vector, &attachment.token_count),
+    )?;
+    let id = conn.last_insert_rowid();
+    Ok(MessageAttachment {
+        id,
+        message_id: attachment.message_id,
+        attachment_type: attachment.attachment_type,
+        attachment_url: attachment.attachment_url.clone(),
+        attachment_content: attachment.attachment_content.clone(),
+        attachment_hash: None,
+        use_vector: attachment.use_vector,
+        token_count: attachment.token_count,
+    })
+}
+
 pub trait Repository<T> {
     fn create(&self, item: &T) -> Result<T>;
     fn read(&self, id: i64) -> Result<Option<T>>;
@@ -198,23 +303,127 @@ impl ConversationRepository {
         )?;
         Ok(())
     }
+
+    /// 按标签筛选对话列表
+    #[instrument(level = "debug", skip(self), fields(page = page, per_page = per_page, tag = tag))]
+    pub fn list_by_tag(&self, page: u32, per_page: u32, tag: &str) -> Result<Vec<Conversation>> {
+        let offset = (page - 1) * per_page;
+        let mut stmt = self.conn.prepare(
+            "SELECT c.id, c.name, c.assistant_id, c.created_time
+             FROM conversation c
+             JOIN conversation_tag_link l ON l.conversation_id = c.id
+             JOIN conversation_tag t ON t.id = l.tag_id
+             WHERE t.name = ?1
+             ORDER BY c.created_time DESC
+             LIMIT ?2 OFFSET ?3",
+        )?;
+        let rows = stmt.query_map(params![tag, per_page, offset], |row| {
+            Ok(Conversation {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                assistant_id: row.get(2)?,
+                created_time: get_required_datetime_from_row(row, 3, "created_time")?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 给对话添加标签，标签不存在时自动创建；重复添加不会报错
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id, tag = tag))]
+    pub fn add_tag(&self, conversation_id: i64, tag: &str) -> Result<()> {
+        self.conn.execute("INSERT OR IGNORE INTO conversation_tag (name) VALUES (?1)", [tag])?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO conversation_tag_link (conversation_id, tag_id)
+             SELECT ?1, id FROM conversation_tag WHERE name = ?2",
+            params![conversation_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// 移除对话上的标签，标签本身保留
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id, tag = tag))]
+    pub fn remove_tag(&self, conversation_id: i64, tag: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM conversation_tag_link
+             WHERE conversation_id = ?1 AND tag_id IN (SELECT id FROM conversation_tag WHERE name = ?2)",
+            params![conversation_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// 删除标签及其全部关联，不影响对话本身
+    #[instrument(level = "debug", skip(self), fields(tag_id = tag_id))]
+    pub fn delete_tag(&self, tag_id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM conversation_tag_link WHERE tag_id = ?1", [tag_id])?;
+        self.conn.execute("DELETE FROM conversation_tag WHERE id = ?1", [tag_id])?;
+        Ok(())
+    }
+
+    /// 获取全部标签及其对话数量，按名称排序
+    #[instrument(level = "debug", skip(self))]
+    pub fn list_tags(&self) -> Result<Vec<ConversationTag>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, t.name, COUNT(l.conversation_id)
+             FROM conversation_tag t
+             LEFT JOIN conversation_tag_link l ON l.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY t.name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ConversationTag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                conversation_count: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 批量获取对话的标签名称，没有标签的对话不出现在结果中
+    #[instrument(level = "debug", skip(self, conversation_ids), fields(count = conversation_ids.len()))]
+    pub fn list_tags_by_conversation_ids(
+        &self,
+        conversation_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<String>>> {
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        if conversation_ids.is_empty() {
+            return Ok(tags);
+        }
+        let placeholders = vec!["?"; conversation_ids.len()].join(",");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT l.conversation_id, t.name
+             FROM conversation_tag_link l
+             JOIN conversation_tag t ON t.id = l.tag_id
+             WHERE l.conversation_id IN ({})
+             ORDER BY t.name",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(conversation_ids.iter()), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (conversation_id, name) = row?;
+            tags.entry(conversation_id).or_default().push(name);
+        }
+        Ok(tags)
+    }
+
+    /// 把一个对话的标签复制到另一个对话（分支对话时继承标签）
+    #[instrument(level = "debug", skip(self), fields(from = from_conversation_id, to = to_conversation_id))]
+    pub fn copy_tags(&self, from_conversation_id: i64, to_conversation_id: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO conversation_tag_link (conversation_id, tag_id)
+             SELECT ?2, tag_id FROM conversation_tag_link WHERE conversation_id = ?1",
+            params![from_conversation_id, to_conversation_id],
+        )?;
+        Ok(())
+    }
 }
 
 impl Repository<Conversation> for ConversationRepository {
     #[instrument(level = "debug", skip(self, conversation), fields(name = conversation.name))]
     fn create(&self, conversation: &Conversation) -> Result<Conversation> {
-        self.conn.execute(
-            "INSERT INTO conversation (name, assistant_id, created_time) VALUES (?1, ?2, ?3)",
-            (&conversation.name, &conversation.assistant_id, &conversation.created_time),
-        )?;
-        let id = self.conn.last_insert_rowid();
-        debug!(conversation_id = id, "conversation inserted");
-        Ok(Conversation {
-            id,
-            name: conversation.name.clone(),
-            assistant_id: conversation.assistant_id,
-            created_time: conversation.created_time,
-        })
+        insert_conversation(&self.conn, conversation)
     }
 
     #[instrument(level = "debug", skip(self), fields(id = id))]
@@ -251,6 +460,51 @@ impl Repository<Conversation> for ConversationRepository {
     }
 }
 
+const MESSAGE_WITH_ATTACHMENT_COLUMNS: &str = "message.id, message.parent_id, message.conversation_id, message.message_type, message.content, message.llm_model_id, message.llm_model_name, message.created_time, message.start_time, message.finish_time, message.token_count, message.input_token_count, message.output_token_count, message.generation_group_id, message.parent_group_id, message.tool_calls_json, message.first_token_time, message.ttft_ms, ma.attachment_type, ma.attachment_url, ma.attachment_content, ma.use_vector as attachment_use_vector, ma.token_count as attachment_token_count";
+
+/// 解析 `message LEFT JOIN message_attachment` 查询的一行，列顺序见 `MESSAGE_WITH_ATTACHMENT_COLUMNS`
+fn message_with_attachment_from_row(
+    row: &rusqlite::Row,
+) -> rusqlite::Result<(Message, Option<MessageAttachment>)> {
+    let attachment_type_int: Option<i64> = row.get(18).ok();
+    let attachment_type = attachment_type_int.map(AttachmentType::try_from).transpose()?;
+    let message = Message {
+        id: row.get(0)?,
+        parent_id: row.get(1)?,
+        conversation_id: row.get(2)?,
+        message_type: row.get(3)?,
+        content: row.get(4)?,
+        llm_model_id: row.get(5)?,
+        llm_model_name: row.get(6)?,
+        created_time: get_required_datetime_from_row(row, 7, "created_time")?,
+        start_time: get_datetime_from_row(row, 8)?,
+        finish_time: get_datetime_from_row(row, 9)?,
+        token_count: row.get(10)?,
+        input_token_count: row.get(11)?,
+        output_token_count: row.get(12)?,
+        generation_group_id: row.get(13)?,
+        parent_group_id: row.get(14)?,
+        tool_calls_json: row.get(15)?,
+        first_token_time: get_datetime_from_row(row, 16)?,
+        ttft_ms: row.get(17).ok(),
+    };
+    let attachment = if attachment_type.is_some() {
+        Some(MessageAttachment {
+            id: 0,
+            message_id: row.get(0)?,
+            attachment_type: attachment_type.unwrap(),
+            attachment_url: row.get(19)?,
+            attachment_content: row.get(20)?,
+            attachment_hash: None,
+            use_vector: row.get(21)?,
+            token_count: row.get(22)?,
+        })
+    } else {
+        None
+    };
+    Ok((message, attachment))
+}
+
 pub struct MessageRepository {
     conn: Connection,
 }
@@ -266,53 +520,166 @@ impl MessageRepository {
         &self,
         conversation_id: i64,
     ) -> Result<Vec<(Message, Option<MessageAttachment>)>> {
-        let mut stmt = self.conn.prepare("SELECT message.id, message.parent_id, message.conversation_id, message.message_type, message.content, message.llm_model_id, message.llm_model_name, message.created_time, message.start_time, message.finish_time, message.token_count, message.input_token_count, message.output_token_count, message.generation_group_id, message.parent_group_id, message.tool_calls_json, message.first_token_time, message.ttft_ms, ma.attachment_type, ma.attachment_url, ma.attachment_content, ma.use_vector as attachment_use_vector, ma.token_count as attachment_token_count
-                                          FROM message
-                                          LEFT JOIN message_attachment ma ON message.id = ma.message_id
-                                          WHERE message.conversation_id = ?1
-                                          ORDER BY message.created_time ASC")?;
-        let rows = stmt.query_map(&[&conversation_id], |row| {
-            let attachment_type_int: Option<i64> = row.get(18).ok();
-            let attachment_type = attachment_type_int.map(AttachmentType::try_from).transpose()?;
-            let message = Message {
-                id: row.get(0)?,
-                parent_id: row.get(1)?,
-                conversation_id: row.get(2)?,
-                message_type: row.get(3)?,
-                content: row.get(4)?,
-                llm_model_id: row.get(5)?,
-                llm_model_name: row.get(6)?,
-                created_time: get_required_datetime_from_row(row, 7, "created_time")?,
-                start_time: get_datetime_from_row(row, 8)?,
-                finish_time: get_datetime_from_row(row, 9)?,
-                token_count: row.get(10)?,
-                input_token_count: row.get(11)?,
-                output_token_count: row.get(12)?,
-                generation_group_id: row.get(13)?,
-                parent_group_id: row.get(14)?,
-                tool_calls_json: row.get(15)?,
-                first_token_time: get_datetime_from_row(row, 16)?,
-                ttft_ms: row.get(17).ok(),
-            };
-            let attachment = if attachment_type.is_some() {
-                Some(MessageAttachment {
-                    id: 0,
-                    message_id: row.get(0)?,
-                    attachment_type: attachment_type.unwrap(),
-                    attachment_url: row.get(19)?,
-                    attachment_content: row.get(20)?,
-                    attachment_hash: None,
-                    use_vector: row.get(21)?,
-                    token_count: row.get(22)?,
-                })
-            } else {
-                None
-            };
-            Ok((message, attachment))
-        })?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM message
+             LEFT JOIN message_attachment ma ON message.id = ma.message_id
+             WHERE message.conversation_id = ?1
+             ORDER BY message.created_time ASC",
+            MESSAGE_WITH_ATTACHMENT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(&[&conversation_id], message_with_attachment_from_row)?;
         rows.collect()
     }
 
+    /// 分页读取对话消息：返回 `before_message_id`（不含）之前最近的 `limit` 条消息（按时间正序）及其附件，
+    /// 第二个返回值表示是否还有更早的消息；`before_message_id` 为空时从最新一条开始。
+    /// limit 截断了生成组（含重新生成的各个版本）时，页面会向前扩展到这些组最早的一条消息
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
+    pub fn list_page_by_conversation_id(
+        &self,
+        conversation_id: i64,
+        before_message_id: Option<i64>,
+        limit: usize,
+    ) -> Result<(Vec<(Message, Option<MessageAttachment>)>, bool)> {
+        let mut id_list =
+            self.list_page_ids(conversation_id, before_message_id, None, Some(limit + 1))?;
+        let mut has_more = id_list.len() > limit;
+        id_list.truncate(limit);
+        if id_list.is_empty() {
+            return Ok((Vec::new(), has_more));
+        }
+
+        // 新加入的消息可能带来新的生成组，循环直到页面不再扩展
+        while has_more {
+            let Some(group_start_id) =
+                self.earliest_generation_group_message(conversation_id, &id_list)?
+            else {
+                break;
+            };
+            let extended =
+                self.list_page_ids(conversation_id, before_message_id, Some(group_start_id), None)?;
+            if extended.len() <= id_list.len() {
+                break;
+            }
+            id_list = extended;
+            has_more = !self
+                .list_page_ids(conversation_id, Some(group_start_id), None, Some(1))?
+                .is_empty();
+        }
+
+        let placeholders = vec!["?"; id_list.len()].join(",");
+        let query = format!(
+            "SELECT {}
+             FROM message
+             LEFT JOIN message_attachment ma ON message.id = ma.message_id
+             WHERE message.id IN ({})
+             ORDER BY message.created_time ASC",
+            MESSAGE_WITH_ATTACHMENT_COLUMNS, placeholders
+        );
+        let mut stmt = self.conn.prepare(&query)?;
+        let rows =
+            stmt.query_map(rusqlite::params_from_iter(&id_list), message_with_attachment_from_row)?;
+        Ok((rows.collect::<Result<Vec<_>>>()?, has_more))
+    }
+
+    /// 按时间倒序列出 `before_message_id`（不含）之前、`from_message_id`（含）之后的消息 id
+    fn list_page_ids(
+        &self,
+        conversation_id: i64,
+        before_message_id: Option<i64>,
+        from_message_id: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM message
+             WHERE conversation_id = ?1
+               AND (?2 IS NULL
+                    OR created_time < (SELECT created_time FROM message WHERE id = ?2)
+                    OR (created_time = (SELECT created_time FROM message WHERE id = ?2) AND id < ?2))
+               AND (?3 IS NULL
+                    OR created_time > (SELECT created_time FROM message WHERE id = ?3)
+                    OR (created_time = (SELECT created_time FROM message WHERE id = ?3) AND id >= ?3))
+             ORDER BY created_time DESC, id DESC
+             LIMIT ?4",
+        )?;
+        let limit = limit.map(|limit| limit as i64).unwrap_or(-1);
+        let id_list = stmt
+            .query_map(
+                rusqlite::params![conversation_id, before_message_id, from_message_id, limit],
+                |row| row.get::<_, i64>(0),
+            )?
+            .collect::<Result<Vec<i64>>>()?;
+        Ok(id_list)
+    }
+
+    /// 找出给定消息所属生成组中最早的一条消息；沿 parent_group_id 把重新生成的各个版本视为同一组
+    fn earliest_generation_group_message(
+        &self,
+        conversation_id: i64,
+        id_list: &[i64],
+    ) -> Result<Option<i64>> {
+        let placeholders = vec!["?"; id_list.len()].join(",");
+        let query = format!(
+            "SELECT generation_group_id, parent_group_id FROM message WHERE id IN ({})",
+            placeholders
+        );
+        let mut stmt = self.conn.prepare(&query)?;
+        let mut group_ids: HashSet<String> = HashSet::new();
+        for row in stmt.query_map(rusqlite::params_from_iter(id_list), |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?))
+        })? {
+            let (generation_group_id, parent_group_id) = row?;
+            group_ids.extend(generation_group_id);
+            group_ids.extend(parent_group_id);
+        }
+        if group_ids.is_empty() {
+            return Ok(None);
+        }
+
+        // 版本之间通过 parent_group_id 相连，展开到不再有新的组为止
+        loop {
+            let placeholders = vec!["?"; group_ids.len()].join(",");
+            let query = format!(
+                "SELECT generation_group_id, parent_group_id FROM message
+                 WHERE conversation_id = ? AND (generation_group_id IN ({0}) OR parent_group_id IN ({0}))",
+                placeholders
+            );
+            let mut params: Vec<rusqlite::types::Value> = vec![conversation_id.into()];
+            for _ in 0..2 {
+                params.extend(group_ids.iter().cloned().map(rusqlite::types::Value::from));
+            }
+            let mut stmt = self.conn.prepare(&query)?;
+            let mut related: HashSet<String> = HashSet::new();
+            for row in stmt.query_map(rusqlite::params_from_iter(params), |row| {
+                Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?))
+            })? {
+                let (generation_group_id, parent_group_id) = row?;
+                related.extend(generation_group_id);
+                related.extend(parent_group_id);
+            }
+            let count = group_ids.len();
+            group_ids.extend(related);
+            if group_ids.len() == count {
+                break;
+            }
+        }
+
+        let placeholders = vec!["?"; group_ids.len()].join(",");
+        let query = format!(
+            "SELECT id FROM message
+             WHERE conversation_id = ? AND generation_group_id IN ({})
+             ORDER BY created_time ASC, id ASC
+             LIMIT 1",
+            placeholders
+        );
+        let mut params: Vec<rusqlite::types::Value> = vec![conversation_id.into()];
+        params.extend(group_ids.into_iter().map(rusqlite::types::Value::from));
+        self.conn
+            .query_row(&query, rusqlite::params_from_iter(params), |row| row.get::<_, i64>(0))
+            .optional()
+    }
+
     #[instrument(level = "debug", skip(self), fields(id = id))]
     pub fn update_finish_time(&self, id: i64) -> Result<()> {
         // Avoid SQLite CURRENT_TIMESTAMP (second precision) which can be earlier than millisecond
@@ -332,6 +699,90 @@ impl MessageRepository {
         Ok(())
     }
 
+    /// 用户编辑消息内容：先把当前内容压入撤销栈（超出上限时丢弃最旧的记录），并清空重做栈
+    #[instrument(level = "debug", skip(self, content), fields(id = id, content_len = content.len()))]
+    pub fn update_content_with_history(&self, id: i64, content: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let current: Option<(i64, String)> = tx
+            .query_row("SELECT conversation_id, content FROM message WHERE id = ?1", [id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()?;
+        let Some((conversation_id, current_content)) = current else {
+            return Ok(());
+        };
+        if current_content == content {
+            return Ok(());
+        }
+
+        tx.execute(
+            "INSERT INTO message_edit_history (message_id, conversation_id, stack, content)
+             VALUES (?1, ?2, 'undo', ?3)",
+            params![id, conversation_id, current_content],
+        )?;
+        tx.execute(
+            "DELETE FROM message_edit_history WHERE message_id = ?1 AND stack = 'redo'",
+            [id],
+        )?;
+        tx.execute(
+            "DELETE FROM message_edit_history WHERE message_id = ?1 AND stack = 'undo' AND id NOT IN (
+                SELECT id FROM message_edit_history WHERE message_id = ?1 AND stack = 'undo'
+                ORDER BY id DESC LIMIT ?2
+            )",
+            params![id, MAX_MESSAGE_EDIT_HISTORY as i64],
+        )?;
+        tx.execute("UPDATE message SET content = ?1 WHERE id = ?2", (content, id))?;
+        tx.commit()
+    }
+
+    /// 撤销最近一次编辑，返回恢复后的内容；没有可撤销的编辑时返回 None
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn undo_edit(&self, id: i64) -> Result<Option<String>> {
+        self.pop_edit_history(id, "undo", "redo")
+    }
+
+    /// 重做最近一次撤销的编辑，返回恢复后的内容；没有可重做的编辑时返回 None
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn redo_edit(&self, id: i64) -> Result<Option<String>> {
+        self.pop_edit_history(id, "redo", "undo")
+    }
+
+    /// 从 `from` 栈弹出一个版本作为消息内容，当前内容压入 `to` 栈
+    fn pop_edit_history(&self, id: i64, from: &str, to: &str) -> Result<Option<String>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let entry: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT id, content FROM message_edit_history
+                 WHERE message_id = ?1 AND stack = ?2 ORDER BY id DESC LIMIT 1",
+                params![id, from],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((entry_id, restored_content)) = entry else {
+            return Ok(None);
+        };
+
+        tx.execute(
+            "INSERT INTO message_edit_history (message_id, conversation_id, stack, content)
+             SELECT id, conversation_id, ?2, content FROM message WHERE id = ?1",
+            params![id, to],
+        )?;
+        tx.execute("DELETE FROM message_edit_history WHERE id = ?1", [entry_id])?;
+        tx.execute("UPDATE message SET content = ?1 WHERE id = ?2", (&restored_content, id))?;
+        tx.commit()?;
+        Ok(Some(restored_content))
+    }
+
+    /// 清空对话中所有消息的编辑历史（删除对话时调用）
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
+    pub fn clear_edit_history(&self, conversation_id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM message_edit_history WHERE conversation_id = ?1",
+            [conversation_id],
+        )?;
+        Ok(())
+    }
+
     /// 更新对话中所有正在进行的消息的 finish_time（用于取消操作）
     /// 只更新 start_time IS NOT NULL 且 finish_time IS NULL 的消息
     #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
@@ -343,55 +794,165 @@ impl MessageRepository {
         )?;
         Ok(updated)
     }
+
+    /// 在消息内容中全文搜索，按时间倒序返回命中的消息与高亮摘要。
+    /// 搜索词过短无法使用 trigram 索引时回退到 LIKE 匹配
+    #[instrument(level = "debug", skip(self), fields(query = query, limit = limit, offset = offset))]
+    pub fn search_content(
+        &self,
+        query: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<MessageSearchHit>> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if let Some(fts_query) = build_message_fts_query(&terms) {
+            let mut stmt = self.conn.prepare(
+                "SELECT m.id, m.conversation_id, c.name, m.message_type, m.created_time,
+                    snippet(message_fts, 0, ?4, ?5, '...', 32)
+                 FROM message_fts
+                 JOIN message m ON m.id = message_fts.rowid
+                 JOIN conversation c ON c.id = m.conversation_id
+                 WHERE message_fts MATCH ?1
+                 ORDER BY m.created_time DESC
+                 LIMIT ?2 OFFSET ?3",
+            )?;
+            let rows = stmt.query_map(
+                params![fts_query, limit, offset, FTS_HIGHLIGHT_START, FTS_HIGHLIGHT_END],
+                |row| {
+                    let snippet: String = row.get(5)?;
+                    Ok(MessageSearchHit {
+                        message_id: row.get(0)?,
+                        conversation_id: row.get(1)?,
+                        conversation_name: row.get(2)?,
+                        message_type: row.get(3)?,
+                        created_time: get_required_datetime_from_row(row, 4, "created_time")?,
+                        snippet: escape_html(&snippet)
+                            .replace(FTS_HIGHLIGHT_START, SEARCH_HIGHLIGHT_START)
+                            .replace(FTS_HIGHLIGHT_END, SEARCH_HIGHLIGHT_END),
+                    })
+                },
+            )?;
+            return rows.collect();
+        }
+
+        let conditions =
+            vec!["m.content LIKE ? COLLATE NOCASE ESCAPE '\\'"; terms.len()].join(" AND ");
+        let sql = format!(
+            "SELECT m.id, m.conversation_id, c.name, m.message_type, m.created_time, m.content
+             FROM message m
+             JOIN conversation c ON c.id = m.conversation_id
+             WHERE {}
+             ORDER BY m.created_time DESC
+             LIMIT {} OFFSET {}",
+            conditions, limit, offset
+        );
+        let patterns: Vec<String> =
+            terms.iter().map(|term| format!("%{}%", escape_like_pattern(term))).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(patterns.iter()), |row| {
+            let content: String = row.get(5)?;
+            Ok(MessageSearchHit {
+                message_id: row.get(0)?,
+                conversation_id: row.get(1)?,
+                conversation_name: row.get(2)?,
+                message_type: row.get(3)?,
+                created_time: get_required_datetime_from_row(row, 4, "created_time")?,
+                snippet: highlight_snippet(&content, &terms, 40),
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 从全文索引中移除对话的全部消息（删除对话时调用，对话删除后消息行仍会保留）
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
+    pub fn remove_conversation_from_search_index(&self, conversation_id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM message_fts WHERE rowid IN (SELECT id FROM message WHERE conversation_id = ?1)",
+            [conversation_id],
+        )?;
+        Ok(())
+    }
+
+    /// 置顶消息：置顶的消息在上下文截断时始终保留
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn pin(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO message_pin (message_id, conversation_id)
+             SELECT id, conversation_id FROM message WHERE id = ?1",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    /// 取消消息置顶
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn unpin(&self, id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM message_pin WHERE message_id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// 获取对话中所有置顶消息的 ID
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
+    pub fn list_pinned_ids(&self, conversation_id: i64) -> Result<HashSet<i64>> {
+        let mut stmt =
+            self.conn.prepare("SELECT message_id FROM message_pin WHERE conversation_id = ?1")?;
+        let rows = stmt.query_map([conversation_id], |row| row.get::<_, i64>(0))?;
+        rows.collect()
+    }
+
+    /// 记录消息的渲染模式（plaintext / code 等非默认模式）
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn set_render_mode(&self, id: i64, render_mode: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO message_render_mode (message_id, conversation_id, render_mode)
+             SELECT id, conversation_id, ?2 FROM message WHERE id = ?1",
+            rusqlite::params![id, render_mode],
+        )?;
+        Ok(())
+    }
+
+    /// 标记部分回复：流式生成中途出错时已输出的内容被保留，记录中断原因
+    #[instrument(level = "debug", skip(self, error), fields(id = id))]
+    pub fn mark_partial(&self, id: i64, error: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO message_partial (message_id, conversation_id, error)
+             SELECT id, conversation_id, ?2 FROM message WHERE id = ?1",
+            rusqlite::params![id, error],
+        )?;
+        Ok(())
+    }
+
+    /// 获取对话中所有部分回复的 ID
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
+    pub fn list_partial_ids(&self, conversation_id: i64) -> Result<HashSet<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT message_id FROM message_partial WHERE conversation_id = ?1")?;
+        let rows = stmt.query_map([conversation_id], |row| row.get::<_, i64>(0))?;
+        rows.collect()
+    }
+
+    /// 获取对话中所有记录了渲染模式的消息，未记录的消息按 markdown 渲染
+    #[instrument(level = "debug", skip(self), fields(conversation_id = conversation_id))]
+    pub fn list_render_modes(&self, conversation_id: i64) -> Result<HashMap<i64, String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT message_id, render_mode FROM message_render_mode WHERE conversation_id = ?1",
+        )?;
+        let rows = stmt.query_map([conversation_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.collect()
+    }
 }
 
 impl Repository<Message> for MessageRepository {
     #[instrument(level = "debug", skip(self, message), fields(conversation_id = message.conversation_id, message_type = message.message_type))]
     fn create(&self, message: &Message) -> Result<Message> {
-        // rusqlite Params trait only supports up to 16 parameters, use named params for 17+ fields
-        self.conn.execute(
-            "INSERT INTO message (parent_id, conversation_id, message_type, content, llm_model_id, llm_model_name, created_time, start_time, finish_time, token_count, input_token_count, output_token_count, generation_group_id, parent_group_id, tool_calls_json, first_token_time, ttft_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
-            rusqlite::params![
-                &message.parent_id,
-                &message.conversation_id,
-                &message.message_type,
-                &message.content,
-                &message.llm_model_id,
-                &message.llm_model_name,
-                &message.created_time,
-                &message.start_time,
-                &message.finish_time,
-                &message.token_count,
-                &message.input_token_count,
-                &message.output_token_count,
-                &message.generation_group_id,
-                &message.parent_group_id,
-                &message.tool_calls_json,
-                &message.first_token_time,
-                &message.ttft_ms,
-            ],
-        )?;
-        let id = self.conn.last_insert_rowid();
-        Ok(Message {
-            id,
-            parent_id: message.parent_id,
-            conversation_id: message.conversation_id,
-            message_type: message.message_type.clone(),
-            content: message.content.clone(),
-            llm_model_id: message.llm_model_id,
-            llm_model_name: message.llm_model_name.clone(),
-            created_time: message.created_time,
-            start_time: message.start_time,
-            finish_time: message.finish_time,
-            token_count: message.token_count,
-            input_token_count: message.input_token_count,
-            output_token_count: message.output_token_count,
-            generation_group_id: message.generation_group_id.clone(),
-            parent_group_id: message.parent_group_id.clone(),
-            tool_calls_json: message.tool_calls_json.clone(),
-            first_token_time: message.first_token_time,
-            ttft_ms: message.ttft_ms,
-        })
+        insert_message(&self.conn, message)
     }
 
     #[instrument(level = "debug", skip(self), fields(id = id))]
@@ -509,24 +1070,51 @@ impl MessageAttachmentRepository {
     }
 }
 
+pub const ATTACHMENT_OCR_STATUS_DONE: &str = "done";
+pub const ATTACHMENT_OCR_STATUS_FAILED: &str = "failed";
+
+impl MessageAttachmentRepository {
+    /// 记录图片附件的 OCR 结果，失败时保存错误信息，重复识别会覆盖旧结果
+    #[instrument(level = "debug", skip(self, ocr_text, error), fields(attachment_id = attachment_id))]
+    pub fn save_ocr_result(
+        &self,
+        attachment_id: i64,
+        status: &str,
+        ocr_text: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO attachment_ocr (attachment_id, status, ocr_text, error, updated_time)
+             VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)",
+            rusqlite::params![attachment_id, status, ocr_text, error],
+        )?;
+        Ok(())
+    }
+
+    /// 获取识别成功且文字不为空的附件 OCR 文本
+    #[instrument(level = "debug", skip(self, id_list), fields(id_count = id_list.len()))]
+    pub fn list_ocr_texts(&self, id_list: &[i64]) -> Result<HashMap<i64, String>> {
+        if id_list.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders = vec!["?"; id_list.len()].join(",");
+        let query = format!(
+            "SELECT attachment_id, ocr_text FROM attachment_ocr
+             WHERE status = '{}' AND ocr_text IS NOT NULL AND ocr_text != '' AND attachment_id IN ({})",
+            ATTACHMENT_OCR_STATUS_DONE, placeholders
+        );
+        let mut stmt = self.conn.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(id_list), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.collect()
+    }
+}
+
 impl Repository<MessageAttachment> for MessageAttachmentRepository {
     #[instrument(level = "debug", skip(self, attachment), fields(message_id = attachment.message_id, attachment_type = ?(attachment.attachment_type as i64)))]
     fn create(&self, attachment: &MessageAttachment) -> Result<MessageAttachment> {
-        self.conn.execute(
-            "INSERT INTO message_attachment (message_id, attachment_type, attachment_url, attachment_content, attachment_hash, use_vector, token_count) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            (&attachment.message_id, &(attachment.attachment_type as i64), &attachment.attachment_url, &attachment.attachment_content, &attachment.attachment_hash, &attachment.use_vector, &attachment.token_count),
-        )?;
-        let id = self.conn.last_insert_rowid();
-        Ok(MessageAttachment {
-            id,
-            message_id: attachment.message_id,
-            attachment_type: attachment.attachment_type,
-            attachment_url: attachment.attachment_url.clone(),
-            attachment_content: attachment.attachment_content.clone(),
-            attachment_hash: None,
-            use_vector: attachment.use_vector,
-            token_count: attachment.token_count,
-        })
+        insert_attachment(&self.conn, attachment)
     }
 
     #[instrument(level = "debug", skip(self), fields(id = id))]
@@ -569,6 +1157,22 @@ pub struct ConversationDatabase {
     db_path: PathBuf,
 }
 
+pub const ACP_SESSION_STATUS_ACTIVE: &str = "active";
+pub const ACP_SESSION_STATUS_STALE: &str = "stale";
+
+/// 持久化的 ACP 会话，用于应用重启后恢复
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcpSessionRecord {
+    pub conversation_id: i64,
+    pub session_id: String,
+    pub working_directory: Option<String>,
+    pub cli_command: Option<String>,
+    /// active 可尝试 session/load 恢复；stale 表示 Agent 进程已退出，需要新建会话
+    pub status: String,
+    /// 用户选择"始终允许"的工具授权
+    pub permission_grants: Vec<String>,
+}
+
 impl ConversationDatabase {
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "conversation.db");
@@ -576,9 +1180,15 @@ impl ConversationDatabase {
         Ok(ConversationDatabase { db_path: db_path.unwrap() })
     }
 
+    /// 直接指定数据库文件路径，供测试在临时目录中使用
+    #[cfg(test)]
+    pub fn from_path(db_path: PathBuf) -> Self {
+        ConversationDatabase { db_path }
+    }
+
     #[instrument(level = "debug", skip(self))]
     pub fn get_connection(&self) -> rusqlite::Result<Connection> {
-        let conn = Connection::open(&self.db_path)?;
+        let conn = open_connection(&self.db_path)?;
         // 性能优化：为所有连接设置更合适的 PRAGMA
         // - WAL 能改善读写并发性能
         // - synchronous=NORMAL 在保证安全的同时提升速度
@@ -590,6 +1200,12 @@ impl ConversationDatabase {
         Ok(conn)
     }
 
+    /// 将 WAL 中的写入合并回主库文件（应用退出时调用）
+    #[instrument(level = "debug", skip(self), err)]
+    pub fn checkpoint(&self) -> rusqlite::Result<()> {
+        self.get_connection()?.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+    }
+
     #[instrument(level = "debug", skip(self), err)]
     pub fn conversation_repo(&self) -> Result<ConversationRepository, AppError> {
         let conn = self.get_connection().map_err(AppError::from)?;
@@ -660,6 +1276,27 @@ impl ConversationDatabase {
             [],
         )?;
 
+        // ACP 会话恢复所需的元数据
+        let mut stmt = conn.prepare("PRAGMA table_info(acp_session)")?;
+        let acp_session_columns: Vec<String> =
+            stmt.query_map([], |row| row.get::<_, String>(1))?.collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+        if !acp_session_columns.contains(&"working_directory".to_string()) {
+            conn.execute("ALTER TABLE acp_session ADD COLUMN working_directory TEXT", [])?;
+        }
+        if !acp_session_columns.contains(&"cli_command".to_string()) {
+            conn.execute("ALTER TABLE acp_session ADD COLUMN cli_command TEXT", [])?;
+        }
+        if !acp_session_columns.contains(&"status".to_string()) {
+            conn.execute(
+                "ALTER TABLE acp_session ADD COLUMN status TEXT NOT NULL DEFAULT 'active'",
+                [],
+            )?;
+        }
+        if !acp_session_columns.contains(&"permission_grants".to_string()) {
+            conn.execute("ALTER TABLE acp_session ADD COLUMN permission_grants TEXT", [])?;
+        }
+
         // 添加迁移逻辑：如果parent_group_id、tool_calls_json、input_token_count或output_token_count列不存在，则添加它们
         let mut stmt = conn.prepare("PRAGMA table_info(message)")?;
         let column_info: Vec<String> = stmt
@@ -692,6 +1329,72 @@ impl ConversationDatabase {
             conn.execute("ALTER TABLE message ADD COLUMN ttft_ms INTEGER", [])?;
         }
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS message_pin (
+                message_id      INTEGER PRIMARY KEY,
+                conversation_id INTEGER NOT NULL,
+                created_time    DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (message_id) REFERENCES message(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_message_pin_conversation_id ON message_pin(conversation_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS message_render_mode (
+                message_id      INTEGER PRIMARY KEY,
+                conversation_id INTEGER NOT NULL,
+                render_mode     TEXT NOT NULL,
+                FOREIGN KEY (message_id) REFERENCES message(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_message_render_mode_conversation_id ON message_render_mode(conversation_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS message_partial (
+                message_id      INTEGER PRIMARY KEY,
+                conversation_id INTEGER NOT NULL,
+                error           TEXT,
+                created_time    DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (message_id) REFERENCES message(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_message_partial_conversation_id ON message_partial(conversation_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS message_edit_history (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                message_id      INTEGER NOT NULL,
+                conversation_id INTEGER NOT NULL,
+                stack           TEXT NOT NULL,
+                content         TEXT NOT NULL,
+                created_time    DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (message_id) REFERENCES message(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_message_edit_history_message_id ON message_edit_history(message_id, stack)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_message_edit_history_conversation_id ON message_edit_history(conversation_id)",
+            [],
+        )?;
+
+        create_message_search_index(&conn)?;
+
         conn.execute(
             "CREATE TABLE IF NOT EXISTS message_attachment (
                 id                 INTEGER
@@ -707,6 +1410,18 @@ impl ConversationDatabase {
             [],
         )?;
 
+        // 图片附件的 OCR 结果，供不支持图片的模型读取截图中的文字
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS attachment_ocr (
+                attachment_id INTEGER PRIMARY KEY,
+                status        TEXT NOT NULL,
+                ocr_text      TEXT,
+                error         TEXT,
+                updated_time  DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
         // 关键索引：显著提升查询性能
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_message_conversation_id ON message(conversation_id)",
@@ -745,6 +1460,30 @@ impl ConversationDatabase {
             [],
         )?;
 
+        // 创建对话标签表，删除标签或对话时只级联删除关联关系
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS conversation_tag (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS conversation_tag_link (
+                conversation_id INTEGER NOT NULL,
+                tag_id INTEGER NOT NULL,
+                PRIMARY KEY (conversation_id, tag_id),
+                FOREIGN KEY (conversation_id) REFERENCES conversation(id) ON DELETE CASCADE,
+                FOREIGN KEY (tag_id) REFERENCES conversation_tag(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conversation_tag_link_tag_id ON conversation_tag_link(tag_id)",
+            [],
+        )?;
+
         // 创建对话Todo表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS conversation_todo (
@@ -769,32 +1508,99 @@ impl ConversationDatabase {
     }
 
     #[instrument(level = "debug", skip(self), err)]
-    pub fn get_acp_session_id(&self, conversation_id: i64) -> Result<Option<String>, AppError> {
+    pub fn get_acp_session(
+        &self,
+        conversation_id: i64,
+    ) -> Result<Option<AcpSessionRecord>, AppError> {
         let conn = self.get_connection().map_err(AppError::from)?;
-        let session_id = conn
+        let record = conn
             .query_row(
-                "SELECT session_id FROM acp_session WHERE conversation_id = ?1",
+                "SELECT conversation_id, session_id, working_directory, cli_command, status, permission_grants
+                 FROM acp_session WHERE conversation_id = ?1",
                 params![conversation_id],
-                |row| row.get(0),
+                |row| {
+                    let grants: Option<String> = row.get(5)?;
+                    Ok(AcpSessionRecord {
+                        conversation_id: row.get(0)?,
+                        session_id: row.get(1)?,
+                        working_directory: row.get(2)?,
+                        cli_command: row.get(3)?,
+                        status: row.get(4)?,
+                        permission_grants: grants
+                            .and_then(|g| serde_json::from_str(&g).ok())
+                            .unwrap_or_default(),
+                    })
+                },
             )
             .optional()
             .map_err(AppError::from)?;
-        Ok(session_id)
+        Ok(record)
     }
 
+    /// 保存会话元数据并标记为 active，已有的权限授权保留
     #[instrument(level = "debug", skip(self), err)]
-    pub fn upsert_acp_session_id(
+    pub fn upsert_acp_session(
         &self,
         conversation_id: i64,
         session_id: &str,
+        working_directory: &str,
+        cli_command: &str,
     ) -> Result<(), AppError> {
         let conn = self.get_connection().map_err(AppError::from)?;
         conn.execute(
-            "INSERT INTO acp_session (conversation_id, session_id, updated_time)
-             VALUES (?1, ?2, CURRENT_TIMESTAMP)
+            "INSERT INTO acp_session (conversation_id, session_id, working_directory, cli_command, status, updated_time)
+             VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)
              ON CONFLICT(conversation_id)
-             DO UPDATE SET session_id = excluded.session_id, updated_time = CURRENT_TIMESTAMP",
-            params![conversation_id, session_id],
+             DO UPDATE SET session_id = excluded.session_id,
+                           working_directory = excluded.working_directory,
+                           cli_command = excluded.cli_command,
+                           status = excluded.status,
+                           updated_time = CURRENT_TIMESTAMP",
+            params![
+                conversation_id,
+                session_id,
+                working_directory,
+                cli_command,
+                ACP_SESSION_STATUS_ACTIVE
+            ],
+        )
+        .map_err(AppError::from)?;
+        Ok(())
+    }
+
+    /// Agent 进程已不可用时标记会话失效，下次提问时新建会话并带上历史记录
+    #[instrument(level = "debug", skip(self), err)]
+    pub fn mark_acp_session_stale(&self, conversation_id: i64) -> Result<(), AppError> {
+        let conn = self.get_connection().map_err(AppError::from)?;
+        conn.execute(
+            "UPDATE acp_session SET status = ?1, updated_time = CURRENT_TIMESTAMP WHERE conversation_id = ?2",
+            params![ACP_SESSION_STATUS_STALE, conversation_id],
+        )
+        .map_err(AppError::from)?;
+        Ok(())
+    }
+
+    /// 记录用户选择"始终允许"的权限授权，会话恢复后继续生效
+    #[instrument(level = "debug", skip(self), err)]
+    pub fn add_acp_permission_grant(
+        &self,
+        conversation_id: i64,
+        grant: &str,
+    ) -> Result<(), AppError> {
+        let Some(mut record) = self.get_acp_session(conversation_id)? else {
+            return Ok(());
+        };
+        if record.permission_grants.iter().any(|g| g == grant) {
+            return Ok(());
+        }
+        record.permission_grants.push(grant.to_string());
+        let grants = serde_json::to_string(&record.permission_grants)
+            .map_err(|e| AppError::UnknownError(e.to_string()))?;
+
+        let conn = self.get_connection().map_err(AppError::from)?;
+        conn.execute(
+            "UPDATE acp_session SET permission_grants = ?1 WHERE conversation_id = ?2",
+            params![grants, conversation_id],
         )
         .map_err(AppError::from)?;
         Ok(())
@@ -816,7 +1622,7 @@ impl ConversationDatabase {
         &self,
         conversation_id: i64,
     ) -> rusqlite::Result<ConversationTokenStats> {
-        let conn = Connection::open(&self.db_path)?;
+        let conn = open_connection(&self.db_path)?;
 
         // 获取总token统计和按类型统计的消息数量
         let (
@@ -1078,7 +1884,7 @@ impl ConversationDatabase {
 
     /// 获取单个消息的token统计信息
     pub fn get_message_token_stats(&self, message_id: i64) -> rusqlite::Result<MessageTokenStats> {
-        let conn = Connection::open(&self.db_path)?;
+        let conn = open_connection(&self.db_path)?;
 
         conn.query_row(
             "SELECT
@@ -1166,6 +1972,7 @@ impl ConversationDatabase {
                     model_name: row.get(4).ok(),
                     ttft_ms,
                     tps,
+                    estimated_cost: None,
                 })
             },
         )
@@ -1173,6 +1980,34 @@ impl ConversationDatabase {
 
     // ============= Todo CRUD Methods =============
 
+    /// 汇总使用情况（常用助手、模型、活跃时段、平均对话长度），只读
+    pub fn get_usage_insights(
+        &self,
+        since: Option<DateTime<Utc>>,
+        utc_offset_minutes: i32,
+        limit: usize,
+    ) -> rusqlite::Result<UsageInsights> {
+        let conn = open_connection(&self.db_path)?;
+        query_usage_insights(&conn, since, utc_offset_minutes, limit)
+    }
+
+    /// 逐行读取用量报表，只读
+    pub fn for_each_usage_report_row<E, F>(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        group_by: UsageReportGroupBy,
+        utc_offset_minutes: i32,
+        on_row: F,
+    ) -> std::result::Result<usize, E>
+    where
+        E: From<rusqlite::Error>,
+        F: FnMut(UsageReportRow) -> std::result::Result<(), E>,
+    {
+        let conn = open_connection(&self.db_path)?;
+        for_each_usage_report_row(&conn, since, until, group_by, utc_offset_minutes, on_row)
+    }
+
     /// Get all todos for a conversation
     #[instrument(level = "debug", skip(self), err)]
     pub fn get_todos(&self, conversation_id: i64) -> Result<Vec<ConversationTodo>, AppError> {
@@ -1272,6 +2107,139 @@ impl ConversationDatabase {
     }
 }
 
+/// 创建消息全文索引（FTS5）及同步触发器，首次创建时为已有消息建立索引。
+/// 触发器覆盖所有写入路径（新建、流式更新、编辑、撤销重做与删除消息），无需在各处手动维护
+pub fn create_message_search_index(conn: &Connection) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'message_fts')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS message_fts USING fts5(content, tokenize = 'trigram');
+         CREATE TRIGGER IF NOT EXISTS message_fts_after_insert AFTER INSERT ON message BEGIN
+             INSERT INTO message_fts(rowid, content) VALUES (new.id, new.content);
+         END;
+         CREATE TRIGGER IF NOT EXISTS message_fts_after_update AFTER UPDATE OF content ON message BEGIN
+             DELETE FROM message_fts WHERE rowid = old.id;
+             INSERT INTO message_fts(rowid, content) VALUES (new.id, new.content);
+         END;
+         CREATE TRIGGER IF NOT EXISTS message_fts_after_delete AFTER DELETE ON message BEGIN
+             DELETE FROM message_fts WHERE rowid = old.id;
+         END;",
+    )?;
+
+    if !exists {
+        conn.execute(
+            "INSERT INTO message_fts(rowid, content)
+             SELECT m.id, m.content FROM message m JOIN conversation c ON c.id = m.conversation_id",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// 把搜索词转换为 FTS5 查询（各词按短语匹配、同时命中），有词过短时返回 None
+pub fn build_message_fts_query(terms: &[&str]) -> Option<String> {
+    if terms.is_empty()
+        || terms.iter().any(|term| term.chars().count() < MESSAGE_SEARCH_MIN_TERM_CHARS)
+    {
+        return None;
+    }
+    Some(
+        terms
+            .iter()
+            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// 转义 LIKE 模式中的通配符，配合 `ESCAPE '\'` 按字面匹配
+fn escape_like_pattern(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// 转义 HTML 特殊字符，摘要中只有高亮标记是 HTML
+fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 截取第一个命中词前后 `context_chars` 个字符作为摘要，并标记其中所有命中的词，内容经过 HTML 转义
+fn highlight_snippet(text: &str, terms: &[&str], context_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text.to_lowercase().chars().collect();
+    // 少数字符小写后长度会变化，此时不做高亮，只截取开头
+    let lowered_terms: Vec<Vec<char>> =
+        terms.iter().map(|term| term.to_lowercase().chars().collect()).collect();
+    let find_at = |pos: usize| {
+        lowered_terms
+            .iter()
+            .filter(|term| !term.is_empty() && lower[pos..].starts_with(term.as_slice()))
+            .map(|term| term.len())
+            .max()
+    };
+
+    let first = if lower.len() == chars.len() {
+        (0..chars.len()).find(|&pos| find_at(pos).is_some())
+    } else {
+        None
+    };
+    let start = first.map(|pos| pos.saturating_sub(context_chars)).unwrap_or(0);
+    let end =
+        first.map(|pos| pos + context_chars * 2).unwrap_or(context_chars * 2).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push_str("...");
+    }
+    let mut pos = start;
+    while pos < end {
+        match if first.is_some() { find_at(pos) } else { None } {
+            Some(len) => {
+                let match_end = (pos + len).min(chars.len());
+                snippet.push_str(SEARCH_HIGHLIGHT_START);
+                snippet.push_str(&escape_html(&chars[pos..match_end].iter().collect::<String>()));
+                snippet.push_str(SEARCH_HIGHLIGHT_END);
+                pos = match_end;
+            }
+            None => {
+                snippet.push_str(&escape_html(chars[pos].encode_utf8(&mut [0; 4])));
+                pos += 1;
+            }
+        }
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// 消息全文搜索结果，snippet 中命中的部分用 SEARCH_HIGHLIGHT_START/END 包裹，其余内容已做 HTML 转义
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageSearchHit {
+    pub message_id: i64,
+    pub conversation_id: i64,
+    pub conversation_name: String,
+    pub message_type: String,
+    #[serde(serialize_with = "serialize_datetime_millis")]
+    pub created_time: DateTime<Utc>,
+    pub snippet: String,
+}
+
+/// 对话标签及使用该标签的对话数量
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConversationTag {
+    pub id: i64,
+    pub name: String,
+    pub conversation_count: i64,
+}
+
 /// Todo item stored in database
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConversationTodo {
@@ -1295,6 +2263,236 @@ pub struct ConversationTodoInput {
     pub active_form: String,
 }
 
+/// 助手使用排行项
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AssistantUsage {
+    pub assistant_id: Option<i64>,
+    /// 助手名称由 assistant 库补全，助手已删除时为空
+    pub assistant_name: Option<String>,
+    pub conversation_count: i64,
+    pub message_count: i64,
+}
+
+/// 模型使用排行项（按 AI 回复数统计）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ModelUsage {
+    pub model_name: String,
+    pub response_count: i64,
+    pub total_tokens: i64,
+}
+
+/// 某个小时内发送的用户消息数
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HourlyUsage {
+    pub hour: u32,
+    pub message_count: i64,
+}
+
+/// 使用情况汇总
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsageInsights {
+    /// 统计窗口起点，None 表示全部历史
+    pub since: Option<DateTime<Utc>>,
+    pub conversation_count: i64,
+    pub message_count: i64,
+    /// 窗口内活跃对话的平均消息数
+    pub avg_messages_per_conversation: f64,
+    pub top_assistants: Vec<AssistantUsage>,
+    pub top_models: Vec<ModelUsage>,
+    /// 按本地时间 0-23 点的用户消息分布（只包含有消息的小时）
+    pub hourly_activity: Vec<HourlyUsage>,
+    pub busiest_hour: Option<u32>,
+}
+
+/// 使用统计的消息口径：起点（?1）之后的消息及其所属助手，使用汇总与用量报表共用
+const USAGE_WINDOW_CTE: &str = "WITH windowed AS (
+        SELECT m.conversation_id, m.message_type, m.llm_model_id, m.llm_model_name,
+               m.token_count, m.input_token_count, m.output_token_count,
+               m.created_time, c.assistant_id
+        FROM message m
+        JOIN conversation c ON c.id = m.conversation_id
+        WHERE ?1 IS NULL OR julianday(m.created_time) >= julianday(?1)
+    )";
+
+/// 以窗口内的消息为口径聚合使用情况，每个维度一次 SQL 聚合
+///
+/// `utc_offset_minutes` 用于把消息时间换算到本地时区再统计活跃时段。
+pub fn query_usage_insights(
+    conn: &Connection,
+    since: Option<DateTime<Utc>>,
+    utc_offset_minutes: i32,
+    limit: usize,
+) -> rusqlite::Result<UsageInsights> {
+    let since_text = since.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let windowed = USAGE_WINDOW_CTE;
+    let limit = limit as i64;
+
+    let (conversation_count, message_count): (i64, i64) = conn.query_row(
+        &format!("{} SELECT COUNT(DISTINCT conversation_id), COUNT(*) FROM windowed", windowed),
+        params![since_text],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{} SELECT assistant_id, COUNT(DISTINCT conversation_id) AS conversations, COUNT(*) AS messages
+            FROM windowed
+            GROUP BY assistant_id
+            ORDER BY conversations DESC, messages DESC
+            LIMIT ?2",
+        windowed
+    ))?;
+    let top_assistants = stmt
+        .query_map(params![since_text, limit], |row| {
+            Ok(AssistantUsage {
+                assistant_id: row.get(0)?,
+                assistant_name: None,
+                conversation_count: row.get(1)?,
+                message_count: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{} SELECT llm_model_name, COUNT(*) AS responses, COALESCE(SUM(token_count), 0)
+            FROM windowed
+            WHERE message_type = 'response' AND llm_model_name IS NOT NULL AND llm_model_name != ''
+            GROUP BY llm_model_name
+            ORDER BY responses DESC
+            LIMIT ?2",
+        windowed
+    ))?;
+    let top_models = stmt
+        .query_map(params![since_text, limit], |row| {
+            Ok(ModelUsage {
+                model_name: row.get(0)?,
+                response_count: row.get(1)?,
+                total_tokens: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{} SELECT CAST(strftime('%H', created_time, ?2) AS INTEGER) AS hour, COUNT(*)
+            FROM windowed
+            WHERE message_type = 'user'
+            GROUP BY hour
+            ORDER BY hour",
+        windowed
+    ))?;
+    let offset_modifier = format!("{:+} minutes", utc_offset_minutes);
+    let hourly_activity = stmt
+        .query_map(params![since_text, offset_modifier], |row| {
+            Ok(HourlyUsage { hour: row.get(0)?, message_count: row.get(1)? })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // 并列时取较早的小时
+    let busiest_hour = hourly_activity
+        .iter()
+        .max_by(|a, b| a.message_count.cmp(&b.message_count).then(b.hour.cmp(&a.hour)))
+        .map(|h| h.hour);
+
+    let avg_messages_per_conversation =
+        if conversation_count > 0 { message_count as f64 / conversation_count as f64 } else { 0.0 };
+
+    Ok(UsageInsights {
+        since,
+        conversation_count,
+        message_count,
+        avg_messages_per_conversation,
+        top_assistants,
+        top_models,
+        hourly_activity,
+        busiest_hour,
+    })
+}
+
+/// 用量报表的时间分组粒度
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageReportGroupBy {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl UsageReportGroupBy {
+    fn strftime_format(&self) -> &'static str {
+        match self {
+            UsageReportGroupBy::Day => "%Y-%m-%d",
+            UsageReportGroupBy::Week => "%Y-W%W",
+            UsageReportGroupBy::Month => "%Y-%m",
+        }
+    }
+}
+
+/// 用量报表的一行：某个时间段内某个助手使用某个模型的 token 汇总
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UsageReportRow {
+    /// 按本地时间分组后的时间段，如 2024-06-01、2024-W22、2024-06
+    pub period: String,
+    pub llm_model_id: Option<i64>,
+    pub model_name: String,
+    pub assistant_id: Option<i64>,
+    pub response_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+}
+
+/// 按时间段、模型、助手聚合 [since, until) 内的 token 用量，逐行交给 `on_row` 处理
+///
+/// 结果直接从游标读取，不会把整个范围的数据收集到内存中；返回处理的行数。
+pub fn for_each_usage_report_row<E, F>(
+    conn: &Connection,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    group_by: UsageReportGroupBy,
+    utc_offset_minutes: i32,
+    mut on_row: F,
+) -> std::result::Result<usize, E>
+where
+    E: From<rusqlite::Error>,
+    F: FnMut(UsageReportRow) -> std::result::Result<(), E>,
+{
+    let since_text = since.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let until_text = until.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let offset_modifier = format!("{:+} minutes", utc_offset_minutes);
+    let mut stmt = conn.prepare(&format!(
+        "{} SELECT strftime(?3, created_time, ?4) AS period, llm_model_id, llm_model_name,
+                assistant_id,
+                COUNT(CASE WHEN message_type = 'response' THEN 1 END),
+                COALESCE(SUM(input_token_count), 0),
+                COALESCE(SUM(output_token_count), 0),
+                COALESCE(SUM(token_count), 0)
+            FROM windowed
+            WHERE llm_model_name IS NOT NULL AND llm_model_name != ''
+              AND (?2 IS NULL OR julianday(created_time) < julianday(?2))
+            GROUP BY period, llm_model_id, llm_model_name, assistant_id
+            ORDER BY period, assistant_id, llm_model_name",
+        USAGE_WINDOW_CTE
+    ))?;
+    let mut rows =
+        stmt.query(params![since_text, until_text, group_by.strftime_format(), offset_modifier])?;
+
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        on_row(UsageReportRow {
+            period: row.get(0)?,
+            llm_model_id: row.get(1)?,
+            model_name: row.get(2)?,
+            assistant_id: row.get(3)?,
+            response_count: row.get(4)?,
+            input_tokens: row.get(5)?,
+            output_tokens: row.get(6)?,
+            total_tokens: row.get(7)?,
+        })?;
+        count += 1;
+    }
+    Ok(count)
+}
+
 /// 对话token统计信息
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConversationTokenStats {
@@ -1338,6 +2536,8 @@ pub struct MessageTokenStats {
     pub model_name: Option<String>,
     pub ttft_ms: Option<i64>, // Time to First Token (毫秒)
     pub tps: Option<f64>,     // Tokens Per Second
+    /// 按配置的模型单价估算的费用，未配置单价时为空
+    pub estimated_cost: Option<f64>,
 }
 
 /// 对话总结结构体
@@ -1352,6 +2552,23 @@ pub struct ConversationSummary {
     pub created_time: DateTime<Utc>,
 }
 
+/// 自动总结的触发条件，三个条件需同时满足；为 0 表示不限制该项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryTriggerCriteria {
+    /// 对话最后一条消息距今的最少空闲秒数
+    pub idle_seconds: i64,
+    /// 对话的最少消息数（只统计用户消息与回复）
+    pub min_messages: i64,
+    /// 对话的最少 token 数（按消息 token_count 累加）
+    pub min_tokens: i64,
+}
+
+impl Default for SummaryTriggerCriteria {
+    fn default() -> Self {
+        SummaryTriggerCriteria { idle_seconds: 600, min_messages: 0, min_tokens: 0 }
+    }
+}
+
 pub struct ConversationSummaryRepository {
     conn: Connection,
 }
@@ -1426,4 +2643,47 @@ impl ConversationSummaryRepository {
         )?;
         Ok(())
     }
+
+    /// 查询满足触发条件且尚未总结的对话，按 ID 倒序，跳过 `exclude_ids` 中正在总结的对话
+    #[instrument(level = "debug", skip(self))]
+    pub fn list_conversations_needing_summary(
+        &self,
+        criteria: &SummaryTriggerCriteria,
+        exclude_ids: &[i64],
+        limit: i64,
+    ) -> Result<Vec<i64>> {
+        let exclude_clause = if exclude_ids.is_empty() {
+            String::new()
+        } else {
+            let ids: Vec<String> = exclude_ids.iter().map(|id| id.to_string()).collect();
+            format!(" AND c.id NOT IN ({})", ids.join(","))
+        };
+        let query = format!(
+            "SELECT c.id
+             FROM conversation c
+             INNER JOIN message m ON m.conversation_id = c.id
+             LEFT JOIN conversation_summary cs ON cs.conversation_id = c.id
+             WHERE cs.id IS NULL{}
+             GROUP BY c.id
+             HAVING (?1 <= 0 OR MAX(m.created_time) < datetime('now', '-' || ?1 || ' seconds'))
+                AND COUNT(CASE WHEN m.message_type IN ('user', 'response') THEN 1 END) >= ?2
+                AND SUM(COALESCE(m.token_count, 0)) >= ?3
+             ORDER BY c.id DESC
+             LIMIT ?4",
+            exclude_clause
+        );
+        let mut stmt = self.conn.prepare(&query)?;
+        let ids = stmt
+            .query_map(
+                rusqlite::params![
+                    criteria.idle_seconds,
+                    criteria.min_messages,
+                    criteria.min_tokens,
+                    limit
+                ],
+                |row| row.get(0),
+            )?
+            .collect::<Result<Vec<i64>>>()?;
+        Ok(ids)
+    }
 }
@@ -0,0 +1,313 @@
+//! 本地数据库静态加密（SQLCipher）
+//!
+//! 加密状态记录在 db 目录下的 `encryption.json`，密钥来自系统钥匙串中保存的随机密钥，
+//! 或用户设置的口令（在设置界面输入后保存在系统钥匙串中，也可以启动时通过环境变量提供）。
+//! 启用后所有经 [`open_connection`] 打开的连接都会先设置密钥；启动时发现仍是明文的数据库文件
+//! 会一次性导出为加密库并原地替换，db 目录之外的数据库（如 artifact 数据库）在打开时迁移。
+
+use rusqlite::{params, Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tracing::{debug, info, instrument};
+
+pub const ENCRYPTION_STATE_FILE: &str = "encryption.json";
+/// 口令模式下启动时读取口令的环境变量，优先于钥匙串中保存的口令
+pub const PASSPHRASE_ENV: &str = "AIPP_DB_PASSPHRASE";
+#[cfg(feature = "sqlcipher")]
+const KEYRING_SERVICE: &str = "com.aipp.database";
+#[cfg(feature = "sqlcipher")]
+const KEYRING_USER: &str = "sqlcipher-key";
+#[cfg(feature = "sqlcipher")]
+const KEYRING_PASSPHRASE_USER: &str = "sqlcipher-passphrase";
+/// 明文 SQLite 文件的文件头，加密后的文件头是随机数据
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// 当前进程使用的 `PRAGMA key` 值，未启用加密时为空
+static DB_KEY: RwLock<Option<String>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// 随机生成并保存在系统钥匙串中的 256 位密钥
+    Keychain,
+    /// 用户设置的口令，由 SQLCipher 自行做密钥派生；启动时先读 `AIPP_DB_PASSPHRASE`，
+    /// 未设置时使用在设置界面输入并保存在系统钥匙串中的口令
+    Passphrase,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptionState {
+    pub enabled: bool,
+    pub key_source: KeySource,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseEncryptionStatus {
+    /// 当前构建是否包含 SQLCipher
+    pub supported: bool,
+    /// 是否已启用加密（可能需要重启后才生效）
+    pub enabled: bool,
+    /// 当前进程打开的连接是否已在使用密钥
+    pub active: bool,
+    pub key_source: Option<KeySource>,
+}
+
+pub fn is_supported() -> bool {
+    cfg!(feature = "sqlcipher")
+}
+
+/// 打开数据库连接，启用加密时设置密钥并立即校验
+pub fn open_connection<P: AsRef<Path>>(path: P) -> rusqlite::Result<Connection> {
+    match active_key() {
+        Some(key) => open_with_key(path.as_ref(), &key),
+        None => Connection::open(path),
+    }
+}
+
+fn active_key() -> Option<String> {
+    DB_KEY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 使用指定密钥打开数据库并立即校验密钥
+pub(crate) fn open_with_key(path: &Path, key: &str) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    apply_key(&conn, key)?;
+    Ok(conn)
+}
+
+/// 加密已启用时，把仍为明文的数据库加密；用于启动时不会扫描到的 db 目录之外的数据库
+pub fn encrypt_if_plaintext(path: &Path) -> Result<(), String> {
+    match active_key() {
+        Some(key) if is_plaintext_database(path) => {
+            info!(path = %path.display(), "Encrypting plaintext database");
+            encrypt_in_place(path, &key)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn apply_key(conn: &Connection, key: &str) -> rusqlite::Result<()> {
+    conn.pragma_update(None, "key", key)?;
+    // SQLCipher 直到第一次读取才校验密钥，这里立即读取，避免之后的操作报出含糊的 "file is not a database"
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())).map_err(|e| {
+        rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_NOTADB),
+            Some(format!("数据库密钥错误或缺失，无法解密本地数据库: {}", e)),
+        )
+    })
+}
+
+/// 启动时调用：读取加密状态与密钥，把仍为明文的数据库加密，然后让后续连接使用密钥
+///
+/// 必须在打开任何数据库连接之前调用
+#[instrument(level = "info", skip_all, err)]
+pub fn init(db_dir: &Path) -> Result<(), String> {
+    let Some(state) = load_state(db_dir)? else {
+        return Ok(());
+    };
+    if !state.enabled {
+        return Ok(());
+    }
+    if !is_supported() {
+        return Err(
+            "本地数据库已启用加密，但当前构建未包含 SQLCipher 支持（需要 sqlcipher feature）"
+                .to_string(),
+        );
+    }
+
+    let key = load_key(state.key_source)?;
+    for path in database_files(db_dir)? {
+        if is_plaintext_database(&path) {
+            info!(path = %path.display(), "Encrypting plaintext database");
+            encrypt_in_place(&path, &key)?;
+        }
+    }
+
+    *DB_KEY.write().unwrap_or_else(|e| e.into_inner()) = Some(key);
+    info!(key_source = ?state.key_source, "Database encryption active");
+    Ok(())
+}
+
+/// 启用加密：准备密钥并写入加密状态，现有数据库在下次启动时迁移
+///
+/// 口令模式下传入的口令保存到系统钥匙串，之后启动无需再设置环境变量
+pub fn enable(
+    db_dir: &Path,
+    key_source: KeySource,
+    passphrase: Option<&str>,
+) -> Result<EncryptionState, String> {
+    if !is_supported() {
+        return Err("当前构建未包含 SQLCipher 支持，无法启用数据库加密".to_string());
+    }
+    if load_state(db_dir)?.is_some_and(|state| state.enabled) {
+        return Err("数据库加密已启用".to_string());
+    }
+
+    match key_source {
+        KeySource::Keychain => store_keychain_secret()?,
+        KeySource::Passphrase => match passphrase.filter(|passphrase| !passphrase.is_empty()) {
+            Some(passphrase) => store_keychain_passphrase(passphrase)?,
+            // 未输入口令时要求本次启动已通过环境变量提供，避免启用后下次启动才发现缺少口令
+            None => {
+                load_key(KeySource::Passphrase)?;
+            }
+        },
+    }
+
+    let state = EncryptionState { enabled: true, key_source };
+    save_state(db_dir, &state)?;
+    Ok(state)
+}
+
+pub fn status(db_dir: &Path) -> Result<DatabaseEncryptionStatus, String> {
+    let state = load_state(db_dir)?.filter(|state| state.enabled);
+    Ok(DatabaseEncryptionStatus {
+        supported: is_supported(),
+        enabled: state.is_some(),
+        active: DB_KEY.read().unwrap_or_else(|e| e.into_inner()).is_some(),
+        key_source: state.map(|state| state.key_source),
+    })
+}
+
+pub fn load_state(db_dir: &Path) -> Result<Option<EncryptionState>, String> {
+    let path = db_dir.join(ENCRYPTION_STATE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("无法解析数据库加密状态文件 {}: {}", path.display(), e))
+}
+
+pub fn save_state(db_dir: &Path, state: &EncryptionState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(db_dir.join(ENCRYPTION_STATE_FILE), content).map_err(|e| e.to_string())
+}
+
+/// 判断文件是否为未加密的 SQLite 数据库；空文件或不存在时返回 false
+pub fn is_plaintext_database(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)).is_ok()
+        && &header == SQLITE_HEADER
+}
+
+fn database_files(db_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(db_dir).map_err(|e| e.to_string())?;
+    Ok(entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "db"))
+        .collect())
+}
+
+/// 用 `sqlcipher_export` 把明文库导出为加密库，再原地替换原文件
+pub(crate) fn encrypt_in_place(path: &Path, key: &str) -> Result<(), String> {
+    let encrypted_path = path.with_extension("db.encrypting");
+    let _ = fs::remove_file(&encrypted_path);
+    let context = |e: rusqlite::Error| format!("加密数据库 {} 失败: {}", path.display(), e);
+
+    {
+        let conn = Connection::open(path).map_err(context)?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())).map_err(context)?;
+        let user_version: i64 =
+            conn.pragma_query_value(None, "user_version", |row| row.get(0)).map_err(context)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            params![encrypted_path.to_string_lossy(), key],
+        )
+        .map_err(context)?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(())).map_err(context)?;
+        conn.pragma_update(Some(DatabaseName::Attached("encrypted")), "user_version", user_version)
+            .map_err(context)?;
+        conn.execute("DETACH DATABASE encrypted", []).map_err(context)?;
+    }
+
+    fs::rename(&encrypted_path, path).map_err(|e| e.to_string())?;
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = OsString::from(path.as_os_str());
+        sidecar.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(sidecar));
+    }
+    debug!(path = %path.display(), "Database encrypted");
+    Ok(())
+}
+
+fn load_key(key_source: KeySource) -> Result<String, String> {
+    match key_source {
+        KeySource::Keychain => load_keychain_secret().map(|secret| format!("x'{}'", secret)),
+        KeySource::Passphrase => std::env::var(PASSPHRASE_ENV)
+            .ok()
+            .filter(|passphrase| !passphrase.is_empty())
+            .or_else(|| load_keychain_passphrase().ok())
+            .ok_or_else(|| {
+                format!(
+                    "数据库已使用口令加密，但系统钥匙串中没有保存的口令，请通过环境变量 {} 提供口令",
+                    PASSPHRASE_ENV
+                )
+            }),
+    }
+}
+
+#[cfg(feature = "sqlcipher")]
+fn keychain_entry(user: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, user).map_err(|e| format!("无法访问系统钥匙串: {}", e))
+}
+
+#[cfg(feature = "sqlcipher")]
+fn load_keychain_passphrase() -> Result<String, String> {
+    keychain_entry(KEYRING_PASSPHRASE_USER)?
+        .get_password()
+        .map_err(|e| format!("无法从系统钥匙串读取数据库口令: {}", e))
+}
+
+#[cfg(feature = "sqlcipher")]
+fn store_keychain_passphrase(passphrase: &str) -> Result<(), String> {
+    keychain_entry(KEYRING_PASSPHRASE_USER)?
+        .set_password(passphrase)
+        .map_err(|e| format!("无法将数据库口令写入系统钥匙串: {}", e))
+}
+
+#[cfg(feature = "sqlcipher")]
+fn load_keychain_secret() -> Result<String, String> {
+    let secret = keychain_entry(KEYRING_USER)?
+        .get_password()
+        .map_err(|e| format!("无法从系统钥匙串读取数据库密钥: {}", e))?;
+    if secret.len() != 64 || !secret.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("系统钥匙串中的数据库密钥格式无效".to_string());
+    }
+    Ok(secret)
+}
+
+#[cfg(feature = "sqlcipher")]
+fn store_keychain_secret() -> Result<(), String> {
+    use rand::RngCore;
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    keychain_entry(KEYRING_USER)?
+        .set_password(&hex::encode(secret))
+        .map_err(|e| format!("无法将数据库密钥写入系统钥匙串: {}", e))
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn load_keychain_secret() -> Result<String, String> {
+    Err("当前构建未包含 SQLCipher 支持".to_string())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn store_keychain_secret() -> Result<(), String> {
+    Err("当前构建未包含 SQLCipher 支持".to_string())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn load_keychain_passphrase() -> Result<String, String> {
+    Err("当前构建未包含 SQLCipher 支持".to_string())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn store_keychain_passphrase(_passphrase: &str) -> Result<(), String> {
+    Err("当前构建未包含 SQLCipher 支持".to_string())
+}
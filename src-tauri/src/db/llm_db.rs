@@ -1,6 +1,8 @@
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument, warn};
 
+use super::encryption::open_connection;
 use super::get_db_path;
 
 #[derive(Debug)]
@@ -42,6 +44,34 @@ pub struct ModelDetail {
     pub configs: Vec<LLMProviderConfig>,
 }
 
+/// 环境配置（如本地/云端），定义启用哪些提供商以及默认助手使用的模型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LLMEnvironmentProfile {
+    pub id: i64,
+    pub name: String,
+    pub enabled_provider_ids: Vec<i64>,
+    pub default_provider_id: Option<i64>,
+    pub default_model_code: Option<String>,
+    pub is_active: bool,
+}
+
+/// 模型单价（每 1K token），按模型编码匹配消息中记录的模型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LLMModelPrice {
+    pub model_code: String,
+    pub input_price_per_1k: f64,
+    pub output_price_per_1k: f64,
+}
+
+impl LLMModelPrice {
+    /// 按输入/输出 token 数估算费用
+    pub fn estimate_cost(&self, input_tokens: i64, output_tokens: i64) -> f64 {
+        (input_tokens as f64 * self.input_price_per_1k
+            + output_tokens as f64 * self.output_price_per_1k)
+            / 1000.0
+    }
+}
+
 pub struct LLMDatabase {
     pub conn: Connection,
 }
@@ -50,7 +80,7 @@ impl LLMDatabase {
     #[instrument(level = "debug", skip(app_handle), err)]
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "llm.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
         Ok(LLMDatabase { conn })
     }
 
@@ -95,6 +125,28 @@ impl LLMDatabase {
                 );",
             [],
         )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS llm_environment_profile (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    enabled_provider_ids TEXT NOT NULL DEFAULT '[]',
+                    default_provider_id INTEGER,
+                    default_model_code TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT 0,
+                    created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+                );",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS llm_model_price (
+                    model_code TEXT PRIMARY KEY,
+                    input_price_per_1k REAL NOT NULL DEFAULT 0,
+                    output_price_per_1k REAL NOT NULL DEFAULT 0,
+                    updated_time DATETIME DEFAULT CURRENT_TIMESTAMP
+                );",
+            [],
+        )?;
 
         if let Err(err) = self.init_llm_provider() {
             warn!(error = ?err, "init_llm_provider failed (may already be initialized)");
@@ -519,6 +571,157 @@ impl LLMDatabase {
         Ok(result)
     }
 
+    fn read_environment_profile(row: &rusqlite::Row) -> rusqlite::Result<LLMEnvironmentProfile> {
+        let enabled_provider_ids: String = row.get(2)?;
+        Ok(LLMEnvironmentProfile {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            enabled_provider_ids: serde_json::from_str(&enabled_provider_ids).unwrap_or_default(),
+            default_provider_id: row.get(3)?,
+            default_model_code: row.get(4)?,
+            is_active: row.get(5)?,
+        })
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_environment_profiles(&self) -> rusqlite::Result<Vec<LLMEnvironmentProfile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, enabled_provider_ids, default_provider_id, default_model_code, is_active \
+             FROM llm_environment_profile ORDER BY id",
+        )?;
+        let profiles = stmt.query_map([], Self::read_environment_profile)?;
+        profiles.collect()
+    }
+
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn get_environment_profile(&self, id: i64) -> rusqlite::Result<LLMEnvironmentProfile> {
+        self.conn.query_row(
+            "SELECT id, name, enabled_provider_ids, default_provider_id, default_model_code, is_active \
+             FROM llm_environment_profile WHERE id = ?",
+            [id],
+            Self::read_environment_profile,
+        )
+    }
+
+    #[instrument(level = "debug", skip(self, enabled_provider_ids), fields(name = name))]
+    pub fn add_environment_profile(
+        &self,
+        name: &str,
+        enabled_provider_ids: &[i64],
+        default_provider_id: Option<i64>,
+        default_model_code: Option<&str>,
+    ) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO llm_environment_profile (name, enabled_provider_ids, default_provider_id, default_model_code) VALUES (?, ?, ?, ?)",
+            params![
+                name,
+                serde_json::to_string(enabled_provider_ids).unwrap_or_else(|_| "[]".to_string()),
+                default_provider_id,
+                default_model_code
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    #[instrument(level = "debug", skip(self, enabled_provider_ids), fields(id = id, name = name))]
+    pub fn update_environment_profile(
+        &self,
+        id: i64,
+        name: &str,
+        enabled_provider_ids: &[i64],
+        default_provider_id: Option<i64>,
+        default_model_code: Option<&str>,
+    ) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE llm_environment_profile SET name = ?, enabled_provider_ids = ?, default_provider_id = ?, default_model_code = ? WHERE id = ?",
+            params![
+                name,
+                serde_json::to_string(enabled_provider_ids).unwrap_or_else(|_| "[]".to_string()),
+                default_provider_id,
+                default_model_code,
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn delete_environment_profile(&self, id: i64) -> rusqlite::Result<()> {
+        self.conn.execute("DELETE FROM llm_environment_profile WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// 切换到指定环境：只启用环境中列出的提供商，并将其标记为当前环境（同一事务内完成）
+    #[instrument(level = "debug", skip(self), fields(id = id))]
+    pub fn apply_environment_profile(&self, id: i64) -> rusqlite::Result<LLMEnvironmentProfile> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut profile = self.get_environment_profile(id)?;
+
+        tx.execute("UPDATE llm_provider SET is_enabled = 0", [])?;
+        for provider_id in &profile.enabled_provider_ids {
+            tx.execute("UPDATE llm_provider SET is_enabled = 1 WHERE id = ?", [provider_id])?;
+        }
+        tx.execute("UPDATE llm_environment_profile SET is_active = (id = ?)", [id])?;
+        tx.commit()?;
+
+        profile.is_active = true;
+        debug!(provider_count = profile.enabled_provider_ids.len(), "environment profile applied");
+        Ok(profile)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_model_prices(&self) -> rusqlite::Result<Vec<LLMModelPrice>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT model_code, input_price_per_1k, output_price_per_1k FROM llm_model_price ORDER BY model_code",
+        )?;
+        let prices = stmt.query_map([], |row| {
+            Ok(LLMModelPrice {
+                model_code: row.get(0)?,
+                input_price_per_1k: row.get(1)?,
+                output_price_per_1k: row.get(2)?,
+            })
+        })?;
+        prices.collect()
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_model_price(&self, model_code: &str) -> rusqlite::Result<Option<LLMModelPrice>> {
+        self.conn
+            .query_row(
+                "SELECT model_code, input_price_per_1k, output_price_per_1k FROM llm_model_price WHERE model_code = ?",
+                params![model_code],
+                |row| {
+                    Ok(LLMModelPrice {
+                        model_code: row.get(0)?,
+                        input_price_per_1k: row.get(1)?,
+                        output_price_per_1k: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    #[instrument(level = "debug", skip(self), fields(model_code = %price.model_code))]
+    pub fn upsert_model_price(&self, price: &LLMModelPrice) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO llm_model_price (model_code, input_price_per_1k, output_price_per_1k, updated_time)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(model_code) DO UPDATE SET
+                input_price_per_1k = excluded.input_price_per_1k,
+                output_price_per_1k = excluded.output_price_per_1k,
+                updated_time = CURRENT_TIMESTAMP",
+            params![price.model_code, price.input_price_per_1k, price.output_price_per_1k],
+        )?;
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub fn delete_model_price(&self, model_code: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM llm_model_price WHERE model_code = ?", params![model_code])?;
+        Ok(())
+    }
+
     #[instrument(level = "debug", skip(self), err)]
     pub fn init_llm_provider(&self) -> rusqlite::Result<()> {
         // 使用 INSERT OR IGNORE 避免重复初始化时触发 UNIQUE 约束错误
@@ -5,6 +5,7 @@ use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 use tracing::instrument;
 
+use crate::db::encryption::open_connection;
 use crate::db::get_db_path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +25,30 @@ pub struct MCPServer {
     pub is_deletable: bool,  // 标识是否可删除（系统初始化的内置工具集不可删除）
     pub proxy_enabled: bool, // 是否使用全局网络代理
     pub created_time: String,
+    #[serde(default)]
+    pub autostart: bool, // 应用启动时是否在后台自动连接
+    #[serde(default)]
+    pub status: Option<String>, // 最近一次健康检查结果：healthy / unhealthy，未检查时为 None
+    #[serde(default)]
+    pub last_healthy_at: Option<String>, // 最近一次健康检查成功的时间
+}
+
+/// 批量操作中单个服务器的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPServerBulkResult {
+    pub id: i64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl MCPServerBulkResult {
+    fn ok(id: i64) -> Self {
+        Self { id, success: true, error: None }
+    }
+
+    fn failed(id: i64, error: impl Into<String>) -> Self {
+        Self { id, success: false, error: Some(error.into()) }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +60,8 @@ pub struct MCPServerTool {
     pub is_enabled: bool,
     pub is_auto_run: bool,
     pub parameters: Option<String>, // JSON string of tool parameters
+    #[serde(default)]
+    pub timeout_ms: Option<i64>, // 单独配置的执行超时（毫秒），为空时沿用服务器或全局设置
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,6 +102,8 @@ pub struct MCPToolCall {
     pub finished_time: Option<String>,
     pub llm_call_id: Option<String>,       // LLM 原生 tool_call_id
     pub assistant_message_id: Option<i64>, // 关联的 assistant 消息ID
+    #[serde(default)]
+    pub timeout_ms: Option<i64>, // 本次执行生效的超时（毫秒），未开始执行时为空
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,13 +177,18 @@ impl MCPDatabase {
     #[instrument(level = "trace", skip(app_handle))]
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "mcp.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;\nPRAGMA synchronous=NORMAL;\nPRAGMA foreign_keys=ON;\nPRAGMA busy_timeout=5000;",
         )?;
         Ok(MCPDatabase { conn })
     }
 
+    /// 将 WAL 中的写入合并回主库文件（应用退出时调用）
+    pub fn checkpoint(&self) -> rusqlite::Result<()> {
+        self.conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+    }
+
     fn short_hash(s: &str) -> String {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
@@ -304,6 +338,7 @@ impl MCPDatabase {
 
         self.migrate_mcp_tool_call_table()?;
         self.migrate_mcp_server_table()?; // ensure headers column exists
+        self.migrate_mcp_server_tool_table()?;
         self.create_dynamic_loading_tables()?;
         let _ = self.rebuild_dynamic_mcp_catalog();
 
@@ -382,6 +417,7 @@ impl MCPDatabase {
                 let mut has_llm_call_id = false;
                 let mut has_assistant_message_id = false;
                 let mut has_subtask_id = false;
+                let mut has_timeout_ms = false;
 
                 for column in column_info {
                     match column {
@@ -392,6 +428,8 @@ impl MCPDatabase {
                                 has_assistant_message_id = true;
                             } else if name == "subtask_id" {
                                 has_subtask_id = true;
+                            } else if name == "timeout_ms" {
+                                has_timeout_ms = true;
                             }
                         }
                         Err(_) => continue,
@@ -413,6 +451,10 @@ impl MCPDatabase {
                     self.conn
                         .execute("ALTER TABLE mcp_tool_call ADD COLUMN subtask_id INTEGER", [])?;
                 }
+                if !has_timeout_ms {
+                    self.conn
+                        .execute("ALTER TABLE mcp_tool_call ADD COLUMN timeout_ms INTEGER", [])?;
+                }
             }
             Err(_) => {
                 // Table might not exist yet, which is fine
@@ -427,6 +469,9 @@ impl MCPDatabase {
             let mut has_headers = false;
             let mut has_is_deletable = false;
             let mut has_proxy_enabled = false;
+            let mut has_autostart = false;
+            let mut has_status = false;
+            let mut has_last_healthy_at = false;
             let cols = stmt.query_map([], |row| Ok(row.get::<_, String>(1)?))?;
             for c in cols {
                 if let Ok(name) = c {
@@ -439,6 +484,15 @@ impl MCPDatabase {
                     if name == "proxy_enabled" {
                         has_proxy_enabled = true;
                     }
+                    if name == "autostart" {
+                        has_autostart = true;
+                    }
+                    if name == "status" {
+                        has_status = true;
+                    }
+                    if name == "last_healthy_at" {
+                        has_last_healthy_at = true;
+                    }
                 }
             }
             if !has_headers {
@@ -458,6 +512,43 @@ impl MCPDatabase {
                     [],
                 );
             }
+            if !has_autostart {
+                // 添加 autostart 字段，默认为 0（按需连接）
+                let _ = self.conn.execute(
+                    "ALTER TABLE mcp_server ADD COLUMN autostart BOOLEAN NOT NULL DEFAULT 0",
+                    [],
+                );
+            }
+            if !has_status {
+                // 健康检查状态，未检查过时为 NULL
+                let _ = self.conn.execute("ALTER TABLE mcp_server ADD COLUMN status TEXT", []);
+            }
+            if !has_last_healthy_at {
+                let _ = self
+                    .conn
+                    .execute("ALTER TABLE mcp_server ADD COLUMN last_healthy_at DATETIME", []);
+            }
+        }
+        Ok(())
+    }
+
+    fn migrate_mcp_server_tool_table(&self) -> rusqlite::Result<()> {
+        if let Ok(mut stmt) = self.conn.prepare("PRAGMA table_info(mcp_server_tool)") {
+            let mut has_timeout_ms = false;
+            let cols = stmt.query_map([], |row| Ok(row.get::<_, String>(1)?))?;
+            for c in cols {
+                if let Ok(name) = c {
+                    if name == "timeout_ms" {
+                        has_timeout_ms = true;
+                    }
+                }
+            }
+            if !has_timeout_ms {
+                // 工具级执行超时（毫秒），NULL 表示沿用服务器或全局设置
+                let _ = self
+                    .conn
+                    .execute("ALTER TABLE mcp_server_tool ADD COLUMN timeout_ms INTEGER", []);
+            }
         }
         Ok(())
     }
@@ -465,7 +556,7 @@ impl MCPDatabase {
     #[instrument(level = "trace", skip(self))]
     pub fn get_mcp_servers(&self) -> rusqlite::Result<Vec<MCPServer>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, transport_type, command, environment_variables, headers, url, timeout, is_long_running, is_enabled, COALESCE(is_builtin, 0), COALESCE(is_deletable, 1), COALESCE(proxy_enabled, 0), created_time \
+            "SELECT id, name, description, transport_type, command, environment_variables, headers, url, timeout, is_long_running, is_enabled, COALESCE(is_builtin, 0), COALESCE(is_deletable, 1), COALESCE(proxy_enabled, 0), created_time, COALESCE(autostart, 0), status, last_healthy_at \
              FROM mcp_server ORDER BY created_time DESC"
         )?;
 
@@ -486,6 +577,9 @@ impl MCPDatabase {
                 is_deletable: row.get(12)?,
                 proxy_enabled: row.get(13)?,
                 created_time: row.get(14)?,
+                autostart: row.get(15)?,
+                status: row.get(16)?,
+                last_healthy_at: row.get(17)?,
             })
         })?;
 
@@ -499,7 +593,7 @@ impl MCPDatabase {
     #[instrument(level = "trace", skip(self), fields(id))]
     pub fn get_mcp_server(&self, id: i64) -> rusqlite::Result<MCPServer> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, transport_type, command, environment_variables, headers, url, timeout, is_long_running, is_enabled, COALESCE(is_builtin, 0), COALESCE(is_deletable, 1), COALESCE(proxy_enabled, 0), created_time \
+            "SELECT id, name, description, transport_type, command, environment_variables, headers, url, timeout, is_long_running, is_enabled, COALESCE(is_builtin, 0), COALESCE(is_deletable, 1), COALESCE(proxy_enabled, 0), created_time, COALESCE(autostart, 0), status, last_healthy_at \
              FROM mcp_server WHERE id = ?"
         )?;
 
@@ -521,6 +615,9 @@ impl MCPDatabase {
                     is_deletable: row.get(12)?,
                     proxy_enabled: row.get(13)?,
                     created_time: row.get(14)?,
+                    autostart: row.get(15)?,
+                    status: row.get(16)?,
+                    last_healthy_at: row.get(17)?,
                 })
             })?
             .next()
@@ -544,7 +641,7 @@ impl MCPDatabase {
         // 构造占位符
         let placeholders = vec!["?"; server_ids.len()].join(",");
         let sql = format!(
-            "SELECT id, name, description, transport_type, command, environment_variables, headers, url, timeout, is_long_running, is_enabled, COALESCE(is_builtin, 0), COALESCE(is_deletable, 1), COALESCE(proxy_enabled, 0), created_time \
+            "SELECT id, name, description, transport_type, command, environment_variables, headers, url, timeout, is_long_running, is_enabled, COALESCE(is_builtin, 0), COALESCE(is_deletable, 1), COALESCE(proxy_enabled, 0), created_time, COALESCE(autostart, 0), status, last_healthy_at \
              FROM mcp_server WHERE id IN ({})",
             placeholders
         );
@@ -567,6 +664,9 @@ impl MCPDatabase {
                     is_deletable: row.get(12)?,
                     proxy_enabled: row.get(13)?,
                     created_time: row.get(14)?,
+                    autostart: row.get(15)?,
+                    status: row.get(16)?,
+                    last_healthy_at: row.get(17)?,
                 })
             })?;
 
@@ -581,7 +681,7 @@ impl MCPDatabase {
         // 取所有 tool
         let placeholders_tools = vec!["?"; servers.len()].join(",");
         let tools_sql = format!(
-            "SELECT id, server_id, tool_name, tool_description, is_enabled, is_auto_run, parameters \
+            "SELECT id, server_id, tool_name, tool_description, is_enabled, is_auto_run, parameters, timeout_ms \
              FROM mcp_server_tool WHERE server_id IN ({}) ORDER BY server_id, tool_name",
             placeholders_tools
         );
@@ -597,6 +697,7 @@ impl MCPDatabase {
                     is_enabled: row.get(4)?,
                     is_auto_run: row.get(5)?,
                     parameters: row.get(6)?,
+                    timeout_ms: row.get(7)?,
                 })
             },
         )?;
@@ -645,6 +746,24 @@ impl MCPDatabase {
         Ok(())
     }
 
+    pub fn set_mcp_server_autostart(&self, id: i64, autostart: bool) -> rusqlite::Result<()> {
+        self.conn
+            .execute("UPDATE mcp_server SET autostart = ? WHERE id = ?", params![autostart, id])?;
+        Ok(())
+    }
+
+    /// 记录健康检查结果，检查成功时同时刷新 last_healthy_at
+    pub fn update_mcp_server_health(&self, id: i64, healthy: bool) -> rusqlite::Result<()> {
+        let status = if healthy { "healthy" } else { "unhealthy" };
+        self.conn.execute(
+            "UPDATE mcp_server SET status = ?, \
+             last_healthy_at = CASE WHEN ? THEN CURRENT_TIMESTAMP ELSE last_healthy_at END \
+             WHERE id = ?",
+            params![status, healthy, id],
+        )?;
+        Ok(())
+    }
+
     pub fn toggle_mcp_server(&self, id: i64, is_enabled: bool) -> rusqlite::Result<()> {
         self.conn.execute(
             "UPDATE mcp_server SET is_enabled = ? WHERE id = ?",
@@ -653,6 +772,58 @@ impl MCPDatabase {
         Ok(())
     }
 
+    /// 批量启用/禁用服务器，在同一事务中执行；不存在的服务器记为失败，数据库错误则整体回滚
+    #[instrument(level = "debug", skip(self, ids), fields(count = ids.len(), is_enabled))]
+    pub fn bulk_toggle_mcp_servers(
+        &self,
+        ids: &[i64],
+        is_enabled: bool,
+    ) -> rusqlite::Result<Vec<MCPServerBulkResult>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut results = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let updated = tx.execute(
+                "UPDATE mcp_server SET is_enabled = ? WHERE id = ?",
+                params![is_enabled, id],
+            )?;
+            results.push(if updated > 0 {
+                MCPServerBulkResult::ok(id)
+            } else {
+                MCPServerBulkResult::failed(id, "MCP服务器不存在")
+            });
+        }
+        tx.commit()?;
+        Ok(results)
+    }
+
+    /// 批量删除服务器，在同一事务中执行；不存在或不可删除的服务器记为失败，数据库错误则整体回滚
+    #[instrument(level = "debug", skip(self, ids), fields(count = ids.len()))]
+    pub fn bulk_delete_mcp_servers(
+        &self,
+        ids: &[i64],
+    ) -> rusqlite::Result<Vec<MCPServerBulkResult>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut results = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let is_deletable = tx
+                .query_row("SELECT is_deletable FROM mcp_server WHERE id = ?", [id], |row| {
+                    row.get::<_, bool>(0)
+                })
+                .optional()?;
+            results.push(match is_deletable {
+                None => MCPServerBulkResult::failed(id, "MCP服务器不存在"),
+                Some(false) => MCPServerBulkResult::failed(id, "系统内置工具集不可删除"),
+                Some(true) => {
+                    // Cascade delete will handle tools and resources
+                    tx.execute("DELETE FROM mcp_server WHERE id = ?", params![id])?;
+                    MCPServerBulkResult::ok(id)
+                }
+            });
+        }
+        tx.commit()?;
+        Ok(results)
+    }
+
     #[instrument(level = "trace", skip(self, description, command, environment_variables, headers, url), fields(name = name, transport_type = transport_type))]
     pub fn upsert_mcp_server_with_builtin(
         &self,
@@ -718,7 +889,7 @@ impl MCPDatabase {
 
     pub fn get_mcp_server_tools(&self, server_id: i64) -> rusqlite::Result<Vec<MCPServerTool>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, server_id, tool_name, tool_description, is_enabled, is_auto_run, parameters 
+            "SELECT id, server_id, tool_name, tool_description, is_enabled, is_auto_run, parameters, timeout_ms
              FROM mcp_server_tool WHERE server_id = ? ORDER BY tool_name"
         )?;
 
@@ -731,6 +902,7 @@ impl MCPDatabase {
                 is_enabled: row.get(4)?,
                 is_auto_run: row.get(5)?,
                 parameters: row.get(6)?,
+                timeout_ms: row.get(7)?,
             })
         })?;
 
@@ -741,6 +913,26 @@ impl MCPDatabase {
         Ok(result)
     }
 
+    pub fn get_mcp_server_tool(&self, tool_id: i64) -> rusqlite::Result<MCPServerTool> {
+        self.conn.query_row(
+            "SELECT id, server_id, tool_name, tool_description, is_enabled, is_auto_run, parameters, timeout_ms
+             FROM mcp_server_tool WHERE id = ?",
+            [tool_id],
+            |row| {
+                Ok(MCPServerTool {
+                    id: row.get(0)?,
+                    server_id: row.get(1)?,
+                    tool_name: row.get(2)?,
+                    tool_description: row.get(3)?,
+                    is_enabled: row.get(4)?,
+                    is_auto_run: row.get(5)?,
+                    parameters: row.get(6)?,
+                    timeout_ms: row.get(7)?,
+                })
+            },
+        )
+    }
+
     /// 删除指定服务器下所有、或指定名称集合之外的工具
     pub fn delete_mcp_server_tools_not_in(
         &self,
@@ -789,6 +981,19 @@ impl MCPDatabase {
         Ok(())
     }
 
+    /// 设置工具级执行超时，传入 None 时恢复为沿用服务器或全局设置
+    pub fn update_mcp_server_tool_timeout(
+        &self,
+        id: i64,
+        timeout_ms: Option<i64>,
+    ) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE mcp_server_tool SET timeout_ms = ? WHERE id = ?",
+            params![timeout_ms, id],
+        )?;
+        Ok(())
+    }
+
     #[instrument(
         level = "trace",
         skip(self, tool_description, parameters),
@@ -1130,7 +1335,7 @@ impl MCPDatabase {
     pub fn get_mcp_tool_call(&self, id: i64) -> rusqlite::Result<MCPToolCall> {
         let mut stmt = self.conn.prepare(
             "SELECT id, conversation_id, message_id, server_id, server_name, tool_name, 
-             parameters, status, result, error, created_time, started_time, finished_time, llm_call_id, assistant_message_id, subtask_id
+             parameters, status, result, error, created_time, started_time, finished_time, llm_call_id, assistant_message_id, subtask_id, timeout_ms
              FROM mcp_tool_call WHERE id = ?"
         )?;
 
@@ -1152,6 +1357,7 @@ impl MCPDatabase {
                 finished_time: row.get(12)?,
                 llm_call_id: row.get(13)?,
                 assistant_message_id: row.get(14)?,
+                timeout_ms: row.get(16)?,
             })
         })
     }
@@ -1228,13 +1434,24 @@ impl MCPDatabase {
         Ok(rows > 0)
     }
 
+    /// 记录本次执行生效的超时时间，便于前端展示
+    pub fn set_mcp_tool_call_timeout(&self, id: i64, timeout_ms: i64) -> rusqlite::Result<()> {
+        self.retry_if_busy(|| {
+            self.conn.execute(
+                "UPDATE mcp_tool_call SET timeout_ms = ? WHERE id = ?",
+                params![timeout_ms, id],
+            )?;
+            Ok(())
+        })
+    }
+
     pub fn get_mcp_tool_calls_by_conversation(
         &self,
         conversation_id: i64,
     ) -> rusqlite::Result<Vec<MCPToolCall>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, conversation_id, message_id, server_id, server_name, tool_name, 
-             parameters, status, result, error, created_time, started_time, finished_time, llm_call_id, assistant_message_id, subtask_id
+             parameters, status, result, error, created_time, started_time, finished_time, llm_call_id, assistant_message_id, subtask_id, timeout_ms
              FROM mcp_tool_call WHERE conversation_id = ? ORDER BY created_time DESC"
         )?;
 
@@ -1256,6 +1473,7 @@ impl MCPDatabase {
                 finished_time: row.get(12)?,
                 llm_call_id: row.get(13)?,
                 assistant_message_id: row.get(14)?,
+                timeout_ms: row.get(16)?,
             })
         })?;
 
@@ -1275,7 +1493,7 @@ impl MCPDatabase {
         let mut stmt = self.conn.prepare(
             "SELECT id, conversation_id, message_id, server_id, server_name, tool_name,
              parameters, status, result, error, created_time, started_time, finished_time,
-             llm_call_id, assistant_message_id, subtask_id
+             llm_call_id, assistant_message_id, subtask_id, timeout_ms
              FROM mcp_tool_call WHERE message_id = ? ORDER BY id ASC",
         )?;
 
@@ -1297,6 +1515,7 @@ impl MCPDatabase {
                 finished_time: row.get(12)?,
                 llm_call_id: row.get(13)?,
                 assistant_message_id: row.get(14)?,
+                timeout_ms: row.get(16)?,
             })
         })?;
 
@@ -13,6 +13,7 @@ use tracing::{debug, error, info, instrument, warn};
 
 pub mod assistant_db;
 pub mod conversation_db;
+pub mod encryption;
 pub mod llm_db;
 pub mod mcp_db;
 pub mod plugin_db;
@@ -21,15 +22,19 @@ pub mod skill_db;
 pub mod system_db;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
 const CURRENT_VERSION: &str = "0.0.10";
 
-pub(crate) fn get_db_path(app_handle: &tauri::AppHandle, db_name: &str) -> Result<PathBuf, String> {
+pub(crate) fn get_db_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_dir = app_handle.path().app_data_dir().unwrap();
     let db_path = app_dir.join("db");
     std::fs::create_dir_all(&db_path).map_err(|e| e.to_string())?;
-    Ok(db_path.join(db_name))
+    Ok(db_path)
+}
+
+pub(crate) fn get_db_path(app_handle: &tauri::AppHandle, db_name: &str) -> Result<PathBuf, String> {
+    Ok(get_db_dir(app_handle)?.join(db_name))
 }
 
 #[instrument(level = "info", skip(app_handle, system_db, llm_db, assistant_db, conversation_db))]
@@ -1,3 +1,4 @@
+use super::encryption::open_connection;
 use super::get_db_path;
 use chrono::prelude::*;
 use rusqlite::{params, Connection, OptionalExtension, Result};
@@ -51,7 +52,7 @@ impl PluginDatabase {
     #[instrument(level = "debug", skip(app_handle), fields(db = "plugin.db"))]
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "plugin.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
         debug!("Opened plugin database");
         Ok(PluginDatabase { conn })
     }
@@ -7,6 +7,7 @@ use tracing::{debug, instrument};
 
 use crate::utils::db_utils::{get_datetime_from_row, get_required_datetime_from_row};
 
+use super::encryption::open_connection;
 use super::get_db_path;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,7 +15,7 @@ pub struct ScheduledTask {
     pub id: i64,
     pub name: String,
     pub is_enabled: bool,
-    pub schedule_type: String, // 'once' | 'interval'
+    pub schedule_type: String, // 'once' | 'interval' | 'cron'
     pub interval_value: Option<i64>,
     pub interval_unit: Option<String>, // 'minute' | 'hour' | 'day' | 'week' | 'month'
     pub start_time: Option<String>,    // HH:mm format for day/week/month schedules
@@ -28,6 +29,12 @@ pub struct ScheduledTask {
     pub notify_prompt: String,
     pub created_time: DateTime<Utc>,
     pub updated_time: DateTime<Utc>,
+    pub cron: Option<String>, // 5-field cron expression for 'cron' schedules
+    pub timezone: Option<String>, // IANA timezone, None = system local time
+    pub max_retries: i64,     // retries after a failed run, 0 = no retry
+    pub retry_delay_secs: i64, // delay before the first retry, doubled on each attempt
+    pub retry_attempt: i64,   // failed attempts in the current retry chain
+    pub retry_scheduled_time: Option<DateTime<Utc>>, // planned slot the retry chain replaces
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,6 +58,43 @@ pub struct ScheduledTaskRun {
     pub error_message: Option<String>,
     pub started_time: DateTime<Utc>,
     pub finished_time: Option<DateTime<Utc>>,
+    pub scheduled_time: Option<DateTime<Utc>>, // planned run time, None for manual runs
+    pub timezone: Option<String>,
+    pub attempt: i64, // 1 for the first run, increased on each retry
+}
+
+/// Columns shared by every scheduled_task table layout, used when rebuilding the table
+const TASK_COLUMNS: &str = "id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time, cron, timezone, max_retries, retry_delay_secs, retry_attempt, retry_scheduled_time";
+
+fn scheduled_task_table_sql(table_name: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            is_enabled BOOLEAN NOT NULL DEFAULT 1,
+            schedule_type TEXT NOT NULL CHECK(schedule_type IN ('once', 'interval', 'cron')),
+            interval_value INTEGER,
+            interval_unit TEXT,
+            start_time TEXT,
+            week_days TEXT,
+            month_days TEXT,
+            run_at DATETIME,
+            next_run_at DATETIME,
+            last_run_at DATETIME,
+            assistant_id INTEGER NOT NULL,
+            task_prompt TEXT NOT NULL,
+            notify_prompt TEXT NOT NULL,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            cron TEXT,
+            timezone TEXT,
+            max_retries INTEGER NOT NULL DEFAULT 0,
+            retry_delay_secs INTEGER NOT NULL DEFAULT 60,
+            retry_attempt INTEGER NOT NULL DEFAULT 0,
+            retry_scheduled_time DATETIME
+        )",
+        table_name
+    )
 }
 
 pub struct ScheduledTaskDatabase {
@@ -62,40 +106,19 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(app_handle), fields(db = "conversation.db"))]
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "conversation.db").unwrap();
-        let conn = Connection::open(&db_path)?;
+        let conn = open_connection(&db_path)?;
         debug!("Opened scheduled task database");
         Ok(ScheduledTaskDatabase { conn, db_path })
     }
 
     pub fn get_connection(&self) -> rusqlite::Result<Connection> {
-        Connection::open(&self.db_path)
+        open_connection(&self.db_path)
     }
 
     #[instrument(level = "debug", skip(self))]
     pub fn create_tables(&self) -> rusqlite::Result<()> {
         let conn = &self.conn;
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS scheduled_task (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                is_enabled BOOLEAN NOT NULL DEFAULT 1,
-                schedule_type TEXT NOT NULL CHECK(schedule_type IN ('once', 'interval')),
-                interval_value INTEGER,
-                interval_unit TEXT,
-                start_time TEXT,
-                week_days TEXT,
-                month_days TEXT,
-                run_at DATETIME,
-                next_run_at DATETIME,
-                last_run_at DATETIME,
-                assistant_id INTEGER NOT NULL,
-                task_prompt TEXT NOT NULL,
-                notify_prompt TEXT NOT NULL,
-                created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_time DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+        conn.execute(&scheduled_task_table_sql("scheduled_task"), [])?;
 
         // Migration: add new columns if they don't exist
         let columns: Vec<String> = conn
@@ -111,6 +134,58 @@ impl ScheduledTaskDatabase {
         if !columns.contains(&"month_days".to_string()) {
             conn.execute("ALTER TABLE scheduled_task ADD COLUMN month_days TEXT", [])?;
         }
+        if !columns.contains(&"cron".to_string()) {
+            conn.execute("ALTER TABLE scheduled_task ADD COLUMN cron TEXT", [])?;
+        }
+        if !columns.contains(&"timezone".to_string()) {
+            conn.execute("ALTER TABLE scheduled_task ADD COLUMN timezone TEXT", [])?;
+        }
+        if !columns.contains(&"max_retries".to_string()) {
+            conn.execute(
+                "ALTER TABLE scheduled_task ADD COLUMN max_retries INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+        if !columns.contains(&"retry_delay_secs".to_string()) {
+            conn.execute(
+                "ALTER TABLE scheduled_task ADD COLUMN retry_delay_secs INTEGER NOT NULL DEFAULT 60",
+                [],
+            )?;
+        }
+        if !columns.contains(&"retry_attempt".to_string()) {
+            conn.execute(
+                "ALTER TABLE scheduled_task ADD COLUMN retry_attempt INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+        if !columns.contains(&"retry_scheduled_time".to_string()) {
+            conn.execute(
+                "ALTER TABLE scheduled_task ADD COLUMN retry_scheduled_time DATETIME",
+                [],
+            )?;
+        }
+
+        // Migration: old tables only allow 'once' / 'interval' in the schedule_type CHECK,
+        // SQLite can't alter constraints so the table is rebuilt to accept 'cron'
+        let table_sql: String = conn.query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'scheduled_task'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !table_sql.contains("'cron'") {
+            conn.execute_batch(&format!(
+                "BEGIN;
+                 DROP TABLE IF EXISTS scheduled_task_migrated;
+                 {};
+                 INSERT INTO scheduled_task_migrated ({columns}) SELECT {columns} FROM scheduled_task;
+                 DROP TABLE scheduled_task;
+                 ALTER TABLE scheduled_task_migrated RENAME TO scheduled_task;
+                 COMMIT;",
+                scheduled_task_table_sql("scheduled_task_migrated"),
+                columns = TASK_COLUMNS,
+            ))?;
+            debug!("Rebuilt scheduled_task table to support cron schedules");
+        }
 
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_scheduled_task_enabled_next_run ON scheduled_task(is_enabled, next_run_at)",
@@ -146,10 +221,29 @@ impl ScheduledTaskDatabase {
                 summary TEXT,
                 error_message TEXT,
                 started_time DATETIME DEFAULT CURRENT_TIMESTAMP,
-                finished_time DATETIME
+                finished_time DATETIME,
+                scheduled_time DATETIME,
+                timezone TEXT,
+                attempt INTEGER NOT NULL DEFAULT 1
             )",
             [],
         )?;
+        let run_columns: Vec<String> = conn
+            .prepare("PRAGMA table_info(scheduled_task_run)")?
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>>>()?;
+        if !run_columns.contains(&"scheduled_time".to_string()) {
+            conn.execute("ALTER TABLE scheduled_task_run ADD COLUMN scheduled_time DATETIME", [])?;
+        }
+        if !run_columns.contains(&"timezone".to_string()) {
+            conn.execute("ALTER TABLE scheduled_task_run ADD COLUMN timezone TEXT", [])?;
+        }
+        if !run_columns.contains(&"attempt".to_string()) {
+            conn.execute(
+                "ALTER TABLE scheduled_task_run ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1",
+                [],
+            )?;
+        }
         conn.execute(
             "CREATE UNIQUE INDEX IF NOT EXISTS idx_scheduled_task_run_run_id ON scheduled_task_run(run_id)",
             [],
@@ -166,7 +260,7 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(self))]
     pub fn list_tasks(&self) -> Result<Vec<ScheduledTask>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time
+            "SELECT id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time, cron, timezone, max_retries, retry_delay_secs, retry_attempt, retry_scheduled_time
              FROM scheduled_task
              ORDER BY created_time DESC",
         )?;
@@ -189,6 +283,12 @@ impl ScheduledTaskDatabase {
                 notify_prompt: row.get(14)?,
                 created_time: get_required_datetime_from_row(row, 15, "created_time")?,
                 updated_time: get_required_datetime_from_row(row, 16, "updated_time")?,
+                cron: row.get(17)?,
+                timezone: row.get(18)?,
+                max_retries: row.get(19)?,
+                retry_delay_secs: row.get(20)?,
+                retry_attempt: row.get(21)?,
+                retry_scheduled_time: get_datetime_from_row(row, 22)?,
             })
         })?;
         let tasks: Vec<ScheduledTask> = rows.collect::<Result<Vec<_>>>()?;
@@ -200,7 +300,7 @@ impl ScheduledTaskDatabase {
         let task = self
             .conn
             .query_row(
-                "SELECT id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time
+                "SELECT id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time, cron, timezone, max_retries, retry_delay_secs, retry_attempt, retry_scheduled_time
                  FROM scheduled_task WHERE id = ?",
                 [id],
                 |row| {
@@ -222,6 +322,12 @@ impl ScheduledTaskDatabase {
                         notify_prompt: row.get(14)?,
                         created_time: get_required_datetime_from_row(row, 15, "created_time")?,
                         updated_time: get_required_datetime_from_row(row, 16, "updated_time")?,
+                        cron: row.get(17)?,
+                        timezone: row.get(18)?,
+                        max_retries: row.get(19)?,
+                        retry_delay_secs: row.get(20)?,
+                        retry_attempt: row.get(21)?,
+                        retry_scheduled_time: get_datetime_from_row(row, 22)?,
                     })
                 },
             )
@@ -232,8 +338,8 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(self, task), fields(name = %task.name))]
     pub fn create_task(&self, task: &ScheduledTask) -> Result<ScheduledTask> {
         self.conn.execute(
-            "INSERT INTO scheduled_task (name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            "INSERT INTO scheduled_task (name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time, cron, timezone, max_retries, retry_delay_secs, retry_attempt, retry_scheduled_time)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
             params![
                 task.name,
                 task.is_enabled,
@@ -250,7 +356,13 @@ impl ScheduledTaskDatabase {
                 task.task_prompt,
                 task.notify_prompt,
                 task.created_time,
-                task.updated_time
+                task.updated_time,
+                task.cron,
+                task.timezone,
+                task.max_retries,
+                task.retry_delay_secs,
+                task.retry_attempt,
+                task.retry_scheduled_time
             ],
         )?;
         let id = self.conn.last_insert_rowid();
@@ -260,7 +372,7 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(self, task), fields(id = task.id))]
     pub fn update_task(&self, task: &ScheduledTask) -> Result<()> {
         self.conn.execute(
-            "UPDATE scheduled_task SET name = ?1, is_enabled = ?2, schedule_type = ?3, interval_value = ?4, interval_unit = ?5, start_time = ?6, week_days = ?7, month_days = ?8, run_at = ?9, next_run_at = ?10, last_run_at = ?11, assistant_id = ?12, task_prompt = ?13, notify_prompt = ?14, updated_time = ?15 WHERE id = ?16",
+            "UPDATE scheduled_task SET name = ?1, is_enabled = ?2, schedule_type = ?3, interval_value = ?4, interval_unit = ?5, start_time = ?6, week_days = ?7, month_days = ?8, run_at = ?9, next_run_at = ?10, last_run_at = ?11, assistant_id = ?12, task_prompt = ?13, notify_prompt = ?14, updated_time = ?15, cron = ?16, timezone = ?17, max_retries = ?18, retry_delay_secs = ?19, retry_attempt = ?20, retry_scheduled_time = ?21 WHERE id = ?22",
             params![
                 task.name,
                 task.is_enabled,
@@ -277,12 +389,48 @@ impl ScheduledTaskDatabase {
                 task.task_prompt,
                 task.notify_prompt,
                 task.updated_time,
+                task.cron,
+                task.timezone,
+                task.max_retries,
+                task.retry_delay_secs,
+                task.retry_attempt,
+                task.retry_scheduled_time,
                 task.id
             ],
         )?;
         Ok(())
     }
 
+    /// 记录失败重试进度。retry_at 不为空时把下次执行时间提前到重试时间，
+    /// 一次性任务执行后已被停用，需要重新启用才能重试。
+    /// scheduled_time 为重试所替代的原计划时间，重试执行后以它为锚点计算下一次常规执行；
+    /// 重试结束（retry_at 为空）时一并清除
+    #[instrument(level = "debug", skip(self), fields(id, retry_attempt))]
+    pub fn set_retry_state(
+        &self,
+        id: i64,
+        retry_attempt: i64,
+        retry_at: Option<DateTime<Utc>>,
+        scheduled_time: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        match retry_at {
+            Some(retry_at) => self.conn.execute(
+                "UPDATE scheduled_task
+                 SET retry_attempt = ?1,
+                     retry_scheduled_time = ?2,
+                     is_enabled = CASE WHEN schedule_type = 'once' THEN 1 ELSE is_enabled END,
+                     next_run_at = CASE WHEN next_run_at IS NULL OR next_run_at > ?3 THEN ?3 ELSE next_run_at END
+                 WHERE id = ?4",
+                params![retry_attempt, scheduled_time, retry_at, id],
+            )?,
+            None => self.conn.execute(
+                "UPDATE scheduled_task SET retry_attempt = ?1, retry_scheduled_time = NULL WHERE id = ?2",
+                params![retry_attempt, id],
+            )?,
+        };
+        Ok(())
+    }
+
     #[instrument(level = "debug", skip(self), fields(id))]
     pub fn delete_task(&self, id: i64) -> Result<()> {
         let _ = self.conn.execute("DELETE FROM scheduled_task_log WHERE task_id = ?", [id]);
@@ -294,7 +442,7 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(self, now), fields(now = %now))]
     pub fn list_due_tasks(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledTask>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time
+            "SELECT id, name, is_enabled, schedule_type, interval_value, interval_unit, start_time, week_days, month_days, run_at, next_run_at, last_run_at, assistant_id, task_prompt, notify_prompt, created_time, updated_time, cron, timezone, max_retries, retry_delay_secs, retry_attempt, retry_scheduled_time
              FROM scheduled_task
              WHERE is_enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?
              ORDER BY next_run_at ASC",
@@ -318,6 +466,12 @@ impl ScheduledTaskDatabase {
                 notify_prompt: row.get(14)?,
                 created_time: get_required_datetime_from_row(row, 15, "created_time")?,
                 updated_time: get_required_datetime_from_row(row, 16, "updated_time")?,
+                cron: row.get(17)?,
+                timezone: row.get(18)?,
+                max_retries: row.get(19)?,
+                retry_delay_secs: row.get(20)?,
+                retry_attempt: row.get(21)?,
+                retry_scheduled_time: get_datetime_from_row(row, 22)?,
             })
         })?;
         let tasks: Vec<ScheduledTask> = rows.collect::<Result<Vec<_>>>()?;
@@ -362,7 +516,7 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(self), fields(task_id, limit))]
     pub fn list_runs_by_task(&self, task_id: i64, limit: u32) -> Result<Vec<ScheduledTaskRun>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, task_id, run_id, status, notify, summary, error_message, started_time, finished_time
+            "SELECT id, task_id, run_id, status, notify, summary, error_message, started_time, finished_time, scheduled_time, timezone, attempt
              FROM scheduled_task_run
              WHERE task_id = ?
              ORDER BY started_time DESC
@@ -379,6 +533,9 @@ impl ScheduledTaskDatabase {
                 error_message: row.get(6)?,
                 started_time: get_required_datetime_from_row(row, 7, "started_time")?,
                 finished_time: get_datetime_from_row(row, 8)?,
+                scheduled_time: get_datetime_from_row(row, 9)?,
+                timezone: row.get(10)?,
+                attempt: row.get(11)?,
             })
         })?;
         let runs: Vec<ScheduledTaskRun> = rows.collect::<Result<Vec<_>>>()?;
@@ -417,8 +574,8 @@ impl ScheduledTaskDatabase {
     #[instrument(level = "debug", skip(self, run), fields(task_id = run.task_id, status = %run.status))]
     pub fn create_run(&self, run: &ScheduledTaskRun) -> Result<ScheduledTaskRun> {
         self.conn.execute(
-            "INSERT INTO scheduled_task_run (task_id, run_id, status, notify, summary, error_message, started_time, finished_time)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO scheduled_task_run (task_id, run_id, status, notify, summary, error_message, started_time, finished_time, scheduled_time, timezone, attempt)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 run.task_id,
                 run.run_id,
@@ -427,7 +584,10 @@ impl ScheduledTaskDatabase {
                 run.summary,
                 run.error_message,
                 run.started_time,
-                run.finished_time
+                run.finished_time,
+                run.scheduled_time,
+                run.timezone,
+                run.attempt
             ],
         )?;
         let id = self.conn.last_insert_rowid();
@@ -5,10 +5,13 @@
 //! association (which skills an assistant can use) is stored here.
 
 use rusqlite::{params, Connection};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use tracing::instrument;
 
+use crate::db::encryption::open_connection;
 use crate::db::get_db_path;
-use crate::skills::types::AssistantSkillConfig;
+use crate::skills::types::{AssistantSkillConfig, GitSkillSource};
 
 pub struct SkillDatabase {
     pub conn: Connection,
@@ -18,7 +21,7 @@ impl SkillDatabase {
     #[instrument(level = "trace", skip(app_handle))]
     pub fn new(app_handle: &tauri::AppHandle) -> rusqlite::Result<Self> {
         let db_path = get_db_path(app_handle, "assistant.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
         Ok(SkillDatabase { conn })
     }
 
@@ -41,6 +44,16 @@ impl SkillDatabase {
             [],
         )?;
 
+        // Per-assistant parameter values (JSON object), added after the initial schema
+        let mut stmt = self.conn.prepare("PRAGMA table_info(assistant_skill_config)")?;
+        let columns: Vec<String> =
+            stmt.query_map([], |row| row.get::<_, String>(1))?.collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+        if !columns.contains(&"parameters".to_string()) {
+            self.conn
+                .execute("ALTER TABLE assistant_skill_config ADD COLUMN parameters TEXT", [])?;
+        }
+
         // Create index for faster lookups
         self.conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_assistant_skill_config_assistant 
@@ -48,6 +61,22 @@ impl SkillDatabase {
             [],
         )?;
 
+        // Git repositories subscribed as skill sources
+        // The checkout lives in {app_data}/skill_sources/git_{id}
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_git_source (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                repo_url TEXT NOT NULL,
+                branch TEXT,
+                auth_token TEXT,
+                last_synced_at DATETIME,
+                last_error TEXT,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+            );",
+            [],
+        )?;
+
         Ok(())
     }
 
@@ -58,22 +87,14 @@ impl SkillDatabase {
         assistant_id: i64,
     ) -> rusqlite::Result<Vec<AssistantSkillConfig>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, assistant_id, skill_identifier, is_enabled, priority, created_time
+            "SELECT id, assistant_id, skill_identifier, is_enabled, priority, created_time,
+                    parameters
              FROM assistant_skill_config
              WHERE assistant_id = ?
              ORDER BY priority ASC, created_time ASC",
         )?;
 
-        let configs = stmt.query_map([assistant_id], |row| {
-            Ok(AssistantSkillConfig {
-                id: row.get(0)?,
-                assistant_id: row.get(1)?,
-                skill_identifier: row.get(2)?,
-                is_enabled: row.get(3)?,
-                priority: row.get(4)?,
-                created_time: row.get(5)?,
-            })
-        })?;
+        let configs = stmt.query_map([assistant_id], Self::skill_config_from_row)?;
 
         let mut result = Vec::new();
         for config in configs {
@@ -89,22 +110,14 @@ impl SkillDatabase {
         assistant_id: i64,
     ) -> rusqlite::Result<Vec<AssistantSkillConfig>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, assistant_id, skill_identifier, is_enabled, priority, created_time
+            "SELECT id, assistant_id, skill_identifier, is_enabled, priority, created_time,
+                    parameters
              FROM assistant_skill_config
              WHERE assistant_id = ? AND is_enabled = 1
              ORDER BY priority ASC, created_time ASC",
         )?;
 
-        let configs = stmt.query_map([assistant_id], |row| {
-            Ok(AssistantSkillConfig {
-                id: row.get(0)?,
-                assistant_id: row.get(1)?,
-                skill_identifier: row.get(2)?,
-                is_enabled: row.get(3)?,
-                priority: row.get(4)?,
-                created_time: row.get(5)?,
-            })
-        })?;
+        let configs = stmt.query_map([assistant_id], Self::skill_config_from_row)?;
 
         let mut result = Vec::new();
         for config in configs {
@@ -113,6 +126,31 @@ impl SkillDatabase {
         Ok(result)
     }
 
+    /// Get enabled skill configs merged along an inheritance chain
+    /// (child first, as returned by `AssistantDatabase::get_inheritance_chain`).
+    /// A child's config for the same skill overrides its base, so a child can
+    /// also disable a skill inherited from the base.
+    #[instrument(level = "trace", skip(self), fields(chain_len = inheritance_chain.len()))]
+    pub fn get_effective_skill_configs(
+        &self,
+        inheritance_chain: &[i64],
+    ) -> rusqlite::Result<Vec<AssistantSkillConfig>> {
+        if let [assistant_id] = inheritance_chain {
+            return self.get_enabled_skill_configs(*assistant_id);
+        }
+
+        let mut merged: Vec<AssistantSkillConfig> = Vec::new();
+        for assistant_id in inheritance_chain.iter().rev() {
+            for config in self.get_assistant_skill_configs(*assistant_id)? {
+                merged.retain(|c| c.skill_identifier != config.skill_identifier);
+                merged.push(config);
+            }
+        }
+        merged.retain(|c| c.is_enabled);
+        merged.sort_by_key(|c| c.priority);
+        Ok(merged)
+    }
+
     /// Add or update a skill config for an assistant
     #[instrument(level = "trace", skip(self), fields(assistant_id, skill_identifier))]
     pub fn upsert_assistant_skill_config(
@@ -156,6 +194,37 @@ impl SkillDatabase {
         }
     }
 
+    /// Replace the parameter values of a skill config
+    #[instrument(level = "trace", skip(self, parameters), fields(id))]
+    pub fn update_skill_config_parameters(
+        &self,
+        id: i64,
+        parameters: &Map<String, Value>,
+    ) -> rusqlite::Result<()> {
+        let parameters =
+            (!parameters.is_empty()).then(|| Value::Object(parameters.clone()).to_string());
+        self.conn.execute(
+            "UPDATE assistant_skill_config SET parameters = ? WHERE id = ?",
+            params![parameters, id],
+        )?;
+        Ok(())
+    }
+
+    fn skill_config_from_row(row: &rusqlite::Row) -> rusqlite::Result<AssistantSkillConfig> {
+        let parameters: Option<String> = row.get(6)?;
+        Ok(AssistantSkillConfig {
+            id: row.get(0)?,
+            assistant_id: row.get(1)?,
+            skill_identifier: row.get(2)?,
+            is_enabled: row.get(3)?,
+            priority: row.get(4)?,
+            created_time: row.get(5)?,
+            parameters: parameters
+                .and_then(|json| serde_json::from_str::<Map<String, Value>>(&json).ok())
+                .unwrap_or_default(),
+        })
+    }
+
     /// Update skill config enabled status
     #[instrument(level = "trace", skip(self), fields(id, is_enabled))]
     pub fn update_skill_config_enabled(&self, id: i64, is_enabled: bool) -> rusqlite::Result<()> {
@@ -204,6 +273,16 @@ impl SkillDatabase {
         assistant_id: i64,
         configs: &[(String, bool, i32)], // (skill_identifier, is_enabled, priority)
     ) -> rusqlite::Result<()> {
+        // Keep parameter values of skills that stay configured
+        let existing_parameters: HashMap<String, Option<String>> = self
+            .conn
+            .prepare(
+                "SELECT skill_identifier, parameters FROM assistant_skill_config
+                 WHERE assistant_id = ?",
+            )?
+            .query_map(params![assistant_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
         // Start transaction
         self.conn.execute("BEGIN TRANSACTION", [])?;
 
@@ -216,12 +295,19 @@ impl SkillDatabase {
         // Insert new configs
         let mut stmt = self.conn.prepare(
             "INSERT INTO assistant_skill_config 
-             (assistant_id, skill_identifier, is_enabled, priority)
-             VALUES (?, ?, ?, ?)",
+             (assistant_id, skill_identifier, is_enabled, priority, parameters)
+             VALUES (?, ?, ?, ?, ?)",
         )?;
 
         for (skill_identifier, is_enabled, priority) in configs {
-            stmt.execute(params![assistant_id, skill_identifier, is_enabled, priority])?;
+            let parameters = existing_parameters.get(skill_identifier).cloned().flatten();
+            stmt.execute(params![
+                assistant_id,
+                skill_identifier,
+                is_enabled,
+                priority,
+                parameters
+            ])?;
         }
 
         // Commit transaction
@@ -244,6 +330,98 @@ impl SkillDatabase {
         Ok(result)
     }
 
+    /// Add a git skill source
+    #[instrument(level = "trace", skip(self, auth_token), fields(repo_url))]
+    pub fn add_git_source(
+        &self,
+        name: &str,
+        repo_url: &str,
+        branch: Option<&str>,
+        auth_token: Option<&str>,
+    ) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO skill_git_source (name, repo_url, branch, auth_token) VALUES (?, ?, ?, ?)",
+            params![name, repo_url, branch, auth_token],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Get all git skill sources
+    pub fn list_git_sources(&self) -> rusqlite::Result<Vec<GitSkillSource>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, repo_url, branch, auth_token, last_synced_at, last_error, created_time
+             FROM skill_git_source
+             ORDER BY id ASC",
+        )?;
+
+        let sources = stmt.query_map([], Self::git_source_from_row)?;
+
+        let mut result = Vec::new();
+        for source in sources {
+            result.push(source?);
+        }
+        Ok(result)
+    }
+
+    /// Get a git skill source by id
+    #[instrument(level = "trace", skip(self), fields(id))]
+    pub fn get_git_source(&self, id: i64) -> rusqlite::Result<Option<GitSkillSource>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, repo_url, branch, auth_token, last_synced_at, last_error, created_time
+             FROM skill_git_source
+             WHERE id = ?",
+        )?;
+
+        match stmt.query_row([id], Self::git_source_from_row) {
+            Ok(source) => Ok(Some(source)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Record a successful clone/pull and clear the last error
+    #[instrument(level = "trace", skip(self), fields(id))]
+    pub fn mark_git_source_synced(&self, id: i64) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE skill_git_source SET last_synced_at = CURRENT_TIMESTAMP, last_error = NULL
+             WHERE id = ?",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed clone/pull, keeping the last successful sync time
+    #[instrument(level = "trace", skip(self, error), fields(id))]
+    pub fn mark_git_source_failed(&self, id: i64, error: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE skill_git_source SET last_error = ? WHERE id = ?",
+            params![error, id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a git skill source
+    #[instrument(level = "trace", skip(self), fields(id))]
+    pub fn delete_git_source(&self, id: i64) -> rusqlite::Result<()> {
+        self.conn.execute("DELETE FROM skill_git_source WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    fn git_source_from_row(row: &rusqlite::Row) -> rusqlite::Result<GitSkillSource> {
+        let auth_token: Option<String> = row.get(4)?;
+        Ok(GitSkillSource {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            repo_url: row.get(2)?,
+            branch: row.get(3)?,
+            has_auth_token: auth_token.as_deref().is_some_and(|token| !token.is_empty()),
+            auth_token,
+            last_synced_at: row.get(5)?,
+            last_error: row.get(6)?,
+            created_time: row.get(7)?,
+        })
+    }
+
     /// Migration: Remove old ClaudeCodeAgents and ClaudeCodeRules skill configs
     /// This should be called once when upgrading to the new skills system
     #[instrument(level = "trace", skip(self))]
@@ -344,6 +522,55 @@ mod tests {
         assert!(!configs.iter().any(|c| c.skill_identifier == "aipp:old_skill"));
     }
 
+    #[test]
+    fn test_git_source_sync_status() {
+        let db = create_test_db();
+
+        let id = db
+            .add_git_source("Team", "https://github.com/acme/skills.git", None, Some("secret"))
+            .unwrap();
+        let source = db.get_git_source(id).unwrap().unwrap();
+        assert!(source.has_auth_token);
+        assert!(source.last_synced_at.is_none());
+        // The token is never serialized to the frontend
+        assert!(!serde_json::to_string(&source).unwrap().contains("secret"));
+
+        db.mark_git_source_synced(id).unwrap();
+        db.mark_git_source_failed(id, "fetch failed").unwrap();
+        let source = db.get_git_source(id).unwrap().unwrap();
+        assert!(source.last_synced_at.is_some());
+        assert_eq!(source.last_error.as_deref(), Some("fetch failed"));
+
+        db.mark_git_source_synced(id).unwrap();
+        assert!(db.get_git_source(id).unwrap().unwrap().last_error.is_none());
+
+        db.delete_git_source(id).unwrap();
+        assert!(db.get_git_source(id).unwrap().is_none());
+        assert!(db.list_git_sources().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_skill_config_parameters_survive_bulk_update() {
+        let db = create_test_db();
+
+        let id = db.upsert_assistant_skill_config(1, "aipp:search", true, 0).unwrap();
+        let parameters = serde_json::json!({ "max_results": 5 }).as_object().unwrap().clone();
+        db.update_skill_config_parameters(id, &parameters).unwrap();
+
+        // Upsert (e.g. toggling) keeps the values
+        db.upsert_assistant_skill_config(1, "aipp:search", false, 0).unwrap();
+        assert_eq!(db.get_assistant_skill_configs(1).unwrap()[0].parameters, parameters);
+
+        db.bulk_update_assistant_skills(
+            1,
+            &[("aipp:search".to_string(), true, 1), ("aipp:other".to_string(), true, 2)],
+        )
+        .unwrap();
+        let configs = db.get_assistant_skill_configs(1).unwrap();
+        assert_eq!(configs[0].parameters, parameters);
+        assert!(configs[1].parameters.is_empty());
+    }
+
     #[test]
     fn test_delete_skill_configs_by_identifier() {
         let db = create_test_db();
@@ -2,6 +2,7 @@ use rusqlite::{params, Connection, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
 
+use super::encryption::open_connection;
 use super::get_db_path;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -22,7 +23,7 @@ impl SystemDatabase {
     #[instrument(level = "debug", skip(app_handle), fields(db = "system.db"))]
     pub fn new(app_handle: &tauri::AppHandle) -> Result<Self> {
         let db_path = get_db_path(app_handle, "system.db");
-        let conn = Connection::open(db_path.unwrap())?;
+        let conn = open_connection(db_path.unwrap())?;
         debug!("Opened system database");
         Ok(SystemDatabase { conn })
     }
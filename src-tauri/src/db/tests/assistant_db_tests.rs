@@ -5,6 +5,7 @@
 //! - AssistantModel 关联操作
 //! - AssistantPrompt 关联操作
 //! - AssistantModelConfig 配置操作
+//! - 助手继承关系与生效配置合并
 //!
 //! ## 测试隔离
 //! 所有测试使用 `Connection::open_in_memory()` 创建内存数据库
@@ -81,6 +82,74 @@ fn create_assistant_test_db() -> Connection {
     )
     .unwrap();
 
+    // 创建 assistant_prompt_param 表
+    conn.execute(
+        "CREATE TABLE assistant_prompt_param (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            assistant_id INTEGER,
+            assistant_prompt_id INTEGER,
+            param_name TEXT NOT NULL,
+            param_type TEXT,
+            param_value TEXT
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建 assistant_mcp_config 表
+    conn.execute(
+        "CREATE TABLE assistant_mcp_config (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            assistant_id INTEGER NOT NULL,
+            mcp_server_id INTEGER NOT NULL,
+            is_enabled BOOLEAN NOT NULL DEFAULT 1,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(assistant_id, mcp_server_id)
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建 assistant_mcp_tool_config 表
+    conn.execute(
+        "CREATE TABLE assistant_mcp_tool_config (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            assistant_id INTEGER NOT NULL,
+            mcp_tool_id INTEGER NOT NULL,
+            is_enabled BOOLEAN NOT NULL DEFAULT 1,
+            is_auto_run BOOLEAN NOT NULL DEFAULT 0,
+            default_arguments TEXT,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(assistant_id, mcp_tool_id)
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建 assistant_starter 表
+    conn.execute(
+        "CREATE TABLE assistant_starter (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            assistant_id INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建 assistant_inheritance 表
+    conn.execute(
+        "CREATE TABLE assistant_inheritance (
+            assistant_id INTEGER PRIMARY KEY,
+            base_assistant_id INTEGER NOT NULL,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
     conn
 }
 
@@ -438,3 +507,212 @@ fn test_assistant_delete_nonexistent_relations() {
     let result = db.delete_assistant_model_config_by_assistant_id(999);
     assert!(result.is_ok());
 }
+
+/// 测试工具默认参数的保存与读取
+///
+/// 验证内容：
+/// - 未配置过的工具设置默认参数后按默认开关状态（启用、不自动执行）新建配置
+/// - 切换启用/自动执行不会清空默认参数
+/// - 传入 None 清空默认参数
+#[test]
+fn test_assistant_mcp_tool_default_arguments() {
+    let db = create_assistant_db();
+
+    assert_eq!(db.get_assistant_mcp_tool_default_arguments(1, 10).unwrap(), None);
+
+    db.set_assistant_mcp_tool_default_arguments(1, 10, Some(r#"{"region":"cn"}"#)).unwrap();
+    let configs = db.get_assistant_mcp_tool_configs(1).unwrap();
+    assert_eq!(configs.len(), 1);
+    assert!(configs[0].is_enabled);
+    assert!(!configs[0].is_auto_run);
+    assert_eq!(configs[0].default_arguments.as_deref(), Some(r#"{"region":"cn"}"#));
+
+    db.upsert_assistant_mcp_tool_config(1, 10, false, true).unwrap();
+    assert_eq!(
+        db.get_assistant_mcp_tool_default_arguments(1, 10).unwrap().as_deref(),
+        Some(r#"{"region":"cn"}"#)
+    );
+    let configs = db.get_assistant_mcp_tool_configs(1).unwrap();
+    assert!(!configs[0].is_enabled);
+    assert!(configs[0].is_auto_run);
+
+    db.set_assistant_mcp_tool_default_arguments(1, 10, None).unwrap();
+    assert_eq!(db.get_assistant_mcp_tool_default_arguments(1, 10).unwrap(), None);
+}
+
+/// 测试对话开场建议的保存与读取
+///
+/// 验证内容：
+/// - 保存后按原顺序读回，空白项被忽略、首尾空白被去除
+/// - 再次保存会整体替换旧的建议
+/// - 不同助手的建议互不影响
+#[test]
+fn test_assistant_starters_round_trip() {
+    let db = create_assistant_db();
+
+    assert!(db.get_assistant_starters(1).unwrap().is_empty());
+
+    let starters = vec![
+        "写一首关于 !assistant_name 的诗".to_string(),
+        "   ".to_string(),
+        "  总结今天 !cd 的新闻 ".to_string(),
+    ];
+    db.set_assistant_starters(1, &starters).unwrap();
+    db.set_assistant_starters(2, &["另一个助手的建议".to_string()]).unwrap();
+
+    assert_eq!(
+        db.get_assistant_starters(1).unwrap(),
+        vec!["写一首关于 !assistant_name 的诗".to_string(), "总结今天 !cd 的新闻".to_string()]
+    );
+
+    db.set_assistant_starters(1, &["新的建议".to_string()]).unwrap();
+    assert_eq!(db.get_assistant_starters(1).unwrap(), vec!["新的建议".to_string()]);
+    assert_eq!(db.get_assistant_starters(2).unwrap(), vec!["另一个助手的建议".to_string()]);
+
+    db.set_assistant_starters(1, &[]).unwrap();
+    assert!(db.get_assistant_starters(1).unwrap().is_empty());
+}
+
+/// 测试助手继承：子助手的生效配置合并基础助手，且拒绝循环继承
+///
+/// 验证内容：
+/// - 子助手的生效提示词为基础助手提示词 + 子助手提示词
+/// - 子助手未选择模型时沿用基础助手的模型，同名模型配置以子助手为准，其余配置继承
+/// - 修改基础助手后子助手的生效配置随之变化
+/// - 继承自身或形成循环继承时被拒绝，原有继承关系不变
+#[test]
+fn test_assistant_inheritance_resolves_effective_config_and_rejects_cycles() {
+    use crate::api::assistant_api::resolve_assistant_detail;
+
+    let db = create_assistant_db();
+
+    let base_id = db.add_assistant("Base", "", Some(0), true).unwrap();
+    let base_prompt_id = db.add_assistant_prompt(base_id, "You are a senior engineer.").unwrap();
+    let base_model_id = db.add_assistant_model(base_id, 1, "gpt-4o", "").unwrap();
+    db.add_assistant_model_config(base_id, base_model_id, "temperature", "0.2", "float").unwrap();
+    db.add_assistant_model_config(base_id, base_model_id, "max_tokens", "4000", "number").unwrap();
+    db.upsert_assistant_mcp_tool_config(base_id, 10, true, true).unwrap();
+
+    let child_id = db.add_assistant("Child", "", Some(0), true).unwrap();
+    db.add_assistant_prompt(child_id, "Always answer in Rust.").unwrap();
+    let child_model_id = db.add_assistant_model(child_id, 0, "", "").unwrap();
+    db.add_assistant_model_config(child_id, child_model_id, "temperature", "0.9", "float").unwrap();
+    db.set_base_assistant(child_id, Some(base_id)).unwrap();
+
+    assert_eq!(db.get_inheritance_chain(child_id).unwrap(), vec![child_id, base_id]);
+    assert_eq!(db.get_child_assistant_ids(base_id).unwrap(), vec![child_id]);
+
+    let effective = resolve_assistant_detail(&db, child_id).unwrap();
+    assert_eq!(effective.assistant.id, child_id);
+    assert_eq!(effective.base_assistant_id, Some(base_id));
+    assert_eq!(effective.prompts[0].prompt, "You are a senior engineer.\n\nAlways answer in Rust.");
+    assert_eq!(effective.model[0].model_code, "gpt-4o");
+    let config = |name: &str| {
+        effective.model_configs.iter().find(|c| c.name == name).and_then(|c| c.value.clone())
+    };
+    assert_eq!(config("temperature").as_deref(), Some("0.9"));
+    assert_eq!(config("max_tokens").as_deref(), Some("4000"));
+    assert_eq!(effective.mcp_tool_configs.len(), 1);
+    assert!(effective.mcp_tool_configs[0].is_auto_run);
+
+    // 修改基础助手会传递给子助手
+    db.update_assistant_prompt(base_prompt_id, "You are a staff engineer.").unwrap();
+    let effective = resolve_assistant_detail(&db, child_id).unwrap();
+    assert!(effective.prompts[0].prompt.starts_with("You are a staff engineer."));
+
+    // 基础助手本身不受子助手影响
+    let base = resolve_assistant_detail(&db, base_id).unwrap();
+    assert_eq!(base.prompts[0].prompt, "You are a staff engineer.");
+
+    // 循环继承被拒绝
+    let grandchild_id = db.add_assistant("Grandchild", "", Some(0), true).unwrap();
+    db.set_base_assistant(grandchild_id, Some(child_id)).unwrap();
+    assert!(db.set_base_assistant(base_id, Some(grandchild_id)).is_err());
+    assert!(db.set_base_assistant(base_id, Some(base_id)).is_err());
+    assert_eq!(db.get_base_assistant_id(base_id).unwrap(), None);
+    assert_eq!(
+        db.get_inheritance_chain(grandchild_id).unwrap(),
+        vec![grandchild_id, child_id, base_id]
+    );
+
+    // 解除继承后只剩自身配置
+    db.set_base_assistant(child_id, None).unwrap();
+    let standalone = resolve_assistant_detail(&db, child_id).unwrap();
+    assert_eq!(standalone.prompts[0].prompt, "Always answer in Rust.");
+    assert_eq!(standalone.model[0].model_code, "");
+}
+
+/// 测试子助手未设置的配置沿用基础助手的设置（组装请求时读取的都是生效配置）
+///
+/// 验证内容：
+/// - 基础助手设置的模型配置，子助手未设置时可以读到
+/// - 基础助手启用的 MCP 服务器与工具，子助手的工具列表中同样启用
+/// - 子助手的工具配置未设置默认参数时沿用基础助手的默认参数
+#[test]
+fn test_child_assistant_sees_config_set_only_on_base() {
+    use crate::api::assistant_api::{mcp_servers_with_tools_for_detail, resolve_assistant_detail};
+    use crate::db::mcp_db::MCPDatabase;
+
+    let mcp_db = MCPDatabase { conn: Connection::open_in_memory().unwrap() };
+    mcp_db.create_tables().unwrap();
+    let server_id = mcp_db
+        .upsert_mcp_server_with_builtin(
+            "search",
+            None,
+            "stdio",
+            Some("npx search"),
+            None,
+            None,
+            None,
+            None,
+            false,
+            true,
+            false,
+            true,
+            false,
+        )
+        .unwrap();
+    let tool_id =
+        mcp_db.upsert_mcp_server_tool(server_id, "web_search", Some("Search"), Some("{}")).unwrap();
+    let db = AssistantDatabase { conn: create_assistant_test_db(), mcp_conn: mcp_db.conn };
+
+    let base_id = db.add_assistant("Base", "", Some(0), true).unwrap();
+    let base_model_id = db.add_assistant_model(base_id, 1, "gpt-4o", "").unwrap();
+    db.add_assistant_model_config(base_id, base_model_id, "response_language", "中文", "string")
+        .unwrap();
+    db.upsert_assistant_mcp_config(base_id, server_id, true).unwrap();
+    db.upsert_assistant_mcp_tool_config(base_id, tool_id, true, true).unwrap();
+    db.set_assistant_mcp_tool_default_arguments(base_id, tool_id, Some(r#"{"region":"cn"}"#))
+        .unwrap();
+
+    let child_id = db.add_assistant("Child", "", Some(0), true).unwrap();
+    db.add_assistant_model(child_id, 0, "", "").unwrap();
+    // 子助手只改了自动运行，没有设置默认参数
+    db.upsert_assistant_mcp_tool_config(child_id, tool_id, true, false).unwrap();
+    db.set_base_assistant(child_id, Some(base_id)).unwrap();
+
+    assert!(db.get_assistant_model_configs(child_id).unwrap().is_empty());
+    assert!(db.get_assistant_mcp_configs(child_id).unwrap().is_empty());
+
+    let effective = resolve_assistant_detail(&db, child_id).unwrap();
+    let language = effective
+        .model_configs
+        .iter()
+        .find(|c| c.name == "response_language")
+        .and_then(|c| c.value.as_deref());
+    assert_eq!(language, Some("中文"));
+
+    let tool_config = effective.mcp_tool_configs.iter().find(|c| c.mcp_tool_id == tool_id).unwrap();
+    assert!(!tool_config.is_auto_run);
+    assert_eq!(tool_config.default_arguments.as_deref(), Some(r#"{"region":"cn"}"#));
+
+    let servers = mcp_servers_with_tools_for_detail(&db, &effective).unwrap();
+    let server = servers.iter().find(|s| s.id == server_id).unwrap();
+    assert!(server.is_enabled);
+    assert!(server.tools[0].is_enabled);
+    assert!(!server.tools[0].is_auto_run);
+
+    // 只读子助手自身配置时看不到基础助手启用的服务器
+    let own = db.get_assistant_mcp_servers_with_tools(child_id).unwrap();
+    assert!(!own.iter().find(|s| s.0 == server_id).unwrap().3);
+}
@@ -7,6 +7,7 @@
 //! - 通过 hash 查询附件
 //! - 批量查询附件
 //! - 附件与消息的关联
+//! - 图片附件的 OCR 结果
 
 use crate::db::conversation_db::*;
 use crate::db::tests::test_helpers::*;
@@ -487,3 +488,32 @@ fn test_multiple_attachments_per_message() {
     let all = repo.list_by_id(&ids).unwrap();
     assert_eq!(all.len(), 3);
 }
+
+// ============================================================================
+// OCR 结果测试
+// ============================================================================
+
+#[test]
+fn test_attachment_ocr_results() {
+    let (repo, message_id) = create_attachment_test_db();
+    let image = repo.create(&create_test_attachment(message_id, AttachmentType::Image)).unwrap();
+    let failed = repo.create(&create_test_attachment(message_id, AttachmentType::Image)).unwrap();
+    let pending = repo.create(&create_test_attachment(message_id, AttachmentType::Image)).unwrap();
+
+    repo.save_ocr_result(image.id, ATTACHMENT_OCR_STATUS_DONE, Some("旧结果"), None).unwrap();
+    // 重新识别覆盖旧结果
+    repo.save_ocr_result(image.id, ATTACHMENT_OCR_STATUS_DONE, Some("Error: disk full"), None)
+        .unwrap();
+    repo.save_ocr_result(
+        failed.id,
+        ATTACHMENT_OCR_STATUS_FAILED,
+        None,
+        Some("tesseract not found"),
+    )
+    .unwrap();
+
+    let texts = repo.list_ocr_texts(&[image.id, failed.id, pending.id]).unwrap();
+    assert_eq!(texts.len(), 1);
+    assert_eq!(texts.get(&image.id).map(String::as_str), Some("Error: disk full"));
+    assert!(repo.list_ocr_texts(&[]).unwrap().is_empty());
+}
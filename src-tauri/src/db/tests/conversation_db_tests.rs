@@ -75,6 +75,47 @@ fn test_conversation_list() {
     assert_eq!(conversations.len(), 3);
 }
 
+/// 测试对话标签
+///
+/// 验证内容：
+/// - 添加标签后可按标签筛选对话，重复添加不产生重复关联
+/// - 分支对话时通过 copy_tags 继承标签
+/// - 删除标签只移除关联，对话本身保留
+#[test]
+fn test_conversation_tags() {
+    let conn = create_test_db();
+    let repo = ConversationRepository::new(conn);
+    let work = create_test_conversation(&repo);
+    let personal = create_test_conversation(&repo);
+
+    repo.add_tag(work.id, "work").unwrap();
+    repo.add_tag(work.id, "work").unwrap();
+    repo.add_tag(work.id, "experiments").unwrap();
+    repo.add_tag(personal.id, "personal").unwrap();
+
+    let tagged = repo.list_by_tag(1, 10, "work").unwrap();
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].id, work.id);
+
+    let tags = repo.list_tags_by_conversation_ids(&[work.id, personal.id]).unwrap();
+    assert_eq!(tags[&work.id], vec!["experiments", "work"]);
+    assert_eq!(tags[&personal.id], vec!["personal"]);
+
+    let fork = create_test_conversation(&repo);
+    repo.copy_tags(work.id, fork.id).unwrap();
+    assert_eq!(repo.list_by_tag(1, 10, "work").unwrap().len(), 2);
+
+    repo.remove_tag(work.id, "experiments").unwrap();
+    let work_tag = repo.list_tags().unwrap().into_iter().find(|t| t.name == "work").unwrap();
+    assert_eq!(work_tag.conversation_count, 2);
+
+    repo.delete_tag(work_tag.id).unwrap();
+    assert!(repo.list_by_tag(1, 10, "work").unwrap().is_empty());
+    assert!(repo.read(work.id).unwrap().is_some());
+    assert!(repo.read(fork.id).unwrap().is_some());
+    assert_eq!(repo.list_tags().unwrap().len(), 2);
+}
+
 /// 测试不关联助手的对话
 ///
 /// 验证内容：
@@ -331,3 +372,338 @@ fn test_conversation_special_characters() {
         assert_eq!(read.name, name);
     }
 }
+
+// ============================================================================
+// 使用情况汇总测试
+// ============================================================================
+
+/// 写入一条对话，返回对话 ID
+fn seed_conversation(
+    conn: &rusqlite::Connection,
+    assistant_id: i64,
+    created_time: chrono::DateTime<chrono::Utc>,
+) -> i64 {
+    conn.execute(
+        "INSERT INTO conversation (name, assistant_id, created_time) VALUES ('seed', ?1, ?2)",
+        rusqlite::params![assistant_id, created_time],
+    )
+    .unwrap();
+    conn.last_insert_rowid()
+}
+
+/// 写入一条消息
+fn seed_message(
+    conn: &rusqlite::Connection,
+    conversation_id: i64,
+    message_type: &str,
+    model_name: Option<&str>,
+    created_time: chrono::DateTime<chrono::Utc>,
+) {
+    conn.execute(
+        "INSERT INTO message (conversation_id, message_type, content, llm_model_name, created_time, token_count)
+         VALUES (?1, ?2, 'seed', ?3, ?4, 10)",
+        rusqlite::params![conversation_id, message_type, model_name, created_time],
+    )
+    .unwrap();
+}
+
+/// 测试使用情况汇总能正确识别最常用的助手和模型
+///
+/// 验证内容：
+/// - 助手按活跃对话数排序，模型按回复数排序
+/// - 平均对话长度与活跃时段按窗口内消息计算
+/// - 窗口之外的旧数据不计入统计
+#[test]
+fn test_usage_insights_identifies_top_assistant_and_model() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let conn = create_test_db();
+    let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+    let morning = Utc.with_ymd_and_hms(2024, 5, 30, 9, 15, 0).unwrap();
+
+    // 助手 1：两个对话，均使用 gpt-4o
+    for _ in 0..2 {
+        let id = seed_conversation(&conn, 1, morning);
+        seed_message(&conn, id, "user", None, morning);
+        seed_message(&conn, id, "response", Some("gpt-4o"), morning);
+    }
+    // 助手 2：一个较长的对话，使用 claude 与 gpt-4o
+    let id = seed_conversation(&conn, 2, now);
+    seed_message(&conn, id, "user", None, now);
+    seed_message(&conn, id, "response", Some("claude"), now);
+    seed_message(&conn, id, "user", None, now);
+    seed_message(&conn, id, "response", Some("gpt-4o"), now);
+    // 窗口之外：助手 3 的大量旧消息不应影响排行
+    let old = now - Duration::days(90);
+    let id = seed_conversation(&conn, 3, old);
+    for _ in 0..5 {
+        seed_message(&conn, id, "response", Some("old-model"), old);
+    }
+
+    let insights = query_usage_insights(&conn, Some(now - Duration::days(7)), 0, 10).unwrap();
+
+    assert_eq!(insights.conversation_count, 3);
+    assert_eq!(insights.message_count, 8);
+    assert!((insights.avg_messages_per_conversation - 8.0 / 3.0).abs() < 1e-9);
+
+    assert_eq!(insights.top_assistants[0].assistant_id, Some(1));
+    assert_eq!(insights.top_assistants[0].conversation_count, 2);
+    assert!(insights.top_assistants.iter().all(|a| a.assistant_id != Some(3)));
+
+    assert_eq!(insights.top_models[0].model_name, "gpt-4o");
+    assert_eq!(insights.top_models[0].response_count, 3);
+    assert!(insights.top_models.iter().all(|m| m.model_name != "old-model"));
+
+    // 9 点与 12 点各两条用户消息，并列时取较早的小时
+    assert_eq!(insights.busiest_hour, Some(9));
+    assert_eq!(
+        insights.hourly_activity,
+        vec![HourlyUsage { hour: 9, message_count: 2 }, HourlyUsage { hour: 12, message_count: 2 }]
+    );
+
+    // 不限窗口时旧数据计入，old-model 成为回复数最多的模型
+    let all_time = query_usage_insights(&conn, None, 0, 10).unwrap();
+    assert_eq!(all_time.conversation_count, 4);
+    assert_eq!(all_time.top_models[0].model_name, "old-model");
+}
+
+/// 测试按天导出用量报表 CSV
+///
+/// 验证内容：
+/// - 表头包含日期、提供商、模型、助手、token 数与估算费用列
+/// - 同一天同一助手同一模型的消息聚合为一行，费用按模型单价估算
+/// - 包含逗号和引号的助手名称按 CSV 规则转义，缺失的名称与单价留空
+#[test]
+fn test_export_usage_report_csv_over_seeded_usage() {
+    use crate::api::token_statistics_api::{
+        UsageReportFormat, UsageReportLabels, UsageReportWriter,
+    };
+    use crate::db::llm_db::LLMModelPrice;
+    use crate::errors::AppError;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    let conn = create_test_db();
+    let first_day = Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap();
+    let second_day = Utc.with_ymd_and_hms(2024, 6, 2, 10, 0, 0).unwrap();
+
+    let id = seed_conversation(&conn, 1, first_day);
+    seed_message(&conn, id, "user", None, first_day);
+    seed_message(&conn, id, "response", Some("gpt-4o"), first_day);
+    seed_message(&conn, id, "response", Some("gpt-4o"), first_day);
+    conn.execute(
+        "UPDATE message SET llm_model_id = 7, input_token_count = 1000, output_token_count = 500
+         WHERE llm_model_name = 'gpt-4o'",
+        [],
+    )
+    .unwrap();
+    let id = seed_conversation(&conn, 2, second_day);
+    seed_message(&conn, id, "response", Some("claude"), second_day);
+
+    let labels = UsageReportLabels {
+        providers_by_model: HashMap::from([(7, "OpenAI".to_string())]),
+        assistants: HashMap::from([(1, "写作, \"助手\"".to_string())]),
+        pricing: HashMap::from([(
+            "gpt-4o".to_string(),
+            LLMModelPrice {
+                model_code: "gpt-4o".to_string(),
+                input_price_per_1k: 0.0025,
+                output_price_per_1k: 0.01,
+            },
+        )]),
+    };
+
+    let mut output = Vec::new();
+    let mut writer = UsageReportWriter::new(&mut output, UsageReportFormat::Csv).unwrap();
+    let rows = for_each_usage_report_row::<AppError, _>(
+        &conn,
+        None,
+        None,
+        UsageReportGroupBy::Day,
+        0,
+        |row| Ok(writer.write_entry(&labels.entry(row))?),
+    )
+    .unwrap();
+    assert_eq!(rows, 2);
+    assert_eq!(writer.finish().unwrap(), 2);
+
+    let csv = String::from_utf8(output).unwrap();
+    assert!(csv.starts_with('\u{feff}'));
+    let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').lines().collect();
+    assert_eq!(
+        lines[0],
+        "date,provider,model,assistant,response_count,input_tokens,output_tokens,total_tokens,estimated_cost"
+    );
+    assert_eq!(lines[1], "2024-06-01,OpenAI,gpt-4o,\"写作, \"\"助手\"\"\",2,2000,1000,20,0.015000");
+    assert_eq!(lines[2], "2024-06-02,,claude,,1,0,0,10,");
+    assert_eq!(lines.len(), 3);
+}
+
+/// 测试跨越窗口边界的对话只统计窗口内的消息
+///
+/// 验证内容：
+/// - 对话创建于窗口之前，但窗口内的消息仍计入
+/// - 同一对话中窗口之前的消息不计入
+#[test]
+fn test_usage_report_counts_messages_across_window_boundary() {
+    use crate::db::conversation_db::{for_each_usage_report_row, UsageReportGroupBy};
+    use crate::errors::AppError;
+    use chrono::{TimeZone, Utc};
+
+    let conn = create_test_db();
+    let before = Utc.with_ymd_and_hms(2024, 6, 1, 23, 0, 0).unwrap();
+    let since = Utc.with_ymd_and_hms(2024, 6, 2, 0, 0, 0).unwrap();
+    let inside = Utc.with_ymd_and_hms(2024, 6, 2, 9, 0, 0).unwrap();
+
+    let id = seed_conversation(&conn, 1, before);
+    seed_message(&conn, id, "response", Some("gpt-4o"), before);
+    seed_message(&conn, id, "response", Some("gpt-4o"), inside);
+
+    let mut rows = Vec::new();
+    for_each_usage_report_row::<AppError, _>(
+        &conn,
+        Some(since),
+        None,
+        UsageReportGroupBy::Day,
+        0,
+        |row| {
+            rows.push(row);
+            Ok(())
+        },
+    )
+    .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].period, "2024-06-02");
+    assert_eq!(rows[0].response_count, 1);
+    assert_eq!(rows[0].total_tokens, 10);
+}
+
+// ============================================================================
+// 对话自动总结触发条件测试
+// ============================================================================
+
+/// 测试自动总结只挑选满足触发阈值的对话
+///
+/// 验证内容：
+/// - 消息数低于阈值的对话被跳过
+/// - 仍在活跃（未达到空闲时间）的对话被跳过
+/// - 已有总结或正在总结的对话被跳过
+#[test]
+fn test_list_conversations_needing_summary_honors_thresholds() {
+    use chrono::{Duration, Utc};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = ConversationDatabase::from_path(temp_dir.path().join("conversation.db"));
+    db.create_tables().unwrap();
+    let conn = db.get_connection().unwrap();
+
+    let idle = Utc::now() - Duration::hours(1);
+    let seed = |count: usize, time| {
+        let id = seed_conversation(&conn, 1, time);
+        for i in 0..count {
+            seed_message(&conn, id, if i % 2 == 0 { "user" } else { "response" }, None, time);
+        }
+        id
+    };
+    let long_id = seed(60, idle);
+    let short_id = seed(20, idle);
+    let active_id = seed(60, Utc::now());
+
+    let repo = db.conversation_summary_repo().unwrap();
+    let criteria = SummaryTriggerCriteria { idle_seconds: 600, min_messages: 50, min_tokens: 0 };
+    assert_eq!(repo.list_conversations_needing_summary(&criteria, &[], 10).unwrap(), vec![long_id]);
+
+    // 不要求空闲时，活跃对话也会被总结；token 阈值按消息 token_count 累加
+    let criteria = SummaryTriggerCriteria { idle_seconds: 0, min_messages: 0, min_tokens: 300 };
+    assert_eq!(
+        repo.list_conversations_needing_summary(&criteria, &[], 10).unwrap(),
+        vec![active_id, long_id]
+    );
+    assert_eq!(
+        repo.list_conversations_needing_summary(&criteria, &[active_id], 10).unwrap(),
+        vec![long_id]
+    );
+
+    repo.create(&ConversationSummary {
+        id: 0,
+        conversation_id: long_id,
+        summary: "done".to_string(),
+        user_intent: String::new(),
+        key_outcomes: String::new(),
+        created_time: Utc::now(),
+    })
+    .unwrap();
+    let criteria = SummaryTriggerCriteria { idle_seconds: 600, min_messages: 0, min_tokens: 0 };
+    assert_eq!(
+        repo.list_conversations_needing_summary(&criteria, &[], 10).unwrap(),
+        vec![short_id]
+    );
+}
+
+// ============================================================================
+// ACP 会话持久化测试
+// ============================================================================
+
+/// 测试 ACP 会话元数据的保存、授权记录与失效标记
+///
+/// 验证内容：
+/// - upsert 保存工作目录与 CLI 命令，状态为 active
+/// - "始终允许"授权去重保存，重新 upsert 后保留
+/// - 标记 stale 后再次 upsert 恢复为 active
+#[test]
+fn test_acp_session_persistence() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = ConversationDatabase::from_path(temp_dir.path().join("conversation.db"));
+    db.create_tables().unwrap();
+
+    assert!(db.get_acp_session(1).unwrap().is_none());
+
+    db.upsert_acp_session(1, "session-a", "/tmp/project", "claude-code-acp").unwrap();
+    db.add_acp_permission_grant(1, "Execute:npm test").unwrap();
+    db.add_acp_permission_grant(1, "Execute:npm test").unwrap();
+
+    let record = db.get_acp_session(1).unwrap().unwrap();
+    assert_eq!(record.session_id, "session-a");
+    assert_eq!(record.working_directory.as_deref(), Some("/tmp/project"));
+    assert_eq!(record.cli_command.as_deref(), Some("claude-code-acp"));
+    assert_eq!(record.status, ACP_SESSION_STATUS_ACTIVE);
+    assert_eq!(record.permission_grants, vec!["Execute:npm test".to_string()]);
+
+    db.mark_acp_session_stale(1).unwrap();
+    assert_eq!(db.get_acp_session(1).unwrap().unwrap().status, ACP_SESSION_STATUS_STALE);
+
+    db.upsert_acp_session(1, "session-b", "/tmp/project", "claude-code-acp").unwrap();
+    let record = db.get_acp_session(1).unwrap().unwrap();
+    assert_eq!(record.session_id, "session-b");
+    assert_eq!(record.status, ACP_SESSION_STATUS_ACTIVE);
+    assert_eq!(record.permission_grants, vec!["Execute:npm test".to_string()]);
+}
+
+/// 测试旧版 acp_session 表迁移后可读取，缺失的元数据为空
+#[test]
+fn test_acp_session_legacy_table_migration() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("conversation.db");
+    {
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE acp_session (
+                conversation_id INTEGER PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                updated_time DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO acp_session (conversation_id, session_id) VALUES (7, 'legacy');",
+        )
+        .unwrap();
+    }
+
+    let db = ConversationDatabase::from_path(db_path);
+    db.create_tables().unwrap();
+
+    let record = db.get_acp_session(7).unwrap().unwrap();
+    assert_eq!(record.session_id, "legacy");
+    assert_eq!(record.working_directory, None);
+    assert_eq!(record.status, ACP_SESSION_STATUS_ACTIVE);
+    assert!(record.permission_grants.is_empty());
+}
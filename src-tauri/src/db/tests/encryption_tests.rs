@@ -0,0 +1,120 @@
+//! 数据库加密状态与明文检测测试
+//!
+//! 状态文件读写、明文库检测以及未启用时的初始化行为不依赖 SQLCipher；
+//! 加密迁移、重新打开与密钥错误的测试只在启用 sqlcipher feature 时运行
+
+use crate::db::encryption::*;
+use rusqlite::Connection;
+use tempfile::tempdir;
+
+/// 测试明文 SQLite 文件能被识别，空文件与非 SQLite 文件不会被当作待迁移的明文库
+#[test]
+fn test_is_plaintext_database() {
+    let dir = tempdir().unwrap();
+
+    let db_path = dir.path().join("plain.db");
+    let conn = Connection::open(&db_path).unwrap();
+    conn.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY);").unwrap();
+    drop(conn);
+    assert!(is_plaintext_database(&db_path));
+
+    let empty_path = dir.path().join("empty.db");
+    std::fs::write(&empty_path, b"").unwrap();
+    assert!(!is_plaintext_database(&empty_path));
+
+    let random_path = dir.path().join("random.db");
+    std::fs::write(&random_path, [0x5Au8; 64]).unwrap();
+    assert!(!is_plaintext_database(&random_path));
+
+    assert!(!is_plaintext_database(&dir.path().join("missing.db")));
+}
+
+/// 测试加密状态文件的读写，缺失时视为未启用
+#[test]
+fn test_encryption_state_roundtrip() {
+    let dir = tempdir().unwrap();
+    assert_eq!(load_state(dir.path()).unwrap(), None);
+
+    let state = EncryptionState { enabled: true, key_source: KeySource::Keychain };
+    save_state(dir.path(), &state).unwrap();
+    assert_eq!(load_state(dir.path()).unwrap(), Some(state));
+
+    std::fs::write(dir.path().join(ENCRYPTION_STATE_FILE), "not json").unwrap();
+    assert!(load_state(dir.path()).unwrap_err().contains(ENCRYPTION_STATE_FILE));
+}
+
+/// 测试未启用加密时初始化不做任何改动，连接按明文打开
+#[test]
+fn test_init_without_encryption_keeps_plaintext() {
+    let dir = tempdir().unwrap();
+    let db_path = dir.path().join("system.db");
+    Connection::open(&db_path).unwrap().execute_batch("CREATE TABLE t (id INTEGER);").unwrap();
+
+    let state = EncryptionState { enabled: false, key_source: KeySource::Keychain };
+    save_state(dir.path(), &state).unwrap();
+    init(dir.path()).unwrap();
+
+    assert!(is_plaintext_database(&db_path));
+    let conn = open_connection(&db_path).unwrap();
+    let count: i64 = conn.query_row("SELECT count(*) FROM t", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 0);
+}
+
+/// 测试未编译 SQLCipher 时，已启用加密的数据目录给出明确错误而不是按明文打开
+#[cfg(not(feature = "sqlcipher"))]
+#[test]
+fn test_init_fails_clearly_without_sqlcipher() {
+    let dir = tempdir().unwrap();
+    let state = EncryptionState { enabled: true, key_source: KeySource::Keychain };
+    save_state(dir.path(), &state).unwrap();
+
+    let err = init(dir.path()).unwrap_err();
+    assert!(err.contains("SQLCipher"));
+    assert!(enable(tempdir().unwrap().path(), KeySource::Keychain, None).is_err());
+}
+
+/// 测试明文库原地加密后保留数据与 user_version，正确密钥可以重新打开，错误密钥给出明确错误
+#[cfg(feature = "sqlcipher")]
+#[test]
+fn test_encrypt_in_place_and_reopen() {
+    let dir = tempdir().unwrap();
+    let db_path = dir.path().join("conversation.db");
+    let conn = Connection::open(&db_path).unwrap();
+    conn.execute_batch(
+        "PRAGMA journal_mode=WAL; CREATE TABLE t (name TEXT); INSERT INTO t VALUES ('hello');
+         PRAGMA user_version = 3;",
+    )
+    .unwrap();
+    drop(conn);
+
+    let key = "correct horse battery staple";
+    encrypt_in_place(&db_path, key).unwrap();
+    assert!(!is_plaintext_database(&db_path));
+    assert!(!dir.path().join("conversation.db.encrypting").exists());
+
+    let conn = open_with_key(&db_path, key).unwrap();
+    let name: String = conn.query_row("SELECT name FROM t", [], |row| row.get(0)).unwrap();
+    assert_eq!(name, "hello");
+    let user_version: i64 =
+        conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+    assert_eq!(user_version, 3);
+    drop(conn);
+
+    let err = open_with_key(&db_path, "wrong passphrase").unwrap_err();
+    assert!(err.to_string().contains("数据库密钥错误或缺失"));
+    // 不带密钥按明文打开时无法读取
+    let conn = Connection::open(&db_path).unwrap();
+    assert!(conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())).is_err());
+}
+
+/// 测试未启用加密时不会改动 db 目录之外的明文数据库
+#[cfg(feature = "sqlcipher")]
+#[test]
+fn test_encrypt_if_plaintext_without_active_key() {
+    let dir = tempdir().unwrap();
+    let db_path = dir.path().join("artifact.db");
+    Connection::open(&db_path).unwrap().execute_batch("CREATE TABLE t (id INTEGER);").unwrap();
+
+    encrypt_if_plaintext(&db_path).unwrap();
+    assert!(is_plaintext_database(&db_path));
+}
@@ -5,6 +5,7 @@
 //! - LLM Model 操作
 //! - LLM Provider Config 配置操作
 //! - Model Detail 查询
+//! - 模型单价配置
 //!
 //! ## 测试隔离
 //! 所有测试使用 `Connection::open_in_memory()` 创建内存数据库
@@ -70,6 +71,33 @@ fn create_llm_test_db() -> Connection {
     )
     .unwrap();
 
+    // 创建 llm_environment_profile 表
+    conn.execute(
+        "CREATE TABLE llm_environment_profile (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            enabled_provider_ids TEXT NOT NULL DEFAULT '[]',
+            default_provider_id INTEGER,
+            default_model_code TEXT,
+            is_active BOOLEAN NOT NULL DEFAULT 0,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建 llm_model_price 表
+    conn.execute(
+        "CREATE TABLE llm_model_price (
+            model_code TEXT PRIMARY KEY,
+            input_price_per_1k REAL NOT NULL DEFAULT 0,
+            output_price_per_1k REAL NOT NULL DEFAULT 0,
+            updated_time DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
     conn
 }
 
@@ -515,3 +543,103 @@ fn test_llm_model_media_support_flags() {
     let all_model = models.iter().find(|m| m.3 == "all").unwrap();
     assert!(all_model.5 && all_model.6 && all_model.7);
 }
+
+// ============================================================================
+// 环境配置测试
+// ============================================================================
+
+/// 测试切换环境配置后启用的提供商与可选模型随之变化
+///
+/// 验证内容：
+/// - 切换环境只启用该环境列出的提供商
+/// - 可选模型列表只包含已启用提供商的模型
+/// - 同一时间只有一个环境处于激活状态
+#[test]
+fn test_switch_environment_profile_changes_active_providers() {
+    let db = create_llm_db();
+    db.add_llm_provider("Ollama", "ollama", "Local", false, true).unwrap();
+    db.add_llm_provider("OpenAI", "openai_api", "Cloud", false, true).unwrap();
+    let providers = db.get_llm_providers().unwrap();
+    let local_id = providers.iter().find(|p| p.1 == "Ollama").unwrap().0;
+    let cloud_id = providers.iter().find(|p| p.1 == "OpenAI").unwrap().0;
+    db.add_llm_model("Qwen", local_id, "qwen3:8b", "", false, false, false).unwrap();
+    db.add_llm_model("GPT-4o", cloud_id, "gpt-4o", "", true, false, false).unwrap();
+
+    let local_profile =
+        db.add_environment_profile("local", &[local_id], Some(local_id), Some("qwen3:8b")).unwrap();
+    let cloud_profile =
+        db.add_environment_profile("cloud", &[cloud_id], Some(cloud_id), Some("gpt-4o")).unwrap();
+
+    let applied = db.apply_environment_profile(local_profile).unwrap();
+    assert!(applied.is_active);
+    assert_eq!(applied.default_model_code.as_deref(), Some("qwen3:8b"));
+    assert!(db.get_llm_provider(local_id).unwrap().is_enabled);
+    assert!(!db.get_llm_provider(cloud_id).unwrap().is_enabled);
+    let models: Vec<String> =
+        db.get_models_for_select().unwrap().into_iter().map(|m| m.1).collect();
+    assert_eq!(models, vec!["qwen3:8b".to_string()]);
+
+    db.apply_environment_profile(cloud_profile).unwrap();
+    assert!(!db.get_llm_provider(local_id).unwrap().is_enabled);
+    assert!(db.get_llm_provider(cloud_id).unwrap().is_enabled);
+    let models: Vec<String> =
+        db.get_models_for_select().unwrap().into_iter().map(|m| m.1).collect();
+    assert_eq!(models, vec!["gpt-4o".to_string()]);
+
+    let active: Vec<String> = db
+        .get_environment_profiles()
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.is_active)
+        .map(|p| p.name)
+        .collect();
+    assert_eq!(active, vec!["cloud".to_string()]);
+}
+
+/// 测试环境配置的增删改
+#[test]
+fn test_environment_profile_crud() {
+    let db = create_llm_db();
+    let id = db.add_environment_profile("dev", &[1, 2], None, None).unwrap();
+
+    let profile = db.get_environment_profile(id).unwrap();
+    assert_eq!(profile.enabled_provider_ids, vec![1, 2]);
+    assert!(!profile.is_active);
+
+    db.update_environment_profile(id, "prod", &[3], Some(3), Some("gpt-4o")).unwrap();
+    let profile = db.get_environment_profile(id).unwrap();
+    assert_eq!(profile.name, "prod");
+    assert_eq!(profile.enabled_provider_ids, vec![3]);
+    assert_eq!(profile.default_provider_id, Some(3));
+
+    db.delete_environment_profile(id).unwrap();
+    assert!(db.get_environment_profile(id).is_err());
+    assert!(db.apply_environment_profile(id).is_err());
+}
+
+/// 测试模型单价的写入、覆盖、删除以及费用估算
+#[test]
+fn test_model_price_upsert_and_estimate() {
+    let db = create_llm_db();
+    let price = LLMModelPrice {
+        model_code: "gpt-4o".to_string(),
+        input_price_per_1k: 0.0025,
+        output_price_per_1k: 0.01,
+    };
+    db.upsert_model_price(&price).unwrap();
+    assert_eq!(db.get_model_prices().unwrap(), vec![price.clone()]);
+    assert_eq!(db.get_model_price("gpt-4o").unwrap(), Some(price.clone()));
+    assert_eq!(db.get_model_price("unknown").unwrap(), None);
+
+    let updated = LLMModelPrice { input_price_per_1k: 0.005, ..price };
+    db.upsert_model_price(&updated).unwrap();
+    let prices = db.get_model_prices().unwrap();
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].input_price_per_1k, 0.005);
+
+    // 2000 输入 * 0.005/1K + 500 输出 * 0.01/1K
+    assert!((updated.estimate_cost(2000, 500) - 0.015).abs() < 1e-12);
+
+    db.delete_model_price("gpt-4o").unwrap();
+    assert!(db.get_model_prices().unwrap().is_empty());
+}
@@ -10,6 +10,7 @@
 //! ## 测试隔离
 //! 所有测试使用 `Connection::open_in_memory()` 创建内存数据库
 
+use crate::db::assistant_db::AssistantDatabase;
 use crate::db::mcp_db::*;
 use rusqlite::Connection;
 
@@ -21,8 +22,11 @@ use rusqlite::Connection;
 ///
 /// **安全性**: 使用内存数据库，不会影响真实数据
 fn create_mcp_test_db() -> Connection {
-    let conn = Connection::open_in_memory().unwrap();
+    init_mcp_test_schema(Connection::open_in_memory().unwrap())
+}
 
+/// 在给定连接上初始化 MCP 相关表结构
+fn init_mcp_test_schema(conn: Connection) -> Connection {
     // 创建 mcp_server 表
     conn.execute(
         "CREATE TABLE mcp_server (
@@ -40,7 +44,10 @@ fn create_mcp_test_db() -> Connection {
             is_builtin BOOLEAN NOT NULL DEFAULT 0,
             is_deletable BOOLEAN NOT NULL DEFAULT 1,
             proxy_enabled BOOLEAN NOT NULL DEFAULT 0,
-            created_time DATETIME DEFAULT CURRENT_TIMESTAMP
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            autostart BOOLEAN NOT NULL DEFAULT 0,
+            status TEXT,
+            last_healthy_at DATETIME
         )",
         [],
     )
@@ -56,6 +63,7 @@ fn create_mcp_test_db() -> Connection {
             is_enabled BOOLEAN NOT NULL DEFAULT 1,
             is_auto_run BOOLEAN NOT NULL DEFAULT 0,
             parameters TEXT,
+            timeout_ms INTEGER,
             created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (server_id) REFERENCES mcp_server(id) ON DELETE CASCADE,
             UNIQUE(server_id, tool_name)
@@ -117,6 +125,7 @@ fn create_mcp_test_db() -> Connection {
             llm_call_id TEXT,
             assistant_message_id INTEGER,
             subtask_id INTEGER,
+            timeout_ms INTEGER,
             FOREIGN KEY (server_id) REFERENCES mcp_server(id) ON DELETE CASCADE
         )",
         [],
@@ -227,6 +236,11 @@ fn test_mcp_server_crud() {
     assert!(updated.is_long_running);
     assert!(!updated.is_enabled);
     assert!(updated.is_builtin);
+    assert!(!updated.autostart);
+
+    // Autostart
+    db.set_mcp_server_autostart(id, true).unwrap();
+    assert!(db.get_mcp_server(id).unwrap().autostart);
 
     // Delete
     db.delete_mcp_server(id).unwrap();
@@ -342,6 +356,14 @@ fn test_mcp_server_tool_operations() {
 
     let final_tools = db.get_mcp_server_tools(server_id).unwrap();
     assert_eq!(final_tools[0].tool_description, Some("Updated description".to_string()));
+    assert_eq!(final_tools[0].timeout_ms, None);
+
+    // 工具级超时，刷新能力时保留
+    db.update_mcp_server_tool_timeout(tool_id, Some(120_000)).unwrap();
+    db.upsert_mcp_server_tool(server_id, "search", Some("Updated description"), None).unwrap();
+    assert_eq!(db.get_mcp_server_tool(tool_id).unwrap().timeout_ms, Some(120_000));
+    db.update_mcp_server_tool_timeout(tool_id, None).unwrap();
+    assert_eq!(db.get_mcp_server_tools(server_id).unwrap()[0].timeout_ms, None);
 }
 
 /// 测试 MCP Server Resource 操作
@@ -455,11 +477,16 @@ fn test_mcp_tool_call_operations() {
     assert_eq!(tool_call.status, "pending");
     assert!(tool_call.result.is_none());
     assert!(tool_call.error.is_none());
+    assert!(tool_call.timeout_ms.is_none());
 
     // 读取 Tool Call
     let read = db.get_mcp_tool_call(tool_call.id).unwrap();
     assert_eq!(read.tool_name, "search");
 
+    // 记录本次执行的超时
+    db.set_mcp_tool_call_timeout(tool_call.id, 45_000).unwrap();
+    assert_eq!(db.get_mcp_tool_call(tool_call.id).unwrap().timeout_ms, Some(45_000));
+
     // 更新为 executing
     db.update_mcp_tool_call_status(tool_call.id, "executing", None, None).unwrap();
     let executing = db.get_mcp_tool_call(tool_call.id).unwrap();
@@ -549,6 +576,31 @@ fn test_mcp_server_toggle() {
     assert!(enabled.is_enabled);
 }
 
+/// 测试 Server 健康状态的记录
+///
+/// 验证内容：
+/// - 未检查过时状态为空
+/// - 检查成功时记录 last_healthy_at，检查失败时保留上次健康时间
+#[test]
+fn test_mcp_server_health_status() {
+    let db = create_mcp_db();
+    let server_id = create_test_server(&db);
+
+    let server = db.get_mcp_server(server_id).unwrap();
+    assert_eq!(server.status, None);
+    assert_eq!(server.last_healthy_at, None);
+
+    db.update_mcp_server_health(server_id, true).unwrap();
+    let healthy = db.get_mcp_server(server_id).unwrap();
+    assert_eq!(healthy.status.as_deref(), Some("healthy"));
+    assert!(healthy.last_healthy_at.is_some());
+
+    db.update_mcp_server_health(server_id, false).unwrap();
+    let unhealthy = &db.get_mcp_servers().unwrap()[0];
+    assert_eq!(unhealthy.status.as_deref(), Some("unhealthy"));
+    assert_eq!(unhealthy.last_healthy_at, healthy.last_healthy_at);
+}
+
 /// 测试 Tool Call 的原子状态转换
 ///
 /// 验证内容：
@@ -897,3 +949,121 @@ fn test_mcp_tool_call_error_handling() {
     assert_eq!(failed.error, Some("Connection timeout after 30000ms".to_string()));
     assert!(failed.finished_time.is_some());
 }
+
+// ============================================================================
+// 批量操作测试
+// ============================================================================
+
+/// 创建测试用的 MCP Server（指定名称），并为其添加一个工具，返回 (server_id, tool_id)
+fn create_named_server_with_tool(db: &MCPDatabase, name: &str) -> (i64, i64) {
+    let server_id = db
+        .upsert_mcp_server_with_builtin(
+            name,
+            None,
+            "stdio",
+            Some("node server.js"),
+            None,
+            None,
+            None,
+            Some(30000),
+            false,
+            true,
+            false,
+            true,
+            false,
+        )
+        .unwrap();
+    let tool_id =
+        db.upsert_mcp_server_tool(server_id, "search", Some("Search"), Some("{}")).unwrap();
+    (server_id, tool_id)
+}
+
+/// 测试批量禁用/删除 MCP Server 及助手绑定配置的一致性
+///
+/// 验证内容：
+/// - 批量禁用返回逐个服务器的结果，不存在的服务器记为失败
+/// - 被禁用的服务器不再出现在助手可用工具中，但绑定配置保留，重新启用后恢复
+/// - 批量删除会清理助手中引用该服务器及其工具的配置，不可删除的服务器记为失败
+#[test]
+fn test_mcp_server_bulk_toggle_and_delete_with_assistant_bindings() {
+    // 使用共享缓存的内存数据库，使 MCPDatabase 与 AssistantDatabase.mcp_conn 指向同一个 mcp 库
+    let uri = "file:mcp_bulk_ops_test?mode=memory&cache=shared";
+    let db = MCPDatabase { conn: init_mcp_test_schema(Connection::open(uri).unwrap()) };
+    let assistant_conn = Connection::open_in_memory().unwrap();
+    assistant_conn
+        .execute_batch(
+            "CREATE TABLE assistant_mcp_config (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                assistant_id INTEGER NOT NULL,
+                mcp_server_id INTEGER NOT NULL,
+                is_enabled BOOLEAN NOT NULL DEFAULT 1,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(assistant_id, mcp_server_id)
+            );
+            CREATE TABLE assistant_mcp_tool_config (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                assistant_id INTEGER NOT NULL,
+                mcp_tool_id INTEGER NOT NULL,
+                is_enabled BOOLEAN NOT NULL DEFAULT 1,
+                is_auto_run BOOLEAN NOT NULL DEFAULT 0,
+                default_arguments TEXT,
+                created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(assistant_id, mcp_tool_id)
+            );",
+        )
+        .unwrap();
+    let assistant_db =
+        AssistantDatabase { conn: assistant_conn, mcp_conn: Connection::open(uri).unwrap() };
+
+    let servers: Vec<(i64, i64)> = ["server-a", "server-b", "server-c"]
+        .iter()
+        .map(|name| create_named_server_with_tool(&db, name))
+        .collect();
+    let (server_a, _) = servers[0];
+    let (server_b, tool_b) = servers[1];
+    let (server_c, _) = servers[2];
+    for &(server_id, tool_id) in &servers {
+        assistant_db.upsert_assistant_mcp_config(1, server_id, true).unwrap();
+        assistant_db.upsert_assistant_mcp_tool_config(1, tool_id, true, true).unwrap();
+    }
+
+    // 批量禁用 a、b 以及一个不存在的服务器
+    let results = db.bulk_toggle_mcp_servers(&[server_a, server_b, 9999], false).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results[0].success && results[1].success);
+    assert!(!results[2].success);
+    assert!(!db.get_mcp_server(server_a).unwrap().is_enabled);
+    assert!(!db.get_mcp_server(server_b).unwrap().is_enabled);
+    assert!(db.get_mcp_server(server_c).unwrap().is_enabled);
+
+    let available = assistant_db.get_assistant_mcp_servers_with_tools(1).unwrap();
+    assert_eq!(available.iter().map(|s| s.0).collect::<Vec<_>>(), vec![server_c]);
+    assert_eq!(assistant_db.get_assistant_mcp_configs(1).unwrap().len(), 3);
+
+    // 重新启用后绑定配置恢复生效
+    db.bulk_toggle_mcp_servers(&[server_a], true).unwrap();
+    let available = assistant_db.get_assistant_mcp_servers_with_tools(1).unwrap();
+    let restored = available.iter().find(|s| s.0 == server_a).unwrap();
+    assert!(restored.3, "assistant binding should survive disable/enable");
+    assert!(restored.4[0].4, "tool auto-run config should survive disable/enable");
+
+    // 批量删除 b 以及一个不可删除的服务器
+    db.conn.execute("UPDATE mcp_server SET is_deletable = 0 WHERE id = ?", [server_c]).unwrap();
+    let results = crate::mcp::registry_api::delete_mcp_servers_and_bindings(
+        &db,
+        &assistant_db,
+        &[server_b, server_c],
+    )
+    .unwrap();
+    assert!(results[0].success);
+    assert!(!results[1].success);
+    assert!(db.get_mcp_server(server_b).is_err());
+    assert!(db.get_mcp_server(server_c).is_ok());
+
+    let server_configs = assistant_db.get_assistant_mcp_configs(1).unwrap();
+    assert!(server_configs.iter().all(|c| c.mcp_server_id != server_b));
+    assert_eq!(server_configs.len(), 2);
+    let tool_configs = assistant_db.get_assistant_mcp_tool_configs(1).unwrap();
+    assert!(tool_configs.iter().all(|c| c.mcp_tool_id != tool_b));
+    assert_eq!(tool_configs.len(), 2);
+}
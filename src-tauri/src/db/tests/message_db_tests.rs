@@ -498,3 +498,314 @@ fn test_message_update_content_nonexistent() {
     let result = msg_repo.update_content(99999, "New content");
     assert!(result.is_ok());
 }
+
+/// 测试消息内容编辑的撤销与重做
+///
+/// 验证内容：
+/// - 连续编辑两次后撤销两次，内容按栈顺序依次恢复到原始内容
+/// - 撤销后可以重做，新的编辑会清空重做栈
+/// - 编辑历史超过上限时丢弃最旧的版本，删除对话时清空历史
+#[test]
+fn test_message_edit_undo_redo() {
+    let (msg_repo, conversation_id) = create_message_test_db();
+    let message =
+        msg_repo.create(&create_test_message(conversation_id, "user", "v1", None, None)).unwrap();
+    let content = |repo: &MessageRepository| repo.read(message.id).unwrap().unwrap().content;
+
+    msg_repo.update_content_with_history(message.id, "v2").unwrap();
+    msg_repo.update_content_with_history(message.id, "v3").unwrap();
+    assert_eq!(content(&msg_repo), "v3");
+
+    assert_eq!(msg_repo.undo_edit(message.id).unwrap().as_deref(), Some("v2"));
+    assert_eq!(content(&msg_repo), "v2");
+    assert_eq!(msg_repo.undo_edit(message.id).unwrap().as_deref(), Some("v1"));
+    assert_eq!(content(&msg_repo), "v1");
+    assert_eq!(msg_repo.undo_edit(message.id).unwrap(), None);
+
+    assert_eq!(msg_repo.redo_edit(message.id).unwrap().as_deref(), Some("v2"));
+    assert_eq!(content(&msg_repo), "v2");
+
+    // 新的编辑清空重做栈
+    msg_repo.update_content_with_history(message.id, "v4").unwrap();
+    assert_eq!(msg_repo.redo_edit(message.id).unwrap(), None);
+    assert_eq!(msg_repo.undo_edit(message.id).unwrap().as_deref(), Some("v2"));
+
+    // 超过上限时只保留最近的版本
+    for i in 0..MAX_MESSAGE_EDIT_HISTORY + 5 {
+        msg_repo.update_content_with_history(message.id, &format!("edit {}", i)).unwrap();
+    }
+    let mut undo_count = 0;
+    while msg_repo.undo_edit(message.id).unwrap().is_some() {
+        undo_count += 1;
+    }
+    assert_eq!(undo_count, MAX_MESSAGE_EDIT_HISTORY);
+
+    msg_repo.update_content_with_history(message.id, "final").unwrap();
+    msg_repo.clear_edit_history(conversation_id).unwrap();
+    assert_eq!(msg_repo.undo_edit(message.id).unwrap(), None);
+    assert_eq!(content(&msg_repo), "final");
+}
+
+/// 测试消息置顶与取消置顶
+///
+/// 验证内容：
+/// - pin 后消息出现在对话的置顶列表中，重复置顶不会报错
+/// - unpin 后消息从置顶列表中移除
+/// - 置顶不存在的消息不会写入任何记录
+#[test]
+fn test_message_pin_and_unpin() {
+    let (msg_repo, conversation_id) = create_message_test_db();
+
+    let spec =
+        msg_repo.create(&create_test_message(conversation_id, "user", "Spec", None, None)).unwrap();
+    let other = msg_repo
+        .create(&create_test_message(conversation_id, "user", "Chit-chat", None, None))
+        .unwrap();
+
+    msg_repo.pin(spec.id).unwrap();
+    msg_repo.pin(spec.id).unwrap();
+    msg_repo.pin(99999).unwrap();
+
+    let pinned = msg_repo.list_pinned_ids(conversation_id).unwrap();
+    assert_eq!(pinned.len(), 1);
+    assert!(pinned.contains(&spec.id));
+    assert!(!pinned.contains(&other.id));
+
+    msg_repo.unpin(spec.id).unwrap();
+    assert!(msg_repo.list_pinned_ids(conversation_id).unwrap().is_empty());
+}
+
+/// 测试部分回复标记
+///
+/// 验证内容：
+/// - mark_partial 后消息出现在对话的部分回复列表中，重复标记会覆盖中断原因
+/// - 标记不存在的消息不会写入任何记录
+#[test]
+fn test_mark_partial_response() {
+    let (msg_repo, conversation_id) = create_message_test_db();
+
+    let partial = msg_repo
+        .create(&create_test_message(conversation_id, "response", "half an answer", None, None))
+        .unwrap();
+    let complete = msg_repo
+        .create(&create_test_message(conversation_id, "response", "full answer", None, None))
+        .unwrap();
+
+    msg_repo.mark_partial(partial.id, "connection reset").unwrap();
+    msg_repo.mark_partial(partial.id, "stream stalled").unwrap();
+    msg_repo.mark_partial(99999, "missing").unwrap();
+
+    let partial_ids = msg_repo.list_partial_ids(conversation_id).unwrap();
+    assert_eq!(partial_ids.len(), 1);
+    assert!(partial_ids.contains(&partial.id));
+    assert!(!partial_ids.contains(&complete.id));
+}
+
+/// 测试助手的渲染模式配置传递到其回复消息
+///
+/// 验证内容：
+/// - 配置 plaintext 的助手，回复消息记录为 plaintext
+/// - 未配置或配置为 markdown 时不记录，前端按默认 markdown 渲染
+#[test]
+fn test_assistant_render_mode_propagates_to_response_message() {
+    use crate::api::ai::render_mode::{record_response_render_mode, RENDER_MODE_CONFIG_KEY};
+
+    let (msg_repo, conversation_id) = create_message_test_db();
+    let render_mode_config = |value: &str| create_test_model_config(RENDER_MODE_CONFIG_KEY, value);
+
+    let plaintext = msg_repo
+        .create(&create_test_message(conversation_id, "response", "raw output", None, None))
+        .unwrap();
+    record_response_render_mode(&msg_repo, plaintext.id, &[render_mode_config("plaintext")])
+        .unwrap();
+
+    let markdown = msg_repo
+        .create(&create_test_message(conversation_id, "response", "**md**", None, None))
+        .unwrap();
+    record_response_render_mode(&msg_repo, markdown.id, &[render_mode_config("markdown")]).unwrap();
+
+    let unconfigured = msg_repo
+        .create(&create_test_message(conversation_id, "response", "**md**", None, None))
+        .unwrap();
+    record_response_render_mode(&msg_repo, unconfigured.id, &[]).unwrap();
+
+    let render_modes = msg_repo.list_render_modes(conversation_id).unwrap();
+    assert_eq!(render_modes.get(&plaintext.id).map(String::as_str), Some("plaintext"));
+    assert!(!render_modes.contains_key(&markdown.id));
+    assert!(!render_modes.contains_key(&unconfigured.id));
+}
+
+/// 测试消息全文搜索
+///
+/// 验证内容：
+/// - 新建、修改、删除消息后索引自动同步
+/// - 命中部分在摘要中被标记，中文与大小写不敏感匹配
+/// - 短搜索词回退到 LIKE 匹配，删除对话后不再返回其消息
+#[test]
+fn test_message_search_content() {
+    let (msg_repo, conversation_id) = create_message_test_db();
+
+    let rust = msg_repo
+        .create(&create_test_message(
+            conversation_id,
+            "response",
+            "Use a LazyLock to share the browser pool across tasks",
+            None,
+            None,
+        ))
+        .unwrap();
+    let chinese = msg_repo
+        .create(&create_test_message(conversation_id, "user", "周末去杭州西湖骑行", None, None))
+        .unwrap();
+
+    let hits = msg_repo.search_content("browser pool", 20, 0).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].message_id, rust.id);
+    assert_eq!(hits[0].conversation_id, conversation_id);
+    assert!(hits[0].snippet.contains("<mark>browser</mark>"));
+
+    assert_eq!(msg_repo.search_content("lazylock", 20, 0).unwrap().len(), 1);
+    assert_eq!(msg_repo.search_content("杭州西湖", 20, 0).unwrap()[0].message_id, chinese.id);
+    // 两个字的词无法使用 trigram 索引，回退到 LIKE
+    let short = msg_repo.search_content("西湖", 20, 0).unwrap();
+    assert_eq!(short.len(), 1);
+    assert!(short[0].snippet.contains("<mark>西湖</mark>"));
+
+    msg_repo.update_content(rust.id, "Switched to a per-request client").unwrap();
+    assert!(msg_repo.search_content("browser pool", 20, 0).unwrap().is_empty());
+    assert_eq!(msg_repo.search_content("per-request", 20, 0).unwrap().len(), 1);
+
+    msg_repo.delete(chinese.id).unwrap();
+    assert!(msg_repo.search_content("杭州西湖", 20, 0).unwrap().is_empty());
+
+    msg_repo.remove_conversation_from_search_index(conversation_id).unwrap();
+    assert!(msg_repo.search_content("per-request", 20, 0).unwrap().is_empty());
+    assert!(msg_repo.search_content("", 20, 0).unwrap().is_empty());
+}
+
+/// 测试搜索摘要转义 HTML，LIKE 回退按字面匹配通配符
+#[test]
+fn test_message_search_escapes_content() {
+    let (msg_repo, conversation_id) = create_message_test_db();
+
+    let html = msg_repo
+        .create(&create_test_message(
+            conversation_id,
+            "response",
+            "<img onerror=alert(1)> 50%_off",
+            None,
+            None,
+        ))
+        .unwrap();
+    msg_repo
+        .create(&create_test_message(conversation_id, "user", "100 percent off", None, None))
+        .unwrap();
+
+    let hits = msg_repo.search_content("onerror", 20, 0).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!(!hits[0].snippet.contains("<img"), "{}", hits[0].snippet);
+    assert!(hits[0].snippet.contains("&lt;img <mark>onerror</mark>=alert(1)&gt;"));
+
+    // 短搜索词走 LIKE 回退
+    let short = msg_repo.search_content("<i", 20, 0).unwrap();
+    assert_eq!(short.len(), 1);
+    assert!(short[0].snippet.starts_with("<mark>&lt;i</mark>mg"), "{}", short[0].snippet);
+
+    let wildcard = msg_repo.search_content("%_", 20, 0).unwrap();
+    assert_eq!(wildcard.len(), 1);
+    assert_eq!(wildcard[0].message_id, html.id);
+}
+
+/// 测试分页读取对话消息
+///
+/// 验证内容：
+/// - 不带游标时返回最新的 limit 条消息（按时间正序），并标记还有更早的消息
+/// - 以上一页最早的消息为游标可继续向前翻页，翻到头时 has_more 为 false
+/// - 游标之后的消息不会重复返回
+#[test]
+fn test_list_messages_page_by_conversation_id() {
+    let (_, _, msg_repo, conversation) = create_shared_test_db();
+
+    let base_time = chrono::Utc::now();
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        let mut message = create_test_message(
+            conversation.id,
+            "user",
+            &format!("Message {}", i),
+            None,
+            Some(new_group_id()),
+        );
+        message.created_time = base_time + chrono::Duration::seconds(i);
+        ids.push(msg_repo.create(&message).unwrap().id);
+    }
+
+    let (latest, has_more) =
+        msg_repo.list_page_by_conversation_id(conversation.id, None, 2).unwrap();
+    let latest_ids: Vec<i64> = latest.iter().map(|(msg, _)| msg.id).collect();
+    assert_eq!(latest_ids, vec![ids[3], ids[4]]);
+    assert!(has_more);
+
+    let (older, has_more) =
+        msg_repo.list_page_by_conversation_id(conversation.id, Some(ids[3]), 2).unwrap();
+    let older_ids: Vec<i64> = older.iter().map(|(msg, _)| msg.id).collect();
+    assert_eq!(older_ids, vec![ids[1], ids[2]]);
+    assert!(has_more);
+
+    let (oldest, has_more) =
+        msg_repo.list_page_by_conversation_id(conversation.id, Some(ids[1]), 2).unwrap();
+    let oldest_ids: Vec<i64> = oldest.iter().map(|(msg, _)| msg.id).collect();
+    assert_eq!(oldest_ids, vec![ids[0]]);
+    assert!(!has_more);
+}
+
+/// 测试分页不会截断生成组
+///
+/// 验证内容：
+/// - limit 落在重新生成的版本中间时，页面向前扩展到原始版本最早的一条消息
+/// - 扩展后 has_more 与下一页游标仍然正确，消息不会重复或遗漏
+#[test]
+fn test_list_messages_page_keeps_generation_groups_whole() {
+    let (_, _, msg_repo, conversation) = create_shared_test_db();
+
+    let base_time = chrono::Utc::now();
+    let original_group = new_group_id();
+    let regenerated_group = new_group_id();
+    let messages = [
+        ("user", Some(new_group_id()), None),
+        ("reasoning", Some(original_group.clone()), None),
+        ("response", Some(original_group.clone()), None),
+        ("reasoning", Some(regenerated_group.clone()), Some(original_group.clone())),
+        ("response", Some(regenerated_group), Some(original_group)),
+    ];
+    let mut ids = Vec::new();
+    for (i, (message_type, group_id, parent_group_id)) in messages.into_iter().enumerate() {
+        let mut message = create_test_message(
+            conversation.id,
+            message_type,
+            &format!("Message {}", i),
+            None,
+            group_id,
+        );
+        message.parent_group_id = parent_group_id;
+        message.created_time = base_time + chrono::Duration::seconds(i as i64);
+        ids.push(msg_repo.create(&message).unwrap().id);
+    }
+
+    let (page, has_more) = msg_repo.list_page_by_conversation_id(conversation.id, None, 3).unwrap();
+    let page_ids: Vec<i64> = page.iter().map(|(msg, _)| msg.id).collect();
+    assert_eq!(page_ids, ids[1..].to_vec());
+    assert!(has_more);
+
+    // 只取到重新生成的版本时，同样带上原始版本
+    let (page, has_more) = msg_repo.list_page_by_conversation_id(conversation.id, None, 1).unwrap();
+    let page_ids: Vec<i64> = page.iter().map(|(msg, _)| msg.id).collect();
+    assert_eq!(page_ids, ids[1..].to_vec());
+    assert!(has_more);
+
+    let (older, has_more) =
+        msg_repo.list_page_by_conversation_id(conversation.id, Some(ids[1]), 3).unwrap();
+    let older_ids: Vec<i64> = older.iter().map(|(msg, _)| msg.id).collect();
+    assert_eq!(older_ids, vec![ids[0]]);
+    assert!(!has_more);
+}
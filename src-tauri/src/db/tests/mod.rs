@@ -7,6 +7,7 @@
 //! ## 测试文件结构
 //! - test_helpers.rs: 共享的测试辅助函数和数据库初始化
 //! - conversation_db_tests.rs: Conversation CRUD 测试
+//! - encryption_tests.rs: 数据库加密状态与明文检测测试
 //! - message_db_tests.rs: Message CRUD 和版本管理测试
 //! - attachment_db_tests.rs: MessageAttachment CRUD 测试
 //! - assistant_db_tests.rs: Assistant 及其关联表测试
@@ -14,18 +15,21 @@
 //! - mcp_db_tests.rs: MCP Server 和 Tool 测试
 //! - system_db_tests.rs: SystemConfig 和 FeatureConfig 测试
 //! - plugin_db_tests.rs: Plugin, PluginStatus, PluginConfiguration, PluginData 测试
+//! - scheduled_task_db_tests.rs: ScheduledTask 表迁移、cron 与时区字段测试
 //!
 //! ## 重要：测试隔离性
 //! 所有测试使用 `Connection::open_in_memory()` 创建内存数据库，
 //! 不会影响项目真实的 db 文件。
 
-mod test_helpers;
+pub(crate) mod test_helpers;
 
 mod assistant_db_tests;
 mod attachment_db_tests;
 mod conversation_db_tests;
+mod encryption_tests;
 mod llm_db_tests;
 mod mcp_db_tests;
 mod message_db_tests;
 mod plugin_db_tests;
+mod scheduled_task_db_tests;
 mod system_db_tests;
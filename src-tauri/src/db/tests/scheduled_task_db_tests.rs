@@ -0,0 +1,184 @@
+//! ScheduledTaskDatabase 单元测试
+//!
+//! 测试旧表结构迁移、cron 与时区字段的读写以及失败重试状态
+
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use rusqlite::Connection;
+
+use crate::db::scheduled_task_db::{ScheduledTask, ScheduledTaskDatabase, ScheduledTaskRun};
+
+/// 只支持 once/interval、没有 cron 列的旧版 scheduled_task 表
+fn create_legacy_db() -> ScheduledTaskDatabase {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE scheduled_task (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            is_enabled BOOLEAN NOT NULL DEFAULT 1,
+            schedule_type TEXT NOT NULL CHECK(schedule_type IN ('once', 'interval')),
+            interval_value INTEGER,
+            interval_unit TEXT,
+            run_at DATETIME,
+            next_run_at DATETIME,
+            last_run_at DATETIME,
+            assistant_id INTEGER NOT NULL,
+            task_prompt TEXT NOT NULL,
+            notify_prompt TEXT NOT NULL,
+            created_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_time DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO scheduled_task (name, schedule_type, interval_value, interval_unit, assistant_id, task_prompt, notify_prompt)
+         VALUES ('旧任务', 'interval', 2, 'hour', 1, 'task', 'notify')",
+        [],
+    )
+    .unwrap();
+    ScheduledTaskDatabase { conn, db_path: PathBuf::new() }
+}
+
+fn cron_task(next_run_offset_minutes: i64) -> ScheduledTask {
+    let now = Utc::now();
+    ScheduledTask {
+        id: 0,
+        name: "工作日早报".to_string(),
+        is_enabled: true,
+        schedule_type: "cron".to_string(),
+        interval_value: None,
+        interval_unit: None,
+        start_time: None,
+        week_days: None,
+        month_days: None,
+        run_at: None,
+        next_run_at: Some(now + Duration::minutes(next_run_offset_minutes)),
+        last_run_at: None,
+        assistant_id: 1,
+        task_prompt: "整理今天的待办".to_string(),
+        notify_prompt: "有待办时通知".to_string(),
+        created_time: now,
+        updated_time: now,
+        cron: Some("0 9 * * 1-5".to_string()),
+        timezone: Some("America/New_York".to_string()),
+        max_retries: 2,
+        retry_delay_secs: 60,
+        retry_attempt: 0,
+        retry_scheduled_time: None,
+    }
+}
+
+#[test]
+fn test_legacy_table_is_migrated_for_cron() {
+    let db = create_legacy_db();
+    db.create_tables().unwrap();
+    // 再次执行迁移不应出错
+    db.create_tables().unwrap();
+
+    let legacy = db.read_task(1).unwrap().expect("legacy task should be kept");
+    assert_eq!(legacy.name, "旧任务");
+    assert_eq!(legacy.interval_value, Some(2));
+    assert_eq!(legacy.cron, None);
+    assert_eq!(legacy.timezone, None);
+
+    let created = db.create_task(&cron_task(-1)).unwrap();
+    assert!(created.id > legacy.id);
+    let stored = db.read_task(created.id).unwrap().unwrap();
+    assert_eq!(stored.schedule_type, "cron");
+    assert_eq!(stored.cron.as_deref(), Some("0 9 * * 1-5"));
+    assert_eq!(stored.timezone.as_deref(), Some("America/New_York"));
+}
+
+#[test]
+fn test_cron_task_update_and_due_list() {
+    let db = create_legacy_db();
+    db.create_tables().unwrap();
+
+    let due = db.create_task(&cron_task(-1)).unwrap();
+    let pending = db.create_task(&cron_task(60)).unwrap();
+
+    let due_ids: Vec<i64> = db.list_due_tasks(Utc::now()).unwrap().iter().map(|t| t.id).collect();
+    assert!(due_ids.contains(&due.id));
+    assert!(!due_ids.contains(&pending.id));
+
+    let updated = ScheduledTask { cron: Some("*/30 * * * *".to_string()), ..pending.clone() };
+    db.update_task(&updated).unwrap();
+    assert_eq!(db.read_task(pending.id).unwrap().unwrap().cron.as_deref(), Some("*/30 * * * *"));
+}
+
+#[test]
+fn test_run_keeps_scheduled_time_and_timezone() {
+    let db = create_legacy_db();
+    db.create_tables().unwrap();
+    let task = db.create_task(&cron_task(-1)).unwrap();
+
+    let run = ScheduledTaskRun {
+        id: 0,
+        task_id: task.id,
+        run_id: "run-1".to_string(),
+        status: "running".to_string(),
+        notify: false,
+        summary: None,
+        error_message: None,
+        started_time: Utc::now(),
+        finished_time: None,
+        scheduled_time: task.next_run_at,
+        timezone: task.timezone.clone(),
+        attempt: 2,
+    };
+    db.create_run(&run).unwrap();
+
+    let runs = db.list_runs_by_task(task.id, 10).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(
+        runs[0].scheduled_time.map(|t| t.timestamp()),
+        task.next_run_at.map(|t| t.timestamp())
+    );
+    assert_eq!(runs[0].timezone.as_deref(), Some("America/New_York"));
+    assert_eq!(runs[0].attempt, 2);
+}
+
+#[test]
+fn test_set_retry_state_moves_next_run_earlier() {
+    let db = create_legacy_db();
+    db.create_tables().unwrap();
+    let task = db.create_task(&cron_task(120)).unwrap();
+    assert_eq!(task.max_retries, 2);
+
+    // 重试时间早于下次计划时间时提前执行，并记录被替代的原计划时间
+    let slot = Utc::now() - Duration::minutes(1);
+    let retry_at = Utc::now() + Duration::minutes(1);
+    db.set_retry_state(task.id, 1, Some(retry_at), Some(slot)).unwrap();
+    let stored = db.read_task(task.id).unwrap().unwrap();
+    assert_eq!(stored.retry_attempt, 1);
+    assert_eq!(stored.next_run_at.map(|t| t.timestamp()), Some(retry_at.timestamp()));
+    assert_eq!(stored.retry_scheduled_time.map(|t| t.timestamp()), Some(slot.timestamp()));
+
+    // 晚于下次计划时间的重试不会推迟原计划
+    db.set_retry_state(task.id, 2, Some(retry_at + Duration::hours(5)), Some(slot)).unwrap();
+    let stored = db.read_task(task.id).unwrap().unwrap();
+    assert_eq!(stored.retry_attempt, 2);
+    assert_eq!(stored.next_run_at.map(|t| t.timestamp()), Some(retry_at.timestamp()));
+
+    // 重试结束时清除原计划时间
+    db.set_retry_state(task.id, 0, None, None).unwrap();
+    assert!(db.read_task(task.id).unwrap().unwrap().retry_scheduled_time.is_none());
+
+    // 已停用的一次性任务重试时重新启用
+    let once = ScheduledTask {
+        schedule_type: "once".to_string(),
+        is_enabled: false,
+        next_run_at: None,
+        ..cron_task(0)
+    };
+    let once = db.create_task(&once).unwrap();
+    db.set_retry_state(once.id, 1, Some(retry_at), None).unwrap();
+    let stored = db.read_task(once.id).unwrap().unwrap();
+    assert!(stored.is_enabled);
+    assert!(stored.next_run_at.is_some());
+
+    db.set_retry_state(once.id, 0, None, None).unwrap();
+    assert_eq!(db.read_task(once.id).unwrap().unwrap().retry_attempt, 0);
+}
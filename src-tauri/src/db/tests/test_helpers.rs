@@ -10,6 +10,7 @@
 //! - 测试结束后自动销毁，无需清理
 //! - 完全隔离，不同测试之间互不影响
 
+use crate::db::assistant_db::AssistantModelConfig;
 use crate::db::conversation_db::*;
 use chrono::Utc;
 use rusqlite::Connection;
@@ -64,6 +65,54 @@ pub fn create_test_db() -> Connection {
     )
     .unwrap();
 
+    // 创建消息置顶表
+    conn.execute(
+        "CREATE TABLE message_pin (
+            message_id INTEGER PRIMARY KEY,
+            conversation_id INTEGER NOT NULL,
+            created_time TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建消息渲染模式表
+    conn.execute(
+        "CREATE TABLE message_render_mode (
+            message_id INTEGER PRIMARY KEY,
+            conversation_id INTEGER NOT NULL,
+            render_mode TEXT NOT NULL
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建部分回复表
+    conn.execute(
+        "CREATE TABLE message_partial (
+            message_id INTEGER PRIMARY KEY,
+            conversation_id INTEGER NOT NULL,
+            error TEXT,
+            created_time TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建消息编辑历史表
+    conn.execute(
+        "CREATE TABLE message_edit_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            message_id INTEGER NOT NULL,
+            conversation_id INTEGER NOT NULL,
+            stack TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_time TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
     // 创建消息附件表
     conn.execute(
         "CREATE TABLE message_attachment (
@@ -80,6 +129,42 @@ pub fn create_test_db() -> Connection {
     )
     .unwrap();
 
+    // 创建附件 OCR 结果表
+    conn.execute(
+        "CREATE TABLE attachment_ocr (
+            attachment_id INTEGER PRIMARY KEY,
+            status TEXT NOT NULL,
+            ocr_text TEXT,
+            error TEXT,
+            updated_time TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建对话标签表
+    conn.execute(
+        "CREATE TABLE conversation_tag (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            created_time TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "CREATE TABLE conversation_tag_link (
+            conversation_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY (conversation_id, tag_id)
+        )",
+        [],
+    )
+    .unwrap();
+
+    // 创建消息全文索引及同步触发器
+    create_message_search_index(&conn).unwrap();
+
     conn
 }
 
@@ -94,6 +179,18 @@ pub fn create_test_conversation(repo: &ConversationRepository) -> Conversation {
     repo.create(&conversation).unwrap()
 }
 
+/// 创建测试用的助手模型配置项
+pub fn create_test_model_config(name: &str, value: &str) -> AssistantModelConfig {
+    AssistantModelConfig {
+        id: 0,
+        assistant_id: 1,
+        assistant_model_id: 1,
+        name: name.to_string(),
+        value: Some(value.to_string()),
+        value_type: "string".to_string(),
+    }
+}
+
 /// 创建测试用的消息数据
 pub fn create_test_message(
     conversation_id: i64,
@@ -124,6 +221,34 @@ pub fn create_test_message(
     }
 }
 
+/// 将测试消息转换为不含附件和重新生成版本的 MessageDetail
+pub fn create_test_message_detail(message: Message) -> MessageDetail {
+    MessageDetail {
+        id: message.id,
+        parent_id: message.parent_id,
+        conversation_id: message.conversation_id,
+        message_type: message.message_type,
+        content: message.content,
+        llm_model_id: message.llm_model_id,
+        created_time: message.created_time,
+        start_time: message.start_time,
+        finish_time: message.finish_time,
+        token_count: message.token_count,
+        input_token_count: message.input_token_count,
+        output_token_count: message.output_token_count,
+        generation_group_id: message.generation_group_id,
+        parent_group_id: message.parent_group_id,
+        tool_calls_json: message.tool_calls_json,
+        first_token_time: message.first_token_time,
+        ttft_ms: message.ttft_ms,
+        is_pinned: false,
+        render_mode: None,
+        is_partial: false,
+        attachment_list: Vec::new(),
+        regenerate: Vec::new(),
+    }
+}
+
 /// 创建共享的测试数据库连接，包含对话和消息表
 pub fn create_shared_test_db(
 ) -> (Connection, ConversationRepository, MessageRepository, Conversation) {
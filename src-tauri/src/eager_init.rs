@@ -0,0 +1,137 @@
+//! 启动预热（功能配置 `eager_init.enabled`，默认关闭）
+//!
+//! 开启后在 setup 结束时启动后台任务：为开启了连接保活的提供商提前创建保活客户端，
+//! 首次提问时可以直接复用已建立的连接。未开启保活的提供商每次请求都会新建客户端，预热没有意义，因此跳过。
+//! 搜索浏览器的预热由搜索工具的 `EAGER_WARMUP` 配置控制（见 `spawn_search_browser_warmup`）。
+//! 任务全部异步执行，不阻塞启动；失败只记录日志。
+
+use crate::api::ai::config::{get_network_proxy_from_config, get_request_timeout_from_config};
+use crate::api::ai::keep_alive::{is_keep_alive_active, keep_alive_enabled};
+use crate::api::genai_client::create_client_with_config;
+use crate::db::llm_db::LLMDatabase;
+use crate::db::system_db::FeatureConfig;
+use crate::FeatureConfigState;
+use std::collections::HashMap;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+/// 功能配置：启动预热所在的 feature_code / key
+pub const EAGER_INIT_FEATURE_CODE: &str = "eager_init";
+pub const EAGER_INIT_ENABLED_KEY: &str = "enabled";
+
+pub fn eager_init_enabled(
+    config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+) -> bool {
+    config_feature_map
+        .get(EAGER_INIT_FEATURE_CODE)
+        .and_then(|config| config.get(EAGER_INIT_ENABLED_KEY))
+        .is_some_and(|config| matches!(config.value.trim(), "true" | "1"))
+}
+
+/// 按配置在后台执行启动预热，需在功能配置状态初始化之后调用
+pub fn spawn_eager_init(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let config_feature_map = match app_handle.try_state::<FeatureConfigState>() {
+            Some(state) => state.config_feature_map.lock().await.clone(),
+            None => return,
+        };
+        if !eager_init_enabled(&config_feature_map) {
+            return;
+        }
+
+        let started = Instant::now();
+        match prime_keep_alive_providers(&app_handle, &config_feature_map) {
+            Ok(primed) => info!(
+                primed,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "Keep-alive providers primed"
+            ),
+            Err(e) => warn!(error = %e, "Failed to prime keep-alive providers"),
+        }
+    });
+}
+
+/// 为每个已启用且开启了连接保活的提供商创建保活客户端（流式与非流式各一个），
+/// 客户端会被缓存并立即开始预热请求，返回成功建立保活的提供商数量
+fn prime_keep_alive_providers(
+    app_handle: &AppHandle,
+    config_feature_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+) -> Result<usize, String> {
+    let llm_db = LLMDatabase::new(app_handle).map_err(|e| e.to_string())?;
+    let providers = llm_db.get_llm_providers().map_err(|e| e.to_string())?;
+    let network_proxy = get_network_proxy_from_config(config_feature_map);
+    let request_timeout = get_request_timeout_from_config(config_feature_map);
+
+    let mut primed = 0;
+    for (provider_id, name, api_type, _, _, is_enabled) in providers {
+        if !is_enabled {
+            continue;
+        }
+        let configs = llm_db.get_llm_provider_config(provider_id).map_err(|e| e.to_string())?;
+        if !keep_alive_enabled(&configs) {
+            continue;
+        }
+        let models = llm_db.get_llm_models(provider_id.to_string()).map_err(|e| e.to_string())?;
+        let Some((_, _, _, model_code, ..)) = models.first() else {
+            debug!(provider_id, %name, "Provider has no model, skipping keep-alive priming");
+            continue;
+        };
+        let proxy_enabled = configs
+            .iter()
+            .find(|config| config.name == "proxy_enabled")
+            .and_then(|config| config.value.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        for is_stream in [false, true] {
+            if let Err(e) = create_client_with_config(
+                &configs,
+                model_code,
+                &api_type,
+                network_proxy.as_deref(),
+                proxy_enabled,
+                Some(request_timeout),
+                is_stream,
+                config_feature_map,
+            ) {
+                warn!(provider_id, %name, error = %e, "Failed to prime provider client");
+            }
+        }
+        // 本地提供商不会启用保活
+        if is_keep_alive_active(provider_id) {
+            debug!(provider_id, %name, "Provider keep-alive connection started");
+            primed += 1;
+        }
+    }
+    Ok(primed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eager_init_config(value: &str) -> HashMap<String, HashMap<String, FeatureConfig>> {
+        let config = FeatureConfig {
+            id: None,
+            feature_code: EAGER_INIT_FEATURE_CODE.to_string(),
+            key: EAGER_INIT_ENABLED_KEY.to_string(),
+            value: value.to_string(),
+            data_type: "string".to_string(),
+            description: None,
+        };
+        HashMap::from([(
+            EAGER_INIT_FEATURE_CODE.to_string(),
+            HashMap::from([(EAGER_INIT_ENABLED_KEY.to_string(), config)]),
+        )])
+    }
+
+    /// 测试启动预热默认关闭，只有显式开启时才执行
+    #[test]
+    fn test_eager_init_is_opt_in() {
+        assert!(!eager_init_enabled(&HashMap::new()));
+        assert!(!eager_init_enabled(&eager_init_config("false")));
+        assert!(eager_init_enabled(&eager_init_config("true")));
+        assert!(eager_init_enabled(&eager_init_config(" 1 ")));
+    }
+}
@@ -0,0 +1,208 @@
+//! 启动时打开哪个窗口
+//!
+//! 由功能配置 `launch.behavior` 决定：打开 Ask、打开 Chat、仅驻留托盘，或恢复上次退出时打开的窗口。
+//! 无论哪种方式，常用窗口都会先以隐藏状态预创建，之后按需显示。
+
+use crate::db::system_db::SystemDatabase;
+use crate::window::{
+    create_ask_window, create_ask_window_hidden, create_chat_ui_window_hidden,
+    create_config_window_hidden, create_schedule_window_hidden, open_chat_ui_window_inner,
+};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// 功能配置：启动方式所在的 feature_code / key
+pub const LAUNCH_FEATURE_CODE: &str = "launch";
+pub const LAUNCH_BEHAVIOR_KEY: &str = "behavior";
+
+/// 系统配置：上次退出时可见的窗口（逗号分隔的窗口 label）
+const LAST_OPEN_WINDOWS_KEY: &str = "last_open_windows";
+/// 上次退出时没有可见窗口
+const NO_OPEN_WINDOWS: &str = "none";
+
+/// 可在启动时恢复的窗口
+const RESTORABLE_WINDOWS: [&str; 4] = ["ask", "chat_ui", "config", "schedule"];
+
+/// 启动方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchBehavior {
+    /// 打开 Ask 窗口（默认）
+    Ask,
+    /// 打开 Chat 窗口
+    Chat,
+    /// 只显示托盘图标，不显示任何窗口
+    Tray,
+    /// 恢复上次退出时打开的窗口
+    Restore,
+}
+
+impl LaunchBehavior {
+    pub fn from_value(value: &str) -> Self {
+        match value.trim() {
+            "chat" => LaunchBehavior::Chat,
+            "tray" => LaunchBehavior::Tray,
+            "restore" => LaunchBehavior::Restore,
+            _ => LaunchBehavior::Ask,
+        }
+    }
+}
+
+/// 解析记录的上次打开窗口，没有记录时返回 None
+pub fn parse_last_open_windows(value: &str) -> Option<Vec<String>> {
+    match value.trim() {
+        "" => None,
+        NO_OPEN_WINDOWS => Some(Vec::new()),
+        value => Some(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|label| RESTORABLE_WINDOWS.contains(label))
+                .map(str::to_string)
+                .collect(),
+        ),
+    }
+}
+
+/// 根据启动方式计算启动时要显示的窗口；恢复模式下没有记录时按 Ask 处理
+pub fn startup_windows(
+    behavior: LaunchBehavior,
+    last_open_windows: Option<&[String]>,
+) -> Vec<&'static str> {
+    match behavior {
+        LaunchBehavior::Ask => vec!["ask"],
+        LaunchBehavior::Chat => vec!["chat_ui"],
+        LaunchBehavior::Tray => Vec::new(),
+        LaunchBehavior::Restore => match last_open_windows {
+            Some(labels) => RESTORABLE_WINDOWS
+                .iter()
+                .copied()
+                .filter(|label| labels.iter().any(|open| open == label))
+                .collect(),
+            None => vec!["ask"],
+        },
+    }
+}
+
+fn read_launch_behavior(system_db: &SystemDatabase) -> LaunchBehavior {
+    match system_db.get_feature_config(LAUNCH_FEATURE_CODE, LAUNCH_BEHAVIOR_KEY) {
+        Ok(config) => config
+            .map(|config| LaunchBehavior::from_value(&config.value))
+            .unwrap_or(LaunchBehavior::Ask),
+        Err(e) => {
+            warn!(error = %e, "Failed to read launch behavior, falling back to ask window");
+            LaunchBehavior::Ask
+        }
+    }
+}
+
+/// 预创建窗口并按启动方式显示（桌面端）
+pub fn open_startup_windows(app_handle: &AppHandle) {
+    let (behavior, last_open_windows) = match SystemDatabase::new(app_handle) {
+        Ok(system_db) => (
+            read_launch_behavior(&system_db),
+            system_db
+                .get_config(LAST_OPEN_WINDOWS_KEY)
+                .ok()
+                .and_then(|v| parse_last_open_windows(&v)),
+        ),
+        Err(e) => {
+            warn!(error = %e, "Failed to open system db for launch behavior");
+            (LaunchBehavior::Ask, None)
+        }
+    };
+    let windows = startup_windows(behavior, last_open_windows.as_deref());
+    info!(?behavior, ?windows, "Opening startup windows");
+
+    create_chat_ui_window_hidden(app_handle);
+    create_config_window_hidden(app_handle);
+    create_schedule_window_hidden(app_handle);
+    if windows.contains(&"ask") {
+        create_ask_window(app_handle);
+    } else {
+        create_ask_window_hidden(app_handle);
+    }
+
+    for label in windows.iter().filter(|label| **label != "ask") {
+        let Some(window) = app_handle.get_webview_window(label) else {
+            continue;
+        };
+        if *label == "chat_ui" {
+            open_chat_ui_window_inner(app_handle, &window);
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+/// 记录当前可见的窗口，供下次以恢复模式启动时使用（退出时调用）
+pub fn record_open_windows(app_handle: &AppHandle) {
+    let open: Vec<&str> = RESTORABLE_WINDOWS
+        .iter()
+        .copied()
+        .filter(|label| {
+            app_handle
+                .get_webview_window(label)
+                .is_some_and(|window| window.is_visible().unwrap_or(false))
+        })
+        .collect();
+    let value = if open.is_empty() { NO_OPEN_WINDOWS.to_string() } else { open.join(",") };
+
+    let result = SystemDatabase::new(app_handle).and_then(|system_db| {
+        if system_db.get_config(LAST_OPEN_WINDOWS_KEY)?.is_empty() {
+            system_db.add_system_config(LAST_OPEN_WINDOWS_KEY, &value)
+        } else {
+            system_db.update_system_config(LAST_OPEN_WINDOWS_KEY, &value)
+        }
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "Failed to record open windows");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    /// 测试配置值选择正确的启动路径
+    #[test]
+    fn test_launch_behavior_selects_startup_windows() {
+        let cases = [
+            ("ask", vec!["ask"]),
+            ("chat", vec!["chat_ui"]),
+            ("tray", vec![]),
+            ("", vec!["ask"]),
+            ("unknown", vec!["ask"]),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                startup_windows(LaunchBehavior::from_value(value), None),
+                expected,
+                "launch behavior {:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_restore_uses_last_open_windows() {
+        let restore = LaunchBehavior::from_value("restore");
+        assert_eq!(restore, LaunchBehavior::Restore);
+
+        let last_open = parse_last_open_windows("config, chat_ui,plugin_window").unwrap();
+        assert_eq!(last_open, labels(&["config", "chat_ui"]));
+        assert_eq!(startup_windows(restore, Some(&last_open)), vec!["chat_ui", "config"]);
+
+        // 上次退出时没有可见窗口：只留托盘
+        let none_open = parse_last_open_windows("none").unwrap();
+        assert!(startup_windows(restore, Some(&none_open)).is_empty());
+
+        // 没有记录（首次启动）：按 Ask 处理
+        assert_eq!(parse_last_open_windows(""), None);
+        assert_eq!(startup_windows(restore, None), vec!["ask"]);
+    }
+}
@@ -3,10 +3,13 @@
 mod api;
 mod artifacts;
 mod db;
+mod eager_init;
 mod errors;
+mod launch;
 mod mcp;
 mod plugin;
 mod scheduler;
+mod shutdown;
 mod skills;
 mod state;
 mod template_engine;
@@ -17,22 +20,31 @@ use std::sync::atomic::{AtomicU8, Ordering};
 
 use crate::api::ai::acp::AcpPermissionState;
 use crate::api::ai_api::{
-    ask_ai, cancel_ai, get_activity_focus, get_conversation_runtime_state, get_shine_state,
-    regenerate_ai, regenerate_conversation_title, tool_result_continue_ask_ai,
+    ask_ai, cancel_ai, cancel_send_confirmation, confirm_and_send, get_active_generation_status,
+    get_activity_focus, get_conversation_runtime_state, get_shine_state, regenerate_ai,
+    regenerate_conversation_title, test_assistant, tool_result_continue_ask_ai,
 };
 use crate::api::assistant_api::{
-    add_assistant, bulk_update_assistant_mcp_tools, copy_assistant, delete_assistant,
-    export_assistant, get_acp_working_directory, get_assistant, get_assistant_field_value,
-    get_assistant_mcp_servers_with_tools, get_assistants, import_assistant, save_assistant,
-    update_assistant_mcp_config, update_assistant_mcp_tool_config,
-    update_assistant_model_config_value,
+    add_assistant, bulk_update_assistant_mcp_tools, copy_assistant, create_assistant_from_template,
+    delete_assistant, export_assistant, get_acp_working_directory, get_assistant,
+    get_assistant_field_value, get_assistant_mcp_servers_with_tools, get_assistant_starters,
+    get_assistants, import_assistant, lint_assistant_prompt, list_assistant_templates,
+    save_assistant, set_assistant_base, update_assistant_mcp_config,
+    update_assistant_mcp_tool_config, update_assistant_mcp_tool_default_arguments,
+    update_assistant_model_config_value, update_assistant_starters,
 };
 use crate::api::attachment_api::{add_attachment, open_attachment_with_default_app};
+use crate::api::backup_api::{create_backup_now, list_backups};
 use crate::api::conversation_api::{
-    create_conversation_with_messages, create_message, delete_conversation, fork_conversation,
-    get_conversation_with_messages, list_conversations, search_conversations,
-    update_assistant_message, update_conversation, update_message_content,
+    add_conversation_tag, create_conversation_with_messages, create_message, delete_conversation,
+    delete_conversation_tag, edit_message, fork_conversation, get_conversation_clean,
+    get_conversation_with_messages, list_conversation_tags, list_conversations, pin_message,
+    redo_message_edit, remove_conversation_tag, search_conversations, search_messages,
+    summarize_conversation_now, undo_message_edit, unpin_message, update_assistant_message,
+    update_conversation, update_message_content,
 };
+use crate::api::conversation_bundle_api::{export_conversation_json, import_conversation};
+use crate::api::conversation_export_api::export_conversation_html;
 use crate::api::copilot_api::{poll_github_copilot_token, start_github_copilot_device_flow};
 #[cfg(desktop)]
 use crate::api::copilot_lsp::{
@@ -40,14 +52,22 @@ use crate::api::copilot_lsp::{
     sign_in_confirm, sign_in_initiate, sign_out_copilot, stop_copilot_lsp, CopilotLspState,
 };
 use crate::api::export_api::{markdown_to_docx, markdown_to_pdf};
-use crate::api::highlight_api::{highlight_code, list_syntect_themes};
+use crate::api::highlight_api::{
+    custom_theme_dir, highlight_code, import_syntect_theme, list_syntect_themes, load_custom_themes,
+};
 use crate::api::llm_api::{
-    add_llm_model, add_llm_provider, delete_llm_model, delete_llm_provider, export_llm_provider,
-    fetch_model_list, get_filtered_models_for_select, get_filtered_providers, get_llm_models,
-    get_llm_provider_config, get_llm_providers, get_models_for_select, import_llm_provider,
-    preview_model_list, update_llm_provider, update_llm_provider_config, update_selected_models,
+    add_environment_profile, add_llm_model, add_llm_provider, delete_environment_profile,
+    delete_llm_model, delete_llm_provider, delete_model_price, export_llm_provider,
+    fetch_model_list, get_environment_profiles, get_filtered_models_for_select,
+    get_filtered_providers, get_llm_models, get_llm_provider_config, get_llm_providers,
+    get_model_prices, get_models_for_select, import_llm_provider, preview_model_list,
+    set_active_environment, set_model_price, test_llm_provider, update_environment_profile,
+    update_llm_provider, update_llm_provider_config, update_selected_models,
+};
+use crate::api::operation_api::{
+    confirm_acp_permission, confirm_operation_permission, confirm_operation_permission_batch,
+    get_denied_operation_commands,
 };
-use crate::api::operation_api::{confirm_acp_permission, confirm_operation_permission};
 use crate::api::plugin_api::{
     disable_plugin, enable_plugin, get_enabled_plugins, get_plugin_config, get_plugin_data,
     get_plugin_root_dir, install_plugin, list_plugins, set_plugin_config, set_plugin_data,
@@ -59,19 +79,24 @@ use crate::api::scheduled_task_api::{
     stop_scheduled_task_run, update_scheduled_task,
 };
 use crate::api::skill_api::{
-    bulk_update_assistant_skills, cleanup_orphaned_skill_configs, delete_skill,
-    fetch_official_skills, get_assistant_skills, get_enabled_assistant_skills, get_skill,
-    get_skill_content, get_skill_sources, get_skills_directory, install_official_skill,
-    open_skill_parent_folder, open_skills_folder, open_source_url, remove_assistant_skill,
-    scan_skills, skill_exists, toggle_assistant_skill, update_assistant_skill_config,
+    add_git_skill_source, bulk_update_assistant_skills, cleanup_orphaned_skill_configs,
+    delete_skill, fetch_official_skills, get_assistant_skills, get_enabled_assistant_skills,
+    get_skill, get_skill_content, get_skill_sources, get_skills_directory, install_official_skill,
+    list_git_skill_sources, open_skill_parent_folder, open_skills_folder, open_source_url,
+    refresh_skill_source, remove_assistant_skill, remove_git_skill_source, scan_skills,
+    skill_exists, toggle_assistant_skill, update_assistant_skill_config,
 };
 use crate::api::system_api::{
-    copy_image_to_clipboard, get_all_feature_config, get_autostart_state, get_bang_list,
-    get_selected_text_api, open_data_folder, open_image, resume_global_shortcut,
-    save_feature_config, set_autostart, set_shortcut_recording, suspend_global_shortcut,
+    copy_image_to_clipboard, enable_database_encryption, get_all_feature_config,
+    get_autostart_state, get_bang_list, get_database_encryption_status, get_selected_text_api,
+    get_shortcut_status, open_data_folder, open_image, resume_global_shortcut, save_feature_config,
+    set_autostart, set_shortcut_recording, suspend_global_shortcut, ShortcutRegistrationStatus,
 };
 use crate::api::todo_api::get_todos;
-use crate::api::token_statistics_api::{get_conversation_token_stats, get_message_token_stats};
+use crate::api::token_statistics_api::{
+    export_usage_report, get_conversation_stats, get_conversation_token_stats,
+    get_message_token_stats, get_usage_insights, get_usage_summary,
+};
 use crate::api::updater_api::{
     check_update, check_update_with_proxy, download_and_install_update,
     download_and_install_update_with_proxy, get_app_version,
@@ -84,9 +109,9 @@ use crate::artifacts::artifact_bridge_api::{
 use crate::artifacts::artifacts_db::ArtifactsDatabase;
 use crate::artifacts::collection_api::{
     delete_artifact_collection, generate_artifact_metadata, get_artifact_by_id,
-    get_artifacts_collection, get_artifacts_for_completion, get_artifacts_statistics,
-    open_artifact_window, save_artifact_to_collection, search_artifacts_collection,
-    update_artifact_collection,
+    get_artifact_version, get_artifact_versions, get_artifacts_collection,
+    get_artifacts_for_completion, get_artifacts_statistics, open_artifact_window,
+    save_artifact_to_collection, search_artifacts_collection, update_artifact_collection,
 };
 use crate::artifacts::env_installer::{
     check_acp_library, check_bun_update, check_bun_update_with_proxy, check_bun_version,
@@ -94,6 +119,9 @@ use crate::artifacts::env_installer::{
     install_acp_library, install_bun, install_python3, install_uv, update_bun,
     update_bun_with_proxy, update_uv, update_uv_with_proxy,
 };
+use crate::artifacts::preview_console::{
+    clear_preview_console_logs, get_preview_console_logs, report_preview_console,
+};
 use crate::artifacts::preview_router::{
     confirm_environment_install, preview_react_component, restore_artifact_preview,
     retry_preview_after_install, run_artifacts,
@@ -115,6 +143,8 @@ use crate::db::llm_db::LLMDatabase;
 use crate::db::mcp_db::MCPDatabase;
 use crate::db::scheduled_task_db::ScheduledTaskDatabase;
 use crate::db::system_db::SystemDatabase;
+use crate::mcp::autostart::get_mcp_server_startup_statuses;
+use crate::mcp::builtin_mcp::search::handler::get_search_browser_liveness;
 use crate::mcp::builtin_mcp::{
     add_or_update_aipp_builtin_server, execute_aipp_builtin_tool,
     handle_preview_file_relay_request, init_builtin_mcp_servers, list_aipp_builtin_templates,
@@ -129,6 +159,8 @@ use crate::mcp::execution_api::{
 use crate::mcp::registry_api::{
     add_mcp_server,
     build_mcp_prompt,
+    bulk_delete_mcp_servers,
+    bulk_toggle_mcp_servers,
     check_disable_agent_mcp,
     check_disable_assistant_agent_mcp,
     check_disable_assistant_operation_mcp,
@@ -142,26 +174,28 @@ use crate::mcp::registry_api::{
     disable_operation_mcp_with_skills,
     enable_operation_mcp_and_skill,
     enable_operation_mcp_and_skills,
+    export_mcp_server,
     get_mcp_provider,
     get_mcp_server,
     get_mcp_server_prompts,
     get_mcp_server_resources,
     get_mcp_server_tools,
     get_mcp_servers,
+    import_mcp_server,
     refresh_mcp_server_capabilities,
     test_mcp_connection,
     toggle_mcp_server,
     update_mcp_server,
     update_mcp_server_prompt,
     update_mcp_server_tool,
+    update_mcp_server_tool_timeout,
 };
 use crate::mcp::summarizer::summarize_all_mcp_catalogs;
 use crate::window::{
-    awaken_aipp, close_sidebar_window, create_ask_window, create_chat_ui_window_hidden,
-    create_config_window_hidden, create_schedule_window_hidden, ensure_hidden_search_window,
-    handle_open_ask_window, open_artifact_collections_window, open_artifact_preview_window,
-    open_chat_ui_window, open_chat_ui_window_inner, open_config_window, open_config_window_inner,
-    open_plugin_window, open_schedule_window, open_sidebar_window,
+    awaken_aipp, close_sidebar_window, ensure_hidden_search_window, handle_open_ask_window,
+    handle_open_chat_window, open_artifact_collections_window, open_artifact_preview_window,
+    open_chat_ui_window, open_config_window, open_config_window_inner, open_plugin_window,
+    open_schedule_window, open_sidebar_window,
 };
 use db::conversation_db::ConversationDatabase;
 use db::database_upgrade;
@@ -172,6 +206,8 @@ use get_selected_text::get_selected_text;
 use serde::{Deserialize, Serialize};
 use state::activity_state::ConversationActivityManager;
 use state::message_token::MessageTokenManager;
+use state::model_list_cache::ModelListCacheState;
+use state::model_select_cache::ModelSelectCacheState;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::path::BaseDirectory;
@@ -191,6 +227,10 @@ use tracing_subscriber::{EnvFilter, FmtSubscriber};
 struct AppState {
     selected_text: TokioMutex<String>,
     recording_shortcut: TokioMutex<bool>,
+    /// 当前注册的 Chat 全局快捷键 id，用于在统一的快捷键回调中区分 Ask / Chat
+    chat_shortcut_id: TokioMutex<Option<u32>>,
+    /// 最近一次全局快捷键注册结果
+    shortcut_status: TokioMutex<Vec<ShortcutRegistrationStatus>>,
 }
 
 #[derive(Clone)]
@@ -262,6 +302,13 @@ async fn get_selected() -> Result<String, String> {
                 result = fallback;
             }
         }
+        // Fallback on Linux: read the PRIMARY selection via wl-paste / xclip / xsel
+        #[cfg(target_os = "linux")]
+        if result.is_empty() {
+            if let Some(fallback) = read_primary_selection_fallback() {
+                result = fallback;
+            }
+        }
         debug!(?result, "initialization result");
         Ok(result)
     }
@@ -354,6 +401,55 @@ fn copy_selection_via_clipboard_fallback() -> Option<String> {
     }
 }
 
+/// Linux 下读取 PRIMARY 选区（鼠标选中即写入，无需模拟复制）
+///
+/// Wayland 会话优先使用 `wl-paste -p`，X11（含 XWayland）依次尝试 `xclip`、`xsel`，
+/// 未安装的工具会跳过。PRIMARY 选区只读不写，无需像 macOS 那样恢复剪贴板。
+#[cfg(target_os = "linux")]
+fn read_primary_selection_fallback() -> Option<String> {
+    use std::process::{Command, Stdio};
+    let t_total = std::time::Instant::now();
+
+    let mut candidates: Vec<(&str, &[&str])> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        candidates.push(("wl-paste", &["--primary", "--no-newline"]));
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        candidates.push(("xclip", &["-o", "-selection", "primary"]));
+        candidates.push(("xsel", &["--output", "--primary"]));
+    }
+
+    for (program, args) in candidates {
+        let Ok(path) = which::which(program) else {
+            debug!(program, "Selection tool not available, skipping");
+            continue;
+        };
+        let output = match Command::new(path)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                debug!(program, error=%e, "Failed to run selection tool");
+                continue;
+            }
+        };
+        if !output.status.success() {
+            debug!(program, status=%output.status, "Selection tool returned no selection");
+            continue;
+        }
+        let text = String::from_utf8_lossy(&output.stdout).to_string();
+        if !text.trim().is_empty() {
+            info!(program, elapsed_ms=%t_total.elapsed().as_millis(), len=text.len(), "PRIMARY selection fallback succeeded");
+            return Some(text);
+        }
+    }
+    None
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 初始化 tracing 日志 (RUST_LOG 环境变量可覆盖)
@@ -411,13 +507,7 @@ pub fn run() {
                         ..
                     } = event
                     {
-                        if let Some(chat_window) =
-                            app_handle_for_click.get_webview_window("chat_ui")
-                        {
-                            open_chat_ui_window_inner(&app_handle_for_click, &chat_window);
-                        } else {
-                            crate::window::create_chat_ui_window(&app_handle_for_click);
-                        }
+                        handle_open_chat_window(&app_handle_for_click);
                     }
                 });
 
@@ -427,11 +517,7 @@ pub fn run() {
                         handle_open_ask_window(app);
                     }
                     "chat" => {
-                        if let Some(chat_window) = app.get_webview_window("chat_ui") {
-                            open_chat_ui_window_inner(app, &chat_window);
-                        } else {
-                            crate::window::create_chat_ui_window(app);
-                        }
+                        handle_open_chat_window(app);
                     }
                     "config" => {
                         if let Some(config_window) = app.get_webview_window("config") {
@@ -454,6 +540,12 @@ pub fn run() {
             )?;
             debug!(?resource_path, "resource path");
 
+            // 加载用户导入的代码高亮主题
+            load_custom_themes(&custom_theme_dir(&app_handle)?);
+
+            // 数据库加密需在打开任何连接之前初始化，密钥缺失或错误时直接报错退出
+            db::encryption::init(&db::get_db_dir(&app_handle)?)?;
+
             let system_db = SystemDatabase::new(&app_handle)?;
             let llm_db = LLMDatabase::new(&app_handle)?;
             let assistant_db = AssistantDatabase::new(&app_handle)?;
@@ -523,6 +615,9 @@ pub fn run() {
                 }
             }
 
+            // 监听技能目录，SKILL.md 修改后无需重启即可生效
+            crate::api::skill_api::start_skills_hot_reload(&app_handle);
+
             let _ = database_upgrade(&app_handle, system_db, llm_db, assistant_db, conversation_db);
 
             // 初始化内置工具集（搜索、操作），如果不存在则自动创建
@@ -536,6 +631,8 @@ pub fn run() {
             {
                 warn!(error = %e, "Failed to cleanup search profile locks on startup");
             }
+            // 按配置预热搜索浏览器，在后台执行，失败只记录警告
+            crate::mcp::builtin_mcp::search::handler::spawn_search_browser_warmup(&app_handle);
 
             // Initialize TodoState with app handle for database persistence
             let todo_state = app.state::<TodoState>();
@@ -547,6 +644,9 @@ pub fn run() {
                 app_handle.clone(),
             );
 
+            // 后台连接标记为自动启动的 MCP 服务器，失败不影响启动
+            crate::mcp::autostart::spawn_mcp_autostart(&app_handle);
+
             // 初始化并启动定时任务调度器
             let scheduler_state = scheduler::SchedulerState::new();
             app.manage(scheduler_state.clone());
@@ -566,21 +666,25 @@ pub fn run() {
                 }
                 #[cfg(desktop)]
                 {
-                    create_chat_ui_window_hidden(&app_handle);
-                    create_config_window_hidden(&app_handle);
-                    create_schedule_window_hidden(&app_handle);
-                    create_ask_window(&app_handle);
+                    crate::launch::open_startup_windows(&app_handle);
                 }
             }
 
+            // 按配置在后台预热开启了连接保活的提供商，缩短首次提问的等待
+            eager_init::spawn_eager_init(&app_handle);
+
             Ok(())
         })
         .manage(AppState {
             selected_text: TokioMutex::new(String::new()),
             recording_shortcut: TokioMutex::new(false),
+            chat_shortcut_id: TokioMutex::new(None),
+            shortcut_status: TokioMutex::new(Vec::new()),
         })
         .manage(AcpSessionState::new())
         .manage(MessageTokenManager::new())
+        .manage(ModelSelectCacheState::new())
+        .manage(ModelListCacheState::new())
         .manage(ConversationActivityManager::new())
         .manage(OperationState::new())
         .manage(AcpPermissionState::new())
@@ -592,12 +696,16 @@ pub fn run() {
     let app = app
         .invoke_handler(tauri::generate_handler![
             ask_ai,
+            confirm_and_send,
+            cancel_send_confirmation,
             tool_result_continue_ask_ai,
             regenerate_ai,
             get_activity_focus,
             get_conversation_runtime_state,
+            get_active_generation_status,
             get_shine_state,
             regenerate_conversation_title,
+            test_assistant,
             generate_artifact_metadata,
             cancel_ai,
             get_selected,
@@ -616,6 +724,14 @@ pub fn run() {
             get_llm_providers,
             get_filtered_providers,
             update_llm_provider,
+            get_environment_profiles,
+            add_environment_profile,
+            update_environment_profile,
+            delete_environment_profile,
+            get_model_prices,
+            set_model_price,
+            delete_model_price,
+            set_active_environment,
             add_llm_provider,
             delete_llm_provider,
             get_llm_provider_config,
@@ -623,6 +739,7 @@ pub fn run() {
             get_llm_models,
             fetch_model_list,
             preview_model_list,
+            test_llm_provider,
             update_selected_models,
             get_models_for_select,
             get_filtered_models_for_select,
@@ -642,20 +759,36 @@ pub fn run() {
             copy_assistant,
             export_assistant,
             import_assistant,
+            list_assistant_templates,
+            create_assistant_from_template,
             list_conversations,
             search_conversations,
+            search_messages,
             get_conversation_with_messages,
+            get_conversation_clean,
             create_conversation_with_messages,
             delete_conversation,
             fork_conversation,
+            summarize_conversation_now,
+            add_conversation_tag,
+            remove_conversation_tag,
+            list_conversation_tags,
+            delete_conversation_tag,
             update_conversation,
             update_message_content,
+            undo_message_edit,
+            redo_message_edit,
+            edit_message,
+            pin_message,
+            unpin_message,
             run_artifacts,
             list_conversation_artifacts,
             restore_artifact_preview,
             save_artifact_to_collection,
             get_artifacts_collection,
             get_artifact_by_id,
+            get_artifact_versions,
+            get_artifact_version,
             search_artifacts_collection,
             update_artifact_collection,
             delete_artifact_collection,
@@ -679,6 +812,11 @@ pub fn run() {
             get_bang_list,
             get_selected_text_api,
             set_shortcut_recording,
+            get_shortcut_status,
+            get_database_encryption_status,
+            enable_database_encryption,
+            create_backup_now,
+            list_backups,
             suspend_global_shortcut,
             resume_global_shortcut,
             copy_image_to_clipboard,
@@ -706,6 +844,9 @@ pub fn run() {
             create_vue_preview,
             create_vue_preview_for_artifact,
             close_vue_preview,
+            report_preview_console,
+            get_preview_console_logs,
+            clear_preview_console_logs,
             run_react_artifact,
             close_react_artifact,
             clear_react_artifact_cache,
@@ -725,13 +866,19 @@ pub fn run() {
             update_mcp_server,
             delete_mcp_server,
             toggle_mcp_server,
+            bulk_toggle_mcp_servers,
+            bulk_delete_mcp_servers,
             get_mcp_server_tools,
             update_mcp_server_tool,
+            update_mcp_server_tool_timeout,
             get_mcp_server_resources,
             get_mcp_server_prompts,
             update_mcp_server_prompt,
             test_mcp_connection,
             refresh_mcp_server_capabilities,
+            get_mcp_server_startup_statuses,
+            export_mcp_server,
+            import_mcp_server,
             summarize_all_mcp_catalogs,
             // Skills 与操作 MCP 联动校验 API
             check_operation_mcp_for_skills,
@@ -748,6 +895,11 @@ pub fn run() {
             get_assistant_mcp_servers_with_tools,
             update_assistant_mcp_config,
             update_assistant_mcp_tool_config,
+            update_assistant_mcp_tool_default_arguments,
+            get_assistant_starters,
+            update_assistant_starters,
+            set_assistant_base,
+            lint_assistant_prompt,
             bulk_update_assistant_mcp_tools,
             update_assistant_model_config_value,
             start_github_copilot_device_flow,
@@ -771,16 +923,24 @@ pub fn run() {
             list_aipp_builtin_templates,
             add_or_update_aipp_builtin_server,
             execute_aipp_builtin_tool,
+            get_search_browser_liveness,
             prepare_preview_file_request_for_ui,
             submit_ask_user_question_response,
             confirm_operation_permission,
+            confirm_operation_permission_batch,
+            get_denied_operation_commands,
             confirm_acp_permission,
             highlight_code,
             ensure_hidden_search_window,
             list_syntect_themes,
+            import_syntect_theme,
             // Skill commands
             scan_skills,
             get_skill_sources,
+            list_git_skill_sources,
+            add_git_skill_source,
+            refresh_skill_source,
+            remove_git_skill_source,
             get_skill_content,
             get_skill,
             skill_exists,
@@ -800,7 +960,11 @@ pub fn run() {
             delete_skill,
             // Token statistics commands
             get_conversation_token_stats,
+            get_conversation_stats,
             get_message_token_stats,
+            export_usage_report,
+            get_usage_insights,
+            get_usage_summary,
             // Autostart commands
             get_autostart_state,
             set_autostart,
@@ -834,13 +998,26 @@ pub fn run() {
             get_todos,
             // Export commands
             markdown_to_docx,
+            export_conversation_html,
+            export_conversation_json,
+            import_conversation,
             markdown_to_pdf,
         ])
         .build(tauri::generate_context!())
         .expect("error while running tauri application");
 
     app.run(|app_handle, e| match e {
-        RunEvent::ExitRequested { api, .. } => {
+        RunEvent::ExitRequested { api, code, .. } => {
+            // 程序化退出（如系统菜单的退出）未经过托盘“退出”时，同样进入清理流程；
+            // 仅关闭所有窗口（code 为 None）时保持后台运行
+            if code.is_some() {
+                let _ = EXIT_STATE.compare_exchange(
+                    EXIT_STATE_IDLE,
+                    EXIT_STATE_REQUESTED,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                );
+            }
             match EXIT_STATE.load(Ordering::SeqCst) {
                 EXIT_STATE_IDLE => {
                     api.prevent_exit();
@@ -858,9 +1035,7 @@ pub fn run() {
                     {
                         let app_handle = app_handle.clone();
                         tauri::async_runtime::spawn(async move {
-                            if let Err(e) = crate::mcp::builtin_mcp::search::handler::shutdown_search_browser_pool().await {
-                                warn!(error = %e, "Failed to shutdown search browser pool");
-                            }
+                            crate::shutdown::graceful_shutdown(&app_handle).await;
                             EXIT_STATE.store(EXIT_STATE_READY, Ordering::SeqCst);
                             app_handle.exit(0);
                         });
@@ -924,14 +1099,15 @@ fn initialize_name_cache_state(app_handle: &tauri::AppHandle) -> NameCacheState
 
 #[cfg(desktop)]
 pub(crate) fn register_global_shortcuts(app_handle: &tauri::AppHandle) {
-    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+    use crate::utils::shortcut_utils::resolve_global_shortcuts;
+    use tauri_plugin_global_shortcut::ShortcutState;
 
     info!("开始注册全局快捷键...");
 
     // 先安装插件（只安装一次即可）。若已安装会返回错误，忽略即可。
     let _ = app_handle.plugin(
         tauri_plugin_global_shortcut::Builder::new()
-            .with_handler(|_app, _shortcut, event| {
+            .with_handler(|_app, shortcut, event| {
                 // 仅在按键释放时触发
                 if event.state() == ShortcutState::Released {
                     let t_event = std::time::Instant::now();
@@ -942,6 +1118,12 @@ pub(crate) fn register_global_shortcuts(app_handle: &tauri::AppHandle) {
                             debug!("正在录入快捷键，忽略全局快捷键事件");
                             return;
                         }
+                        // Chat 快捷键：直接打开 Chat 窗口，不捕获选中文本
+                        if *state.chat_shortcut_id.blocking_lock() == Some(shortcut.id()) {
+                            handle_open_chat_window(_app);
+                            info!(elapsed_ms=%t_event.elapsed().as_millis(), "Chat window opened via global shortcut");
+                            return;
+                        }
                     }
 
                     // macOS：使用“先复制，再延迟聚焦，再后台读取剪贴板”的策略，绕过慢速 crate
@@ -1041,6 +1223,22 @@ pub(crate) fn register_global_shortcuts(app_handle: &tauri::AppHandle) {
                             }
                         }
 
+                        // Linux：快速获取为空时，后台读取 PRIMARY 选区并通知前端
+                        #[cfg(target_os = "linux")]
+                        {
+                            let app_handle = _app.clone();
+                            tauri::async_runtime::spawn_blocking(move || {
+                                if let Some(text) = read_primary_selection_fallback() {
+                                    let _ = app_handle.emit("get_selected_text_event", text.clone());
+                                    if let Some(state) = app_handle.try_state::<AppState>() {
+                                        *state.selected_text.blocking_lock() = text;
+                                    }
+                                } else {
+                                    debug!("PRIMARY selection fallback: no selection");
+                                }
+                            });
+                        }
+
                         let dt_total = t_event.elapsed().as_millis();
                         info!(elapsed_ms=%dt_total, "Global shortcut handling finished initial path");
                     }
@@ -1049,162 +1247,119 @@ pub(crate) fn register_global_shortcuts(app_handle: &tauri::AppHandle) {
             .build(),
     );
 
-    // 根据配置计算需要注册的快捷键字符串（global-hotkey 解析格式）
-    let (shortcut_str, from_fallback) = {
+    // 根据配置计算需要注册的快捷键（global-hotkey 解析格式）
+    let shortcuts = {
         let state = app_handle.state::<FeatureConfigState>();
         let config_feature_map = state.config_feature_map.blocking_lock();
-        if let Some(shortcuts_cfg) = config_feature_map.get("shortcuts") {
-            if let Some(sc) = shortcuts_cfg.get("shortcut") {
-                (sc.value.clone(), false)
-            } else {
-                // 兼容旧字段：modifier_key + Space
-                let modifier = shortcuts_cfg
-                    .get("modifier_key")
-                    .map(|c| c.value.clone())
-                    .unwrap_or_else(|| {
-                        #[cfg(target_os = "macos")]
-                        {
-                            "option".to_string()
-                        }
-                        #[cfg(not(target_os = "macos"))]
-                        {
-                            "alt".to_string()
-                        }
-                    });
-                let mk = modifier.to_lowercase();
-                let mod_token = if mk == "ctrl" || mk == "control" {
-                    "Ctrl"
-                } else if mk == "shift" {
-                    "Shift"
-                } else if mk == "cmd" || mk == "command" || mk == "super" {
-                    #[cfg(target_os = "macos")]
-                    {
-                        "Command"
-                    }
-                    #[cfg(not(target_os = "macos"))]
-                    {
-                        "Super"
-                    }
-                } else if mk == "option" || mk == "alt" {
-                    "Alt"
-                } else {
-                    #[cfg(target_os = "macos")]
-                    {
-                        "Option"
-                    }
-                    #[cfg(not(target_os = "macos"))]
-                    {
-                        "Alt"
-                    }
-                };
-                (format!("{}+Space", mod_token), true)
-            }
-        } else {
-            // 默认值
-            #[cfg(target_os = "macos")]
-            let s = "Option+Space".to_string();
-            #[cfg(not(target_os = "macos"))]
-            let s = "Alt+Space".to_string();
-            (s, true)
-        }
+        resolve_global_shortcuts(config_feature_map.get("shortcuts"))
     };
 
-    // 先清空旧注册，再注册新快捷键
-    if let Err(e) = app_handle.global_shortcut().unregister_all() {
-        debug!(error=%e, "卸载旧全局快捷键失败或未注册，继续");
-    }
-
-    match app_handle.global_shortcut().register(shortcut_str.as_str()) {
-        Ok(_) => {
-            if from_fallback {
-                info!("✓ 成功注册全局快捷键(回退): {}", shortcut_str);
-            } else {
-                info!("✓ 成功注册全局快捷键: {}", shortcut_str);
-            }
-        }
-        Err(e) => {
-            warn!(error=%e, shortcut=%shortcut_str, "无法注册全局快捷键 (可能格式无效或被占用)");
-        }
-    }
+    let (chat_shortcut_id, status) = apply_global_shortcuts(app_handle, &shortcuts);
+    report_shortcut_status(app_handle, &status);
+    let state = app_handle.state::<AppState>();
+    *state.chat_shortcut_id.blocking_lock() = chat_shortcut_id;
+    *state.shortcut_status.blocking_lock() = status;
 }
 
 #[cfg(desktop)]
 pub(crate) async fn reconfigure_global_shortcuts_async(app_handle: &tauri::AppHandle) {
-    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+    use crate::utils::shortcut_utils::resolve_global_shortcuts;
 
     info!("开始重新注册全局快捷键(异步)...");
 
-    // 计算当前配置的快捷键字符串（异步锁避免阻塞 runtime）
-    let shortcut_str = {
+    // 计算当前配置的快捷键（异步锁避免阻塞 runtime）
+    let shortcuts = {
         let state = app_handle.state::<FeatureConfigState>();
         let config_feature_map = state.config_feature_map.lock().await;
-        if let Some(shortcuts_cfg) = config_feature_map.get("shortcuts") {
-            if let Some(sc) = shortcuts_cfg.get("shortcut") {
-                sc.value.clone()
-            } else {
-                // 回退基于旧字段 modifier_key
-                let modifier = shortcuts_cfg
-                    .get("modifier_key")
-                    .map(|c| c.value.clone())
-                    .unwrap_or_else(|| {
-                        #[cfg(target_os = "macos")]
-                        {
-                            "option".to_string()
-                        }
-                        #[cfg(not(target_os = "macos"))]
-                        {
-                            "alt".to_string()
-                        }
-                    });
-                let mk = modifier.to_lowercase();
-                let mod_token = if mk == "ctrl" || mk == "control" {
-                    "Ctrl"
-                } else if mk == "shift" {
-                    "Shift"
-                } else if mk == "cmd" || mk == "command" || mk == "super" {
-                    #[cfg(target_os = "macos")]
-                    {
-                        "Command"
-                    }
-                    #[cfg(not(target_os = "macos"))]
-                    {
-                        "Super"
-                    }
-                } else if mk == "option" || mk == "alt" {
-                    "Alt"
-                } else {
-                    #[cfg(target_os = "macos")]
-                    {
-                        "Option"
-                    }
-                    #[cfg(not(target_os = "macos"))]
-                    {
-                        "Alt"
-                    }
-                };
-                format!("{}+Space", mod_token)
-            }
-        } else {
-            // 默认值
-            #[cfg(target_os = "macos")]
-            let s = "Option+Space".to_string();
-            #[cfg(not(target_os = "macos"))]
-            let s = "Alt+Space".to_string();
-            s
-        }
+        resolve_global_shortcuts(config_feature_map.get("shortcuts"))
     };
 
-    // 重新注册
+    let (chat_shortcut_id, status) = apply_global_shortcuts(app_handle, &shortcuts);
+    report_shortcut_status(app_handle, &status);
+    let state = app_handle.state::<AppState>();
+    *state.chat_shortcut_id.lock().await = chat_shortcut_id;
+    *state.shortcut_status.lock().await = status;
+}
+
+/// 清空旧注册后注册 Ask / Chat 快捷键，返回成功注册的 Chat 快捷键 id 与每个快捷键的注册结果
+///
+/// Chat 快捷键与 Ask 相同时只注册 Ask，Chat 记为注册失败
+#[cfg(desktop)]
+fn apply_global_shortcuts(
+    app_handle: &tauri::AppHandle,
+    shortcuts: &crate::utils::shortcut_utils::GlobalShortcuts,
+) -> (Option<u32>, Vec<ShortcutRegistrationStatus>) {
+    use crate::utils::shortcut_utils::shortcuts_conflict;
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
     if let Err(e) = app_handle.global_shortcut().unregister_all() {
         debug!(error=%e, "卸载旧全局快捷键失败或未注册，继续");
     }
-    match app_handle.global_shortcut().register(shortcut_str.as_str()) {
-        Ok(_) => info!("✓ 成功注册全局快捷键: {}", shortcut_str),
+
+    let status_of =
+        |action: &str, shortcut: &str, result: Result<(), String>| ShortcutRegistrationStatus {
+            action: action.to_string(),
+            shortcut: shortcut.to_string(),
+            registered: result.is_ok(),
+            error: result.err(),
+        };
+
+    let ask_result = match app_handle.global_shortcut().register(shortcuts.ask.as_str()) {
+        Ok(_) => {
+            if shortcuts.ask_from_fallback {
+                info!("✓ 成功注册 Ask 全局快捷键(回退): {}", shortcuts.ask);
+            } else {
+                info!("✓ 成功注册 Ask 全局快捷键: {}", shortcuts.ask);
+            }
+            Ok(())
+        }
         Err(e) => {
-            warn!(error=%e, shortcut=%shortcut_str, "无法注册全局快捷键 (可能格式无效或被占用)")
+            warn!(error=%e, shortcut=%shortcuts.ask, "无法注册 Ask 全局快捷键 (可能格式无效或被占用)");
+            Err(e.to_string())
         }
+    };
+    let mut status = vec![status_of("ask", &shortcuts.ask, ask_result)];
+
+    let Some(chat) = shortcuts.chat.as_deref() else {
+        return (None, status);
+    };
+    let mut chat_shortcut_id = None;
+    let chat_result = if shortcuts_conflict(chat, &shortcuts.ask) {
+        warn!(ask=%shortcuts.ask, chat=%chat, "Chat 全局快捷键与 Ask 快捷键冲突，已跳过注册");
+        Err(format!("与 Ask 快捷键 {} 冲突", shortcuts.ask))
+    } else {
+        match chat.parse::<Shortcut>() {
+            Ok(shortcut) => match app_handle.global_shortcut().register(shortcut) {
+                Ok(_) => {
+                    info!("✓ 成功注册 Chat 全局快捷键: {}", chat);
+                    chat_shortcut_id = Some(shortcut.id());
+                    Ok(())
+                }
+                Err(e) => {
+                    warn!(error=%e, shortcut=%chat, "无法注册 Chat 全局快捷键 (可能被占用)");
+                    Err(e.to_string())
+                }
+            },
+            Err(e) => {
+                warn!(error=%e, shortcut=%chat, "Chat 全局快捷键格式无效");
+                Err(e.to_string())
+            }
+        }
+    };
+    status.push(status_of("chat", chat, chat_result));
+    (chat_shortcut_id, status)
+}
+
+/// 通知前端快捷键注册结果：每个失败项发送 `shortcut_registration_failed`，
+/// 并发送完整的 `shortcut_status_changed`，重新注册成功后前端据此清除错误提示
+#[cfg(desktop)]
+fn report_shortcut_status(app_handle: &tauri::AppHandle, status: &[ShortcutRegistrationStatus]) {
+    for failed in status.iter().filter(|s| !s.registered) {
+        let _ = app_handle.emit("shortcut_registration_failed", failed);
     }
+    let _ = app_handle.emit("shortcut_status_changed", status);
 }
+
 const EXIT_STATE_IDLE: u8 = 0;
 const EXIT_STATE_REQUESTED: u8 = 1;
 const EXIT_STATE_CLEANING: u8 = 2;
@@ -0,0 +1,200 @@
+//! MCP 服务器自动启动：应用启动时在后台连接标记了自动启动的已启用服务器并刷新能力，
+//! 避免首次调用工具时才去拉起进程、获取工具列表。连接失败只记录日志和启动状态，不影响应用启动。
+
+use crate::db::mcp_db::{MCPDatabase, MCPServer};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+static STARTUP_STATUSES: OnceLock<Mutex<HashMap<i64, MCPServerStartupStatus>>> = OnceLock::new();
+
+fn startup_statuses() -> &'static Mutex<HashMap<i64, MCPServerStartupStatus>> {
+    STARTUP_STATUSES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MCPServerStartupState {
+    Connecting,
+    Connected,
+    Failed,
+}
+
+/// 自动启动的服务器在本次运行中的连接状态
+#[derive(Debug, Clone, Serialize)]
+pub struct MCPServerStartupStatus {
+    pub server_id: i64,
+    pub server_name: String,
+    pub state: MCPServerStartupState,
+    pub error: Option<String>,
+    pub updated_time: String,
+}
+
+fn record_status(server: &MCPServer, state: MCPServerStartupState, error: Option<String>) {
+    let status = MCPServerStartupStatus {
+        server_id: server.id,
+        server_name: server.name.clone(),
+        state,
+        error,
+        updated_time: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    startup_statuses().lock().unwrap().insert(server.id, status);
+}
+
+/// 获取服务器的自动启动状态，未参与自动启动时返回 None
+pub fn get_startup_status(server_id: i64) -> Option<MCPServerStartupStatus> {
+    startup_statuses().lock().unwrap().get(&server_id).cloned()
+}
+
+/// 需要自动启动的服务器：已启用且开启了自动启动
+pub fn autostart_servers(servers: Vec<MCPServer>) -> Vec<MCPServer> {
+    servers.into_iter().filter(|server| server.is_enabled && server.autostart).collect()
+}
+
+/// 并发连接所有需要自动启动的服务器，逐个记录结果；单个服务器失败不影响其他服务器
+pub async fn run_autostart<F, Fut>(
+    servers: Vec<MCPServer>,
+    connect: F,
+) -> Vec<MCPServerStartupStatus>
+where
+    F: Fn(MCPServer) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let servers = autostart_servers(servers);
+    let tasks = servers.into_iter().map(|server| {
+        record_status(&server, MCPServerStartupState::Connecting, None);
+        let connecting = connect(server.clone());
+        async move {
+            match connecting.await {
+                Ok(()) => {
+                    info!(server_id = server.id, name = %server.name, "MCP server autostarted");
+                    record_status(&server, MCPServerStartupState::Connected, None);
+                }
+                Err(e) => {
+                    warn!(
+                        server_id = server.id,
+                        name = %server.name,
+                        error = %e,
+                        "MCP server autostart failed"
+                    );
+                    record_status(&server, MCPServerStartupState::Failed, Some(e));
+                }
+            }
+            get_startup_status(server.id)
+        }
+    });
+    futures::future::join_all(tasks).await.into_iter().flatten().collect()
+}
+
+/// 应用启动时调用：在后台连接自动启动的服务器并刷新能力，不阻塞启动流程
+pub fn spawn_mcp_autostart(app_handle: &tauri::AppHandle) {
+    let servers = match MCPDatabase::new(app_handle).and_then(|db| db.get_mcp_servers()) {
+        Ok(servers) => servers,
+        Err(e) => {
+            warn!(error = %e, "Failed to load MCP servers for autostart");
+            return;
+        }
+    };
+    if !servers.iter().any(|server| server.is_enabled && server.autostart) {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        run_autostart(servers, |server| {
+            let app_handle = app_handle.clone();
+            async move {
+                crate::mcp::registry_api::fetch_mcp_server_capabilities(app_handle, server.id)
+                    .await
+                    .map(|_| ())
+            }
+        })
+        .await;
+    });
+}
+
+#[tauri::command]
+pub async fn get_mcp_server_startup_statuses() -> Result<Vec<MCPServerStartupStatus>, String> {
+    let mut statuses: Vec<_> = startup_statuses().lock().unwrap().values().cloned().collect();
+    statuses.sort_by_key(|status| status.server_id);
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn server(id: i64, is_enabled: bool, autostart: bool) -> MCPServer {
+        MCPServer {
+            id,
+            name: format!("server-{}", id),
+            description: String::new(),
+            transport_type: "stdio".to_string(),
+            command: Some("npx some-mcp-server".to_string()),
+            environment_variables: None,
+            headers: None,
+            url: None,
+            timeout: None,
+            is_long_running: false,
+            is_enabled,
+            is_builtin: false,
+            is_deletable: true,
+            proxy_enabled: false,
+            created_time: String::new(),
+            autostart,
+            status: None,
+            last_healthy_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_autostart_connects_enabled_autostart_servers_only() {
+        let connected = Arc::new(Mutex::new(Vec::new()));
+        let servers = vec![
+            server(9_200_001, true, true),
+            server(9_200_002, true, false),
+            server(9_200_003, false, true),
+        ];
+
+        let statuses = run_autostart(servers, |server| {
+            let connected = connected.clone();
+            async move {
+                connected.lock().unwrap().push(server.id);
+                Ok(())
+            }
+        })
+        .await;
+
+        assert_eq!(*connected.lock().unwrap(), vec![9_200_001]);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].state, MCPServerStartupState::Connected);
+        assert_eq!(
+            get_startup_status(9_200_001).map(|status| status.state),
+            Some(MCPServerStartupState::Connected)
+        );
+        assert!(get_startup_status(9_200_002).is_none());
+        assert!(get_startup_status(9_200_003).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_autostart_failure_is_recorded_without_affecting_others() {
+        let servers = vec![server(9_200_011, true, true), server(9_200_012, true, true)];
+
+        run_autostart(servers, |server| async move {
+            if server.id == 9_200_011 {
+                Err("spawn failed".to_string())
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+
+        let failed = get_startup_status(9_200_011).unwrap();
+        assert_eq!(failed.state, MCPServerStartupState::Failed);
+        assert_eq!(failed.error.as_deref(), Some("spawn failed"));
+        assert_eq!(get_startup_status(9_200_012).unwrap().state, MCPServerStartupState::Connected);
+    }
+}
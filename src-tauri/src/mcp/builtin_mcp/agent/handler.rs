@@ -1,12 +1,23 @@
 //! Agent handler - implements agent tool operations
 
 use super::types::*;
-use crate::api::skill_api::get_skill_content_internal;
+use crate::api::conversation_api::get_conversation_with_messages;
+use crate::api::skill_api::{create_scanner, get_skill_content_internal};
+use crate::db::conversation_db::MessageDetail;
 use crate::skills::scanner::SkillScanner;
-use std::path::PathBuf;
+use crate::NameCacheState;
 use tauri::{AppHandle, Manager};
 use tracing::{debug, error, info, instrument};
 
+/// Default token budget for `read_conversation` output
+pub const READ_CONVERSATION_DEFAULT_MAX_TOKENS: usize = 8000;
+
+/// Rough chars-per-token ratio used to bound `read_conversation` output
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Message types exposed to `read_conversation`; system prompts and reasoning are never returned
+const READABLE_MESSAGE_TYPES: &[&str] = &["user", "response", "tool_result"];
+
 /// Handler for Agent tools
 pub struct AgentHandler {
     app_handle: AppHandle,
@@ -17,19 +28,9 @@ impl AgentHandler {
         Self { app_handle }
     }
 
-    /// Get home directory path
-    fn get_home_dir() -> PathBuf {
-        dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
-    }
-
-    /// Get app data directory path
-    fn get_app_data_dir(&self) -> PathBuf {
-        self.app_handle.path().app_data_dir().unwrap_or_else(|_| PathBuf::from("."))
-    }
-
-    /// Create a skill scanner
+    /// Create a skill scanner (built-in and git sources)
     fn create_scanner(&self) -> SkillScanner {
-        SkillScanner::new(Self::get_home_dir(), self.get_app_data_dir())
+        create_scanner(&self.app_handle)
     }
 
     /// Load a skill's content by name and source type
@@ -109,4 +110,77 @@ impl AgentHandler {
             }
         }
     }
+
+    /// Read the messages of the active conversation, bounded by a token budget
+    #[instrument(skip(self, request))]
+    pub async fn read_conversation(
+        &self,
+        conversation_id: i64,
+        request: ReadConversationRequest,
+    ) -> Result<ReadConversationResponse, String> {
+        let name_cache_state = self.app_handle.state::<NameCacheState>();
+        let conversation = get_conversation_with_messages(
+            self.app_handle.clone(),
+            name_cache_state,
+            conversation_id,
+            None,
+            None,
+        )
+        .await?;
+        let response = build_read_conversation_response(conversation.messages, &request);
+        debug!(
+            total = response.total,
+            returned = response.messages.len(),
+            truncated = response.truncated,
+            "Conversation read for agent"
+        );
+        Ok(response)
+    }
+}
+
+/// Select the visible messages in range and fit them into the token budget.
+///
+/// When the budget is exceeded the most recent messages are kept, since the
+/// tail of the selected range is usually what the model needs to continue.
+pub fn build_read_conversation_response(
+    messages: Vec<MessageDetail>,
+    request: &ReadConversationRequest,
+) -> ReadConversationResponse {
+    let visible: Vec<MessageDetail> = messages
+        .into_iter()
+        .filter(|m| READABLE_MESSAGE_TYPES.contains(&m.message_type.as_str()))
+        .collect();
+    let total = visible.len();
+
+    let start = request.offset.unwrap_or(1).max(1) - 1;
+    let limit = request.limit.unwrap_or(total);
+    let max_tokens = request.max_tokens.unwrap_or(READ_CONVERSATION_DEFAULT_MAX_TOKENS).max(1);
+    let mut remaining_chars = max_tokens * CHARS_PER_TOKEN;
+
+    let mut truncated = false;
+    let mut selected = Vec::new();
+    for (i, message) in visible.into_iter().enumerate().skip(start).take(limit).rev() {
+        if remaining_chars == 0 {
+            truncated = true;
+            break;
+        }
+        let char_count = message.content.chars().count();
+        let content = if char_count > remaining_chars {
+            truncated = true;
+            let kept: String = message.content.chars().take(remaining_chars).collect();
+            format!("{}\n...[truncated]", kept)
+        } else {
+            message.content
+        };
+        remaining_chars = remaining_chars.saturating_sub(char_count);
+        selected.push(ConversationMessageEntry {
+            index: i + 1,
+            id: message.id,
+            message_type: message.message_type,
+            content,
+        });
+    }
+    selected.reverse();
+
+    ReadConversationResponse { total, messages: selected, truncated }
 }
@@ -2,6 +2,9 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::db::conversation_db::{Message, MessageDetail};
+    use crate::db::tests::test_helpers::{create_test_message, create_test_message_detail};
+    use crate::mcp::builtin_mcp::agent::handler::build_read_conversation_response;
     use crate::mcp::builtin_mcp::agent::types::*;
     use crate::mcp::builtin_mcp::templates::{get_builtin_tools_for_command, BuiltinToolInfo};
 
@@ -163,4 +166,72 @@ mod tests {
         assert_eq!(parsed.path, file.path);
         assert_eq!(parsed.content, file.content);
     }
+
+    // ============================================
+    // read_conversation Tests
+    // ============================================
+
+    fn message(id: i64, message_type: &str, content: &str) -> MessageDetail {
+        create_test_message_detail(Message {
+            id,
+            ..create_test_message(1, message_type, content, None, None)
+        })
+    }
+
+    fn conversation_fixture() -> Vec<MessageDetail> {
+        vec![
+            message(1, "system", "secret system prompt"),
+            message(2, "user", "What is Rust?"),
+            message(3, "reasoning", "thinking..."),
+            message(4, "response", "Rust is a systems language."),
+            message(5, "user", "Show an example"),
+            message(6, "response", "fn main() {}"),
+        ]
+    }
+
+    #[test]
+    fn test_read_conversation_tool_exists() {
+        let tools = get_builtin_tools_for_command("aipp:agent");
+        let tool: Option<&BuiltinToolInfo> = tools.iter().find(|t| t.name == "read_conversation");
+        assert!(tool.is_some(), "read_conversation tool should exist");
+    }
+
+    /// 测试 read_conversation 按顺序返回当前对话的可见消息
+    ///
+    /// 验证内容：
+    /// - system / reasoning 消息不会返回
+    /// - 返回的消息保持时间顺序，index 从 1 开始
+    #[test]
+    fn test_read_conversation_returns_messages_in_order() {
+        let request = ReadConversationRequest { offset: None, limit: None, max_tokens: None };
+        let response = build_read_conversation_response(conversation_fixture(), &request);
+
+        assert_eq!(response.total, 4);
+        assert!(!response.truncated);
+        let ids: Vec<i64> = response.messages.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![2, 4, 5, 6]);
+        let indexes: Vec<usize> = response.messages.iter().map(|m| m.index).collect();
+        assert_eq!(indexes, vec![1, 2, 3, 4]);
+        assert!(response.messages.iter().all(|m| !m.content.contains("secret")));
+    }
+
+    #[test]
+    fn test_read_conversation_range() {
+        let request = ReadConversationRequest { offset: Some(2), limit: Some(2), max_tokens: None };
+        let response = build_read_conversation_response(conversation_fixture(), &request);
+
+        let ids: Vec<i64> = response.messages.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+        assert_eq!(response.total, 4);
+    }
+
+    #[test]
+    fn test_read_conversation_token_budget_keeps_latest() {
+        let request = ReadConversationRequest { offset: None, limit: None, max_tokens: Some(5) };
+        let response = build_read_conversation_response(conversation_fixture(), &request);
+
+        assert!(response.truncated);
+        assert_eq!(response.messages.last().map(|m| m.id), Some(6));
+        assert!(response.messages.iter().all(|m| m.id != 2));
+    }
 }
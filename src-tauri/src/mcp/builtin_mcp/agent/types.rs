@@ -34,3 +34,38 @@ pub struct SkillFileContent {
     /// File content
     pub content: String,
 }
+
+/// Request to re-read messages of the active conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadConversationRequest {
+    /// Start position (1-indexed) among the visible messages
+    pub offset: Option<usize>,
+    /// Maximum number of messages to return
+    pub limit: Option<usize>,
+    /// Approximate token budget for the returned content
+    pub max_tokens: Option<usize>,
+}
+
+/// A single message returned by `read_conversation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationMessageEntry {
+    /// Position (1-indexed) among the visible messages
+    pub index: usize,
+    /// Message id
+    pub id: i64,
+    /// Message type (user / response / tool_result)
+    pub message_type: String,
+    /// Message content, may be cut off to fit the token budget
+    pub content: String,
+}
+
+/// Response from reading the conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadConversationResponse {
+    /// Total number of visible messages in the conversation
+    pub total: usize,
+    /// Messages in chronological order
+    pub messages: Vec<ConversationMessageEntry>,
+    /// Whether messages were dropped or cut off because of the token budget
+    pub truncated: bool,
+}
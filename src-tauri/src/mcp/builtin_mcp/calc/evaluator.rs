@@ -0,0 +1,474 @@
+use super::units::{convert, find_unit, Unit};
+
+/// 表达式最大长度（字符数），避免模型传入超长内容
+pub const MAX_EXPRESSION_LEN: usize = 500;
+/// 括号与一元运算的最大嵌套深度，避免递归过深
+const MAX_NESTING_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    Percent,
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// 计算结果；单位换算时 `unit` 为目标单位
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub value: f64,
+    pub unit: Option<Unit>,
+}
+
+/// 运算数；`percent` 标记由 `%` 得到的值，用于 `a + b%` 这类按比例增减的写法
+#[derive(Debug, Clone, Copy)]
+struct Operand {
+    value: f64,
+    percent: bool,
+}
+
+impl Operand {
+    fn plain(value: f64) -> Self {
+        Operand { value, percent: false }
+    }
+}
+
+/// 计算表达式：支持四则运算、乘方、取模、百分比（`18% of 2450`、`200 + 10%`）、
+/// 常用函数与常量，以及 `5 km to mi` 形式的单位换算
+pub fn evaluate(expression: &str) -> Result<Evaluation, String> {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Err("Expression is empty".to_string());
+    }
+    let len = expression.chars().count();
+    if len > MAX_EXPRESSION_LEN {
+        return Err(format!(
+            "Expression is too long ({} characters, max {})",
+            len, MAX_EXPRESSION_LEN
+        ));
+    }
+
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser { tokens, pos: 0, depth: 0 };
+    let value = parser.parse_expr()?.value;
+
+    let from_unit = match parser.peek() {
+        Some(Token::Ident(name)) => find_unit(name),
+        _ => None,
+    };
+    let unit = match from_unit {
+        Some(from) => {
+            parser.pos += 1;
+            Some(parser.parse_conversion(value, from)?)
+        }
+        None => None,
+    };
+    if let Some(token) = parser.peek() {
+        return Err(format!("Unexpected token: {}", describe_token(token)));
+    }
+
+    let evaluation = match unit {
+        Some((converted, to)) => Evaluation { value: converted, unit: Some(to) },
+        None => Evaluation { value, unit: None },
+    };
+    if !evaluation.value.is_finite() {
+        return Err("Result is not a finite number".to_string());
+    }
+    Ok(evaluation)
+}
+
+/// 格式化结果：整数不带小数点，其余最多保留 10 位小数并去掉末尾的 0
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    if value.abs() >= 1e15 || value.abs() < 1e-10 {
+        return format!("{:e}", value);
+    }
+    let formatted = format!("{:.10}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == '_')
+                {
+                    i += 1;
+                }
+                // 科学计数法：1e3、2.5E-4
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+                let number =
+                    text.parse::<f64>().map_err(|_| format!("Invalid number: {}", text))?;
+                tokens.push(Token::Number(number));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '°' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '°') {
+                    i += 1;
+                }
+                let ident: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(ident.trim_start_matches('°').to_string()));
+                continue;
+            }
+            '+' => tokens.push(Token::Plus),
+            '-' | '−' => tokens.push(Token::Minus),
+            '*' | '×' => {
+                if chars.get(i + 1) == Some(&'*') {
+                    tokens.push(Token::Caret);
+                    i += 1;
+                } else {
+                    tokens.push(Token::Star);
+                }
+            }
+            '/' | '÷' => tokens.push(Token::Slash),
+            '^' => tokens.push(Token::Caret),
+            '%' => tokens.push(Token::Percent),
+            '(' => tokens.push(Token::LParen),
+            ')' => tokens.push(Token::RParen),
+            ',' => tokens.push(Token::Comma),
+            other => return Err(format!("Unexpected character: '{}'", other)),
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format_number(*n),
+        Token::Ident(name) => format!("'{}'", name),
+        Token::Plus => "'+'".to_string(),
+        Token::Minus => "'-'".to_string(),
+        Token::Star => "'*'".to_string(),
+        Token::Slash => "'/'".to_string(),
+        Token::Caret => "'^'".to_string(),
+        Token::Percent => "'%'".to_string(),
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+        Token::Comma => "','".to_string(),
+    }
+}
+
+fn is_keyword(token: Option<&Token>, keyword: &str) -> bool {
+    matches!(token, Some(Token::Ident(name)) if name.eq_ignore_ascii_case(keyword))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+        Ok(())
+    }
+
+    /// `<数值> <单位> to|in|as <单位>`，单位已在调用前读取
+    fn parse_conversion(&mut self, value: f64, from: Unit) -> Result<(f64, Unit), String> {
+        let peek = self.peek();
+        if !(is_keyword(peek, "to") || is_keyword(peek, "in") || is_keyword(peek, "as")) {
+            return Err(format!("Expected 'to' after unit {}, e.g. '5 km to mi'", from.symbol));
+        }
+        self.pos += 1;
+        let to = match self.next() {
+            Some(Token::Ident(name)) => {
+                find_unit(&name).ok_or_else(|| format!("Unknown unit: {}", name))?
+            }
+            _ => return Err("Expected target unit after 'to'".to_string()),
+        };
+        Ok((convert(value, from, to)?, to))
+    }
+
+    fn parse_expr(&mut self) -> Result<Operand, String> {
+        let mut lhs = self.parse_term()?;
+        loop {
+            let sign = match self.peek() {
+                Some(Token::Plus) => 1.0,
+                Some(Token::Minus) => -1.0,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = self.parse_term()?;
+            // 200 + 10% 表示在 200 的基础上增加 10%
+            let delta = if rhs.percent && !lhs.percent { lhs.value * rhs.value } else { rhs.value };
+            lhs = Operand::plain(lhs.value + sign * delta);
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Operand, String> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => "*",
+                Some(Token::Slash) => "/",
+                token if is_keyword(token, "of") => "*",
+                token if is_keyword(token, "mod") => "mod",
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            let value = match op {
+                "*" => lhs.value * rhs.value,
+                "/" => {
+                    if rhs.value == 0.0 {
+                        return Err("Division by zero".to_string());
+                    }
+                    lhs.value / rhs.value
+                }
+                _ => {
+                    if rhs.value == 0.0 {
+                        return Err("Modulo by zero".to_string());
+                    }
+                    lhs.value % rhs.value
+                }
+            };
+            lhs = Operand::plain(value);
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Operand, String> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.pos += 1;
+                self.enter()?;
+                let operand = self.parse_unary()?;
+                self.depth -= 1;
+                Ok(Operand { value: -operand.value, percent: operand.percent })
+            }
+            Some(Token::Plus) => {
+                self.pos += 1;
+                self.enter()?;
+                let operand = self.parse_unary();
+                self.depth -= 1;
+                operand
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    /// 乘方为右结合，且优先级高于一元负号：-2^2 = -4
+    fn parse_power(&mut self) -> Result<Operand, String> {
+        let base = self.parse_postfix()?;
+        if self.peek() != Some(&Token::Caret) {
+            return Ok(base);
+        }
+        self.pos += 1;
+        self.enter()?;
+        let exponent = self.parse_unary()?;
+        self.depth -= 1;
+        Ok(Operand::plain(base.value.powf(exponent.value)))
+    }
+
+    fn parse_postfix(&mut self) -> Result<Operand, String> {
+        let mut operand = self.parse_primary()?;
+        while self.peek() == Some(&Token::Percent) {
+            self.pos += 1;
+            operand = Operand { value: operand.value / 100.0, percent: true };
+        }
+        Ok(operand)
+    }
+
+    fn parse_primary(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Operand::plain(n)),
+            Some(Token::LParen) => {
+                self.enter()?;
+                let inner = self.parse_expr()?;
+                self.depth -= 1;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    let args = self.parse_arguments()?;
+                    return call_function(&name, &args).map(Operand::plain);
+                }
+                match name.to_lowercase().as_str() {
+                    "pi" | "π" => Ok(Operand::plain(std::f64::consts::PI)),
+                    "e" => Ok(Operand::plain(std::f64::consts::E)),
+                    _ => Err(format!("Unknown identifier: {}", name)),
+                }
+            }
+            Some(token) => Err(format!("Unexpected token: {}", describe_token(&token))),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+
+    /// 读取函数参数，左括号已消费
+    fn parse_arguments(&mut self) -> Result<Vec<f64>, String> {
+        self.enter()?;
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_expr()?.value);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => break,
+                _ => return Err("Missing closing parenthesis".to_string()),
+            }
+        }
+        self.depth -= 1;
+        Ok(args)
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Result<f64, String> {
+    let lower = name.to_lowercase();
+    let unary = |f: fn(f64) -> f64| -> Result<f64, String> {
+        match args {
+            [x] => Ok(f(*x)),
+            _ => Err(format!("{}() takes exactly 1 argument", name)),
+        }
+    };
+    match lower.as_str() {
+        "sqrt" => match args {
+            [x] if *x < 0.0 => Err("sqrt() of a negative number".to_string()),
+            _ => unary(f64::sqrt),
+        },
+        "abs" => unary(f64::abs),
+        "round" => unary(f64::round),
+        "floor" => unary(f64::floor),
+        "ceil" => unary(f64::ceil),
+        "exp" => unary(f64::exp),
+        "sin" => unary(f64::sin),
+        "cos" => unary(f64::cos),
+        "tan" => unary(f64::tan),
+        "ln" | "log" | "log10" | "log2" => {
+            if args.iter().any(|x| *x <= 0.0) {
+                return Err(format!("{}() of a non-positive number", name));
+            }
+            match lower.as_str() {
+                "ln" => unary(f64::ln),
+                "log2" => unary(f64::log2),
+                _ => unary(f64::log10),
+            }
+        }
+        "min" | "max" => {
+            if args.is_empty() {
+                return Err(format!("{}() needs at least 1 argument", name));
+            }
+            let fold: fn(f64, f64) -> f64 = if lower == "min" { f64::min } else { f64::max };
+            Ok(args.iter().copied().fold(args[0], fold))
+        }
+        _ => Err(format!("Unknown function: {}", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(expression: &str) -> f64 {
+        evaluate(expression).unwrap().value
+    }
+
+    #[test]
+    fn test_arithmetic_precedence() {
+        assert_eq!(value("1 + 2 * 3"), 7.0);
+        assert_eq!(value("(1 + 2) * 3"), 9.0);
+        assert_eq!(value("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(value("-2^2"), -4.0);
+        assert_eq!(value("7 mod 4 + 10 / 4"), 5.5);
+        assert_eq!(value("1_000 * 1.5e3"), 1_500_000.0);
+        assert_eq!(value("max(3, sqrt(16), 2) + abs(-1)"), 5.0);
+    }
+
+    #[test]
+    fn test_percentages() {
+        assert_eq!(value("18% of 2450"), 441.0);
+        assert_eq!(value("2450 * 18%"), 441.0);
+        assert_eq!(value("200 + 10%"), 220.0);
+        assert_eq!(value("200 - 25%"), 150.0);
+        assert_eq!(value("50%"), 0.5);
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        let result = evaluate("1.5 km to m").unwrap();
+        assert_eq!(result.value, 1500.0);
+        assert_eq!(result.unit.unwrap().symbol, "m");
+        assert!((value("(60 + 40) c in f") - 212.0).abs() < 1e-9);
+        assert!(evaluate("3 kg to m").unwrap_err().contains("Cannot convert"));
+    }
+
+    #[test]
+    fn test_rejects_division_by_zero() {
+        assert_eq!(evaluate("1 / (2 - 2)").unwrap_err(), "Division by zero");
+        assert_eq!(evaluate("5 mod 0").unwrap_err(), "Modulo by zero");
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        let long = "1+".repeat(MAX_EXPRESSION_LEN);
+        assert!(evaluate(&long).unwrap_err().starts_with("Expression is too long"));
+        let nested = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert_eq!(evaluate(&nested).unwrap_err(), "Expression is nested too deeply");
+        assert!(evaluate("").is_err());
+        assert!(evaluate("2 +").is_err());
+        assert!(evaluate("(1 + 2").is_err());
+        assert!(evaluate("foo + 1").unwrap_err().contains("Unknown identifier"));
+        assert!(evaluate("10 ^ 400").unwrap_err().contains("finite"));
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(441.0), "441");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(-2.5), "-2.5");
+        assert_eq!(format_number(1.0 / 3.0), "0.3333333333");
+    }
+}
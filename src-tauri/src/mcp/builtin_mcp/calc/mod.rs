@@ -0,0 +1,32 @@
+//! 内置计算器（aipp:calc）：确定性地计算数学表达式，弥补模型算术不可靠的问题。
+
+pub mod evaluator;
+pub mod units;
+
+use serde::Serialize;
+
+/// evaluate 工具的返回结果
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluateResponse {
+    pub expression: String,
+    pub result: f64,
+    pub unit: Option<String>,
+    /// 展示给模型的文本，例如 `18% of 2450 = 441`
+    pub text: String,
+}
+
+pub fn evaluate_expression(expression: &str) -> Result<EvaluateResponse, String> {
+    let evaluation = evaluator::evaluate(expression)?;
+    let unit = evaluation.unit.map(|unit| unit.symbol.to_string());
+    let formatted = evaluator::format_number(evaluation.value);
+    let text = match &unit {
+        Some(unit) => format!("{} = {} {}", expression.trim(), formatted, unit),
+        None => format!("{} = {}", expression.trim(), formatted),
+    };
+    Ok(EvaluateResponse {
+        expression: expression.trim().to_string(),
+        result: evaluation.value,
+        unit,
+        text,
+    })
+}
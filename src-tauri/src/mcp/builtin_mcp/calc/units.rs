@@ -0,0 +1,120 @@
+/// 单位所属的量纲，只有同一量纲的单位之间可以换算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Time,
+    Data,
+    Temperature,
+}
+
+/// 可识别的单位：`factor` 为换算到该量纲基准单位（m、kg、l、s、byte）的倍数，温度单独处理
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub symbol: &'static str,
+    pub dimension: Dimension,
+    factor: f64,
+}
+
+const fn unit(symbol: &'static str, dimension: Dimension, factor: f64) -> Unit {
+    Unit { symbol, dimension, factor }
+}
+
+/// (别名列表, 单位)；别名统一小写匹配
+const UNITS: &[(&[&str], Unit)] = &[
+    (&["mm", "millimeter", "millimeters"], unit("mm", Dimension::Length, 0.001)),
+    (&["cm", "centimeter", "centimeters"], unit("cm", Dimension::Length, 0.01)),
+    (&["m", "meter", "meters", "metre", "metres"], unit("m", Dimension::Length, 1.0)),
+    (&["km", "kilometer", "kilometers"], unit("km", Dimension::Length, 1000.0)),
+    (&["inch", "inches"], unit("inch", Dimension::Length, 0.0254)),
+    (&["ft", "foot", "feet"], unit("ft", Dimension::Length, 0.3048)),
+    (&["yd", "yard", "yards"], unit("yd", Dimension::Length, 0.9144)),
+    (&["mi", "mile", "miles"], unit("mi", Dimension::Length, 1609.344)),
+    (&["mg", "milligram", "milligrams"], unit("mg", Dimension::Mass, 0.000001)),
+    (&["g", "gram", "grams"], unit("g", Dimension::Mass, 0.001)),
+    (&["kg", "kilogram", "kilograms"], unit("kg", Dimension::Mass, 1.0)),
+    (&["t", "ton", "tons", "tonne", "tonnes"], unit("t", Dimension::Mass, 1000.0)),
+    (&["oz", "ounce", "ounces"], unit("oz", Dimension::Mass, 0.028349523125)),
+    (&["lb", "lbs", "pound", "pounds"], unit("lb", Dimension::Mass, 0.45359237)),
+    (&["ml", "milliliter", "milliliters"], unit("ml", Dimension::Volume, 0.001)),
+    (&["l", "liter", "liters", "litre", "litres"], unit("l", Dimension::Volume, 1.0)),
+    (&["gal", "gallon", "gallons"], unit("gal", Dimension::Volume, 3.785411784)),
+    (&["ms", "millisecond", "milliseconds"], unit("ms", Dimension::Time, 0.001)),
+    (&["s", "sec", "second", "seconds"], unit("s", Dimension::Time, 1.0)),
+    (&["min", "minute", "minutes"], unit("min", Dimension::Time, 60.0)),
+    (&["h", "hr", "hour", "hours"], unit("h", Dimension::Time, 3600.0)),
+    (&["day", "days"], unit("day", Dimension::Time, 86400.0)),
+    (&["week", "weeks"], unit("week", Dimension::Time, 604800.0)),
+    (&["b", "byte", "bytes"], unit("B", Dimension::Data, 1.0)),
+    (&["kb"], unit("KB", Dimension::Data, 1024.0)),
+    (&["mb"], unit("MB", Dimension::Data, 1048576.0)),
+    (&["gb"], unit("GB", Dimension::Data, 1073741824.0)),
+    (&["tb"], unit("TB", Dimension::Data, 1099511627776.0)),
+    (&["c", "celsius"], unit("°C", Dimension::Temperature, 1.0)),
+    (&["f", "fahrenheit"], unit("°F", Dimension::Temperature, 1.0)),
+    (&["k", "kelvin"], unit("K", Dimension::Temperature, 1.0)),
+];
+
+pub fn find_unit(name: &str) -> Option<Unit> {
+    let name = name.to_lowercase();
+    UNITS.iter().find(|(aliases, _)| aliases.contains(&name.as_str())).map(|(_, unit)| *unit)
+}
+
+/// 在同一量纲的单位之间换算
+pub fn convert(value: f64, from: Unit, to: Unit) -> Result<f64, String> {
+    if from.dimension != to.dimension {
+        return Err(format!("Cannot convert {} to {}", from.symbol, to.symbol));
+    }
+    if from.dimension == Dimension::Temperature {
+        return Ok(celsius_to(to, to_celsius(from, value)));
+    }
+    Ok(value * from.factor / to.factor)
+}
+
+fn to_celsius(unit: Unit, value: f64) -> f64 {
+    match unit.symbol {
+        "°F" => (value - 32.0) * 5.0 / 9.0,
+        "K" => value - 273.15,
+        _ => value,
+    }
+}
+
+fn celsius_to(unit: Unit, celsius: f64) -> f64 {
+    match unit.symbol {
+        "°F" => celsius * 9.0 / 5.0 + 32.0,
+        "K" => celsius + 273.15,
+        _ => celsius,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_linear_units() {
+        let km = find_unit("km").unwrap();
+        let mile = find_unit("Miles").unwrap();
+        assert!((convert(5.0, km, mile).unwrap() - 3.10685596).abs() < 1e-6);
+        assert_eq!(
+            convert(1.0, find_unit("gb").unwrap(), find_unit("mb").unwrap()).unwrap(),
+            1024.0
+        );
+    }
+
+    #[test]
+    fn test_convert_temperature() {
+        let c = find_unit("c").unwrap();
+        let f = find_unit("fahrenheit").unwrap();
+        let k = find_unit("k").unwrap();
+        assert!((convert(100.0, c, f).unwrap() - 212.0).abs() < 1e-9);
+        assert!((convert(32.0, f, k).unwrap() - 273.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_convert_rejects_mismatched_dimensions() {
+        let err = convert(1.0, find_unit("kg").unwrap(), find_unit("m").unwrap()).unwrap_err();
+        assert_eq!(err, "Cannot convert kg to m");
+    }
+}
@@ -4,6 +4,7 @@ use tauri::{AppHandle, Manager};
 use tracing::{debug, error, instrument};
 
 pub mod agent;
+pub mod calc;
 pub mod interaction;
 pub mod operation;
 pub mod search;
@@ -370,7 +371,10 @@ pub async fn execute_aipp_builtin_tool(
                     let result_type_str = args.get("result_type").and_then(|v| v.as_str());
 
                     let result_type = SearchResultType::from_str(result_type_str);
-                    let request = SearchRequest { query: query.to_string(), result_type };
+                    let bypass_cache =
+                        args.get("bypass_cache").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let request =
+                        SearchRequest { query: query.to_string(), result_type, bypass_cache };
 
                     match handler.search_web_with_type(request).await {
                         Ok(response) => {
@@ -394,9 +398,17 @@ pub async fn execute_aipp_builtin_tool(
                                         "isError": false
                                     })
                                 }
-                                SearchResponse::ItemsOnly(items) => {
+                                SearchResponse::ItemsOnly { items, filtered_count } => {
+                                    let mut content =
+                                        vec![serde_json::json!({"type": "json", "json": items})];
+                                    if filtered_count > 0 {
+                                        content.push(serde_json::json!({
+                                            "type": "text",
+                                            "text": format!("{} results were filtered by domain rules", filtered_count)
+                                        }));
+                                    }
                                     serde_json::json!({
-                                        "content": [{"type": "json", "json": items}],
+                                        "content": content,
                                         "isError": false
                                     })
                                 }
@@ -441,6 +453,36 @@ pub async fn execute_aipp_builtin_tool(
                 }),
             }
         }
+        "calc" => match tool_name.as_str() {
+            "evaluate" => {
+                let expression = args
+                    .get("expression")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| "Missing required parameter: expression".to_string())?;
+                match calc::evaluate_expression(expression) {
+                    Ok(response) => serde_json::json!({
+                        "content": [{"type": "text", "text": response.text}],
+                        "isError": false,
+                        "metadata": {
+                            "expression": response.expression,
+                            "result": response.result,
+                            "unit": response.unit
+                        }
+                    }),
+                    Err(e) => {
+                        debug!(error = %e, "evaluate tool rejected expression");
+                        serde_json::json!({
+                            "content": [{"type": "text", "text": format!("Failed to evaluate expression: {}", e)}],
+                            "isError": true
+                        })
+                    }
+                }
+            }
+            _ => serde_json::json!({
+                "content": [{"type": "text", "text": format!("Unknown calc tool: {}", tool_name)}],
+                "isError": true
+            }),
+        },
         "operation" => {
             use operation::types::*;
 
@@ -623,7 +665,7 @@ pub async fn execute_aipp_builtin_tool(
                         run_in_background,
                     };
 
-                    match handler.execute_bash(&state, request).await {
+                    match handler.execute_bash(&state, request, conversation_id).await {
                         Ok(response) => {
                             let text = if let Some(output) = &response.output {
                                 output.clone()
@@ -685,6 +727,45 @@ pub async fn execute_aipp_builtin_tool(
                 }),
             }
         }
+        "file_reader" => match tool_name.as_str() {
+            "read_file" => {
+                use operation::readonly_ops::{ReadOnlyFileConfig, ReadOnlyFileOperations};
+                use operation::types::ReadOnlyFileRequest;
+
+                let path = args
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| "Missing required parameter: path".to_string())?;
+                let max_bytes = args.get("max_bytes").and_then(|v| v.as_u64()).map(|v| v as usize);
+
+                let config = ReadOnlyFileConfig::load(&app_handle);
+                let request = ReadOnlyFileRequest { path: path.to_string(), max_bytes };
+                match ReadOnlyFileOperations::read_file(&config, request) {
+                    Ok(response) => serde_json::json!({
+                        "content": [{"type": "text", "text": response.content}],
+                        "isError": false,
+                        "metadata": {
+                            "file_path": response.file_path,
+                            "encoding": response.encoding,
+                            "file_size": response.file_size,
+                            "bytes_read": response.bytes_read,
+                            "truncated": response.truncated
+                        }
+                    }),
+                    Err(e) => {
+                        debug!(error = %e, path = %path, "file_reader read_file rejected");
+                        serde_json::json!({
+                            "content": [{"type": "text", "text": e}],
+                            "isError": true
+                        })
+                    }
+                }
+            }
+            _ => serde_json::json!({
+                "content": [{"type": "text", "text": format!("Unknown file_reader tool: {}", tool_name)}],
+                "isError": true
+            }),
+        },
         "artifact" => {
             use crate::artifacts::workspace::{
                 get_artifact_workspace, show_artifact, ShowArtifactRequest,
@@ -953,6 +1034,37 @@ pub async fn execute_aipp_builtin_tool(
                         }
                     }
                 }
+                "read_conversation" => {
+                    let conversation_id = conversation_id.ok_or_else(|| {
+                        "read_conversation requires conversation context".to_string()
+                    })?;
+                    let request = ReadConversationRequest {
+                        offset: args.get("offset").and_then(|v| v.as_u64()).map(|v| v as usize),
+                        limit: args.get("limit").and_then(|v| v.as_u64()).map(|v| v as usize),
+                        max_tokens: args
+                            .get("max_tokens")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as usize),
+                    };
+
+                    match handler.read_conversation(conversation_id, request).await {
+                        Ok(response) => serde_json::json!({
+                            "content": [{"type": "json", "json": response.messages}],
+                            "isError": false,
+                            "metadata": {
+                                "total": response.total,
+                                "truncated": response.truncated
+                            }
+                        }),
+                        Err(e) => {
+                            error!(error = %e, "read_conversation tool execution failed");
+                            serde_json::json!({
+                                "content": [{"type": "text", "text": e}],
+                                "isError": true
+                            })
+                        }
+                    }
+                }
                 "load_mcp_server" | "load_mcp_tool" => {
                     execute_dynamic_mcp_tool(&app_handle, &tool_name, &args, conversation_id)?
                 }
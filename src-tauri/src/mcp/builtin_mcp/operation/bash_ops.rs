@@ -310,6 +310,8 @@ impl Clone for OperationState {
             written_files: self.written_files.clone(),
             bash_processes: self.bash_processes.clone(),
             pending_permissions: self.pending_permissions.clone(),
+            permission_batch: self.permission_batch.clone(),
+            denied_commands: self.denied_commands.clone(),
         }
     }
 }
@@ -0,0 +1,306 @@
+use regex::Regex;
+use tracing::warn;
+
+/// 内置的危险命令拒绝规则，默认启用，可通过 USE_DEFAULT_COMMAND_DENYLIST=false 关闭
+pub const DEFAULT_DENY_RULES: &[&str] = &[
+    // 递归删除根目录 / 家目录
+    r"regex:\brm\s+(?:-\S+\s+)*-\S*[rR]\S*\s+(?:-\S+\s+)*(?:/|/\*|~/?|\$HOME/?)(?:\s|$)",
+    // 格式化磁盘
+    "mkfs",
+    "regex:^mkfs\\.",
+    "diskutil eraseDisk",
+    "regex:(?i)^format\\s+[a-z]:",
+    "regex:(?i)^format-volume\\b",
+    // 直接写入块设备
+    r"regex:\bdd\b.*\bof=/dev/(?:sd|hd|nvme|disk|mmcblk)",
+    r"regex:>\s*/dev/(?:sd|hd|nvme|disk|mmcblk)",
+    // fork 炸弹
+    r"regex::\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:",
+    // 关机 / 重启
+    "shutdown",
+    "reboot",
+    "halt",
+    "poweroff",
+    // 递归修改根目录权限
+    r"regex:\bch(?:mod|own)\s+(?:-\S+\s+)*-\S*R\S*\s+\S+\s+/(?:\s|$)",
+    // Windows 递归删除整个盘符
+    r"regex:(?i)\bremove-item\b.*-recurse\b.*\s[a-z]:\\?\s*$",
+    r"regex:(?i)\b(?:rd|rmdir)\s+/s\s+/q\s+[a-z]:\\?(?:\s|$)",
+];
+
+/// 包装在真实命令外层、判断时需要剥离的前缀命令，以及它们需要带参数值的选项
+const WRAPPER_COMMANDS: &[(&str, &[&str])] = &[
+    ("sudo", &["-u", "-g", "-p", "-C", "-D"]),
+    ("doas", &["-u", "-C"]),
+    ("env", &["-u", "-C", "-S"]),
+    ("nice", &["-n"]),
+    ("nohup", &[]),
+    ("time", &[]),
+    ("command", &[]),
+    ("exec", &[]),
+    ("builtin", &[]),
+];
+
+/// 通过 `-c` 执行字符串参数的 shell，判断时展开其中的命令
+const SHELL_COMMANDS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh"];
+/// `-c` 嵌套展开的最大层数
+const MAX_SHELL_NESTING: usize = 4;
+
+/// 单条命令规则：普通文本按命令前缀匹配，`regex:` 开头的按正则匹配
+#[derive(Debug, Clone)]
+pub enum CommandRule {
+    Prefix(String),
+    Regex(Regex),
+}
+
+impl CommandRule {
+    /// 解析一行规则，空行和 `#` 注释返回 None，非法正则记录警告后忽略
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        if let Some(pattern) = line.strip_prefix("regex:") {
+            return match Regex::new(pattern.trim()) {
+                Ok(regex) => Some(CommandRule::Regex(regex)),
+                Err(e) => {
+                    warn!(pattern = %pattern, error = %e, "Ignoring invalid command rule regex");
+                    None
+                }
+            };
+        }
+        Some(CommandRule::Prefix(collapse_whitespace(line)))
+    }
+
+    fn matches_segment(&self, segment: &str) -> bool {
+        match self {
+            CommandRule::Prefix(prefix) => {
+                segment == prefix
+                    || segment
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+            }
+            CommandRule::Regex(regex) => regex.is_match(segment),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            CommandRule::Prefix(prefix) => prefix.clone(),
+            CommandRule::Regex(regex) => format!("regex:{}", regex.as_str()),
+        }
+    }
+}
+
+/// Bash 命令执行前的允许/拒绝策略
+///
+/// - 任一子命令命中拒绝规则（正则规则同时匹配完整命令）即拒绝
+/// - 允许列表非空时，每个子命令都必须命中允许规则
+#[derive(Debug, Clone, Default)]
+pub struct CommandPolicy {
+    allow: Vec<CommandRule>,
+    deny: Vec<CommandRule>,
+}
+
+impl CommandPolicy {
+    pub fn new(allow_lines: &[String], deny_lines: &[String], use_default_deny: bool) -> Self {
+        let mut deny: Vec<CommandRule> = if use_default_deny {
+            DEFAULT_DENY_RULES.iter().filter_map(|line| CommandRule::parse(line)).collect()
+        } else {
+            Vec::new()
+        };
+        deny.extend(deny_lines.iter().filter_map(|line| CommandRule::parse(line)));
+        let allow = allow_lines.iter().filter_map(|line| CommandRule::parse(line)).collect();
+        Self { allow, deny }
+    }
+
+    /// 检查命令是否允许执行，拒绝时返回原因
+    pub fn check(&self, command: &str) -> Result<(), String> {
+        let mut segments = Vec::new();
+        collect_segments(command, 0, &mut segments);
+
+        for rule in &self.deny {
+            let whole_match =
+                matches!(rule, CommandRule::Regex(regex) if regex.is_match(command.trim()));
+            if whole_match || segments.iter().any(|segment| rule.matches_segment(segment)) {
+                return Err(format!("Command denied by rule '{}'", rule.describe()));
+            }
+        }
+
+        if !self.allow.is_empty() {
+            if let Some(segment) = segments
+                .iter()
+                .find(|segment| !self.allow.iter().any(|rule| rule.matches_segment(segment)))
+            {
+                return Err(format!("Command '{}' is not in the allowlist", segment));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 按 `;`、`&&`、`||`、`|`、`&`、换行以及 `$(...)`、反引号、子 shell 括号拆分子命令，引号内的分隔符不拆分
+pub fn split_command_segments(command: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut in_single = false;
+    let mut in_double = false;
+    // 每层 `$(` 进入前的双引号状态，遇到对应的 `)` 时恢复
+    let mut substitutions: Vec<bool> = Vec::new();
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if !in_double => {
+                in_single = !in_single;
+                current.push(c);
+            }
+            '"' if !in_single => {
+                in_double = !in_double;
+                current.push(c);
+            }
+            '\\' if !in_single => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            // 命令替换在双引号内同样会执行
+            '$' if !in_single && chars.peek() == Some(&'(') => {
+                chars.next();
+                segments.push(std::mem::take(&mut current));
+                substitutions.push(in_double);
+                in_double = false;
+            }
+            ')' if !in_single && !in_double => {
+                segments.push(std::mem::take(&mut current));
+                if let Some(outer_double) = substitutions.pop() {
+                    in_double = outer_double;
+                }
+            }
+            '`' if !in_single => segments.push(std::mem::take(&mut current)),
+            ';' | '|' | '&' | '\n' | '(' | '{' | '}' if !in_single && !in_double => {
+                segments.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    segments.push(current);
+    segments.into_iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// 拆分并归一化命令中的子命令；`sh|bash|zsh -c '...'` 替换为其中展开的子命令
+fn collect_segments(command: &str, depth: usize, segments: &mut Vec<String>) {
+    for segment in split_command_segments(command) {
+        let words = segment_words(&segment);
+        if words.is_empty() {
+            continue;
+        }
+        match shell_payload(&words) {
+            Some(payload) if depth < MAX_SHELL_NESTING => {
+                collect_segments(payload, depth + 1, segments)
+            }
+            _ => segments.push(words.join(" ")),
+        }
+    }
+}
+
+/// 返回 shell `-c` 选项（含 `-lc`、`-ec` 等组合写法）后的命令字符串
+fn shell_payload(words: &[String]) -> Option<&str> {
+    if !SHELL_COMMANDS.contains(&words[0].as_str()) {
+        return None;
+    }
+    let mut rest = words[1..].iter().skip_while(|word| {
+        !(word.starts_with('-') && !word.starts_with("--") && word.contains('c'))
+    });
+    rest.next()?;
+    rest.find(|word| !word.starts_with('-')).map(String::as_str)
+}
+
+/// 归一化子命令：剥离 sudo/env 等包装命令与 `VAR=value` 赋值，去掉引号，程序名只保留文件名，压缩空白
+pub fn normalize_segment(segment: &str) -> String {
+    segment_words(segment).join(" ")
+}
+
+fn segment_words(segment: &str) -> Vec<String> {
+    let mut tokens = split_words(segment).into_iter().peekable();
+
+    loop {
+        let Some(token) = tokens.peek() else {
+            return Vec::new();
+        };
+        if is_env_assignment(token) {
+            tokens.next();
+        } else if let Some((_, value_options)) =
+            WRAPPER_COMMANDS.iter().find(|(name, _)| *name == token.as_str())
+        {
+            tokens.next();
+            // 包装命令自身的选项（如 sudo -u root）
+            while let Some(option) = tokens.next_if(|t| t.starts_with('-')) {
+                if value_options.contains(&option.as_str()) {
+                    tokens.next();
+                }
+            }
+        } else {
+            break;
+        }
+    }
+
+    let Some(program) = tokens.next() else {
+        return Vec::new();
+    };
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(&program).to_string();
+    std::iter::once(program).chain(tokens).collect()
+}
+
+/// 按空白拆分单词并按 shell 规则去掉引号与转义，避免 `rm -rf "/"` 这类写法绕过规则
+fn split_words(segment: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut chars = segment.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            '\\' if !in_single => {
+                if let Some(next) = chars.next() {
+                    // 双引号内只有少数字符会被转义
+                    if in_double && !matches!(next, '"' | '\\' | '$' | '`') {
+                        current.push(c);
+                    }
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !in_single && !in_double => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+                continue;
+            }
+            _ => current.push(c),
+        }
+        in_word = true;
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+fn is_env_assignment(token: &str) -> bool {
+    token.split_once('=').is_some_and(|(key, _)| {
+        let mut chars = key.chars();
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
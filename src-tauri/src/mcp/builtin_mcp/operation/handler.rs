@@ -80,8 +80,11 @@ impl OperationHandler {
         &self,
         state: &OperationState,
         request: ExecuteBashRequest,
+        conversation_id: Option<i64>,
     ) -> Result<ExecuteBashResponse, String> {
         info!("Handling execute_bash request");
+        // 命令策略优先于自动执行，命中拒绝规则的命令直接拒绝
+        self.permission_manager().check_command(state, &request.command, conversation_id).await?;
         BashOperations::execute_bash(state, request).await
     }
 
@@ -1,7 +1,9 @@
 pub mod bash_ops;
+pub mod command_policy;
 pub mod file_ops;
 pub mod handler;
 pub mod permission;
+pub mod readonly_ops;
 pub mod state;
 pub mod types;
 
@@ -1,10 +1,67 @@
 use crate::db::mcp_db::MCPDatabase;
 use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 use tracing::{debug, info, warn};
 
+use super::command_policy::CommandPolicy;
 use super::state::OperationState;
-use super::types::{PermissionDecision, PermissionRequestEvent};
+use super::types::{
+    DeniedCommandRecord, PermissionBatchRequestEvent, PermissionDecision, PermissionRequestEvent,
+};
+
+/// 权限请求合并窗口：窗口内连续发起的请求合并为一次确认
+pub const PERMISSION_BATCH_WINDOW: Duration = Duration::from_millis(300);
+
+/// 提交一个权限请求并等待用户决策
+///
+/// 请求先进入当前批次；批次中的第一个请求会在 `window` 结束后把整批交给 `dispatch` 发送。
+/// 每个请求持有独立的通道，批次内各项的决策互不影响。
+pub async fn submit_batched_permission_request<F>(
+    operation_state: &OperationState,
+    event: PermissionRequestEvent,
+    window: Duration,
+    dispatch: F,
+) -> Result<PermissionDecision, String>
+where
+    F: FnOnce(Vec<PermissionRequestEvent>) -> Result<(), String> + Send + 'static,
+{
+    let request_id = event.request_id.clone();
+
+    // 创建 oneshot 通道等待用户响应
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    // 存储待处理请求
+    operation_state.store_permission_request(request_id.clone(), tx).await;
+
+    if operation_state.enqueue_permission_batch(event).await {
+        // 在独立任务中发送批次，避免发起方被取消时整批请求无人发送
+        let state = operation_state.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+            let batch = state.take_permission_batch().await;
+            let request_ids: Vec<String> = batch.iter().map(|r| r.request_id.clone()).collect();
+            if let Err(e) = dispatch(batch) {
+                warn!(error = %e, "Failed to dispatch permission requests");
+                for request_id in &request_ids {
+                    state.remove_permission_request(request_id).await;
+                }
+            }
+        });
+    }
+
+    // 等待用户响应（无超时，一直等待）
+    match rx.await {
+        Ok(decision) => {
+            info!(request_id = %request_id, decision = ?decision, "Permission decision received");
+            Ok(decision)
+        }
+        Err(_) => {
+            warn!(request_id = %request_id, "Permission request channel closed unexpectedly");
+            Err("Permission request was cancelled".to_string())
+        }
+    }
+}
 
 /// 权限管理器
 pub struct PermissionManager {
@@ -16,8 +73,19 @@ impl PermissionManager {
         Self { app_handle }
     }
 
-    fn parse_allowed_directories(env_text: &str) -> Vec<String> {
-        let mut dirs = Vec::new();
+    /// 解析 `KEY=VALUE` 行，KEY 必须是合法的环境变量名
+    fn split_env_line(line: &str) -> Option<(&str, &str)> {
+        let (key, value) = line.split_once('=')?;
+        let key = key.trim();
+        let mut chars = key.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        valid.then_some((key, value))
+    }
+
+    /// 读取可跨多行的环境变量值：首行 `KEY=VALUE` 之后的续行都属于该变量，直到下一条 `KEY=VALUE`
+    fn parse_multiline_env_value(env_text: &str, key: &str) -> Vec<String> {
+        let mut values = Vec::new();
         let mut collecting = false;
 
         for raw_line in env_text.lines() {
@@ -26,25 +94,49 @@ impl PermissionManager {
                 continue;
             }
 
-            if let Some(value) = line.strip_prefix("ALLOWED_DIRECTORIES=") {
-                collecting = true;
+            if let Some((line_key, value)) = Self::split_env_line(line) {
+                collecting = line_key == key;
                 let value = value.trim();
-                if !value.is_empty() {
-                    dirs.push(value.to_string());
+                if collecting && !value.is_empty() {
+                    values.push(value.to_string());
                 }
                 continue;
             }
 
             if collecting {
-                // 下一条 KEY=VALUE 说明白名单段结束
-                if line.contains('=') {
-                    break;
-                }
-                dirs.push(line.to_string());
+                values.push(line.to_string());
             }
         }
 
-        dirs
+        values
+    }
+
+    fn parse_allowed_directories(env_text: &str) -> Vec<String> {
+        Self::parse_multiline_env_value(env_text, "ALLOWED_DIRECTORIES")
+    }
+
+    /// 从 COMMAND_ALLOWLIST / COMMAND_DENYLIST / USE_DEFAULT_COMMAND_DENYLIST 构建命令策略
+    fn parse_command_policy(env_text: &str) -> CommandPolicy {
+        let allow = Self::parse_multiline_env_value(env_text, "COMMAND_ALLOWLIST");
+        let deny = Self::parse_multiline_env_value(env_text, "COMMAND_DENYLIST");
+        let use_default_deny =
+            Self::parse_multiline_env_value(env_text, "USE_DEFAULT_COMMAND_DENYLIST")
+                .first()
+                .map(|value| !value.eq_ignore_ascii_case("false"))
+                .unwrap_or(true);
+        CommandPolicy::new(&allow, &deny, use_default_deny)
+    }
+
+    /// 读取内置操作工具的环境变量配置文本
+    fn load_operation_env_text(db: &MCPDatabase) -> Option<String> {
+        db.conn
+            .prepare(
+                "SELECT environment_variables FROM mcp_server WHERE command = ? AND is_builtin = 1 LIMIT 1",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_row(["aipp:operation"], |row| row.get::<_, Option<String>>(0))
+            })
+            .unwrap_or(None)
     }
 
     fn normalize_absolute_path(path: &Path) -> Option<PathBuf> {
@@ -102,17 +194,7 @@ impl PermissionManager {
     pub fn load_whitelist(&self) -> Vec<String> {
         match MCPDatabase::new(&self.app_handle) {
             Ok(db) => {
-                let env_text: Option<String> = db
-                    .conn
-                    .prepare(
-                        "SELECT environment_variables FROM mcp_server WHERE command = ? AND is_builtin = 1 LIMIT 1",
-                    )
-                    .and_then(|mut stmt| {
-                        stmt.query_row(["aipp:operation"], |row| row.get::<_, Option<String>>(0))
-                    })
-                    .unwrap_or(None);
-
-                if let Some(text) = env_text {
+                if let Some(text) = Self::load_operation_env_text(&db) {
                     return Self::parse_allowed_directories(&text)
                         .into_iter()
                         .map(|s| s.trim().to_string())
@@ -174,13 +256,6 @@ impl PermissionManager {
     ) -> Result<PermissionDecision, String> {
         let request_id = uuid::Uuid::new_v4().to_string();
 
-        // 创建 oneshot 通道等待用户响应
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        // 存储待处理请求
-        operation_state.store_permission_request(request_id.clone(), tx).await;
-
-        // 发送事件到前端
         let event = PermissionRequestEvent {
             request_id: request_id.clone(),
             operation: operation.to_string(),
@@ -190,23 +265,27 @@ impl PermissionManager {
 
         info!(request_id = %request_id, operation = %operation, path = %path, "Requesting permission from user");
 
-        if let Err(e) = self.app_handle.emit("operation-permission-request", &event) {
-            operation_state.remove_permission_request(&request_id).await;
-            warn!(error = %e, "Failed to emit permission request event");
-            return Err("Failed to request permission".to_string());
-        }
-
-        // 等待用户响应（无超时，一直等待）
-        match rx.await {
-            Ok(decision) => {
-                info!(request_id = %request_id, decision = ?decision, "Permission decision received");
-                Ok(decision)
-            }
-            Err(_) => {
-                warn!(request_id = %request_id, "Permission request channel closed unexpectedly");
-                Err("Permission request was cancelled".to_string())
-            }
-        }
+        // 单个请求沿用原有事件，多个请求合并为一次批量确认
+        let app_handle = self.app_handle.clone();
+        submit_batched_permission_request(
+            operation_state,
+            event,
+            PERMISSION_BATCH_WINDOW,
+            move |mut requests| {
+                let result = if requests.len() == 1 {
+                    app_handle.emit("operation-permission-request", &requests.remove(0))
+                } else {
+                    let batch = PermissionBatchRequestEvent {
+                        batch_id: uuid::Uuid::new_v4().to_string(),
+                        requests,
+                    };
+                    info!(batch_id = %batch.batch_id, count = batch.requests.len(), "Requesting batched permission from user");
+                    app_handle.emit("operation-permission-batch-request", &batch)
+                };
+                result.map_err(|e| e.to_string())
+            },
+        )
+        .await
     }
 
     /// 将目录添加到白名单
@@ -214,15 +293,7 @@ impl PermissionManager {
         let db = MCPDatabase::new(&self.app_handle).map_err(|e| e.to_string())?;
 
         // 获取当前的环境变量
-        let env_text: Option<String> = db
-            .conn
-            .prepare(
-                "SELECT environment_variables FROM mcp_server WHERE command = ? AND is_builtin = 1 LIMIT 1",
-            )
-            .and_then(|mut stmt| {
-                stmt.query_row(["aipp:operation"], |row| row.get::<_, Option<String>>(0))
-            })
-            .unwrap_or(None);
+        let env_text = Self::load_operation_env_text(&db);
 
         // 解析并更新白名单
         let mut env_map: std::collections::HashMap<String, String> =
@@ -230,23 +301,30 @@ impl PermissionManager {
         let mut current_dirs: Vec<String> =
             env_text.as_deref().map(Self::parse_allowed_directories).unwrap_or_default();
         if let Some(text) = &env_text {
-            let mut in_allowed_directories = false;
+            let mut current_key: Option<String> = None;
             for line in text.lines() {
                 let line = line.trim();
                 if line.is_empty() || line.starts_with('#') {
                     continue;
                 }
-                if let Some((k, v)) = line.split_once('=') {
-                    let key = k.trim();
+                if let Some((key, v)) = Self::split_env_line(line) {
+                    current_key = Some(key.to_string());
                     if key == "ALLOWED_DIRECTORIES" {
-                        in_allowed_directories = true;
                         continue;
                     }
-                    in_allowed_directories = false;
                     env_map.insert(key.to_string(), v.trim().to_string());
-                } else if in_allowed_directories {
-                    // ALLOWED_DIRECTORIES 的续行，由 parse_allowed_directories 统一处理
-                    continue;
+                } else if let Some(key) = current_key.as_deref() {
+                    // ALLOWED_DIRECTORIES 的续行由 parse_allowed_directories 统一处理，
+                    // 其他多行变量（如命令黑白名单）原样保留
+                    if key == "ALLOWED_DIRECTORIES" {
+                        continue;
+                    }
+                    if let Some(value) = env_map.get_mut(key) {
+                        if !value.is_empty() {
+                            value.push('\n');
+                        }
+                        value.push_str(line);
+                    }
                 }
             }
         }
@@ -279,6 +357,48 @@ impl PermissionManager {
         Ok(())
     }
 
+    /// 加载 Bash 命令允许/拒绝策略
+    pub fn load_command_policy(&self) -> CommandPolicy {
+        match MCPDatabase::new(&self.app_handle) {
+            Ok(db) => Self::parse_command_policy(
+                Self::load_operation_env_text(&db).as_deref().unwrap_or_default(),
+            ),
+            Err(e) => {
+                // 读取配置失败时仍启用默认拒绝列表
+                warn!(error = %e, "Failed to load command policy from database");
+                CommandPolicy::new(&[], &[], true)
+            }
+        }
+    }
+
+    /// 执行前检查 Bash 命令，被拒绝时记录到状态并通知前端
+    pub async fn check_command(
+        &self,
+        operation_state: &OperationState,
+        command: &str,
+        conversation_id: Option<i64>,
+    ) -> Result<(), String> {
+        let Err(reason) = self.load_command_policy().check(command) else {
+            return Ok(());
+        };
+
+        warn!(command = %command, reason = %reason, conversation_id = ?conversation_id, "Bash command denied by policy");
+        let record = DeniedCommandRecord {
+            command: command.to_string(),
+            reason: reason.clone(),
+            conversation_id,
+            denied_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        operation_state.record_denied_command(record.clone()).await;
+        if let Err(e) = self.app_handle.emit("operation-command-denied", &record) {
+            warn!(error = %e, "Failed to emit command denied event");
+        }
+        Err(reason)
+    }
+
     /// 检查路径并在需要时请求权限
     pub async fn check_and_request_permission(
         &self,
@@ -0,0 +1,214 @@
+use base64::Engine;
+use encoding_rs::{Encoding, GB18030, UTF_8, WINDOWS_1252};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::{debug, info};
+
+use super::types::*;
+
+/// 只读文件读取工具的 command
+pub const FILE_READER_COMMAND: &str = "aipp:file_reader";
+
+/// 只读文件读取配置，来自 aipp:file_reader 的环境变量
+#[derive(Debug, Clone)]
+pub struct ReadOnlyFileConfig {
+    /// 允许读取的根目录，未配置时拒绝所有读取
+    pub root_directory: Option<PathBuf>,
+    /// 单次读取的字节上限
+    pub max_bytes: usize,
+    /// 二进制文件大小上限，超过则拒绝读取
+    pub binary_size_limit: u64,
+}
+
+impl Default for ReadOnlyFileConfig {
+    fn default() -> Self {
+        Self {
+            root_directory: None,
+            max_bytes: ReadOnlyFileOperations::DEFAULT_MAX_BYTES,
+            binary_size_limit: ReadOnlyFileOperations::DEFAULT_BINARY_SIZE_LIMIT,
+        }
+    }
+}
+
+impl ReadOnlyFileConfig {
+    /// 解析 `KEY=VALUE` 形式的环境变量文本
+    pub fn from_env_text(env_text: &str) -> Self {
+        let env: HashMap<&str, &str> = env_text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+        let defaults = Self::default();
+
+        Self {
+            root_directory: env
+                .get("ROOT_DIRECTORY")
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            max_bytes: env
+                .get("MAX_BYTES")
+                .and_then(|value| value.parse().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(defaults.max_bytes),
+            binary_size_limit: env
+                .get("BINARY_SIZE_LIMIT")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(defaults.binary_size_limit),
+        }
+    }
+
+    /// 从数据库读取内置文件读取工具的配置
+    pub fn load(app_handle: &AppHandle) -> Self {
+        use crate::db::mcp_db::MCPDatabase;
+
+        let env_text = MCPDatabase::new(app_handle).ok().and_then(|db| {
+            db.conn
+                .prepare(
+                    "SELECT environment_variables FROM mcp_server WHERE command = ? AND is_builtin = 1 LIMIT 1",
+                )
+                .and_then(|mut stmt| {
+                    stmt.query_row([FILE_READER_COMMAND], |row| row.get::<_, Option<String>>(0))
+                })
+                .unwrap_or(None)
+        });
+        let config = Self::from_env_text(env_text.as_deref().unwrap_or_default());
+        debug!(?config, "Loaded file reader config");
+        config
+    }
+}
+
+/// 只读文件操作实现
+///
+/// 与 FileOperations 不同，这里不走权限确认流程，安全性由根目录限制保证：
+/// 只能读取根目录内的文件，且不会写入任何内容。
+pub struct ReadOnlyFileOperations;
+
+impl ReadOnlyFileOperations {
+    /// 默认单次读取字节上限（256 KB）
+    pub const DEFAULT_MAX_BYTES: usize = 256 * 1024;
+    /// 默认二进制文件大小上限（64 KB）
+    pub const DEFAULT_BINARY_SIZE_LIMIT: u64 = 64 * 1024;
+    /// 用于二进制检测的采样字节数
+    const BINARY_SNIFF_BYTES: usize = 8000;
+
+    /// 读取根目录内的文件
+    pub fn read_file(
+        config: &ReadOnlyFileConfig,
+        request: ReadOnlyFileRequest,
+    ) -> Result<ReadOnlyFileResponse, String> {
+        let path = Self::resolve_path(config, &request.path)?;
+
+        // 只读取普通文件，目录、FIFO、设备文件等一律拒绝，避免读取时阻塞或读到无限流
+        let metadata =
+            path.metadata().map_err(|e| format!("Failed to read file metadata: {}", e))?;
+        if !metadata.is_file() {
+            return Err(format!("Not a regular file: {}", request.path.trim()));
+        }
+
+        let file_size = metadata.len();
+        let max_bytes = request.max_bytes.unwrap_or(config.max_bytes).min(config.max_bytes).max(1);
+
+        let file = File::open(&path).map_err(|e| format!("Failed to open file: {}", e))?;
+        let mut bytes = Vec::with_capacity(max_bytes.min(file_size as usize));
+        file.take(max_bytes as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let truncated = (bytes.len() as u64) < file_size;
+        let file_path = path.to_string_lossy().to_string();
+
+        if Self::is_binary(&bytes) {
+            if file_size > config.binary_size_limit {
+                return Err(format!(
+                    "Binary file is too large to read ({} bytes, limit {} bytes)",
+                    file_size, config.binary_size_limit
+                ));
+            }
+            info!(path = %file_path, bytes = bytes.len(), "Binary file read as base64");
+            return Ok(ReadOnlyFileResponse {
+                file_path,
+                content: base64::engine::general_purpose::STANDARD.encode(&bytes),
+                encoding: "base64".to_string(),
+                file_size,
+                bytes_read: bytes.len(),
+                truncated,
+            });
+        }
+
+        let (content, encoding) = Self::decode_text(&bytes, truncated);
+        info!(path = %file_path, bytes = bytes.len(), encoding, truncated, "File read successfully");
+
+        Ok(ReadOnlyFileResponse {
+            file_path,
+            content,
+            encoding: encoding.to_string(),
+            file_size,
+            bytes_read: bytes.len(),
+            truncated,
+        })
+    }
+
+    /// 将请求路径解析为根目录内的真实路径，符号链接和 `..` 都会先被解析再做校验
+    fn resolve_path(config: &ReadOnlyFileConfig, path: &str) -> Result<PathBuf, String> {
+        let root = config.root_directory.as_ref().ok_or_else(|| {
+            "Root directory is not configured. Set ROOT_DIRECTORY for the file reader tool."
+                .to_string()
+        })?;
+        let root =
+            root.canonicalize().map_err(|e| format!("Root directory is not accessible: {}", e))?;
+
+        let requested = Path::new(path.trim());
+        let candidate =
+            if requested.is_absolute() { requested.to_path_buf() } else { root.join(requested) };
+        let resolved =
+            candidate.canonicalize().map_err(|_| format!("File not found: {}", path.trim()))?;
+
+        if !resolved.starts_with(&root) {
+            return Err(format!("Access denied: {} is outside the root directory", path.trim()));
+        }
+        Ok(resolved)
+    }
+
+    /// 采样开头的字节，含有 NUL 且不是 UTF-16 BOM 时视为二进制文件
+    fn is_binary(bytes: &[u8]) -> bool {
+        if Encoding::for_bom(bytes).is_some() {
+            return false;
+        }
+        bytes.iter().take(Self::BINARY_SNIFF_BYTES).any(|&b| b == 0)
+    }
+
+    /// 检测编码并解码：BOM 优先，其次 UTF-8，再尝试 GB18030（兼容 GBK），最后回退到 Windows-1252
+    fn decode_text(bytes: &[u8], truncated: bool) -> (String, &'static str) {
+        if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+            let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+            return (text.into_owned(), encoding.name());
+        }
+
+        match std::str::from_utf8(bytes) {
+            Ok(text) => return (text.to_string(), UTF_8.name()),
+            // 截断处可能切断了一个多字节字符，丢弃不完整的尾部
+            Err(e) if truncated && e.error_len().is_none() => {
+                let text = String::from_utf8_lossy(&bytes[..e.valid_up_to()]);
+                return (text.into_owned(), UTF_8.name());
+            }
+            Err(_) => {}
+        }
+
+        // GB18030 字符最长 4 字节，截断时最多丢弃 3 个尾部字节再尝试
+        let max_trim = if truncated { 3.min(bytes.len()) } else { 0 };
+        for trim in 0..=max_trim {
+            let (text, had_errors) =
+                GB18030.decode_without_bom_handling(&bytes[..bytes.len() - trim]);
+            if !had_errors {
+                return (text.into_owned(), GB18030.name());
+            }
+        }
+
+        let (text, _) = WINDOWS_1252.decode_without_bom_handling(bytes);
+        (text.into_owned(), WINDOWS_1252.name())
+    }
+}
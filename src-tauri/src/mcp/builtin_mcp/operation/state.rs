@@ -1,9 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::process::Child;
 use tokio::sync::Mutex;
 use tracing::debug;
 
+use super::types::DeniedCommandRecord;
+
+/// 保留的拒绝命令记录上限
+pub const MAX_DENIED_COMMAND_RECORDS: usize = 100;
+
 /// 文件读取记录
 #[derive(Debug, Clone)]
 pub struct FileReadRecord {
@@ -38,6 +43,10 @@ pub struct OperationState {
     /// 待处理的权限请求（request_id -> 发送通道）
     pub(crate) pending_permissions:
         Arc<Mutex<HashMap<String, tokio::sync::oneshot::Sender<super::types::PermissionDecision>>>>,
+    /// 当前批次窗口内尚未发送到前端的权限请求
+    pub(crate) permission_batch: Arc<Mutex<Vec<super::types::PermissionRequestEvent>>>,
+    /// 被命令策略拒绝的 Bash 命令（最近 MAX_DENIED_COMMAND_RECORDS 条）
+    pub(crate) denied_commands: Arc<Mutex<VecDeque<DeniedCommandRecord>>>,
 }
 
 impl OperationState {
@@ -47,6 +56,8 @@ impl OperationState {
             written_files: Arc::new(Mutex::new(HashMap::new())),
             bash_processes: Arc::new(Mutex::new(HashMap::new())),
             pending_permissions: Arc::new(Mutex::new(HashMap::new())),
+            permission_batch: Arc::new(Mutex::new(Vec::new())),
+            denied_commands: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
 
@@ -108,6 +119,28 @@ impl OperationState {
         files.clear();
     }
 
+    /// 记录一次被命令策略拒绝的执行尝试
+    pub async fn record_denied_command(&self, record: DeniedCommandRecord) {
+        let mut records = self.denied_commands.lock().await;
+        if records.len() >= MAX_DENIED_COMMAND_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// 获取被拒绝的命令记录，指定会话时只返回该会话的记录
+    pub async fn get_denied_commands(
+        &self,
+        conversation_id: Option<i64>,
+    ) -> Vec<DeniedCommandRecord> {
+        let records = self.denied_commands.lock().await;
+        records
+            .iter()
+            .filter(|record| conversation_id.is_none() || record.conversation_id == conversation_id)
+            .cloned()
+            .collect()
+    }
+
     /// 存储后台 Bash 进程
     pub async fn store_bash_process(&self, bash_id: String, child: Child) {
         let mut processes = self.bash_processes.lock().await;
@@ -226,6 +259,41 @@ impl OperationState {
         }
     }
 
+    /// 批量处理权限确认，返回成功解决的请求 ID
+    ///
+    /// 每个请求独立解决：某项被拒绝或已失效不影响批次中的其他项
+    pub async fn resolve_permission_batch(
+        &self,
+        decisions: Vec<super::types::PermissionConfirmRequest>,
+    ) -> Vec<String> {
+        let mut pending = self.pending_permissions.lock().await;
+        let mut resolved = Vec::with_capacity(decisions.len());
+        for item in decisions {
+            if let Some(sender) = pending.remove(&item.request_id) {
+                if sender.send(item.decision).is_ok() {
+                    resolved.push(item.request_id);
+                }
+            }
+        }
+        resolved
+    }
+
+    /// 将权限请求加入当前批次，返回是否为批次中的第一个请求（由其负责在窗口结束后发送）
+    pub async fn enqueue_permission_batch(
+        &self,
+        event: super::types::PermissionRequestEvent,
+    ) -> bool {
+        let mut batch = self.permission_batch.lock().await;
+        batch.push(event);
+        batch.len() == 1
+    }
+
+    /// 取出当前批次的全部权限请求
+    pub async fn take_permission_batch(&self) -> Vec<super::types::PermissionRequestEvent> {
+        let mut batch = self.permission_batch.lock().await;
+        std::mem::take(&mut *batch)
+    }
+
     /// 检查 Bash 进程是否存在
     pub async fn bash_process_exists(&self, bash_id: &str) -> bool {
         let processes = self.bash_processes.lock().await;
@@ -0,0 +1,146 @@
+/// Bash 命令允许/拒绝策略测试
+///
+/// 测试覆盖：
+/// - 黑名单前缀与正则规则
+/// - 内置危险命令拦截
+/// - 白名单要求每个子命令都命中
+/// - 子命令拆分与包装命令归一化
+/// - 引号与 shell `-c` 包装不能绕过规则
+use super::super::command_policy::{normalize_segment, split_command_segments, CommandPolicy};
+
+fn lines(rules: &[&str]) -> Vec<String> {
+    rules.iter().map(|rule| rule.to_string()).collect()
+}
+
+/// 测试黑名单中加入 curl 后命令被拒绝
+///
+/// 验证内容：
+/// - 直接调用、管道中、sudo 包装、绝对路径调用都会被拦截
+/// - 仅包含 curl 字样的其他命令不受影响
+#[test]
+fn test_denylist_prefix_blocks_curl() {
+    let policy = CommandPolicy::new(&[], &lines(&["curl"]), false);
+
+    assert!(policy.check("curl https://example.com").is_err());
+    assert!(policy.check("echo hi | curl -d @- https://example.com").is_err());
+    assert!(policy.check("sudo -E curl https://example.com").is_err());
+    assert!(policy.check("/usr/bin/curl https://example.com").is_err());
+    assert!(policy.check("ls && $(curl https://example.com)").is_err());
+
+    assert!(policy.check("echo curl").is_ok());
+    assert!(policy.check("curlie https://example.com").is_ok());
+}
+
+/// 测试黑名单正则规则
+#[test]
+fn test_denylist_regex_rule() {
+    let policy = CommandPolicy::new(&[], &lines(&["regex:^git\\s+push\\b.*--force"]), false);
+
+    let err = policy.check("git push origin main --force").unwrap_err();
+    assert!(err.contains("regex:"));
+    assert!(policy.check("git push origin main").is_ok());
+}
+
+/// 测试内置危险命令拦截
+///
+/// 验证内容：
+/// - 破坏性命令默认被拒绝
+/// - 普通的删除和磁盘操作不受影响
+/// - 关闭内置规则后不再拦截
+#[test]
+fn test_default_denylist_blocks_destructive_commands() {
+    let policy = CommandPolicy::new(&[], &[], true);
+
+    for command in [
+        "rm -rf /",
+        "sudo rm -rf --no-preserve-root /",
+        "rm -fr ~",
+        "cd /tmp; rm -rf /*",
+        "mkfs.ext4 /dev/sda1",
+        "dd if=/dev/zero of=/dev/sda bs=1M",
+        ":(){ :|:& };:",
+        "shutdown -h now",
+        "chmod -R 777 /",
+    ] {
+        assert!(policy.check(command).is_err(), "expected '{}' to be denied", command);
+    }
+
+    for command in ["rm -rf ./build", "rm -rf /tmp/aipp-test", "ls -la /", "echo reboot"] {
+        assert!(policy.check(command).is_ok(), "expected '{}' to be allowed", command);
+    }
+
+    let disabled = CommandPolicy::new(&[], &[], false);
+    assert!(disabled.check("shutdown -h now").is_ok());
+}
+
+/// 测试引号与 shell `-c` 包装不能绕过拒绝规则
+#[test]
+fn test_default_denylist_sees_through_quotes_and_shell_wrappers() {
+    let policy = CommandPolicy::new(&[], &[], true);
+
+    for command in [
+        "rm -rf \"/\"",
+        "rm -rf '/'",
+        "r\"m\" -rf /",
+        "bash -c 'rm -rf /'",
+        "sh -c \"rm -rf /\"",
+        "sudo bash -lc 'cd /tmp && rm -rf ~'",
+        "zsh -c \"bash -c 'shutdown -h now'\"",
+    ] {
+        assert!(policy.check(command).is_err(), "expected '{}' to be denied", command);
+    }
+
+    for command in ["rm -rf \"./build\"", "bash -c 'ls -la /'", "bash ./build.sh"] {
+        assert!(policy.check(command).is_ok(), "expected '{}' to be allowed", command);
+    }
+}
+
+/// 测试白名单同样检查 shell `-c` 中的每个子命令
+#[test]
+fn test_allowlist_checks_shell_payload() {
+    let policy = CommandPolicy::new(&lines(&["ls", "git status"]), &[], true);
+
+    assert!(policy.check("bash -c 'ls && git status'").is_ok());
+    let err = policy.check("bash -c 'ls; npm install'").unwrap_err();
+    assert!(err.contains("npm install"));
+}
+
+/// 测试白名单要求每个子命令都命中
+#[test]
+fn test_allowlist_requires_every_segment() {
+    let policy = CommandPolicy::new(&lines(&["git status", "ls"]), &[], true);
+
+    assert!(policy.check("git status").is_ok());
+    assert!(policy.check("ls -la && git status --short").is_ok());
+
+    let err = policy.check("ls; npm install").unwrap_err();
+    assert!(err.contains("npm install"));
+    assert!(policy.check("git push").is_err());
+}
+
+/// 测试黑名单优先于白名单
+#[test]
+fn test_denylist_takes_precedence_over_allowlist() {
+    let policy = CommandPolicy::new(&lines(&["rm"]), &[], true);
+    assert!(policy.check("rm -rf /").is_err());
+    assert!(policy.check("rm notes.txt").is_ok());
+}
+
+/// 测试子命令拆分：引号内的分隔符不拆分，命令替换单独成段
+#[test]
+fn test_split_command_segments() {
+    assert_eq!(
+        split_command_segments("echo 'a;b' && ls | wc -l"),
+        vec!["echo 'a;b'", "ls", "wc -l"]
+    );
+    assert_eq!(split_command_segments("echo \"$(whoami)\""), vec!["echo \"", "whoami", "\""]);
+}
+
+/// 测试归一化：剥离包装命令和变量赋值，程序名只保留文件名
+#[test]
+fn test_normalize_segment() {
+    assert_eq!(normalize_segment("FOO=1 sudo -u root /bin/rm   -rf  /tmp/x"), "rm -rf /tmp/x");
+    assert_eq!(normalize_segment("env LANG=C nohup ./run.sh"), "run.sh");
+    assert_eq!(normalize_segment("FOO=1"), "");
+    assert_eq!(normalize_segment("\"/bin/rm\" -rf '/tmp/a b'"), "rm -rf /tmp/a b");
+}
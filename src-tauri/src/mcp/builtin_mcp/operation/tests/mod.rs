@@ -2,5 +2,8 @@
 // 所有测试使用临时文件/目录，确保对真实系统无害
 
 mod bash_ops_tests;
+mod command_policy_tests;
 mod file_ops_tests;
+mod permission_tests;
+mod readonly_ops_tests;
 mod state_tests;
@@ -0,0 +1,114 @@
+/// 权限请求批量确认测试
+///
+/// 测试覆盖：
+/// - 窗口内的多个权限请求合并为一个批次发送
+/// - 批次内各项独立决策，拒绝某项不影响其他项
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::super::permission::submit_batched_permission_request;
+use super::super::state::OperationState;
+use super::super::types::{PermissionConfirmRequest, PermissionDecision, PermissionRequestEvent};
+
+fn request_event(request_id: &str, path: &str) -> PermissionRequestEvent {
+    PermissionRequestEvent {
+        request_id: request_id.to_string(),
+        operation: "write_file".to_string(),
+        path: path.to_string(),
+        conversation_id: Some(1),
+    }
+}
+
+/// 测试三个操作合并为一个批次，允许其中两个后只有这两个被执行
+///
+/// 验证内容：
+/// - 三个请求只触发一次发送，且批次包含全部三项
+/// - 被拒绝的请求不会阻塞已允许的请求
+/// - 批量解决返回实际解决的请求 ID
+#[tokio::test]
+async fn test_batched_permissions_execute_only_approved_operations() {
+    let state = OperationState::new();
+    let executed: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let (batch_tx, mut batch_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let mut handles = Vec::new();
+    for (request_id, path) in
+        [("req-1", "/tmp/a.txt"), ("req-2", "/tmp/b.txt"), ("req-3", "/tmp/c.txt")]
+    {
+        let state = state.clone();
+        let executed = executed.clone();
+        let batch_tx = batch_tx.clone();
+        handles.push(tokio::spawn(async move {
+            let decision = submit_batched_permission_request(
+                &state,
+                request_event(request_id, path),
+                Duration::from_millis(50),
+                move |requests| batch_tx.send(requests).map_err(|e| e.to_string()),
+            )
+            .await
+            .unwrap();
+
+            // 模拟操作执行：只有获得允许的操作才会执行
+            if decision != PermissionDecision::Deny {
+                executed.lock().unwrap().push(path.to_string());
+            }
+        }));
+    }
+    drop(batch_tx);
+
+    let batch = batch_rx.recv().await.expect("batch should be dispatched");
+    let mut batch_ids: Vec<&str> = batch.iter().map(|r| r.request_id.as_str()).collect();
+    batch_ids.sort();
+    assert_eq!(batch_ids, vec!["req-1", "req-2", "req-3"]);
+
+    let resolved = state
+        .resolve_permission_batch(vec![
+            PermissionConfirmRequest {
+                request_id: "req-1".to_string(),
+                decision: PermissionDecision::Allow,
+            },
+            PermissionConfirmRequest {
+                request_id: "req-2".to_string(),
+                decision: PermissionDecision::Deny,
+            },
+            PermissionConfirmRequest {
+                request_id: "req-3".to_string(),
+                decision: PermissionDecision::Allow,
+            },
+            // 不存在的请求被忽略
+            PermissionConfirmRequest {
+                request_id: "req-missing".to_string(),
+                decision: PermissionDecision::Allow,
+            },
+        ])
+        .await;
+    assert_eq!(resolved, vec!["req-1", "req-2", "req-3"]);
+
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let mut executed = executed.lock().unwrap().clone();
+    executed.sort();
+    assert_eq!(executed, vec!["/tmp/a.txt", "/tmp/c.txt"]);
+
+    // 三个请求只发送了一次
+    assert!(batch_rx.recv().await.is_none());
+}
+
+/// 测试批次发送失败时，批次内的请求都会被取消而不是一直等待
+#[tokio::test]
+async fn test_batch_dispatch_failure_cancels_requests() {
+    let state = OperationState::new();
+
+    let result = submit_batched_permission_request(
+        &state,
+        request_event("req-fail", "/tmp/fail.txt"),
+        Duration::from_millis(10),
+        |_| Err("emit failed".to_string()),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(!state.resolve_permission_request("req-fail", PermissionDecision::Allow).await);
+}
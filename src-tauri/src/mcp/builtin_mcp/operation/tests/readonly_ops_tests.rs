@@ -0,0 +1,179 @@
+/// 只读文件读取测试
+///
+/// 使用临时目录作为根目录，验证：
+/// - 根目录限制（`..`、绝对路径、符号链接都不能越界）
+/// - 字节上限截断与编码检测
+/// - 二进制文件大小限制
+use super::super::readonly_ops::{ReadOnlyFileConfig, ReadOnlyFileOperations};
+use super::super::types::*;
+use std::fs;
+use tempfile::TempDir;
+
+/// 辅助函数：以临时目录为根目录创建配置
+fn config_for(root: &TempDir) -> ReadOnlyFileConfig {
+    ReadOnlyFileConfig {
+        root_directory: Some(root.path().to_path_buf()),
+        ..ReadOnlyFileConfig::default()
+    }
+}
+
+fn read(
+    config: &ReadOnlyFileConfig,
+    path: &str,
+    max_bytes: Option<usize>,
+) -> Result<ReadOnlyFileResponse, String> {
+    ReadOnlyFileOperations::read_file(
+        config,
+        ReadOnlyFileRequest { path: path.to_string(), max_bytes },
+    )
+}
+
+// ============= 根目录限制测试 =============
+
+/// 测试读取根目录内的 README（相对路径与绝对路径）
+#[test]
+fn test_read_file_inside_root() {
+    let root = tempfile::tempdir().unwrap();
+    fs::write(root.path().join("README.md"), "# 项目说明\n\nHello").unwrap();
+    let config = config_for(&root);
+
+    let response = read(&config, "README.md", None).unwrap();
+    assert_eq!(response.content, "# 项目说明\n\nHello");
+    assert_eq!(response.encoding, "UTF-8");
+    assert!(!response.truncated);
+
+    let absolute = root.path().join("README.md").to_string_lossy().to_string();
+    assert_eq!(read(&config, &absolute, None).unwrap().content, response.content);
+}
+
+/// 测试根目录外的文件被拒绝
+#[test]
+fn test_read_file_outside_root_rejected() {
+    let parent = tempfile::tempdir().unwrap();
+    let root_path = parent.path().join("root");
+    fs::create_dir(&root_path).unwrap();
+    fs::write(parent.path().join("secret.txt"), "secret").unwrap();
+    let config = ReadOnlyFileConfig {
+        root_directory: Some(root_path.clone()),
+        ..ReadOnlyFileConfig::default()
+    };
+
+    let error = read(&config, "../secret.txt", None).unwrap_err();
+    assert!(error.contains("outside the root directory"), "{}", error);
+
+    let absolute = parent.path().join("secret.txt").to_string_lossy().to_string();
+    assert!(read(&config, &absolute, None).unwrap_err().contains("outside the root directory"));
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(parent.path().join("secret.txt"), root_path.join("link.txt"))
+            .unwrap();
+        assert!(read(&config, "link.txt", None)
+            .unwrap_err()
+            .contains("outside the root directory"));
+    }
+}
+
+/// 测试未配置根目录时拒绝所有读取
+#[test]
+fn test_read_file_requires_root_directory() {
+    let root = tempfile::tempdir().unwrap();
+    fs::write(root.path().join("a.txt"), "a").unwrap();
+    let path = root.path().join("a.txt").to_string_lossy().to_string();
+
+    let error = read(&ReadOnlyFileConfig::default(), &path, None).unwrap_err();
+    assert!(error.contains("ROOT_DIRECTORY"), "{}", error);
+}
+
+/// 测试目录和特殊文件等非普通文件被拒绝
+#[test]
+fn test_read_file_rejects_non_regular_files() {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir(root.path().join("docs")).unwrap();
+    let config = config_for(&root);
+
+    let error = read(&config, "docs", None).unwrap_err();
+    assert!(error.contains("Not a regular file"), "{}", error);
+
+    #[cfg(unix)]
+    {
+        let fifo = root.path().join("pipe");
+        let status = std::process::Command::new("mkfifo").arg(&fifo).status();
+        if status.is_ok_and(|status| status.success()) {
+            let error = read(&config, "pipe", None).unwrap_err();
+            assert!(error.contains("Not a regular file"), "{}", error);
+        }
+    }
+}
+
+// ============= 字节上限与编码测试 =============
+
+/// 测试按字节上限截断，且不会切断多字节字符
+#[test]
+fn test_read_file_truncates_at_max_bytes() {
+    let root = tempfile::tempdir().unwrap();
+    fs::write(root.path().join("zh.txt"), "你好世界").unwrap();
+    let config = config_for(&root);
+
+    // 每个汉字 3 字节，上限 7 字节时只返回前两个字
+    let response = read(&config, "zh.txt", Some(7)).unwrap();
+    assert_eq!(response.content, "你好");
+    assert_eq!(response.bytes_read, 7);
+    assert_eq!(response.file_size, 12);
+    assert!(response.truncated);
+
+    // 请求的上限不能超过配置的上限
+    let config = ReadOnlyFileConfig { max_bytes: 3, ..config };
+    assert_eq!(read(&config, "zh.txt", Some(1024)).unwrap().content, "你");
+}
+
+/// 测试 GBK 与 UTF-16 编码检测
+#[test]
+fn test_read_file_detects_encoding() {
+    let root = tempfile::tempdir().unwrap();
+    let config = config_for(&root);
+
+    let (gbk, _, _) = encoding_rs::GBK.encode("中文说明");
+    fs::write(root.path().join("gbk.txt"), &gbk).unwrap();
+    let response = read(&config, "gbk.txt", None).unwrap();
+    assert_eq!(response.content, "中文说明");
+    assert_eq!(response.encoding, "gb18030");
+
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend("hi".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    fs::write(root.path().join("utf16.txt"), &utf16).unwrap();
+    let response = read(&config, "utf16.txt", None).unwrap();
+    assert_eq!(response.content, "hi");
+    assert_eq!(response.encoding, "UTF-16LE");
+}
+
+// ============= 二进制文件测试 =============
+
+/// 测试小的二进制文件以 base64 返回，超过上限的被拒绝
+#[test]
+fn test_read_file_binary_size_limit() {
+    let root = tempfile::tempdir().unwrap();
+    fs::write(root.path().join("small.bin"), [0u8, 1, 2, 3]).unwrap();
+    fs::write(root.path().join("large.bin"), vec![0u8; 2048]).unwrap();
+    let config = ReadOnlyFileConfig { binary_size_limit: 1024, ..config_for(&root) };
+
+    let response = read(&config, "small.bin", None).unwrap();
+    assert_eq!(response.encoding, "base64");
+    assert_eq!(response.content, "AAECAw==");
+
+    let error = read(&config, "large.bin", None).unwrap_err();
+    assert!(error.contains("Binary file is too large"), "{}", error);
+}
+
+/// 测试环境变量配置解析
+#[test]
+fn test_config_from_env_text() {
+    let config = ReadOnlyFileConfig::from_env_text(
+        "ROOT_DIRECTORY=/tmp/project\nMAX_BYTES=4096\nBINARY_SIZE_LIMIT=abc",
+    );
+    assert_eq!(config.root_directory, Some(std::path::PathBuf::from("/tmp/project")));
+    assert_eq!(config.max_bytes, 4096);
+    assert_eq!(config.binary_size_limit, ReadOnlyFileOperations::DEFAULT_BINARY_SIZE_LIMIT);
+
+    assert!(ReadOnlyFileConfig::from_env_text("").root_directory.is_none());
+}
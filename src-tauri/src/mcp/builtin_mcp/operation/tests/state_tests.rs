@@ -4,8 +4,8 @@
 /// - 文件读取记录
 /// - Bash 进程状态管理
 /// - 权限请求处理
-use super::super::state::OperationState;
-use super::super::types::PermissionDecision;
+use super::super::state::{OperationState, MAX_DENIED_COMMAND_RECORDS};
+use super::super::types::{DeniedCommandRecord, PermissionDecision};
 
 /// 测试 OperationState 默认创建
 #[tokio::test]
@@ -203,3 +203,31 @@ async fn test_operation_state_clone() {
     // state1 也应该看到变化
     assert!(!state1.has_file_been_read(path).await);
 }
+
+/// 测试被拒绝命令的记录与按会话过滤
+#[tokio::test]
+async fn test_denied_command_records() {
+    let state = OperationState::new();
+    let record = |command: &str, conversation_id: Option<i64>| DeniedCommandRecord {
+        command: command.to_string(),
+        reason: "Command denied by rule 'curl'".to_string(),
+        conversation_id,
+        denied_at: 0,
+    };
+
+    state.record_denied_command(record("curl a", Some(1))).await;
+    state.record_denied_command(record("curl b", Some(2))).await;
+
+    assert_eq!(state.get_denied_commands(None).await.len(), 2);
+    let first = state.get_denied_commands(Some(1)).await;
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].command, "curl a");
+
+    // 超出上限时丢弃最早的记录
+    for i in 0..MAX_DENIED_COMMAND_RECORDS {
+        state.record_denied_command(record(&format!("curl {}", i), Some(3))).await;
+    }
+    let all = state.get_denied_commands(None).await;
+    assert_eq!(all.len(), MAX_DENIED_COMMAND_RECORDS);
+    assert!(state.get_denied_commands(Some(1)).await.is_empty());
+}
@@ -72,6 +72,32 @@ pub struct EditFileResponse {
     pub message: String,
 }
 
+/// 只读文件读取请求（aipp:file_reader）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyFileRequest {
+    /// 文件路径，相对路径基于配置的根目录
+    pub path: String,
+    /// 最多读取的字节数（可选，不超过配置上限）
+    pub max_bytes: Option<usize>,
+}
+
+/// 只读文件读取响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyFileResponse {
+    /// 解析后的绝对路径
+    pub file_path: String,
+    /// 文件内容，二进制文件为 base64
+    pub content: String,
+    /// 检测到的编码，如 UTF-8、GBK、UTF-16LE；二进制文件为 base64
+    pub encoding: String,
+    /// 文件总字节数
+    pub file_size: u64,
+    /// 实际读取的字节数
+    pub bytes_read: usize,
+    /// 是否因字节上限被截断
+    pub truncated: bool,
+}
+
 /// 目录列表请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListDirectoryRequest {
@@ -182,6 +208,15 @@ pub struct PermissionRequestEvent {
     pub conversation_id: Option<i64>,
 }
 
+/// 批量权限请求事件（短时间内连续发起的多个权限请求合并为一次确认）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionBatchRequestEvent {
+    /// 批次 ID
+    pub batch_id: String,
+    /// 批次内的权限请求，每项仍可单独允许或拒绝
+    pub requests: Vec<PermissionRequestEvent>,
+}
+
 /// 权限决策
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -194,6 +229,19 @@ pub enum PermissionDecision {
     Deny,
 }
 
+/// 被命令策略拒绝的 Bash 命令记录（同时作为 operation-command-denied 事件负载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeniedCommandRecord {
+    /// 被拒绝的命令
+    pub command: String,
+    /// 拒绝原因（命中的规则）
+    pub reason: String,
+    /// 会话 ID
+    pub conversation_id: Option<i64>,
+    /// 拒绝时间（Unix 时间戳）
+    pub denied_at: u64,
+}
+
 /// 权限确认请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionConfirmRequest {
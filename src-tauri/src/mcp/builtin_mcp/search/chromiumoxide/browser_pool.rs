@@ -1,10 +1,16 @@
+use super::cleanup_profile_locks;
+use super::liveness::{
+    apply_liveness_probe, probe_liveness, probe_pages, BrowserLivenessState, BrowserLivenessStatus,
+    LivenessProbe, LIVENESS_PROBE_SCRIPT,
+};
 use chromiumoxide::browser::Browser;
 use futures::StreamExt;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
@@ -16,19 +22,37 @@ pub struct BrowserPool {
     /// Browser 实例
     browser: Arc<Mutex<Option<Arc<Mutex<Browser>>>>>,
     /// 可用页面队列
-    idle_pages: Arc<Mutex<Vec<chromiumoxide::page::Page>>>,
+    idle_pages: Arc<Mutex<Vec<IdlePage>>>,
     /// 当前活跃页面计数
     active_count: Arc<AtomicUsize>,
+    /// 是否已关闭（应用退出时），关闭后不再启动浏览器
+    shut_down: Arc<AtomicBool>,
+    /// 最近一次存活探测状态
+    liveness: Arc<std::sync::Mutex<BrowserLivenessStatus>>,
+    /// 存活探测任务是否已启动
+    liveness_started: Arc<AtomicBool>,
     /// 配置
     config: BrowserPoolConfig,
 }
 
+/// 空闲页面及其归还时间
+#[derive(Clone)]
+struct IdlePage {
+    page: chromiumoxide::page::Page,
+    idle_since: Instant,
+}
+
+/// 默认最大并发页面数
+pub const DEFAULT_MAX_PAGES: usize = 5;
+/// 默认页面空闲超时（秒）
+pub const DEFAULT_PAGE_IDLE_TIMEOUT_SECS: u64 = 60;
+
 /// 浏览器池配置
 #[derive(Clone, Debug)]
 pub struct BrowserPoolConfig {
     /// 最大并发页面数
     pub max_pages: usize,
-    /// 页面空闲超时（秒），暂未实现
+    /// 页面空闲超时（秒），空闲超过该时间的页面在下次取用时关闭，0 表示不过期
     pub page_idle_timeout_secs: u64,
     /// 用户数据目录
     pub user_data_dir: Option<String>,
@@ -38,6 +62,53 @@ pub struct BrowserPoolConfig {
     pub headless: bool,
     /// 启动参数
     pub launch_args: Vec<String>,
+    /// 存活探测间隔（秒），0 表示不探测
+    pub liveness_interval_secs: u64,
+    /// 存活探测超时（秒），超时未响应视为浏览器卡死
+    pub liveness_timeout_secs: u64,
+}
+
+impl BrowserPoolConfig {
+    /// 从内置搜索服务器的环境变量读取池配置，池大小至少为 1
+    pub fn from_search_config(
+        config: &HashMap<String, String>,
+        browser_path: PathBuf,
+        user_data_dir: Option<String>,
+        launch_args: Vec<String>,
+    ) -> Self {
+        let parse_u64 = |key: &str| config.get(key).and_then(|v| v.trim().parse::<u64>().ok());
+        Self {
+            max_pages: config
+                .get("MAX_CONCURRENT_PAGES")
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(DEFAULT_MAX_PAGES)
+                .max(1),
+            page_idle_timeout_secs: parse_u64("PAGE_IDLE_TIMEOUT_SECS")
+                .unwrap_or(DEFAULT_PAGE_IDLE_TIMEOUT_SECS),
+            user_data_dir,
+            browser_path,
+            headless: config
+                .get("HEADLESS")
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(true),
+            launch_args,
+            liveness_interval_secs: parse_u64("LIVENESS_CHECK_INTERVAL_SECS").unwrap_or(60),
+            liveness_timeout_secs: parse_u64("LIVENESS_TIMEOUT_SECS").unwrap_or(10),
+        }
+    }
+
+    /// 空闲页面是否已超过空闲超时
+    fn is_idle_expired(&self, idle_since: Instant) -> bool {
+        self.page_idle_timeout_secs > 0
+            && idle_since.elapsed() >= Duration::from_secs(self.page_idle_timeout_secs)
+    }
+}
+
+/// 是否在应用启动时预先启动浏览器（EAGER_WARMUP 环境变量），默认关闭
+pub fn eager_warmup_enabled(config: &HashMap<String, String>) -> bool {
+    config
+        .get("EAGER_WARMUP")
+        .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
 }
 
 impl BrowserPool {
@@ -47,12 +118,19 @@ impl BrowserPool {
             browser: Arc::new(Mutex::new(None)),
             idle_pages: Arc::new(Mutex::new(Vec::new())),
             active_count: Arc::new(AtomicUsize::new(0)),
+            shut_down: Arc::new(AtomicBool::new(false)),
+            liveness: Arc::new(std::sync::Mutex::new(BrowserLivenessStatus::new("pool"))),
+            liveness_started: Arc::new(AtomicBool::new(false)),
             config,
         }
     }
 
     /// 获取一个页面（自动创建或复用）
     pub async fn acquire_page(&self) -> Result<PooledPage, String> {
+        if self.is_shut_down() {
+            return Err("Browser pool has been shut down".to_string());
+        }
+
         // 检查并发限制
         let current = self.active_count.load(Ordering::Acquire);
         if current >= self.config.max_pages {
@@ -78,9 +156,16 @@ impl BrowserPool {
                 let mut idle = self.idle_pages.lock().await;
                 idle.pop()
             };
-            let Some(page) = maybe_page else {
+            let Some(IdlePage { page, idle_since }) = maybe_page else {
                 break;
             };
+            if self.config.is_idle_expired(idle_since) {
+                debug!("Closing page that exceeded idle timeout");
+                if let Err(e) = page.close().await {
+                    debug!(error = %e, "Failed to close expired idle page");
+                }
+                continue;
+            }
 
             match self.ensure_page_healthy(&page).await {
                 Ok(_) => {
@@ -140,15 +225,25 @@ impl BrowserPool {
         Ok(PooledPage { page: Some(page), pool: Some(self.clone()) })
     }
 
+    /// 预先启动浏览器，避免首次搜索时等待浏览器启动
+    pub async fn warm_up(&self) -> Result<(), String> {
+        self.get_or_init_browser().await.map(|_| ())
+    }
+
     /// 获取或初始化浏览器
     async fn get_or_init_browser(&self) -> Result<Arc<Mutex<Browser>>, String> {
         let mut browser_slot = self.browser.lock().await;
         if let Some(existing) = browser_slot.as_ref() {
             return Ok(existing.clone());
         }
+        // 退出过程中不再启动新的浏览器进程
+        if self.is_shut_down() {
+            return Err("Browser pool has been shut down".to_string());
+        }
 
         let browser = Arc::new(Mutex::new(self.initialize_browser().await?));
         *browser_slot = Some(browser.clone());
+        self.start_liveness_monitor();
         Ok(browser)
     }
 
@@ -166,23 +261,128 @@ impl BrowserPool {
         };
 
         if let Some(browser) = old_browser {
-            let mut guard = browser.lock().await;
-            if let Err(e) = guard.close().await {
-                warn!(error = %e, "Failed to close stale browser before recreation");
-            }
-            if let Err(e) = guard.wait().await {
-                warn!(error = %e, "Failed to wait for stale browser exit before recreation");
+            // 卡死的浏览器可能不响应 close，超时后直接结束进程
+            let close_result = tokio::time::timeout(self.liveness_timeout(), async {
+                let mut guard = browser.lock().await;
+                if let Err(e) = guard.close().await {
+                    warn!(error = %e, "Failed to close stale browser before recreation");
+                }
+                if let Err(e) = guard.wait().await {
+                    warn!(error = %e, "Failed to wait for stale browser exit before recreation");
+                }
+            })
+            .await;
+            if close_result.is_err() {
+                warn!("Stale browser did not close in time, killing process");
+                match browser.try_lock() {
+                    Ok(mut guard) => {
+                        if let Some(Err(e)) = guard.kill().await {
+                            warn!(error = %e, "Failed to kill stale browser process");
+                        }
+                    }
+                    Err(_) => warn!("Stale browser is still locked, leaving it to be dropped"),
+                }
             }
         }
 
+        // 旧进程被强制结束时可能残留单例锁，导致新浏览器无法使用同一用户目录
+        cleanup_profile_locks(&self.user_data_dir(), "browser_recreate");
+
         self.get_or_init_browser().await
     }
 
     async fn ensure_page_healthy(&self, page: &chromiumoxide::page::Page) -> Result<(), String> {
-        page.evaluate("() => document.readyState")
-            .await
-            .map(|_| ())
-            .map_err(|e| format!("Page health check failed: {}", e))
+        let check = async {
+            page.evaluate("() => document.readyState")
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Page health check failed: {}", e))
+        };
+        match probe_liveness(check, self.liveness_timeout()).await {
+            LivenessProbe::Alive(_) => Ok(()),
+            LivenessProbe::Hung => Err("Page health check failed: page not responding".to_string()),
+            LivenessProbe::Failed(e) => Err(e),
+        }
+    }
+
+    fn liveness_timeout(&self) -> Duration {
+        Duration::from_secs(self.config.liveness_timeout_secs.max(1))
+    }
+
+    fn user_data_dir(&self) -> PathBuf {
+        match self.config.user_data_dir {
+            Some(ref dir) => PathBuf::from(dir),
+            None => std::env::temp_dir().join("aipp_chromiumoxide_pool"),
+        }
+    }
+
+    /// 浏览器首次启动后开始定期探测，池关闭时退出
+    fn start_liveness_monitor(&self) {
+        if self.config.liveness_interval_secs == 0
+            || self.liveness_started.swap(true, Ordering::AcqRel)
+        {
+            return;
+        }
+        let pool = self.clone();
+        let interval = Duration::from_secs(self.config.liveness_interval_secs);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if pool.is_shut_down() {
+                    break;
+                }
+                pool.check_liveness().await;
+            }
+            debug!("Browser liveness monitor stopped");
+        });
+    }
+
+    /// 探测浏览器是否卡死：有空闲页面时在页面上执行脚本，否则查询浏览器版本；
+    /// 超时未响应时重启浏览器。正在使用的页面不参与探测
+    pub async fn check_liveness(&self) -> BrowserLivenessState {
+        let browser = { self.browser.lock().await.clone() };
+        let Some(browser) = browser else {
+            return self.liveness_status().state;
+        };
+        let timeout = self.liveness_timeout();
+
+        let pages = {
+            let mut idle = self.idle_pages.lock().await;
+            std::mem::take(&mut *idle)
+        };
+        let probe = if pages.is_empty() {
+            let version = async {
+                let guard = browser.lock().await;
+                guard.version().await.map(|_| ()).map_err(|e| e.to_string())
+            };
+            probe_liveness(version, timeout).await
+        } else {
+            let (alive, probe) = probe_pages(
+                pages,
+                |idle: IdlePage| async move {
+                    idle.page
+                        .evaluate(LIVENESS_PROBE_SCRIPT)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                },
+                timeout,
+            )
+            .await;
+            self.idle_pages.lock().await.extend(alive);
+            probe
+        };
+
+        apply_liveness_probe(&self.liveness, probe, || async {
+            self.recreate_browser().await.map(|_| ())
+        })
+        .await;
+        self.liveness_status().state
+    }
+
+    /// 最近一次存活探测状态
+    pub fn liveness_status(&self) -> BrowserLivenessStatus {
+        self.liveness.lock().unwrap().clone()
     }
 
     fn is_connection_closed_error(error: &str) -> bool {
@@ -199,11 +399,7 @@ impl BrowserPool {
         info!("Initializing Chromium BrowserPool");
 
         // 创建用户数据目录
-        let user_data_dir = if let Some(ref dir) = self.config.user_data_dir {
-            PathBuf::from(dir)
-        } else {
-            std::env::temp_dir().join("aipp_chromiumoxide_pool")
-        };
+        let user_data_dir = self.user_data_dir();
 
         if let Err(e) = fs::create_dir_all(&user_data_dir) {
             warn!(error = %e, "Failed to create user_data_dir");
@@ -269,7 +465,9 @@ impl BrowserPool {
         Ok(browser)
     }
 
+    /// 关闭浏览器进程并清空页面池，之后的 acquire_page 都会失败
     pub async fn shutdown(&self) -> Result<(), String> {
+        self.shut_down.store(true, Ordering::Release);
         let browser = {
             let mut browser_slot = self.browser.lock().await;
             browser_slot.take()
@@ -293,7 +491,7 @@ impl BrowserPool {
     /// 归还页面到池中
     async fn return_page(&self, page: chromiumoxide::page::Page) {
         let mut idle = self.idle_pages.lock().await;
-        idle.push(page);
+        idle.push(IdlePage { page, idle_since: Instant::now() });
         // 减少活跃计数
         self.active_count.fetch_sub(1, Ordering::AcqRel);
         debug!(
@@ -307,6 +505,11 @@ impl BrowserPool {
     pub fn active_count(&self) -> usize {
         self.active_count.load(Ordering::Acquire)
     }
+
+    /// 是否已关闭
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::Acquire)
+    }
 }
 
 /// 池化的页面，自动归还到池中
@@ -348,3 +551,80 @@ impl Drop for PooledPage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_config(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn config_from(pairs: &[(&str, &str)]) -> BrowserPoolConfig {
+        BrowserPoolConfig::from_search_config(
+            &search_config(pairs),
+            PathBuf::from("/nonexistent/chromium"),
+            None,
+            Vec::new(),
+        )
+    }
+
+    /// 测试从搜索环境变量读取池大小与空闲超时
+    #[test]
+    fn test_pool_config_from_search_config() {
+        let defaults = config_from(&[]);
+        assert_eq!(defaults.max_pages, DEFAULT_MAX_PAGES);
+        assert_eq!(defaults.page_idle_timeout_secs, DEFAULT_PAGE_IDLE_TIMEOUT_SECS);
+        assert!(defaults.headless);
+
+        assert_eq!(config_from(&[("MAX_CONCURRENT_PAGES", "1")]).max_pages, 1);
+        assert_eq!(config_from(&[("MAX_CONCURRENT_PAGES", " 4 ")]).max_pages, 4);
+        // 0 会让池拒绝所有请求，按 1 处理；无效值回退默认
+        assert_eq!(config_from(&[("MAX_CONCURRENT_PAGES", "0")]).max_pages, 1);
+        assert_eq!(config_from(&[("MAX_CONCURRENT_PAGES", "many")]).max_pages, DEFAULT_MAX_PAGES);
+        assert_eq!(config_from(&[("PAGE_IDLE_TIMEOUT_SECS", "300")]).page_idle_timeout_secs, 300);
+
+        assert!(!eager_warmup_enabled(&search_config(&[])));
+        assert!(eager_warmup_enabled(&search_config(&[("EAGER_WARMUP", "true")])));
+        assert!(!eager_warmup_enabled(&search_config(&[("EAGER_WARMUP", "false")])));
+    }
+
+    /// 测试空闲超时判断，0 表示不过期
+    #[test]
+    fn test_idle_page_expiry() {
+        let stale = Instant::now() - Duration::from_secs(120);
+        assert!(config_from(&[("PAGE_IDLE_TIMEOUT_SECS", "60")]).is_idle_expired(stale));
+        assert!(!config_from(&[("PAGE_IDLE_TIMEOUT_SECS", "600")]).is_idle_expired(stale));
+        assert!(!config_from(&[("PAGE_IDLE_TIMEOUT_SECS", "0")]).is_idle_expired(stale));
+    }
+
+    /// 测试无法启动浏览器时预热返回错误，由调用方记录警告
+    #[tokio::test]
+    async fn test_warm_up_after_shutdown_fails() {
+        let pool = BrowserPool::new(config_from(&[("LIVENESS_CHECK_INTERVAL_SECS", "0")]));
+        pool.shutdown().await.unwrap();
+        assert!(pool.warm_up().await.is_err());
+    }
+
+    /// 测试 shutdown 后池不再启动浏览器
+    #[tokio::test]
+    async fn test_shutdown_stops_pool() {
+        let pool = BrowserPool::new(BrowserPoolConfig {
+            max_pages: 2,
+            page_idle_timeout_secs: 0,
+            user_data_dir: None,
+            browser_path: PathBuf::from("/nonexistent/chromium"),
+            headless: true,
+            launch_args: Vec::new(),
+            liveness_interval_secs: 0,
+            liveness_timeout_secs: 10,
+        });
+
+        pool.shutdown().await.unwrap();
+
+        assert!(pool.is_shut_down());
+        let err = pool.acquire_page().await.err().expect("acquire after shutdown should fail");
+        assert!(err.contains("shut down"));
+        assert_eq!(pool.active_count(), 0);
+    }
+}
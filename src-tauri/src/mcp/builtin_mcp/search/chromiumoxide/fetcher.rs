@@ -1,13 +1,23 @@
 use super::super::browser::BrowserManager;
 use super::super::engine_manager::SearchEngine;
 use super::super::fingerprint::{FingerprintConfig, FingerprintManager, TimingConfig};
+use super::super::pdf::{
+    extract_pdf_text, is_pdf_bytes, is_pdf_content_type, looks_like_pdf_url, read_pdf_body,
+};
+use super::super::safe_search::{apply_safe_search_to_url, safe_search_url, SafeSearchLevel};
 use super::browser_pool::BrowserPool;
+use super::cleanup_profile_locks;
+use super::liveness::{
+    probe_liveness, recycle_standalone_if_hung, LIVENESS_PROBE_SCRIPT, STANDALONE_LIVENESS,
+};
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::page::Page;
 use chromiumoxide_cdp::cdp::browser_protocol::{emulation, network, page as cdp_page};
 use futures::StreamExt;
 use rand::Rng;
 use serde_json::{json, Value};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 use tokio::process::Command as TokioCommand;
@@ -20,6 +30,8 @@ use tracing::{debug, error, info, trace, warn};
 const DEBUG_SAVE_HTML: bool = false;
 /// 调试HTML保存目录
 const DEBUG_HTML_DIR: &str = "~/tmp";
+/// 独立浏览器启动后存活探测的超时时间
+const STANDALONE_LIVENESS_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone)]
 pub struct FetchConfig {
@@ -34,6 +46,8 @@ pub struct FetchConfig {
     /// Kagi 会话链接，仅在使用 Kagi 搜索引擎时生效
     /// 格式如：https://kagi.com/search?token=xxxxx
     pub kagi_session_url: Option<String>,
+    /// 安全搜索级别，支持的引擎直接访问带安全搜索参数的结果页
+    pub safe_search: SafeSearchLevel,
 }
 
 impl Default for FetchConfig {
@@ -48,6 +62,22 @@ impl Default for FetchConfig {
             wait_timeout_ms: 15000,
             wait_poll_ms: 250,
             kagi_session_url: None,
+            safe_search: SafeSearchLevel::Off,
+        }
+    }
+}
+
+/// 抓取结果：网页 HTML，或主文档是 PDF 时从中提取的纯文本
+#[derive(Debug, Clone)]
+pub enum FetchedContent {
+    Html(String),
+    PdfText(String),
+}
+
+impl FetchedContent {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FetchedContent::Html(text) | FetchedContent::PdfText(text) => text,
         }
     }
 }
@@ -420,8 +450,8 @@ impl ContentFetcher {
         }
     }
 
-    /// 使用HTTP直接请求
-    async fn fetch_with_http(&self, url: &str) -> Result<String, String> {
+    /// 构建直接请求使用的 HTTP 客户端（带 UA 与代理配置）
+    fn build_http_client(&self) -> Result<reqwest::Client, String> {
         let user_agent = self.config.user_agent.as_deref().unwrap_or(
             "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36"
         );
@@ -437,8 +467,84 @@ impl ContentFetcher {
             client_builder = client_builder.proxy(proxy);
         }
 
-        let client =
-            client_builder.build().map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        client_builder.build().map_err(|e| format!("Failed to build HTTP client: {}", e))
+    }
+
+    /// URL 以 `.pdf` 结尾时直接下载并提取文本，其他链接返回 None，
+    /// 由常规抓取根据主文档的 Content-Type 识别 PDF（如 arXiv 的 /pdf/ 链接）
+    pub async fn fetch_pdf_text(&self, url: &str) -> Result<Option<String>, String> {
+        if !looks_like_pdf_url(url) {
+            return Ok(None);
+        }
+        match self.download_pdf(url).await? {
+            Some(bytes) => Self::extract_pdf(url, bytes).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 下载 PDF（受大小上限约束），响应不是 PDF 时返回 None
+    async fn download_pdf(&self, url: &str) -> Result<Option<Vec<u8>>, String> {
+        let client = self.build_http_client()?;
+        let resp = client
+            .get(url)
+            .header("Accept", "application/pdf,*/*;q=0.8")
+            .send()
+            .await
+            .map_err(|e| format!("HTTP request error: {}", e))?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(format!("HTTP status {} when fetching {}", status.as_u16(), url));
+        }
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        // 以 .pdf 结尾的链接也可能返回 HTML（如登录页），此时不读取正文，按普通网页处理
+        if !is_pdf_content_type(&content_type) && content_type.starts_with("text/") {
+            debug!(%url, content_type = %content_type, "URL is not a PDF, using regular fetch");
+            return Ok(None);
+        }
+        let bytes = read_pdf_body(resp).await?;
+        if !is_pdf_content_type(&content_type) && !is_pdf_bytes(&bytes) {
+            debug!(%url, content_type = %content_type, "URL is not a PDF, using regular fetch");
+            return Ok(None);
+        }
+        Ok(Some(bytes))
+    }
+
+    /// 从 PDF 中提取文本，解析是 CPU 密集操作，放到阻塞线程中执行；解析器 panic 时作为错误返回
+    async fn extract_pdf(url: &str, bytes: Vec<u8>) -> Result<String, String> {
+        info!(%url, bytes = bytes.len(), "Extracting text from PDF");
+        tokio::task::spawn_blocking(move || extract_pdf_text(&bytes))
+            .await
+            .map_err(|e| format!("PDF extraction aborted: {}", e))?
+    }
+
+    /// 浏览器导航到 PDF 时主文档的 Content-Type 为 application/pdf（显示的是内置查看器）
+    async fn page_is_pdf(page: &chromiumoxide::page::Page) -> bool {
+        page.evaluate("() => document.contentType")
+            .await
+            .ok()
+            .and_then(|val| val.value().and_then(|v| v.as_str().map(is_pdf_content_type)))
+            .unwrap_or(false)
+    }
+
+    /// 浏览器打开的是 PDF 时改为直接下载并提取文本
+    async fn fetch_pdf_from_page(&self, url: &str) -> Result<FetchedContent, String> {
+        info!(%url, "Page is a PDF document, downloading it directly");
+        let bytes = self
+            .download_pdf(url)
+            .await?
+            .ok_or_else(|| "Page is a PDF document but the download is not a PDF".to_string())?;
+        Self::extract_pdf(url, bytes).await.map(FetchedContent::PdfText)
+    }
+
+    /// 使用HTTP直接请求，响应为 PDF 时提取文本
+    async fn fetch_with_http(&self, url: &str) -> Result<FetchedContent, String> {
+        let client = self.build_http_client()?;
 
         let resp = client
             .get(url)
@@ -453,6 +559,16 @@ impl ContentFetcher {
             return Err(format!("HTTP status {} when fetching {}", status.as_u16(), url));
         }
 
+        let is_pdf = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(is_pdf_content_type);
+        if is_pdf {
+            let bytes = read_pdf_body(resp).await?;
+            return Self::extract_pdf(url, bytes).await.map(FetchedContent::PdfText);
+        }
+
         let text = resp.text().await.map_err(|e| format!("Failed to read response body: {}", e))?;
 
         if text.trim().is_empty() {
@@ -460,11 +576,11 @@ impl ContentFetcher {
             return Err("Empty response body".to_string());
         }
 
-        Ok(text)
+        Ok(FetchedContent::Html(text))
     }
 
     /// WebView兜底导航（不提取内容）
-    async fn fallback_webview_navigation(&self, url: &str) -> Result<String, String> {
+    async fn fallback_webview_navigation(&self, url: &str) -> Result<FetchedContent, String> {
         if let Err(e) = crate::window::ensure_hidden_search_window(self.app_handle.clone()).await {
             warn!(error = %e, "Failed to create hidden search window");
         } else if let Some(window) = self.app_handle.get_webview_window("hidden_search") {
@@ -482,14 +598,18 @@ impl ContentFetcher {
         url: &str,
         browser_manager: &BrowserManager,
         browser_pool: Option<&BrowserPool>,
-    ) -> Result<String, String> {
+    ) -> Result<FetchedContent, String> {
         info!(%url, "Starting content fetch");
 
         // 策略1: Chromiumoxide（最优，支持复杂动态内容）
         match self.fetch_with_chromiumoxide(url, browser_manager, browser_pool).await {
-            Ok(html) => {
-                info!(strategy = "chromiumoxide", bytes = html.len(), "Fetched content");
-                return Ok(html);
+            Ok(content) => {
+                info!(
+                    strategy = "chromiumoxide",
+                    bytes = content.as_str().len(),
+                    "Fetched content"
+                );
+                return Ok(content);
             }
             Err(e) => {
                 warn!(
@@ -505,7 +625,7 @@ impl ContentFetcher {
         match self.fetch_with_headless_browser(url, browser_manager).await {
             Ok(html) => {
                 info!(strategy = "headless", bytes = html.len(), "Fetched content");
-                return Ok(html);
+                return Ok(FetchedContent::Html(html));
             }
             Err(e) => {
                 warn!(
@@ -519,9 +639,9 @@ impl ContentFetcher {
 
         // 策略3: HTTP直接请求（兜底，适合静态内容）
         match self.fetch_with_http(url).await {
-            Ok(html) => {
-                info!(strategy = "http", bytes = html.len(), "Fetched content");
-                return Ok(html);
+            Ok(content) => {
+                info!(strategy = "http", bytes = content.as_str().len(), "Fetched content");
+                return Ok(content);
             }
             Err(e) => {
                 warn!(
@@ -537,13 +657,60 @@ impl ContentFetcher {
         self.fallback_webview_navigation(url).await
     }
 
+    /// 启动浏览器并打开空白页
+    async fn launch_browser_with_page(config: BrowserConfig) -> Result<(Browser, Page), String> {
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .map_err(|e| format!("Failed to launch browser: {}", e))?;
+
+        // 启动事件处理器
+        tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                trace!(?event, "Chromium event received");
+            }
+        });
+
+        let page = browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| format!("Failed to create page: {}", e))?;
+        Ok((browser, page))
+    }
+
+    /// 启动独立浏览器（未使用浏览器池）并探测一次存活状态；
+    /// 页面不响应探测脚本时视为卡死，结束进程、清理单例锁后重新启动
+    async fn launch_standalone_page(
+        config: BrowserConfig,
+        user_data_dir: &Path,
+    ) -> Result<(Browser, Page), String> {
+        let (browser, page) = Self::launch_browser_with_page(config.clone()).await?;
+        let check = async {
+            page.evaluate(LIVENESS_PROBE_SCRIPT).await.map(|_| ()).map_err(|e| e.to_string())
+        };
+        let probe = probe_liveness(check, STANDALONE_LIVENESS_TIMEOUT).await;
+
+        recycle_standalone_if_hung(
+            &STANDALONE_LIVENESS,
+            probe,
+            (browser, page),
+            |(mut browser, _page)| async move {
+                if let Some(Err(e)) = browser.kill().await {
+                    warn!(error = %e, "Failed to kill hung standalone browser process");
+                }
+                cleanup_profile_locks(user_data_dir, "standalone_recycle");
+                Self::launch_browser_with_page(config).await
+            },
+        )
+        .await
+    }
+
     /// 使用Chromiumoxide抓取内容
     async fn fetch_with_chromiumoxide(
         &mut self,
         url: &str,
         browser_manager: &BrowserManager,
         browser_pool: Option<&BrowserPool>,
-    ) -> Result<String, String> {
+    ) -> Result<FetchedContent, String> {
         // 如果有浏览器池，使用池化页面
         if let Some(pool) = browser_pool {
             return self.fetch_with_pooled_page(url, pool).await;
@@ -558,8 +725,6 @@ impl ContentFetcher {
 
         let stealth_args = FingerprintManager::get_stealth_launch_args();
 
-        use chromiumoxide::BrowserConfig;
-
         let mut builder = BrowserConfig::builder()
             .user_data_dir(&user_data_dir)
             .no_sandbox()
@@ -611,31 +776,18 @@ impl ContentFetcher {
         let config =
             builder.build().map_err(|e| format!("Failed to build browser config: {}", e))?;
 
-        let (browser, mut handler) = chromiumoxide::browser::Browser::launch(config)
-            .await
-            .map_err(|e| {
+        let (_browser, page) =
+            Self::launch_standalone_page(config, &user_data_dir).await.map_err(|e| {
                 format!(
-                    "Failed to launch browser (path={}, exists={}, headless={}, user_data_dir={}): {}",
+                    "{} (path={}, exists={}, headless={}, user_data_dir={})",
+                    e,
                     browser_path.display(),
                     browser_path_exists,
                     self.config.headless,
-                    user_data_dir.display(),
-                    e
+                    user_data_dir.display()
                 )
             })?;
 
-        // 启动事件处理器
-        tokio::spawn(async move {
-            while let Some(event) = handler.next().await {
-                trace!(?event, "Chromium event received");
-            }
-        });
-
-        let page = browser
-            .new_page("about:blank")
-            .await
-            .map_err(|e| format!("Failed to create page: {}", e))?;
-
         let fingerprint = self.fingerprint_manager.get_stable_fingerprint(None).clone();
 
         // 注入反检测脚本
@@ -646,6 +798,9 @@ impl ContentFetcher {
         self.set_page_http_headers(&page, &fingerprint).await?;
 
         self.goto_with_timeout(&page, url, "fetch_content").await?;
+        if Self::page_is_pdf(&page).await {
+            return self.fetch_pdf_from_page(url).await;
+        }
 
         // 等待页面加载完成
         self.wait_for_content(&page).await?;
@@ -666,7 +821,7 @@ impl ContentFetcher {
             return Err("Empty HTML from Chromiumoxide".to_string());
         }
 
-        Ok(html)
+        Ok(FetchedContent::Html(html))
     }
 
     /// 使用浏览器池抓取URL内容
@@ -674,7 +829,7 @@ impl ContentFetcher {
         &mut self,
         url: &str,
         pool: &BrowserPool,
-    ) -> Result<String, String> {
+    ) -> Result<FetchedContent, String> {
         let mut pooled_page = pool.acquire_page().await?;
         let page = pooled_page.page();
 
@@ -689,6 +844,9 @@ impl ContentFetcher {
 
         // 导航到 URL
         self.goto_with_timeout(page, url, "fetch_content_pooled").await?;
+        if Self::page_is_pdf(page).await {
+            return self.fetch_pdf_from_page(url).await;
+        }
 
         // 等待页面加载完成
         self.wait_for_content(page).await?;
@@ -712,7 +870,7 @@ impl ContentFetcher {
         info!(bytes = html.len(), "Successfully fetched content (pooled)");
 
         // pooled_page 离开作用域时自动归还到池中
-        Ok(html)
+        Ok(FetchedContent::Html(html))
     }
 
     /// 使用系统浏览器headless模式抓取
@@ -838,6 +996,76 @@ impl ContentFetcher {
             + if action_range > 0 { rand::random::<u64>() % action_range } else { 0 };
         sleep(Duration::from_millis(initial_delay)).await;
 
+        // 开启安全搜索时直接访问带参数的结果页，确保设置生效
+        if let Some(search_url) = safe_search_url(search_engine, query, self.config.safe_search) {
+            info!(
+                %search_url,
+                safe_search = self.config.safe_search.as_str(),
+                "Navigating to search results with safe search"
+            );
+            let navigate_stage_start = Instant::now();
+            self.navigate_with_retry(page, &search_url).await.map_err(|e| {
+                let timeout_like = Self::is_timeout_like(&e);
+                error!(
+                    stage = "navigate_safe_search",
+                    engine = search_engine.as_str(),
+                    timeout_like,
+                    elapsed_ms = navigate_stage_start.elapsed().as_millis() as u64,
+                    error = %e,
+                    "Search stage failed"
+                );
+                format!("Search stage navigate_safe_search failed: {}", e)
+            })?;
+        } else {
+            self.humanized_search_from_homepage(page, query, search_engine).await?;
+        }
+
+        // 等待结果加载，使用配置的等待时间加随机延时
+        let wait_time = self.config.wait_timeout_ms + rand::random::<u64>() % 2000;
+        let wait_stage_start = Instant::now();
+        self.wait_for_results_with_timeout(page, wait_time, search_engine).await.map_err(|e| {
+            let timeout_like = Self::is_timeout_like(&e);
+            error!(
+                stage = "wait_search_results",
+                engine = search_engine.as_str(),
+                timeout_like,
+                elapsed_ms = wait_stage_start.elapsed().as_millis() as u64,
+                error = %e,
+                "Search stage failed"
+            );
+            format!("Search stage wait_search_results failed: {}", e)
+        })?;
+
+        // 增强的HTML提取，带重试机制
+        let extract_stage_start = Instant::now();
+        let html = self.extract_page_html_with_retry(page).await.map_err(|e| {
+            let timeout_like = Self::is_timeout_like(&e);
+            error!(
+                stage = "extract_html",
+                engine = search_engine.as_str(),
+                timeout_like,
+                elapsed_ms = extract_stage_start.elapsed().as_millis() as u64,
+                error = %e,
+                "Search stage failed"
+            );
+            format!("Search stage extract_html failed: {}", e)
+        })?;
+
+        debug!("Successfully retrieved {} bytes", html.len());
+
+        // 保存调试HTML
+        Self::save_debug_html(&html, "search_result");
+
+        Ok(html)
+    }
+
+    /// 从搜索引擎首页模拟输入并提交搜索
+    async fn humanized_search_from_homepage(
+        &mut self,
+        page: &chromiumoxide::page::Page,
+        query: &str,
+        search_engine: &SearchEngine,
+    ) -> Result<(), String> {
         // 带重试的导航到搜索引擎首页
         let homepage_url = search_engine.homepage_url();
         let navigate_stage_start = Instant::now();
@@ -887,43 +1115,7 @@ impl ContentFetcher {
             format!("Search stage submit_search failed: {}", e)
         })?;
 
-        // 等待结果加载，使用配置的等待时间加随机延时
-        let wait_time = self.config.wait_timeout_ms + rand::random::<u64>() % 2000;
-        let wait_stage_start = Instant::now();
-        self.wait_for_results_with_timeout(page, wait_time, search_engine).await.map_err(|e| {
-            let timeout_like = Self::is_timeout_like(&e);
-            error!(
-                stage = "wait_search_results",
-                engine = search_engine.as_str(),
-                timeout_like,
-                elapsed_ms = wait_stage_start.elapsed().as_millis() as u64,
-                error = %e,
-                "Search stage failed"
-            );
-            format!("Search stage wait_search_results failed: {}", e)
-        })?;
-
-        // 增强的HTML提取，带重试机制
-        let extract_stage_start = Instant::now();
-        let html = self.extract_page_html_with_retry(page).await.map_err(|e| {
-            let timeout_like = Self::is_timeout_like(&e);
-            error!(
-                stage = "extract_html",
-                engine = search_engine.as_str(),
-                timeout_like,
-                elapsed_ms = extract_stage_start.elapsed().as_millis() as u64,
-                error = %e,
-                "Search stage failed"
-            );
-            format!("Search stage extract_html failed: {}", e)
-        })?;
-
-        debug!("Successfully retrieved {} bytes", html.len());
-
-        // 保存调试HTML
-        Self::save_debug_html(&html, "search_result");
-
-        Ok(html)
+        Ok(())
     }
 
     /// 带重试机制的HTML提取
@@ -1588,8 +1780,6 @@ impl ContentFetcher {
 
         let stealth_args = FingerprintManager::get_stealth_launch_args();
 
-        use chromiumoxide::BrowserConfig;
-
         let mut builder = BrowserConfig::builder()
             .user_data_dir(&user_data_dir)
             .no_sandbox()
@@ -1627,21 +1817,7 @@ impl ContentFetcher {
         let config =
             builder.build().map_err(|e| format!("Failed to build browser config: {}", e))?;
 
-        let (browser, mut handler) = chromiumoxide::browser::Browser::launch(config)
-            .await
-            .map_err(|e| format!("Failed to launch browser: {}", e))?;
-
-        // 启动事件处理器
-        tokio::spawn(async move {
-            while let Some(event) = handler.next().await {
-                trace!(?event, "Chromium event received");
-            }
-        });
-
-        let page = browser
-            .new_page("about:blank")
-            .await
-            .map_err(|e| format!("Failed to create page: {}", e))?;
+        let (_browser, page) = Self::launch_standalone_page(config, &user_data_dir).await?;
 
         let fingerprint = self.fingerprint_manager.get_stable_fingerprint(None).clone();
 
@@ -1677,7 +1853,7 @@ impl ContentFetcher {
         query: &str,
         search_engine: &SearchEngine,
         pool: &BrowserPool,
-    ) -> Result<String, String> {
+    ) -> Result<FetchedContent, String> {
         let mut pooled_page = pool.acquire_page().await?;
         let page = pooled_page.page();
 
@@ -1707,7 +1883,7 @@ impl ContentFetcher {
         info!(bytes = html.len(), "Successfully fetched search content (pooled)");
 
         // pooled_page 离开作用域时自动归还到池中
-        Ok(html)
+        Ok(FetchedContent::Html(html))
     }
 
     /// 为搜索请求定制的获取方法
@@ -1777,6 +1953,8 @@ impl ContentFetcher {
         } else {
             format!("{}?q={}", session_url, encoded_query)
         };
+        let search_url =
+            apply_safe_search_to_url(&search_url, &SearchEngine::Kagi, self.config.safe_search);
 
         info!(%search_url, "Fetching Kagi search results with session URL");
 
@@ -1795,8 +1973,6 @@ impl ContentFetcher {
 
         let stealth_args = FingerprintManager::get_stealth_launch_args();
 
-        use chromiumoxide::BrowserConfig;
-
         let mut builder = BrowserConfig::builder()
             .user_data_dir(&user_data_dir)
             .no_sandbox()
@@ -1834,21 +2010,7 @@ impl ContentFetcher {
         let config =
             builder.build().map_err(|e| format!("Failed to build browser config: {}", e))?;
 
-        let (browser, mut handler) = chromiumoxide::browser::Browser::launch(config)
-            .await
-            .map_err(|e| format!("Failed to launch browser: {}", e))?;
-
-        // 启动事件处理器
-        tokio::spawn(async move {
-            while let Some(event) = handler.next().await {
-                trace!(?event, "Chromium event received");
-            }
-        });
-
-        let page = browser
-            .new_page("about:blank")
-            .await
-            .map_err(|e| format!("Failed to create page: {}", e))?;
+        let (_browser, page) = Self::launch_standalone_page(config, &user_data_dir).await?;
 
         let fingerprint = self.fingerprint_manager.get_stable_fingerprint(None).clone();
 
@@ -1894,7 +2056,7 @@ impl ContentFetcher {
         &mut self,
         search_url: &str,
         pool: &BrowserPool,
-    ) -> Result<String, String> {
+    ) -> Result<FetchedContent, String> {
         let mut pooled_page = pool.acquire_page().await?;
         let page = pooled_page.page();
 
@@ -0,0 +1,306 @@
+//! 浏览器存活探测：崩溃会表现为连接关闭，但浏览器卡死时连接仍在，只是所有请求都不再响应。
+//! 定期在页面上执行一段简单脚本，超时未响应即视为卡死，由浏览器池重启浏览器。
+//! 未启用浏览器池时每次抓取都会启动独立浏览器，启动后先探测一次，卡死则重新启动。
+
+use serde::Serialize;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// 探测脚本，只要页面还能执行 JS 就会立即返回
+pub const LIVENESS_PROBE_SCRIPT: &str = "() => 1";
+
+/// 单次探测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LivenessProbe {
+    /// 正常响应，附带响应耗时
+    Alive(Duration),
+    /// 超时未响应，视为卡死
+    Hung,
+    /// 返回了错误（页面关闭、连接断开等），交给原有的崩溃处理
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserLivenessState {
+    Unknown,
+    Alive,
+    Hung,
+    Recycled,
+}
+
+/// 浏览器最近一次存活探测的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct BrowserLivenessStatus {
+    pub browser: String,
+    pub state: BrowserLivenessState,
+    pub last_checked_time: Option<String>,
+    pub last_response_ms: Option<u64>,
+    pub last_error: Option<String>,
+    /// 因卡死被重启的次数
+    pub recycle_count: u32,
+}
+
+impl BrowserLivenessStatus {
+    pub fn new(browser: &str) -> Self {
+        Self {
+            browser: browser.to_string(),
+            state: BrowserLivenessState::Unknown,
+            last_checked_time: None,
+            last_response_ms: None,
+            last_error: None,
+            recycle_count: 0,
+        }
+    }
+}
+
+/// 独立浏览器（未使用浏览器池时按次启动）最近一次存活探测的状态
+pub static STANDALONE_LIVENESS: LazyLock<Mutex<BrowserLivenessStatus>> =
+    LazyLock::new(|| Mutex::new(BrowserLivenessStatus::new("standalone")));
+
+/// 在超时时间内等待探测完成
+pub async fn probe_liveness<Fut>(probe: Fut, timeout: Duration) -> LivenessProbe
+where
+    Fut: Future<Output = Result<(), String>>,
+{
+    let start = Instant::now();
+    match tokio::time::timeout(timeout, probe).await {
+        Ok(Ok(())) => LivenessProbe::Alive(start.elapsed()),
+        Ok(Err(e)) => LivenessProbe::Failed(e),
+        Err(_) => LivenessProbe::Hung,
+    }
+}
+
+/// 逐个探测页面，返回仍然可用的页面与整体结果
+///
+/// 任一页面卡死即视为整个浏览器卡死，此时不再返回页面（浏览器会被重启）；
+/// 出错的页面直接丢弃，只有全部出错时整体才算失败
+pub async fn probe_pages<P, F, Fut>(
+    pages: Vec<P>,
+    probe: F,
+    timeout: Duration,
+) -> (Vec<P>, LivenessProbe)
+where
+    P: Clone,
+    F: Fn(P) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut alive = Vec::new();
+    let mut slowest = Duration::ZERO;
+    let mut last_error = None;
+    for page in pages {
+        match probe_liveness(probe(page.clone()), timeout).await {
+            LivenessProbe::Alive(elapsed) => {
+                slowest = slowest.max(elapsed);
+                alive.push(page);
+            }
+            LivenessProbe::Hung => return (Vec::new(), LivenessProbe::Hung),
+            LivenessProbe::Failed(e) => last_error = Some(e),
+        }
+    }
+    match (alive.is_empty(), last_error) {
+        (true, Some(e)) => (alive, LivenessProbe::Failed(e)),
+        _ => (alive, LivenessProbe::Alive(slowest)),
+    }
+}
+
+/// 记录探测结果，卡死时调用 `recycle` 重启浏览器；返回是否执行了重启
+pub async fn apply_liveness_probe<F, Fut>(
+    status: &Mutex<BrowserLivenessStatus>,
+    probe: LivenessProbe,
+    recycle: F,
+) -> bool
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let checked_time = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let browser = {
+        let mut status = status.lock().unwrap();
+        status.last_checked_time = Some(checked_time);
+        match &probe {
+            LivenessProbe::Alive(elapsed) => {
+                status.state = BrowserLivenessState::Alive;
+                status.last_response_ms = Some(elapsed.as_millis() as u64);
+                status.last_error = None;
+            }
+            LivenessProbe::Hung => {
+                status.state = BrowserLivenessState::Hung;
+                status.last_response_ms = None;
+            }
+            LivenessProbe::Failed(e) => {
+                status.last_error = Some(e.clone());
+            }
+        }
+        status.browser.clone()
+    };
+    if probe != LivenessProbe::Hung {
+        return false;
+    }
+
+    warn!(browser = %browser, "Browser did not respond to liveness probe, recycling");
+    let result = recycle().await;
+    let mut status = status.lock().unwrap();
+    match result {
+        Ok(()) => {
+            info!(browser = %browser, "Hung browser recycled");
+            status.state = BrowserLivenessState::Recycled;
+            status.recycle_count += 1;
+            status.last_error = None;
+        }
+        Err(e) => {
+            warn!(browser = %browser, error = %e, "Failed to recycle hung browser");
+            status.last_error = Some(e);
+        }
+    }
+    true
+}
+
+/// 记录独立浏览器的探测结果：正常时返回原实例，卡死时调用 `relaunch` 换成新实例，
+/// 探测出错时返回错误
+pub async fn recycle_standalone_if_hung<T, F, Fut>(
+    status: &Mutex<BrowserLivenessStatus>,
+    probe: LivenessProbe,
+    launched: T,
+    relaunch: F,
+) -> Result<T, String>
+where
+    F: FnOnce(T) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let mut launched = Some(launched);
+    let mut relaunched = None;
+    let (current, slot) = (&mut launched, &mut relaunched);
+    apply_liveness_probe(status, probe.clone(), move || async move {
+        if let Some(hung) = current.take() {
+            *slot = Some(relaunch(hung).await?);
+        }
+        Ok(())
+    })
+    .await;
+
+    match probe {
+        LivenessProbe::Alive(_) => launched.ok_or_else(|| "Browser already recycled".to_string()),
+        LivenessProbe::Failed(e) => Err(e),
+        LivenessProbe::Hung => relaunched.ok_or_else(|| {
+            status
+                .lock()
+                .unwrap()
+                .last_error
+                .clone()
+                .unwrap_or_else(|| "Browser not responding".to_string())
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 模拟页面：hung 为 true 时探测永远不会返回
+    #[derive(Clone)]
+    struct MockPage {
+        id: u32,
+        hung: bool,
+    }
+
+    async fn mock_probe(page: MockPage) -> Result<(), String> {
+        if page.hung {
+            std::future::pending::<()>().await;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hung_page_is_detected_and_browser_recycled() {
+        let pages = vec![MockPage { id: 1, hung: false }, MockPage { id: 2, hung: true }];
+        let (alive, probe) = probe_pages(pages, mock_probe, Duration::from_millis(50)).await;
+        assert_eq!(probe, LivenessProbe::Hung);
+        assert!(alive.is_empty());
+
+        let status = Mutex::new(BrowserLivenessStatus::new("pool"));
+        let recycled = AtomicUsize::new(0);
+        let did_recycle = apply_liveness_probe(&status, probe, || async {
+            recycled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await;
+
+        assert!(did_recycle);
+        assert_eq!(recycled.load(Ordering::SeqCst), 1);
+        let status = status.lock().unwrap().clone();
+        assert_eq!(status.state, BrowserLivenessState::Recycled);
+        assert_eq!(status.recycle_count, 1);
+        assert!(status.last_checked_time.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_hung_standalone_browser_is_detected_and_relaunched() {
+        let hung = MockPage { id: 1, hung: true };
+        let probe = probe_liveness(mock_probe(hung.clone()), Duration::from_millis(50)).await;
+        assert_eq!(probe, LivenessProbe::Hung);
+
+        let status = Mutex::new(BrowserLivenessStatus::new("standalone"));
+        let relaunched = AtomicUsize::new(0);
+        let page = recycle_standalone_if_hung(&status, probe, hung, |old: MockPage| {
+            relaunched.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(MockPage { id: old.id + 1, hung: false }) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(page.id, 2);
+        assert_eq!(relaunched.load(Ordering::SeqCst), 1);
+        let status = status.lock().unwrap().clone();
+        assert_eq!(status.state, BrowserLivenessState::Recycled);
+        assert_eq!(status.recycle_count, 1);
+
+        // 正常响应时保留原实例，探测出错时直接返回错误，都不会重新启动
+        let responsive = MockPage { id: 3, hung: false };
+        let probe = probe_liveness(mock_probe(responsive.clone()), Duration::from_millis(50)).await;
+        let status = Mutex::new(BrowserLivenessStatus::new("standalone"));
+        let page = recycle_standalone_if_hung(&status, probe, responsive, |_: MockPage| {
+            relaunched.fetch_add(1, Ordering::SeqCst);
+            async { Ok(MockPage { id: 0, hung: false }) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(page.id, 3);
+
+        let failed = recycle_standalone_if_hung(
+            &status,
+            LivenessProbe::Failed("connection closed".to_string()),
+            MockPage { id: 4, hung: false },
+            |_: MockPage| async { Ok(MockPage { id: 0, hung: false }) },
+        )
+        .await;
+        assert_eq!(failed.unwrap_err(), "connection closed");
+        assert_eq!(relaunched.load(Ordering::SeqCst), 1);
+        assert_eq!(status.lock().unwrap().recycle_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_responsive_pages_are_kept_without_recycling() {
+        let pages = vec![MockPage { id: 1, hung: false }, MockPage { id: 2, hung: false }];
+        let (alive, probe) = probe_pages(pages, mock_probe, Duration::from_millis(50)).await;
+        assert!(matches!(probe, LivenessProbe::Alive(_)));
+        assert_eq!(alive.iter().map(|page| page.id).collect::<Vec<_>>(), vec![1, 2]);
+
+        let status = Mutex::new(BrowserLivenessStatus::new("pool"));
+        let recycled = AtomicUsize::new(0);
+        let did_recycle = apply_liveness_probe(&status, probe, || async {
+            recycled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await;
+        assert!(!did_recycle);
+        assert_eq!(recycled.load(Ordering::SeqCst), 0);
+        let status = status.lock().unwrap().clone();
+        assert_eq!(status.state, BrowserLivenessState::Alive);
+        assert!(status.last_response_ms.is_some());
+        assert_eq!(status.recycle_count, 0);
+    }
+}
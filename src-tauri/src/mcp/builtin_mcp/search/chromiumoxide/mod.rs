@@ -4,9 +4,11 @@ use tracing::{info, warn};
 
 pub mod browser_pool;
 pub mod fetcher;
+pub mod liveness;
 
-pub use browser_pool::{BrowserPool, BrowserPoolConfig, PooledPage};
-pub use fetcher::{ContentFetcher, FetchConfig};
+pub use browser_pool::{eager_warmup_enabled, BrowserPool, BrowserPoolConfig, PooledPage};
+pub use fetcher::{ContentFetcher, FetchConfig, FetchedContent};
+pub use liveness::{BrowserLivenessState, BrowserLivenessStatus, STANDALONE_LIVENESS};
 
 pub(crate) fn cleanup_profile_locks(user_data_dir: &Path, context: &str) {
     let lock_files = ["SingletonLock", "SingletonSocket", "SingletonCookie"];
@@ -6,6 +6,7 @@ pub enum SearchEngine {
     Google,
     Bing,
     DuckDuckGo,
+    DuckDuckGoHtml,
     Kagi,
 }
 
@@ -15,6 +16,7 @@ impl SearchEngine {
             "google" => Some(SearchEngine::Google),
             "bing" => Some(SearchEngine::Bing),
             "duckduckgo" | "ddg" => Some(SearchEngine::DuckDuckGo),
+            "duckduckgo_html" | "ddg_html" => Some(SearchEngine::DuckDuckGoHtml),
             "kagi" => Some(SearchEngine::Kagi),
             _ => None,
         }
@@ -25,6 +27,7 @@ impl SearchEngine {
             SearchEngine::Google => "google",
             SearchEngine::Bing => "bing",
             SearchEngine::DuckDuckGo => "duckduckgo",
+            SearchEngine::DuckDuckGoHtml => "duckduckgo_html",
             SearchEngine::Kagi => "kagi",
         }
     }
@@ -37,6 +40,9 @@ impl SearchEngine {
             SearchEngine::DuckDuckGo => {
                 super::engines::duckduckgo::DuckDuckGoEngine::default_wait_selectors()
             }
+            SearchEngine::DuckDuckGoHtml => {
+                super::engines::duckduckgo_html::DuckDuckGoHtmlEngine::default_wait_selectors()
+            }
             SearchEngine::Kagi => super::engines::kagi::KagiEngine::default_wait_selectors(),
         }
     }
@@ -49,6 +55,9 @@ impl SearchEngine {
             SearchEngine::DuckDuckGo => {
                 super::engines::duckduckgo::DuckDuckGoEngine::display_name()
             }
+            SearchEngine::DuckDuckGoHtml => {
+                super::engines::duckduckgo_html::DuckDuckGoHtmlEngine::display_name()
+            }
             SearchEngine::Kagi => super::engines::kagi::KagiEngine::display_name(),
         }
     }
@@ -61,6 +70,9 @@ impl SearchEngine {
             SearchEngine::DuckDuckGo => {
                 super::engines::duckduckgo::DuckDuckGoEngine::homepage_url()
             }
+            SearchEngine::DuckDuckGoHtml => {
+                super::engines::duckduckgo_html::DuckDuckGoHtmlEngine::homepage_url()
+            }
             SearchEngine::Kagi => super::engines::kagi::KagiEngine::homepage_url(),
         }
     }
@@ -73,6 +85,9 @@ impl SearchEngine {
             SearchEngine::DuckDuckGo => {
                 super::engines::duckduckgo::DuckDuckGoEngine::search_input_selectors()
             }
+            SearchEngine::DuckDuckGoHtml => {
+                super::engines::duckduckgo_html::DuckDuckGoHtmlEngine::search_input_selectors()
+            }
             SearchEngine::Kagi => super::engines::kagi::KagiEngine::search_input_selectors(),
         }
     }
@@ -85,6 +100,9 @@ impl SearchEngine {
             SearchEngine::DuckDuckGo => {
                 super::engines::duckduckgo::DuckDuckGoEngine::search_button_selectors()
             }
+            SearchEngine::DuckDuckGoHtml => {
+                super::engines::duckduckgo_html::DuckDuckGoHtmlEngine::search_button_selectors()
+            }
             SearchEngine::Kagi => super::engines::kagi::KagiEngine::search_button_selectors(),
         }
     }
@@ -154,6 +172,13 @@ mod tests {
         assert_eq!(SearchEngine::from_str("DuckDuckGo"), Some(SearchEngine::DuckDuckGo));
     }
 
+    #[test]
+    fn test_search_engine_from_str_duckduckgo_html() {
+        assert_eq!(SearchEngine::from_str("duckduckgo_html"), Some(SearchEngine::DuckDuckGoHtml));
+        assert_eq!(SearchEngine::from_str("ddg_html"), Some(SearchEngine::DuckDuckGoHtml));
+        assert_eq!(SearchEngine::DuckDuckGoHtml.as_str(), "duckduckgo_html");
+    }
+
     #[test]
     fn test_search_engine_from_str_kagi() {
         assert_eq!(SearchEngine::from_str("kagi"), Some(SearchEngine::Kagi));
@@ -216,6 +241,12 @@ mod tests {
         assert!(url.starts_with("https://"));
     }
 
+    #[test]
+    fn test_search_engine_homepage_url_duckduckgo_html() {
+        let url = SearchEngine::DuckDuckGoHtml.homepage_url();
+        assert!(url.starts_with("https://html.duckduckgo.com"));
+    }
+
     #[test]
     fn test_search_engine_homepage_url_kagi() {
         let url = SearchEngine::Kagi.homepage_url();
@@ -273,9 +304,13 @@ mod tests {
 
     #[test]
     fn test_search_engine_roundtrip() {
-        for engine in
-            [SearchEngine::Google, SearchEngine::Bing, SearchEngine::DuckDuckGo, SearchEngine::Kagi]
-        {
+        for engine in [
+            SearchEngine::Google,
+            SearchEngine::Bing,
+            SearchEngine::DuckDuckGo,
+            SearchEngine::DuckDuckGoHtml,
+            SearchEngine::Kagi,
+        ] {
             let json = serde_json::to_string(&engine).unwrap();
             let deserialized: SearchEngine = serde_json::from_str(&json).unwrap();
             assert_eq!(deserialized, engine);
@@ -362,9 +397,13 @@ mod tests {
     fn test_get_wait_selectors_for_each_engine() {
         let manager = SearchEngineManager::new(None);
 
-        for engine in
-            [SearchEngine::Google, SearchEngine::Bing, SearchEngine::DuckDuckGo, SearchEngine::Kagi]
-        {
+        for engine in [
+            SearchEngine::Google,
+            SearchEngine::Bing,
+            SearchEngine::DuckDuckGo,
+            SearchEngine::DuckDuckGoHtml,
+            SearchEngine::Kagi,
+        ] {
             let selectors = manager.get_wait_selectors(&engine, None);
             assert!(!selectors.is_empty(), "Engine {:?} should have default selectors", engine);
         }
@@ -6,14 +6,15 @@ pub struct SearchEngineBase;
 impl SearchEngineBase {
     /// 将HTML转换为Markdown格式
     pub fn html_to_markdown(html: &str) -> String {
-        // 基本的HTML到Markdown转换
-        let mut markdown = html.to_string();
-
         // 清理HTML，只保留主要内容相关的部分
-        markdown = Self::extract_main_content(&markdown);
+        let content = Self::extract_main_content(html);
+        Self::fragment_to_markdown(&content)
+    }
 
+    /// 将已提取好的HTML片段直接转换为Markdown，不再做主要内容提取
+    pub fn fragment_to_markdown(html: &str) -> String {
         // HTML标签转换为Markdown语法
-        markdown = Self::convert_html_tags_to_markdown(&markdown);
+        let markdown = Self::convert_html_tags_to_markdown(html);
 
         // 清理多余的空白行
         let lines: Vec<&str> = markdown.lines().collect();
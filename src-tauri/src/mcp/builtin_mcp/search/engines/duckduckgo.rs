@@ -95,6 +95,9 @@ impl DuckDuckGoEngine {
                 snippet: snippet.trim().to_string(),
                 rank,
                 display_url: None,
+                relevance_rank: None,
+                index: 0,
+                id: String::new(),
             })
         } else {
             None
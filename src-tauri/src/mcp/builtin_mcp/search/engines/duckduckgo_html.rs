@@ -0,0 +1,219 @@
+use crate::mcp::builtin_mcp::search::types::{SearchItem, SearchResults};
+use scraper::{Html, Selector};
+
+/// DuckDuckGo HTML 版搜索引擎实现（html.duckduckgo.com，无 JS，反爬较宽松）
+pub struct DuckDuckGoHtmlEngine;
+
+impl DuckDuckGoHtmlEngine {
+    pub fn display_name() -> &'static str {
+        "DuckDuckGo (HTML)"
+    }
+
+    pub fn homepage_url() -> &'static str {
+        "https://html.duckduckgo.com/html/"
+    }
+
+    pub fn search_input_selectors() -> Vec<&'static str> {
+        vec!["input[name='q']", "#search_form_input_homepage", "#search_form_input"]
+    }
+
+    pub fn search_button_selectors() -> Vec<&'static str> {
+        vec!["#search_button_homepage", "input[type='submit']", "button[type='submit']"]
+    }
+
+    pub fn default_wait_selectors() -> Vec<String> {
+        vec![
+            "#links".to_string(),
+            ".results".to_string(),
+            ".result__body".to_string(),
+            ".no-results".to_string(),
+        ]
+    }
+
+    /// 解析 DuckDuckGo HTML 版搜索结果，跳过广告结果
+    pub fn parse_search_results(html: &str, query: &str) -> SearchResults {
+        let mut items = Vec::new();
+        let document = Html::parse_document(html);
+
+        if let Ok(selector) = Selector::parse("div.result") {
+            for card in document.select(&selector) {
+                if card.value().classes().any(|class| class == "result--ad") {
+                    continue;
+                }
+                if let Some(item) = Self::parse_card_element(card, items.len() + 1) {
+                    items.push(item);
+                    if items.len() >= 20 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        SearchResults {
+            query: query.to_string(),
+            search_engine: Self::display_name().to_string(),
+            engine_id: "duckduckgo_html".to_string(),
+            homepage_url: Self::homepage_url().to_string(),
+            items,
+            total_results: None,
+            search_time_ms: None,
+        }
+    }
+
+    /// 从结果卡片元素中抽取一个条目
+    fn parse_card_element(card: scraper::ElementRef<'_>, rank: usize) -> Option<SearchItem> {
+        let link_selector = Selector::parse("a.result__a").ok()?;
+        let link = card.select(&link_selector).next()?;
+        let title = link.text().collect::<String>().trim().to_string();
+        let url = link.value().attr("href").and_then(Self::resolve_result_url)?;
+        if title.is_empty() {
+            return None;
+        }
+
+        let snippet = Self::first_text_in(card, &[".result__snippet"]).unwrap_or_default();
+        let display_url = Self::first_text_in(card, &[".result__url"]);
+
+        Some(SearchItem {
+            title,
+            url,
+            snippet,
+            rank,
+            display_url,
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
+        })
+    }
+
+    /// HTML 版结果链接通常是 `//duckduckgo.com/l/?uddg=<编码后的真实地址>` 跳转链接，
+    /// 这里还原出真实 URL；非跳转链接只接受 http(s) 地址
+    fn resolve_result_url(href: &str) -> Option<String> {
+        if href.contains("duckduckgo.com/l/") {
+            let query = href.split_once('?')?.1;
+            let encoded = query.split('&').find_map(|pair| pair.strip_prefix("uddg="))?;
+            let decoded = urlencoding::decode(encoded).ok()?.into_owned();
+            return decoded.starts_with("http").then_some(decoded);
+        }
+        href.starts_with("http").then(|| href.to_string())
+    }
+
+    /// 在元素内按给定选择器列表找到首个文本
+    fn first_text_in(root: scraper::ElementRef<'_>, selectors: &[&str]) -> Option<String> {
+        for sel in selectors {
+            if let Ok(selector) = Selector::parse(sel) {
+                if let Some(node) = root.select(&selector).next() {
+                    let text = node.text().collect::<String>();
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        return Some(text.to_string());
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_methods() {
+        assert_eq!(DuckDuckGoHtmlEngine::display_name(), "DuckDuckGo (HTML)");
+        assert_eq!(DuckDuckGoHtmlEngine::homepage_url(), "https://html.duckduckgo.com/html/");
+        assert!(DuckDuckGoHtmlEngine::search_input_selectors().contains(&"input[name='q']"));
+        assert!(!DuckDuckGoHtmlEngine::search_button_selectors().is_empty());
+        assert!(DuckDuckGoHtmlEngine::default_wait_selectors().contains(&"#links".to_string()));
+    }
+
+    #[test]
+    fn test_parse_search_results_empty_html() {
+        let results = DuckDuckGoHtmlEngine::parse_search_results("", "test query");
+
+        assert_eq!(results.query, "test query");
+        assert_eq!(results.engine_id, "duckduckgo_html");
+        assert_eq!(results.homepage_url, "https://html.duckduckgo.com/html/");
+        assert!(results.items.is_empty());
+        assert!(results.total_results.is_none());
+    }
+
+    #[test]
+    fn test_parse_search_results_decodes_redirect_and_skips_ads() {
+        let html = r#"
+            <html>
+                <body>
+                    <div id="links" class="results">
+                        <div class="result results_links result--ad">
+                            <div class="result__body">
+                                <h2 class="result__title">
+                                    <a class="result__a" href="https://ads.example.com">Ad</a>
+                                </h2>
+                            </div>
+                        </div>
+                        <div class="result results_links results_links_deep web-result">
+                            <div class="result__body">
+                                <h2 class="result__title">
+                                    <a class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F&amp;rut=abc">Rust Programming Language</a>
+                                </h2>
+                                <a class="result__url" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F">www.rust-lang.org</a>
+                                <a class="result__snippet" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F">A language empowering everyone.</a>
+                            </div>
+                        </div>
+                        <div class="result results_links web-result">
+                            <div class="result__body">
+                                <h2 class="result__title">
+                                    <a class="result__a" href="https://doc.rust-lang.org/book/">The Book</a>
+                                </h2>
+                                <a class="result__snippet">Learn Rust.</a>
+                            </div>
+                        </div>
+                    </div>
+                </body>
+            </html>
+        "#;
+        let results = DuckDuckGoHtmlEngine::parse_search_results(html, "rust");
+
+        assert_eq!(results.items.len(), 2);
+        let first = &results.items[0];
+        assert_eq!(first.title, "Rust Programming Language");
+        assert_eq!(first.url, "https://www.rust-lang.org/");
+        assert_eq!(first.snippet, "A language empowering everyone.");
+        assert_eq!(first.display_url.as_deref(), Some("www.rust-lang.org"));
+        assert_eq!(first.rank, 1);
+
+        let second = &results.items[1];
+        assert_eq!(second.url, "https://doc.rust-lang.org/book/");
+        assert_eq!(second.rank, 2);
+        assert!(second.display_url.is_none());
+    }
+
+    #[test]
+    fn test_parse_search_results_skips_cards_without_valid_link() {
+        let html = r#"
+            <div class="result"><a class="result__a" href="/relative">Relative</a></div>
+            <div class="result"><span>No link</span></div>
+            <div class="no-results">No results.</div>
+        "#;
+        let results = DuckDuckGoHtmlEngine::parse_search_results(html, "nothing");
+
+        assert!(results.items.is_empty());
+    }
+
+    #[test]
+    fn test_parse_search_results_max_20_items() {
+        let mut html = String::from("<div id='links'>");
+        for i in 0..30 {
+            html.push_str(&format!(
+                r#"<div class="result"><a class="result__a" href="https://example{}.com">Result {}</a></div>"#,
+                i, i
+            ));
+        }
+        html.push_str("</div>");
+
+        let results = DuckDuckGoHtmlEngine::parse_search_results(&html, "test");
+
+        assert_eq!(results.items.len(), 20);
+        assert_eq!(results.items[19].rank, 20);
+    }
+}
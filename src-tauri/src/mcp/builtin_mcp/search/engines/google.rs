@@ -174,6 +174,9 @@ impl GoogleEngine {
                 snippet: snippet.trim().to_string(),
                 rank,
                 display_url: display_url.map(|s| s.trim().to_string()),
+                relevance_rank: None,
+                index: 0,
+                id: String::new(),
             })
         } else {
             None
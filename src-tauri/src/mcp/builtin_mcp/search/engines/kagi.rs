@@ -118,6 +118,9 @@ impl KagiEngine {
             snippet: snippet.unwrap_or_default().trim().to_string(),
             rank,
             display_url: display_url.map(|s| s.trim().to_string()),
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
         })
     }
 
@@ -146,6 +149,9 @@ impl KagiEngine {
             snippet: snippet.unwrap_or_default().trim().to_string(),
             rank,
             display_url: None,
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
         })
     }
 
@@ -1,5 +1,6 @@
 pub mod base;
 pub mod bing;
 pub mod duckduckgo;
+pub mod duckduckgo_html;
 pub mod google;
 pub mod kagi;
@@ -1,10 +1,18 @@
 use super::browser::BrowserManager;
 use super::chromiumoxide::{
-    cleanup_profile_locks, BrowserPool, BrowserPoolConfig, ContentFetcher, FetchConfig,
+    cleanup_profile_locks, eager_warmup_enabled, BrowserLivenessState, BrowserLivenessStatus,
+    BrowserPool, BrowserPoolConfig, ContentFetcher, FetchConfig, FetchedContent,
+    STANDALONE_LIVENESS,
 };
 use super::engine_manager::{SearchEngine, SearchEngineManager};
 use super::engines::base::SearchEngineBase;
 use super::fingerprint::FingerprintManager;
+use super::readability::{extract_readable_html, readability_enabled};
+use super::result_cache::{search_cache_ttl, search_result_cache, SearchCacheKey};
+use super::result_filter::{filter_search_items, ResultFilterConfig};
+use super::result_ids::assign_result_ids;
+use super::result_ranker::{rerank_enabled, rerank_search_items};
+use super::safe_search::{filter_unsafe_items, needs_content_filter, SafeSearchLevel};
 use super::types::{SearchRequest, SearchResponse, SearchResultType};
 use anyhow::Result;
 use std::collections::HashMap;
@@ -32,6 +40,38 @@ pub fn cleanup_search_profile_locks(app_handle: &AppHandle) -> Result<(), String
     Ok(())
 }
 
+/// 开启 EAGER_WARMUP 时在后台预先启动一个浏览器，失败只记录警告，不影响应用启动
+pub fn spawn_search_browser_warmup(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let config = match load_search_config_from_db(&app_handle) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!(error = %e, "Failed to load search config for browser warm-up");
+                return;
+            }
+        };
+        if !eager_warmup_enabled(&config) {
+            return;
+        }
+        let started = Instant::now();
+        let result = match SearchHandler::new(app_handle).get_or_create_browser_pool().await {
+            Ok(Some(pool)) => pool.warm_up().await,
+            Ok(None) => {
+                debug!("Browser pool disabled, skipping warm-up");
+                return;
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                info!(elapsed_ms = started.elapsed().as_millis() as u64, "Search browser warmed up")
+            }
+            Err(e) => warn!(error = %e, "Search browser warm-up failed"),
+        }
+    });
+}
+
 pub async fn shutdown_search_browser_pool() -> Result<(), String> {
     if let Some(pool) = GLOBAL_BROWSER_POOL.get() {
         pool.shutdown().await?;
@@ -39,6 +79,19 @@ pub async fn shutdown_search_browser_pool() -> Result<(), String> {
     Ok(())
 }
 
+/// 获取搜索浏览器最近一次存活探测状态：浏览器池创建后包含池浏览器，
+/// 独立浏览器探测过后包含独立浏览器
+#[tauri::command]
+pub async fn get_search_browser_liveness() -> Result<Vec<BrowserLivenessStatus>, String> {
+    let mut statuses: Vec<BrowserLivenessStatus> =
+        GLOBAL_BROWSER_POOL.get().map(|pool| pool.liveness_status()).into_iter().collect();
+    let standalone = STANDALONE_LIVENESS.lock().unwrap().clone();
+    if standalone.state != BrowserLivenessState::Unknown {
+        statuses.push(standalone);
+    }
+    Ok(statuses)
+}
+
 fn resolve_search_user_data_dir(
     app_handle: &AppHandle,
     config: &HashMap<String, String>,
@@ -86,20 +139,12 @@ impl SearchHandler {
         let browser_path = browser_manager.get_browser_path()?;
 
         let user_data_dir = resolve_search_user_data_dir(&self.app_handle, &config)?;
-        let pool_config = BrowserPoolConfig {
-            max_pages: config.get("MAX_CONCURRENT_PAGES").and_then(|v| v.parse().ok()).unwrap_or(5), // 默认最多5个并发页面
-            page_idle_timeout_secs: config
-                .get("PAGE_IDLE_TIMEOUT_SECS")
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60),
-            user_data_dir: Some(user_data_dir.to_string_lossy().to_string()),
+        let pool_config = BrowserPoolConfig::from_search_config(
+            &config,
             browser_path,
-            headless: config
-                .get("HEADLESS")
-                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
-                .unwrap_or(true),
-            launch_args: FingerprintManager::get_stealth_launch_args(),
-        };
+            Some(user_data_dir.to_string_lossy().to_string()),
+            FingerprintManager::get_stealth_launch_args(),
+        );
 
         let pool = GLOBAL_BROWSER_POOL
             .get_or_try_init(
@@ -128,15 +173,31 @@ impl SearchHandler {
 
         info!(engine = search_engine.as_str(), display_name = search_engine.display_name(), ?request.result_type, "Using search engine");
 
-        // 首先获取HTML内容
-        match self
-            .fetch_search_html(&request.query, &search_engine, &browser_manager, &config)
+        // 相同查询在缓存有效期内直接返回已解析的结果，不再启动浏览器搜索；
+        // 安全搜索级别不同的结果不能互相复用
+        let cache_key = SearchCacheKey::new(
+            &request.query,
+            search_engine.as_str(),
+            config.get("PROXY_SERVER").map(|s| s.as_str()),
+            request.result_type.clone(),
+        )
+        .with_safe_search(SafeSearchLevel::from_config(&config))
+        .with_result_config(&config);
+        let cache_ttl = search_cache_ttl(&config);
+        let search = async {
+            // 首先获取HTML内容
+            let html = self
+                .fetch_search_html(&request.query, &search_engine, &browser_manager, &config)
+                .await?;
+            // 根据结果类型处理HTML
+            self.process_html_by_type(html, &request, &search_engine, &config)
+        };
+
+        match search_result_cache()
+            .get_or_search(cache_key, cache_ttl, request.bypass_cache, search)
             .await
         {
-            Ok(html) => {
-                // 根据结果类型处理HTML
-                self.process_html_by_type(html, &request, &search_engine)
-            }
+            Ok(response) => Ok(response),
             Err(e) => {
                 let timeout_like = is_timeout_like(&e);
                 error!(
@@ -209,6 +270,7 @@ impl SearchHandler {
         html: String,
         request: &SearchRequest,
         search_engine: &SearchEngine,
+        config: &HashMap<String, String>,
     ) -> Result<SearchResponse, String> {
         match request.result_type {
             SearchResultType::Html => Ok(SearchResponse::Html {
@@ -254,13 +316,46 @@ impl SearchHandler {
                             &request.query,
                         )
                     }
+                    SearchEngine::DuckDuckGoHtml => {
+                        super::engines::duckduckgo_html::DuckDuckGoHtmlEngine::parse_search_results(
+                            &html,
+                            &request.query,
+                        )
+                    }
                     SearchEngine::Kagi => super::engines::kagi::KagiEngine::parse_search_results(
                         &html,
                         &request.query,
                     ),
                 };
+                // 按配置进行域名去重和黑白名单过滤
+                let filter_config = ResultFilterConfig::from_config(config);
+                let mut filtered = filter_search_items(search_results.items, &filter_config);
+                // 引擎不支持安全搜索参数时按内容过滤
+                let safe_search = SafeSearchLevel::from_config(config);
+                if needs_content_filter(search_engine, safe_search) {
+                    let (items, unsafe_count) = filter_unsafe_items(filtered.items, safe_search);
+                    filtered.items = items;
+                    filtered.filtered_count += unsafe_count;
+                }
+                if filtered.filtered_count > 0 {
+                    info!(
+                        engine = search_engine.as_str(),
+                        filtered_count = filtered.filtered_count,
+                        kept = filtered.items.len(),
+                        "Search result items filtered"
+                    );
+                }
+                // 按配置基于查询词重合度重排，原始排名保留在 rank 中
+                let items = if rerank_enabled(config) {
+                    debug!(engine = search_engine.as_str(), "Re-ranking search items by relevance");
+                    rerank_search_items(&request.query, filtered.items)
+                } else {
+                    filtered.items
+                };
+                // 按最终顺序编号并生成稳定 id，便于模型引用具体结果
+                let items = assign_result_ids(items);
                 // 返回简化格式，仅包含搜索结果项数组
-                Ok(SearchResponse::ItemsOnly(search_results.items))
+                Ok(SearchResponse::ItemsOnly { items, filtered_count: filtered.filtered_count })
             }
         }
     }
@@ -281,16 +376,53 @@ impl SearchHandler {
         let fetch_config = self.build_general_fetch_config(&config)?;
         let mut fetcher = ContentFetcher::new(self.app_handle.clone(), fetch_config);
 
+        // PDF 链接直接下载并提取文本，浏览器渲染的是 PDF 查看器，无法得到正文
+        match fetcher.fetch_pdf_text(url).await {
+            Ok(Some(text)) => {
+                info!(chars = text.len(), "Extracted text from PDF");
+                return Ok(text);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!(
+                    error = %e,
+                    elapsed_ms = start.elapsed().as_millis() as u64,
+                    "Failed to fetch PDF"
+                );
+                return Err(format!("Failed to fetch PDF: {}", e));
+            }
+        }
+
         // 获取浏览器池
         let browser_pool = self.get_or_create_browser_pool().await?;
 
         match fetcher.fetch_content(url, &browser_manager, browser_pool.as_ref()).await {
-            Ok(html) => {
+            // 主文档是 PDF（如 arXiv 的 /pdf/ 链接）时直接返回提取的文本
+            Ok(FetchedContent::PdfText(text)) => {
+                info!(chars = text.len(), "Extracted text from PDF");
+                Ok(text)
+            }
+            Ok(FetchedContent::Html(html)) => {
                 info!("Successfully fetched URL content, bytes = {}", html.len());
 
                 match result_type {
                     "markdown" => {
-                        let markdown_content = SearchEngineBase::html_to_markdown(&html);
+                        // 正文提取失败时回退到整页转换
+                        let readable = readability_enabled(&config)
+                            .then(|| extract_readable_html(&html))
+                            .flatten();
+                        let markdown_content = match readable {
+                            Some(article) => {
+                                let markdown = SearchEngineBase::fragment_to_markdown(&article);
+                                debug!(
+                                    html_bytes = html.len(),
+                                    markdown_bytes = markdown.len(),
+                                    "Converted readable content to markdown"
+                                );
+                                markdown
+                            }
+                            None => SearchEngineBase::html_to_markdown(&html),
+                        };
                         Ok(markdown_content)
                     }
                     "html" | _ => Ok(html),
@@ -343,6 +475,7 @@ impl SearchHandler {
                 .get("KAGI_SESSION_URL")
                 .cloned()
                 .filter(|s| !s.trim().is_empty()),
+            safe_search: SafeSearchLevel::from_config(config),
         })
     }
 
@@ -377,6 +510,7 @@ impl SearchHandler {
                 .unwrap_or(15000),
             wait_poll_ms: config.get("WAIT_POLL_MS").and_then(|v| v.parse().ok()).unwrap_or(250),
             kagi_session_url: None, // 通用抓取不需要 Kagi 会话链接
+            safe_search: SafeSearchLevel::Off,
         })
     }
 }
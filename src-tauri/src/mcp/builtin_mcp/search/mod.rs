@@ -3,6 +3,13 @@ pub mod engine_manager;
 pub mod engines;
 pub mod fingerprint;
 pub mod handler;
+pub mod pdf;
+pub mod readability;
+pub mod result_cache;
+pub mod result_filter;
+pub mod result_ids;
+pub mod result_ranker;
+pub mod safe_search;
 pub mod types;
 
 // chromiumoxide implementation
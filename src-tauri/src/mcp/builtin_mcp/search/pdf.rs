@@ -0,0 +1,117 @@
+//! fetch_url 的 PDF 支持：识别 PDF 链接并提取纯文本，页与页之间以 `---` 分隔
+
+/// 页面之间的分隔符，与 Markdown 分隔线一致
+const PAGE_SEPARATOR: &str = "\n\n---\n\n";
+/// 下载 PDF 的大小上限，超出时放弃提取，避免把超大文件整个读入内存
+pub const MAX_PDF_BYTES: usize = 50 * 1024 * 1024;
+
+/// URL 路径是否以 `.pdf` 结尾（忽略查询参数、锚点与大小写）
+pub fn looks_like_pdf_url(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.to_lowercase().ends_with(".pdf")
+}
+
+/// Content-Type 是否为 PDF（忽略 charset 等参数）
+pub fn is_pdf_content_type(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/pdf"))
+}
+
+/// 内容是否以 PDF 文件头开头
+pub fn is_pdf_bytes(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"%PDF-")
+}
+
+fn pdf_too_large_error(limit: usize) -> String {
+    format!("PDF is larger than the {} MB limit", limit / 1024 / 1024)
+}
+
+/// 把下载的分块追加到缓冲区，累计超过上限时返回错误
+fn append_within_limit(body: &mut Vec<u8>, chunk: &[u8], limit: usize) -> Result<(), String> {
+    if body.len() + chunk.len() > limit {
+        return Err(pdf_too_large_error(limit));
+    }
+    body.extend_from_slice(chunk);
+    Ok(())
+}
+
+/// 分块读取 PDF 响应体，Content-Length 或实际读取的字节数超过 `MAX_PDF_BYTES` 时返回错误
+pub async fn read_pdf_body(mut resp: reqwest::Response) -> Result<Vec<u8>, String> {
+    if resp.content_length().is_some_and(|len| len > MAX_PDF_BYTES as u64) {
+        return Err(pdf_too_large_error(MAX_PDF_BYTES));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) =
+        resp.chunk().await.map_err(|e| format!("Failed to read PDF body: {}", e))?
+    {
+        append_within_limit(&mut body, &chunk, MAX_PDF_BYTES)?;
+    }
+    Ok(body)
+}
+
+/// 按页拼接文本，保留页边界；每页去掉首尾空白
+pub fn join_pdf_pages(pages: &[String]) -> String {
+    pages.iter().map(|page| page.trim()).collect::<Vec<_>>().join(PAGE_SEPARATOR)
+}
+
+/// 从 PDF 字节中提取文本，没有可提取的文本（如扫描件）时返回错误
+pub fn extract_pdf_text(bytes: &[u8]) -> Result<String, String> {
+    let pages = pdf_extract::extract_text_from_mem_by_pages(bytes)
+        .map_err(|e| format!("Failed to extract PDF text: {}", e))?;
+    if pages.iter().all(|page| page.trim().is_empty()) {
+        return Err("PDF contains no extractable text (it may be a scanned document)".to_string());
+    }
+    Ok(join_pdf_pages(&pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_pdf_url() {
+        assert!(looks_like_pdf_url("https://example.com/paper.pdf"));
+        assert!(looks_like_pdf_url("https://example.com/Paper.PDF?download=1#page=2"));
+        assert!(!looks_like_pdf_url("https://arxiv.org/abs/1706.03762"));
+        assert!(!looks_like_pdf_url("https://example.com/?file=paper.pdf"));
+    }
+
+    #[test]
+    fn test_pdf_content_detection() {
+        assert!(is_pdf_content_type("application/pdf"));
+        assert!(is_pdf_content_type("Application/PDF; charset=binary"));
+        assert!(!is_pdf_content_type("text/html; charset=utf-8"));
+        assert!(is_pdf_bytes(b"%PDF-1.7\n..."));
+        assert!(!is_pdf_bytes(b"<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn test_append_within_limit() {
+        let mut body = Vec::new();
+        append_within_limit(&mut body, b"%PDF-", 8).unwrap();
+        append_within_limit(&mut body, b"1.7", 8).unwrap();
+        assert_eq!(body, b"%PDF-1.7");
+        assert!(append_within_limit(&mut body, b"\n", 8).is_err());
+        assert_eq!(body.len(), 8);
+    }
+
+    #[test]
+    fn test_join_pdf_pages_keeps_page_boundaries() {
+        let pages = vec![
+            "  Attention Is All You Need\n".to_string(),
+            String::new(),
+            "References\n".to_string(),
+        ];
+        assert_eq!(
+            join_pdf_pages(&pages),
+            "Attention Is All You Need\n\n---\n\n\n\n---\n\nReferences"
+        );
+    }
+
+    #[test]
+    fn test_extract_pdf_text_rejects_invalid_pdf() {
+        assert!(extract_pdf_text(b"not a pdf").is_err());
+    }
+}
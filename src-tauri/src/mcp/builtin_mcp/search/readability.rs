@@ -0,0 +1,225 @@
+//! fetch_url 的正文提取：参考 Readability（arc90）的打分思路，按段落文本为祖先节点打分，
+//! 选出得分最高的容器作为正文，去掉导航、广告、推荐等与正文无关的内容后再转 Markdown
+
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+/// 参与打分的段落最少字符数
+const MIN_PARAGRAPH_CHARS: usize = 25;
+/// 正文候选至少要有的字符数，不足时认为无法确定正文
+const MIN_ARTICLE_CHARS: usize = 250;
+/// 正文候选的最低得分
+const MIN_CANDIDATE_SCORE: f64 = 20.0;
+
+const POSITIVE_HINTS: &[&str] =
+    &["article", "body", "content", "entry", "main", "page", "post", "story", "text", "blog"];
+const NEGATIVE_HINTS: &[&str] = &[
+    "comment",
+    "footer",
+    "sidebar",
+    "nav",
+    "menu",
+    "related",
+    "share",
+    "social",
+    "promo",
+    "banner",
+    "advert",
+    "sponsor",
+    "widget",
+    "popup",
+    "cookie",
+    "subscribe",
+    "breadcrumb",
+];
+/// 这些标签内的段落不参与打分
+const SKIPPED_ANCESTORS: &[&str] = &["nav", "header", "footer", "aside", "form", "menu"];
+
+/// 是否在 markdown 模式下提取正文（来自内置搜索服务器的 FETCH_READABILITY_MODE 环境变量），默认开启
+pub fn readability_enabled(config: &HashMap<String, String>) -> bool {
+    config
+        .get("FETCH_READABILITY_MODE")
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(true)
+}
+
+fn class_and_id(element: &ElementRef<'_>) -> String {
+    let value = element.value();
+    format!("{} {}", value.attr("class").unwrap_or_default(), value.id().unwrap_or_default())
+        .to_lowercase()
+}
+
+/// 根据 class/id 给出的正文倾向加减分
+fn class_weight(element: &ElementRef<'_>) -> f64 {
+    let hints = class_and_id(element);
+    let mut weight = 0.0;
+    if NEGATIVE_HINTS.iter().any(|hint| hints.contains(hint)) {
+        weight -= 25.0;
+    }
+    if POSITIVE_HINTS.iter().any(|hint| hints.contains(hint)) {
+        weight += 25.0;
+    }
+    weight
+}
+
+/// 节点首次成为候选时的初始分：按标签类型与 class/id 倾向
+fn initial_score(element: &ElementRef<'_>) -> f64 {
+    let tag_score = match element.value().name() {
+        "article" => 10.0,
+        "div" | "section" | "main" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag_score + class_weight(element)
+}
+
+fn text_len(element: &ElementRef<'_>) -> usize {
+    element.text().map(|t| t.trim().chars().count()).sum()
+}
+
+/// 链接文字占全部文字的比例，导航和推荐列表通常接近 1
+fn link_density(element: &ElementRef<'_>, link_selector: &Selector) -> f64 {
+    let total = text_len(element);
+    if total == 0 {
+        return 0.0;
+    }
+    let links: usize = element.select(link_selector).map(|a| text_len(&a)).sum();
+    links as f64 / total as f64
+}
+
+fn in_skipped_ancestor(element: &ElementRef<'_>) -> bool {
+    element.ancestors().filter_map(ElementRef::wrap).any(|ancestor| {
+        SKIPPED_ANCESTORS.contains(&ancestor.value().name()) || class_weight(&ancestor) < 0.0
+    })
+}
+
+/// 提取正文所在节点的 HTML；找不到足够可信的正文时返回 None，由调用方回退到整页转换
+pub fn extract_readable_html(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let paragraph_selector = Selector::parse("p, pre, td, blockquote").ok()?;
+    let link_selector = Selector::parse("a").ok()?;
+
+    // 每个段落为父节点加分，祖父节点加一半
+    let mut scores = HashMap::new();
+    for paragraph in document.select(&paragraph_selector) {
+        if in_skipped_ancestor(&paragraph) {
+            continue;
+        }
+        let text: String = paragraph.text().collect();
+        let chars = text.trim().chars().count();
+        if chars < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let commas = text.matches([',', '，', '、']).count();
+        let content_score = 1.0 + commas as f64 + (chars as f64 / 100.0).min(3.0);
+
+        let ancestors = paragraph.ancestors().filter_map(ElementRef::wrap).take(2);
+        for (level, ancestor) in ancestors.enumerate() {
+            if matches!(ancestor.value().name(), "body" | "html") {
+                break;
+            }
+            let divider = if level == 0 { 1.0 } else { 2.0 };
+            *scores.entry(ancestor.id()).or_insert_with(|| initial_score(&ancestor)) +=
+                content_score / divider;
+        }
+    }
+
+    // 按链接密度折算后取最高分
+    let (top, top_score) = scores
+        .iter()
+        .filter_map(|(id, score)| {
+            let element = ElementRef::wrap(document.tree.get(*id)?)?;
+            Some((element, score * (1.0 - link_density(&element, &link_selector))))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    if top_score < MIN_CANDIDATE_SCORE || text_len(&top) < MIN_ARTICLE_CHARS {
+        return None;
+    }
+
+    // 正文可能被拆成多个相邻容器，得分足够高的兄弟节点一并保留
+    let sibling_threshold = (top_score * 0.2).max(10.0);
+    let mut parts = Vec::new();
+    let siblings = top
+        .parent()
+        .map(|parent| parent.children().filter_map(ElementRef::wrap).collect::<Vec<_>>())
+        .unwrap_or_else(|| vec![top]);
+    for sibling in siblings {
+        let keep = if sibling.id() == top.id() {
+            true
+        } else if let Some(score) = scores.get(&sibling.id()) {
+            score * (1.0 - link_density(&sibling, &link_selector)) >= sibling_threshold
+        } else {
+            sibling.value().name() == "p"
+                && text_len(&sibling) > 80
+                && link_density(&sibling, &link_selector) < 0.25
+        };
+        if keep {
+            parts.push(sibling.html());
+        }
+    }
+
+    Some(format!("<div>{}</div>", parts.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article_html(paragraphs: usize) -> String {
+        let body = (0..paragraphs)
+            .map(|i| {
+                format!(
+                    "<p>Paragraph {} of the story, with enough words, commas, and detail to \
+                     count as real article text for the extractor.</p>",
+                    i
+                )
+            })
+            .collect::<String>();
+        format!(
+            r#"<html><body>
+                <nav><a href="/">Home</a> <a href="/world">World</a> <a href="/tech">Tech</a></nav>
+                <div class="ad-banner advert"><p>Buy now, limited offer, best price, click here today!</p></div>
+                <div class="article-body">{}</div>
+                <div class="related"><p><a href="/a">Related story one with a long headline text</a></p></div>
+                <footer><p>Copyright 2026, Example News, all rights reserved worldwide.</p></footer>
+            </body></html>"#,
+            body
+        )
+    }
+
+    #[test]
+    fn test_readability_enabled_defaults_on() {
+        assert!(readability_enabled(&HashMap::new()));
+        let mut config = HashMap::new();
+        config.insert("FETCH_READABILITY_MODE".to_string(), "false".to_string());
+        assert!(!readability_enabled(&config));
+    }
+
+    #[test]
+    fn test_extracts_article_and_drops_boilerplate() {
+        let extracted = extract_readable_html(&article_html(6)).expect("article should be found");
+
+        assert!(extracted.contains("Paragraph 0 of the story"));
+        assert!(extracted.contains("Paragraph 5 of the story"));
+        assert!(!extracted.contains("Buy now"));
+        assert!(!extracted.contains("Related story"));
+        assert!(!extracted.contains("Copyright"));
+        assert!(!extracted.contains("World"));
+    }
+
+    #[test]
+    fn test_returns_none_when_not_confident() {
+        assert!(extract_readable_html("").is_none());
+        // 正文太短时无法确定，交给整页转换
+        assert!(extract_readable_html(&article_html(1)).is_none());
+        let link_list = (0..20)
+            .map(|i| {
+                format!("<p><a href='/{}'>Link number {} with a fairly long title</a></p>", i, i)
+            })
+            .collect::<String>();
+        assert!(extract_readable_html(&format!("<div>{}</div>", link_list)).is_none());
+    }
+}
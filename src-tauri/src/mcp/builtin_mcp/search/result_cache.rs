@@ -0,0 +1,321 @@
+//! 搜索结果缓存：TTL 内查询与结果相关配置都相同的搜索直接返回已解析的结果
+//!
+//! 与页面抓取不同，这里缓存的是解析后的结构化搜索输出（`SearchResponse`），
+//! 命中时完全跳过浏览器搜索流程，Agent 反复搜索同一内容时收益明显。
+
+use super::result_filter::ResultFilterConfig;
+use super::result_ranker::rerank_enabled;
+use super::safe_search::SafeSearchLevel;
+use super::types::{SearchResponse, SearchResultType};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// 默认缓存有效期（秒）
+pub const DEFAULT_SEARCH_CACHE_TTL_SECS: u64 = 600;
+
+static SEARCH_RESULT_CACHE: OnceLock<SearchResultCache> = OnceLock::new();
+
+/// 进程级搜索结果缓存
+pub fn search_result_cache() -> &'static SearchResultCache {
+    SEARCH_RESULT_CACHE.get_or_init(SearchResultCache::new)
+}
+
+/// 缓存有效期（来自内置搜索服务器的 SEARCH_CACHE_TTL_SECS 环境变量），0 表示关闭缓存
+pub fn search_cache_ttl(config: &HashMap<String, String>) -> Duration {
+    let secs = config
+        .get("SEARCH_CACHE_TTL_SECS")
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SEARCH_CACHE_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// 缓存键：查询词、搜索引擎、出口区域（以代理配置区分）、结果类型，
+/// 以及会影响返回结果的安全搜索、域名过滤与重排配置
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchCacheKey {
+    query: String,
+    engine: String,
+    region: String,
+    result_type: SearchResultType,
+    safe_search: SafeSearchLevel,
+    result_filter: ResultFilterConfig,
+    rerank: bool,
+}
+
+impl SearchCacheKey {
+    pub fn new(
+        query: &str,
+        engine: &str,
+        region: Option<&str>,
+        result_type: SearchResultType,
+    ) -> Self {
+        Self {
+            query: query.trim().to_string(),
+            engine: engine.to_string(),
+            region: region.map(|r| r.trim().to_string()).unwrap_or_default(),
+            result_type,
+            safe_search: SafeSearchLevel::default(),
+            result_filter: ResultFilterConfig::default(),
+            rerank: false,
+        }
+    }
+
+    /// 安全搜索级别不同的结果不能互相复用
+    pub fn with_safe_search(mut self, safe_search: SafeSearchLevel) -> Self {
+        self.safe_search = safe_search;
+        self
+    }
+
+    /// 域名过滤（BLOCKED_DOMAINS、ALLOWED_DOMAINS、DEDUPE_BY_DOMAIN）与重排（RERANK_BY_RELEVANCE）
+    /// 配置修改后，之前缓存的结果不再命中
+    pub fn with_result_config(mut self, config: &HashMap<String, String>) -> Self {
+        self.result_filter = ResultFilterConfig::from_config(config);
+        self.rerank = rerank_enabled(config);
+        self
+    }
+}
+
+struct CacheEntry {
+    stored_at: Instant,
+    response: SearchResponse,
+}
+
+type Clock = Box<dyn Fn() -> Instant + Send + Sync>;
+
+pub struct SearchResultCache {
+    entries: Mutex<HashMap<SearchCacheKey, CacheEntry>>,
+    /// 当前时间来源，测试中可替换以模拟时间流逝
+    clock: Clock,
+}
+
+impl SearchResultCache {
+    pub fn new() -> Self {
+        Self { entries: Mutex::new(HashMap::new()), clock: Box::new(Instant::now) }
+    }
+
+    #[cfg(test)]
+    fn with_clock(clock: impl Fn() -> Instant + Send + Sync + 'static) -> Self {
+        Self { entries: Mutex::new(HashMap::new()), clock: Box::new(clock) }
+    }
+
+    fn is_fresh(&self, entry: &CacheEntry, ttl: Duration) -> bool {
+        (self.clock)().saturating_duration_since(entry.stored_at) < ttl
+    }
+
+    /// 读取未过期的缓存结果，过期条目顺带移除
+    pub fn get(&self, key: &SearchCacheKey, ttl: Duration) -> Option<SearchResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if self.is_fresh(entry, ttl) => Some(entry.response.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// 写入缓存，同时清理已过期的条目
+    pub fn insert(&self, key: SearchCacheKey, response: SearchResponse, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| self.is_fresh(entry, ttl));
+        entries.insert(key, CacheEntry { stored_at: (self.clock)(), response });
+    }
+
+    /// 命中缓存时直接返回（`search` 不会被执行），否则执行 `search` 并缓存成功结果
+    ///
+    /// `bypass` 为 true 时跳过读取缓存但仍用新结果刷新缓存；`ttl` 为 0 时不使用缓存。
+    pub async fn get_or_search(
+        &self,
+        key: SearchCacheKey,
+        ttl: Duration,
+        bypass: bool,
+        search: impl Future<Output = Result<SearchResponse, String>>,
+    ) -> Result<SearchResponse, String> {
+        if ttl.is_zero() {
+            return search.await;
+        }
+        if !bypass {
+            if let Some(response) = self.get(&key, ttl) {
+                debug!(query = %key.query, engine = %key.engine, "Search result cache hit");
+                return Ok(response);
+            }
+        }
+
+        let response = search.await?;
+        self.insert(key, response.clone(), ttl);
+        Ok(response)
+    }
+}
+
+impl Default for SearchResultCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn markdown_response(content: &str) -> SearchResponse {
+        SearchResponse::Markdown {
+            query: "rust async".to_string(),
+            homepage_url: "https://www.google.com".to_string(),
+            search_engine: "Google".to_string(),
+            engine_id: "google".to_string(),
+            markdown_content: content.to_string(),
+            message: "ok".to_string(),
+        }
+    }
+
+    fn markdown_content(response: &SearchResponse) -> &str {
+        match response {
+            SearchResponse::Markdown { markdown_content, .. } => markdown_content,
+            _ => panic!("unexpected response type"),
+        }
+    }
+
+    /// 测试 TTL 内重复搜索命中缓存，不再走浏览器搜索
+    #[tokio::test]
+    async fn test_repeated_search_within_ttl_hits_cache() {
+        let cache = SearchResultCache::new();
+        let browser_runs = AtomicUsize::new(0);
+        let runs = &browser_runs;
+        let ttl = Duration::from_secs(60);
+        let key = || SearchCacheKey::new("rust async", "google", None, SearchResultType::Markdown);
+        let browser_search = move || async move {
+            let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok::<_, String>(markdown_response(&format!("run {}", run)))
+        };
+
+        let first = cache.get_or_search(key(), ttl, false, browser_search()).await.unwrap();
+        let second = cache.get_or_search(key(), ttl, false, browser_search()).await.unwrap();
+
+        assert_eq!(browser_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(markdown_content(&first), "run 1");
+        assert_eq!(markdown_content(&second), "run 1");
+    }
+
+    /// 测试 bypass、不同缓存键与关闭缓存时都会重新搜索
+    #[tokio::test]
+    async fn test_cache_bypass_key_and_disabled() {
+        let cache = SearchResultCache::new();
+        let browser_runs = AtomicUsize::new(0);
+        let runs = &browser_runs;
+        let ttl = Duration::from_secs(60);
+        let browser_search = move || async move {
+            let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok::<_, String>(markdown_response(&format!("run {}", run)))
+        };
+        let key = |region: Option<&str>, result_type: SearchResultType| {
+            SearchCacheKey::new("rust async", "google", region, result_type)
+        };
+
+        cache
+            .get_or_search(key(None, SearchResultType::Markdown), ttl, false, browser_search())
+            .await
+            .unwrap();
+        // bypass 重新搜索并刷新缓存
+        let refreshed = cache
+            .get_or_search(key(None, SearchResultType::Markdown), ttl, true, browser_search())
+            .await
+            .unwrap();
+        assert_eq!(markdown_content(&refreshed), "run 2");
+        let cached = cache.get(&key(None, SearchResultType::Markdown), ttl).unwrap();
+        assert_eq!(markdown_content(&cached), "run 2");
+
+        // 区域、结果类型或安全搜索级别不同视为不同的搜索
+        cache
+            .get_or_search(
+                key(None, SearchResultType::Markdown).with_safe_search(SafeSearchLevel::Strict),
+                ttl,
+                false,
+                browser_search(),
+            )
+            .await
+            .unwrap();
+        cache
+            .get_or_search(
+                key(Some("http://127.0.0.1:7890"), SearchResultType::Markdown),
+                ttl,
+                false,
+                browser_search(),
+            )
+            .await
+            .unwrap();
+        cache
+            .get_or_search(key(None, SearchResultType::Items), ttl, false, browser_search())
+            .await
+            .unwrap();
+        assert_eq!(browser_runs.load(Ordering::SeqCst), 5);
+
+        // TTL 为 0 时不使用缓存
+        cache
+            .get_or_search(
+                key(None, SearchResultType::Markdown),
+                Duration::ZERO,
+                false,
+                browser_search(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(browser_runs.load(Ordering::SeqCst), 6);
+    }
+
+    /// 测试超过 TTL 的条目不再命中，并在读取时被移除
+    #[test]
+    fn test_expired_entry_is_not_returned() {
+        let start = Instant::now();
+        let offset = std::sync::Arc::new(Mutex::new(Duration::ZERO));
+        let now = offset.clone();
+        let cache = SearchResultCache::with_clock(move || start + *now.lock().unwrap());
+        let ttl = Duration::from_secs(60);
+        let key = SearchCacheKey::new("rust async", "bing", None, SearchResultType::Markdown);
+        cache.insert(key.clone(), markdown_response("old"), ttl);
+
+        *offset.lock().unwrap() = Duration::from_secs(59);
+        assert_eq!(markdown_content(&cache.get(&key, ttl).unwrap()), "old");
+
+        *offset.lock().unwrap() = Duration::from_secs(61);
+        assert!(cache.get(&key, ttl).is_none());
+        assert!(cache.entries.lock().unwrap().is_empty());
+    }
+
+    /// 测试域名过滤与重排配置修改后不再命中之前的缓存
+    #[test]
+    fn test_result_config_is_part_of_key() {
+        let cache = SearchResultCache::new();
+        let ttl = Duration::from_secs(60);
+        let key = |config: &HashMap<String, String>| {
+            SearchCacheKey::new("rust async", "google", None, SearchResultType::Items)
+                .with_result_config(config)
+        };
+        cache.insert(key(&HashMap::new()), markdown_response("unfiltered"), ttl);
+        assert!(cache.get(&key(&HashMap::new()), ttl).is_some());
+
+        for (name, value) in [
+            ("BLOCKED_DOMAINS", "spam.com"),
+            ("ALLOWED_DOMAINS", "docs.rs"),
+            ("DEDUPE_BY_DOMAIN", "true"),
+            ("RERANK_BY_RELEVANCE", "true"),
+        ] {
+            let config = HashMap::from([(name.to_string(), value.to_string())]);
+            assert!(cache.get(&key(&config), ttl).is_none(), "{} should change the key", name);
+        }
+    }
+
+    #[test]
+    fn test_search_cache_ttl_from_config() {
+        let mut config = HashMap::new();
+        assert_eq!(search_cache_ttl(&config), Duration::from_secs(DEFAULT_SEARCH_CACHE_TTL_SECS));
+        config.insert("SEARCH_CACHE_TTL_SECS".to_string(), "0".to_string());
+        assert!(search_cache_ttl(&config).is_zero());
+        config.insert("SEARCH_CACHE_TTL_SECS".to_string(), "120".to_string());
+        assert_eq!(search_cache_ttl(&config), Duration::from_secs(120));
+    }
+}
@@ -0,0 +1,222 @@
+use super::types::SearchItem;
+use std::collections::{HashMap, HashSet};
+
+/// 常见的二级公共后缀，用于计算可注册域名（如 example.co.uk）
+const SECOND_LEVEL_SUFFIXES: &[&str] = &[
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "com.cn", "net.cn", "org.cn", "gov.cn", "edu.cn",
+    "com.hk", "com.tw", "co.jp", "ne.jp", "or.jp", "co.kr", "com.au", "net.au", "org.au", "com.br",
+    "com.sg", "co.in", "co.nz",
+];
+
+/// 搜索结果后处理配置（来自内置搜索服务器的环境变量）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ResultFilterConfig {
+    /// 是否按可注册域名去重（保留排名最靠前的一条）
+    pub dedupe_by_domain: bool,
+    /// 屏蔽的域名列表（包含子域名）
+    pub blocked_domains: Vec<String>,
+    /// 仅允许的域名列表，非空时启用白名单模式
+    pub allowed_domains: Vec<String>,
+}
+
+impl ResultFilterConfig {
+    pub fn from_config(config: &HashMap<String, String>) -> Self {
+        Self {
+            dedupe_by_domain: config
+                .get("DEDUPE_BY_DOMAIN")
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false),
+            blocked_domains: parse_domain_list(config.get("BLOCKED_DOMAINS")),
+            allowed_domains: parse_domain_list(config.get("ALLOWED_DOMAINS")),
+        }
+    }
+
+    pub fn is_noop(&self) -> bool {
+        !self.dedupe_by_domain && self.blocked_domains.is_empty() && self.allowed_domains.is_empty()
+    }
+}
+
+/// 过滤后的搜索结果
+#[derive(Debug, Clone)]
+pub struct FilteredItems {
+    pub items: Vec<SearchItem>,
+    /// 被过滤掉的结果数量
+    pub filtered_count: usize,
+}
+
+fn parse_domain_list(value: Option<&String>) -> Vec<String> {
+    value
+        .map(|v| {
+            v.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .map(|s| s.trim().trim_start_matches("*.").trim_start_matches('.').to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 从 URL 中提取主机名（小写，去掉端口和 www. 前缀）
+pub fn extract_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let authority = rest.split(|c| c == '/' || c == '?' || c == '#').next()?;
+    let host_port = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let host = host_port.split(':').next()?.trim().trim_end_matches('.').to_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    Some(host.trim_start_matches("www.").to_string())
+}
+
+/// 计算可注册域名，例如 `news.example.co.uk` -> `example.co.uk`
+pub fn registrable_domain(host: &str) -> String {
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    if labels.len() <= 2 {
+        return labels.join(".");
+    }
+    let last_two = labels[labels.len() - 2..].join(".");
+    let keep = if SECOND_LEVEL_SUFFIXES.contains(&last_two.as_str()) { 3 } else { 2 };
+    labels[labels.len().saturating_sub(keep)..].join(".")
+}
+
+fn host_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// 按配置过滤搜索结果：白名单 -> 黑名单 -> 域名去重，保持原有排名顺序
+pub fn filter_search_items(items: Vec<SearchItem>, config: &ResultFilterConfig) -> FilteredItems {
+    if config.is_noop() {
+        return FilteredItems { items, filtered_count: 0 };
+    }
+
+    let total = items.len();
+    let mut seen_domains = HashSet::new();
+    let kept: Vec<SearchItem> = items
+        .into_iter()
+        .filter(|item| {
+            let Some(host) = extract_host(&item.url) else {
+                // 无法解析主机名的结果在白名单模式下丢弃，其余情况保留
+                return config.allowed_domains.is_empty();
+            };
+            if !config.allowed_domains.is_empty()
+                && !config.allowed_domains.iter().any(|d| host_matches(&host, d))
+            {
+                return false;
+            }
+            if config.blocked_domains.iter().any(|d| host_matches(&host, d)) {
+                return false;
+            }
+            if config.dedupe_by_domain {
+                return seen_domains.insert(registrable_domain(&host));
+            }
+            true
+        })
+        .collect();
+
+    FilteredItems { filtered_count: total - kept.len(), items: kept }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(rank: usize, url: &str) -> SearchItem {
+        SearchItem {
+            title: format!("Result {}", rank),
+            url: url.to_string(),
+            snippet: String::new(),
+            rank,
+            display_url: None,
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
+        }
+    }
+
+    fn fixture() -> Vec<SearchItem> {
+        vec![
+            item(1, "https://www.rust-lang.org/learn"),
+            item(2, "https://spam-seo.com/rust"),
+            item(3, "https://doc.rust-lang.org/book/"),
+            item(4, "https://github.com/rust-lang/rust"),
+            item(5, "https://news.spam-seo.com/page"),
+            item(6, "https://blog.example.co.uk/rust"),
+            item(7, "https://shop.example.co.uk/rust"),
+        ]
+    }
+
+    fn urls(items: &[SearchItem]) -> Vec<&str> {
+        items.iter().map(|i| i.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_extract_host() {
+        assert_eq!(extract_host("https://www.Example.com:8080/a?b#c"), Some("example.com".into()));
+        assert_eq!(extract_host("http://user@sub.example.com"), Some("sub.example.com".into()));
+        assert_eq!(extract_host("example.org/path"), Some("example.org".into()));
+        assert_eq!(extract_host("https:///path"), None);
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("doc.rust-lang.org"), "rust-lang.org");
+        assert_eq!(registrable_domain("rust-lang.org"), "rust-lang.org");
+        assert_eq!(registrable_domain("blog.example.co.uk"), "example.co.uk");
+    }
+
+    #[test]
+    fn test_from_config_parses_lists() {
+        let mut config = HashMap::new();
+        config.insert("DEDUPE_BY_DOMAIN".to_string(), "true".to_string());
+        config.insert("BLOCKED_DOMAINS".to_string(), "Spam-SEO.com, *.bad.net".to_string());
+        let filter = ResultFilterConfig::from_config(&config);
+
+        assert!(filter.dedupe_by_domain);
+        assert_eq!(filter.blocked_domains, vec!["spam-seo.com", "bad.net"]);
+        assert!(filter.allowed_domains.is_empty());
+    }
+
+    #[test]
+    fn test_filter_noop_keeps_everything() {
+        let result = filter_search_items(fixture(), &ResultFilterConfig::default());
+        assert_eq!(result.items.len(), 7);
+        assert_eq!(result.filtered_count, 0);
+    }
+
+    #[test]
+    fn test_filter_dedupe_and_blocklist() {
+        let config = ResultFilterConfig {
+            dedupe_by_domain: true,
+            blocked_domains: vec!["spam-seo.com".to_string()],
+            allowed_domains: vec![],
+        };
+        let result = filter_search_items(fixture(), &config);
+
+        assert_eq!(
+            urls(&result.items),
+            vec![
+                "https://www.rust-lang.org/learn",
+                "https://github.com/rust-lang/rust",
+                "https://blog.example.co.uk/rust",
+            ]
+        );
+        let ranks: Vec<usize> = result.items.iter().map(|i| i.rank).collect();
+        assert_eq!(ranks, vec![1, 4, 6]);
+        assert_eq!(result.filtered_count, 4);
+    }
+
+    #[test]
+    fn test_filter_allowlist_only() {
+        let config = ResultFilterConfig {
+            dedupe_by_domain: false,
+            blocked_domains: vec![],
+            allowed_domains: vec!["rust-lang.org".to_string()],
+        };
+        let result = filter_search_items(fixture(), &config);
+
+        assert_eq!(
+            urls(&result.items),
+            vec!["https://www.rust-lang.org/learn", "https://doc.rust-lang.org/book/"]
+        );
+        assert_eq!(result.filtered_count, 5);
+    }
+}
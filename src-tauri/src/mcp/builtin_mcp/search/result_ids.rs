@@ -0,0 +1,94 @@
+use super::types::SearchItem;
+use sha2::{Digest, Sha256};
+
+/// 内容哈希 id 的长度（十六进制字符数）
+const RESULT_ID_LEN: usize = 12;
+
+/// 计算结果的内容哈希 id：忽略首尾空白与链接末尾的 `/`，同一链接与标题总是得到同一个 id
+pub fn result_content_id(item: &SearchItem) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(item.url.trim().trim_end_matches('/').as_bytes());
+    hasher.update(b"\n");
+    hasher.update(item.title.trim().as_bytes());
+    let digest = format!("{:x}", hasher.finalize());
+    digest[..RESULT_ID_LEN].to_string()
+}
+
+/// 按最终展示顺序（重排后的排名优先，其次原始排名，再以 id 兜底）排序，
+/// 并写入从 1 开始的序号与内容哈希 id，保证同一组结果每次输出的顺序与 id 一致
+pub fn assign_result_ids(items: Vec<SearchItem>) -> Vec<SearchItem> {
+    let mut items: Vec<SearchItem> = items
+        .into_iter()
+        .map(|mut item| {
+            item.id = result_content_id(&item);
+            item
+        })
+        .collect();
+    items.sort_by(|a, b| {
+        a.relevance_rank
+            .unwrap_or(a.rank)
+            .cmp(&b.relevance_rank.unwrap_or(b.rank))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    for (index, item) in items.iter_mut().enumerate() {
+        item.index = index + 1;
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(rank: usize, url: &str) -> SearchItem {
+        SearchItem {
+            title: format!("Result {}", rank),
+            url: url.to_string(),
+            snippet: String::new(),
+            rank,
+            display_url: None,
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_assign_result_ids_sequential_indices_and_stable_ids() {
+        let run = || {
+            assign_result_ids(vec![
+                item(3, "https://c.example.com"),
+                item(1, "https://a.example.com/"),
+                item(2, "https://b.example.com"),
+            ])
+        };
+        let first = run();
+        let second = run();
+
+        let indices: Vec<usize> = first.iter().map(|i| i.index).collect();
+        assert_eq!(indices, vec![1, 2, 3]);
+        let ranks: Vec<usize> = first.iter().map(|i| i.rank).collect();
+        assert_eq!(ranks, vec![1, 2, 3]);
+
+        let ids: Vec<&str> = first.iter().map(|i| i.id.as_str()).collect();
+        let ids_again: Vec<&str> = second.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ids_again);
+        assert!(ids.iter().all(|id| id.len() == RESULT_ID_LEN));
+        assert_ne!(ids[0], ids[1]);
+
+        // 末尾斜杠不影响 id
+        assert_eq!(first[0].id, result_content_id(&item(1, "https://a.example.com")));
+    }
+
+    #[test]
+    fn test_assign_result_ids_follows_relevance_rank() {
+        let mut a = item(1, "https://a.example.com");
+        a.relevance_rank = Some(2);
+        let mut b = item(2, "https://b.example.com");
+        b.relevance_rank = Some(1);
+
+        let items = assign_result_ids(vec![a, b]);
+        assert_eq!(items[0].url, "https://b.example.com");
+        assert_eq!((items[0].index, items[1].index), (1, 2));
+    }
+}
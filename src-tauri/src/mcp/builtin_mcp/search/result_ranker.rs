@@ -0,0 +1,135 @@
+use super::types::SearchItem;
+use std::collections::{HashMap, HashSet};
+
+/// 标题命中一个查询词的得分
+const TITLE_TERM_WEIGHT: f64 = 2.0;
+/// 摘要命中一个查询词的得分
+const SNIPPET_TERM_WEIGHT: f64 = 1.0;
+
+/// 是否启用按查询相关度重排（来自内置搜索服务器的 RERANK_BY_RELEVANCE 环境变量）
+pub fn rerank_enabled(config: &HashMap<String, String>) -> bool {
+    config
+        .get("RERANK_BY_RELEVANCE")
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+/// 切分词项：字母数字连续段为一个词，中日韩字符逐字成词，统一小写
+fn tokenize(text: &str) -> HashSet<String> {
+    let mut terms = HashSet::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !current.is_empty() {
+                terms.insert(std::mem::take(&mut current));
+            }
+            terms.insert(c.to_string());
+        } else if c.is_alphanumeric() {
+            current.extend(c.to_lowercase());
+        } else if !current.is_empty() {
+            terms.insert(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        terms.insert(current);
+    }
+    terms
+}
+
+/// 计算结果与查询的词项重合得分：标题命中权重高于摘要
+pub fn relevance_score(query_terms: &HashSet<String>, item: &SearchItem) -> f64 {
+    let title_terms = tokenize(&item.title);
+    let snippet_terms = tokenize(&item.snippet);
+    query_terms
+        .iter()
+        .map(|term| {
+            let mut score = 0.0;
+            if title_terms.contains(term) {
+                score += TITLE_TERM_WEIGHT;
+            }
+            if snippet_terms.contains(term) {
+                score += SNIPPET_TERM_WEIGHT;
+            }
+            score
+        })
+        .sum()
+}
+
+/// 按查询相关度重排搜索结果，得分相同时保持原始排名顺序
+///
+/// `rank` 保留搜索引擎的原始排名，重排后的位置写入 `relevance_rank`。
+pub fn rerank_search_items(query: &str, items: Vec<SearchItem>) -> Vec<SearchItem> {
+    let query_terms = tokenize(query);
+    let mut scored: Vec<(f64, SearchItem)> =
+        items.into_iter().map(|item| (relevance_score(&query_terms, &item), item)).collect();
+    scored.sort_by(|(score_a, a), (score_b, b)| {
+        score_b.total_cmp(score_a).then_with(|| a.rank.cmp(&b.rank))
+    });
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, mut item))| {
+            item.relevance_rank = Some(index + 1);
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(rank: usize, title: &str, snippet: &str) -> SearchItem {
+        SearchItem {
+            title: title.to_string(),
+            url: format!("https://example.com/{}", rank),
+            snippet: snippet.to_string(),
+            rank,
+            display_url: None,
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_rerank_orders_by_relevance_with_rank_tiebreak() {
+        let items = vec![
+            item(1, "Cooking recipes", "Delicious pasta dishes"),
+            item(2, "Rust async book", "Learn async programming in Rust"),
+            item(3, "Rust homepage", "A language empowering everyone"),
+            item(4, "Async patterns", "Futures and executors explained"),
+            item(5, "Gardening tips", "Grow tomatoes at home"),
+        ];
+
+        let reranked = rerank_search_items("Rust async", items);
+
+        // 得分：#2 = 2+2+1+1 = 6，#3 = 2，#4 = 2，#1/#5 = 0
+        let original_ranks: Vec<usize> = reranked.iter().map(|i| i.rank).collect();
+        assert_eq!(original_ranks, vec![2, 3, 4, 1, 5]);
+        let relevance_ranks: Vec<Option<usize>> =
+            reranked.iter().map(|i| i.relevance_rank).collect();
+        assert_eq!(relevance_ranks, vec![Some(1), Some(2), Some(3), Some(4), Some(5)]);
+    }
+
+    #[test]
+    fn test_relevance_score_matches_whole_terms_and_cjk() {
+        let query_terms = tokenize("Rust 教程");
+        assert_eq!(relevance_score(&query_terms, &item(1, "Rust 入门教程", "")), 6.0);
+        // "rusty" 不应命中 "rust"
+        assert_eq!(relevance_score(&query_terms, &item(2, "Rusty nails", "")), 0.0);
+    }
+
+    #[test]
+    fn test_rerank_enabled() {
+        let mut config = HashMap::new();
+        assert!(!rerank_enabled(&config));
+        config.insert("RERANK_BY_RELEVANCE".to_string(), "true".to_string());
+        assert!(rerank_enabled(&config));
+    }
+}
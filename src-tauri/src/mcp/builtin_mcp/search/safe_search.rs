@@ -0,0 +1,228 @@
+use super::engine_manager::SearchEngine;
+use super::types::SearchItem;
+use std::collections::HashMap;
+
+/// 不支持安全搜索参数的搜索引擎，按标题、链接和摘要中的关键词过滤结果
+const EXPLICIT_KEYWORDS: &[&str] = &[
+    "porn",
+    "xxx",
+    "nsfw",
+    "hentai",
+    "nude",
+    "naked",
+    "sex video",
+    "sex cam",
+    "escort",
+    "onlyfans",
+    "adult video",
+    "erotic",
+    "色情",
+    "成人视频",
+    "裸体",
+    "黄色网站",
+    "av在线",
+    "无码",
+];
+
+/// 严格模式下额外过滤的关键词
+const STRICT_KEYWORDS: &[&str] =
+    &["sexy", "lingerie", "bikini", "gore", "casino", "gambling", "博彩", "赌场", "性感", "血腥"];
+
+/// 安全搜索级别（内置搜索服务器配置 `SAFE_SEARCH`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SafeSearchLevel {
+    #[default]
+    Off,
+    Moderate,
+    Strict,
+}
+
+impl SafeSearchLevel {
+    pub fn from_config(config: &HashMap<String, String>) -> Self {
+        match config.get("SAFE_SEARCH").map(|v| v.trim().to_lowercase()).as_deref() {
+            Some("moderate") => SafeSearchLevel::Moderate,
+            Some("strict") => SafeSearchLevel::Strict,
+            _ => SafeSearchLevel::Off,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SafeSearchLevel::Off => "off",
+            SafeSearchLevel::Moderate => "moderate",
+            SafeSearchLevel::Strict => "strict",
+        }
+    }
+}
+
+/// 搜索引擎对应的安全搜索查询参数，引擎不支持或未开启时返回 None
+///
+/// Google 只区分开关，中等与严格都使用 `safe=active`
+pub fn safe_search_param(
+    engine: &SearchEngine,
+    level: SafeSearchLevel,
+) -> Option<(&'static str, &'static str)> {
+    match (engine, level) {
+        (_, SafeSearchLevel::Off) => None,
+        (SearchEngine::Google, _) => Some(("safe", "active")),
+        (SearchEngine::Bing, SafeSearchLevel::Moderate) => Some(("adlt", "moderate")),
+        (SearchEngine::Bing, SafeSearchLevel::Strict) => Some(("adlt", "strict")),
+        (SearchEngine::DuckDuckGo | SearchEngine::DuckDuckGoHtml, SafeSearchLevel::Moderate) => {
+            Some(("kp", "-1"))
+        }
+        (SearchEngine::DuckDuckGo | SearchEngine::DuckDuckGoHtml, SafeSearchLevel::Strict) => {
+            Some(("kp", "1"))
+        }
+        (SearchEngine::Kagi, _) => None,
+    }
+}
+
+/// 在 URL 后追加安全搜索参数，引擎不支持时原样返回
+pub fn apply_safe_search_to_url(
+    url: &str,
+    engine: &SearchEngine,
+    level: SafeSearchLevel,
+) -> String {
+    match safe_search_param(engine, level) {
+        Some((key, value)) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("{}{}{}={}", url, separator, key, value)
+        }
+        None => url.to_string(),
+    }
+}
+
+/// 开启安全搜索时直接访问的结果页 URL（带查询词与安全搜索参数），引擎不支持时返回 None
+pub fn safe_search_url(
+    engine: &SearchEngine,
+    query: &str,
+    level: SafeSearchLevel,
+) -> Option<String> {
+    safe_search_param(engine, level)?;
+    let base = match engine {
+        SearchEngine::Google => "https://www.google.com/search",
+        SearchEngine::Bing => "https://www.bing.com/search",
+        SearchEngine::DuckDuckGo => "https://duckduckgo.com/",
+        SearchEngine::DuckDuckGoHtml => "https://html.duckduckgo.com/html/",
+        SearchEngine::Kagi => return None,
+    };
+    let url = format!("{}?q={}", base, urlencoding::encode(query));
+    Some(apply_safe_search_to_url(&url, engine, level))
+}
+
+/// 引擎无法通过参数开启安全搜索时，需要在结果中按内容过滤
+pub fn needs_content_filter(engine: &SearchEngine, level: SafeSearchLevel) -> bool {
+    level != SafeSearchLevel::Off && safe_search_param(engine, level).is_none()
+}
+
+/// 粗略判断结果是否包含不适宜内容
+pub fn is_unsafe_item(item: &SearchItem, level: SafeSearchLevel) -> bool {
+    if level == SafeSearchLevel::Off {
+        return false;
+    }
+    let text = format!("{} {} {}", item.title, item.url, item.snippet).to_lowercase();
+    let matches = |keywords: &[&str]| keywords.iter().any(|k| text.contains(k));
+    matches(EXPLICIT_KEYWORDS) || (level == SafeSearchLevel::Strict && matches(STRICT_KEYWORDS))
+}
+
+/// 按内容过滤不适宜的结果，返回保留的结果与过滤数量
+pub fn filter_unsafe_items(
+    items: Vec<SearchItem>,
+    level: SafeSearchLevel,
+) -> (Vec<SearchItem>, usize) {
+    let before = items.len();
+    let kept: Vec<SearchItem> =
+        items.into_iter().filter(|item| !is_unsafe_item(item, level)).collect();
+    let filtered = before - kept.len();
+    (kept, filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(title: &str, url: &str) -> SearchItem {
+        SearchItem {
+            title: title.to_string(),
+            url: url.to_string(),
+            snippet: String::new(),
+            rank: 1,
+            display_url: None,
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_safe_search_param_added_to_query_url_per_engine() {
+        let strict = SafeSearchLevel::Strict;
+        assert_eq!(
+            safe_search_url(&SearchEngine::Google, "rust lang", strict).unwrap(),
+            "https://www.google.com/search?q=rust%20lang&safe=active"
+        );
+        assert_eq!(
+            safe_search_url(&SearchEngine::Bing, "rust", strict).unwrap(),
+            "https://www.bing.com/search?q=rust&adlt=strict"
+        );
+        assert_eq!(
+            safe_search_url(&SearchEngine::Bing, "rust", SafeSearchLevel::Moderate).unwrap(),
+            "https://www.bing.com/search?q=rust&adlt=moderate"
+        );
+        assert_eq!(
+            safe_search_url(&SearchEngine::DuckDuckGo, "rust", strict).unwrap(),
+            "https://duckduckgo.com/?q=rust&kp=1"
+        );
+        assert_eq!(
+            safe_search_url(&SearchEngine::DuckDuckGo, "rust", SafeSearchLevel::Moderate).unwrap(),
+            "https://duckduckgo.com/?q=rust&kp=-1"
+        );
+        assert_eq!(
+            safe_search_url(&SearchEngine::DuckDuckGoHtml, "rust", strict).unwrap(),
+            "https://html.duckduckgo.com/html/?q=rust&kp=1"
+        );
+
+        // Kagi 不支持参数，改为按内容过滤；未开启时保持原有的首页输入流程
+        assert!(safe_search_url(&SearchEngine::Kagi, "rust", strict).is_none());
+        assert!(needs_content_filter(&SearchEngine::Kagi, strict));
+        assert!(!needs_content_filter(&SearchEngine::Google, strict));
+        assert!(safe_search_url(&SearchEngine::Google, "rust", SafeSearchLevel::Off).is_none());
+        assert_eq!(
+            apply_safe_search_to_url(
+                "https://kagi.com/search?token=abc&q=rust",
+                &SearchEngine::Kagi,
+                strict
+            ),
+            "https://kagi.com/search?token=abc&q=rust"
+        );
+    }
+
+    #[test]
+    fn test_safe_search_level_from_config() {
+        let config = |v: &str| HashMap::from([("SAFE_SEARCH".to_string(), v.to_string())]);
+        assert_eq!(SafeSearchLevel::from_config(&config("Strict")), SafeSearchLevel::Strict);
+        assert_eq!(SafeSearchLevel::from_config(&config("moderate")), SafeSearchLevel::Moderate);
+        assert_eq!(SafeSearchLevel::from_config(&config("unknown")), SafeSearchLevel::Off);
+        assert_eq!(SafeSearchLevel::from_config(&HashMap::new()), SafeSearchLevel::Off);
+    }
+
+    #[test]
+    fn test_filter_unsafe_items_by_level() {
+        let items = vec![
+            item("Rust Programming Language", "https://www.rust-lang.org"),
+            item("Free XXX videos", "https://example-adult.com"),
+            item("Bikini season styles", "https://fashion.example.com"),
+        ];
+
+        let (kept, filtered) = filter_unsafe_items(items.clone(), SafeSearchLevel::Moderate);
+        assert_eq!(filtered, 1);
+        assert_eq!(kept.len(), 2);
+
+        let (kept, filtered) = filter_unsafe_items(items.clone(), SafeSearchLevel::Strict);
+        assert_eq!(filtered, 2);
+        assert_eq!(kept[0].url, "https://www.rust-lang.org");
+
+        let (kept, _) = filter_unsafe_items(items, SafeSearchLevel::Off);
+        assert_eq!(kept.len(), 3);
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// 搜索结果类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum SearchResultType {
     /// 返回原始HTML内容
@@ -37,6 +37,9 @@ pub struct SearchRequest {
     /// 期望的结果类型（默认 Markdown）
     #[serde(default)]
     pub result_type: SearchResultType,
+    /// 跳过搜索结果缓存，强制重新搜索（新结果仍会写入缓存）
+    #[serde(default)]
+    pub bypass_cache: bool,
 }
 
 /// 单个搜索结果项
@@ -52,6 +55,15 @@ pub struct SearchItem {
     pub rank: usize,
     /// 显示的URL（如果与实际URL不同）
     pub display_url: Option<String>,
+    /// 按查询相关度重排后的排名（从1开始），未启用重排时为空，原始排名仍保留在 `rank`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relevance_rank: Option<usize>,
+    /// 返回给模型的最终序号（从1开始），模型可据此引用“第 N 条结果”
+    #[serde(default)]
+    pub index: usize,
+    /// 基于链接与标题的内容哈希，同一结果在多次搜索中保持不变
+    #[serde(default)]
+    pub id: String,
 }
 
 /// 结构化搜索结果
@@ -97,8 +109,8 @@ pub enum SearchResponse {
     },
     /// 结构化结果响应（完整对象）
     Items(SearchResults),
-    /// 简化的搜索结果响应（仅包含结果项数组）
-    ItemsOnly(Vec<SearchItem>),
+    /// 简化的搜索结果响应（仅包含结果项数组及被过滤的数量）
+    ItemsOnly { items: Vec<SearchItem>, filtered_count: usize },
 }
 
 #[cfg(test)]
@@ -160,6 +172,7 @@ mod tests {
         let request = SearchRequest {
             query: "test query".to_string(),
             result_type: SearchResultType::Markdown,
+            bypass_cache: false,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -197,6 +210,9 @@ mod tests {
             snippet: "Test snippet".to_string(),
             rank: 1,
             display_url: Some("example.com".to_string()),
+            relevance_rank: None,
+            index: 0,
+            id: String::new(),
         };
 
         let json = serde_json::to_string(&item).unwrap();
@@ -239,6 +255,9 @@ mod tests {
                 snippet: "Official Rust website".to_string(),
                 rank: 1,
                 display_url: None,
+                relevance_rank: None,
+                index: 0,
+                id: String::new(),
             }],
             total_results: Some(1000000),
             search_time_ms: Some(250),
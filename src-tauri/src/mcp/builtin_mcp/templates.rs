@@ -83,6 +83,10 @@ fn builtin_templates() -> Vec<BuiltinTemplateInfo> {
                     EnvVarOption { label: "Google".into(), value: "google".into() },
                     EnvVarOption { label: "Bing".into(), value: "bing".into() },
                     EnvVarOption { label: "DuckDuckGo".into(), value: "duckduckgo".into() },
+                    EnvVarOption {
+                        label: "DuckDuckGo (HTML)".into(),
+                        value: "duckduckgo_html".into(),
+                    },
                     EnvVarOption { label: "Kagi".into(), value: "kagi".into() },
                 ]),
             },
@@ -146,8 +150,163 @@ fn builtin_templates() -> Vec<BuiltinTemplateInfo> {
                 placeholder: Some("15000".into()),
                 options: None,
             },
+            BuiltinTemplateEnvVar {
+                key: "DEDUPE_BY_DOMAIN".into(),
+                label: "按域名去重".into(),
+                required: false,
+                tip: Some("启用后结构化搜索结果（items）中同一域名只保留排名最靠前的一条".into()),
+                field_type: "boolean".into(),
+                default_value: Some("false".into()),
+                placeholder: None,
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "BLOCKED_DOMAINS".into(),
+                label: "屏蔽域名".into(),
+                required: false,
+                tip: Some("从结构化搜索结果中过滤掉的域名（包含子域名），多个域名用逗号分隔".into()),
+                field_type: "text".into(),
+                default_value: None,
+                placeholder: Some("spam-site.com, example.net".into()),
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "ALLOWED_DOMAINS".into(),
+                label: "仅允许域名".into(),
+                required: false,
+                tip: Some("填写后结构化搜索结果只保留这些域名（包含子域名）的结果，多个域名用逗号分隔".into()),
+                field_type: "text".into(),
+                default_value: None,
+                placeholder: Some("rust-lang.org, github.com".into()),
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "RERANK_BY_RELEVANCE".into(),
+                label: "按相关度重排".into(),
+                required: false,
+                tip: Some("启用后结构化搜索结果（items）按标题和摘要与查询词的重合度重新排序，得分相同时保持原始排名，原始排名保留在 rank 字段中".into()),
+                field_type: "boolean".into(),
+                default_value: Some("false".into()),
+                placeholder: None,
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "FETCH_READABILITY_MODE".into(),
+                label: "网页正文提取".into(),
+                required: false,
+                tip: Some("fetch_url 以 Markdown 返回时只保留正文，去掉导航、广告和推荐等内容以节省 token；无法识别正文时返回整页内容。HTML 模式不受影响".into()),
+                field_type: "boolean".into(),
+                default_value: Some("true".into()),
+                placeholder: None,
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "SAFE_SEARCH".into(),
+                label: "安全搜索".into(),
+                required: false,
+                tip: Some("过滤不适宜内容。Google、Bing、DuckDuckGo 通过搜索参数强制开启（Google 仅区分开关），Kagi 不支持参数，改为按内容关键词过滤结构化结果。开启后搜索时直接访问结果页，模型无法关闭".into()),
+                field_type: "select".into(),
+                default_value: Some("off".into()),
+                placeholder: None,
+                options: Some(vec![
+                    EnvVarOption { label: "关闭".into(), value: "off".into() },
+                    EnvVarOption { label: "中等".into(), value: "moderate".into() },
+                    EnvVarOption { label: "严格".into(), value: "strict".into() },
+                ]),
+            },
+            BuiltinTemplateEnvVar {
+                key: "SEARCH_CACHE_TTL_SECS".into(),
+                label: "搜索结果缓存时间".into(),
+                required: false,
+                tip: Some("相同查询、搜索引擎、代理与结果类型的搜索在该时间（秒）内直接返回缓存结果，0 表示不缓存".into()),
+                field_type: "number".into(),
+                default_value: Some("600".into()),
+                placeholder: Some("600".into()),
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "MAX_CONCURRENT_PAGES".into(),
+                label: "浏览器池大小".into(),
+                required: false,
+                tip: Some("同时打开的最大页面数，内存较小的机器可设为 1".into()),
+                field_type: "number".into(),
+                default_value: Some("5".into()),
+                placeholder: Some("5".into()),
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "PAGE_IDLE_TIMEOUT_SECS".into(),
+                label: "页面最长空闲时间".into(),
+                required: false,
+                tip: Some("空闲超过该时间（秒）的页面不再复用，下次搜索时重新打开，0 表示不限制".into()),
+                field_type: "number".into(),
+                default_value: Some("60".into()),
+                placeholder: Some("60".into()),
+                options: None,
+            },
+            BuiltinTemplateEnvVar {
+                key: "EAGER_WARMUP".into(),
+                label: "启动时预热浏览器".into(),
+                required: false,
+                tip: Some("应用启动后在后台预先启动浏览器，加快首次搜索；启动失败不影响应用".into()),
+                field_type: "boolean".into(),
+                default_value: Some("false".into()),
+                placeholder: None,
+                options: None,
+            },
         ],
         },
+        // 计算器
+        BuiltinTemplateInfo {
+            id: "calc".into(),
+            name: "计算器".into(),
+            description: "内置的确定性计算工具，支持四则运算、乘方、百分比、常用函数以及长度、重量、温度等单位换算。模型需要精确计算时调用，避免心算出错。".into(),
+            command: "aipp:calc".into(),
+            transport_type: "stdio".into(),
+            required_envs: vec![],
+            default_timeout: Some(10000),
+        },
+        // 只读文件读取
+        BuiltinTemplateInfo {
+            id: "file_reader".into(),
+            name: "文件读取".into(),
+            description: "内置的只读文件读取工具，只能读取配置的根目录内的文件，自动识别文本编码。无需逐次授权，适合让助手查看项目文件、总结 README 等场景。".into(),
+            command: "aipp:file_reader".into(),
+            transport_type: "stdio".into(),
+            required_envs: vec![
+                BuiltinTemplateEnvVar {
+                    key: "ROOT_DIRECTORY".into(),
+                    label: "根目录".into(),
+                    required: true,
+                    tip: Some("只允许读取该目录内的文件，未配置时拒绝所有读取。符号链接会先解析，指向目录外的同样拒绝。".into()),
+                    field_type: "text".into(),
+                    default_value: None,
+                    placeholder: Some("/Users/username/projects".into()),
+                    options: None,
+                },
+                BuiltinTemplateEnvVar {
+                    key: "MAX_BYTES".into(),
+                    label: "单次读取字节上限".into(),
+                    required: false,
+                    tip: Some("单次最多读取的字节数，超出部分截断，默认 262144（256 KB）".into()),
+                    field_type: "number".into(),
+                    default_value: Some("262144".into()),
+                    placeholder: Some("262144".into()),
+                    options: None,
+                },
+                BuiltinTemplateEnvVar {
+                    key: "BINARY_SIZE_LIMIT".into(),
+                    label: "二进制文件大小上限".into(),
+                    required: false,
+                    tip: Some("超过该大小（字节）的二进制文件直接拒绝读取，较小的二进制文件以 base64 返回，默认 65536（64 KB）".into()),
+                    field_type: "number".into(),
+                    default_value: Some("65536".into()),
+                    placeholder: Some("65536".into()),
+                    options: None,
+                },
+            ],
+            default_timeout: Some(10000),
+        },
         // 操作工具
         BuiltinTemplateInfo {
             id: "operation".into(),
@@ -201,6 +360,36 @@ fn builtin_templates() -> Vec<BuiltinTemplateInfo> {
                     placeholder: Some("120000".into()),
                     options: None,
                 },
+                BuiltinTemplateEnvVar {
+                    key: "COMMAND_DENYLIST".into(),
+                    label: "命令黑名单".into(),
+                    required: false,
+                    tip: Some("禁止执行的命令，每行一条规则。普通文本按命令前缀匹配（如 curl、git push），以 regex: 开头的按正则匹配。命中后直接拒绝，不会弹窗确认。".into()),
+                    field_type: "textarea".into(),
+                    default_value: None,
+                    placeholder: Some("curl\ngit push\nregex:\\bsudo\\b".into()),
+                    options: None,
+                },
+                BuiltinTemplateEnvVar {
+                    key: "COMMAND_ALLOWLIST".into(),
+                    label: "命令白名单".into(),
+                    required: false,
+                    tip: Some("只允许执行的命令，每行一条规则，格式同黑名单。为空时不限制；不为空时，命令中的每个子命令都必须命中白名单。".into()),
+                    field_type: "textarea".into(),
+                    default_value: None,
+                    placeholder: Some("git status\nnpm\nls".into()),
+                    options: None,
+                },
+                BuiltinTemplateEnvVar {
+                    key: "USE_DEFAULT_COMMAND_DENYLIST".into(),
+                    label: "启用内置危险命令拦截".into(),
+                    required: false,
+                    tip: Some("拦截 rm -rf /、mkfs、dd 写磁盘、fork 炸弹、关机重启等明显具有破坏性的命令".into()),
+                    field_type: "boolean".into(),
+                    default_value: Some("true".into()),
+                    placeholder: None,
+                    options: None,
+                },
             ],
             default_timeout: Some(180000), // 3分钟，操作工具可能执行较长命令
         },
@@ -346,6 +535,27 @@ pub fn get_builtin_tools_for_command(command: &str) -> Vec<BuiltinToolInfo> {
                     "required": ["names"]
                 }),
             },
+            BuiltinToolInfo {
+                name: "read_conversation".into(),
+                description: "Re-read messages of the current conversation (user messages, assistant responses and tool results) in chronological order. Use this when earlier context may have been truncated. Output is bounded by max_tokens; when the budget is exceeded the most recent messages in the range are kept.".into(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "offset": {
+                            "type": "number",
+                            "description": "Position (1-indexed) of the first message to return. Defaults to 1."
+                        },
+                        "limit": {
+                            "type": "number",
+                            "description": "Maximum number of messages to return. Defaults to all messages from offset."
+                        },
+                        "max_tokens": {
+                            "type": "number",
+                            "description": "Approximate token budget for the returned content. Defaults to 8000."
+                        }
+                    }
+                }),
+            },
         ],
         Some("ui_interaction") => vec![
             BuiltinToolInfo {
@@ -490,10 +700,43 @@ pub fn get_builtin_tools_for_command(command: &str) -> Vec<BuiltinToolInfo> {
                 }),
             },
         ],
+        Some("file_reader") => vec![BuiltinToolInfo {
+            name: "read_file".into(),
+            description: "只读地读取根目录内的文件并以文本返回，自动识别 UTF-8、UTF-16、GBK 等编码。路径可以是绝对路径，也可以是相对根目录的路径；超过字节上限的部分会被截断，二进制文件较小时以 base64 返回，过大时拒绝。".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "文件路径，相对路径基于配置的根目录，例如 'README.md'"
+                    },
+                    "max_bytes": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "最多读取的字节数，不超过工具配置的上限"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }],
+        Some("calc") => vec![BuiltinToolInfo {
+            name: "evaluate".into(),
+            description: "精确计算数学表达式并以文本返回结果。涉及算术、百分比或单位换算时请调用此工具，不要心算。支持 + - * / ^ mod、括号、百分比（如 '18% of 2450'、'200 + 10%'）、函数 sqrt/abs/round/floor/ceil/ln/log/exp/sin/cos/tan/min/max、常量 pi/e，以及单位换算（如 '5 km to mi'、'100 c to f'、'2 GB to MB'）。".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "expression": {
+                        "type": "string",
+                        "description": "要计算的表达式，最长 500 个字符，例如 '18% of 2450' 或 '(3 + 4) * 2'"
+                    }
+                },
+                "required": ["expression"]
+            }),
+        }],
         Some("search") => vec![
             BuiltinToolInfo {
                 name: "search_web".into(),
-                description: "搜索网络内容，当进行事实性验证、实事信息、研究特定主题等情况时使用最佳。当搜索结果没有可用时，可以尝试更改关键字进行搜索；当搜索结果的简介有限但判断该结果有可用性时，请进一步通过fetch_url工具获取到页面完整的信息。".into(),
+                description: "搜索网络内容，当进行事实性验证、实事信息、研究特定主题等情况时使用最佳。当搜索结果没有可用时，可以尝试更改关键字进行搜索；当搜索结果的简介有限但判断该结果有可用性时，请进一步通过fetch_url工具获取到页面完整的信息。安全搜索由搜索服务器配置强制执行，无法通过参数或搜索语法关闭。".into(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -506,6 +749,11 @@ pub fn get_builtin_tools_for_command(command: &str) -> Vec<BuiltinToolInfo> {
                             "enum": ["markdown", "items"],
                             "default": "markdown",
                             "description": "结果格式类型：\n- markdown: 将HTML转换为Markdown格式，便于阅读和处理\n- items: 返回结构化的搜索结果列表，包含标题、URL、摘要等字段"
+                        },
+                        "bypass_cache": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "跳过搜索结果缓存强制重新搜索，仅在需要最新结果时使用"
                         }
                     },
                     "required": ["query"]
@@ -513,7 +761,7 @@ pub fn get_builtin_tools_for_command(command: &str) -> Vec<BuiltinToolInfo> {
             },
             BuiltinToolInfo {
                 name: "fetch_url".into(),
-                description: "获取网页内容，支持多种结果格式。可以返回Markdown格式的网页内容。".into(),
+                description: "获取网页内容，支持多种结果格式。可以返回Markdown格式的网页内容；URL 为 PDF 时返回提取出的文本，页与页之间以 --- 分隔。".into(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -991,6 +1239,7 @@ mod tests {
         assert!(options.iter().any(|o| o.value == "google"));
         assert!(options.iter().any(|o| o.value == "bing"));
         assert!(options.iter().any(|o| o.value == "duckduckgo"));
+        assert!(options.iter().any(|o| o.value == "duckduckgo_html"));
         assert!(options.iter().any(|o| o.value == "kagi"));
     }
 
@@ -1007,10 +1256,45 @@ mod tests {
         assert_eq!(env.default_value, Some("true".into()));
     }
 
+    #[test]
+    fn test_search_template_browser_pool_envs() {
+        let templates = builtin_templates();
+        let search = templates.iter().find(|t| t.id == "search").unwrap();
+
+        let pool_size = search.required_envs.iter().find(|e| e.key == "MAX_CONCURRENT_PAGES");
+        assert_eq!(pool_size.map(|e| e.field_type.as_str()), Some("number"));
+        assert!(search.required_envs.iter().any(|e| e.key == "PAGE_IDLE_TIMEOUT_SECS"));
+
+        let warmup = search.required_envs.iter().find(|e| e.key == "EAGER_WARMUP").unwrap();
+        assert_eq!(warmup.field_type, "boolean");
+        assert_eq!(warmup.default_value, Some("false".into()));
+    }
+
     // ============================================
     // get_builtin_tools_for_command Tests
     // ============================================
 
+    #[test]
+    fn test_get_tools_for_calc_command() {
+        let tools = get_builtin_tools_for_command("aipp:calc");
+        assert_eq!(tools.len(), 1, "Calc command should have 1 tool");
+        assert_eq!(tools[0].name, "evaluate");
+        assert_eq!(tools[0].input_schema["required"][0], "expression");
+    }
+
+    #[test]
+    fn test_get_tools_for_file_reader_command() {
+        let tools = get_builtin_tools_for_command("aipp:file_reader");
+        assert_eq!(tools.len(), 1, "File reader command should have 1 tool");
+        assert_eq!(tools[0].name, "read_file");
+        assert_eq!(tools[0].input_schema["required"][0], "path");
+
+        let templates = builtin_templates();
+        let file_reader = templates.iter().find(|t| t.id == "file_reader").unwrap();
+        let root = file_reader.required_envs.iter().find(|e| e.key == "ROOT_DIRECTORY").unwrap();
+        assert!(root.required);
+    }
+
     #[test]
     fn test_get_tools_for_search_command() {
         let tools = get_builtin_tools_for_command("aipp:search");
@@ -81,6 +81,12 @@ pub async fn detect_and_process_mcp_calls(
     content: &str,
     mcp_override_config: Option<&McpOverrideConfig>,
 ) -> Result<Option<String>, anyhow::Error> {
+    // 强制回答轮次：工具已禁用，模型输出中的工具调用不再执行
+    if crate::api::ai::tool_budget::is_forced_answer_turn(conversation_id) {
+        warn!("Forced final answer turn, skipping MCP call detection");
+        return Ok(None);
+    }
+
     // Check conversation-level recursion depth to prevent infinite loops
     let depth_state = CONVERSATION_MCP_DEPTH.get_or_init(|| Arc::new(Mutex::new(HashMap::new())));
     let mut depth_map = depth_state.lock().await;
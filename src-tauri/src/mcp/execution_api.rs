@@ -7,18 +7,25 @@
 //! 4. 将执行结果写回数据库并触发前端事件
 //! 5. 在工具成功后继续驱动 AI 对话（包含重试场景）
 use crate::api::ai::config::{
-    get_continue_on_tool_error_from_config, get_network_proxy_from_config,
+    get_continue_on_tool_error_from_config, get_mcp_tool_timeout_from_config,
+    get_network_proxy_from_config,
 };
 use crate::api::ai::events::{ConversationEvent, MCPToolCallUpdateEvent};
 use crate::api::ai_api::{
     batch_tool_result_continue_ask_ai_impl, sanitize_tool_name, tool_result_continue_ask_ai_impl,
 };
+use crate::api::assistant_api::resolve_assistant_detail;
+use crate::db::assistant_db::AssistantDatabase;
 use crate::db::conversation_db::{ConversationDatabase, Repository};
 use crate::db::mcp_db::{ConversationLoadedMCPTool, MCPDatabase, MCPServer, MCPToolCall};
 use crate::mcp::builtin_mcp::{execute_aipp_builtin_tool, is_builtin_mcp_call};
 use crate::mcp::is_dynamic_mcp_loading_enabled_for_assistant;
+use crate::mcp::tool_defaults::merge_default_arguments;
+use crate::mcp::tool_result_integrity::{declared_tool_call_ids, validate_tool_result_correlation};
 use crate::state::activity_state::ConversationActivityManager;
-use crate::utils::window_utils::send_conversation_event_to_chat_windows;
+use crate::utils::window_utils::{
+    emit_conversation_event, send_conversation_event_to_chat_windows,
+};
 use anyhow::{anyhow, bail, Context, Result};
 use rmcp::{
     model::{CallToolRequestParams, ClientCapabilities, ClientInfo, Implementation},
@@ -44,6 +51,20 @@ use tracing::{debug, error, info, instrument, warn};
 /// 各种传输方式统一使用的默认超时时间（毫秒）
 const DEFAULT_TIMEOUT_MS: u64 = 30_000;
 
+/// 工具执行超时（毫秒）：工具单独配置 > 服务器超时 > 全局配置 > 默认值，非正数视为未配置
+fn resolve_tool_timeout_ms(
+    tool_timeout_ms: Option<i64>,
+    global_timeout_ms: Option<u64>,
+    server_timeout_ms: Option<i32>,
+) -> u64 {
+    tool_timeout_ms
+        .filter(|ms| *ms > 0)
+        .map(|ms| ms as u64)
+        .or_else(|| server_timeout_ms.filter(|ms| *ms > 0).map(|ms| ms as u64))
+        .or(global_timeout_ms.filter(|ms| *ms > 0))
+        .unwrap_or(DEFAULT_TIMEOUT_MS)
+}
+
 type ToolCancelRegistry = Arc<Mutex<HashMap<i64, CancellationToken>>>;
 type ContinuationLockRegistry = Arc<Mutex<HashMap<i64, Arc<Mutex<()>>>>>;
 type PendingBatchContinuationRegistry = Arc<Mutex<HashMap<i64, bool>>>;
@@ -84,14 +105,24 @@ async fn cancel_tool_call_execution(call_id: i64) -> bool {
     }
 }
 
-fn tool_call_history_id(tool_call: &MCPToolCall) -> String {
+/// 取消所有正在执行的工具调用，stdio 子进程随客户端一同退出（应用退出时调用）
+pub async fn cancel_all_tool_call_executions() -> usize {
+    let tokens: Vec<CancellationToken> =
+        tool_cancel_registry().lock().await.drain().map(|(_, token)| token).collect();
+    for token in &tokens {
+        token.cancel();
+    }
+    tokens.len()
+}
+
+pub(crate) fn tool_call_history_id(tool_call: &MCPToolCall) -> String {
     tool_call
         .llm_call_id
         .clone()
         .unwrap_or_else(|| format!("mcp_tool_call_{}", tool_call.id))
 }
 
-fn build_tool_result_message_content(tool_call: &MCPToolCall) -> Option<String> {
+pub(crate) fn build_tool_result_message_content(tool_call: &MCPToolCall) -> Option<String> {
     let result_content = match tool_call.status.as_str() {
         "success" => tool_call.result.clone().unwrap_or_else(|| "(空)".to_string()),
         "failed" => format!("Error: {}", tool_call.error.as_deref().unwrap_or("未知错误")),
@@ -152,7 +183,6 @@ fn ensure_tool_result_messages(
     tool_calls: &[MCPToolCall],
 ) -> Result<usize> {
     use crate::api::ai::events::{ConversationEvent, MessageAddEvent, MessageUpdateEvent};
-    use tauri::Emitter;
 
     let existing_messages = conversation_db
         .message_repo()
@@ -211,7 +241,7 @@ fn ensure_tool_result_messages(
                 })
                 .unwrap(),
             };
-            let _ = window.emit(format!("conversation_event_{}", conversation_id).as_str(), add_event);
+            emit_conversation_event(window, conversation_id, add_event);
 
             created_count += 1;
             tool_result_message
@@ -232,7 +262,7 @@ fn ensure_tool_result_messages(
             })
             .unwrap(),
         };
-        let _ = window.emit(format!("conversation_event_{}", conversation_id).as_str(), update_event);
+        emit_conversation_event(window, conversation_id, update_event);
 
         existing_tool_result_messages.insert(tool_result_id, tool_result_message);
     }
@@ -415,6 +445,56 @@ async fn is_dynamic_loading_enabled_for_conversation(
     Ok(is_dynamic_mcp_loading_enabled_for_assistant(app_handle, assistant_id).await)
 }
 
+/// 将对话所属助手为该工具配置的默认参数合并进模型提供的参数，查询失败时使用原参数
+fn resolve_tool_call_parameters(
+    app_handle: &tauri::AppHandle,
+    db: &MCPDatabase,
+    tool_call: &MCPToolCall,
+) -> String {
+    let lookup = || -> std::result::Result<Option<String>, String> {
+        let assistant_id = ConversationDatabase::new(app_handle)
+            .map_err(|e| e.to_string())?
+            .conversation_repo()
+            .map_err(|e| e.to_string())?
+            .read(tool_call.conversation_id)
+            .map_err(|e| e.to_string())?
+            .and_then(|conversation| conversation.assistant_id)
+            .unwrap_or(1);
+        let requested = sanitize_tool_name(&tool_call.tool_name);
+        let tool = db
+            .get_mcp_server_tools(tool_call.server_id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|tool| {
+                tool.tool_name == tool_call.tool_name
+                    || sanitize_tool_name(&tool.tool_name) == requested
+            });
+        let Some(tool) = tool else {
+            return Ok(None);
+        };
+        // 按合并了基础助手的生效配置读取，子助手未设置时沿用基础助手的默认参数
+        let assistant_db = AssistantDatabase::new(app_handle).map_err(|e| e.to_string())?;
+        Ok(resolve_assistant_detail(&assistant_db, assistant_id)?
+            .mcp_tool_configs
+            .into_iter()
+            .find(|config| config.mcp_tool_id == tool.id)
+            .and_then(|config| config.default_arguments)
+            .filter(|arguments| !arguments.trim().is_empty()))
+    };
+
+    match lookup() {
+        Ok(Some(default_arguments)) => {
+            debug!(call_id = tool_call.id, "merging assistant default arguments into tool call");
+            merge_default_arguments(&default_arguments, &tool_call.parameters)
+        }
+        Ok(None) => tool_call.parameters.clone(),
+        Err(e) => {
+            warn!(call_id = tool_call.id, error = %e, "failed to load tool default arguments");
+            tool_call.parameters.clone()
+        }
+    }
+}
+
 // 处理工具执行结果
 /// 根据执行结果更新状态并尝试触发会话续写。即使续写失败也不影响主执行成功标记。
 #[instrument(skip(app_handle,state,feature_config_state,window,tool_call,execution_result), fields(call_id=call_id, conversation_id=?tool_call.conversation_id, retry=?is_retry, trigger_continuation))]
@@ -911,6 +991,29 @@ pub async fn execute_mcp_tool_call(
         }
     }
 
+    // 解析本次执行的超时并写入记录，进入执行中状态时一并推送给前端
+    let timeout_ms = {
+        let tool_timeout_ms = db
+            .get_mcp_server_tools(server.id)
+            .ok()
+            .and_then(|tools| {
+                let sanitized_name = sanitize_tool_name(&tool_call.tool_name);
+                tools.into_iter().find(|tool| {
+                    tool.tool_name == tool_call.tool_name
+                        || sanitize_tool_name(&tool.tool_name) == sanitized_name
+                })
+            })
+            .and_then(|tool| tool.timeout_ms);
+        let global_timeout_ms = {
+            let config_map = feature_config_state.config_feature_map.lock().await;
+            get_mcp_tool_timeout_from_config(&config_map)
+        };
+        resolve_tool_timeout_ms(tool_timeout_ms, global_timeout_ms, server.timeout)
+    };
+    if let Err(e) = db.set_mcp_tool_call_timeout(call_id, timeout_ms as i64) {
+        warn!(call_id, error = %e, "failed to record tool call timeout");
+    }
+
     // 原子性地将状态转为执行中，避免并发重复执行
     if !db
         .mark_mcp_tool_call_executing_if_pending(call_id)
@@ -934,6 +1037,9 @@ pub async fn execute_mcp_tool_call(
         activity_manager.set_mcp_executing(&app_handle, tool_call.conversation_id, call_id).await;
     }
 
+    // 合并助手为该工具配置的默认参数（模型提供的参数优先）
+    let parameters = resolve_tool_call_parameters(&app_handle, &db, &tool_call);
+
     // 执行工具
     let cancel_token = register_cancel_token(call_id).await;
     let execution_result = {
@@ -942,9 +1048,10 @@ pub async fn execute_mcp_tool_call(
             &feature_config_state,
             &server,
             &tool_call.tool_name,
-            &tool_call.parameters,
+            &parameters,
             Some(tool_call.conversation_id),
             Some(cancel_token.clone()),
+            Some(timeout_ms),
         );
         tokio::select! {
             _ = cancel_token.cancelled() => Err("Cancelled by user".to_string()),
@@ -1169,11 +1276,31 @@ pub async fn send_mcp_tool_results(
         .map_err(|e| format!("获取对话信息失败: {}", e))?
         .ok_or_else(|| "未找到对话".to_string())?;
 
+    // 校验工具结果与助手最后一轮声明的工具调用一一对应，错配时不发送
+    let assistant_message = conversation_db
+        .message_repo()
+        .map_err(|e| format!("获取消息仓库失败: {}", e))?
+        .read(message_id)
+        .map_err(|e| format!("获取助手消息失败: {}", e))?;
+    let declared_call_ids = assistant_message
+        .as_ref()
+        .and_then(|message| declared_tool_call_ids(message.tool_calls_json.as_deref()))
+        .unwrap_or_else(|| tool_calls.iter().map(tool_call_history_id).collect());
+    let result_call_ids = tool_calls
+        .iter()
+        .filter(|tc| build_tool_result_message_content(tc).is_some())
+        .map(tool_call_history_id)
+        .collect::<Vec<_>>();
+    if let Err(e) = validate_tool_result_correlation(&declared_call_ids, &result_call_ids) {
+        warn!(message_id, error = %e, "tool result correlation check failed");
+        return Err(e);
+    }
+
     let assistant_id = conversation.assistant_id.ok_or_else(|| "对话未关联助手".to_string())?;
 
     // 获取助手信息以获取模型详情
     let assistant_detail =
-        crate::api::assistant_api::get_assistant(app_handle.clone(), assistant_id)
+        crate::api::assistant_api::get_assistant(app_handle.clone(), assistant_id, Some(true))
             .map_err(|e| format!("获取助手信息失败: {}", e))?;
     if assistant_detail.model.is_empty() {
         return Err("助手未配置模型".to_string());
@@ -1581,7 +1708,7 @@ pub async fn trigger_conversation_continuation_batch(
 
     // 获取助手信息以获取模型详情
     let assistant_detail =
-        crate::api::assistant_api::get_assistant(app_handle.clone(), assistant_id)
+        crate::api::assistant_api::get_assistant(app_handle.clone(), assistant_id, Some(true))
             .map_err(|e| anyhow!("获取助手信息失败: {}", e))?;
     if assistant_detail.model.is_empty() {
         return Err(anyhow!("助手未配置模型"));
@@ -1669,6 +1796,7 @@ pub async fn trigger_conversation_continuation_batch(
 /// 统一的工具执行函数，根据传输类型选择相应的执行策略（公开供子任务复用）
 /// 根据服务器配置的传输类型选择执行方式。
 /// cancel_token 用于支持取消操作，当收到取消信号时立即终止执行。
+/// timeout_ms 为空时使用服务器配置的超时或默认值。
 #[instrument(skip(app_handle,feature_config_state,server,parameters,cancel_token), fields(server_id=server.id, transport=%server.transport_type, tool_name=%tool_name))]
 pub async fn execute_tool_by_transport(
     app_handle: &tauri::AppHandle,
@@ -1678,9 +1806,12 @@ pub async fn execute_tool_by_transport(
     parameters: &str,
     conversation_id: Option<i64>,
     cancel_token: Option<CancellationToken>,
+    timeout_ms: Option<u64>,
 ) -> std::result::Result<String, String> {
     // 如果没有提供 cancel_token，创建一个永远不会取消的令牌
     let cancel_token = cancel_token.unwrap_or_else(CancellationToken::new);
+    let timeout_ms =
+        timeout_ms.unwrap_or_else(|| resolve_tool_timeout_ms(None, None, server.timeout));
 
     match server.transport_type.as_str() {
         // If stdio but command is aipp:*, route to builtin executor
@@ -1694,6 +1825,7 @@ pub async fn execute_tool_by_transport(
                         parameters,
                         conversation_id,
                         Some(cancel_token.clone()),
+                        timeout_ms,
                     )
                     .await
                     .map_err(|e| e.to_string())
@@ -1704,6 +1836,7 @@ pub async fn execute_tool_by_transport(
                         tool_name,
                         parameters,
                         Some(cancel_token.clone()),
+                        timeout_ms,
                     )
                     .await
                     .map_err(|e| e.to_string())
@@ -1715,6 +1848,7 @@ pub async fn execute_tool_by_transport(
                     tool_name,
                     parameters,
                     Some(cancel_token.clone()),
+                    timeout_ms,
                 )
                 .await
                 .map_err(|e| e.to_string())
@@ -1727,6 +1861,7 @@ pub async fn execute_tool_by_transport(
             tool_name,
             parameters,
             Some(cancel_token.clone()),
+            timeout_ms,
         )
         .await
         .map_err(|e| e.to_string()),
@@ -1737,6 +1872,7 @@ pub async fn execute_tool_by_transport(
             tool_name,
             parameters,
             Some(cancel_token.clone()),
+            timeout_ms,
         )
         .await
         .map_err(|e| e.to_string()),
@@ -1748,6 +1884,7 @@ pub async fn execute_tool_by_transport(
             parameters,
             conversation_id,
             Some(cancel_token.clone()),
+            timeout_ms,
         )
         .await
         .map_err(|e| e.to_string()),
@@ -1758,7 +1895,9 @@ pub async fn execute_tool_by_transport(
     }
 }
 // Reuse shared MCP header parsing utilities
-use crate::mcp::util::{parse_server_headers, sanitize_headers_for_log};
+use crate::mcp::util::{
+    load_server_env_vars, parse_server_headers, sanitize_env_for_log, sanitize_headers_for_log,
+};
 /// 通过 stdio 传输执行工具（外部进程）。
 /// cancel_token 用于支持取消操作，当收到取消信号时立即终止执行。
 #[instrument(skip(app_handle,server,parameters,cancel_token), fields(server_id=server.id, tool_name=%tool_name))]
@@ -1768,6 +1907,7 @@ async fn execute_stdio_tool(
     tool_name: &str,
     parameters: &str,
     cancel_token: Option<CancellationToken>,
+    timeout_ms: u64,
 ) -> Result<String> {
     let cancel_token = cancel_token.unwrap_or_else(CancellationToken::new);
     let command = server.command.as_ref().ok_or_else(|| anyhow!("未为 stdio 传输指定命令"))?;
@@ -1775,8 +1915,10 @@ async fn execute_stdio_tool(
     if parts.is_empty() {
         bail!("命令为空");
     }
+    // 环境变量可能包含密钥，日志中只保留名称
+    let env_vars = load_server_env_vars(app_handle, server).await;
+    debug!(env = ?sanitize_env_for_log(&env_vars), "Spawning stdio MCP server");
 
-    let timeout_ms = server.timeout.map(|v| v as u64).unwrap_or(DEFAULT_TIMEOUT_MS);
     let start = std::time::Instant::now();
 
     // 定义实际的执行逻辑
@@ -1787,12 +1929,8 @@ async fn execute_stdio_tool(
                     if parts.len() > 1 {
                         cmd.args(&parts[1..]);
                     }
-                    if let Some(env_vars) = &server.environment_variables {
-                        for line in env_vars.lines() {
-                            if let Some((key, value)) = line.split_once('=') {
-                                cmd.env(key.trim(), value.trim());
-                            }
-                        }
+                    for (key, value) in &env_vars {
+                        cmd.env(key, value);
                     }
                 }))
                 .context("创建子进程失败")?,
@@ -1818,7 +1956,7 @@ async fn execute_stdio_tool(
             match result {
                 Ok(Ok(r)) => Ok(r),
                 Ok(Err(e)) => Err(anyhow!("工具执行失败: {}", e)),
-                Err(_) => Err(anyhow!("工具执行超时")),
+                Err(_) => Err(anyhow!("工具执行超时（{}ms）", timeout_ms)),
             }
         }
     };
@@ -1835,17 +1973,89 @@ async fn execute_stdio_tool(
     }
 }
 
-/// 通过 SSE 传输执行工具（rmcp >= 0.16 已移除，保留显式错误）。
-#[instrument(skip_all)]
+/// 通过 SSE 传输执行工具。
+/// cancel_token 用于支持取消操作，当收到取消信号时立即终止执行。
+#[instrument(skip(feature_config_state,server,parameters,cancel_token), fields(server_id=server.id, tool_name=%tool_name))]
 async fn execute_sse_tool(
     _app_handle: &tauri::AppHandle,
-    _feature_config_state: &tauri::State<'_, crate::FeatureConfigState>,
-    _server: &MCPServer,
-    _tool_name: &str,
-    _parameters: &str,
-    _cancel_token: Option<CancellationToken>,
+    feature_config_state: &tauri::State<'_, crate::FeatureConfigState>,
+    server: &MCPServer,
+    tool_name: &str,
+    parameters: &str,
+    cancel_token: Option<CancellationToken>,
+    timeout_ms: u64,
 ) -> Result<String> {
-    Err(anyhow!("SSE transport 已在当前 rmcp 版本移除，请改用 HTTP transport"))
+    let cancel_token = cancel_token.unwrap_or_else(CancellationToken::new);
+
+    // 获取代理配置
+    let network_proxy = if server.proxy_enabled {
+        let config_map = feature_config_state.config_feature_map.lock().await;
+        get_network_proxy_from_config(&config_map)
+    } else {
+        None
+    };
+    let client_info = ClientInfo {
+        meta: None,
+        protocol_version: Default::default(),
+        capabilities: ClientCapabilities::default(),
+        client_info: Implementation {
+            name: "AIPP MCP SSE Client".to_string(),
+            version: "0.1.0".to_string(),
+            ..Default::default()
+        },
+    };
+
+    let start = std::time::Instant::now();
+
+    // 定义实际的执行逻辑，握手也计入超时
+    let execution = async move {
+        let transport = crate::mcp::sse_transport::connect_sse(server, network_proxy.as_deref())
+            .await
+            .map_err(|e| anyhow!(e))?;
+        let (channels, errors) = transport.into_parts();
+        let client = client_info
+            .serve(channels)
+            .await
+            .map_err(|e| anyhow!("Failed to initialize SSE client: {}", errors.describe(e)))?;
+        let args = parse_tool_arguments(parameters).context("解析工具参数失败")?;
+        let request_param = build_call_tool_request(tool_name, args);
+
+        let response = client
+            .call_tool(request_param)
+            .await
+            .map_err(|e| anyhow!("Tool call failed: {}", errors.describe(e)))?;
+        debug!(is_error=?response.is_error, parts=?response.content.len(), "received sse tool response");
+
+        // Cancel the client connection
+        let _ = client.cancel().await;
+
+        serialize_tool_response(&response)
+    };
+
+    // 使用 select! 同时监听取消信号和超时
+    let result: Result<String, anyhow::Error> = tokio::select! {
+        _ = cancel_token.cancelled() => {
+            Err(anyhow!("Cancelled by user"))
+        }
+        result = tokio::time::timeout(std::time::Duration::from_millis(timeout_ms), execution) => {
+            match result {
+                Ok(Ok(r)) => Ok(r),
+                Ok(Err(e)) => Err(anyhow!("Tool execution failed: {}", e)),
+                Err(_) => Err(anyhow!("Timeout while executing tool ({}ms)", timeout_ms)),
+            }
+        }
+    };
+
+    match result {
+        Ok(result) => {
+            info!(elapsed_ms=?start.elapsed().as_millis(), "sse tool executed successfully");
+            Ok(result)
+        }
+        Err(e) => {
+            error!(elapsed_ms=?start.elapsed().as_millis(), error=%e, "sse tool execution failed");
+            Err(e)
+        }
+    }
 }
 
 /// 执行内置（aipp:*）工具：不经网络，直接在本地实现。
@@ -1858,11 +2068,10 @@ async fn execute_builtin_tool(
     parameters: &str,
     conversation_id: Option<i64>,
     cancel_token: Option<CancellationToken>,
+    timeout_ms: u64,
 ) -> Result<String> {
     let cancel_token = cancel_token.unwrap_or_else(CancellationToken::new);
     let command = server.command.clone().unwrap_or_default();
-    // 获取超时配置，使用服务器配置的超时或默认值
-    let timeout_ms = server.timeout.map(|v| v as u64).unwrap_or(DEFAULT_TIMEOUT_MS);
     // AskUserQuestion 需要等待用户交互，不应受超时限制。
     let wait_indefinitely = command == "aipp:ui_interaction" && tool_name == "ask_user_question";
     let start = std::time::Instant::now();
@@ -1965,6 +2174,7 @@ async fn execute_http_tool(
     tool_name: &str,
     parameters: &str,
     cancel_token: Option<CancellationToken>,
+    timeout_ms: u64,
 ) -> Result<String> {
     let cancel_token = cancel_token.unwrap_or_else(CancellationToken::new);
     let url = server.url.as_ref().ok_or_else(|| anyhow!("No URL specified for HTTP transport"))?;
@@ -2031,7 +2241,6 @@ async fn execute_http_tool(
     };
 
     let start = std::time::Instant::now();
-    let timeout_ms = server.timeout.map(|v| v as u64).unwrap_or(DEFAULT_TIMEOUT_MS);
 
     // 定义实际的执行逻辑
     let execution = async move {
@@ -2058,7 +2267,7 @@ async fn execute_http_tool(
             match result {
                 Ok(Ok(r)) => Ok(r),
                 Ok(Err(e)) => Err(anyhow!("Tool execution failed: {}", e)),
-                Err(_) => Err(anyhow!("Timeout while executing tool")),
+                Err(_) => Err(anyhow!("Timeout while executing tool ({}ms)", timeout_ms)),
             }
         }
     };
@@ -0,0 +1,151 @@
+//! MCP 服务器健康检查：由调度器按配置的间隔连接已启用的远程服务器，记录健康状态，
+//! 服务器变为不可用或恢复时通知前端，避免到工具调用时才发现连接已断开。
+
+use crate::db::mcp_db::{MCPDatabase, MCPServer};
+use crate::mcp::registry_api::ping_mcp_server;
+use serde::Serialize;
+use tauri::Emitter;
+use tracing::{info, warn};
+
+pub const MCP_SERVER_HEALTH_CHANGED_EVENT: &str = "mcp_server_health_changed";
+
+const STATUS_HEALTHY: &str = "healthy";
+const STATUS_UNHEALTHY: &str = "unhealthy";
+
+/// 服务器健康状态发生变化时推送给前端的事件
+#[derive(Debug, Clone, Serialize)]
+pub struct MCPServerHealthEvent {
+    pub server_id: i64,
+    pub server_name: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub last_healthy_at: Option<String>,
+}
+
+/// 参与定期健康检查的服务器：已启用的远程（sse/http）服务器。
+/// stdio 服务器按需拉起进程，定期检查意味着反复启动子进程，因此只在手动测试时记录状态。
+pub fn is_health_check_target(server: &MCPServer) -> bool {
+    server.is_enabled
+        && !server.is_builtin
+        && matches!(server.transport_type.as_str(), "sse" | "http")
+}
+
+/// 是否需要通知前端：变为不可用或从不可用恢复时通知，首次检查即健康时不通知
+pub fn should_notify_health_change(previous_status: Option<&str>, healthy: bool) -> bool {
+    if healthy {
+        previous_status == Some(STATUS_UNHEALTHY)
+    } else {
+        previous_status != Some(STATUS_UNHEALTHY)
+    }
+}
+
+/// 记录一次连接结果，`server` 为检查前读取的服务器信息，用于判断状态是否发生变化
+pub fn record_mcp_server_health(
+    app_handle: &tauri::AppHandle,
+    db: &MCPDatabase,
+    server: &MCPServer,
+    result: &Result<(), String>,
+) {
+    let healthy = result.is_ok();
+    if let Err(e) = db.update_mcp_server_health(server.id, healthy) {
+        warn!(server_id = server.id, error = %e, "Failed to save MCP server health status");
+        return;
+    }
+    if !should_notify_health_change(server.status.as_deref(), healthy) {
+        return;
+    }
+
+    let error = result.as_ref().err().cloned();
+    if healthy {
+        info!(server_id = server.id, name = %server.name, "MCP server recovered");
+    } else {
+        warn!(
+            server_id = server.id,
+            name = %server.name,
+            error = ?error,
+            "MCP server became unhealthy"
+        );
+    }
+    let last_healthy_at = db
+        .get_mcp_server(server.id)
+        .map(|updated| updated.last_healthy_at)
+        .unwrap_or_else(|_| server.last_healthy_at.clone());
+    let event = MCPServerHealthEvent {
+        server_id: server.id,
+        server_name: server.name.clone(),
+        status: if healthy { STATUS_HEALTHY } else { STATUS_UNHEALTHY }.to_string(),
+        error,
+        last_healthy_at,
+    };
+    let _ = app_handle.emit(MCP_SERVER_HEALTH_CHANGED_EVENT, &event);
+}
+
+/// 并发检查所有需要健康检查的服务器并记录结果
+pub async fn check_mcp_servers_health(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let servers = MCPDatabase::new(app_handle)
+        .and_then(|db| db.get_mcp_servers())
+        .map_err(|e| e.to_string())?;
+    let targets: Vec<MCPServer> = servers.into_iter().filter(is_health_check_target).collect();
+    if targets.is_empty() {
+        return Ok(());
+    }
+
+    let results =
+        futures::future::join_all(targets.iter().map(|server| ping_mcp_server(app_handle, server)))
+            .await;
+    let db = MCPDatabase::new(app_handle).map_err(|e| e.to_string())?;
+    for (server, result) in targets.iter().zip(results.iter()) {
+        record_mcp_server_health(app_handle, &db, server, result);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(transport_type: &str, is_enabled: bool, is_builtin: bool) -> MCPServer {
+        MCPServer {
+            id: 1,
+            name: "remote".to_string(),
+            description: String::new(),
+            transport_type: transport_type.to_string(),
+            command: None,
+            environment_variables: None,
+            headers: None,
+            url: Some("http://127.0.0.1:3000/mcp".to_string()),
+            timeout: None,
+            is_long_running: false,
+            is_enabled,
+            is_builtin,
+            is_deletable: true,
+            proxy_enabled: false,
+            created_time: String::new(),
+            autostart: false,
+            status: None,
+            last_healthy_at: None,
+        }
+    }
+
+    #[test]
+    fn test_only_enabled_remote_servers_are_checked() {
+        assert!(is_health_check_target(&server("http", true, false)));
+        assert!(is_health_check_target(&server("sse", true, false)));
+        assert!(!is_health_check_target(&server("http", false, false)));
+        assert!(!is_health_check_target(&server("http", true, true)));
+        assert!(!is_health_check_target(&server("stdio", true, false)));
+    }
+
+    #[test]
+    fn test_notify_only_on_health_transitions() {
+        // 变为不可用时通知，持续不可用不重复通知
+        assert!(should_notify_health_change(None, false));
+        assert!(should_notify_health_change(Some("healthy"), false));
+        assert!(!should_notify_health_change(Some("unhealthy"), false));
+
+        // 从不可用恢复时通知，首次检查即健康不通知
+        assert!(should_notify_health_change(Some("unhealthy"), true));
+        assert!(!should_notify_health_change(None, true));
+        assert!(!should_notify_health_change(Some("healthy"), true));
+    }
+}
@@ -1,11 +1,17 @@
 // Central MCP module: prompt building, detection, execution API, and builtin wrappers
 
+pub mod autostart;
 pub mod builtin_mcp;
 pub mod detection;
 pub mod execution_api;
+pub mod health;
 pub mod prompt;
 pub mod registry_api;
+pub mod server_share;
+pub mod sse_transport;
 pub mod summarizer;
+pub mod tool_defaults;
+pub mod tool_result_integrity;
 pub mod util;
 
 // Re-exports for convenience to minimize callsite churn
@@ -1,7 +1,8 @@
 use crate::api::assistant_api::{
-    get_assistant_field_value, get_assistant_mcp_servers_with_tools, MCPServerWithTools,
-    MCPToolInfo,
+    get_assistant_field_value, mcp_servers_with_tools_for_detail, resolve_assistant_detail,
+    MCPServerWithTools, MCPToolInfo,
 };
+use crate::db::assistant_db::AssistantDatabase;
 use crate::db::mcp_db::MCPDatabase;
 use crate::errors::AppError;
 use std::collections::{HashMap, HashSet};
@@ -191,8 +192,11 @@ pub async fn collect_mcp_info_for_assistant(
     let all_servers = if dynamic_loading_enabled {
         collect_all_enabled_servers_for_dynamic_mode(app_handle)?
     } else {
-        get_assistant_mcp_servers_with_tools(app_handle.clone(), assistant_id)
-            .await
+        // 按合并了基础助手的生效配置读取，子助手同样会带上基础助手启用的服务器与工具
+        let assistant_db = AssistantDatabase::new(app_handle)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to get MCP servers: {}", e)))?;
+        resolve_assistant_detail(&assistant_db, assistant_id)
+            .and_then(|detail| mcp_servers_with_tools_for_detail(&assistant_db, &detail))
             .map_err(|e| AppError::DatabaseError(format!("Failed to get MCP servers: {}", e)))?
     };
     debug!(total_servers = all_servers.len(), "Loaded assistant MCP servers");
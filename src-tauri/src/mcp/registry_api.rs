@@ -1,10 +1,14 @@
+use crate::api::ai::config::get_network_proxy_from_config;
+use crate::api::assistant_api::resolve_assistant_detail;
+use crate::db::assistant_db::AssistantDatabase;
 use crate::db::mcp_db::{
-    MCPDatabase, MCPServer, MCPServerPrompt, MCPServerResource, MCPServerTool,
+    MCPDatabase, MCPServer, MCPServerBulkResult, MCPServerPrompt, MCPServerResource, MCPServerTool,
 };
+use crate::mcp::util::{load_server_env_vars, sanitize_env_for_log};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
-use tauri::Emitter;
-use tracing::{info, instrument, warn};
+use tauri::{Emitter, Manager};
+use tracing::{debug, info, instrument, warn};
 
 // 超时常量集中定义，避免魔法数字分散
 const STDIO_TEST_TIMEOUT: Duration = Duration::from_secs(5);
@@ -49,25 +53,6 @@ fn split_command_line(input: &str) -> Vec<String> {
     parts
 }
 
-// 环境变量解析：忽略空行与以#开头的注释，只处理 KEY=VALUE 形式
-fn parse_env_vars(env: &str) -> Vec<(String, String)> {
-    let mut result = Vec::new();
-    for line in env.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-        if let Some((k, v)) = line.split_once('=') {
-            let key = k.trim();
-            if key.is_empty() {
-                continue;
-            }
-            result.push((key.to_string(), v.trim().to_string()));
-        }
-    }
-    result
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct McpToolInfo {
     pub name: String,
@@ -105,6 +90,9 @@ pub struct MCPServerRequest {
     pub is_enabled: bool,
     pub is_builtin: Option<bool>,
     pub proxy_enabled: bool,
+    /// 应用启动时是否在后台自动连接，未传时保持原值（新增时为关闭）
+    #[serde(default)]
+    pub autostart: Option<bool>,
 }
 
 // 打开数据库的辅助函数，减少重复样板代码
@@ -241,6 +229,9 @@ pub async fn add_mcp_server(
             request.proxy_enabled,
         )
         .map_err(|e| e.to_string())?;
+    if let Some(autostart) = request.autostart {
+        db.set_mcp_server_autostart(server_id, autostart).map_err(|e| e.to_string())?;
+    }
     let _ = db.rebuild_dynamic_mcp_catalog();
 
     Ok(server_id)
@@ -271,6 +262,9 @@ pub async fn update_mcp_server(
         request.proxy_enabled,
     )
     .map_err(|e| e.to_string())?;
+    if let Some(autostart) = request.autostart {
+        db.set_mcp_server_autostart(id, autostart).map_err(|e| e.to_string())?;
+    }
     let _ = db.rebuild_dynamic_mcp_catalog();
 
     Ok(())
@@ -305,6 +299,69 @@ pub async fn toggle_mcp_server(
     Ok(())
 }
 
+/// 批量启用/禁用 MCP 服务器。禁用时保留助手的绑定配置，重新启用后即可恢复；
+/// 被禁用的服务器在读取助手工具时会被自动排除。
+#[tauri::command]
+#[instrument(level = "debug", skip(app_handle, ids), fields(count = ids.len(), is_enabled))]
+pub async fn bulk_toggle_mcp_servers(
+    app_handle: tauri::AppHandle,
+    ids: Vec<i64>,
+    is_enabled: bool,
+) -> Result<Vec<MCPServerBulkResult>, String> {
+    let db = open_db(&app_handle)?;
+    let results = db.bulk_toggle_mcp_servers(&ids, is_enabled).map_err(|e| e.to_string())?;
+    let _ = db.rebuild_dynamic_mcp_catalog();
+    Ok(results)
+}
+
+/// 批量删除 MCP 服务器，并清理助手中引用这些服务器及其工具的绑定配置
+#[tauri::command]
+#[instrument(level = "debug", skip(app_handle, ids), fields(count = ids.len()))]
+pub async fn bulk_delete_mcp_servers(
+    app_handle: tauri::AppHandle,
+    ids: Vec<i64>,
+) -> Result<Vec<MCPServerBulkResult>, String> {
+    let db = open_db(&app_handle)?;
+    let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let results = delete_mcp_servers_and_bindings(&db, &assistant_db, &ids)?;
+    let _ = db.rebuild_dynamic_mcp_catalog();
+    Ok(results)
+}
+
+pub fn delete_mcp_servers_and_bindings(
+    db: &MCPDatabase,
+    assistant_db: &AssistantDatabase,
+    ids: &[i64],
+) -> Result<Vec<MCPServerBulkResult>, String> {
+    // 删除后工具记录会被级联清除，需提前记录工具 ID
+    let mut tool_ids_by_server = std::collections::HashMap::new();
+    for &id in ids {
+        let tool_ids: Vec<i64> = db
+            .get_mcp_server_tools(id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|tool| tool.id)
+            .collect();
+        tool_ids_by_server.insert(id, tool_ids);
+    }
+
+    let results = db.bulk_delete_mcp_servers(ids).map_err(|e| e.to_string())?;
+
+    let deleted_server_ids: Vec<i64> =
+        results.iter().filter(|result| result.success).map(|result| result.id).collect();
+    let deleted_tool_ids: Vec<i64> = deleted_server_ids
+        .iter()
+        .flat_map(|id| tool_ids_by_server.remove(id).unwrap_or_default())
+        .collect();
+    if let Err(e) =
+        assistant_db.delete_assistant_mcp_bindings(&deleted_server_ids, &deleted_tool_ids)
+    {
+        warn!(error = %e, "failed to clean up assistant bindings for deleted MCP servers");
+    }
+
+    Ok(results)
+}
+
 #[tauri::command]
 #[instrument(level = "debug", skip(app_handle), fields(server_id))]
 pub async fn get_mcp_server_tools(
@@ -330,6 +387,21 @@ pub async fn update_mcp_server_tool(
     Ok(())
 }
 
+/// 设置单个工具的执行超时（毫秒），传入 None 时沿用全局或服务器设置
+#[tauri::command]
+#[instrument(level = "debug", skip(app_handle), fields(tool_id, timeout_ms))]
+pub async fn update_mcp_server_tool_timeout(
+    app_handle: tauri::AppHandle,
+    tool_id: i64,
+    timeout_ms: Option<i64>,
+) -> Result<(), String> {
+    if timeout_ms.is_some_and(|ms| ms <= 0) {
+        return Err("工具超时时间必须大于 0".to_string());
+    }
+    let db = open_db(&app_handle)?;
+    db.update_mcp_server_tool_timeout(tool_id, timeout_ms).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[instrument(level = "debug", skip(app_handle), fields(server_id))]
 pub async fn get_mcp_server_resources(
@@ -350,36 +422,46 @@ pub async fn test_mcp_connection(
     let db = open_db(&app_handle)?;
     let server = db.get_mcp_server(server_id).map_err(|e| e.to_string())?;
 
-    // 测试实际的MCP连接
-    let test_result = match server.transport_type.as_str() {
+    let test_result = ping_mcp_server(&app_handle, &server).await;
+    if let Err(e) = &test_result {
+        warn!(error = %e, "MCP connection test failed");
+    }
+    // 手动测试的结果同样计入健康状态
+    if !server.is_builtin {
+        crate::mcp::health::record_mcp_server_health(&app_handle, &db, &server, &test_result);
+    }
+    Ok(test_result.is_ok())
+}
+
+/// 实际连接一次 MCP 服务器以确认其可用，内置 aipp:* 服务器无需连接直接视为可用
+pub(crate) async fn ping_mcp_server(
+    app_handle: &tauri::AppHandle,
+    server: &MCPServer,
+) -> Result<(), String> {
+    match server.transport_type.as_str() {
         "stdio" => {
             if let Some(cmd) = &server.command {
                 if crate::mcp::builtin_mcp::is_builtin_mcp_call(cmd) {
                     // 内置 aipp:* 不需要实际连接
                     Ok(())
                 } else {
-                    test_stdio_connection(&server).await
+                    test_stdio_connection(app_handle, server).await
                 }
             } else {
-                test_stdio_connection(&server).await
+                test_stdio_connection(app_handle, server).await
             }
         }
-        "sse" => test_sse_connection(&server).await,
-        "http" => test_http_connection(&server).await,
+        "sse" => test_sse_connection(app_handle, server).await,
+        "http" => test_http_connection(server).await,
         _ => Err(format!("Unsupported transport type: {}", server.transport_type)),
-    };
-
-    match test_result {
-        Ok(_) => Ok(true),
-        Err(e) => {
-            warn!(error = %e, "MCP connection test failed");
-            Ok(false)
-        }
     }
 }
 
 // 测试stdio连接
-async fn test_stdio_connection(server: &MCPServer) -> Result<(), String> {
+async fn test_stdio_connection(
+    app_handle: &tauri::AppHandle,
+    server: &MCPServer,
+) -> Result<(), String> {
     use rmcp::{
         transport::{ConfigureCommandExt, TokioChildProcess},
         ServiceExt,
@@ -391,6 +473,8 @@ async fn test_stdio_connection(server: &MCPServer) -> Result<(), String> {
     if parts.is_empty() {
         return Err("Empty command".to_string());
     }
+    let env_vars = load_server_env_vars(app_handle, server).await;
+    debug!(server_id = server.id, env = ?sanitize_env_for_log(&env_vars), "Testing stdio MCP server");
 
     // 简短的连接测试，超时时间更短
     let client_result = tokio::time::timeout(STDIO_TEST_TIMEOUT, async {
@@ -399,10 +483,8 @@ async fn test_stdio_connection(server: &MCPServer) -> Result<(), String> {
                 if parts.len() > 1 {
                     cmd.args(&parts[1..]);
                 }
-                if let Some(env_vars) = &server.environment_variables {
-                    for (k, v) in parse_env_vars(env_vars) {
-                        cmd.env(k, v);
-                    }
+                for (k, v) in &env_vars {
+                    cmd.env(k, v);
                 }
             }))?)
             .await?;
@@ -420,9 +502,60 @@ async fn test_stdio_connection(server: &MCPServer) -> Result<(), String> {
     }
 }
 
-// 测试SSE连接（rmcp >= 0.16 已移除，保留显式错误）
-async fn test_sse_connection(_server: &MCPServer) -> Result<(), String> {
-    Err("SSE transport 已在当前 rmcp 版本移除，请改用 HTTP transport".to_string())
+/// 服务器启用代理时读取全局网络代理，与工具执行时的连接方式保持一致
+async fn sse_proxy_for_server(app_handle: &tauri::AppHandle, server: &MCPServer) -> Option<String> {
+    if !server.proxy_enabled {
+        return None;
+    }
+    let feature_config_state = app_handle.state::<crate::FeatureConfigState>();
+    let config_map = feature_config_state.config_feature_map.lock().await;
+    get_network_proxy_from_config(&config_map)
+}
+
+// 测试SSE连接
+async fn test_sse_connection(
+    app_handle: &tauri::AppHandle,
+    server: &MCPServer,
+) -> Result<(), String> {
+    use rmcp::{
+        model::{ClientCapabilities, ClientInfo, Implementation},
+        ServiceExt,
+    };
+
+    let client_info = ClientInfo {
+        meta: None,
+        protocol_version: Default::default(),
+        capabilities: ClientCapabilities::default(),
+        client_info: Implementation {
+            name: "AIPP MCP Test Client".to_string(),
+            version: "0.1.0".to_string(),
+            ..Default::default()
+        },
+    };
+
+    let network_proxy = sse_proxy_for_server(app_handle, server).await;
+
+    // 简短的连接测试，握手与初始化共用同一超时
+    let client_result = tokio::time::timeout(
+        std::time::Duration::from_millis(server.timeout.unwrap_or(5000) as u64),
+        async {
+            let transport =
+                crate::mcp::sse_transport::connect_sse(server, network_proxy.as_deref()).await?;
+            let (channels, errors) = transport.into_parts();
+            client_info.serve(channels).await.map_err(|e| errors.describe(e))
+        },
+    )
+    .await;
+
+    match client_result {
+        Ok(Ok(client)) => {
+            // 测试成功，取消连接
+            let _ = client.cancel().await;
+            Ok(())
+        }
+        Ok(Err(e)) => Err(format!("Failed to create MCP SSE client: {}", e)),
+        Err(_) => Err("Timeout while connecting to SSE server".to_string()),
+    }
 }
 
 // 测试HTTP连接
@@ -521,6 +654,17 @@ pub async fn update_mcp_server_prompt(
 pub async fn refresh_mcp_server_capabilities(
     app_handle: tauri::AppHandle,
     server_id: i64,
+) -> Result<(Vec<MCPServerTool>, Vec<MCPServerResource>, Vec<MCPServerPrompt>), String> {
+    let capabilities = fetch_mcp_server_capabilities(app_handle.clone(), server_id).await?;
+    crate::mcp::summarizer::trigger_mcp_catalog_summary_generation(app_handle, server_id);
+    Ok(capabilities)
+}
+
+/// 连接服务器并增量更新工具、资源和提示词，只重建动态目录，不触发目录摘要生成。
+/// 自动启动等后台场景使用，避免每次启动都调用 LLM 重新生成摘要。
+pub async fn fetch_mcp_server_capabilities(
+    app_handle: tauri::AppHandle,
+    server_id: i64,
 ) -> Result<(Vec<MCPServerTool>, Vec<MCPServerResource>, Vec<MCPServerPrompt>), String> {
     let db = open_db(&app_handle)?;
     let server = db.get_mcp_server(server_id).map_err(|e| e.to_string())?;
@@ -559,10 +703,6 @@ pub async fn refresh_mcp_server_capabilities(
     match result {
         Ok(_) => {
             let _ = db.rebuild_dynamic_mcp_catalog();
-            crate::mcp::summarizer::trigger_mcp_catalog_summary_generation(
-                app_handle.clone(),
-                server_id,
-            );
             let tools = db.get_mcp_server_tools(server_id).map_err(|e| e.to_string())?;
             let resources = db.get_mcp_server_resources(server_id).map_err(|e| e.to_string())?;
             let prompts = db.get_mcp_server_prompts(server_id).map_err(|e| e.to_string())?;
@@ -576,6 +716,59 @@ pub async fn refresh_mcp_server_capabilities(
     }
 }
 
+/// 导出的 MCP 服务器配置；包含密钥时附带警告
+#[derive(Debug, Serialize)]
+pub struct MCPServerExport {
+    pub content: String,
+    pub secrets_included: bool,
+    pub warning: Option<String>,
+}
+
+/// 导入 MCP 服务器的结果
+#[derive(Debug, Serialize)]
+pub struct MCPServerImportResult {
+    pub server_id: i64,
+    pub name: String,
+    /// 导出时被移除值的环境变量/请求头，需要用户补全
+    pub redacted_secrets: Vec<String>,
+}
+
+#[tauri::command]
+#[instrument(level = "debug", skip(app_handle), fields(id, include_secrets))]
+pub async fn export_mcp_server(
+    app_handle: tauri::AppHandle,
+    id: i64,
+    include_secrets: bool,
+) -> Result<MCPServerExport, String> {
+    let db = open_db(&app_handle)?;
+    let shared = crate::mcp::server_share::export_server_config(&db, id, include_secrets)?;
+    let content = serde_json::to_string_pretty(&shared).map_err(|e| e.to_string())?;
+    let warning = include_secrets.then(|| {
+        warn!(server_id = id, "Exporting MCP server config with secrets included");
+        "导出内容包含密钥等敏感信息，请勿公开分享".to_string()
+    });
+    Ok(MCPServerExport { content, secrets_included: include_secrets, warning })
+}
+
+#[tauri::command]
+#[instrument(level = "debug", skip(app_handle, json))]
+pub async fn import_mcp_server(
+    app_handle: tauri::AppHandle,
+    json: String,
+) -> Result<MCPServerImportResult, String> {
+    let shared = crate::mcp::server_share::parse_shared_server(&json)?;
+    let server_id = {
+        let db = open_db(&app_handle)?;
+        let server_id = crate::mcp::server_share::import_server_config(&db, &shared)?;
+        let _ = db.rebuild_dynamic_mcp_catalog();
+        server_id
+    };
+    // 导入的服务器处于停用状态，不在导入时连接，由用户检查配置后启用
+    let name = open_db(&app_handle)?.get_mcp_server(server_id).map_err(|e| e.to_string())?.name;
+
+    Ok(MCPServerImportResult { server_id, name, redacted_secrets: shared.redacted_names() })
+}
+
 // Stdio transport implementation
 #[instrument(level = "debug", skip_all)]
 async fn get_stdio_capabilities(
@@ -590,6 +783,8 @@ async fn get_stdio_capabilities(
     use tokio::process::Command;
 
     let db = open_db(&app_handle)?;
+    let env_vars = load_server_env_vars(&app_handle, &server).await;
+    debug!(server_id, env = ?sanitize_env_for_log(&env_vars), "Fetching stdio capabilities");
 
     // 获取命令，如果没有则返回错误
     let command = server.command.ok_or("No command specified for stdio transport")?;
@@ -611,10 +806,8 @@ async fn get_stdio_capabilities(
                     if parts.len() > 1 {
                         cmd.args(&parts[1..]);
                     }
-                    if let Some(env_vars) = &server.environment_variables {
-                        for (k, v) in parse_env_vars(env_vars) {
-                            cmd.env(k, v);
-                        }
+                    for (k, v) in &env_vars {
+                        cmd.env(k, v);
                     }
                 }))?)
                 .await?;
@@ -711,13 +904,119 @@ async fn get_stdio_capabilities(
     Ok(())
 }
 
-#[instrument(level = "debug", skip_all)]
+#[instrument(level = "debug", skip(app_handle, server), fields(server_id))]
 async fn get_sse_capabilities(
-    _app_handle: tauri::AppHandle,
-    _server_id: i64,
-    _server: MCPServer,
+    app_handle: tauri::AppHandle,
+    server_id: i64,
+    server: MCPServer,
 ) -> Result<(), String> {
-    Err("SSE transport 已在当前 rmcp 版本移除，请改用 HTTP transport".to_string())
+    use rmcp::{
+        model::{ClientCapabilities, ClientInfo, Implementation},
+        ServiceExt,
+    };
+
+    let db = open_db(&app_handle)?;
+
+    let client_info = ClientInfo {
+        meta: None,
+        protocol_version: Default::default(),
+        capabilities: ClientCapabilities::default(),
+        client_info: Implementation {
+            name: "AIPP MCP SSE Client".to_string(),
+            version: "0.1.0".to_string(),
+            ..Default::default()
+        },
+    };
+
+    let network_proxy = sse_proxy_for_server(&app_handle, &server).await;
+
+    // 握手（等待 endpoint 事件）与初始化共用连接超时
+    let client_result = tokio::time::timeout(
+        std::time::Duration::from_millis(
+            server.timeout.unwrap_or(CONNECT_TIMEOUT_DEFAULT_MS as i32) as u64,
+        ),
+        async {
+            let transport =
+                crate::mcp::sse_transport::connect_sse(&server, network_proxy.as_deref()).await?;
+            let (channels, errors) = transport.into_parts();
+            client_info.serve(channels).await.map_err(|e| errors.describe(e))
+        },
+    )
+    .await;
+
+    let client = match client_result {
+        Ok(Ok(client)) => client,
+        Ok(Err(e)) => {
+            return Err(format!("Failed to create MCP SSE client: {}", e));
+        }
+        Err(_) => {
+            return Err("Timeout while connecting to SSE server".to_string());
+        }
+    };
+
+    // 获取能力
+    let capabilities_result = tokio::time::timeout(CAPABILITY_TIMEOUT, async {
+        let (tools_result, resources_result, prompts_result) = tokio::join!(
+            client.list_all_tools(),
+            client.list_all_resources(),
+            client.list_all_prompts()
+        );
+        (tools_result, resources_result, prompts_result)
+    })
+    .await;
+
+    let (tools_result, resources_result, prompts_result) = match capabilities_result {
+        Ok(results) => results,
+        Err(_) => {
+            return Err("Timeout while getting SSE server capabilities".to_string());
+        }
+    };
+
+    let tools_simple = tools_result.ok().map(|tools| {
+        tools
+            .into_iter()
+            .map(|tool| SimpleTool {
+                name: tool.name.to_string(),
+                description: tool.description.as_ref().map(|d| d.to_string()),
+                params_json: serde_json::to_string(&tool.input_schema)
+                    .unwrap_or_else(|_| "{}".to_string()),
+            })
+            .collect::<Vec<_>>()
+    });
+    let resources_simple = resources_result.ok().map(|resources| {
+        resources
+            .into_iter()
+            .map(|r| SimpleResource {
+                uri: r.uri.to_string(),
+                name: r.name.to_string(),
+                mime_type: r.mime_type.as_deref().unwrap_or("unknown").to_string(),
+                description: r.description.as_ref().map(|d| d.to_string()),
+            })
+            .collect::<Vec<_>>()
+    });
+    let prompts_simple = prompts_result.ok().map(|prompts| {
+        prompts
+            .into_iter()
+            .map(|p| {
+                let args_json = if let Some(args) = p.arguments {
+                    serde_json::to_string(&args).unwrap_or_else(|_| "{}".to_string())
+                } else {
+                    "{}".to_string()
+                };
+                SimplePrompt {
+                    name: p.name.to_string(),
+                    description: p.description.as_ref().map(|d| d.to_string()),
+                    args_json,
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+    persist_capability_sets(&db, server_id, "sse", tools_simple, resources_simple, prompts_simple)?;
+
+    // 取消客户端连接
+    let _ = client.cancel().await;
+
+    Ok(())
 }
 
 #[instrument(level = "debug", skip(app_handle, server), fields(server_id))]
@@ -1099,10 +1398,11 @@ pub fn is_agent_load_skill_ready(
         _ => return Ok(false),
     };
 
+    // 按合并了基础助手的生效配置判断
     let assistant_db = AssistantDatabase::new(app_handle).map_err(|e| e.to_string())?;
-    let assistant_mcp_configs =
-        assistant_db.get_assistant_mcp_configs(assistant_id).map_err(|e| e.to_string())?;
-    let assistant_enabled = assistant_mcp_configs
+    let detail = resolve_assistant_detail(&assistant_db, assistant_id)?;
+    let assistant_enabled = detail
+        .mcp_configs
         .iter()
         .find(|c| c.mcp_server_id == agent_server.id)
         .map(|c| c.is_enabled)
@@ -1111,9 +1411,8 @@ pub fn is_agent_load_skill_ready(
         return Ok(false);
     }
 
-    let assistant_tool_configs =
-        assistant_db.get_assistant_mcp_tool_configs(assistant_id).map_err(|e| e.to_string())?;
-    let load_skill_assistant_enabled = assistant_tool_configs
+    let load_skill_assistant_enabled = detail
+        .mcp_tool_configs
         .iter()
         .find(|c| c.mcp_tool_id == load_skill.id)
         .map(|c| c.is_enabled)
@@ -1168,10 +1467,11 @@ pub async fn check_operation_mcp_for_skills(
     // 检查助手级 MCP 配置
     use crate::db::assistant_db::AssistantDatabase;
     let assistant_db = AssistantDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    // 按合并了基础助手的生效配置判断
+    let detail = resolve_assistant_detail(&assistant_db, assistant_id)?;
     let assistant_enabled = if let Some(server) = &operation_mcp {
-        let mcp_configs =
-            assistant_db.get_assistant_mcp_configs(assistant_id).map_err(|e| e.to_string())?;
-        mcp_configs
+        detail
+            .mcp_configs
             .iter()
             .find(|c| c.mcp_server_id == server.id)
             .map(|c| c.is_enabled)
@@ -1189,9 +1489,8 @@ pub async fn check_operation_mcp_for_skills(
         agent_load_skill_enabled,
         agent_load_skill_assistant_enabled,
     ) = if let Some(agent) = &agent_server {
-        let assistant_mcp_configs =
-            assistant_db.get_assistant_mcp_configs(assistant_id).map_err(|e| e.to_string())?;
-        let assistant_enabled_flag = assistant_mcp_configs
+        let assistant_enabled_flag = detail
+            .mcp_configs
             .iter()
             .find(|c| c.mcp_server_id == agent.id)
             .map(|c| c.is_enabled)
@@ -1201,10 +1500,8 @@ pub async fn check_operation_mcp_for_skills(
         let load_skill = tools.iter().find(|t| t.tool_name == AGENT_LOAD_SKILL_TOOL_NAME);
         let load_skill_enabled = load_skill.map(|t| t.is_enabled).unwrap_or(false);
 
-        let assistant_tool_configs =
-            assistant_db.get_assistant_mcp_tool_configs(assistant_id).map_err(|e| e.to_string())?;
         let load_skill_assistant_enabled = load_skill
-            .and_then(|tool| assistant_tool_configs.iter().find(|c| c.mcp_tool_id == tool.id))
+            .and_then(|tool| detail.mcp_tool_configs.iter().find(|c| c.mcp_tool_id == tool.id))
             .map(|c| c.is_enabled)
             .unwrap_or(false);
 
@@ -1224,11 +1521,13 @@ pub async fn check_operation_mcp_for_skills(
         && agent_load_skill_enabled
         && agent_load_skill_assistant_enabled;
 
-    // 获取助手启用的 Skills 数量
+    // 获取助手启用的 Skills 数量（含继承自基础助手的）
     use crate::db::skill_db::SkillDatabase;
     let skill_db = SkillDatabase::new(&app_handle).map_err(|e| e.to_string())?;
+    let inheritance_chain =
+        assistant_db.get_inheritance_chain(assistant_id).map_err(|e| e.to_string())?;
     let enabled_skills =
-        skill_db.get_enabled_skill_configs(assistant_id).map_err(|e| e.to_string())?;
+        skill_db.get_effective_skill_configs(&inheritance_chain).map_err(|e| e.to_string())?;
 
     Ok(OperationMcpCheckResult {
         operation_mcp_id,
@@ -0,0 +1,638 @@
+//! MCP 服务器配置的导出与导入
+//!
+//! 导出内容为可移植的 JSON：传输方式、命令/URL、环境变量、请求头以及工具级设置（启用、自动运行）。
+//! 默认会移除密钥类的值（名称中含 KEY、TOKEN、SECRET、PASSWORD 等的环境变量、Authorization、Cookie 等请求头，
+//! 以及 URL 查询参数和命令行参数中的同类值），只保留名称；显式选择包含密钥时，导出内容会标记 `secrets_included`，
+//! 由调用方提示用户妥善保管。
+//!
+//! 导入的配置来自外部文件，不可信：导入后的服务器不会是内置服务器，工具也不会自动运行。
+
+use crate::db::mcp_db::MCPDatabase;
+use crate::mcp::util::parse_env_vars;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const MCP_SERVER_SHARE_TYPE: &str = "mcp_server";
+pub const MCP_SERVER_SHARE_VERSION: &str = "1.0";
+
+/// 名称中包含这些片段（不区分大小写）的环境变量/请求头视为密钥
+const SECRET_NAME_MARKERS: [&str; 9] =
+    ["KEY", "TOKEN", "SECRET", "PASSWORD", "PASSWD", "AUTH", "CREDENTIAL", "COOKIE", "PRIVATE"];
+
+/// URL 查询参数与命令行参数中被移除的密钥值替换为该占位符
+pub const REDACTED_PLACEHOLDER: &str = "__REDACTED__";
+
+/// 导出的 MCP 服务器配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedMCPServer {
+    pub version: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+    /// 是否包含密钥的真实值
+    pub secrets_included: bool,
+    pub server: MCPServerShareData,
+    #[serde(default)]
+    pub tools: Vec<MCPToolShareData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MCPServerShareData {
+    pub name: String,
+    pub description: String,
+    pub transport_type: String,
+    pub command: Option<String>,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub environment_variables: Vec<SharedConfigEntry>,
+    #[serde(default)]
+    pub headers: Vec<SharedConfigEntry>,
+    pub timeout: Option<i32>,
+    pub is_long_running: bool,
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub is_builtin: bool,
+    #[serde(default)]
+    pub proxy_enabled: bool,
+    #[serde(default)]
+    pub autostart: bool,
+}
+
+/// 环境变量或请求头；`value` 为 None 表示该值是密钥且已被移除
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedConfigEntry {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// 工具级设置，同时带上描述与参数，导入后即使未能连接也能展示工具
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MCPToolShareData {
+    pub tool_name: String,
+    pub tool_description: Option<String>,
+    pub parameters: Option<String>,
+    pub is_enabled: bool,
+    pub is_auto_run: bool,
+    #[serde(default)]
+    pub timeout_ms: Option<i64>,
+}
+
+impl SharedMCPServer {
+    /// 被移除了值的环境变量、请求头、URL 参数（`url:名称`）与命令参数（`command:名称`），导入后需要用户补全
+    pub fn redacted_names(&self) -> Vec<String> {
+        let entries = self
+            .server
+            .environment_variables
+            .iter()
+            .chain(self.server.headers.iter())
+            .filter(|entry| entry.value.is_none())
+            .map(|entry| entry.name.clone());
+        let url_params = self
+            .server
+            .url
+            .as_deref()
+            .map(redacted_url_params)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| format!("url:{}", name));
+        let command_args = self
+            .server
+            .command
+            .as_deref()
+            .map(redacted_command_args)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| format!("command:{}", name));
+        entries.chain(url_params).chain(command_args).collect()
+    }
+}
+
+/// 判断环境变量/请求头名称是否为密钥
+pub fn is_secret_name(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SECRET_NAME_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+fn share_entry(name: &str, value: &str, include_secrets: bool) -> SharedConfigEntry {
+    let redact = !include_secrets && !value.is_empty() && is_secret_name(name);
+    SharedConfigEntry {
+        name: name.to_string(),
+        value: if redact { None } else { Some(value.to_string()) },
+    }
+}
+
+/// 移除 URL 查询参数中的密钥值，例如 `?api_key=xxx` -> `?api_key=__REDACTED__`
+pub fn redact_url(url: &str) -> String {
+    let (base, fragment) = match url.split_once('#') {
+        Some((base, fragment)) => (base, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((path, query)) = base.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if !value.is_empty() && is_secret_name(&decode_name(name)) => {
+                format!("{}={}", name, REDACTED_PLACEHOLDER)
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    match fragment {
+        Some(fragment) => format!("{}?{}#{}", path, query, fragment),
+        None => format!("{}?{}", path, query),
+    }
+}
+
+fn decode_name(name: &str) -> String {
+    urlencoding::decode(name).map(|n| n.into_owned()).unwrap_or_else(|_| name.to_string())
+}
+
+fn redacted_url_params(url: &str) -> Vec<String> {
+    let base = url.split('#').next().unwrap_or_default();
+    let Some((_, query)) = base.split_once('?') else {
+        return Vec::new();
+    };
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(_, value)| *value == REDACTED_PLACEHOLDER)
+        .map(|(name, _)| decode_name(name))
+        .collect()
+}
+
+/// 命令参数的名称部分：`--api-key` -> `api-key`，`TOKEN` -> `TOKEN`
+fn arg_name(arg: &str) -> &str {
+    arg.trim_start_matches('-')
+}
+
+/// 移除命令行参数中的密钥值，支持 `--token=xxx`、`--token xxx` 与 `TOKEN=xxx` 三种写法
+pub fn redact_command(command: &str) -> String {
+    let args: Vec<&str> = command.split_whitespace().collect();
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut changed = false;
+    let mut index = 0;
+    while index < args.len() {
+        let arg = args[index];
+        if let Some((name, value)) = arg.split_once('=') {
+            if !value.is_empty() && is_secret_name(arg_name(name)) {
+                redacted.push(format!("{}={}", name, REDACTED_PLACEHOLDER));
+                changed = true;
+                index += 1;
+                continue;
+            }
+        } else if arg.starts_with('-') && is_secret_name(arg_name(arg)) {
+            if args.get(index + 1).is_some_and(|value| !value.starts_with('-')) {
+                redacted.push(arg.to_string());
+                redacted.push(REDACTED_PLACEHOLDER.to_string());
+                changed = true;
+                index += 2;
+                continue;
+            }
+        }
+        redacted.push(arg.to_string());
+        index += 1;
+    }
+    if changed {
+        redacted.join(" ")
+    } else {
+        command.to_string()
+    }
+}
+
+fn redacted_command_args(command: &str) -> Vec<String> {
+    let args: Vec<&str> = command.split_whitespace().collect();
+    args.iter()
+        .enumerate()
+        .filter_map(|(index, arg)| match arg.split_once('=') {
+            Some((name, value)) if value == REDACTED_PLACEHOLDER => {
+                Some(arg_name(name).to_string())
+            }
+            _ if *arg == REDACTED_PLACEHOLDER && index > 0 => {
+                Some(arg_name(args[index - 1]).to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 导出指定服务器的配置
+pub fn export_server_config(
+    db: &MCPDatabase,
+    server_id: i64,
+    include_secrets: bool,
+) -> Result<SharedMCPServer, String> {
+    let server = db.get_mcp_server(server_id).map_err(|e| e.to_string())?;
+    let tools = db.get_mcp_server_tools(server_id).map_err(|e| e.to_string())?;
+
+    let environment_variables = server
+        .environment_variables
+        .as_deref()
+        .map(parse_env_vars)
+        .unwrap_or_default()
+        .iter()
+        .map(|(name, value)| share_entry(name, value, include_secrets))
+        .collect();
+
+    let headers = match server.headers.as_deref().map(str::trim) {
+        Some(raw) if !raw.is_empty() => {
+            let map: Map<String, Value> =
+                serde_json::from_str(raw).map_err(|e| format!("请求头配置解析失败: {}", e))?;
+            map.iter()
+                .map(|(name, value)| {
+                    let value =
+                        value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                    share_entry(name, &value, include_secrets)
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(SharedMCPServer {
+        version: MCP_SERVER_SHARE_VERSION.to_string(),
+        data_type: MCP_SERVER_SHARE_TYPE.to_string(),
+        secrets_included: include_secrets,
+        server: MCPServerShareData {
+            name: server.name,
+            description: server.description,
+            transport_type: server.transport_type,
+            command: if include_secrets {
+                server.command
+            } else {
+                server.command.as_deref().map(redact_command)
+            },
+            url: if include_secrets { server.url } else { server.url.as_deref().map(redact_url) },
+            environment_variables,
+            headers,
+            timeout: server.timeout,
+            is_long_running: server.is_long_running,
+            is_enabled: server.is_enabled,
+            is_builtin: server.is_builtin,
+            proxy_enabled: server.proxy_enabled,
+            autostart: server.autostart,
+        },
+        tools: tools
+            .into_iter()
+            .map(|tool| MCPToolShareData {
+                tool_name: tool.tool_name,
+                tool_description: tool.tool_description,
+                parameters: tool.parameters,
+                is_enabled: tool.is_enabled,
+                is_auto_run: tool.is_auto_run,
+                timeout_ms: tool.timeout_ms,
+            })
+            .collect(),
+    })
+}
+
+/// 解析并校验导出的 JSON
+pub fn parse_shared_server(json: &str) -> Result<SharedMCPServer, String> {
+    let shared: SharedMCPServer =
+        serde_json::from_str(json.trim()).map_err(|e| format!("无效的 MCP 服务器配置: {}", e))?;
+    if shared.data_type != MCP_SERVER_SHARE_TYPE {
+        return Err(format!("不是 MCP 服务器配置（type = {}）", shared.data_type));
+    }
+    if shared.version.split('.').next() != MCP_SERVER_SHARE_VERSION.split('.').next() {
+        return Err(format!("不支持的配置版本: {}", shared.version));
+    }
+    if shared.server.name.trim().is_empty() {
+        return Err("服务器名称不能为空".to_string());
+    }
+    Ok(shared)
+}
+
+/// 名称已存在时追加“(导入)”后缀，避免覆盖同名服务器
+fn unique_server_name(db: &MCPDatabase, name: &str) -> Result<String, String> {
+    let existing: Vec<String> = db
+        .get_mcp_servers()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|server| server.name)
+        .collect();
+    if !existing.iter().any(|n| n == name) {
+        return Ok(name.to_string());
+    }
+    let mut candidate = format!("{} (导入)", name);
+    let mut index = 2;
+    while existing.iter().any(|n| *n == candidate) {
+        candidate = format!("{} (导入 {})", name, index);
+        index += 1;
+    }
+    Ok(candidate)
+}
+
+/// 将导出的配置创建为新服务器并恢复工具级设置，返回新服务器 ID
+///
+/// 已移除的密钥以空值写入，需要用户在编辑服务器时补全。
+/// 文件中的 `is_builtin` 与工具的 `is_auto_run` 不被信任：导入的服务器始终是普通服务器，工具需要确认后才运行。
+/// 导入的服务器总是停用且不自动启动，由用户检查配置后再手动启用。
+pub fn import_server_config(db: &MCPDatabase, shared: &SharedMCPServer) -> Result<i64, String> {
+    let data = &shared.server;
+    let name = unique_server_name(db, data.name.trim())?;
+
+    let environment_variables = data
+        .environment_variables
+        .iter()
+        .map(|entry| format!("{}={}", entry.name, entry.value.as_deref().unwrap_or_default()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let headers = if data.headers.is_empty() {
+        None
+    } else {
+        let map: Map<String, Value> = data
+            .headers
+            .iter()
+            .map(|entry| {
+                (entry.name.clone(), Value::String(entry.value.clone().unwrap_or_default()))
+            })
+            .collect();
+        Some(Value::Object(map).to_string())
+    };
+
+    let server_id = db
+        .upsert_mcp_server_with_builtin(
+            &name,
+            Some(&data.description),
+            &data.transport_type,
+            data.command.as_deref(),
+            Some(&environment_variables).filter(|env| !env.is_empty()).map(String::as_str),
+            headers.as_deref(),
+            data.url.as_deref(),
+            data.timeout,
+            data.is_long_running,
+            false, // 导入的服务器默认停用
+            false, // 导入的服务器不能是内置服务器
+            true,  // 导入的服务器总是可删除
+            data.proxy_enabled,
+        )
+        .map_err(|e| e.to_string())?;
+    db.set_mcp_server_autostart(server_id, false).map_err(|e| e.to_string())?;
+
+    for tool in &shared.tools {
+        let tool_id = db
+            .upsert_mcp_server_tool(
+                server_id,
+                &tool.tool_name,
+                tool.tool_description.as_deref(),
+                tool.parameters.as_deref(),
+            )
+            .map_err(|e| e.to_string())?;
+        // 自动运行不随文件导入，避免外部配置绕过工具调用确认
+        db.update_mcp_server_tool(tool_id, tool.is_enabled, false).map_err(|e| e.to_string())?;
+        db.update_mcp_server_tool_timeout(tool_id, tool.timeout_ms).map_err(|e| e.to_string())?;
+    }
+
+    Ok(server_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn create_db() -> MCPDatabase {
+        let db = MCPDatabase { conn: Connection::open_in_memory().unwrap() };
+        db.create_tables().unwrap();
+        db
+    }
+
+    fn create_source_server(db: &MCPDatabase) -> i64 {
+        let server_id = db
+            .upsert_mcp_server_with_builtin(
+                "github",
+                Some("GitHub MCP"),
+                "http",
+                None,
+                Some("GITHUB_TOKEN=ghp_secret\nREGION=us"),
+                Some(r#"{"Authorization":"Bearer ghp_secret","X-Client":"aipp"}"#),
+                Some("https://example.com/mcp"),
+                Some(15000),
+                false,
+                true,
+                false,
+                true,
+                true,
+            )
+            .unwrap();
+        db.set_mcp_server_autostart(server_id, true).unwrap();
+        let search = db
+            .upsert_mcp_server_tool(
+                server_id,
+                "search",
+                Some("Search"),
+                Some(r#"{"type":"object"}"#),
+            )
+            .unwrap();
+        db.update_mcp_server_tool(search, true, true).unwrap();
+        db.update_mcp_server_tool_timeout(search, Some(90_000)).unwrap();
+        let delete = db.upsert_mcp_server_tool(server_id, "delete_repo", None, None).unwrap();
+        db.update_mcp_server_tool(delete, false, false).unwrap();
+        server_id
+    }
+
+    /// 导入后预期的配置：服务器停用、不自动启动，工具不自动运行
+    fn as_imported(mut shared: SharedMCPServer) -> SharedMCPServer {
+        shared.server.is_enabled = false;
+        shared.server.autostart = false;
+        for tool in &mut shared.tools {
+            tool.is_auto_run = false;
+        }
+        shared
+    }
+
+    fn round_trip(include_secrets: bool) -> (SharedMCPServer, MCPDatabase, i64) {
+        let source = create_db();
+        let source_id = create_source_server(&source);
+        let exported = export_server_config(&source, source_id, include_secrets).unwrap();
+        let json = serde_json::to_string(&exported).unwrap();
+
+        let target = create_db();
+        let imported = parse_shared_server(&json).unwrap();
+        assert_eq!(imported, exported);
+        let target_id = import_server_config(&target, &imported).unwrap();
+        (exported, target, target_id)
+    }
+
+    /// 测试包含密钥时导出再导入到新库，配置与工具设置完全一致
+    #[test]
+    fn test_round_trip_with_secrets() {
+        let (exported, target, target_id) = round_trip(true);
+        assert!(exported.secrets_included);
+        assert!(exported.redacted_names().is_empty());
+
+        let server = target.get_mcp_server(target_id).unwrap();
+        assert_eq!(server.name, "github");
+        assert_eq!(server.transport_type, "http");
+        assert_eq!(server.url.as_deref(), Some("https://example.com/mcp"));
+        assert_eq!(server.timeout, Some(15000));
+        assert!(server.proxy_enabled);
+        assert!(!server.is_enabled);
+        assert!(!server.autostart);
+        assert_eq!(
+            server.environment_variables.as_deref(),
+            Some("GITHUB_TOKEN=ghp_secret\nREGION=us")
+        );
+        let headers: Value = serde_json::from_str(server.headers.as_deref().unwrap()).unwrap();
+        assert_eq!(headers["Authorization"], "Bearer ghp_secret");
+        assert_eq!(headers["X-Client"], "aipp");
+
+        // 再次导出新库中的服务器应与原导出一致（启用、自动启动与自动运行不随导入恢复）
+        assert_eq!(export_server_config(&target, target_id, true).unwrap(), as_imported(exported));
+    }
+
+    /// 测试默认导出移除密钥，其余配置与工具设置保持一致
+    #[test]
+    fn test_round_trip_redacts_secrets() {
+        let (exported, target, target_id) = round_trip(false);
+        assert!(!exported.secrets_included);
+        assert_eq!(exported.redacted_names(), vec!["GITHUB_TOKEN", "Authorization"]);
+        let json = serde_json::to_string(&exported).unwrap();
+        assert!(!json.contains("ghp_secret"));
+
+        let server = target.get_mcp_server(target_id).unwrap();
+        assert_eq!(server.environment_variables.as_deref(), Some("GITHUB_TOKEN=\nREGION=us"));
+        let headers: Value = serde_json::from_str(server.headers.as_deref().unwrap()).unwrap();
+        assert_eq!(headers["Authorization"], "");
+        assert_eq!(headers["X-Client"], "aipp");
+
+        let tools = target.get_mcp_server_tools(target_id).unwrap();
+        let settings: Vec<(&str, bool, bool)> =
+            tools.iter().map(|t| (t.tool_name.as_str(), t.is_enabled, t.is_auto_run)).collect();
+        assert_eq!(settings, vec![("delete_repo", false, false), ("search", true, false)]);
+        assert_eq!(tools[1].parameters.as_deref(), Some(r#"{"type":"object"}"#));
+        assert_eq!(tools[0].timeout_ms, None);
+        assert_eq!(tools[1].timeout_ms, Some(90_000));
+
+        // 除被移除的密钥外，重新导出的结果与原导出一致
+        let mut reexported = export_server_config(&target, target_id, false).unwrap();
+        for entry in reexported
+            .server
+            .environment_variables
+            .iter_mut()
+            .chain(reexported.server.headers.iter_mut())
+        {
+            if is_secret_name(&entry.name) && entry.value.as_deref() == Some("") {
+                entry.value = None;
+            }
+        }
+        assert_eq!(reexported, as_imported(exported));
+    }
+
+    #[test]
+    fn test_import_keeps_existing_server_with_same_name() {
+        let db = create_db();
+        let original_id = create_source_server(&db);
+        let exported = export_server_config(&db, original_id, false).unwrap();
+
+        let first = import_server_config(&db, &exported).unwrap();
+        let second = import_server_config(&db, &exported).unwrap();
+        assert_ne!(first, original_id);
+        assert_eq!(db.get_mcp_server(first).unwrap().name, "github (导入)");
+        assert_eq!(db.get_mcp_server(second).unwrap().name, "github (导入 2)");
+        // 原服务器的密钥未被覆盖
+        assert_eq!(
+            db.get_mcp_server(original_id).unwrap().environment_variables.as_deref(),
+            Some("GITHUB_TOKEN=ghp_secret\nREGION=us")
+        );
+    }
+
+    /// 测试导入不信任文件中的内置标记与自动运行设置
+    #[test]
+    fn test_import_forces_builtin_and_auto_run_off() {
+        let db = create_db();
+        let source_id = create_source_server(&db);
+        let mut shared = export_server_config(&db, source_id, false).unwrap();
+        shared.server.is_builtin = true;
+        shared.tools.iter_mut().for_each(|tool| tool.is_auto_run = true);
+
+        let imported_id = import_server_config(&db, &shared).unwrap();
+        let server = db.get_mcp_server(imported_id).unwrap();
+        assert!(!server.is_builtin);
+        assert!(server.is_deletable);
+        assert!(db.get_mcp_server_tools(imported_id).unwrap().iter().all(|tool| !tool.is_auto_run));
+    }
+
+    /// 测试 URL 查询参数中的密钥被移除
+    #[test]
+    fn test_export_redacts_url_query_secrets() {
+        let db = create_db();
+        let server_id = db
+            .upsert_mcp_server_with_builtin(
+                "remote",
+                None,
+                "sse",
+                None,
+                None,
+                None,
+                Some("https://example.com/sse?api_key=sk-live&region=us&access_token=abc#top"),
+                None,
+                false,
+                true,
+                false,
+                true,
+                false,
+            )
+            .unwrap();
+
+        let exported = export_server_config(&db, server_id, false).unwrap();
+        assert_eq!(
+            exported.server.url.as_deref(),
+            Some("https://example.com/sse?api_key=__REDACTED__&region=us&access_token=__REDACTED__#top")
+        );
+        assert_eq!(exported.redacted_names(), vec!["url:api_key", "url:access_token"]);
+
+        let with_secrets = export_server_config(&db, server_id, true).unwrap();
+        assert!(with_secrets.server.url.unwrap().contains("sk-live"));
+    }
+
+    /// 测试命令行参数中的密钥被移除
+    #[test]
+    fn test_export_redacts_command_arg_secrets() {
+        let db = create_db();
+        let server_id = db
+            .upsert_mcp_server_with_builtin(
+                "local",
+                None,
+                "stdio",
+                Some("npx -y server --api-key sk-live --token=ghp_secret --verbose GITHUB_TOKEN=ghp_other"),
+                None,
+                None,
+                None,
+                None,
+                false,
+                true,
+                false,
+                true,
+                false,
+            )
+            .unwrap();
+
+        let exported = export_server_config(&db, server_id, false).unwrap();
+        let command = exported.server.command.clone().unwrap();
+        assert_eq!(
+            command,
+            "npx -y server --api-key __REDACTED__ --token=__REDACTED__ --verbose GITHUB_TOKEN=__REDACTED__"
+        );
+        assert!(!command.contains("sk-live") && !command.contains("ghp_"));
+        assert_eq!(
+            exported.redacted_names(),
+            vec!["command:api-key", "command:token", "command:GITHUB_TOKEN"]
+        );
+
+        // 不含密钥的命令原样保留
+        assert_eq!(redact_command("npx  -y server"), "npx  -y server");
+    }
+
+    #[test]
+    fn test_parse_rejects_other_share_types() {
+        assert!(parse_shared_server("not json").is_err());
+        let other = r#"{"version":"1.0","type":"assistant","secrets_included":false,"server":{"name":"x","description":"","transport_type":"stdio","command":"npx x","url":null,"timeout":null,"is_long_running":false,"is_enabled":true}}"#;
+        assert!(parse_shared_server(other).unwrap_err().contains("assistant"));
+        let future = other.replace("\"assistant\"", "\"mcp_server\"").replace("1.0", "2.0");
+        assert!(parse_shared_server(&future).unwrap_err().contains("2.0"));
+        let ok = other.replace("\"assistant\"", "\"mcp_server\"");
+        assert!(parse_shared_server(&ok).unwrap().tools.is_empty());
+    }
+}
@@ -0,0 +1,831 @@
+//! 旧版 HTTP+SSE MCP 传输客户端。
+//!
+//! 协议流程：客户端 GET 服务器地址建立事件流，服务器先推送 `endpoint` 事件告知消息 POST 地址，
+//! 之后客户端将 JSON-RPC 请求 POST 到该地址，响应与通知通过事件流中的 `message` 事件返回。
+//! rmcp 自 0.16 起不再内置该传输，这里基于 reqwest 实现，并以 (Sink, Stream) 的形式交给 rmcp 使用。
+//! 事件流断开后服务器端的会话随之失效：传输会在有限次数内重新握手获取新的 endpoint，
+//! 重放 `initialize` 与 `notifications/initialized` 恢复会话，并将尚未收到响应的请求重发一次；
+//! 重连失败时关闭整个传输并记录原因。
+
+use crate::db::mcp_db::MCPServer;
+use crate::mcp::util::{parse_server_headers, sanitize_headers_for_log};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::Url;
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+const DEFAULT_EVENT: &str = "message";
+const ENDPOINT_EVENT: &str = "endpoint";
+const ERROR_BODY_SNIPPET_LEN: usize = 200;
+const INITIALIZE_METHOD: &str = "initialize";
+const INITIALIZED_NOTIFICATION: &str = "notifications/initialized";
+const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+type ByteStream = BoxStream<'static, Result<Vec<u8>, String>>;
+/// 重连期间由读取任务持有，写入任务因此会等到会话恢复后再发送新消息
+type SharedEndpoint = Arc<tokio::sync::Mutex<Url>>;
+
+/// 一条完整的 SSE 事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// 增量解析 SSE 字节流，按空行切分事件；多行 data 以换行拼接，注释行与 id/retry 字段忽略
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let decoded = String::from_utf8_lossy(&raw[..raw.len() - 1]);
+            let line = decoded.strip_suffix('\r').unwrap_or(&decoded);
+            if let Some(event) = self.process_line(line) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            let event = self.event.take();
+            if self.data.is_empty() {
+                return None;
+            }
+            let data = std::mem::take(&mut self.data).join("\n");
+            return Some(SseEvent {
+                event: event.unwrap_or_else(|| DEFAULT_EVENT.to_string()),
+                data,
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// 记录后台读写任务的失败原因，连接关闭后用于给出比 "connection closed" 更明确的错误
+#[derive(Debug, Clone, Default)]
+pub struct SseErrorSlot(Arc<Mutex<Option<String>>>);
+
+impl SseErrorSlot {
+    fn set(&self, error: String) {
+        if let Ok(mut slot) = self.0.lock() {
+            slot.get_or_insert(error);
+        }
+    }
+
+    fn get(&self) -> Option<String> {
+        self.0.lock().ok().and_then(|slot| slot.clone())
+    }
+
+    /// 在原始错误后附上传输层记录的失败原因
+    pub fn describe(&self, error: impl std::fmt::Display) -> String {
+        match self.get() {
+            Some(cause) => format!("{} ({})", error, cause),
+            None => error.to_string(),
+        }
+    }
+}
+
+/// 恢复会话所需的客户端消息：initialize 握手以及尚未收到响应的请求
+#[derive(Debug, Default)]
+struct SessionLog {
+    initialize: Option<Value>,
+    initialized: Option<Value>,
+    in_flight: Vec<InFlightRequest>,
+}
+
+#[derive(Debug)]
+struct InFlightRequest {
+    id: Value,
+    message: Value,
+    retried: bool,
+}
+
+impl SessionLog {
+    fn record(&mut self, message: &Value) {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return;
+        };
+        match message.get("id") {
+            Some(id) => {
+                if method == INITIALIZE_METHOD {
+                    self.initialize = Some(message.clone());
+                }
+                self.in_flight.push(InFlightRequest {
+                    id: id.clone(),
+                    message: message.clone(),
+                    retried: false,
+                });
+            }
+            None if method == INITIALIZED_NOTIFICATION => self.initialized = Some(message.clone()),
+            None => {}
+        }
+    }
+
+    fn complete(&mut self, message: &Value) {
+        if message.get("method").is_some() {
+            return;
+        }
+        if let Some(id) = message.get("id") {
+            self.in_flight.retain(|request| &request.id != id);
+        }
+    }
+
+    /// initialize 本身还在等待响应时无需单独重放，按普通请求重发即可
+    fn initialize_pending(&self) -> bool {
+        let id = self.initialize.as_ref().and_then(|message| message.get("id"));
+        id.is_some_and(|id| self.in_flight.iter().any(|request| &request.id == id))
+    }
+
+    /// 取出重连后需要重发的请求；每个请求只重发一次，已重发过的返回其 id 以便直接报错
+    fn take_retries(&mut self) -> (Vec<Value>, Vec<Value>) {
+        let mut retries = Vec::new();
+        let mut failed = Vec::new();
+        self.in_flight.retain_mut(|request| {
+            if request.retried {
+                failed.push(request.id.clone());
+                false
+            } else {
+                request.retried = true;
+                retries.push(request.message.clone());
+                true
+            }
+        });
+        (retries, failed)
+    }
+}
+
+/// 已完成握手的 SSE 传输，通过 `into_parts` 交给 rmcp 的 `serve`
+pub struct SseClientTransport {
+    sender: UnboundedSender<ClientJsonRpcMessage>,
+    receiver: UnboundedReceiver<ServerJsonRpcMessage>,
+    errors: SseErrorSlot,
+}
+
+impl SseClientTransport {
+    pub fn into_parts(
+        self,
+    ) -> (
+        (UnboundedSender<ClientJsonRpcMessage>, UnboundedReceiver<ServerJsonRpcMessage>),
+        SseErrorSlot,
+    ) {
+        ((self.sender, self.receiver), self.errors)
+    }
+}
+
+/// 构建带自定义请求头（含 Authorization）与可选代理的 reqwest 客户端
+pub fn build_sse_client(
+    server: &MCPServer,
+    proxy: Option<&str>,
+) -> Result<reqwest::Client, String> {
+    let (_auth_header, all_headers) = parse_server_headers(server);
+    let mut header_map = HeaderMap::new();
+    if let Some(hdrs) = all_headers.as_ref() {
+        let to_log = sanitize_headers_for_log(hdrs);
+        info!(server_id = server.id, headers = ?to_log, "Using SSE headers");
+        for (k, v) in hdrs.iter() {
+            if let (Ok(name), Ok(value)) =
+                (HeaderName::try_from(k.as_str()), HeaderValue::from_str(v.as_str()))
+            {
+                header_map.insert(name, value);
+            }
+        }
+    }
+    let mut builder = reqwest::Client::builder().default_headers(header_map);
+    if let Some(proxy_url) = proxy.filter(|p| !p.trim().is_empty()) {
+        match reqwest::Proxy::all(proxy_url) {
+            Ok(proxy) => {
+                builder = builder.proxy(proxy);
+                info!(proxy_url = %proxy_url, server_id = server.id, "SSE proxy configured");
+            }
+            Err(e) => {
+                warn!(error = %e, proxy_url = %proxy_url, server_id = server.id, "SSE proxy configuration failed");
+            }
+        }
+    }
+    builder.build().map_err(|e| format!("Failed to build reqwest client for SSE: {}", e))
+}
+
+/// 连接 SSE 服务器并等待 `endpoint` 事件，握手完成后启动后台读写任务
+pub async fn connect_sse(
+    server: &MCPServer,
+    proxy: Option<&str>,
+) -> Result<SseClientTransport, String> {
+    let raw_url = server.url.as_ref().ok_or("No URL specified for SSE transport")?;
+    let sse_url = Url::parse(raw_url).map_err(|e| format!("SSE 地址无效: {}", e))?;
+    let client = build_sse_client(server, proxy)?;
+    let transport = start_transport(client, sse_url).await?;
+    info!(server_id = server.id, "SSE handshake completed");
+    Ok(transport)
+}
+
+/// 完成握手并启动后台读写任务
+async fn start_transport(
+    client: reqwest::Client,
+    sse_url: Url,
+) -> Result<SseClientTransport, String> {
+    let (stream, parser, endpoint, pending) = handshake(&client, &sse_url).await?;
+    debug!(endpoint = %endpoint, "SSE endpoint received");
+
+    let (outgoing_tx, outgoing_rx) = unbounded::<ClientJsonRpcMessage>();
+    let (incoming_tx, incoming_rx) = unbounded::<ServerJsonRpcMessage>();
+    let endpoint = Arc::new(tokio::sync::Mutex::new(endpoint));
+    let session = Arc::new(Mutex::new(SessionLog::default()));
+    let errors = SseErrorSlot::default();
+    let cancel = CancellationToken::new();
+
+    let reader = SseReader {
+        client: client.clone(),
+        sse_url,
+        endpoint: endpoint.clone(),
+        session: session.clone(),
+        incoming: incoming_tx,
+        errors: errors.clone(),
+        cancel: cancel.clone(),
+    };
+    tokio::spawn(reader.run(stream, parser, pending));
+    tokio::spawn(run_writer(client, endpoint, session, outgoing_rx, errors.clone(), cancel));
+
+    Ok(SseClientTransport { sender: outgoing_tx, receiver: incoming_rx, errors })
+}
+
+/// 建立事件流并读取到 `endpoint` 事件为止；同一批数据中 endpoint 之后的事件原样返回
+async fn handshake(
+    client: &reqwest::Client,
+    sse_url: &Url,
+) -> Result<(ByteStream, SseParser, Url, Vec<SseEvent>), String> {
+    let mut stream = open_event_stream(client, sse_url).await?;
+    let mut parser = SseParser::default();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("SSE 握手失败：读取事件流出错: {}", e))?;
+        let mut events = parser.feed(&chunk).into_iter();
+        while let Some(event) = events.next() {
+            if event.event == ENDPOINT_EVENT {
+                let endpoint = resolve_endpoint(sse_url, &event.data)?;
+                return Ok((stream, parser, endpoint, events.collect()));
+            }
+            debug!(event = %event.event, "Ignoring SSE event before endpoint");
+        }
+    }
+    Err("SSE 握手失败：服务器在发送 endpoint 事件前关闭了事件流".to_string())
+}
+
+async fn open_event_stream(client: &reqwest::Client, sse_url: &Url) -> Result<ByteStream, String> {
+    let response = client
+        .get(sse_url.clone())
+        .header(ACCEPT, "text/event-stream")
+        .send()
+        .await
+        .map_err(|e| format!("SSE 握手失败：无法连接 {}: {}", sse_url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("SSE 握手失败：HTTP {} {}", status, body_snippet(&body)));
+    }
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if !content_type.starts_with("text/event-stream") {
+        return Err(format!(
+            "SSE 握手失败：响应类型为 '{}'，期望 text/event-stream（该地址可能是 HTTP transport）",
+            content_type
+        ));
+    }
+    Ok(response
+        .bytes_stream()
+        .map(|chunk| chunk.map(|b| b.to_vec()).map_err(|e| e.to_string()))
+        .boxed())
+}
+
+/// endpoint 既可能是绝对地址，也可能是相对于 SSE 地址的路径
+pub fn resolve_endpoint(sse_url: &Url, endpoint: &str) -> Result<Url, String> {
+    sse_url
+        .join(endpoint.trim())
+        .map_err(|e| format!("SSE 握手失败：endpoint '{}' 无效: {}", endpoint, e))
+}
+
+async fn post_message(client: &reqwest::Client, url: &Url, message: &Value) -> Result<(), String> {
+    match client.post(url.clone()).json(message).send().await {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            Err(format!("SSE 消息发送失败：HTTP {} {}", status, body_snippet(&body)))
+        }
+        Err(e) => Err(format!("SSE 消息发送失败：{}: {}", url, e)),
+    }
+}
+
+/// 读取事件流直到收到指定 id 的响应，该响应被丢弃，其余事件按原顺序返回
+async fn wait_for_response(
+    stream: &mut ByteStream,
+    parser: &mut SseParser,
+    mut events: Vec<SseEvent>,
+    id: &Value,
+) -> Result<Vec<SseEvent>, String> {
+    loop {
+        if let Some(pos) = events.iter().position(|event| is_response_to(event, id)) {
+            events.remove(pos);
+            return Ok(events);
+        }
+        match stream.next().await {
+            Some(Ok(chunk)) => events.extend(parser.feed(&chunk)),
+            Some(Err(e)) => return Err(format!("重新初始化失败：读取事件流出错: {}", e)),
+            None => return Err("重新初始化失败：服务器关闭了事件流".to_string()),
+        }
+    }
+}
+
+fn is_response_to(event: &SseEvent, id: &Value) -> bool {
+    event.event == DEFAULT_EVENT
+        && serde_json::from_str::<Value>(&event.data)
+            .is_ok_and(|message| message.get("method").is_none() && message.get("id") == Some(id))
+}
+
+/// 为无法再重发的请求构造错误响应，避免调用方一直等待
+fn request_failed(id: Value, reason: &str) -> Option<ServerJsonRpcMessage> {
+    serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32603, "message": reason },
+    }))
+    .ok()
+}
+
+fn body_snippet(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(ERROR_BODY_SNIPPET_LEN) {
+        Some((idx, _)) => format!("{}...", &body[..idx]),
+        None => body.to_string(),
+    }
+}
+
+/// 读取事件流并转发 `message` 事件；事件流断开时重连，重连失败再关闭整个传输
+struct SseReader {
+    client: reqwest::Client,
+    sse_url: Url,
+    endpoint: SharedEndpoint,
+    session: Arc<Mutex<SessionLog>>,
+    incoming: UnboundedSender<ServerJsonRpcMessage>,
+    errors: SseErrorSlot,
+    cancel: CancellationToken,
+}
+
+impl SseReader {
+    async fn run(self, mut stream: ByteStream, mut parser: SseParser, pending: Vec<SseEvent>) {
+        if !self.dispatch(pending).await {
+            self.cancel.cancel();
+            return;
+        }
+        loop {
+            let next = tokio::select! {
+                _ = self.cancel.cancelled() => return,
+                next = stream.next() => next,
+            };
+            let error = match next {
+                Some(Ok(chunk)) => {
+                    if !self.dispatch(parser.feed(&chunk)).await {
+                        self.cancel.cancel();
+                        return;
+                    }
+                    continue;
+                }
+                Some(Err(e)) => format!("SSE 事件流已断开：读取出错: {}", e),
+                None => "SSE 事件流已断开：服务器关闭了连接".to_string(),
+            };
+            warn!(url = %self.sse_url, error = %error, "SSE stream lost, reconnecting");
+            match self.reconnect().await {
+                Ok((new_stream, new_parser, pending)) => {
+                    info!(url = %self.sse_url, "SSE session restored");
+                    stream = new_stream;
+                    parser = new_parser;
+                    if !self.dispatch(pending).await {
+                        self.cancel.cancel();
+                        return;
+                    }
+                }
+                Err(e) => {
+                    let error = format!("{}；{}", error, e);
+                    warn!(url = %self.sse_url, error = %error, "SSE reconnect failed, closing transport");
+                    self.errors.set(error);
+                    self.cancel.cancel();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// 有限次数内重新握手并恢复会话，成功后替换 endpoint
+    async fn reconnect(&self) -> Result<(ByteStream, SseParser, Vec<SseEvent>), String> {
+        let mut endpoint = self.endpoint.lock().await;
+        let (retries, failed) = match self.session.lock() {
+            Ok(mut session) => session.take_retries(),
+            Err(_) => return Err("会话状态不可用".to_string()),
+        };
+        for id in failed {
+            if let Some(message) = request_failed(id, "SSE 事件流再次断开，请求未能完成")
+            {
+                let _ = self.incoming.unbounded_send(message);
+            }
+        }
+
+        let mut last_error = String::new();
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            let delay = RECONNECT_BASE_DELAY * 2u32.pow(attempt - 1);
+            tokio::select! {
+                _ = self.cancel.cancelled() => return Err("传输已关闭".to_string()),
+                _ = tokio::time::sleep(delay) => {}
+            }
+            match tokio::time::timeout(RECONNECT_HANDSHAKE_TIMEOUT, self.resume_session(&retries))
+                .await
+            {
+                Ok(Ok((stream, parser, new_endpoint, pending))) => {
+                    debug!(endpoint = %new_endpoint, attempt, "SSE endpoint received after reconnect");
+                    *endpoint = new_endpoint;
+                    return Ok((stream, parser, pending));
+                }
+                Ok(Err(e)) => last_error = e,
+                Err(_) => last_error = "重连握手超时".to_string(),
+            }
+            warn!(attempt, error = %last_error, "SSE reconnect attempt failed");
+        }
+        Err(format!("重连失败（已尝试 {} 次）: {}", MAX_RECONNECT_ATTEMPTS, last_error))
+    }
+
+    /// 重新握手，在新会话上重放 initialize 并重发未完成的请求
+    async fn resume_session(
+        &self,
+        retries: &[Value],
+    ) -> Result<(ByteStream, SseParser, Url, Vec<SseEvent>), String> {
+        let (mut stream, mut parser, endpoint, mut pending) =
+            handshake(&self.client, &self.sse_url).await?;
+        let (initialize, initialized) = match self.session.lock() {
+            Ok(session) if !session.initialize_pending() => {
+                (session.initialize.clone(), session.initialized.clone())
+            }
+            Ok(_) => (None, None),
+            Err(_) => return Err("会话状态不可用".to_string()),
+        };
+        if let Some(initialize) = initialize {
+            post_message(&self.client, &endpoint, &initialize).await?;
+            pending =
+                wait_for_response(&mut stream, &mut parser, pending, &initialize["id"]).await?;
+            if let Some(initialized) = initialized {
+                post_message(&self.client, &endpoint, &initialized).await?;
+            }
+        }
+        for message in retries {
+            post_message(&self.client, &endpoint, message).await?;
+        }
+        Ok((stream, parser, endpoint, pending))
+    }
+
+    /// 处理一批事件，接收端已关闭时返回 false
+    async fn dispatch(&self, events: Vec<SseEvent>) -> bool {
+        for event in events {
+            match event.event.as_str() {
+                ENDPOINT_EVENT => match resolve_endpoint(&self.sse_url, &event.data) {
+                    Ok(endpoint) => *self.endpoint.lock().await = endpoint,
+                    Err(e) => warn!(error = %e, "Ignoring invalid SSE endpoint event"),
+                },
+                DEFAULT_EVENT => {
+                    let message = serde_json::from_str::<Value>(&event.data).and_then(|value| {
+                        if let Ok(mut session) = self.session.lock() {
+                            session.complete(&value);
+                        }
+                        serde_json::from_value::<ServerJsonRpcMessage>(value)
+                    });
+                    match message {
+                        Ok(message) => {
+                            if self.incoming.unbounded_send(message).is_err() {
+                                return false;
+                            }
+                        }
+                        Err(e) => warn!(error = %e, "Ignoring malformed SSE message"),
+                    }
+                }
+                other => debug!(event = %other, "Ignoring unknown SSE event"),
+            }
+        }
+        true
+    }
+}
+
+/// 将客户端消息依次 POST 到当前 endpoint，发送失败时关闭整个传输
+async fn run_writer(
+    client: reqwest::Client,
+    endpoint: SharedEndpoint,
+    session: Arc<Mutex<SessionLog>>,
+    mut outgoing: UnboundedReceiver<ClientJsonRpcMessage>,
+    errors: SseErrorSlot,
+    cancel: CancellationToken,
+) {
+    loop {
+        let message = tokio::select! {
+            _ = cancel.cancelled() => return,
+            message = outgoing.next() => message,
+        };
+        let Some(message) = message else {
+            // rmcp 已释放传输，连同事件流一起关闭
+            cancel.cancel();
+            return;
+        };
+        let message = match serde_json::to_value(&message) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize SSE message");
+                continue;
+            }
+        };
+        let url = endpoint.lock().await;
+        if cancel.is_cancelled() {
+            return;
+        }
+        // 先登记再发送，保证响应到达或重连重发时都能找到该请求
+        if let Ok(mut session) = session.lock() {
+            session.record(&message);
+        }
+        let Err(error) = post_message(&client, &url, &message).await else {
+            continue;
+        };
+        warn!(error = %error, "SSE message POST failed");
+        errors.set(error);
+        cancel.cancel();
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_handles_split_chunks_and_multiline_data() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b"event: endpoint\r\nda").is_empty());
+        let events =
+            parser.feed(b"ta: /messages?session_id=1\r\n\r\n: keep-alive\n\ndata: a\ndata: b\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".to_string(),
+                    data: "/messages?session_id=1".to_string()
+                },
+                SseEvent { event: "message".to_string(), data: "a\nb".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parser_ignores_events_without_data() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b"event: ping\nid: 1\n\n").is_empty());
+        // 上一事件的 event 名不应泄漏到下一事件
+        let events = parser.feed(b"data:{}\n\n");
+        assert_eq!(events, vec![SseEvent { event: "message".to_string(), data: "{}".to_string() }]);
+    }
+
+    #[test]
+    fn test_resolve_endpoint() {
+        let base = Url::parse("https://example.com/mcp/sse").unwrap();
+        assert_eq!(
+            resolve_endpoint(&base, "/messages?session_id=abc").unwrap().as_str(),
+            "https://example.com/messages?session_id=abc"
+        );
+        assert_eq!(
+            resolve_endpoint(&base, "messages").unwrap().as_str(),
+            "https://example.com/mcp/messages"
+        );
+        assert_eq!(
+            resolve_endpoint(&base, "https://other.example.com/rpc").unwrap().as_str(),
+            "https://other.example.com/rpc"
+        );
+    }
+
+    #[test]
+    fn test_error_slot_keeps_first_cause() {
+        let slot = SseErrorSlot::default();
+        assert_eq!(slot.describe("connection closed"), "connection closed");
+        slot.set("HTTP 401".to_string());
+        slot.set("later".to_string());
+        assert_eq!(slot.describe("connection closed"), "connection closed (HTTP 401)");
+    }
+
+    async fn read_http_request(socket: &mut tokio::net::TcpStream) -> (String, Value) {
+        use tokio::io::AsyncReadExt;
+
+        let mut data = Vec::new();
+        let mut buf = [0u8; 1024];
+        let header_end = loop {
+            if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            let n = socket.read(&mut buf).await.unwrap();
+            assert!(n > 0, "connection closed before request headers");
+            data.extend_from_slice(&buf[..n]);
+        };
+        let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+        let content_length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map(|(_, value)| value.trim().parse::<usize>().unwrap())
+            .unwrap_or(0);
+        while data.len() < header_end + content_length {
+            let n = socket.read(&mut buf).await.unwrap();
+            data.extend_from_slice(&buf[..n]);
+        }
+        let body = serde_json::from_slice(&data[header_end..]).unwrap_or(Value::Null);
+        (head, body)
+    }
+
+    async fn open_stream(
+        listener: &tokio::net::TcpListener,
+        session_id: u32,
+    ) -> tokio::net::TcpStream {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let (head, _) = read_http_request(&mut socket).await;
+        assert!(head.starts_with("GET /sse"));
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n\
+             event: endpoint\ndata: /messages?session_id={}\n\n",
+            session_id
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket
+    }
+
+    /// 接收一次 POST 并返回 202，断言请求发往指定会话且方法一致
+    async fn accept_post(
+        listener: &tokio::net::TcpListener,
+        session_id: u32,
+        method: &str,
+    ) -> Value {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let (head, body) = read_http_request(&mut socket).await;
+        assert!(head.contains(&format!("session_id={}", session_id)), "{}", head);
+        assert_eq!(body["method"], method);
+        socket
+            .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        body
+    }
+
+    async fn push_message(stream: &mut tokio::net::TcpStream, message: Value) {
+        use tokio::io::AsyncWriteExt;
+
+        let event = format!("event: message\ndata: {}\n\n", message);
+        stream.write_all(event.as_bytes()).await.unwrap();
+    }
+
+    fn initialize_result(id: &Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "serverInfo": { "name": "test", "version": "1.0" },
+            },
+        })
+    }
+
+    fn client_message(value: Value) -> ClientJsonRpcMessage {
+        serde_json::from_value(value).unwrap()
+    }
+
+    async fn next_message(receiver: &mut UnboundedReceiver<ServerJsonRpcMessage>) -> Value {
+        let message = tokio::time::timeout(Duration::from_secs(10), receiver.next())
+            .await
+            .expect("timed out waiting for SSE message")
+            .expect("transport closed");
+        serde_json::to_value(message).unwrap()
+    }
+
+    /// 事件流断开后重新握手、重放 initialize，并在新会话上重发未完成的请求
+    #[tokio::test]
+    async fn test_dropped_stream_recovers() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let sse_url =
+            Url::parse(&format!("http://{}/sse", listener.local_addr().unwrap())).unwrap();
+        let server = tokio::spawn(async move {
+            let mut stream = open_stream(&listener, 1).await;
+            let initialize = accept_post(&listener, 1, INITIALIZE_METHOD).await;
+            push_message(&mut stream, initialize_result(&initialize["id"])).await;
+            accept_post(&listener, 1, INITIALIZED_NOTIFICATION).await;
+            // 请求已送达但响应返回前事件流断开
+            accept_post(&listener, 1, "tools/list").await;
+            drop(stream);
+
+            let mut stream = open_stream(&listener, 2).await;
+            let initialize = accept_post(&listener, 2, INITIALIZE_METHOD).await;
+            push_message(&mut stream, initialize_result(&initialize["id"])).await;
+            accept_post(&listener, 2, INITIALIZED_NOTIFICATION).await;
+            let request = accept_post(&listener, 2, "tools/list").await;
+            push_message(
+                &mut stream,
+                json!({ "jsonrpc": "2.0", "id": request["id"], "result": { "tools": [] } }),
+            )
+            .await;
+            stream
+        });
+
+        let transport = start_transport(reqwest::Client::new(), sse_url).await.unwrap();
+        let ((sender, mut receiver), errors) = transport.into_parts();
+        sender
+            .unbounded_send(client_message(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "1.0" },
+                },
+            })))
+            .unwrap();
+        assert_eq!(next_message(&mut receiver).await["id"], 0);
+        sender
+            .unbounded_send(client_message(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/initialized",
+            })))
+            .unwrap();
+        sender
+            .unbounded_send(client_message(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/list",
+                "params": {},
+            })))
+            .unwrap();
+
+        // 重放的 initialize 响应不会转发给调用方，下一条即为重发请求的响应
+        let response = next_message(&mut receiver).await;
+        assert_eq!(response["id"], 1);
+        assert!(response.get("result").is_some());
+        assert_eq!(errors.describe("ok"), "ok");
+        let _stream = server.await.unwrap();
+    }
+
+    /// 重连次数用尽后关闭传输并记录断开原因
+    #[tokio::test]
+    async fn test_stream_drop_closes_transport_after_reconnect_fails() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let sse_url =
+            Url::parse(&format!("http://{}/sse", listener.local_addr().unwrap())).unwrap();
+        let server = tokio::spawn(async move {
+            // 断开事件流并关闭监听，后续重连全部被拒绝
+            drop(open_stream(&listener, 1).await);
+        });
+
+        let transport = start_transport(reqwest::Client::new(), sse_url).await.unwrap();
+        server.await.unwrap();
+        let ((_sender, mut receiver), errors) = transport.into_parts();
+        let closed = tokio::time::timeout(Duration::from_secs(15), receiver.next()).await;
+        assert!(matches!(closed, Ok(None)));
+        let cause = errors.describe("connection closed");
+        assert!(cause.contains("SSE 事件流已断开"), "{}", cause);
+        assert!(cause.contains("重连失败"), "{}", cause);
+    }
+}
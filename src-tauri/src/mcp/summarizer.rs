@@ -1,6 +1,6 @@
 use crate::api::ai::config::{
-    calculate_retry_delay, get_network_proxy_from_config, get_request_timeout_from_config,
-    get_retry_attempts_from_config,
+    get_network_proxy_from_config, get_request_timeout_from_config, get_retry_attempts_from_config,
+    get_retry_backoff_from_config,
 };
 use crate::api::genai_client;
 use crate::db::llm_db::LLMDatabase;
@@ -446,6 +446,8 @@ async fn generate_mcp_catalog_summary(
     .chat_request;
 
     let max_retry_attempts = get_retry_attempts_from_config(&config_map).max(1);
+
+    let retry_backoff = get_retry_backoff_from_config(&config_map);
     let mut attempts = 0;
     let response_text = loop {
         match client.exec_chat(&model_detail.model.code, chat_request.clone(), None).await {
@@ -455,7 +457,7 @@ async fn generate_mcp_catalog_summary(
                 if attempts >= max_retry_attempts {
                     return Err(AppError::ProviderError(format!("MCP 摘要生成失败: {}", e)));
                 }
-                sleep(Duration::from_millis(calculate_retry_delay(attempts))).await;
+                sleep(Duration::from_millis(retry_backoff.delay_for(attempts))).await;
             }
         }
     };
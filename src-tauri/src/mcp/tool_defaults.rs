@@ -0,0 +1,159 @@
+//! 助手级别的工具默认参数
+//!
+//! 默认参数保存在 `assistant_mcp_tool_config.default_arguments`（JSON 对象），
+//! 执行工具前与模型提供的参数合并，模型显式提供的参数优先。
+
+use serde_json::{Map, Value};
+
+/// 校验并规范化默认参数
+///
+/// 返回 `Ok(None)` 表示清空默认参数（空字符串或空对象）。
+/// 工具 schema 声明了 `properties` 时，默认参数的键必须是已声明的属性（`additionalProperties: true` 除外），
+/// 值需匹配属性的 `type` 与 `enum`。
+pub fn validate_default_arguments(
+    schema: Option<&str>,
+    default_arguments: &str,
+) -> Result<Option<String>, String> {
+    if default_arguments.trim().is_empty() {
+        return Ok(None);
+    }
+    let defaults: Value = serde_json::from_str(default_arguments)
+        .map_err(|e| format!("默认参数不是合法的 JSON: {}", e))?;
+    let Some(defaults) = defaults.as_object() else {
+        return Err("默认参数必须是 JSON 对象".to_string());
+    };
+    if defaults.is_empty() {
+        return Ok(None);
+    }
+
+    let schema: Option<Value> = schema
+        .filter(|s| !s.trim().is_empty())
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|e| format!("工具参数 schema 解析失败: {}", e))?;
+    if let Some(properties) = schema.as_ref().and_then(|s| s.get("properties")?.as_object()) {
+        let allow_additional = schema
+            .as_ref()
+            .and_then(|s| s.get("additionalProperties"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        for (key, value) in defaults {
+            match properties.get(key) {
+                Some(property) => validate_property_value(key, property, value)?,
+                None if allow_additional => {}
+                None => return Err(format!("工具参数中不存在 '{}'", key)),
+            }
+        }
+    }
+
+    Ok(Some(Value::Object(defaults.clone()).to_string()))
+}
+
+fn validate_property_value(key: &str, property: &Value, value: &Value) -> Result<(), String> {
+    let types: Vec<&str> = match property.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| value_matches_type(value, t)) {
+        return Err(format!("参数 '{}' 的默认值类型应为 {}", key, types.join(" | ")));
+    }
+    if let Some(options) = property.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return Err(format!("参数 '{}' 的默认值不在可选值范围内", key));
+        }
+    }
+    Ok(())
+}
+
+fn value_matches_type(value: &Value, json_type: &str) -> bool {
+    match json_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// 将默认参数合并到模型提供的参数中，只补充模型未提供的键
+///
+/// 模型参数无法解析为对象时原样返回，交由工具自身报错。
+pub fn merge_default_arguments(default_arguments: &str, parameters: &str) -> String {
+    let Ok(Value::Object(defaults)) = serde_json::from_str::<Value>(default_arguments) else {
+        return parameters.to_string();
+    };
+    let mut arguments = if parameters.trim().is_empty() {
+        Map::new()
+    } else {
+        match serde_json::from_str::<Value>(parameters) {
+            Ok(Value::Object(arguments)) => arguments,
+            _ => return parameters.to_string(),
+        }
+    };
+    for (key, value) in defaults {
+        arguments.entry(key).or_insert(value);
+    }
+    Value::Object(arguments).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "properties": {
+            "query": { "type": "string" },
+            "region": { "type": "string", "enum": ["us", "cn", "jp"] },
+            "limit": { "type": "integer" }
+        },
+        "required": ["query"]
+    }"#;
+
+    #[test]
+    fn test_defaults_applied_when_omitted_and_overridden_when_provided() {
+        let defaults = validate_default_arguments(Some(SCHEMA), r#"{"region": "cn", "limit": 5}"#)
+            .unwrap()
+            .unwrap();
+
+        let merged: Value =
+            serde_json::from_str(&merge_default_arguments(&defaults, r#"{"query": "rust"}"#))
+                .unwrap();
+        assert_eq!(merged["query"], "rust");
+        assert_eq!(merged["region"], "cn", "omitted argument should use the default");
+        assert_eq!(merged["limit"], 5);
+
+        let merged: Value = serde_json::from_str(&merge_default_arguments(
+            &defaults,
+            r#"{"query": "rust", "region": "jp"}"#,
+        ))
+        .unwrap();
+        assert_eq!(merged["region"], "jp", "model-provided argument should win");
+        assert_eq!(merged["limit"], 5);
+
+        let merged: Value = serde_json::from_str(&merge_default_arguments(&defaults, "")).unwrap();
+        assert_eq!(merged["region"], "cn");
+    }
+
+    #[test]
+    fn test_validate_default_arguments_against_schema() {
+        assert_eq!(validate_default_arguments(Some(SCHEMA), "").unwrap(), None);
+        assert_eq!(validate_default_arguments(Some(SCHEMA), "{}").unwrap(), None);
+        assert!(validate_default_arguments(Some(SCHEMA), "[1]").is_err());
+        assert!(validate_default_arguments(Some(SCHEMA), r#"{"unknown": 1}"#).is_err());
+        assert!(validate_default_arguments(Some(SCHEMA), r#"{"limit": "5"}"#).is_err());
+        assert!(validate_default_arguments(Some(SCHEMA), r#"{"region": "eu"}"#).is_err());
+        // 没有 schema 时只要求是 JSON 对象
+        assert!(validate_default_arguments(None, r#"{"anything": true}"#).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_merge_keeps_unparsable_parameters() {
+        assert_eq!(merge_default_arguments(r#"{"a": 1}"#, "not json"), "not json");
+        assert_eq!(merge_default_arguments("", r#"{"a":2}"#), r#"{"a":2}"#);
+    }
+}
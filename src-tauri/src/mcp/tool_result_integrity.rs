@@ -0,0 +1,111 @@
+//! 工具结果关联完整性校验
+//!
+//! 多轮工具调用中，工具结果通过 call_id / llm_call_id 与助手声明的工具调用关联。
+//! 发送前确认助手最后一轮声明的每个调用都有且仅有一条结果、且没有多余的孤立结果，
+//! 避免把错配的请求发给提供商后才被拒绝、甚至破坏后续对话。
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// 从助手消息的 tool_calls_json 中提取声明的调用 ID，JSON 缺失或无法解析时返回 None
+pub fn declared_tool_call_ids(tool_calls_json: Option<&str>) -> Option<Vec<String>> {
+    let calls: Vec<Value> = serde_json::from_str(tool_calls_json?).ok()?;
+    Some(
+        calls
+            .iter()
+            .filter_map(|call| call.get("call_id").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// 校验待发送的工具结果与声明的工具调用一一对应
+///
+/// - 每个声明的调用必须有一条结果
+/// - 同一调用不能有多条结果
+/// - 不能提交未声明调用的结果
+pub fn validate_tool_result_correlation(
+    declared_call_ids: &[String],
+    result_call_ids: &[String],
+) -> Result<(), String> {
+    let declared: HashSet<&str> = declared_call_ids.iter().map(String::as_str).collect();
+
+    let mut seen = HashSet::new();
+    let mut duplicated = Vec::new();
+    let mut orphaned = Vec::new();
+    for call_id in result_call_ids {
+        if !seen.insert(call_id.as_str()) {
+            duplicated.push(call_id.as_str());
+        } else if !declared.contains(call_id.as_str()) {
+            orphaned.push(call_id.as_str());
+        }
+    }
+    let missing: Vec<&str> = declared_call_ids
+        .iter()
+        .map(String::as_str)
+        .filter(|call_id| !seen.contains(call_id))
+        .collect();
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("以下工具调用缺少结果: {}", missing.join(", ")));
+    }
+    if !orphaned.is_empty() {
+        problems.push(format!("以下工具结果没有对应的工具调用: {}", orphaned.join(", ")));
+    }
+    if !duplicated.is_empty() {
+        problems.push(format!("以下工具调用存在重复结果: {}", duplicated.join(", ")));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("工具结果与工具调用不匹配，已取消发送。{}", problems.join("；")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matching_results_pass() {
+        assert!(validate_tool_result_correlation(
+            &ids(&["call_1", "call_2"]),
+            &ids(&["call_2", "call_1"])
+        )
+        .is_ok());
+    }
+
+    /// 测试缺少结果与错配的 call_id 会返回校验错误
+    #[test]
+    fn test_missing_and_mismatched_results_are_rejected() {
+        let err = validate_tool_result_correlation(&ids(&["call_1", "call_2"]), &ids(&["call_1"]))
+            .unwrap_err();
+        assert!(err.contains("缺少结果: call_2"), "{}", err);
+
+        let err = validate_tool_result_correlation(
+            &ids(&["call_1", "call_2"]),
+            &ids(&["call_1", "call_x"]),
+        )
+        .unwrap_err();
+        assert!(err.contains("缺少结果: call_2"), "{}", err);
+        assert!(err.contains("没有对应的工具调用: call_x"), "{}", err);
+
+        let err = validate_tool_result_correlation(&ids(&["call_1"]), &ids(&["call_1", "call_1"]))
+            .unwrap_err();
+        assert!(err.contains("重复结果: call_1"), "{}", err);
+    }
+
+    #[test]
+    fn test_declared_tool_call_ids() {
+        let json = r#"[{"call_id":"call_a","fn_name":"search","fn_arguments":{}},{"call_id":"call_b","fn_name":"read","fn_arguments":{}}]"#;
+        assert_eq!(declared_tool_call_ids(Some(json)), Some(ids(&["call_a", "call_b"])));
+        assert_eq!(declared_tool_call_ids(None), None);
+        assert_eq!(declared_tool_call_ids(Some("not json")), None);
+    }
+}
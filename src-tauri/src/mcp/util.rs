@@ -1,8 +1,13 @@
 use crate::db::mcp_db::MCPServer;
+use crate::db::system_db::FeatureConfig;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use tauri::Manager;
 use tracing::{debug, warn};
 
+/// Placeholder prefix referencing a feature config value: ${config:feature_code.key}
+const FEATURE_CONFIG_PLACEHOLDER_PREFIX: &str = "config:";
+
 /// Parse server.headers JSON string into:
 /// - Option<String> Authorization header value (after env placeholder replacement)
 /// - Option<HashMap<String,String>> of all headers (after env placeholder replacement)
@@ -67,6 +72,13 @@ pub fn replace_env_placeholders(input: &str) -> String {
 
 /// Replace ${VAR} placeholders using server env map first, then OS env variables.
 fn replace_env_placeholders_from_maps(input: &str, server_env: &HashMap<String, String>) -> String {
+    replace_placeholders_with(input, |name| {
+        server_env.get(name).cloned().or_else(|| std::env::var(name).ok())
+    })
+}
+
+/// Replace ${NAME} placeholders using `lookup`; keep placeholder if lookup returns None
+fn replace_placeholders_with(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
     let mut out = String::new();
     let chars: Vec<char> = input.chars().collect();
     let mut i = 0;
@@ -74,12 +86,9 @@ fn replace_env_placeholders_from_maps(input: &str, server_env: &HashMap<String,
         if chars[i] == '$' && i + 1 < chars.len() && chars[i + 1] == '{' {
             if let Some(end) = chars[i + 2..].iter().position(|c| *c == '}') {
                 let var_name: String = chars[i + 2..i + 2 + end].iter().collect();
-                if let Some(val) = server_env.get(&var_name) {
-                    out.push_str(val);
-                } else if let Ok(val) = std::env::var(&var_name) {
-                    out.push_str(&val);
-                } else {
-                    out.push_str(&format!("${{{}}}", var_name));
+                match lookup(&var_name) {
+                    Some(val) => out.push_str(&val),
+                    None => out.push_str(&format!("${{{}}}", var_name)),
                 }
                 i += 2 + end + 1; // skip ${VAR}
                 continue;
@@ -91,6 +100,80 @@ fn replace_env_placeholders_from_maps(input: &str, server_env: &HashMap<String,
     out
 }
 
+/// Resolve server.environment_variables for the spawned stdio process. Values may reference
+/// feature config entries via ${config:feature_code.key}, other server env entries or OS env via ${VAR}.
+pub fn resolve_server_env_vars(
+    server: &MCPServer,
+    feature_config: &HashMap<String, HashMap<String, FeatureConfig>>,
+) -> Vec<(String, String)> {
+    let Some(env) = server.environment_variables.as_deref() else {
+        return Vec::new();
+    };
+    let server_env = build_server_env_map(server);
+    parse_env_vars(env)
+        .into_iter()
+        .map(|(key, value)| {
+            let resolved = replace_placeholders_with(&value, |name| {
+                if let Some(reference) = name.strip_prefix(FEATURE_CONFIG_PLACEHOLDER_PREFIX) {
+                    let (feature_code, config_key) = reference.split_once('.')?;
+                    return feature_config
+                        .get(feature_code)
+                        .and_then(|configs| configs.get(config_key))
+                        .map(|config| config.value.clone());
+                }
+                // Do not let an entry resolve to its own unresolved raw value
+                server_env
+                    .get(name)
+                    .filter(|_| name != key)
+                    .cloned()
+                    .or_else(|| std::env::var(name).ok())
+            });
+            if resolved.contains("${") {
+                warn!(key = %key, "Env value contains unresolved ${{...}} placeholder. Check feature config or environment.");
+            }
+            (key, resolved)
+        })
+        .collect()
+}
+
+/// Same as [`resolve_server_env_vars`], reading feature config from app state.
+pub async fn load_server_env_vars(
+    app_handle: &tauri::AppHandle,
+    server: &MCPServer,
+) -> Vec<(String, String)> {
+    match app_handle.try_state::<crate::FeatureConfigState>() {
+        Some(state) => {
+            let feature_config = state.config_feature_map.lock().await;
+            resolve_server_env_vars(server, &feature_config)
+        }
+        None => resolve_server_env_vars(server, &HashMap::new()),
+    }
+}
+
+/// Env vars for logs: keep names only, every value may be a secret.
+pub fn sanitize_env_for_log(env: &[(String, String)]) -> Vec<String> {
+    env.iter().map(|(k, v)| format!("{}=<redacted len={}>", k, v.len())).collect()
+}
+
+/// Parse KEY=VALUE lines, skipping blank lines, `#` comments and entries without a key.
+pub fn parse_env_vars(env: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    for line in env.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((k, v)) = line.split_once('=') {
+            let key = k.trim();
+            if key.is_empty() {
+                continue;
+            }
+            result.push((key.to_string(), v.trim().to_string()));
+        }
+    }
+    result
+}
+
 /// Build a K=V map from server.environment_variables (lines KEY=value). Later entries override earlier.
 fn build_server_env_map(server: &MCPServer) -> HashMap<String, String> {
     let mut map = HashMap::new();
@@ -163,6 +246,9 @@ mod tests {
             is_deletable: true,
             proxy_enabled: false,
             created_time: "2024-01-01T00:00:00Z".to_string(),
+            autostart: false,
+            status: None,
+            last_healthy_at: None,
         }
     }
 
@@ -422,4 +508,85 @@ mod tests {
         assert!(auth.is_none());
         assert!(headers.is_none());
     }
+
+    // ============================================
+    // resolve_server_env_vars Tests
+    // ============================================
+
+    fn feature_config_map(
+        feature_code: &str,
+        key: &str,
+        value: &str,
+    ) -> HashMap<String, HashMap<String, FeatureConfig>> {
+        let config = FeatureConfig {
+            id: None,
+            feature_code: feature_code.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            data_type: "string".to_string(),
+            description: None,
+        };
+        HashMap::from([(feature_code.to_string(), HashMap::from([(key.to_string(), config)]))])
+    }
+
+    #[test]
+    fn test_resolve_server_env_vars_feature_config_reference() {
+        let server = create_test_server(
+            None,
+            Some("GITHUB_TOKEN=${config:mcp_secrets.github_token}\nREGION=us".to_string()),
+        );
+        let config = feature_config_map("mcp_secrets", "github_token", "ghp_123");
+        let env = resolve_server_env_vars(&server, &config);
+        assert_eq!(
+            env,
+            vec![
+                ("GITHUB_TOKEN".to_string(), "ghp_123".to_string()),
+                ("REGION".to_string(), "us".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_server_env_vars_server_env_and_unresolved() {
+        std::env::remove_var("NONEXISTENT_VAR_ENV_ABC");
+        let server = create_test_server(
+            None,
+            Some(
+                "BASE=https://api.example.com\nURL=${BASE}/v1\nMISSING=${config:none.key}\nUNSET=${NONEXISTENT_VAR_ENV_ABC}"
+                    .to_string(),
+            ),
+        );
+        let env = resolve_server_env_vars(&server, &HashMap::new());
+        assert_eq!(env[1], ("URL".to_string(), "https://api.example.com/v1".to_string()));
+        assert_eq!(env[2], ("MISSING".to_string(), "${config:none.key}".to_string()));
+        assert_eq!(env[3], ("UNSET".to_string(), "${NONEXISTENT_VAR_ENV_ABC}".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_server_env_vars_self_reference() {
+        let server =
+            create_test_server(None, Some("AIPP_SELF_REF_TEST=${AIPP_SELF_REF_TEST}".to_string()));
+
+        // The entry must not resolve to its own raw value; unresolved stays a placeholder
+        std::env::remove_var("AIPP_SELF_REF_TEST");
+        let env = resolve_server_env_vars(&server, &HashMap::new());
+        assert_eq!(
+            env,
+            vec![("AIPP_SELF_REF_TEST".to_string(), "${AIPP_SELF_REF_TEST}".to_string())]
+        );
+
+        // A self reference falls through to the OS env, e.g. PATH=${PATH}
+        std::env::set_var("AIPP_SELF_REF_TEST", "/usr/bin");
+        let env = resolve_server_env_vars(&server, &HashMap::new());
+        std::env::remove_var("AIPP_SELF_REF_TEST");
+        assert_eq!(env, vec![("AIPP_SELF_REF_TEST".to_string(), "/usr/bin".to_string())]);
+    }
+
+    #[test]
+    fn test_sanitize_env_for_log_hides_values() {
+        let env = vec![("GITHUB_TOKEN".to_string(), "ghp_123".to_string())];
+        let logged = sanitize_env_for_log(&env);
+        assert_eq!(logged, vec!["GITHUB_TOKEN=<redacted len=7>".to_string()]);
+        assert!(!logged[0].contains("ghp_123"));
+    }
 }
@@ -0,0 +1,26 @@
+//! 数据目录自动备份定时任务
+//!
+//! 随调度器每分钟检查一次，距最近一次备份超过配置的间隔后备份数据库并轮转旧备份。
+//! 最近一次备份时间取自备份目录名，应用重启后不会重复备份。
+
+use chrono::Local;
+use tracing::{debug, info};
+
+use crate::api::backup_api::{is_backup_due, list_backups_in, load_backup_settings, run_backup};
+
+pub async fn run_backup_task(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let settings = load_backup_settings(app_handle).await?;
+    if !settings.enabled {
+        debug!("自动备份未启用，跳过");
+        return Ok(());
+    }
+
+    let latest = list_backups_in(&settings.directory)?.first().map(|backup| backup.created_at);
+    if !is_backup_due(latest, settings.interval, Local::now()) {
+        return Ok(());
+    }
+
+    let backup = run_backup(app_handle, &settings).await?;
+    info!(name = %backup.name, "自动备份完成");
+    Ok(())
+}
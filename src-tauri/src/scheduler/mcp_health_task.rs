@@ -0,0 +1,78 @@
+//! MCP 服务器健康检查定时任务
+//!
+//! 随调度器每分钟判断一次是否到达配置的检查间隔，到达后在后台检查已启用的远程 MCP 服务器。
+
+use std::time::{Duration, Instant};
+
+use crate::api::ai::config::get_mcp_health_check_interval_from_config;
+use crate::mcp::health::check_mcp_servers_health;
+use crate::FeatureConfigState;
+use tauri::Manager;
+use tracing::{debug, warn};
+
+use super::SchedulerState;
+
+/// 调度周期存在少量抖动，预留容差避免间隔等于调度周期时被推迟一整轮
+const TICK_TOLERANCE: Duration = Duration::from_secs(1);
+
+pub async fn run_mcp_health_check(
+    app_handle: &tauri::AppHandle,
+    scheduler_state: &SchedulerState,
+) -> Result<(), String> {
+    let feature_state = app_handle
+        .try_state::<FeatureConfigState>()
+        .ok_or_else(|| "无法获取功能配置状态".to_string())?;
+    let interval = {
+        let config_map = feature_state.config_feature_map.lock().await;
+        get_mcp_health_check_interval_from_config(&config_map)
+    };
+    let Some(interval) = interval else {
+        debug!("MCP 健康检查已关闭，跳过");
+        return Ok(());
+    };
+
+    let now = Instant::now();
+    {
+        let mut last_check = scheduler_state.last_mcp_health_check.lock().await;
+        if !is_health_check_due(*last_check, interval, now) {
+            return Ok(());
+        }
+        *last_check = Some(now);
+    }
+
+    // 连接远程服务器可能较慢，放到后台执行，不阻塞其他定时任务
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = check_mcp_servers_health(&app_handle).await {
+            warn!(error = %e, "MCP 健康检查失败");
+        }
+    });
+    Ok(())
+}
+
+fn is_health_check_due(last_check: Option<Instant>, interval: Duration, now: Instant) -> bool {
+    match last_check {
+        Some(last_check) => now.duration_since(last_check) + TICK_TOLERANCE >= interval,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_check_due_after_interval() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(300);
+        assert!(is_health_check_due(None, interval, now));
+        assert!(!is_health_check_due(Some(now), interval, now + Duration::from_secs(120)));
+        assert!(is_health_check_due(Some(now), interval, now + Duration::from_secs(300)));
+        // 调度周期的少量抖动不应推迟检查
+        assert!(is_health_check_due(
+            Some(now),
+            Duration::from_secs(60),
+            now + Duration::from_millis(59_900)
+        ));
+    }
+}
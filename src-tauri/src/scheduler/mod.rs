@@ -2,13 +2,17 @@
 //!
 //! 提供基于 tokio::time::interval 的定时任务框架，支持注册多个周期性任务。
 
+mod backup_task;
+mod mcp_health_task;
 mod scheduled_task;
 mod summary_task;
 
 use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex as TokioMutex;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 /// 调度器状态，用于管理正在进行的任务
@@ -18,6 +22,10 @@ pub struct SchedulerState {
     pub summarizing_conversations: Arc<TokioMutex<std::collections::HashSet<i64>>>,
     /// 正在执行的定时任务 ID 集合
     pub running_scheduled_tasks: Arc<TokioMutex<HashSet<i64>>>,
+    /// 上一次 MCP 服务器健康检查的时间
+    pub last_mcp_health_check: Arc<TokioMutex<Option<Instant>>>,
+    /// 应用退出时取消，用于停止调度循环
+    shutdown_token: CancellationToken,
 }
 
 impl SchedulerState {
@@ -25,8 +33,15 @@ impl SchedulerState {
         Self {
             summarizing_conversations: Arc::new(TokioMutex::new(std::collections::HashSet::new())),
             running_scheduled_tasks: Arc::new(TokioMutex::new(HashSet::new())),
+            last_mcp_health_check: Arc::new(TokioMutex::new(None)),
+            shutdown_token: CancellationToken::new(),
         }
     }
+
+    /// 停止调度循环，正在执行的周期任务完成后不再开始新的周期
+    pub fn shutdown(&self) {
+        self.shutdown_token.cancel();
+    }
 }
 
 impl Default for SchedulerState {
@@ -49,27 +64,95 @@ pub fn start_scheduler(app_handle: tauri::AppHandle, scheduler_state: SchedulerS
             error!(error = %e, "定时任务执行失败");
         }
 
-        let mut interval = tokio::time::interval(Duration::from_secs(60));
-
-        // 跳过第一次立即执行，等待第一个完整周期
-        interval.tick().await;
-
-        loop {
-            interval.tick().await;
+        let shutdown_token = scheduler_state.shutdown_token.clone();
+        let (app_handle, scheduler_state) = (&app_handle, &scheduler_state);
+        run_until_shutdown(Duration::from_secs(60), shutdown_token, || async move {
             debug!("定时任务调度器：开始执行周期任务");
 
             // 执行对话总结任务
-            if let Err(e) = summary_task::run_summary_task(&app_handle, &scheduler_state).await {
+            if let Err(e) = summary_task::run_summary_task(app_handle, scheduler_state).await {
                 error!(error = %e, "对话总结定时任务执行失败");
             }
 
             if let Err(e) =
-                scheduled_task::run_scheduled_tasks(app_handle.clone(), &scheduler_state).await
+                scheduled_task::run_scheduled_tasks(app_handle.clone(), scheduler_state).await
             {
                 error!(error = %e, "定时任务执行失败");
             }
-        }
+
+            if let Err(e) = mcp_health_task::run_mcp_health_check(app_handle, scheduler_state).await
+            {
+                error!(error = %e, "MCP 健康检查定时任务执行失败");
+            }
+
+            if let Err(e) = backup_task::run_backup_task(app_handle).await {
+                error!(error = %e, "自动备份定时任务执行失败");
+            }
+        })
+        .await;
+
+        info!("定时任务调度器已停止");
     });
 
     info!("定时任务调度器已启动，每分钟执行一次");
 }
+
+/// 按固定周期执行任务，直到收到停止信号
+///
+/// 跳过第一次立即执行，等待第一个完整周期。
+async fn run_until_shutdown<F, Fut>(
+    period: Duration,
+    shutdown_token: CancellationToken,
+    mut tick: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut interval = tokio::time::interval(period);
+    interval.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            _ = interval.tick() => {}
+        }
+        tick().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 测试 shutdown 后调度循环退出且不再执行周期任务
+    #[tokio::test]
+    async fn test_scheduler_loop_stops_on_shutdown() {
+        let state = SchedulerState::new();
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let counter = ticks.clone();
+        let handle = tokio::spawn(run_until_shutdown(
+            Duration::from_millis(10),
+            state.shutdown_token.clone(),
+            move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            },
+        ));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(ticks.load(Ordering::SeqCst) > 0, "loop should tick before shutdown");
+
+        state.shutdown();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("scheduler loop should exit after shutdown")
+            .unwrap();
+
+        let ticks_after_shutdown = ticks.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), ticks_after_shutdown);
+    }
+}
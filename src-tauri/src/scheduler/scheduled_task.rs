@@ -1,19 +1,13 @@
 use chrono::Utc;
 use tracing::{error, info, warn};
 
-use crate::api::scheduled_task_api::{
-    compute_next_run_at_with_config, execute_scheduled_task, ScheduleConfig,
-};
+use crate::api::scheduled_task_api::{execute_scheduled_task, plan_scheduled_run};
 use crate::db::scheduled_task_db::{ScheduledTask, ScheduledTaskDatabase};
 use crate::FeatureConfigState;
 use tauri::Manager;
 
 use super::SchedulerState;
 
-fn parse_json_array(s: &Option<String>) -> Option<Vec<i32>> {
-    s.as_ref().and_then(|v| serde_json::from_str(v).ok())
-}
-
 pub async fn run_scheduled_tasks(
     app_handle: tauri::AppHandle,
     scheduler_state: &SchedulerState,
@@ -61,27 +55,12 @@ async fn process_scheduled_task(
     task: &ScheduledTask,
 ) -> Result<(), String> {
     let now = Utc::now();
-    let next_run_at = if task.schedule_type == "once" {
-        None
-    } else {
-        compute_next_run_at_with_config(
-            ScheduleConfig {
-                schedule_type: &task.schedule_type,
-                interval_value: task.interval_value,
-                interval_unit: task.interval_unit.as_deref(),
-                start_time: task.start_time.as_deref(),
-                week_days: parse_json_array(&task.week_days),
-                month_days: parse_json_array(&task.month_days),
-                run_at: task.run_at,
-            },
-            now,
-        )?
-    };
+    let plan = plan_scheduled_run(task, now)?;
 
     let updated = ScheduledTask {
         is_enabled: if task.schedule_type == "once" { false } else { task.is_enabled },
         last_run_at: Some(now),
-        next_run_at,
+        next_run_at: plan.next_run_at,
         updated_time: now,
         ..task.clone()
     };
@@ -89,7 +68,10 @@ async fn process_scheduled_task(
     let db = ScheduledTaskDatabase::new(app_handle).map_err(|e| e.to_string())?;
     db.update_task(&updated).map_err(|e| e.to_string())?;
 
-    match execute_scheduled_task(app_handle, feature_state, &updated).await {
+    let attempt = task.retry_attempt + 1;
+    match execute_scheduled_task(app_handle, feature_state, &updated, plan.scheduled_time, attempt)
+        .await
+    {
         Ok(result) => {
             if result.notify {
                 info!(task_id = updated.id, "定时任务完成并通知");
@@ -2,7 +2,7 @@
 //!
 //! 每分钟扫描需要总结的对话，并触发总结生成。
 
-use crate::db::conversation_db::ConversationDatabase;
+use crate::db::conversation_db::{ConversationDatabase, SummaryTriggerCriteria};
 use crate::db::system_db::FeatureConfig;
 use crate::errors::AppError;
 use crate::FeatureConfigState;
@@ -12,9 +12,8 @@ use tracing::{debug, error, info, warn};
 
 use super::SchedulerState;
 
-/// 对话空闲时间阈值（秒）
-/// 对话最后一条消息超过此时间后，才会触发总结
-const IDLE_THRESHOLD_SECONDS: i64 = 600; // 10 分钟
+/// 每轮最多查询的候选对话数
+const QUERY_LIMIT: i64 = 10;
 
 /// 同时进行的最大总结任务数
 const MAX_CONCURRENT_SUMMARIES: usize = 3;
@@ -22,7 +21,7 @@ const MAX_CONCURRENT_SUMMARIES: usize = 3;
 /// 执行对话总结定时任务
 ///
 /// 查询需要总结的对话（满足以下条件）：
-/// 1. 最后一条消息时间超过 10 分钟
+/// 1. 满足 `conversation_summary` 配置的触发阈值（空闲时间、消息数、token 数）
 /// 2. 尚未生成过总结
 /// 3. 当前没有正在进行的总结任务
 pub async fn run_summary_task(
@@ -37,8 +36,9 @@ pub async fn run_summary_task(
     }
 
     // 获取需要总结的对话列表
+    let criteria = summary_trigger_criteria(&config_map);
     let conversations_to_summarize =
-        get_conversations_needing_summary(app_handle, scheduler_state).await?;
+        get_conversations_needing_summary(app_handle, scheduler_state, &criteria).await?;
 
     if conversations_to_summarize.is_empty() {
         debug!("没有需要总结的对话");
@@ -70,6 +70,7 @@ pub async fn run_summary_task(
                 &app_handle_clone,
                 conversation_id,
                 config_map_clone,
+                false,
             )
             .await;
 
@@ -114,18 +115,53 @@ fn is_summary_enabled(config_map: &HashMap<String, HashMap<String, FeatureConfig
         .unwrap_or(true) // 默认启用
 }
 
+/// 读取配置项并解析为非负整数，未配置或解析失败时使用默认值
+fn get_config_i64(
+    config_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+    key: &str,
+    default: i64,
+) -> i64 {
+    config_map
+        .get("conversation_summary")
+        .and_then(|fc| fc.get(key))
+        .and_then(|c| c.value.trim().parse::<i64>().ok())
+        .map(|v| v.max(0))
+        .unwrap_or(default)
+}
+
+/// 从功能配置中读取自动总结的触发条件
+///
+/// - `summary_idle_minutes`: 对话空闲多少分钟后才总结，默认 10，0 表示不要求空闲
+/// - `summary_min_messages`: 至少多少条消息才总结，默认 0（不限制）
+/// - `summary_min_tokens`: 至少多少 token 才总结，默认 0（不限制）
+fn summary_trigger_criteria(
+    config_map: &HashMap<String, HashMap<String, FeatureConfig>>,
+) -> SummaryTriggerCriteria {
+    let defaults = SummaryTriggerCriteria::default();
+    SummaryTriggerCriteria {
+        idle_seconds: get_config_i64(
+            config_map,
+            "summary_idle_minutes",
+            defaults.idle_seconds / 60,
+        ) * 60,
+        min_messages: get_config_i64(config_map, "summary_min_messages", defaults.min_messages),
+        min_tokens: get_config_i64(config_map, "summary_min_tokens", defaults.min_tokens),
+    }
+}
+
 /// 获取需要总结的对话列表
 ///
 /// 条件：
-/// 1. 最后一条消息时间超过 IDLE_THRESHOLD_SECONDS
+/// 1. 满足配置的空闲时间、消息数和 token 数阈值
 /// 2. 尚未生成过总结（conversation_summary 表中不存在记录）
 /// 3. 当前不在正在总结的集合中
 async fn get_conversations_needing_summary(
     app_handle: &tauri::AppHandle,
     scheduler_state: &SchedulerState,
+    criteria: &SummaryTriggerCriteria,
 ) -> Result<Vec<i64>, AppError> {
     let conversation_db = ConversationDatabase::new(app_handle).map_err(AppError::from)?;
-    let conn = conversation_db.get_connection().map_err(AppError::from)?;
+    let repo = conversation_db.conversation_summary_repo()?;
 
     // 获取当前正在总结的对话 ID
     let summarizing_ids: Vec<i64> = {
@@ -133,54 +169,54 @@ async fn get_conversations_needing_summary(
         summarizing.iter().copied().collect()
     };
 
-    // 构建排除条件
-    let exclude_clause = if summarizing_ids.is_empty() {
-        String::new()
-    } else {
-        let ids_str: Vec<String> = summarizing_ids.iter().map(|id| id.to_string()).collect();
-        format!(" AND c.id NOT IN ({})", ids_str.join(","))
-    };
-
-    // 查询需要总结的对话
-    // 条件：
-    // 1. 对话中有消息
-    // 2. 最后一条消息时间超过阈值
-    // 3. 没有总结记录
-    // 4. 不在正在总结的集合中
-    let query = format!(
-        r#"
-        SELECT DISTINCT c.id
-        FROM conversation c
-        INNER JOIN message m ON m.conversation_id = c.id
-        LEFT JOIN conversation_summary cs ON cs.conversation_id = c.id
-        WHERE cs.id IS NULL
-          AND (
-              SELECT MAX(m2.created_time)
-              FROM message m2
-              WHERE m2.conversation_id = c.id
-          ) < datetime('now', '-{} seconds')
-          {}
-        ORDER BY c.id DESC
-        LIMIT 10
-        "#,
-        IDLE_THRESHOLD_SECONDS, exclude_clause
-    );
-
-    let mut stmt = conn.prepare(&query).map_err(|e| {
-        error!(error = %e, "准备查询语句失败");
-        AppError::DatabaseError(format!("准备查询语句失败: {}", e))
-    })?;
-
-    let conversation_ids: Vec<i64> = stmt
-        .query_map([], |row| row.get(0))
+    let conversation_ids = repo
+        .list_conversations_needing_summary(criteria, &summarizing_ids, QUERY_LIMIT)
         .map_err(|e| {
-            error!(error = %e, "执行查询失败");
-            AppError::DatabaseError(format!("执行查询失败: {}", e))
-        })?
-        .filter_map(|r| r.ok())
-        .collect();
+            error!(error = %e, "查询需要总结的对话失败");
+            AppError::DatabaseError(format!("查询需要总结的对话失败: {}", e))
+        })?;
 
-    debug!(count = conversation_ids.len(), "查询到需要总结的对话数量");
+    debug!(count = conversation_ids.len(), ?criteria, "查询到需要总结的对话数量");
 
     Ok(conversation_ids)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(values: &[(&str, &str)]) -> HashMap<String, HashMap<String, FeatureConfig>> {
+        let feature = values
+            .iter()
+            .map(|(key, value)| {
+                (
+                    key.to_string(),
+                    FeatureConfig {
+                        id: None,
+                        feature_code: "conversation_summary".to_string(),
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        data_type: "string".to_string(),
+                        description: None,
+                    },
+                )
+            })
+            .collect();
+        HashMap::from([("conversation_summary".to_string(), feature)])
+    }
+
+    #[test]
+    fn test_trigger_criteria_from_config() {
+        assert_eq!(summary_trigger_criteria(&HashMap::new()), SummaryTriggerCriteria::default());
+
+        let criteria = summary_trigger_criteria(&config(&[
+            ("summary_min_messages", "50"),
+            ("summary_idle_minutes", "0"),
+            ("summary_min_tokens", "abc"),
+        ]));
+        assert_eq!(
+            criteria,
+            SummaryTriggerCriteria { idle_seconds: 0, min_messages: 50, min_tokens: 0 }
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! 应用退出时的有序关闭流程
+//!
+//! 由托盘“退出”和系统退出触发：结束进行中的生成（保留已生成的内容）、停止调度器与技能目录监听、
+//! 关闭搜索浏览器池以及 MCP / ACP / Copilot 子进程，最后将数据库 WAL 合并回主库文件。
+
+use crate::api::copilot_lsp::stop_copilot_lsp;
+use crate::db::conversation_db::ConversationDatabase;
+use crate::db::mcp_db::MCPDatabase;
+use crate::mcp::builtin_mcp::search::handler::shutdown_search_browser_pool;
+use crate::mcp::execution_api::{
+    cancel_all_tool_call_executions, cancel_mcp_tool_calls_by_conversation,
+};
+use crate::scheduler::SchedulerState;
+use crate::skills::watcher::stop_skill_watcher;
+use crate::state::message_token::MessageTokenManager;
+use crate::AcpSessionState;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// 关闭流程的最长等待时间，超时后直接退出，避免卡住退出
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 中止生成任务后留给任务写入剩余内容的时间
+const GENERATION_FLUSH_GRACE: Duration = Duration::from_millis(200);
+
+/// 执行有序关闭流程，超时或出错都不会阻止退出
+pub async fn graceful_shutdown(app_handle: &AppHandle) {
+    info!("Graceful shutdown started");
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, run_shutdown_steps(app_handle)).await.is_err() {
+        warn!(timeout_secs = SHUTDOWN_TIMEOUT.as_secs(), "Graceful shutdown timed out");
+    }
+    info!("Graceful shutdown finished");
+}
+
+async fn run_shutdown_steps(app_handle: &AppHandle) {
+    #[cfg(desktop)]
+    crate::launch::record_open_windows(app_handle);
+
+    if let Some(scheduler_state) = app_handle.try_state::<SchedulerState>() {
+        scheduler_state.shutdown();
+    }
+    stop_skill_watcher();
+
+    finalize_active_generations(app_handle).await;
+
+    let cancelled_tool_calls = cancel_all_tool_call_executions().await;
+    if cancelled_tool_calls > 0 {
+        info!(count = cancelled_tool_calls, "Cancelled running MCP tool calls");
+    }
+
+    // 丢弃 ACP 会话句柄后会话线程退出，子进程随之结束（kill_on_drop）
+    if let Some(acp_state) = app_handle.try_state::<AcpSessionState>() {
+        acp_state.sessions.lock().await.clear();
+    }
+
+    if let Err(e) = stop_copilot_lsp(app_handle.clone()).await {
+        warn!(error = %e, "Failed to stop Copilot language server");
+    }
+
+    if let Err(e) = shutdown_search_browser_pool().await {
+        warn!(error = %e, "Failed to shutdown search browser pool");
+    }
+
+    flush_databases(app_handle);
+}
+
+/// 取消进行中的生成：中止任务（流式内容在任务释放时写入），并为未结束的消息补上 finish_time
+async fn finalize_active_generations(app_handle: &AppHandle) {
+    let Some(token_manager) = app_handle.try_state::<MessageTokenManager>() else {
+        return;
+    };
+    let conversation_ids = token_manager.active_conversation_ids().await;
+    if conversation_ids.is_empty() {
+        return;
+    }
+    info!(count = conversation_ids.len(), "Cancelling active generations");
+
+    for conversation_id in &conversation_ids {
+        token_manager.cancel_request(*conversation_id).await;
+        if let Err(e) = cancel_mcp_tool_calls_by_conversation(app_handle, *conversation_id).await {
+            warn!(conversation_id, error = %e, "Failed to cancel MCP tool calls on shutdown");
+        }
+    }
+    tokio::time::sleep(GENERATION_FLUSH_GRACE).await;
+
+    let Ok(db) = ConversationDatabase::new(app_handle) else {
+        return;
+    };
+    let Ok(message_repo) = db.message_repo() else {
+        return;
+    };
+    for conversation_id in conversation_ids {
+        if let Err(e) = message_repo.finish_pending_messages(conversation_id) {
+            warn!(conversation_id, error = %e, "Failed to finish pending messages on shutdown");
+        }
+    }
+}
+
+/// 合并对话库和 MCP 库的 WAL，确保退出前写入落盘
+fn flush_databases(app_handle: &AppHandle) {
+    match ConversationDatabase::new(app_handle) {
+        Ok(db) => {
+            if let Err(e) = db.checkpoint() {
+                warn!(error = %e, "Failed to checkpoint conversation database");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to open conversation database for checkpoint"),
+    }
+    match MCPDatabase::new(app_handle) {
+        Ok(db) => {
+            if let Err(e) = db.checkpoint() {
+                warn!(error = %e, "Failed to checkpoint MCP database");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to open MCP database for checkpoint"),
+    }
+}
@@ -0,0 +1,161 @@
+//! Skill content cache - parsed SKILL.md files keyed by path + mtime
+//!
+//! Shared by the scanner (metadata), `get_skill_content` and prompt assembly so a
+//! skill referenced repeatedly in a conversation is read from disk only once per
+//! file revision. Parse failures are cached too, so a corrupt file is not re-read
+//! until its mtime changes.
+
+use crate::skills::types::SkillMetadata;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use tracing::debug;
+
+static SKILL_CONTENT_CACHE: OnceLock<SkillContentCache> = OnceLock::new();
+
+/// Process-wide skill content cache
+pub fn skill_content_cache() -> &'static SkillContentCache {
+    SKILL_CONTENT_CACHE.get_or_init(SkillContentCache::new)
+}
+
+/// A parsed skill file (frontmatter + body), without additional files
+#[derive(Debug, Clone)]
+pub struct ParsedSkillFile {
+    pub metadata: SkillMetadata,
+    pub body: String,
+}
+
+struct CacheEntry {
+    modified: SystemTime,
+    parsed: Result<ParsedSkillFile, String>,
+}
+
+pub struct SkillContentCache {
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+    disk_reads: AtomicUsize,
+}
+
+impl SkillContentCache {
+    pub fn new() -> Self {
+        Self { entries: Mutex::new(HashMap::new()), disk_reads: AtomicUsize::new(0) }
+    }
+
+    /// Get the parsed skill file, re-reading only when the file's mtime changed
+    pub fn get_or_parse(
+        &self,
+        file_path: &Path,
+        parse: impl FnOnce(&str, &Path) -> Result<ParsedSkillFile, String>,
+    ) -> Result<ParsedSkillFile, String> {
+        let modified = match fs::metadata(file_path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(_) => {
+                // File is gone or mtime is unsupported: drop any stale entry and don't cache
+                self.invalidate(file_path);
+                return self.read_and_parse(file_path, parse);
+            }
+        };
+
+        if let Some(entry) = self.entries.lock().unwrap().get(file_path) {
+            if entry.modified == modified {
+                return entry.parsed.clone();
+            }
+            debug!("Skill file changed on disk, invalidating cache: {:?}", file_path);
+        }
+
+        let parsed = self.read_and_parse(file_path, parse);
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(file_path.to_path_buf(), CacheEntry { modified, parsed: parsed.clone() });
+        parsed
+    }
+
+    /// Remove a single file from the cache (e.g. from a file watcher)
+    pub fn invalidate(&self, file_path: &Path) {
+        self.entries.lock().unwrap().remove(file_path);
+    }
+
+    /// Number of times a skill file was actually read from disk
+    #[cfg(test)]
+    pub fn disk_reads(&self) -> usize {
+        self.disk_reads.load(Ordering::Relaxed)
+    }
+
+    fn read_and_parse(
+        &self,
+        file_path: &Path,
+        parse: impl FnOnce(&str, &Path) -> Result<ParsedSkillFile, String>,
+    ) -> Result<ParsedSkillFile, String> {
+        self.disk_reads.fetch_add(1, Ordering::Relaxed);
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to read skill file: {}", e))?;
+        parse(&content, file_path)
+    }
+}
+
+impl Default for SkillContentCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::Duration;
+    use tempfile::NamedTempFile;
+
+    fn parse_body(content: &str, _path: &Path) -> Result<ParsedSkillFile, String> {
+        if content.contains("CORRUPT") {
+            return Err("corrupt skill file".to_string());
+        }
+        Ok(ParsedSkillFile { metadata: SkillMetadata::default(), body: content.to_string() })
+    }
+
+    fn set_mtime(file: &NamedTempFile, offset_secs: u64) {
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + offset_secs);
+        file.as_file().set_modified(mtime).unwrap();
+    }
+
+    #[test]
+    fn test_cache_hit_and_mtime_change() {
+        let cache = SkillContentCache::new();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"first version").unwrap();
+        set_mtime(&file, 0);
+
+        let first = cache.get_or_parse(file.path(), parse_body).unwrap();
+        let second = cache.get_or_parse(file.path(), parse_body).unwrap();
+        assert_eq!(first.body, "first version");
+        assert_eq!(second.body, "first version");
+        assert_eq!(cache.disk_reads(), 1, "cache hit should not read the file again");
+
+        fs::write(file.path(), "second version").unwrap();
+        set_mtime(&file, 60);
+
+        let third = cache.get_or_parse(file.path(), parse_body).unwrap();
+        assert_eq!(third.body, "second version");
+        assert_eq!(cache.disk_reads(), 2, "mtime change should bust the cache");
+    }
+
+    #[test]
+    fn test_corrupt_file_is_cached_until_changed() {
+        let cache = SkillContentCache::new();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"CORRUPT").unwrap();
+        set_mtime(&file, 0);
+
+        assert!(cache.get_or_parse(file.path(), parse_body).is_err());
+        assert!(cache.get_or_parse(file.path(), parse_body).is_err());
+        assert_eq!(cache.disk_reads(), 1);
+
+        fs::write(file.path(), "fixed").unwrap();
+        set_mtime(&file, 60);
+        assert_eq!(cache.get_or_parse(file.path(), parse_body).unwrap().body, "fixed");
+        assert_eq!(cache.disk_reads(), 2);
+    }
+}
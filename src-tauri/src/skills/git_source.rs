@@ -0,0 +1,247 @@
+//! Git skill sources - subscribe to a shared skills repository
+//!
+//! Each source is checked out into `{app_data}/skill_sources/git_{id}` with the
+//! system `git` binary. A first clone goes to a temporary directory and is only
+//! moved into place on success; updates fetch before touching the working tree,
+//! so a failed pull keeps the last good checkout and just reports the error.
+
+use crate::skills::types::{GitSkillSource, SkillSourceConfig};
+use base64::Engine;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+/// Directory under app data that holds git source checkouts
+const GIT_SOURCES_DIR: &str = "skill_sources";
+
+/// Upper bound for a single clone/fetch so an unreachable host can't hang the UI
+const GIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Checkout directory of a git source
+pub fn checkout_dir(app_data_dir: &Path, source_id: i64) -> PathBuf {
+    app_data_dir.join(GIT_SOURCES_DIR).join(format!("git_{}", source_id))
+}
+
+/// Directory to scan inside a checkout: `skills/` when the repository has one, otherwise the root
+fn scan_dir(checkout: &Path) -> PathBuf {
+    let skills_dir = checkout.join("skills");
+    if skills_dir.is_dir() {
+        skills_dir
+    } else {
+        checkout.to_path_buf()
+    }
+}
+
+/// Build the scanner source config for a git source. Only SKILL.md files count as
+/// single-file skills, so a README at the repository root is not picked up.
+pub fn source_config(source: &GitSkillSource, app_data_dir: &Path) -> SkillSourceConfig {
+    let checkout = checkout_dir(app_data_dir, source.id);
+    SkillSourceConfig {
+        source_type: source.source_type(),
+        display_name: source.name.clone(),
+        paths: vec![scan_dir(&checkout).to_string_lossy().to_string()],
+        file_pattern: "SKILL.md".to_string(),
+        is_enabled: true,
+        is_builtin: false,
+    }
+}
+
+/// Check that a repository URL is something we are willing to hand to git
+pub fn validate_repo_url(url: &str) -> Result<(), String> {
+    let url = url.trim();
+    let supported = ["https://", "http://", "ssh://", "git@"];
+    if url.is_empty() || !supported.iter().any(|prefix| url.starts_with(prefix)) {
+        return Err(format!("Unsupported repository URL (use https, ssh or git@): {}", url));
+    }
+    if url.chars().any(char::is_whitespace) {
+        return Err("Repository URL must not contain whitespace".to_string());
+    }
+    Ok(())
+}
+
+/// The token travels as an HTTP header, so only hand it to git over https. SSH
+/// remotes authenticate with keys and plain http would leak it in cleartext.
+pub fn ensure_token_transport(url: &str, token: Option<&str>) -> Result<(), String> {
+    if token.is_some() && !url.trim().to_ascii_lowercase().starts_with("https://") {
+        return Err("Access tokens can only be used with https repository URLs".to_string());
+    }
+    Ok(())
+}
+
+/// Clone the repository, or pull the tracked branch into an existing checkout
+pub async fn sync_git_source(source: &GitSkillSource, checkout: &Path) -> Result<(), String> {
+    let token = source.auth_token.as_deref().filter(|token| !token.is_empty());
+    ensure_token_transport(&source.repo_url, token)?;
+    if checkout.join(".git").is_dir() {
+        pull(source, checkout, token).await
+    } else {
+        clone(source, checkout, token).await
+    }
+}
+
+async fn clone(
+    source: &GitSkillSource,
+    checkout: &Path,
+    token: Option<&str>,
+) -> Result<(), String> {
+    let parent = checkout.parent().ok_or_else(|| "Invalid checkout directory".to_string())?;
+    std::fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create skill sources directory: {}", e))?;
+
+    let temp_dir = checkout.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    let temp_path = temp_dir.to_string_lossy().to_string();
+    let mut args = vec!["clone", "--depth", "1"];
+    if let Some(branch) = source.branch.as_deref() {
+        args.extend(["--branch", branch]);
+    }
+    args.extend(["--", source.repo_url.trim(), temp_path.as_str()]);
+
+    if let Err(e) = run_git(&args, None, token).await {
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        return Err(e);
+    }
+
+    // Leftovers from an interrupted checkout are not a git repository, replace them
+    if checkout.exists() {
+        std::fs::remove_dir_all(checkout)
+            .map_err(|e| format!("Failed to replace checkout directory: {}", e))?;
+    }
+    std::fs::rename(&temp_dir, checkout).map_err(|e| {
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        format!("Failed to move checkout into place: {}", e)
+    })?;
+
+    info!(source_id = source.id, "Cloned git skill source into {}", checkout.display());
+    Ok(())
+}
+
+async fn pull(source: &GitSkillSource, checkout: &Path, token: Option<&str>) -> Result<(), String> {
+    let branch = source.branch.as_deref().unwrap_or("HEAD");
+    // The working tree is only touched after the fetch succeeded
+    run_git(&["fetch", "--depth", "1", "origin", branch], Some(checkout), token).await?;
+    run_git(&["reset", "--hard", "FETCH_HEAD"], Some(checkout), None).await?;
+    run_git(&["clean", "-fd"], Some(checkout), None).await?;
+
+    info!(source_id = source.id, "Updated git skill source at {}", checkout.display());
+    Ok(())
+}
+
+/// Run git without prompting; the token is passed as an HTTP header through the
+/// environment so it never shows up in the process arguments or in the remote URL
+async fn run_git(args: &[&str], cwd: Option<&Path>, token: Option<&str>) -> Result<(), String> {
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    if let Some(token) = token {
+        command
+            .env("GIT_CONFIG_COUNT", "1")
+            .env("GIT_CONFIG_KEY_0", "http.extraHeader")
+            .env("GIT_CONFIG_VALUE_0", auth_header(token));
+    }
+    #[cfg(windows)]
+    {
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    debug!("Running git {}", args.first().unwrap_or(&""));
+    let output = match tokio::time::timeout(GIT_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("Failed to run git (is it installed?): {}", e)),
+        Err(_) => return Err(format!("git {} timed out", args.first().unwrap_or(&""))),
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = stderr.trim();
+    let message = if message.is_empty() { "unknown error" } else { message };
+    Err(redact(&format!("git {} failed: {}", args.first().unwrap_or(&""), message), token))
+}
+
+/// Basic auth header accepted by GitHub, GitLab and Gitea for personal access tokens
+fn auth_header(token: &str) -> String {
+    let credentials =
+        base64::engine::general_purpose::STANDARD.encode(format!("x-access-token:{}", token));
+    format!("Authorization: Basic {}", credentials)
+}
+
+fn redact(message: &str, token: Option<&str>) -> String {
+    match token {
+        Some(token) => message.replace(token, "***"),
+        None => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(id: i64) -> GitSkillSource {
+        GitSkillSource {
+            id,
+            name: "Team Skills".to_string(),
+            repo_url: "https://github.com/acme/skills.git".to_string(),
+            branch: None,
+            auth_token: None,
+            has_auth_token: false,
+            last_synced_at: None,
+            last_error: None,
+            created_time: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_repo_url() {
+        assert!(validate_repo_url("https://github.com/acme/skills.git").is_ok());
+        assert!(validate_repo_url("git@github.com:acme/skills.git").is_ok());
+        assert!(validate_repo_url("--upload-pack=touch /tmp/x").is_err());
+        assert!(validate_repo_url("file:///etc").is_err());
+        assert!(validate_repo_url("https://github.com/acme/skills.git --depth").is_err());
+    }
+
+    #[test]
+    fn test_source_config_prefers_skills_dir() {
+        let app_data = tempfile::tempdir().unwrap();
+        let checkout = checkout_dir(app_data.path(), 3);
+        std::fs::create_dir_all(&checkout).unwrap();
+
+        let config = source_config(&source(3), app_data.path());
+        assert_eq!(config.source_type.as_str(), "git_3");
+        assert_eq!(config.display_name, "Team Skills");
+        assert_eq!(config.paths, vec![checkout.to_string_lossy().to_string()]);
+
+        std::fs::create_dir_all(checkout.join("skills")).unwrap();
+        let config = source_config(&source(3), app_data.path());
+        assert_eq!(config.paths, vec![checkout.join("skills").to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn test_token_requires_https() {
+        assert!(ensure_token_transport("https://github.com/acme/skills.git", Some("t")).is_ok());
+        assert!(ensure_token_transport("http://git.example.com/skills.git", None).is_ok());
+        assert!(ensure_token_transport("http://git.example.com/skills.git", Some("t")).is_err());
+        assert!(ensure_token_transport("git@github.com:acme/skills.git", Some("t")).is_err());
+    }
+
+    #[test]
+    fn test_token_is_redacted() {
+        let header = auth_header("ghp_secret");
+        assert!(header.starts_with("Authorization: Basic "));
+        assert!(!header.contains("ghp_secret"));
+        assert_eq!(
+            redact("fatal: could not read ghp_secret", Some("ghp_secret")),
+            "fatal: could not read ***"
+        );
+    }
+}
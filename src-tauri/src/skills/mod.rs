@@ -5,12 +5,17 @@
 //! - AIPP internal skills directory
 //! - Claude Code (~/.claude/agents/, ~/.claude/rules/)
 //! - Codex CLI
+//! - Git repositories (shared team skills, cloned into the app data dir)
 //! - Custom user-defined sources
 
+pub mod cache;
+pub mod git_source;
+pub mod params;
 pub mod parser;
 pub mod prompt;
 pub mod scanner;
 pub mod types;
+pub mod watcher;
 
 // Re-exports for convenience
 pub use prompt::{collect_skills_info_for_assistant, format_skills_prompt};
@@ -0,0 +1,255 @@
+//! Skill parameter validation - checks per-assistant parameter values against the
+//! schema declared in SKILL.md before they are saved or injected into the prompt
+
+use crate::skills::types::{SkillParameter, SkillParameterType};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Error code returned by `update_assistant_skill_config` when validation fails
+pub const SKILL_PARAMETERS_INVALID: &str = "SKILL_PARAMETERS_INVALID";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillParameterErrorKind {
+    /// The skill does not declare this parameter
+    UnknownKey,
+    /// The value does not match the declared type
+    TypeMismatch,
+    /// The value is not one of the declared options
+    InvalidOption,
+    /// A required parameter without a default has no value
+    MissingRequired,
+}
+
+/// A single validation failure, keyed by parameter name
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SkillParameterError {
+    pub key: String,
+    pub kind: SkillParameterErrorKind,
+    /// Declared type, when the parameter is known
+    pub expected: Option<SkillParameterType>,
+    pub message: String,
+}
+
+/// Structured error payload, serialized to JSON as the command's error string
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillParameterValidationError {
+    pub code: &'static str,
+    pub skill_identifier: String,
+    pub errors: Vec<SkillParameterError>,
+}
+
+impl SkillParameterValidationError {
+    pub fn new(skill_identifier: &str, errors: Vec<SkillParameterError>) -> Self {
+        Self {
+            code: SKILL_PARAMETERS_INVALID,
+            skill_identifier: skill_identifier.to_string(),
+            errors,
+        }
+    }
+
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| SKILL_PARAMETERS_INVALID.to_string())
+    }
+}
+
+fn matches_type(param_type: SkillParameterType, value: &Value) -> bool {
+    match param_type {
+        SkillParameterType::String => value.is_string(),
+        SkillParameterType::Number => value.is_number(),
+        SkillParameterType::Integer => value.is_i64() || value.is_u64(),
+        SkillParameterType::Boolean => value.is_boolean(),
+    }
+}
+
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Validate parameter values against a skill's schema. `null` clears a value.
+/// Returns every failure at once so the UI can mark all offending fields.
+pub fn validate_skill_parameters(
+    schema: &[SkillParameter],
+    values: &Map<String, Value>,
+) -> Result<(), Vec<SkillParameterError>> {
+    let mut errors = Vec::new();
+
+    for (key, value) in values {
+        let Some(parameter) = schema.iter().find(|p| &p.name == key) else {
+            errors.push(SkillParameterError {
+                key: key.clone(),
+                kind: SkillParameterErrorKind::UnknownKey,
+                expected: None,
+                message: format!("Unknown parameter '{}'", key),
+            });
+            continue;
+        };
+        if value.is_null() {
+            continue;
+        }
+        if !matches_type(parameter.param_type, value) {
+            errors.push(SkillParameterError {
+                key: key.clone(),
+                kind: SkillParameterErrorKind::TypeMismatch,
+                expected: Some(parameter.param_type),
+                message: format!(
+                    "Parameter '{}' expects {}, got {}",
+                    key,
+                    parameter.param_type.as_str(),
+                    value_type_name(value)
+                ),
+            });
+            continue;
+        }
+        if let Some(text) = value.as_str() {
+            if !parameter.options.is_empty() && !parameter.options.iter().any(|o| o == text) {
+                errors.push(SkillParameterError {
+                    key: key.clone(),
+                    kind: SkillParameterErrorKind::InvalidOption,
+                    expected: Some(parameter.param_type),
+                    message: format!(
+                        "Parameter '{}' must be one of: {}",
+                        key,
+                        parameter.options.join(", ")
+                    ),
+                });
+            }
+        }
+    }
+
+    for parameter in schema.iter().filter(|p| p.required && p.default.is_none()) {
+        if values.get(&parameter.name).map_or(true, Value::is_null) {
+            errors.push(SkillParameterError {
+                key: parameter.name.clone(),
+                kind: SkillParameterErrorKind::MissingRequired,
+                expected: Some(parameter.param_type),
+                message: format!("Parameter '{}' is required", parameter.name),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Effective values to inject: declared defaults overridden by configured values.
+/// Values that no longer validate (the SKILL.md changed after they were saved) are dropped.
+pub fn effective_skill_parameters(
+    schema: &[SkillParameter],
+    values: &Map<String, Value>,
+) -> Map<String, Value> {
+    let mut effective = Map::new();
+    for parameter in schema {
+        let configured = values.get(&parameter.name).filter(|value| {
+            !value.is_null()
+                && matches_type(parameter.param_type, value)
+                && (parameter.options.is_empty()
+                    || value
+                        .as_str()
+                        .map_or(true, |text| parameter.options.iter().any(|o| o == text)))
+        });
+        if let Some(value) = configured.or(parameter.default.as_ref()) {
+            effective.insert(parameter.name.clone(), value.clone());
+        }
+    }
+    effective
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Vec<SkillParameter> {
+        vec![
+            SkillParameter {
+                name: "max_results".to_string(),
+                param_type: SkillParameterType::Number,
+                description: None,
+                required: true,
+                default: None,
+                options: Vec::new(),
+            },
+            SkillParameter {
+                name: "language".to_string(),
+                param_type: SkillParameterType::String,
+                description: None,
+                required: false,
+                default: Some(json!("zh")),
+                options: vec!["zh".to_string(), "en".to_string()],
+            },
+        ]
+    }
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_string_where_number_expected_is_rejected() {
+        let errors = validate_skill_parameters(&schema(), &values(json!({ "max_results": "10" })))
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].key, "max_results");
+        assert_eq!(errors[0].kind, SkillParameterErrorKind::TypeMismatch);
+        assert_eq!(errors[0].expected, Some(SkillParameterType::Number));
+    }
+
+    #[test]
+    fn test_unknown_key_and_missing_required() {
+        let errors =
+            validate_skill_parameters(&schema(), &values(json!({ "max_result": 10 }))).unwrap_err();
+        let kinds: Vec<_> = errors.iter().map(|e| (e.key.as_str(), e.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("max_result", SkillParameterErrorKind::UnknownKey),
+                ("max_results", SkillParameterErrorKind::MissingRequired),
+            ]
+        );
+
+        assert!(validate_skill_parameters(&schema(), &values(json!({ "max_results": 10 }))).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_option() {
+        let errors = validate_skill_parameters(
+            &schema(),
+            &values(json!({ "max_results": 3, "language": "fr" })),
+        )
+        .unwrap_err();
+        assert_eq!(errors[0].kind, SkillParameterErrorKind::InvalidOption);
+    }
+
+    #[test]
+    fn test_effective_parameters_apply_defaults_and_drop_stale_values() {
+        let effective = effective_skill_parameters(
+            &schema(),
+            &values(json!({ "max_results": "stale", "removed": true })),
+        );
+        assert_eq!(Value::Object(effective), json!({ "language": "zh" }));
+    }
+
+    #[test]
+    fn test_error_payload_is_structured() {
+        let error = SkillParameterValidationError::new(
+            "agents:search",
+            validate_skill_parameters(&schema(), &values(json!({ "max_results": "10" })))
+                .unwrap_err(),
+        );
+        let payload: Value = serde_json::from_str(&error.to_json_string()).unwrap();
+        assert_eq!(payload["code"], SKILL_PARAMETERS_INVALID);
+        assert_eq!(payload["errors"][0]["kind"], "type_mismatch");
+        assert_eq!(payload["errors"][0]["expected"], "number");
+    }
+}
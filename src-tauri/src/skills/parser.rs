@@ -1,6 +1,9 @@
 //! SKILL.md parser - extracts YAML frontmatter and content
 
-use crate::skills::types::{SkillContent, SkillFile, SkillMetadata};
+use crate::skills::cache::{skill_content_cache, ParsedSkillFile};
+use crate::skills::types::{
+    SkillContent, SkillFile, SkillMetadata, SkillParameter, SkillParameterType,
+};
 use std::fs;
 use std::path::Path;
 use tracing::{debug, warn};
@@ -12,10 +15,7 @@ impl SkillParser {
     /// Parse only the metadata (frontmatter) from a skill file
     /// This is the fast path - used for listing skills
     pub fn parse_metadata(file_path: &Path) -> Result<SkillMetadata, String> {
-        let content = fs::read_to_string(file_path)
-            .map_err(|e| format!("Failed to read skill file: {}", e))?;
-
-        Self::extract_metadata(&content, file_path)
+        Self::parse_cached(file_path).map(|parsed| parsed.metadata)
     }
 
     /// Parse the full content of a skill file including additional files
@@ -23,11 +23,7 @@ impl SkillParser {
         file_path: &Path,
         identifier: &str,
     ) -> Result<(SkillMetadata, SkillContent), String> {
-        let content = fs::read_to_string(file_path)
-            .map_err(|e| format!("Failed to read skill file: {}", e))?;
-
-        let metadata = Self::extract_metadata(&content, file_path)?;
-        let body = Self::extract_body(&content);
+        let ParsedSkillFile { metadata, body } = Self::parse_cached(file_path)?;
 
         // Load additional files if specified
         let additional_files = Self::load_additional_files(file_path, &metadata.requires_files)?;
@@ -38,6 +34,16 @@ impl SkillParser {
         Ok((metadata, skill_content))
     }
 
+    /// Parse frontmatter and body through the shared path + mtime cache
+    fn parse_cached(file_path: &Path) -> Result<ParsedSkillFile, String> {
+        skill_content_cache().get_or_parse(file_path, |content, path| {
+            Ok(ParsedSkillFile {
+                metadata: Self::extract_metadata(content, path)?,
+                body: Self::extract_body(content),
+            })
+        })
+    }
+
     /// Extract metadata from content string
     fn extract_metadata(content: &str, file_path: &Path) -> Result<SkillMetadata, String> {
         let trimmed = content.trim_start();
@@ -64,7 +70,8 @@ impl SkillParser {
     fn parse_yaml_frontmatter(yaml: &str, file_path: &Path) -> Result<SkillMetadata, String> {
         let mut metadata = SkillMetadata::default();
 
-        for line in yaml.lines() {
+        let mut lines = yaml.lines().peekable();
+        while let Some(line) = lines.next() {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') {
                 continue;
@@ -75,6 +82,16 @@ impl SkillParser {
                 let value = value.trim().trim_matches('"').trim_matches('\'');
 
                 match key.as_str() {
+                    "parameters" if value.is_empty() => {
+                        // Block list: indented "- name: ..." items
+                        let mut block = Vec::new();
+                        while let Some(next) = lines
+                            .next_if(|l| l.starts_with([' ', '\t', '-']) || l.trim().is_empty())
+                        {
+                            block.push(next);
+                        }
+                        metadata.parameters = Self::parse_parameters(&block, file_path);
+                    }
                     "name" => metadata.name = Some(value.to_string()),
                     "description" => metadata.description = Some(value.to_string()),
                     "version" => metadata.version = Some(value.to_string()),
@@ -100,6 +117,89 @@ impl SkillParser {
         Ok(metadata)
     }
 
+    /// Parse the `parameters:` block list. Items with a missing name or an unknown type
+    /// are skipped with a warning, so a typo never breaks the whole skill.
+    fn parse_parameters(block: &[&str], file_path: &Path) -> Vec<SkillParameter> {
+        let mut items: Vec<Vec<(String, String)>> = Vec::new();
+        for line in block {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = match line.strip_prefix('-') {
+                Some(rest) => {
+                    items.push(Vec::new());
+                    rest.trim()
+                }
+                None => line,
+            };
+            if let (Some(item), Some((key, value))) = (items.last_mut(), entry.split_once(':')) {
+                item.push((
+                    key.trim().to_lowercase(),
+                    value.trim().trim_matches('"').trim_matches('\'').to_string(),
+                ));
+            }
+        }
+
+        let mut parameters: Vec<SkillParameter> = Vec::new();
+        for fields in items {
+            let field = |name: &str| {
+                fields.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+            };
+            let Some(name) = field("name").filter(|name| !name.is_empty()) else {
+                warn!("Skill parameter without a name in {:?}", file_path);
+                continue;
+            };
+            let type_name = field("type").unwrap_or("string");
+            let Some(param_type) = SkillParameterType::parse(type_name) else {
+                warn!(
+                    "Unknown type '{}' for skill parameter '{}' in {:?}",
+                    type_name, name, file_path
+                );
+                continue;
+            };
+            if parameters.iter().any(|p| p.name == name) {
+                warn!("Duplicate skill parameter '{}' in {:?}", name, file_path);
+                continue;
+            }
+            let default = field("default").filter(|value| !value.is_empty()).and_then(|value| {
+                let parsed = Self::parse_parameter_value(param_type, value);
+                if parsed.is_none() {
+                    warn!(
+                        "Invalid default '{}' for skill parameter '{}' in {:?}",
+                        value, name, file_path
+                    );
+                }
+                parsed
+            });
+
+            parameters.push(SkillParameter {
+                name: name.to_string(),
+                param_type,
+                description: field("description").map(|value| value.to_string()),
+                required: field("required").is_some_and(|value| value.eq_ignore_ascii_case("true")),
+                default,
+                options: field("options").map(Self::parse_yaml_array).unwrap_or_default(),
+            });
+        }
+        parameters
+    }
+
+    /// Parse a scalar frontmatter value as the given parameter type
+    fn parse_parameter_value(
+        param_type: SkillParameterType,
+        value: &str,
+    ) -> Option<serde_json::Value> {
+        match param_type {
+            SkillParameterType::String => Some(serde_json::Value::from(value)),
+            SkillParameterType::Number => {
+                value.parse::<f64>().ok().and_then(serde_json::Number::from_f64).map(Into::into)
+            }
+            SkillParameterType::Integer => value.parse::<i64>().ok().map(Into::into),
+            SkillParameterType::Boolean => value.parse::<bool>().ok().map(Into::into),
+        }
+    }
+
     /// Parse YAML array value (inline format: [item1, item2] or item1, item2)
     fn parse_yaml_array(value: &str) -> Vec<String> {
         let value = value.trim();
@@ -151,6 +251,7 @@ impl SkillParser {
             author: None,
             tags: Vec::new(),
             requires_files: Vec::new(),
+            parameters: Vec::new(),
         }
     }
 
@@ -275,6 +376,43 @@ Body content here."#;
         assert_eq!(body, "Body content here.");
     }
 
+    #[test]
+    fn test_parse_parameters() {
+        let content = r#"---
+name: search
+description: Search the team wiki
+parameters:
+  - name: max_results
+    type: number
+    description: How many results to return
+    required: true
+    default: 5
+  - name: language
+    type: string
+    options: [zh, en]
+  - name: broken
+    type: date
+tags: [search]
+---
+
+Body"#;
+
+        let mut file = NamedTempFile::with_suffix(".md").unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        let metadata = SkillParser::parse_metadata(file.path()).unwrap();
+
+        assert_eq!(metadata.parameters.len(), 2);
+        let max_results = &metadata.parameters[0];
+        assert_eq!(max_results.name, "max_results");
+        assert_eq!(max_results.param_type, SkillParameterType::Number);
+        assert!(max_results.required);
+        assert_eq!(max_results.default, Some(serde_json::json!(5.0)));
+        assert_eq!(metadata.parameters[1].options, vec!["zh", "en"]);
+        // Keys after the block are still parsed
+        assert_eq!(metadata.tags, vec!["search"]);
+    }
+
     #[test]
     fn test_parse_yaml_array() {
         assert_eq!(SkillParser::parse_yaml_array("[a, b, c]"), vec!["a", "b", "c"]);
@@ -1,8 +1,12 @@
 //! Skills prompt integration - collects and formats skills for AI prompts
 
-use crate::api::skill_api::{get_enabled_assistant_skills_internal, get_skill_content_internal};
+use crate::api::skill_api::{
+    get_enabled_assistant_skills_with_parameters_internal, get_skill_content_internal,
+};
 use crate::errors::AppError;
 use crate::skills::types::ScannedSkill;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use tracing::{debug, info, instrument, warn};
 
 /// Skills information for an assistant
@@ -10,6 +14,8 @@ use tracing::{debug, info, instrument, warn};
 pub struct SkillsInfoForAssistant {
     /// List of enabled skills with their metadata
     pub enabled_skills: Vec<ScannedSkill>,
+    /// Effective parameter values by skill identifier (skills without parameters are omitted)
+    pub parameters: HashMap<String, Map<String, Value>>,
 }
 
 /// Collect skills information for an assistant
@@ -18,11 +24,21 @@ pub async fn collect_skills_info_for_assistant(
     app_handle: &tauri::AppHandle,
     assistant_id: i64,
 ) -> Result<SkillsInfoForAssistant, AppError> {
-    let enabled_skills = get_enabled_assistant_skills_internal(app_handle, assistant_id).await?;
+    let skills_with_parameters =
+        get_enabled_assistant_skills_with_parameters_internal(app_handle, assistant_id).await?;
+
+    let mut enabled_skills = Vec::with_capacity(skills_with_parameters.len());
+    let mut parameters = HashMap::new();
+    for (skill, values) in skills_with_parameters {
+        if !values.is_empty() {
+            parameters.insert(skill.identifier.clone(), values);
+        }
+        enabled_skills.push(skill);
+    }
 
     debug!(enabled_skills_count = enabled_skills.len(), "Collected skills info for assistant");
 
-    Ok(SkillsInfoForAssistant { enabled_skills })
+    Ok(SkillsInfoForAssistant { enabled_skills, parameters })
 }
 
 /// Format skills into the assistant prompt
@@ -72,6 +88,13 @@ pub async fn format_skills_prompt(
             skills_content.push_str(&format!("**标签**: {}\n\n", skill.metadata.tags.join(", ")));
         }
 
+        // 添加助手为该技能配置的参数（已按技能声明校验）
+        if let Some(values) = skills_info.parameters.get(&skill.identifier) {
+            let values: Vec<String> =
+                values.iter().map(|(key, value)| format!("`{}` = {}", key, value)).collect();
+            skills_content.push_str(&format!("**参数**: {}\n\n", values.join(", ")));
+        }
+
         skills_content.push_str("---\n\n");
     }
 
@@ -77,6 +77,32 @@ impl SkillScanner {
         all_skills
     }
 
+    /// Existing paths to watch for hot-reload: source directories and files, plus the
+    /// install paths listed in Claude Code's installed_plugins.json
+    pub fn watch_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for source in self.sources.iter().filter(|source| source.is_enabled) {
+            for path_pattern in &source.paths {
+                let expanded_path = self.expand_path(path_pattern);
+                if !expanded_path.exists() {
+                    continue;
+                }
+                if source.source_type.as_str() == "claude_code_skills" {
+                    paths.extend(
+                        self.parse_installed_plugins(&expanded_path)
+                            .into_iter()
+                            .map(|(_, plugin_path)| plugin_path)
+                            .filter(|plugin_path| plugin_path.exists()),
+                    );
+                }
+                paths.push(expanded_path);
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
     /// Scan all sources and return as a map by identifier
     pub fn scan_all_as_map(&self) -> HashMap<String, ScannedSkill> {
         self.scan_all().into_iter().map(|s| (s.identifier.clone(), s)).collect()
@@ -235,7 +261,7 @@ impl SkillScanner {
                 Some(ScannedSkill {
                     identifier,
                     source_type: source.source_type.clone(),
-                    source_display_name: source.source_type.display_name().to_string(),
+                    source_display_name: source.display_name.clone(),
                     file_path: file_path.to_string_lossy().to_string(),
                     relative_path,
                     metadata,
@@ -400,7 +426,7 @@ impl SkillScanner {
                 Some(ScannedSkill {
                     identifier,
                     source_type: source.source_type.clone(),
-                    source_display_name: source.source_type.display_name().to_string(),
+                    source_display_name: source.display_name.clone(),
                     file_path: skill_file.to_string_lossy().to_string(),
                     relative_path,
                     metadata,
@@ -460,7 +486,7 @@ impl SkillScanner {
                 Some(ScannedSkill {
                     identifier,
                     source_type: source.source_type.clone(),
-                    source_display_name: source.source_type.display_name().to_string(),
+                    source_display_name: source.display_name.clone(),
                     file_path: skill_file.to_string_lossy().to_string(),
                     relative_path: folder_name.to_string(),
                     metadata,
@@ -147,6 +147,56 @@ pub struct SkillMetadata {
     pub tags: Vec<String>,
     /// Files required by this skill (relative paths)
     pub requires_files: Vec<String>,
+    /// Configurable parameters declared under `parameters:`
+    #[serde(default)]
+    pub parameters: Vec<SkillParameter>,
+}
+
+/// Value type of a skill parameter
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillParameterType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+}
+
+impl SkillParameterType {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "string" | "str" | "text" => Some(Self::String),
+            "number" | "float" => Some(Self::Number),
+            "integer" | "int" => Some(Self::Integer),
+            "boolean" | "bool" => Some(Self::Boolean),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+        }
+    }
+}
+
+/// A parameter declared in SKILL.md frontmatter, configurable per assistant
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillParameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: SkillParameterType,
+    pub description: Option<String>,
+    /// Required parameters without a default must be configured
+    #[serde(default)]
+    pub required: bool,
+    pub default: Option<serde_json::Value>,
+    /// Allowed values (string parameters only), empty means any value
+    #[serde(default)]
+    pub options: Vec<String>,
 }
 
 /// A scanned skill with metadata
@@ -218,6 +268,9 @@ pub struct AssistantSkillConfig {
     /// Priority for ordering when multiple skills are enabled
     pub priority: i32,
     pub created_time: String,
+    /// Parameter values, validated against the skill's declared parameters
+    #[serde(default)]
+    pub parameters: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Skill with config and existence status (for API responses)
@@ -230,3 +283,32 @@ pub struct SkillWithConfig {
     /// Whether the skill file still exists
     pub exists: bool,
 }
+
+/// A git repository subscribed as a skill source (stored in database)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitSkillSource {
+    pub id: i64,
+    /// Display name for UI
+    pub name: String,
+    /// Repository URL (https, ssh or git@host:path)
+    pub repo_url: String,
+    /// Branch to track, None tracks the remote default branch
+    pub branch: Option<String>,
+    /// Access token for private repositories, never sent to the frontend
+    #[serde(skip_serializing, default)]
+    pub auth_token: Option<String>,
+    /// Whether an access token is configured
+    pub has_auth_token: bool,
+    /// Time of the last successful clone/pull
+    pub last_synced_at: Option<String>,
+    /// Error from the last failed clone/pull, cleared on success
+    pub last_error: Option<String>,
+    pub created_time: String,
+}
+
+impl GitSkillSource {
+    /// Source type used in skill identifiers: "git_{id}"
+    pub fn source_type(&self) -> SkillSourceType {
+        SkillSourceType::Custom(format!("git_{}", self.id))
+    }
+}
@@ -0,0 +1,190 @@
+//! Skill file watcher - hot-reloads skills when SKILL.md files change on disk
+//!
+//! Watches every enabled skill source, coalesces bursts of file events (editors
+//! often write a file several times per save) and then invalidates the content
+//! cache for the changed files and emits `skills_changed`, so the UI rescans
+//! without an app restart.
+
+use crate::skills::cache::skill_content_cache;
+use crate::skills::scanner::SkillScanner;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tracing::{debug, info, warn};
+
+/// Event emitted to the frontend after skill files changed
+pub const SKILLS_CHANGED_EVENT: &str = "skills_changed";
+
+/// Quiet period after the last file event before a change batch is flushed
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// The active watcher and the paths it watches; dropping the watcher closes the
+/// event channel and ends the debounce task
+static SKILL_WATCHER: Mutex<Option<ActiveWatcher>> = Mutex::new(None);
+
+struct ActiveWatcher {
+    _watcher: RecommendedWatcher,
+    paths: Vec<PathBuf>,
+}
+
+/// Restart the watcher only when the set of existing source paths changed, e.g. a
+/// source directory was created after startup. Cheap enough to call on every rescan.
+pub fn ensure_skill_watcher(app_handle: &AppHandle, scanner: &SkillScanner) {
+    let watch_paths = scanner.watch_paths();
+    let unchanged = match SKILL_WATCHER.lock().unwrap().as_ref() {
+        Some(active) => active.paths == watch_paths,
+        None => watch_paths.is_empty(),
+    };
+    if !unchanged {
+        start_skill_watcher(app_handle, scanner);
+    }
+}
+
+/// Start watching all enabled skill sources, replacing any previous watcher
+pub fn start_skill_watcher(app_handle: &AppHandle, scanner: &SkillScanner) {
+    let watch_paths = scanner.watch_paths();
+    if watch_paths.is_empty() {
+        stop_skill_watcher();
+        debug!("No skill source paths exist, skill watcher not started");
+        return;
+    }
+
+    let (tx, rx) = unbounded_channel::<PathBuf>();
+    let mut watcher =
+        match notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                for path in event.paths.into_iter().filter(|path| is_relevant_path(path)) {
+                    let _ = tx.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Skill watcher error: {}", e),
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("Failed to create skill watcher: {}", e);
+                return;
+            }
+        };
+
+    let mut watched = 0;
+    for path in &watch_paths {
+        let mode =
+            if path.is_dir() { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+        match watcher.watch(path, mode) {
+            Ok(()) => watched += 1,
+            Err(e) => warn!("Failed to watch skill path {:?}: {}", path, e),
+        }
+    }
+    if watched == 0 {
+        return;
+    }
+    info!("Watching {} skill source paths for changes", watched);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        debounce_changes(rx, DEBOUNCE_WINDOW, |changed| {
+            for path in &changed {
+                skill_content_cache().invalidate(path);
+            }
+            info!("{} skill files changed on disk, notifying frontend", changed.len());
+            let _ = app_handle.emit(SKILLS_CHANGED_EVENT, changed.len());
+        })
+        .await;
+        debug!("Skill watcher debounce task ended");
+    });
+
+    *SKILL_WATCHER.lock().unwrap() = Some(ActiveWatcher { _watcher: watcher, paths: watch_paths });
+}
+
+/// Stop the skill watcher, if running
+pub fn stop_skill_watcher() {
+    if SKILL_WATCHER.lock().unwrap().take().is_some() {
+        info!("Skill watcher stopped");
+    }
+}
+
+/// Skip editor swap/backup files and git internals (git skill sources) so they don't
+/// trigger rescans
+fn is_relevant_path(path: &Path) -> bool {
+    if path.components().any(|component| component.as_os_str() == ".git") {
+        return false;
+    }
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return true;
+    };
+    !(name.ends_with('~')
+        || name.starts_with(".#")
+        || name.ends_with(".swp")
+        || name.ends_with(".swx")
+        || name.ends_with(".tmp")
+        || name == ".DS_Store")
+}
+
+/// Collect changed paths until no new event arrives within `window`, then flush the
+/// batch once. Returns when the sender side is dropped.
+async fn debounce_changes(
+    mut rx: UnboundedReceiver<PathBuf>,
+    window: Duration,
+    mut on_flush: impl FnMut(HashSet<PathBuf>),
+) {
+    while let Some(first) = rx.recv().await {
+        let mut changed = HashSet::from([first]);
+        let mut closed = false;
+        loop {
+            match tokio::time::timeout(window, rx.recv()).await {
+                Ok(Some(path)) => {
+                    changed.insert(path);
+                }
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+        on_flush(changed);
+        if closed {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rapid_saves_are_coalesced() {
+        let (tx, rx) = unbounded_channel();
+        let flushes = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let recorded = flushes.clone();
+        let task = tokio::spawn(debounce_changes(rx, Duration::from_millis(50), move |changed| {
+            recorded.lock().unwrap().push(changed.len());
+        }));
+
+        for _ in 0..20 {
+            tx.send(PathBuf::from("/skills/demo/SKILL.md")).unwrap();
+        }
+        tx.send(PathBuf::from("/skills/demo/helper.md")).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        tx.send(PathBuf::from("/skills/other/SKILL.md")).unwrap();
+        drop(tx);
+        task.await.unwrap();
+
+        assert_eq!(*flushes.lock().unwrap(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_editor_temp_files_are_ignored() {
+        assert!(is_relevant_path(Path::new("/skills/demo/SKILL.md")));
+        assert!(!is_relevant_path(Path::new("/skills/demo/.SKILL.md.swp")));
+        assert!(!is_relevant_path(Path::new("/skills/demo/SKILL.md~")));
+        assert!(!is_relevant_path(Path::new("/skills/demo/.#SKILL.md")));
+        assert!(!is_relevant_path(Path::new("/skill_sources/git_1/.git/FETCH_HEAD")));
+    }
+}
@@ -103,6 +103,12 @@ impl MessageTokenManager {
         tokens.get(&conversation_id).cloned()
     }
 
+    /// 当前仍有生成任务在运行的对话 ID
+    pub async fn active_conversation_ids(&self) -> Vec<i64> {
+        let task_handles = self.task_handles.lock().await;
+        task_handles.keys().copied().collect()
+    }
+
     pub fn get_task_handles(&self) -> Arc<Mutex<HashMap<i64, AbortHandle>>> {
         Arc::clone(&self.task_handles)
     }
@@ -1,2 +1,4 @@
 pub mod activity_state;
 pub mod message_token;
+pub mod model_list_cache;
+pub mod model_select_cache;
@@ -0,0 +1,101 @@
+use crate::api::llm_api::RemoteModel;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+struct CachedModelList {
+    cached_at: Instant,
+    models: Vec<RemoteModel>,
+}
+
+/// 提供商远程模型列表的缓存，避免每次打开模型选择对话框都请求提供商接口。
+/// key 为提供商 id；存活时间由调用方按网络配置传入，方便配置修改后立即生效
+#[derive(Clone, Default)]
+pub struct ModelListCacheState {
+    entries: Arc<Mutex<HashMap<i64, CachedModelList>>>,
+    /// 各提供商最近一次从远程获取模型列表的时间（毫秒时间戳），缓存失效后仍保留
+    last_fetched: Arc<Mutex<HashMap<i64, i64>>>,
+}
+
+impl ModelListCacheState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self, provider_id: i64, ttl: Duration) -> Option<Vec<RemoteModel>> {
+        let mut entries = self.entries.lock().await;
+        match entries.get(&provider_id) {
+            Some(cached) if cached.cached_at.elapsed() < ttl => Some(cached.models.clone()),
+            Some(_) => {
+                entries.remove(&provider_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub async fn insert(&self, provider_id: i64, models: Vec<RemoteModel>) {
+        self.entries
+            .lock()
+            .await
+            .insert(provider_id, CachedModelList { cached_at: Instant::now(), models });
+        self.last_fetched.lock().await.insert(provider_id, chrono::Utc::now().timestamp_millis());
+    }
+
+    pub async fn invalidate(&self, provider_id: i64) {
+        if self.entries.lock().await.remove(&provider_id).is_some() {
+            debug!(provider_id, "Model list cache invalidated");
+        }
+    }
+
+    pub async fn last_fetched_at(&self) -> HashMap<i64, i64> {
+        self.last_fetched.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(code: &str) -> RemoteModel {
+        RemoteModel {
+            name: code.to_string(),
+            code: code.to_string(),
+            description: format!("Model: {}", code),
+            vision_support: false,
+            audio_support: false,
+            video_support: false,
+        }
+    }
+
+    const TTL: Duration = Duration::from_secs(600);
+
+    #[tokio::test]
+    async fn test_cache_hit_within_ttl() {
+        let cache = ModelListCacheState::new();
+        assert!(cache.get(1, TTL).await.is_none());
+
+        cache.insert(1, vec![model("gpt-4o"), model("gpt-4.1")]).await;
+        assert_eq!(cache.get(1, TTL).await.map(|m| m.len()), Some(2));
+        assert!(cache.get(2, TTL).await.is_none());
+        assert!(cache.get(1, Duration::ZERO).await.is_none());
+    }
+
+    /// 测试修改 endpoint/key 后缓存失效，但最近获取时间保留
+    #[tokio::test]
+    async fn test_invalidate_keeps_last_fetched() {
+        let cache = ModelListCacheState::new();
+        cache.insert(1, vec![model("gpt-4o")]).await;
+        cache.insert(2, vec![model("claude-sonnet-4")]).await;
+
+        cache.invalidate(1).await;
+        assert!(cache.get(1, TTL).await.is_none());
+        assert!(cache.get(2, TTL).await.is_some());
+
+        let fetched = cache.last_fetched_at().await;
+        assert!(fetched.contains_key(&1));
+        assert!(fetched.contains_key(&2));
+    }
+}
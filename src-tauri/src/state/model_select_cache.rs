@@ -0,0 +1,148 @@
+use crate::api::llm_api::{LlmProvider, ModelForSelect};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// 模型/提供商选择列表缓存的默认存活时间
+const DEFAULT_TTL: Duration = Duration::from_secs(30);
+
+/// 按 key 缓存查询结果的短时内存缓存
+pub struct TtlCache<K, V> {
+    entries: Arc<Mutex<HashMap<K, (Instant, V)>>>,
+    ttl: Duration,
+}
+
+impl<K, V> Clone for TtlCache<K, V> {
+    fn clone(&self) -> Self {
+        Self { entries: self.entries.clone(), ttl: self.ttl }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self { entries: Arc::new(Mutex::new(HashMap::new())), ttl }
+    }
+
+    pub async fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().await;
+        let expired = match entries.get(key) {
+            Some((cached_at, value)) if cached_at.elapsed() < self.ttl => {
+                return Some(value.clone())
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            entries.remove(key);
+        }
+        None
+    }
+
+    pub async fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().await;
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    pub async fn clear(&self) {
+        self.entries.lock().await.clear();
+    }
+}
+
+/// 模型选择器相关查询的缓存，任何提供商/模型变更都需要调用 `invalidate`
+#[derive(Clone)]
+pub struct ModelSelectCacheState {
+    /// key 为 assistant_type，`None` 表示未过滤的全部模型
+    pub models: TtlCache<Option<i64>, Vec<ModelForSelect>>,
+    /// key 为 assistant_type
+    pub providers: TtlCache<i64, Vec<LlmProvider>>,
+}
+
+impl ModelSelectCacheState {
+    pub fn new() -> Self {
+        Self::with_ttl(DEFAULT_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self { models: TtlCache::new(ttl), providers: TtlCache::new(ttl) }
+    }
+
+    pub async fn invalidate(&self) {
+        self.models.clear().await;
+        self.providers.clear().await;
+        debug!("Model select cache invalidated");
+    }
+}
+
+impl Default for ModelSelectCacheState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: i64, code: &str) -> ModelForSelect {
+        ModelForSelect {
+            name: code.to_string(),
+            code: code.to_string(),
+            id,
+            llm_provider_id: 1,
+            models_fetched_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_keyed_by_filter() {
+        let cache = ModelSelectCacheState::new();
+        cache.models.insert(None, vec![model(1, "gpt-4o"), model(2, "acp-agent")]).await;
+        cache.models.insert(Some(4), vec![model(2, "acp-agent")]).await;
+
+        assert_eq!(cache.models.get(&None).await.map(|m| m.len()), Some(2));
+        assert_eq!(cache.models.get(&Some(4)).await.map(|m| m.len()), Some(1));
+        assert!(cache.models.get(&Some(0)).await.is_none());
+    }
+
+    /// 测试变更后缓存失效，下一次读取能看到新数据
+    #[tokio::test]
+    async fn test_invalidate_reflects_mutation() {
+        let cache = ModelSelectCacheState::new();
+        cache.models.insert(None, vec![model(1, "gpt-4o")]).await;
+        cache
+            .providers
+            .insert(
+                0,
+                vec![LlmProvider {
+                    id: 1,
+                    name: "OpenAI".to_string(),
+                    api_type: "openai".to_string(),
+                    description: String::new(),
+                    is_official: false,
+                    is_enabled: true,
+                    keep_alive_active: false,
+                }],
+            )
+            .await;
+
+        // 模拟 update_selected_models：先失效，再按最新数据库结果回填
+        cache.invalidate().await;
+        assert!(cache.models.get(&None).await.is_none());
+        assert!(cache.providers.get(&0).await.is_none());
+
+        cache.models.insert(None, vec![model(1, "gpt-4o"), model(3, "gpt-4.1")]).await;
+        let models = cache.models.get(&None).await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[1].code, "gpt-4.1");
+    }
+
+    #[tokio::test]
+    async fn test_cache_entry_expires() {
+        let cache = ModelSelectCacheState::with_ttl(Duration::from_millis(0));
+        cache.models.insert(None, vec![model(1, "gpt-4o")]).await;
+        assert!(cache.models.get(&None).await.is_none());
+    }
+}
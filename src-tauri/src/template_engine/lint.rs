@@ -0,0 +1,254 @@
+//! 助手系统提示词检查：在保存前发现未定义的模板变量、过长的提示词和相互矛盾的指令
+
+use super::{extract_references, placeholder_regex, TemplateEngine, PLACEHOLDER_VARIABLES};
+use crate::utils::token_utils::estimate_tokens;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// 运行时由模板上下文提供的变量
+pub const CONTEXT_VARIABLES: &[&str] = &["selected_text", "conversation_id"];
+
+/// 估算 token 超过该值时提示提示词过长
+pub const LONG_PROMPT_TOKEN_THRESHOLD: usize = 8000;
+
+/// 容易同时出现的矛盾指令关键词（两组关键词都命中时提示）
+const CONFLICTING_KEYWORDS: &[(&[&str], &[&str])] = &[
+    (&["简洁", "简短", "concise", "brief"], &["详细", "详尽", "in detail", "detailed"]),
+    (
+        &["使用markdown", "use markdown"],
+        &["不要使用markdown", "纯文本", "no markdown", "plain text"],
+    ),
+    (&["用中文", "使用中文", "in chinese"], &["用英文", "使用英文", "in english"]),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Warning,
+    Info,
+}
+
+/// 单条检查结果，位置为字符偏移，行列从 1 开始
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PromptLintIssue {
+    pub code: String,
+    pub severity: LintSeverity,
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+fn issue(
+    prompt: &str,
+    code: &str,
+    severity: LintSeverity,
+    message: String,
+    byte_start: usize,
+    byte_end: usize,
+) -> PromptLintIssue {
+    let before = &prompt[..byte_start];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+    let start = before.chars().count();
+    PromptLintIssue {
+        code: code.to_string(),
+        severity,
+        message,
+        start,
+        end: start + prompt[byte_start..byte_end].chars().count(),
+        line,
+        column,
+    }
+}
+
+/// 检查助手系统提示词，`params` 为助手定义的提示词参数名
+pub fn lint_prompt(
+    engine: &TemplateEngine,
+    prompt: &str,
+    params: &[String],
+) -> Vec<PromptLintIssue> {
+    let mut issues = Vec::new();
+    if prompt.trim().is_empty() {
+        issues.push(issue(
+            prompt,
+            "empty_prompt",
+            LintSeverity::Warning,
+            "提示词为空".to_string(),
+            0,
+            0,
+        ));
+        return issues;
+    }
+
+    let mut referenced = HashSet::new();
+    for reference in extract_references(prompt) {
+        // 只检查 ASCII 标识符，避免把中文感叹号后的正文误判为变量
+        if !reference.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        referenced.insert(reference.name.clone());
+        if engine.has_command(&reference.name)
+            || CONTEXT_VARIABLES.contains(&reference.name.as_str())
+            || params.contains(&reference.name)
+        {
+            continue;
+        }
+        let (code, message) = if reference.args.is_some() {
+            ("unknown_command", format!("未知的模板命令 !{}，运行时不会被替换", reference.name))
+        } else {
+            ("undefined_param", format!("引用了未定义的参数 !{}，运行时不会被替换", reference.name))
+        };
+        issues.push(issue(
+            prompt,
+            code,
+            LintSeverity::Warning,
+            message,
+            reference.start,
+            reference.end,
+        ));
+    }
+
+    for cap in placeholder_regex().captures_iter(prompt) {
+        let whole = cap.get(0).unwrap();
+        let name = cap[1].to_string();
+        if !PLACEHOLDER_VARIABLES.contains(&name.as_str()) && !params.contains(&name) {
+            issues.push(issue(
+                prompt,
+                "undefined_param",
+                LintSeverity::Warning,
+                format!("引用了未定义的参数 {}，运行时不会被替换", whole.as_str()),
+                whole.start(),
+                whole.end(),
+            ));
+        }
+        referenced.insert(name);
+    }
+
+    for param in params.iter().filter(|param| !referenced.contains(*param)) {
+        issues.push(issue(
+            prompt,
+            "unused_param",
+            LintSeverity::Info,
+            format!("参数 {} 未在提示词中使用", param),
+            0,
+            0,
+        ));
+    }
+
+    let tokens = estimate_tokens(prompt);
+    if tokens > LONG_PROMPT_TOKEN_THRESHOLD {
+        issues.push(issue(
+            prompt,
+            "prompt_too_long",
+            LintSeverity::Warning,
+            format!("提示词约 {} tokens，过长会挤占对话上下文", tokens),
+            0,
+            prompt.len(),
+        ));
+    }
+
+    let lower = prompt.to_lowercase();
+    for (left, right) in CONFLICTING_KEYWORDS {
+        let find =
+            |keywords: &[&str]| keywords.iter().find_map(|k| lower.find(k).map(|pos| (pos, *k)));
+        if let (Some((left_pos, left_kw)), Some((_, right_kw))) = (find(left), find(right)) {
+            // 否定形式（如“不要使用markdown”）包含肯定关键词时不算冲突
+            if right_kw.contains(left_kw) {
+                continue;
+            }
+            // to_lowercase 可能改变字节长度，定位失败时退回开头
+            let (start, end) = if prompt.is_char_boundary(left_pos)
+                && prompt.is_char_boundary(left_pos + left_kw.len())
+            {
+                (left_pos, left_pos + left_kw.len())
+            } else {
+                (0, 0)
+            };
+            issues.push(issue(
+                prompt,
+                "conflicting_instructions",
+                LintSeverity::Info,
+                format!("提示词同时包含“{}”和“{}”，指令可能相互矛盾", left_kw, right_kw),
+                start,
+                end,
+            ));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(issues: &[PromptLintIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_reports_unknown_command_and_undefined_param() {
+        let engine = TemplateEngine::new();
+        let prompt =
+            "今天是 !cd。\n请阅读 !fetch_page(https://a.com) 并回答 !topic 相关问题，选中内容：!s";
+        let issues = lint_prompt(&engine, prompt, &["tone".to_string()]);
+
+        let unknown = issues.iter().find(|i| i.code == "unknown_command").unwrap();
+        assert_eq!(unknown.line, 2);
+        assert_eq!(unknown.column, 5);
+        assert_eq!(
+            prompt
+                .chars()
+                .skip(unknown.start)
+                .take(unknown.end - unknown.start)
+                .collect::<String>(),
+            "!fetch_page(https://a.com)"
+        );
+
+        let undefined = issues.iter().find(|i| i.code == "undefined_param").unwrap();
+        assert!(undefined.message.contains("!topic"));
+        assert_eq!(codes(&issues).iter().filter(|c| **c == "undefined_param").count(), 1);
+
+        // 定义了但未引用的参数
+        assert!(issues.iter().any(|i| i.code == "unused_param" && i.message.contains("tone")));
+    }
+
+    #[test]
+    fn test_lint_accepts_defined_params_and_context_variables() {
+        let engine = TemplateEngine::new();
+        let issues = lint_prompt(
+            &engine,
+            "围绕 !topic 回答，对话 !conversation_id！请注意格式",
+            &["topic".to_string()],
+        );
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_lint_checks_brace_placeholders() {
+        let engine = TemplateEngine::new();
+        let issues = lint_prompt(
+            &engine,
+            "Today is {{date}}, hello {{ user_name }}. Tone: {{tone}}, topic: {{topic}}",
+            &["tone".to_string()],
+        );
+        assert_eq!(codes(&issues), vec!["undefined_param"]);
+        assert!(issues[0].message.contains("{{topic}}"));
+    }
+
+    #[test]
+    fn test_lint_long_prompt_and_conflicts() {
+        let engine = TemplateEngine::new();
+        let long_prompt = "a".repeat(LONG_PROMPT_TOKEN_THRESHOLD * 4 + 4);
+        assert_eq!(codes(&lint_prompt(&engine, &long_prompt, &[])), vec!["prompt_too_long"]);
+
+        let issues = lint_prompt(&engine, "回答要简洁。\n每个步骤都要详细说明。", &[]);
+        assert_eq!(codes(&issues), vec!["conflicting_instructions"]);
+        assert_eq!((issues[0].line, issues[0].column), (1, 4));
+
+        let issues = lint_prompt(&engine, "不要使用markdown，输出纯文本", &[]);
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+}
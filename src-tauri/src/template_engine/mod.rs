@@ -6,11 +6,13 @@ use regex::Regex;
 use reqwest;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tracing::debug;
+use std::sync::{Arc, OnceLock};
+use tracing::{debug, warn};
 
+use crate::db::assistant_db::AssistantPromptParam;
 // 用于 HTML 内容清理
 use crate::mcp::builtin_mcp::search::engines::base::SearchEngineBase;
+pub mod lint;
 mod plugin_bangs;
 pub use plugin_bangs::build_template_engine;
 
@@ -161,6 +163,39 @@ pub struct TemplateEngine {
     commands: HashMap<String, Bang>,
 }
 
+/// 模板中的一处 `!name` 或 `!name(args)` 引用，位置为字节偏移
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateReference {
+    pub name: String,
+    /// 包含括号的参数部分，如 `(url)`；无参数时为 None
+    pub args: Option<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+fn bang_regex() -> &'static Regex {
+    static BANG_REGEX: OnceLock<Regex> = OnceLock::new();
+    BANG_REGEX.get_or_init(|| {
+        Regex::new(r"[!！](\w+)(\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\([^()]*\))*\))*\))*\))*\))*\))*\))*\))*\))*\))?").unwrap()
+    })
+}
+
+/// 提取模板中的所有 `!name(args)` 引用（命令与上下文变量使用同一语法）
+pub fn extract_references(template: &str) -> Vec<TemplateReference> {
+    bang_regex()
+        .captures_iter(template)
+        .map(|cap| {
+            let whole = cap.get(0).unwrap();
+            TemplateReference {
+                name: cap[1].to_string(),
+                args: cap.get(2).map(|m| m.as_str().to_string()),
+                start: whole.start(),
+                end: whole.end(),
+            }
+        })
+        .collect()
+}
+
 #[derive(Clone)]
 pub struct Bang {
     pub name: String,
@@ -332,10 +367,9 @@ impl TemplateEngine {
 
     // 解析并替换模板字符串
     pub async fn parse(&self, template: &str, context: &HashMap<String, String>) -> String {
-        let re = Regex::new(r"[!！](\w+)(\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\((?:[^()]|\([^()]*\))*\))*\))*\))*\))*\))*\))*\))*\))*\))?").unwrap();
         let mut result = template.to_string();
 
-        for cap in re.captures_iter(template) {
+        for cap in bang_regex().captures_iter(template) {
             debug!(?cap, "parse bang capture");
             let command = &cap[1];
             let args = cap.get(2).map_or("", |m| m.as_str());
@@ -360,5 +394,48 @@ impl TemplateEngine {
     }
 }
 
+/// 系统提示词中 `{{name}}` 占位符的内置变量
+pub const PLACEHOLDER_VARIABLES: &[&str] = &["date", "selected_text", "user_name"];
+
+fn placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER_REGEX: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER_REGEX.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_]\w*)\s*\}\}").unwrap())
+}
+
+/// 组装系统提示词中 `{{name}}` 占位符可用的变量：内置的 date、selected_text、user_name，
+/// 以及助手上定义的自定义参数（同名时自定义参数优先）
+pub fn prompt_placeholder_variables(
+    selected_text: &str,
+    prompt_params: &[AssistantPromptParam],
+) -> HashMap<String, String> {
+    let user_name =
+        std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+    let mut variables = HashMap::from([
+        ("date".to_string(), Local::now().format("%Y-%m-%d").to_string()),
+        ("selected_text".to_string(), selected_text.to_string()),
+        ("user_name".to_string(), user_name),
+    ]);
+    for param in prompt_params {
+        let name = param.param_name.trim();
+        if !name.is_empty() {
+            variables.insert(name.to_string(), param.param_value.clone().unwrap_or_default());
+        }
+    }
+    variables
+}
+
+/// 替换模板中的 `{{name}}` 占位符，未知的占位符原样保留并记录日志
+pub fn render_placeholders(template: &str, variables: &HashMap<String, String>) -> String {
+    placeholder_regex()
+        .replace_all(template, |cap: &regex::Captures| match variables.get(&cap[1]) {
+            Some(value) => value.clone(),
+            None => {
+                warn!(placeholder = &cap[1], "unknown prompt placeholder, left intact");
+                cap[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
 #[cfg(test)]
 mod tests;
@@ -172,6 +172,7 @@ async fn execute_runtime_bang(
         &JsonValue::Object(parameters).to_string(),
         conversation_id,
         None,
+        None,
     )
     .await
     {
@@ -233,6 +234,9 @@ fn build_builtin_server(command: &str) -> MCPServer {
         is_deletable: false,
         proxy_enabled: false,
         created_time: String::new(),
+        autostart: false,
+        status: None,
+        last_healthy_at: None,
     }
 }
 
@@ -321,6 +325,9 @@ fn build_plugin_mcp_server(
         is_deletable: false,
         proxy_enabled: definition.proxy_enabled,
         created_time: String::new(),
+        autostart: false,
+        status: None,
+        last_healthy_at: None,
     })
 }
 
@@ -177,3 +177,33 @@ async fn test_file_command() {
     let result3 = engine.parse("!file(/nonexistent/path.txt)", &context).await;
     assert!(result3.contains("!file_error"));
 }
+
+#[test]
+fn test_render_placeholders() {
+    let variables = prompt_placeholder_variables("选中的文本", &[]);
+    let result = render_placeholders(
+        "Today is {{date}}. Selected: {{ selected_text }}. Keep {{unknown}} as is.",
+        &variables,
+    );
+    assert_eq!(
+        result,
+        format!(
+            "Today is {}. Selected: 选中的文本. Keep {{{{unknown}}}} as is.",
+            Local::now().format("%Y-%m-%d")
+        )
+    );
+}
+
+#[test]
+fn test_render_placeholders_with_custom_params() {
+    let params = vec![AssistantPromptParam {
+        id: 1,
+        assistant_id: 1,
+        assistant_prompt_id: 1,
+        param_name: "tone".to_string(),
+        param_type: Some("text".to_string()),
+        param_value: Some("friendly".to_string()),
+    }];
+    let variables = prompt_placeholder_variables("", &params);
+    assert_eq!(render_placeholders("Be {{tone}}.", &variables), "Be friendly.");
+}
@@ -0,0 +1,285 @@
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+};
+
+/// 查找下次执行时间时最多向后检查的天数，覆盖闰年 2 月 29 日这类四年一遇的表达式
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 8;
+
+/// 夏令时等原因跳过的本地时间段最长跨度（分钟），个别时区曾整天跳过
+const MAX_GAP_MINUTES: i64 = 24 * 60;
+
+const MONTH_NAMES: &[&str] =
+    &["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// 解析后的 5 段式 cron 表达式：分 时 日 月 星期
+///
+/// 支持 `*`、列表 `1,3,5`、范围 `1-5`、步长 `*/15` / `0-30/10`，
+/// 月份与星期可使用英文缩写（JAN、MON），星期中 0 和 7 都表示周日，
+/// 以及 `@hourly`、`@daily`、`@weekly`、`@monthly`、`@yearly` 简写。
+/// 与标准 cron 一致：日和星期都被限定时，满足其一即触发
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    month_days: Vec<bool>,
+    months: Vec<bool>,
+    week_days: Vec<bool>,
+    month_days_restricted: bool,
+    week_days_restricted: bool,
+}
+
+/// 把时区中的本地时间换算为具体时刻：回拨造成的重复时刻取较早的一次，
+/// 夏令时跳过的时刻顺延到跳过之后的第一个有效时刻
+pub fn resolve_local_datetime<Tz: TimeZone>(
+    timezone: &Tz,
+    local: NaiveDateTime,
+) -> Option<DateTime<Tz>> {
+    match timezone.from_local_datetime(&local) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => (1..=MAX_GAP_MINUTES).find_map(|minutes| {
+            timezone.from_local_datetime(&(local + Duration::minutes(minutes))).earliest()
+        }),
+    }
+}
+
+struct FieldSpec {
+    label: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const MINUTE_FIELD: FieldSpec = FieldSpec { label: "分钟", min: 0, max: 59, names: &[] };
+const HOUR_FIELD: FieldSpec = FieldSpec { label: "小时", min: 0, max: 23, names: &[] };
+const MONTH_DAY_FIELD: FieldSpec = FieldSpec { label: "日期", min: 1, max: 31, names: &[] };
+const MONTH_FIELD: FieldSpec = FieldSpec { label: "月份", min: 1, max: 12, names: MONTH_NAMES };
+// 星期允许 7 表示周日，解析后折算为 0
+const WEEKDAY_FIELD: FieldSpec =
+    FieldSpec { label: "星期", min: 0, max: 7, names: WEEKDAY_NAMES };
+
+impl FieldSpec {
+    fn parse_value(&self, value: &str) -> Option<u32> {
+        if let Some(index) = self.names.iter().position(|name| name.eq_ignore_ascii_case(value)) {
+            // 月份名从 1 开始，星期名从 0 开始
+            return Some(index as u32 + self.min);
+        }
+        value.parse::<u32>().ok().filter(|v| *v >= self.min && *v <= self.max)
+    }
+
+    /// 解析单个字段，返回 [0, max] 的命中表
+    fn parse(&self, field: &str) -> Result<Vec<bool>, String> {
+        let invalid = || format!("cron 表达式的{}字段无效: {}", self.label, field);
+        let mut matched = vec![false; self.max as usize + 1];
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step = step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?;
+                    (range, Some(step))
+                }
+                None => (part, None),
+            };
+            let (start, end) = if range == "*" {
+                (self.min, self.max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                let start = self.parse_value(start).ok_or_else(invalid)?;
+                let end = self.parse_value(end).ok_or_else(invalid)?;
+                if start > end {
+                    return Err(invalid());
+                }
+                (start, end)
+            } else {
+                let value = self.parse_value(range).ok_or_else(invalid)?;
+                // `5/10` 表示从 5 开始每隔 10
+                (value, if step.is_some() { self.max } else { value })
+            };
+            for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+                matched[value as usize] = true;
+            }
+        }
+        Ok(matched)
+    }
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        let expanded = match expression.to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ if expression.starts_with('@') => {
+                return Err(format!("不支持的 cron 简写: {}", expression))
+            }
+            _ => expression,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "cron 表达式需要 5 个字段（分 时 日 月 星期），实际为 {} 个",
+                fields.len()
+            ));
+        }
+
+        let mut week_days = WEEKDAY_FIELD.parse(fields[4])?;
+        if week_days[7] {
+            week_days[0] = true;
+        }
+        week_days.truncate(7);
+
+        let schedule = CronSchedule {
+            minutes: MINUTE_FIELD.parse(fields[0])?,
+            hours: HOUR_FIELD.parse(fields[1])?,
+            month_days: MONTH_DAY_FIELD.parse(fields[2])?,
+            months: MONTH_FIELD.parse(fields[3])?,
+            week_days,
+            month_days_restricted: !fields[2].starts_with('*'),
+            week_days_restricted: !fields[4].starts_with('*'),
+        };
+
+        // 排除 `0 0 30 2 *` 这类永远不会触发的表达式
+        let has_valid_day = (1..=12u32).filter(|m| schedule.months[*m as usize]).any(|month| {
+            let days_in_month = if month == 2 { 29 } else { 30 + (month + month / 8) % 2 };
+            (1..=days_in_month).any(|day| schedule.month_days[day as usize])
+        });
+        if schedule.month_days_restricted && !schedule.week_days_restricted && !has_valid_day {
+            return Err(format!("cron 表达式永远不会触发: {}", expression));
+        }
+
+        Ok(schedule)
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months[date.month() as usize] {
+            return false;
+        }
+        let day_matches = self.month_days[date.day() as usize];
+        let weekday_matches = self.week_days[date.weekday().num_days_from_sunday() as usize];
+        match (self.month_days_restricted, self.week_days_restricted) {
+            (true, true) => day_matches || weekday_matches,
+            (true, false) => day_matches,
+            (false, true) => weekday_matches,
+            (false, false) => true,
+        }
+    }
+
+    /// 计算严格晚于 after 的下一次触发时间，按 after 所在时区解释表达式；
+    /// 夏令时跳过的时刻顺延到之后第一个有效时刻，重复的时刻只触发较早的一次
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let local = after.naive_local();
+        let start_date = local.date();
+        let (start_hour, start_minute) = (local.hour(), local.minute());
+
+        for offset in 0..=MAX_LOOKAHEAD_DAYS {
+            let date = start_date + Duration::days(offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in (0..24u32).filter(|h| self.hours[*h as usize]) {
+                if offset == 0 && hour < start_hour {
+                    continue;
+                }
+                for minute in (0..60u32).filter(|m| self.minutes[*m as usize]) {
+                    if offset == 0 && hour == start_hour && minute < start_minute {
+                        continue;
+                    }
+                    let candidate = date
+                        .and_hms_opt(hour, minute, 0)
+                        .and_then(|local| resolve_local_datetime(&timezone, local));
+                    if let Some(candidate) = candidate.filter(|c| c > after) {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(&utc(after))
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_weekday_morning() {
+        // 2026-10-16 是周五
+        assert_eq!(next("0 9 * * 1-5", "2026-10-16T08:59:30Z"), "2026-10-16T09:00:00+00:00");
+        assert_eq!(next("0 9 * * 1-5", "2026-10-16T09:00:00Z"), "2026-10-19T09:00:00+00:00");
+        assert_eq!(next("0 9 * * MON-FRI", "2026-10-17T12:00:00Z"), "2026-10-19T09:00:00+00:00");
+    }
+
+    #[test]
+    fn test_steps_lists_and_macros() {
+        assert_eq!(next("*/15 * * * *", "2026-10-16T10:07:00Z"), "2026-10-16T10:15:00+00:00");
+        assert_eq!(next("5,35 */6 * * *", "2026-10-16T06:40:00Z"), "2026-10-16T12:05:00+00:00");
+        assert_eq!(next("@monthly", "2026-12-15T00:00:00Z"), "2027-01-01T00:00:00+00:00");
+        assert_eq!(next("0 0 * * 7", "2026-10-16T00:00:00Z"), "2026-10-18T00:00:00+00:00");
+        assert_eq!(next("0 0 29 2 *", "2026-03-01T00:00:00Z"), "2028-02-29T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_dst_transitions_in_timezone() {
+        let new_york = chrono_tz::America::New_York;
+        let after = |s: &str| utc(s).with_timezone(&new_york);
+        let schedule = CronSchedule::parse("30 2 * * *").unwrap();
+
+        // 2026-03-08 02:30 被夏令时跳过，顺延到 03:00 EDT
+        let next = schedule.next_after(&after("2026-03-07T08:00:00Z")).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-03-08T03:00:00-04:00");
+        let next = schedule.next_after(&next).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-03-09T02:30:00-04:00");
+
+        // 2026-11-01 01:30 出现两次，只在较早的 EDT 时刻触发一次
+        let schedule = CronSchedule::parse("30 1 * * *").unwrap();
+        let next = schedule.next_after(&after("2026-10-31T12:00:00Z")).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-11-01T01:30:00-04:00");
+        let next = schedule.next_after(&next).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-11-02T01:30:00-05:00");
+    }
+
+    #[test]
+    fn test_day_of_month_or_weekday() {
+        // 日和星期同时限定时满足其一即可：15 号或周一
+        assert_eq!(next("0 8 15 * 1", "2026-10-13T00:00:00Z"), "2026-10-15T08:00:00+00:00");
+        assert_eq!(next("0 8 15 * 1", "2026-10-15T09:00:00Z"), "2026-10-19T08:00:00+00:00");
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in [
+            "",
+            "0 9 * *",
+            "0 9 * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "@every_minute",
+            "0 0 30 2 *",
+        ] {
+            assert!(CronSchedule::parse(expression).is_err(), "{} should be rejected", expression);
+        }
+    }
+}
@@ -1,6 +1,10 @@
 pub mod bun_utils;
+pub mod cron_utils;
 pub mod db_utils;
 pub mod python_utils;
 pub mod share_utils;
+#[cfg(desktop)]
+pub mod shortcut_utils;
+pub mod token_utils;
 pub mod uv_utils;
 pub mod window_utils;
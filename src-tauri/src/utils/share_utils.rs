@@ -26,7 +26,7 @@ pub struct AssistantShareData {
     pub model_configs: Vec<ModelConfigShare>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfigShare {
     pub name: String,
     pub value: String,
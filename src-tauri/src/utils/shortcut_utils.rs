@@ -0,0 +1,153 @@
+use crate::db::system_db::FeatureConfig;
+use std::collections::HashMap;
+
+/// 根据 shortcuts 配置解析出的全局快捷键（global-hotkey 解析格式）
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalShortcuts {
+    /// 唤起 Ask 窗口的快捷键
+    pub ask: String,
+    /// 直接打开 Chat 窗口的快捷键，未配置时为空
+    pub chat: Option<String>,
+    /// Ask 快捷键是否来自旧字段 modifier_key 或默认值
+    pub ask_from_fallback: bool,
+}
+
+/// 读取 Ask / Chat 两个全局快捷键
+///
+/// Ask 依次读取 `shortcut_ask`、旧字段 `shortcut`、旧字段 `modifier_key` + Space，
+/// 都没有时使用平台默认值；Chat 只读取 `shortcut_chat`
+pub fn resolve_global_shortcuts(
+    shortcuts_cfg: Option<&HashMap<String, FeatureConfig>>,
+) -> GlobalShortcuts {
+    let value_of = |key: &str| {
+        shortcuts_cfg
+            .and_then(|cfg| cfg.get(key))
+            .map(|c| c.value.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let (ask, ask_from_fallback) = match value_of("shortcut_ask").or_else(|| value_of("shortcut")) {
+        Some(ask) => (ask, false),
+        None => (legacy_modifier_shortcut(value_of("modifier_key").as_deref()), true),
+    };
+
+    GlobalShortcuts { ask, chat: value_of("shortcut_chat"), ask_from_fallback }
+}
+
+/// 兼容旧字段：modifier_key + Space，未配置时使用平台默认修饰键
+fn legacy_modifier_shortcut(modifier: Option<&str>) -> String {
+    let mk = modifier.unwrap_or_default().to_lowercase();
+    let mod_token = if mk == "ctrl" || mk == "control" {
+        "Ctrl"
+    } else if mk == "shift" {
+        "Shift"
+    } else if mk == "cmd" || mk == "command" || mk == "super" {
+        #[cfg(target_os = "macos")]
+        {
+            "Command"
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            "Super"
+        }
+    } else {
+        #[cfg(target_os = "macos")]
+        {
+            "Option"
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            "Alt"
+        }
+    };
+    format!("{}+Space", mod_token)
+}
+
+/// 判断两个快捷键是否为同一组合，忽略大小写、顺序以及修饰键别名（Option/Alt、Cmd/Super 等）
+pub fn shortcuts_conflict(a: &str, b: &str) -> bool {
+    normalize_shortcut(a) == normalize_shortcut(b)
+}
+
+fn normalize_shortcut(shortcut: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = shortcut
+        .split('+')
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .map(|t| match t.as_str() {
+            "option" | "alt" => "alt".to_string(),
+            "control" | "ctrl" => "ctrl".to_string(),
+            "cmd" | "command" | "super" | "meta" => "super".to_string(),
+            "cmdorctrl" | "commandorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    "super".to_string()
+                } else {
+                    "ctrl".to_string()
+                }
+            }
+            _ => t,
+        })
+        .collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcuts_cfg(entries: &[(&str, &str)]) -> HashMap<String, FeatureConfig> {
+        entries
+            .iter()
+            .map(|(key, value)| {
+                let config = FeatureConfig {
+                    id: None,
+                    feature_code: "shortcuts".to_string(),
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    data_type: "string".to_string(),
+                    description: None,
+                };
+                (key.to_string(), config)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_separate_ask_and_chat_shortcuts() {
+        let cfg = shortcuts_cfg(&[
+            ("shortcut_ask", "Option+Space"),
+            ("shortcut_chat", "Option+Shift+Space"),
+            ("shortcut", "Ctrl+Space"),
+        ]);
+        let resolved = resolve_global_shortcuts(Some(&cfg));
+        assert_eq!(resolved.ask, "Option+Space");
+        assert_eq!(resolved.chat.as_deref(), Some("Option+Shift+Space"));
+        assert!(!resolved.ask_from_fallback);
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_legacy_keys() {
+        let cfg = shortcuts_cfg(&[("shortcut", "Ctrl+Shift+I"), ("shortcut_chat", "  ")]);
+        let resolved = resolve_global_shortcuts(Some(&cfg));
+        assert_eq!(resolved.ask, "Ctrl+Shift+I");
+        assert_eq!(resolved.chat, None);
+
+        let cfg = shortcuts_cfg(&[("modifier_key", "ctrl")]);
+        let resolved = resolve_global_shortcuts(Some(&cfg));
+        assert_eq!(resolved.ask, "Ctrl+Space");
+        assert!(resolved.ask_from_fallback);
+
+        let resolved = resolve_global_shortcuts(None);
+        assert!(resolved.ask.ends_with("+Space"));
+        assert!(resolved.ask_from_fallback);
+    }
+
+    #[test]
+    fn test_shortcuts_conflict_ignores_alias_and_order() {
+        assert!(shortcuts_conflict("Option+Space", "alt+space"));
+        assert!(shortcuts_conflict("Shift+Alt+Space", "Alt+Shift+Space"));
+        assert!(shortcuts_conflict("Cmd+K", "Super+K"));
+        assert!(!shortcuts_conflict("Option+Space", "Option+Shift+Space"));
+    }
+}
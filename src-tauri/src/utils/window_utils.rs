@@ -1,7 +1,7 @@
 use crate::api::ai::events::{
     ConversationEvent, ErrorNotificationPayload, ERROR_NOTIFICATION_EVENT,
 };
-use tauri::{Emitter, Manager, Window};
+use tauri::{Emitter, EventTarget, Manager, Runtime, Window};
 
 /// 检查chat和ask窗口是否有任何一个聚焦
 /// 如果有任何一个窗口聚焦，返回true；否则返回false
@@ -63,22 +63,69 @@ pub fn send_error_to_appropriate_window(
     }
 }
 
+/// 对话事件名，前端按对话 id 监听
+pub fn conversation_event_name(conversation_id: i64) -> String {
+    format!("conversation_event_{}", conversation_id)
+}
+
+/// 向所有窗口广播对话事件
+/// 生成过程中的事件不绑定发起请求的窗口，同一对话在多个窗口中打开时都能实时更新；
+/// 错误通知仍通过 `send_error_to_appropriate_window` 按窗口路由
+pub fn emit_conversation_event<R: Runtime>(
+    emitter: &impl Emitter<R>,
+    conversation_id: i64,
+    event: ConversationEvent,
+) {
+    let _ = emitter.emit_to(EventTarget::Any, &conversation_event_name(conversation_id), event);
+}
+
 /// 向对话相关窗口发送对话事件
-/// 同时向 ask 和 chat_ui 窗口发送对话事件，确保所有相关界面都能收到通知
-pub fn send_conversation_event_to_chat_windows(
-    app_handle: &tauri::AppHandle,
+/// 广播给所有窗口（ask、chat_ui 及其他打开了该对话的窗口），确保所有相关界面都能收到通知
+pub fn send_conversation_event_to_chat_windows<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
     conversation_id: i64,
     event: ConversationEvent,
 ) {
-    let event_name = format!("conversation_event_{}", conversation_id);
+    emit_conversation_event(app_handle, conversation_id, event);
+}
 
-    // 发送给 Ask 窗口
-    if let Some(ask_window) = app_handle.get_webview_window("ask") {
-        let _ = ask_window.emit(&event_name, &event);
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tauri::{Listener, WebviewUrl, WebviewWindowBuilder};
 
-    // 发送给 Chat UI 窗口
-    if let Some(chat_ui_window) = app_handle.get_webview_window("chat_ui") {
-        let _ = chat_ui_window.emit(&event_name, &event);
+    #[test]
+    fn test_conversation_event_reaches_all_windows_listening_to_conversation() {
+        let app = tauri::test::mock_app();
+        let received = Arc::new(AtomicUsize::new(0));
+        let windows: Vec<_> = ["ask", "chat_ui", "chat_ui_2"]
+            .into_iter()
+            .map(|label| {
+                WebviewWindowBuilder::new(&app, label, WebviewUrl::default()).build().unwrap()
+            })
+            .collect();
+        for window in &windows {
+            let received = received.clone();
+            window.listen(conversation_event_name(42), move |_| {
+                received.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        let other_conversation = Arc::new(AtomicUsize::new(0));
+        let other = other_conversation.clone();
+        app.listen(conversation_event_name(43), move |_| {
+            other.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // 从发起请求的窗口发出，其他窗口同样能收到
+        let event =
+            ConversationEvent { r#type: "message_update".to_string(), data: serde_json::json!({}) };
+        emit_conversation_event(&windows[0], 42, event.clone());
+        assert_eq!(received.load(Ordering::SeqCst), windows.len());
+
+        send_conversation_event_to_chat_windows(app.handle(), 42, event);
+        assert_eq!(received.load(Ordering::SeqCst), windows.len() * 2);
+        assert_eq!(other_conversation.load(Ordering::SeqCst), 0);
     }
 }
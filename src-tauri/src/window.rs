@@ -638,6 +638,15 @@ pub fn open_config_window_inner(_app: &AppHandle, window: &tauri::WebviewWindow)
     }
 }
 
+/// 显示聊天窗口，窗口不存在时创建（用于托盘与全局快捷键）
+pub fn handle_open_chat_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("chat_ui") {
+        open_chat_ui_window_inner(app, &window);
+    } else {
+        create_chat_ui_window(app);
+    }
+}
+
 /// 内部函数：显示聊天窗口（用于托盘菜单）
 pub fn open_chat_ui_window_inner(app: &AppHandle, window: &tauri::WebviewWindow) {
     #[cfg(desktop)]
@@ -205,7 +205,12 @@ const ConfigForm: React.FC<ConfigFormProps> = ({
                         <Select
                             disabled={field.disabled}
                             value={fieldRenderData.value}
-                            onValueChange={fieldRenderData.onChange}
+                            onValueChange={(value) => {
+                                fieldRenderData.onChange(value);
+                                if (field.onChange) {
+                                    field.onChange(value);
+                                }
+                            }}
                         >
                             <SelectTrigger className="w-full max-w-full focus:ring-ring/20 focus:border-ring overflow-hidden">
                                 <SelectValue placeholder={field.label} />
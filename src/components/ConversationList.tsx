@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useRef, useState, memo } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { EllipsisVertical } from "lucide-react";
+import { toast } from "sonner";
 import ConversationTitleEditDialog from "./ConversationTitleEditDialog";
 import useConversationManager from "../hooks/useConversationManager";
 import { Conversation } from "../data/Conversation";
@@ -21,6 +22,7 @@ interface ConversationItemProps {
     onSelect: (id: string) => void;
     onOpenTitleEdit: (id: number, title: string) => void;
     onOpenDelete: (id: string, name: string) => void;
+    onSummarize: (id: number) => void;
 }
 
 const ConversationItem = memo(function ConversationItem({
@@ -29,6 +31,7 @@ const ConversationItem = memo(function ConversationItem({
     onSelect,
     onOpenTitleEdit,
     onOpenDelete,
+    onSummarize,
 }: ConversationItemProps) {
     const { enabled: antiLeakageEnabled, isRevealed } = useAntiLeakage();
 
@@ -82,6 +85,15 @@ const ConversationItem = memo(function ConversationItem({
                     >
                         修改标题
                     </DropdownMenuItem>
+                    <DropdownMenuItem
+                        data-aipp-slot="chat-conversation-item-menu-summarize"
+                        onClick={(e) => {
+                            e.stopPropagation();
+                            onSummarize(conversation.id);
+                        }}
+                    >
+                        立即总结
+                    </DropdownMenuItem>
                     <DropdownMenuItem
                         data-aipp-slot="chat-conversation-item-menu-delete"
                         onClick={(e) => {
@@ -115,7 +127,7 @@ const ConversationList = memo(function ConversationList({
     const [hasMoreData, setHasMoreData] = useState(true);
     const [isLoadingMore, setIsLoadingMore] = useState(false);
     const scrollContainerRef = useRef<HTMLDivElement>(null);
-    const { deleteConversation, listConversations } = useConversationManager();
+    const { deleteConversation, listConversations, summarizeConversationNow } = useConversationManager();
 
     useEffect(() => {
         setIsLoading(true);
@@ -354,6 +366,16 @@ const ConversationList = memo(function ConversationList({
         setDeleteConversationName("");
     }, []);
 
+    const handleSummarize = useCallback(async (id: number) => {
+        toast.info("正在总结对话...");
+        try {
+            await summarizeConversationNow(id);
+            toast.success("对话总结已更新");
+        } catch (error) {
+            toast.error(`对话总结失败: ${error}`);
+        }
+    }, [summarizeConversationNow]);
+
     return (
         <div
             className="flex-1 overflow-y-auto overflow-x-hidden px-3 bg-background"
@@ -369,6 +391,7 @@ const ConversationList = memo(function ConversationList({
                         onSelect={onSelectConversation}
                         onOpenTitleEdit={openTitleEditDialog}
                         onOpenDelete={openDeleteDialog}
+                        onSummarize={handleSummarize}
                     />
                 ))}
             </ul>
@@ -601,6 +601,12 @@ const ConversationUI = forwardRef<ConversationUIRef, ConversationUIProps>(
             assistantRunApi,
         });
 
+        // 点击开场建议：回填到输入框，由用户确认后再发送
+        const handleStarterSelect = useCallback((starter: string) => {
+            setInputText(starter);
+            inputAreaRef.current?.focus();
+        }, []);
+
         // ============= 初始化和生命周期逻辑 =============
 
         // 暴露给外部的方法
@@ -926,6 +932,7 @@ const ConversationUI = forwardRef<ConversationUIRef, ConversationUIProps>(
                             selectedAssistant={selectedAssistant}
                             assistants={assistants}
                             setSelectedAssistant={setSelectedAssistant}
+                            onStarterSelect={handleStarterSelect}
                         />
                         <div ref={messagesEndRef} data-aipp-slot="chat-messages-end-anchor" />
                     </div>
@@ -19,10 +19,12 @@ import rehypeRaw from 'rehype-raw';
 import TipsComponent from '@/react-markdown/components/TipsComponent';
 import { resolveCodeBlockMeta } from '@/react-markdown/remarkCodeBlockMeta';
 import { useArtifactEvents, ArtifactData, EnvironmentCheckData } from '@/hooks/useArtifactEvents';
+import { usePreviewConsole } from '@/hooks/usePreviewConsole';
 import { Button } from '@/components/ui/button';
 import { Tabs, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { Loader2 } from 'lucide-react';
 import EnvironmentInstallDialog from '@/components/EnvironmentInstallDialog';
+import PreviewConsolePanel from '@/components/PreviewConsolePanel';
 import 'katex/dist/katex.min.css';
 
 interface EmbeddedArtifactPreviewProps {
@@ -45,6 +47,7 @@ export default function EmbeddedArtifactPreview({ className, previewOnly = false
     const [drawioXmlContent, setDrawioXmlContent] = useState<string>('');
     const [originalCode, setOriginalCode] = useState<string>('');
     const drawioIframeRef = useRef<HTMLIFrameElement>(null);
+    const previewIframeRef = useRef<HTMLIFrameElement>(null);
     const logsEndRef = useRef<HTMLDivElement | null>(null);
     const isInstalling = useRef<boolean>(false);
     const restoreAbortRef = useRef<boolean>(false);
@@ -63,6 +66,12 @@ export default function EmbeddedArtifactPreview({ className, previewOnly = false
         previewTypeRef.current = previewType;
     }, [previewType]);
 
+    // 收集 React/Vue 预览中的 console 输出与未捕获异常
+    const { log: previewConsoleLog, clear: clearPreviewConsole } = usePreviewConsole({
+        iframeRef: previewIframeRef,
+        previewId: previewType === 'react' || previewType === 'vue' ? previewType : null,
+    });
+
     useEffect(() => {
         currentLangRef.current = currentLang;
         currentInputStrRef.current = currentInputStr;
@@ -527,11 +536,15 @@ export default function EmbeddedArtifactPreview({ className, previewOnly = false
                             </div>
                         ) : (
                             /* React/Vue iframe 预览 */
-                            <iframe
-                                src={previewUrl || ''}
-                                className="w-full h-full border-0"
-                                sandbox="allow-scripts allow-same-origin allow-forms allow-popups"
-                            />
+                            <div className="h-full flex flex-col">
+                                <iframe
+                                    ref={previewIframeRef}
+                                    src={previewUrl || ''}
+                                    className="w-full flex-1 border-0"
+                                    sandbox="allow-scripts allow-same-origin allow-forms allow-popups"
+                                />
+                                <PreviewConsolePanel log={previewConsoleLog} onClear={clearPreviewConsole} />
+                            </div>
                         )}
                     </div>
                 )}
@@ -2,12 +2,13 @@ import React, { useMemo } from "react";
 import UnifiedMarkdown from "./UnifiedMarkdown";
 import ReasoningMessage from "./ReasoningMessage";
 import ErrorMessage from "./message-item/ErrorMessage";
+import FilteredMessage from "./message-item/FilteredMessage";
 import MessageActionButtons from "./message-item/MessageActionButtons";
 import ImageAttachments from "./message-item/ImageAttachments";
 import RawTextRenderer from "./RawTextRenderer";
 import { ShineBorder } from "./magicui/shine-border";
 import { DEFAULT_SHINE_BORDER_CONFIG } from "@/utils/shineConfig";
-import { Message, StreamEvent, MCPToolCallUpdateEvent } from "../data/Conversation";
+import { Message, StreamEvent, MCPToolCallUpdateEvent, RegenerateMode } from "../data/Conversation";
 import { useCopyHandler } from "../hooks/useCopyHandler";
 import { useCustomTagParser } from "../hooks/useCustomTagParser";
 import { useMarkdownConfig } from "../hooks/useMarkdownConfig";
@@ -21,7 +22,7 @@ interface MessageItemProps {
     message: Message;
     streamEvent?: StreamEvent;
     onCodeRun?: (lang: string, code: string) => void;
-    onMessageRegenerate?: () => void;
+    onMessageRegenerate?: (mode?: RegenerateMode) => void;
     onMessageEdit?: () => void;
     onMessageFork?: () => void;
     isReasoningExpanded?: boolean;
@@ -261,6 +262,11 @@ const MessageItem = React.memo<MessageItemProps>(
             return <ErrorMessage content={message.content} messageId={message.id} />;
         }
 
+        // 早期返回：内容审核拦截消息
+        if (message.message_type === "filtered") {
+            return <FilteredMessage content={message.content} messageId={message.id} />;
+        }
+
         // 常规消息渲染
         return (
             <div className="flex flex-col" data-message-item data-message-id={message.id} data-message-type={message.message_type}>
@@ -283,6 +289,10 @@ const MessageItem = React.memo<MessageItemProps>(
 
                     <ImageAttachments attachments={message.attachment_list} />
 
+                    {message.is_partial && (
+                        <div className="mt-2 text-xs text-muted-foreground">回复在生成中途中断，内容可能不完整</div>
+                    )}
+
                     <MessageActionButtons
                         messageType={message.message_type}
                         isUserMessage={isUserMessage}
@@ -290,6 +300,7 @@ const MessageItem = React.memo<MessageItemProps>(
                         onCopy={handleCopy}
                         onEdit={onMessageEdit}
                         onRegenerate={onMessageRegenerate}
+                        hasToolCalls={/<!--\s*MCP_TOOL_CALL:/.test(message.content)}
                         onFork={onMessageFork}
                         tokenCount={message.token_count}
                         inputTokenCount={message.input_token_count}
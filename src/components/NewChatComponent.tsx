@@ -1,3 +1,5 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import AskWindowPrepare from "./AskWindowPrepare";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "./ui/select";
 import { useIsMobile } from "../hooks/use-mobile";
@@ -12,6 +14,8 @@ interface NewChatComponentProps {
     selectedAssistant: number;
     setSelectedAssistant: (assistantId: number) => void;
     assistants: AssistantListItem[];
+    // 点击开场建议时回填到输入框
+    onStarterSelect?: (starter: string) => void;
 }
 
 const NewChatComponent: React.FC<NewChatComponentProps> = ({
@@ -19,8 +23,34 @@ const NewChatComponent: React.FC<NewChatComponentProps> = ({
     selectedAssistant,
     setSelectedAssistant,
     assistants,
+    onStarterSelect,
 }: NewChatComponentProps) => {
     const isMobile = useIsMobile();
+    const [starters, setStarters] = useState<string[]>([]);
+
+    // 切换助手时加载开场建议（模板变量由后端在此时解析）
+    useEffect(() => {
+        if (selectedAssistant < 0) {
+            setStarters([]);
+            return;
+        }
+        let cancelled = false;
+        invoke<string[]>("get_assistant_starters", { assistantId: selectedAssistant })
+            .then((result) => {
+                if (!cancelled) {
+                    setStarters(result);
+                }
+            })
+            .catch((error) => {
+                console.error("Failed to load assistant starters:", error);
+                if (!cancelled) {
+                    setStarters([]);
+                }
+            });
+        return () => {
+            cancelled = true;
+        };
+    }, [selectedAssistant]);
 
     // 移动端不需要拖动区域
     const dragProps = isMobile ? {} : { "data-tauri-drag-region": true };
@@ -53,6 +83,24 @@ const NewChatComponent: React.FC<NewChatComponentProps> = ({
                     ))}
                 </SelectContent>
             </Select>
+            {starters.length > 0 && onStarterSelect && (
+                <div
+                    className="mt-6 flex flex-wrap justify-center gap-2 max-w-2xl"
+                    data-aipp-slot="chat-new-conversation-starters"
+                >
+                    {starters.map((starter, index) => (
+                        <button
+                            key={index}
+                            type="button"
+                            className="px-3 py-1.5 text-sm rounded-full border border-border bg-background text-foreground hover:bg-muted transition-colors max-w-xs truncate"
+                            title={starter}
+                            onClick={() => onStarterSelect(starter)}
+                        >
+                            {starter}
+                        </button>
+                    ))}
+                </div>
+            )}
         </div>
     );
 };
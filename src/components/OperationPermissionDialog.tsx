@@ -7,6 +7,7 @@ import {
     AlertDialogTitle,
 } from "@/components/ui/alert-dialog";
 import { Button } from "@/components/ui/button";
+import { useEffect, useState } from "react";
 import { Shield, ShieldAlert, ShieldCheck } from "lucide-react";
 
 // Re-export types from InlineInteractionCards for backward compatibility
@@ -27,6 +28,13 @@ export interface OperationPermissionRequest {
     conversation_id?: number;
 }
 
+export interface OperationPermissionBatchRequest {
+    batch_id: string;
+    requests: OperationPermissionRequest[];
+}
+
+export type OperationPermissionDecision = 'allow' | 'allow_and_save' | 'deny';
+
 export interface AcpPermissionOption {
     option_id: string;
     name: string;
@@ -50,6 +58,13 @@ interface OperationPermissionDialogProps {
     errorMessage?: string | null;
 }
 
+interface OperationPermissionBatchDialogProps {
+    batch: OperationPermissionBatchRequest | null;
+    isOpen: boolean;
+    onDecision: (decisions: Record<string, OperationPermissionDecision>) => void;
+    errorMessage?: string | null;
+}
+
 interface AcpPermissionDialogProps {
     request: AcpPermissionRequest | null;
     isOpen: boolean;
@@ -148,6 +163,113 @@ export function OperationPermissionDialog({
     );
 }
 
+export function OperationPermissionBatchDialog({
+    batch,
+    isOpen,
+    onDecision,
+    errorMessage,
+}: OperationPermissionBatchDialogProps) {
+    // 每项默认允许，用户可逐项切换为拒绝
+    const [approved, setApproved] = useState<Record<string, boolean>>({});
+
+    useEffect(() => {
+        if (!batch) return;
+        setApproved(Object.fromEntries(batch.requests.map((request) => [request.request_id, true])));
+    }, [batch]);
+
+    if (!batch) return null;
+
+    const submitAll = (decision: OperationPermissionDecision) => {
+        onDecision(Object.fromEntries(batch.requests.map((request) => [request.request_id, decision])));
+    };
+
+    const submitSelected = () => {
+        onDecision(
+            Object.fromEntries(
+                batch.requests.map((request) => [
+                    request.request_id,
+                    approved[request.request_id] === false ? 'deny' : 'allow',
+                ])
+            )
+        );
+    };
+
+    return (
+        <AlertDialog open={isOpen}>
+            <AlertDialogContent className="max-w-lg">
+                <AlertDialogHeader>
+                    <AlertDialogTitle className="flex items-center gap-2">
+                        <Shield className="h-5 w-5 text-yellow-500" />
+                        批量操作权限请求
+                    </AlertDialogTitle>
+                    <AlertDialogDescription asChild>
+                        <div className="space-y-3">
+                            <p>AI 助手请求执行以下 {batch.requests.length} 个操作，可逐项取消勾选以拒绝：</p>
+                            <div className="rounded-md bg-muted p-3 space-y-2 max-h-64 overflow-y-auto">
+                                {batch.requests.map((request) => (
+                                    <label
+                                        key={request.request_id}
+                                        className="flex items-start gap-2 text-sm cursor-pointer"
+                                    >
+                                        <input
+                                            type="checkbox"
+                                            className="mt-0.5"
+                                            checked={approved[request.request_id] !== false}
+                                            onChange={(e) =>
+                                                setApproved((prev) => ({
+                                                    ...prev,
+                                                    [request.request_id]: e.target.checked,
+                                                }))
+                                            }
+                                        />
+                                        <span className="font-medium text-foreground shrink-0">
+                                            {operationLabels[request.operation] || request.operation}
+                                        </span>
+                                        <span className="font-mono text-xs break-all text-foreground">
+                                            {request.path}
+                                        </span>
+                                    </label>
+                                ))}
+                            </div>
+                            <p className="text-xs text-muted-foreground">
+                                这些路径不在允许访问的目录白名单中，被拒绝的操作不会影响其他已允许的操作。
+                            </p>
+                            {errorMessage ? (
+                                <p className="text-xs text-destructive">{errorMessage}</p>
+                            ) : null}
+                        </div>
+                    </AlertDialogDescription>
+                </AlertDialogHeader>
+                <AlertDialogFooter className="flex-col sm:flex-row gap-2">
+                    <Button
+                        variant="outline"
+                        onClick={() => submitAll('deny')}
+                        className="flex items-center gap-2"
+                    >
+                        <ShieldAlert className="h-4 w-4" />
+                        全部拒绝
+                    </Button>
+                    <Button
+                        variant="outline"
+                        onClick={submitSelected}
+                        className="flex items-center gap-2"
+                    >
+                        <Shield className="h-4 w-4" />
+                        允许所选
+                    </Button>
+                    <Button
+                        onClick={() => submitAll('allow')}
+                        className="flex items-center gap-2"
+                    >
+                        <ShieldCheck className="h-4 w-4" />
+                        全部允许
+                    </Button>
+                </AlertDialogFooter>
+            </AlertDialogContent>
+        </AlertDialog>
+    );
+}
+
 const acpOptionStyle = (kind: string) => {
     switch (kind) {
         case "allow_always":
@@ -8,13 +8,34 @@ interface PasswordDialogProps {
     isOpen: boolean;
     onClose: () => void;
     onConfirm: (password: string) => Promise<void>;
+    /** 以下文案默认用于导出配置，其他场景（如数据库口令）可以覆盖 */
+    heading?: string;
+    description?: string;
+    tips?: string[];
+    confirmText?: string;
+    loadingText?: string;
+    successMessage?: string;
+    failureMessage?: string;
 }
 
+const DEFAULT_TIPS = [
+    '请牢记此密码，丢失后无法恢复',
+    '建议使用强密码，包含字母、数字和特殊字符',
+    '导入时需要输入相同的密码才能解密',
+];
+
 const PasswordDialog: React.FC<PasswordDialogProps> = ({ 
     title, 
     isOpen, 
     onClose, 
-    onConfirm 
+    onConfirm,
+    heading = '设置导出密码',
+    description,
+    tips = DEFAULT_TIPS,
+    confirmText = '确认导出',
+    loadingText = '导出中...',
+    successMessage = '导出成功',
+    failureMessage = '导出失败',
 }) => {
     const [password, setPassword] = useState('');
     const [confirmPassword, setConfirmPassword] = useState('');
@@ -37,13 +58,13 @@ const PasswordDialog: React.FC<PasswordDialogProps> = ({
             setPassword('');
             setConfirmPassword('');
             onClose();
-            toast.success('导出成功');
+            toast.success(successMessage);
         } catch (error) {
-            toast.error(error instanceof Error ? error.message : '导出失败');
+            toast.error(error instanceof Error ? error.message : failureMessage);
         } finally {
             setLoading(false);
         }
-    }, [password, canSubmit, onConfirm, onClose]);
+    }, [password, canSubmit, onConfirm, onClose, successMessage, failureMessage]);
 
     const handleClose = useCallback(() => {
         if (loading) return;
@@ -76,7 +97,7 @@ const PasswordDialog: React.FC<PasswordDialogProps> = ({
                 <div className="flex items-center justify-between p-6 border-b border-border">
                     <div className="flex items-center gap-3">
                         <Lock className="h-5 w-5 text-primary" />
-                        <h2 className="text-xl font-semibold text-foreground">设置导出密码</h2>
+                        <h2 className="text-xl font-semibold text-foreground">{heading}</h2>
                     </div>
                     <button
                         onClick={handleClose}
@@ -91,7 +112,7 @@ const PasswordDialog: React.FC<PasswordDialogProps> = ({
                 <div className="p-6">
                     <div className="space-y-5">
                         <div className="text-sm text-muted-foreground">
-                            为了保护 {title} 的安全信息（如API密钥），请设置一个用于加密的密码。
+                            {description ?? `为了保护 ${title} 的安全信息（如API密钥），请设置一个用于加密的密码。`}
                         </div>
 
                         {/* 密码输入 */}
@@ -167,9 +188,9 @@ const PasswordDialog: React.FC<PasswordDialogProps> = ({
                                 <div className="text-xs text-orange-700 dark:text-orange-300">
                                     <p className="font-medium mb-1">安全提示：</p>
                                     <ul className="space-y-1">
-                                        <li>• 请牢记此密码，丢失后无法恢复</li>
-                                        <li>• 建议使用强密码，包含字母、数字和特殊字符</li>
-                                        <li>• 导入时需要输入相同的密码才能解密</li>
+                                        {tips.map((tip) => (
+                                            <li key={tip}>• {tip}</li>
+                                        ))}
                                     </ul>
                                 </div>
                             </div>
@@ -192,7 +213,7 @@ const PasswordDialog: React.FC<PasswordDialogProps> = ({
                         disabled={!canSubmit}
                         className="px-6 bg-primary hover:bg-primary/90 text-primary-foreground shadow-md hover:shadow-lg transition-all disabled:opacity-50 disabled:cursor-not-allowed"
                     >
-                        {loading ? '导出中...' : '确认导出'}
+                        {loading ? loadingText : confirmText}
                     </Button>
                 </div>
             </div>
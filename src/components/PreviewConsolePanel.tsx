@@ -0,0 +1,112 @@
+/**
+ * 预览错误面板
+ * 展示 React/Vue 预览中的未捕获异常与 console.error/warn 输出，
+ * 首个未捕获异常单独突出显示，可复制后发给助手修正代码
+ */
+import { useState } from 'react';
+import { writeText } from '@tauri-apps/plugin-clipboard-manager';
+import { AlertTriangle, ChevronDown, ChevronRight, Copy, Trash2 } from 'lucide-react';
+import { toast } from 'sonner';
+import { Button } from '@/components/ui/button';
+import type { PreviewConsoleEntry, PreviewConsoleLog } from '@/hooks/usePreviewConsole';
+
+interface PreviewConsolePanelProps {
+    log: PreviewConsoleLog | null;
+    onClear: () => void;
+}
+
+const LEVEL_LABELS: Record<string, string> = {
+    uncaught: '未捕获异常',
+    unhandledrejection: '未处理的 Promise 拒绝',
+    error: 'console.error',
+    warn: 'console.warn',
+};
+
+function formatEntry(entry: PreviewConsoleEntry): string {
+    const label = LEVEL_LABELS[entry.level] ?? entry.level;
+    return entry.stack && !entry.message.includes(entry.stack)
+        ? `[${label}] ${entry.message}\n${entry.stack}`
+        : `[${label}] ${entry.message}`;
+}
+
+function isSameEntry(a: PreviewConsoleEntry, b: PreviewConsoleEntry | null): boolean {
+    return !!b && a.timestamp === b.timestamp && a.level === b.level && a.message === b.message;
+}
+
+export default function PreviewConsolePanel({ log, onClear }: PreviewConsolePanelProps) {
+    const [expanded, setExpanded] = useState(false);
+
+    if (!log || log.error_count === 0) {
+        return null;
+    }
+
+    const firstError = log.first_error;
+    // 首个未捕获异常单独展示，这里只列出其余的错误与警告
+    const problems = log.entries.filter(
+        (entry) => entry.level !== 'log' && entry.level !== 'info' && !isSameEntry(entry, firstError)
+    );
+
+    const handleCopy = async () => {
+        const lines = ['预览运行时出现错误：'];
+        if (firstError) {
+            lines.push('首个未捕获异常：', formatEntry(firstError));
+        }
+        if (problems.length > 0) {
+            lines.push('', '其他错误与警告：', ...problems.map(formatEntry));
+        }
+        try {
+            await writeText(lines.join('\n'));
+            toast.success('已复制预览错误');
+        } catch (e) {
+            toast.error('复制失败: ' + e);
+        }
+    };
+
+    return (
+        <div className="border-t border-destructive/30 bg-destructive/5 text-sm">
+            <div className="flex items-center gap-2 px-3 py-2">
+                <button
+                    type="button"
+                    className="flex flex-1 items-center gap-2 text-left text-destructive"
+                    onClick={() => setExpanded((value) => !value)}
+                >
+                    {expanded ? <ChevronDown className="h-4 w-4" /> : <ChevronRight className="h-4 w-4" />}
+                    <AlertTriangle className="h-4 w-4" />
+                    <span className="font-medium">预览错误 ({log.error_count})</span>
+                    {firstError && !expanded && (
+                        <span className="truncate text-xs text-muted-foreground">{firstError.message}</span>
+                    )}
+                </button>
+                <Button variant="ghost" size="sm" onClick={handleCopy} title="复制错误信息，可发给助手修正代码">
+                    <Copy className="h-4 w-4" />
+                </Button>
+                <Button variant="ghost" size="sm" onClick={onClear} title="清空">
+                    <Trash2 className="h-4 w-4" />
+                </Button>
+            </div>
+            {expanded && (
+                <div className="max-h-64 space-y-2 overflow-auto px-3 pb-3">
+                    {firstError && (
+                        <div className="rounded border border-destructive/40 bg-background p-2">
+                            <div className="mb-1 text-xs font-medium text-destructive">首个未捕获异常</div>
+                            <pre className="whitespace-pre-wrap break-all font-mono text-xs">{formatEntry(firstError)}</pre>
+                        </div>
+                    )}
+                    {problems.map((entry, index) => (
+                        <pre
+                            key={`${entry.timestamp}-${index}`}
+                            className={`whitespace-pre-wrap break-all rounded bg-background p-2 font-mono text-xs ${
+                                entry.level === 'warn' ? 'text-amber-600' : 'text-destructive'
+                            }`}
+                        >
+                            {formatEntry(entry)}
+                        </pre>
+                    ))}
+                    {log.dropped > 0 && (
+                        <div className="text-xs text-muted-foreground">已省略较早的 {log.dropped} 条日志</div>
+                    )}
+                </div>
+            )}
+        </div>
+    );
+}
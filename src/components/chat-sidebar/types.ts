@@ -39,6 +39,7 @@ export interface SearchResultItem {
     snippet?: string;
     displayUrl?: string;
     rank?: number;
+    relevanceRank?: number; // 按相关度重排后的排名（启用重排时）
 }
 
 export interface ContextItem {
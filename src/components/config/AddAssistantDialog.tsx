@@ -13,6 +13,12 @@ import { toast } from "sonner";
 import { AssistantDetail } from "../../data/Assistant";
 import { Plus } from "lucide-react";
 
+interface AssistantTemplate {
+    id: string;
+    name: string;
+    description: string;
+}
+
 interface AddAssistantDialogProps {
     assistantTypes: AssistantType[];
     onAssistantAdded: (assistantDetail: AssistantDetail) => void;
@@ -25,6 +31,26 @@ const AddAssistantDialog: React.FC<AddAssistantDialogProps> = ({
     triggerButtonProps,
 }) => {
     const [openAddAssistantDialog, setOpenAddAssistantDialog] = React.useState<boolean>(false);
+    const [templates, setTemplates] = React.useState<AssistantTemplate[]>([]);
+
+    React.useEffect(() => {
+        if (!openAddAssistantDialog) return;
+        invoke<AssistantTemplate[]>("list_assistant_templates")
+            .then(setTemplates)
+            .catch((error) => console.error("加载助手模板失败:", error));
+    }, [openAddAssistantDialog]);
+
+    const createFromTemplate = (templateId: string) => {
+        invoke<AssistantDetail>("create_assistant_from_template", { templateId })
+            .then((assistantDetail: AssistantDetail) => {
+                onAssistantAdded(assistantDetail);
+                setOpenAddAssistantDialog(false);
+                toast.success("已从模板创建助手");
+            })
+            .catch((error) => {
+                toast.error("从模板创建助手失败: " + error);
+            });
+    };
 
     const formSchema = z.object({
         name: z.string().min(1, "名称不能为空"),
@@ -70,6 +96,25 @@ const AddAssistantDialog: React.FC<AddAssistantDialogProps> = ({
                 <DialogHeader>
                     <DialogTitle>新增助手</DialogTitle>
                 </DialogHeader>
+                {templates.length > 0 && (
+                    <div className="space-y-2">
+                        <div className="text-sm font-medium">从模板创建</div>
+                        <div className="grid grid-cols-2 gap-2">
+                            {templates.map((template) => (
+                                <Button
+                                    key={template.id}
+                                    type="button"
+                                    variant="outline"
+                                    className="h-auto flex-col items-start whitespace-normal text-left"
+                                    onClick={() => createFromTemplate(template.id)}
+                                >
+                                    <span className="font-medium">{template.name}</span>
+                                    <span className="text-xs text-muted-foreground">{template.description}</span>
+                                </Button>
+                            ))}
+                        </div>
+                    </div>
+                )}
                 <Form {...form}>
                     <form onSubmit={form.handleSubmit(onSubmit)} className="space-y-4">
                         <FormField
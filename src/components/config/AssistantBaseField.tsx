@@ -0,0 +1,77 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from 'sonner';
+import { Assistant } from "@/data/Assistant";
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "../ui/select";
+
+const NO_BASE = "none";
+
+interface AssistantBaseFieldProps {
+    assistantId: number;
+    assistantType: number;
+    // 当前继承的基础助手，来自 get_assistant 返回的 base_assistant_id
+    baseAssistantId: number | null;
+    onConfigChange?: () => void;
+}
+
+const AssistantBaseField: React.FC<AssistantBaseFieldProps> = ({
+    assistantId,
+    assistantType,
+    baseAssistantId,
+    onConfigChange
+}) => {
+    const [assistants, setAssistants] = useState<Assistant[]>([]);
+    const [value, setValue] = useState<string>(baseAssistantId ? String(baseAssistantId) : NO_BASE);
+
+    useEffect(() => {
+        setValue(baseAssistantId ? String(baseAssistantId) : NO_BASE);
+    }, [assistantId, baseAssistantId]);
+
+    useEffect(() => {
+        invoke<Assistant[]>("get_assistants")
+            .then((list) => setAssistants(
+                list.filter((a) => a.id !== assistantId && a.assistant_type === assistantType)
+            ))
+            .catch((error) => console.error('Failed to load assistants:', error));
+    }, [assistantId, assistantType]);
+
+    const handleChange = useCallback(async (next: string) => {
+        const previous = value;
+        setValue(next);
+        try {
+            await invoke('set_assistant_base', {
+                assistantId,
+                baseAssistantId: next === NO_BASE ? null : Number(next),
+            });
+            toast.success(next === NO_BASE ? '已取消继承' : '基础助手已更新');
+            onConfigChange?.();
+        } catch (error) {
+            console.error('Failed to set assistant base:', error);
+            toast.error('设置基础助手失败: ' + error);
+            setValue(previous);
+        }
+    }, [assistantId, value, onConfigChange]);
+
+    return (
+        <div className="space-y-2">
+            <Select value={value} onValueChange={handleChange}>
+                <SelectTrigger>
+                    <SelectValue placeholder="不继承" />
+                </SelectTrigger>
+                <SelectContent>
+                    <SelectItem value={NO_BASE}>不继承</SelectItem>
+                    {assistants.map((assistant) => (
+                        <SelectItem key={assistant.id} value={String(assistant.id)}>
+                            {assistant.name}
+                        </SelectItem>
+                    ))}
+                </SelectContent>
+            </Select>
+            <p className="text-xs text-muted-foreground">
+                继承基础助手的提示词、模型配置、工具与技能：提示词追加在基础助手之后，同名配置以当前助手为准，留空的配置沿用基础助手
+            </p>
+        </div>
+    );
+};
+
+export default AssistantBaseField;
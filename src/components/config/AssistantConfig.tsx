@@ -1,6 +1,7 @@
 import React, { useCallback, useEffect, useMemo, useState } from "react";
 import { toast } from "sonner";
-import { AssistantDetail, AssistantListItem } from "../../data/Assistant";
+import { invoke } from "@tauri-apps/api/core";
+import { AssistantDetail, AssistantListItem, PromptLintIssue } from "../../data/Assistant";
 import { useAssistantListListener } from "../../hooks/useAssistantListListener";
 import { Bot, Settings, User, Download } from "lucide-react";
 import { Button } from "../ui/button";
@@ -244,6 +245,24 @@ const AssistantConfig: React.FC<AssistantConfigProps> = ({ pluginList, navigateT
 
         const values = form.getValues();
 
+        // 检查提示词，只提示问题，不阻止保存
+        invoke<PromptLintIssue[]>("lint_assistant_prompt", {
+            prompt: values.prompt ?? "",
+            params: currentAssistant.prompt_params.map((param) => param.param_name),
+        })
+            .then((issues) => {
+                const warnings = issues.filter((issue) => issue.severity === "warning");
+                if (warnings.length > 0) {
+                    toast.warning(`提示词检查发现 ${warnings.length} 个问题`, {
+                        description: warnings
+                            .slice(0, 3)
+                            .map((issue) => `第 ${issue.line} 行：${issue.message}`)
+                            .join("\n"),
+                    });
+                }
+            })
+            .catch((error) => console.warn("lint_assistant_prompt failed", error));
+
         saveAssistant({
             ...currentAssistant,
             assistant: {
@@ -292,6 +311,10 @@ const AssistantConfig: React.FC<AssistantConfigProps> = ({ pluginList, navigateT
                         return true;
                     }
 
+                    if (key === "edit_behavior") {
+                        return true;
+                    }
+
                     if (key === "dynamic_mcp_loading_enabled") {
                         return true;
                     }
@@ -339,8 +362,8 @@ const AssistantConfig: React.FC<AssistantConfigProps> = ({ pluginList, navigateT
                         } else {
                             valueType = "string";
                         }
-                    } else if (key === "reasoning_effort") {
-                        // 内置 reasoning_effort 字段
+                    } else if (key === "reasoning_effort" || key === "edit_behavior") {
+                        // 内置 reasoning_effort / edit_behavior 字段
                         valueType = "string";
                     }
 
@@ -0,0 +1,62 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from 'sonner';
+import { Textarea } from "../ui/textarea";
+import { Button } from "../ui/button";
+
+interface AssistantStartersFieldProps {
+    assistantId: number;
+    // 原始模板（未解析变量），来自 get_assistant 返回的 starters
+    starters: string[];
+    onConfigChange?: () => void;
+}
+
+const AssistantStartersField: React.FC<AssistantStartersFieldProps> = ({
+    assistantId,
+    starters,
+    onConfigChange
+}) => {
+    const initialText = starters.join('\n');
+    const [text, setText] = useState<string>(initialText);
+    const [saving, setSaving] = useState(false);
+
+    useEffect(() => {
+        setText(initialText);
+    }, [assistantId, initialText]);
+
+    const handleSave = useCallback(async () => {
+        const items = text.split('\n').map((line) => line.trim()).filter((line) => line.length > 0);
+        try {
+            setSaving(true);
+            await invoke('update_assistant_starters', { assistantId, starters: items });
+            toast.success('开场建议已保存');
+            onConfigChange?.();
+        } catch (error) {
+            console.error('Failed to update assistant starters:', error);
+            toast.error('保存开场建议失败: ' + error);
+        } finally {
+            setSaving(false);
+        }
+    }, [assistantId, text, onConfigChange]);
+
+    return (
+        <div className="space-y-2">
+            <Textarea
+                className="h-28"
+                value={text}
+                placeholder={'每行一条，例如：\n帮我用 !assistant_name 的风格写一段自我介绍'}
+                onChange={(e) => setText(e.target.value)}
+            />
+            <div className="flex items-center justify-between gap-2">
+                <p className="text-xs text-muted-foreground">
+                    新对话中以可点击的建议展示，支持 !cd、!assistant_name 等模板变量
+                </p>
+                <Button size="sm" variant="outline" disabled={saving} onClick={handleSave}>
+                    保存建议
+                </Button>
+            </div>
+        </div>
+    );
+};
+
+export default AssistantStartersField;
@@ -89,18 +89,22 @@ const BuiltinToolDialog: React.FC<BuiltinToolDialogProps> = ({
         if (editing && !currentTemplate) return; // Wait for template to load in editing mode
 
         // Parse the initial env text
+        // Continuation lines of multi-line values (directory / command lists) belong to the previous KEY
         const parsedEnvs: Record<string, string> = {};
         if (initialEnvText) {
+            let currentKey: string | null = null;
             initialEnvText
                 .split("\n")
                 .map((l) => l.trim())
                 .filter(Boolean)
                 .forEach((line) => {
-                    const idx = line.indexOf("=");
-                    if (idx > 0) {
-                        const k = line.slice(0, idx).trim();
-                        const v = line.slice(idx + 1).trim();
-                        if (k) parsedEnvs[k] = v;
+                    const match = line.match(/^([A-Za-z_][A-Za-z0-9_]*)\s*=(.*)$/);
+                    if (match) {
+                        currentKey = match[1];
+                        parsedEnvs[currentKey] = match[2].trim();
+                    } else if (currentKey) {
+                        const prev = parsedEnvs[currentKey];
+                        parsedEnvs[currentKey] = prev ? `${prev}\n${line}` : line;
                     }
                 });
         }
@@ -109,6 +109,7 @@ const FeatureAssistantConfig: React.FC = () => {
             notification_on_completion: "false",
             code_theme_light: "github",
             code_theme_dark: "github-dark",
+            edit_behavior: "truncate",
         },
     });
 
@@ -121,12 +122,18 @@ const FeatureAssistantConfig: React.FC = () => {
             title_model: "",
             title_summary_length: "100",
             title_prompt: "",
+            title_trigger: "first_response",
+            title_trigger_turns: "2",
+            title_include_tool_results: false,
             // 表单自动填写
             form_autofill_enabled: true,
             form_autofill_model: "",
             // 对话总结（实验功能，默认关闭）
             conversation_summary_enabled: false,
             conversation_summary_model: "",
+            summary_min_messages: "0",
+            summary_min_tokens: "0",
+            summary_idle_minutes: "10",
             // 记忆总结（实验功能，默认关闭）
             memory_summary_enabled: false,
             memory_summary_model: "",
@@ -145,19 +152,35 @@ const FeatureAssistantConfig: React.FC = () => {
     const networkForm = useForm({
         defaultValues: {
             request_timeout: "180",
+            stream_idle_timeout_ms: "0",
+            model_list_cache_ttl_secs: "600",
+            mcp_health_check_interval_secs: "300",
+            mcp_tool_timeout_ms: "",
             retry_attempts: "3",
+            retry_backoff: "exponential",
+            retry_max_delay_ms: "",
+            bad_request_fallback: "remove_tools,strip_extra_params",
             network_proxy: "",
         },
     });
 
-    const dataFolderForm = useForm({});
+    const dataFolderForm = useForm({
+        defaultValues: {
+            backup_enabled: false,
+            backup_interval_hours: "24",
+            backup_keep_count: "7",
+            backup_directory: "",
+        },
+    });
 
     // 根据平台设置快捷键默认值
     const isMac = typeof navigator !== 'undefined' && navigator.userAgent.toLowerCase().indexOf('mac') !== -1;
     const shortcutsForm = useForm({
         defaultValues: {
             // 新格式：标准 accelerator 字符串，例如 "Alt+Space"、"Ctrl+Shift+I"
-            shortcut: isMac ? "Option+Space" : "Alt+Space",
+            shortcut_ask: isMac ? "Option+Space" : "Alt+Space",
+            // 直接打开 Chat 窗口，为空表示不注册
+            shortcut_chat: "",
             // 兼容旧字段
             modifier_key: isMac ? "option" : "alt",
         },
@@ -196,6 +219,7 @@ const FeatureAssistantConfig: React.FC = () => {
                     notification_on_completion: displayConfig.get("notification_on_completion") || "false",
                     code_theme_light: displayConfig.get("code_theme_light") || "github",
                     code_theme_dark: displayConfig.get("code_theme_dark") || "github-dark",
+                    edit_behavior: displayConfig.get("edit_behavior") || "truncate",
                 });
             }
 
@@ -222,6 +246,9 @@ const FeatureAssistantConfig: React.FC = () => {
                     title_model: modelCode && providerId ? `${modelCode}%%${providerId}` : "",
                     title_summary_length: summaryConfig.get("title_summary_length") || summaryConfig.get("summary_length") || "100",
                     title_prompt: summaryConfig.get("title_prompt") || summaryConfig.get("prompt") || "",
+                    title_trigger: summaryConfig.get("title_trigger") || "first_response",
+                    title_trigger_turns: summaryConfig.get("title_trigger_turns") || "2",
+                    title_include_tool_results: summaryConfig.get("title_include_tool_results") === "true",
                     // 表单自动填写
                     form_autofill_enabled: summaryConfig.get("form_autofill_enabled") !== "false",
                     form_autofill_model: summaryConfig.get("form_autofill_model") || "",
@@ -232,6 +259,9 @@ const FeatureAssistantConfig: React.FC = () => {
                         const providerId = summaryConfig.get("conversation_summary_provider_id") || "";
                         return model && providerId ? `${model}%%${providerId}` : "";
                     })(),
+                    summary_min_messages: summaryConfig.get("summary_min_messages") || "0",
+                    summary_min_tokens: summaryConfig.get("summary_min_tokens") || "0",
+                    summary_idle_minutes: summaryConfig.get("summary_idle_minutes") || "10",
                     // 记忆总结
                     memory_summary_enabled: summaryConfig.get("memory_summary_enabled") !== "false",
                     memory_summary_model: (() => {
@@ -265,15 +295,35 @@ const FeatureAssistantConfig: React.FC = () => {
             if (networkConfig) {
                 networkForm.reset({
                     request_timeout: networkConfig.get("request_timeout") || "180",
+                    stream_idle_timeout_ms: networkConfig.get("stream_idle_timeout_ms") || "0",
+                    model_list_cache_ttl_secs: networkConfig.get("model_list_cache_ttl_secs") || "600",
+                    mcp_health_check_interval_secs: networkConfig.get("mcp_health_check_interval_secs") || "300",
+                    mcp_tool_timeout_ms: networkConfig.get("mcp_tool_timeout_ms") || "",
                     retry_attempts: networkConfig.get("retry_attempts") || "3",
+                    retry_backoff: networkConfig.get("retry_backoff") || "exponential",
+                    retry_max_delay_ms: networkConfig.get("retry_max_delay_ms") || "",
+                    // 空字符串表示关闭降级重试，因此只在未配置时使用默认顺序
+                    bad_request_fallback:
+                        networkConfig.get("bad_request_fallback") ?? "remove_tools,strip_extra_params",
                     network_proxy: networkConfig.get("network_proxy") || "",
                 });
             }
 
+            // 更新自动备份配置
+            const backupConfig = featureConfig.get("data_backup");
+            if (backupConfig) {
+                dataFolderForm.reset({
+                    backup_enabled: backupConfig.get("backup_enabled") === "true",
+                    backup_interval_hours: backupConfig.get("backup_interval_hours") || "24",
+                    backup_keep_count: backupConfig.get("backup_keep_count") || "7",
+                    backup_directory: backupConfig.get("backup_directory") || "",
+                });
+            }
+
             // 更新 shortcuts 表单
             const shortcutsConfig = featureConfig.get("shortcuts");
             if (shortcutsConfig) {
-                const shortcut = shortcutsConfig.get("shortcut");
+                const shortcut = shortcutsConfig.get("shortcut_ask") || shortcutsConfig.get("shortcut");
                 const modifier_key = shortcutsConfig.get("modifier_key") || (isMac ? "option" : "alt");
                 // 若无新字段，则按旧逻辑回退到 修饰键+Space
                 const fallbackShortcut = (() => {
@@ -284,7 +334,8 @@ const FeatureAssistantConfig: React.FC = () => {
                     return isMac ? "Option+Space" : "Alt+Space";
                 })();
                 shortcutsForm.reset({
-                    shortcut: shortcut || fallbackShortcut,
+                    shortcut_ask: shortcut || fallbackShortcut,
+                    shortcut_chat: shortcutsConfig.get("shortcut_chat") || "",
                     modifier_key,
                 });
             }
@@ -326,6 +377,7 @@ const FeatureAssistantConfig: React.FC = () => {
             notification_on_completion: values.notification_on_completion.toString(),
             code_theme_light: values.code_theme_light,
             code_theme_dark: values.code_theme_dark,
+            edit_behavior: values.edit_behavior,
         });
     }, [displayForm, saveFeatureConfig]);
 
@@ -361,6 +413,9 @@ const FeatureAssistantConfig: React.FC = () => {
             title_provider_id: titleModel.provider_id,
             title_summary_length: values.title_summary_length,
             title_prompt: values.title_prompt,
+            title_trigger: values.title_trigger,
+            title_trigger_turns: values.title_trigger_turns,
+            title_include_tool_results: values.title_include_tool_results.toString(),
             // 表单自动填写
             form_autofill_enabled: values.form_autofill_enabled.toString(),
             form_autofill_model: values.form_autofill_model,
@@ -369,6 +424,9 @@ const FeatureAssistantConfig: React.FC = () => {
             conversation_summary_enabled: values.conversation_summary_enabled.toString(),
             conversation_summary_model: conversationSummaryModel.model_code,
             conversation_summary_provider_id: conversationSummaryModel.provider_id,
+            summary_min_messages: values.summary_min_messages,
+            summary_min_tokens: values.summary_min_tokens,
+            summary_idle_minutes: values.summary_idle_minutes,
             // 记忆总结
             memory_summary_enabled: values.memory_summary_enabled.toString(),
             memory_summary_model: memorySummaryModel.model_code,
@@ -380,16 +438,35 @@ const FeatureAssistantConfig: React.FC = () => {
         const values = networkForm.getValues();
         await saveFeatureConfig("network_config", {
             request_timeout: values.request_timeout,
+            stream_idle_timeout_ms: values.stream_idle_timeout_ms,
+            model_list_cache_ttl_secs: values.model_list_cache_ttl_secs,
+            mcp_health_check_interval_secs: values.mcp_health_check_interval_secs,
+            mcp_tool_timeout_ms: values.mcp_tool_timeout_ms,
             retry_attempts: values.retry_attempts,
+            retry_backoff: values.retry_backoff,
+            retry_max_delay_ms: values.retry_max_delay_ms,
+            bad_request_fallback: values.bad_request_fallback,
             network_proxy: values.network_proxy,
         });
     }, [networkForm, saveFeatureConfig]);
 
+    const handleSaveDataBackupConfig = useCallback(async () => {
+        const v = dataFolderForm.getValues();
+        await saveFeatureConfig("data_backup", {
+            backup_enabled: String(v.backup_enabled),
+            backup_interval_hours: v.backup_interval_hours,
+            backup_keep_count: v.backup_keep_count,
+            backup_directory: v.backup_directory,
+        });
+    }, [dataFolderForm, saveFeatureConfig]);
+
     const handleSaveShortcutsConfig = useCallback(async () => {
         const v = shortcutsForm.getValues();
         await saveFeatureConfig("shortcuts", {
-            // 保存新旧两种字段，后端优先读取 shortcut
-            shortcut: v.shortcut,
+            // 保存新旧字段，后端优先读取 shortcut_ask，旧字段 shortcut 同步为 Ask 快捷键
+            shortcut_ask: v.shortcut_ask,
+            shortcut_chat: v.shortcut_chat,
+            shortcut: v.shortcut_ask,
             modifier_key: v.modifier_key,
         });
     }, [shortcutsForm, saveFeatureConfig]);
@@ -468,11 +545,12 @@ const FeatureAssistantConfig: React.FC = () => {
                 onSaveDisplay={handleSaveDisplayConfig}
                 onSaveSummary={handleSaveSummaryConfig}
                 onSaveNetwork={handleSaveNetworkConfig}
+                onSaveDataBackup={handleSaveDataBackupConfig}
                 onSaveShortcuts={handleSaveShortcutsConfig}
                 onSaveExperimental={handleSaveExperimentalConfig}
             />
         </div>
-    ), [selectedFeature, displayForm, summaryForm, previewForm, networkForm, dataFolderForm, shortcutsForm, otherForm, experimentalForm, aboutForm, versionManager, handleSaveDisplayConfig, handleSaveSummaryConfig, handleSaveNetworkConfig, handleSaveShortcutsConfig, handleSaveExperimentalConfig]);
+    ), [selectedFeature, displayForm, summaryForm, previewForm, networkForm, dataFolderForm, shortcutsForm, otherForm, experimentalForm, aboutForm, versionManager, handleSaveDisplayConfig, handleSaveSummaryConfig, handleSaveNetworkConfig, handleSaveDataBackupConfig, handleSaveShortcutsConfig, handleSaveExperimentalConfig]);
 
     return (
         <ConfigPageLayout
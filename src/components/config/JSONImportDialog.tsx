@@ -11,13 +11,16 @@ interface JSONImportDialogProps {
     isOpen: boolean;
     onClose: () => void;
     onImport: (configs: MCPServerRequest[]) => void;
+    // AIPP 导出的 MCP 服务器配置（type 为 mcp_server）交给后端导入
+    onImportShared: (json: string) => void;
 }
 
 
 const JSONImportDialog: React.FC<JSONImportDialogProps> = ({
     isOpen,
     onClose,
-    onImport
+    onImport,
+    onImportShared
 }) => {
     const [jsonText, setJsonText] = useState('');
     const [isLoading, setIsLoading] = useState(false);
@@ -89,7 +92,8 @@ const JSONImportDialog: React.FC<JSONImportDialogProps> = ({
                 if (!config.url.startsWith('http')) {
                     throw new Error(`服务器 "${serverName}" 的url必须是http/https`);
                 }
-                transport_type = 'http';
+                // 兼容常见配置中的 type/transport 字段声明 SSE 传输
+                transport_type = config.type === 'sse' || config.transport === 'sse' ? 'sse' : 'http';
                 url = config.url;
             } else if (config.command || config.args) {
                 transport_type = 'stdio';
@@ -144,8 +148,8 @@ const JSONImportDialog: React.FC<JSONImportDialogProps> = ({
             throw new Error('缺少必需的字段: name');
         }
 
-        if (!config.transport_type || !['stdio', 'http'].includes(config.transport_type)) {
-            throw new Error('transport_type必须是stdio或http之一');
+        if (!config.transport_type || !['stdio', 'sse', 'http'].includes(config.transport_type)) {
+            throw new Error('transport_type必须是stdio、sse或http之一');
         }
 
         // 类型特定验证
@@ -153,7 +157,7 @@ const JSONImportDialog: React.FC<JSONImportDialogProps> = ({
             throw new Error('stdio类型必须提供command字段');
         }
 
-        if ((config.transport_type === 'http') && !config.url) {
+        if ((config.transport_type === 'http' || config.transport_type === 'sse') && !config.url) {
             throw new Error(`${config.transport_type}类型必须提供url字段`);
         }
 
@@ -188,6 +192,10 @@ const JSONImportDialog: React.FC<JSONImportDialogProps> = ({
 
         try {
             const config = JSON.parse(jsonText);
+            if (config.type === 'mcp_server' && config.server) {
+                onImportShared(jsonText);
+                return;
+            }
             const mcpConfigs = validateMCPConfig(config);
             onImport(mcpConfigs);
             const count = mcpConfigs.length;
@@ -196,7 +204,7 @@ const JSONImportDialog: React.FC<JSONImportDialogProps> = ({
             const errorMessage = error instanceof Error ? error.message : 'JSON格式无效';
             toast.error(`导入失败: ${errorMessage}`);
         }
-    }, [jsonText, onImport]);
+    }, [jsonText, onImport, onImportShared]);
 
     const handleCancel = useCallback(() => {
         onClose();
@@ -29,6 +29,7 @@ interface LLMProvider {
     description: string;
     is_official: boolean;
     is_enabled: boolean;
+    keep_alive_active?: boolean;
 }
 
 const LLMProviderConfig: React.FC = () => {
@@ -86,6 +87,7 @@ const LLMProviderConfig: React.FC = () => {
         { value: 'anthropic', label: 'Anthropic API' },
         { value: 'cohere', label: 'Cohere API' },
         { value: 'deepseek', label: 'DeepSeek API' },
+        { value: 'azure', label: 'Azure OpenAI' },
         { value: 'github_copilot', label: 'GitHub Copilot' },
         { value: 'acp', label: 'ACP (Agent Client Protocol)' },
     ]
@@ -306,6 +308,11 @@ const LLMProviderConfig: React.FC = () => {
                         <div className="flex-1 truncate">
                             <div className="font-medium truncate">{provider.name}</div>
                         </div>
+                        {provider.keep_alive_active && (
+                            <span className="ml-2 text-xs text-muted-foreground flex-shrink-0" title="连接保活中">
+                                保活
+                            </span>
+                        )}
                         {provider.is_enabled && (
                             <Zap className="h-3 w-3 ml-2 flex-shrink-0" />
                         )}
@@ -96,6 +96,7 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
 
     const isCopilotProvider = apiType === "github_copilot";
     const isAcpProvider = apiType === "acp";
+    const isAzureProvider = apiType === "azure";
 
     // API 类型显示标签映射
     const apiTypeLabels: Record<string, string> = {
@@ -104,6 +105,7 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
         'anthropic': 'Anthropic API',
         'cohere': 'Cohere API',
         'deepseek': 'DeepSeek API',
+        'azure': 'Azure OpenAI',
         'github_copilot': 'GitHub Copilot',
         'acp': 'ACP(Agent Client Protocol)',
     };
@@ -120,6 +122,16 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
         { value: "gemini", label: "Gemini CLI (原生支持)" },
     ];
 
+    // API 协议选项：决定请求构建与响应解析方式，auto 表示按 API 类型自动推断
+    const apiDialectOptions = [
+        { value: "auto", label: "自动（按 API 类型推断）" },
+        { value: "openai", label: "OpenAI Chat Completions" },
+        { value: "anthropic", label: "Anthropic Messages" },
+        { value: "gemini", label: "Gemini generateContent" },
+        { value: "cohere", label: "Cohere" },
+        { value: "ollama", label: "Ollama" },
+    ];
+
     // GitHub Copilot 授权管理
     const copilot = useCopilot({
         llmProviderId: id,
@@ -145,7 +157,11 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
             endpoint: "",
             api_key: "",
             proxy_enabled: "false",
+            api_dialect: "auto",
             acp_cli_command: "",
+            resource_name: "",
+            api_version: "v1",
+            deployment_names: "",
         }),
         [],
     );
@@ -207,6 +223,10 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
             endpoint: "",
             api_key: "",
             proxy_enabled: "false",
+            api_dialect: "auto",
+            resource_name: "",
+            api_version: "v1",
+            deployment_names: "",
         });
         setTags([]);
         setHasApiKey(false);
@@ -218,7 +238,7 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
             configArray.forEach((item) => {
                 newConfig[item.name] = item.value;
             });
-            form.reset(newConfig);
+            form.reset({ api_dialect: "auto", api_version: "v1", ...newConfig });
 
             // 检查 GitHub Copilot 是否有 api_key
             if (isCopilotProvider) {
@@ -675,22 +695,73 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
                     value: apiTypeLabel,
                 },
             },
-            {
-                key: "endpoint",
-                config: {
-                    type: "input" as const,
-                    label: "Endpoint",
-                    value: "",
-                },
-            },
-            {
-                key: "api_key",
-                config: {
-                    type: "password" as const,
-                    label: "API Key",
-                    value: "",
-                },
-            },
+            ...(isAzureProvider
+                ? [
+                      {
+                          key: "resource_name",
+                          config: {
+                              type: "input" as const,
+                              label: "资源名称",
+                              value: "",
+                              tooltip: "对应 https://{资源名称}.openai.azure.com，留空时使用 Endpoint",
+                          },
+                      },
+                      {
+                          key: "api_key",
+                          config: {
+                              type: "password" as const,
+                              label: "API Key",
+                              value: "",
+                          },
+                      },
+                      {
+                          key: "api_version",
+                          config: {
+                              type: "input" as const,
+                              label: "API 版本",
+                              value: "v1",
+                              tooltip: "v1 使用 /openai/v1/ 接口；填写日期版本（如 2024-10-21）时使用 /openai/deployments/{部署名}/ 接口",
+                          },
+                      },
+                      {
+                          key: "deployment_names",
+                          config: {
+                              type: "textarea" as const,
+                              label: "部署名映射",
+                              value: "",
+                              tooltip:
+                                  'JSON 格式，模型代码到部署名的映射，例如 {"gpt-4o": "my-gpt4o-deployment"}；获取模型列表时返回这里配置的部署',
+                          },
+                      },
+                  ]
+                : [
+                      {
+                          key: "endpoint",
+                          config: {
+                              type: "input" as const,
+                              label: "Endpoint",
+                              value: "",
+                          },
+                      },
+                      {
+                          key: "api_key",
+                          config: {
+                              type: "password" as const,
+                              label: "API Key",
+                              value: "",
+                          },
+                      },
+                      {
+                          key: "api_dialect",
+                          config: {
+                              type: "select" as const,
+                              label: "API 协议",
+                              value: "auto",
+                              options: apiDialectOptions,
+                              tooltip: "显式指定请求/响应协议，适用于 Endpoint 与 API 类型不一致的代理服务",
+                          },
+                      },
+                  ]),
             {
                 key: "tagInput",
                 config: {
@@ -759,7 +830,7 @@ const LLMProviderConfigForm: React.FC<LLMProviderConfigFormProps> = ({
                 },
             },
         ];
-    }, [apiType, apiTypeLabel, isCopilotProvider, isAcpProvider, acpCliOptions, tagInputRender, isAdvancedConfigExpanded, form, updateField, proxyEnabled, hasApiKey, copilot.authInfo, copilot.isAuthorizing, copilot.scanConfigAuth, copilot.oauthFlowAuth, copilot.cancelAuthorization, id, tags, onTagsChange]);
+    }, [apiType, apiTypeLabel, isCopilotProvider, isAcpProvider, isAzureProvider, acpCliOptions, apiDialectOptions, tagInputRender, isAdvancedConfigExpanded, form, updateField, proxyEnabled, hasApiKey, copilot.authInfo, copilot.isAuthorizing, copilot.scanConfigAuth, copilot.oauthFlowAuth, copilot.cancelAuthorization, id, tags, onTagsChange]);
 
     // 打开改名对话框
     const handleOpenRenameDialog = useCallback(() => {
@@ -4,17 +4,24 @@ import { Button } from "../ui/button";
 import { Switch } from "../ui/switch";
 import { Badge } from "../ui/badge";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "../ui/tabs";
-import { Trash2, Edit, RefreshCw, Zap } from "lucide-react";
+import { Trash2, Edit, RefreshCw, Zap, Share2 } from "lucide-react";
 import MCP from "@/assets/mcp.svg?react";
 import { Tooltip, TooltipTrigger, TooltipContent } from "../ui/tooltip";
 import { toast } from 'sonner';
 import { listen } from "@tauri-apps/api/event";
+import { writeText } from "@tauri-apps/plugin-clipboard-manager";
 import ConfirmDialog from "../ConfirmDialog";
 import MCPServerDialog from "./MCPServerDialog";
 import MCPActionDropdown from "./MCPActionDropdown";
 import JSONImportDialog from "./JSONImportDialog";
 import MCPToolItem from "./MCPToolItem";
 import BuiltinToolDialog from "./BuiltinToolDialog";
+import {
+    DropdownMenu,
+    DropdownMenuContent,
+    DropdownMenuItem,
+    DropdownMenuTrigger,
+} from "../ui/dropdown-menu";
 
 // 导入公共组件
 import {
@@ -25,7 +32,7 @@ import {
     SelectOption
 } from "../common";
 
-import { MCPServer, MCPServerTool, MCPServerResource, MCPServerPrompt, MCPServerRequest } from "../../data/MCP";
+import { MCPServer, MCPServerTool, MCPServerResource, MCPServerPrompt, MCPServerRequest, MCPServerExport, MCPServerImportResult, MCPServerStartupStatus, MCPServerHealthEvent } from "../../data/MCP";
 import { MCPTemplate } from "../../data/MCPTemplates";
 import { useSkillsMcpValidation, DisableOperationMcpCheckResult, AGENT_MCP_COMMAND } from "../../hooks/useSkillsMcpValidation";
 import { PinyinFilter } from "../../utils/pinyinFilter";
@@ -36,6 +43,7 @@ const MCPConfig: React.FC = () => {
     const [serverTools, setServerTools] = useState<MCPServerTool[]>([]);
     const [serverResources, setServerResources] = useState<MCPServerResource[]>([]);
     const [serverPrompts, setServerPrompts] = useState<MCPServerPrompt[]>([]);
+    const [startupStatus, setStartupStatus] = useState<MCPServerStartupStatus | null>(null);
     const [searchQuery, setSearchQuery] = useState('');
     // 所有服务器的工具缓存，用于搜索穿透
     const [allServerTools, setAllServerTools] = useState<Map<number, MCPServerTool[]>>(new Map());
@@ -163,9 +171,17 @@ const MCPConfig: React.FC = () => {
         const unlistenPromise = listen('mcp_state_changed', () => {
             getMcpServers();
         });
+        // 健康检查发现服务器不可用或恢复时刷新状态
+        const unlistenHealthPromise = listen<MCPServerHealthEvent>('mcp_server_health_changed', (event) => {
+            if (event.payload.status === 'unhealthy') {
+                toast.warning(`MCP 服务器 ${event.payload.server_name} 连接失败: ${event.payload.error ?? '未知错误'}`);
+            }
+            getMcpServers();
+        });
 
         return () => {
             unlistenPromise.then(unlisten => unlisten());
+            unlistenHealthPromise.then(unlisten => unlisten());
         };
     }, []);
 
@@ -174,6 +190,9 @@ const MCPConfig: React.FC = () => {
             getServerTools(selectedServer.id);
             getServerResources(selectedServer.id);
             getServerPrompts(selectedServer.id);
+            invoke<MCPServerStartupStatus[]>('get_mcp_server_startup_statuses')
+                .then((statuses) => setStartupStatus(statuses.find(s => s.server_id === selectedServer.id) ?? null))
+                .catch((e) => console.error('获取自动启动状态失败:', e));
         }
     }, [selectedServer, getServerTools, getServerResources, getServerPrompts]);
 
@@ -358,6 +377,18 @@ const MCPConfig: React.FC = () => {
         }
     }, [selectedServer, serverTools, checkDisableAgentMcp]);
 
+    // 更新工具执行超时，null 表示沿用全局或服务器设置
+    const handleUpdateToolTimeout = useCallback(async (toolId: number, timeoutMs: number | null) => {
+        try {
+            await invoke('update_mcp_server_tool_timeout', { toolId, timeoutMs });
+            setServerTools(prev => prev.map(tool =>
+                tool.id === toolId ? { ...tool, timeout_ms: timeoutMs } : tool
+            ));
+        } catch (e) {
+            toast.error('更新工具超时失败: ' + e);
+        }
+    }, []);
+
     // 更新提示配置
     const handleUpdatePrompt = useCallback(async (promptId: number, isEnabled: boolean) => {
         try {
@@ -425,6 +456,36 @@ const MCPConfig: React.FC = () => {
         }
     }, [openAddServerDialog, getMcpServers]);
 
+    // 导入 AIPP 导出的服务器配置
+    const handleSharedImport = useCallback(async (json: string) => {
+        setJsonImportDialogOpen(false);
+        try {
+            const result = await invoke<MCPServerImportResult>('import_mcp_server', { json });
+            toast.success(`已导入 ${result.name}，请检查配置后手动启用`);
+            if (result.redacted_secrets.length > 0) {
+                toast.info(`请编辑服务器补全以下密钥: ${result.redacted_secrets.join(', ')}`);
+            }
+            getMcpServers();
+        } catch (e) {
+            toast.error('导入MCP服务器失败: ' + e);
+        }
+    }, [getMcpServers]);
+
+    // 导出服务器配置到剪贴板
+    const handleExportServer = useCallback(async (serverId: number, includeSecrets: boolean) => {
+        try {
+            const result = await invoke<MCPServerExport>('export_mcp_server', { id: serverId, includeSecrets });
+            await writeText(result.content);
+            if (result.warning) {
+                toast.warning(`配置已复制到剪贴板。${result.warning}`);
+            } else {
+                toast.success('配置已复制到剪贴板（已移除密钥）');
+            }
+        } catch (e) {
+            toast.error('导出MCP服务器失败: ' + e);
+        }
+    }, []);
+
     // 关闭JSON导入对话框
     const closeJSONImportDialog = useCallback(() => {
         setJsonImportDialogOpen(false);
@@ -506,6 +567,12 @@ const MCPConfig: React.FC = () => {
         </SidebarList>
     ), [filteredMcpServers, selectedServer?.id, handleSelectServer, handleTemplateSelect, handleJSONImport, searchQuery]);
 
+    // 健康状态由后台检查更新，从最新的服务器列表中读取
+    const selectedServerHealth = useMemo(
+        () => selectedServer ? mcpServers.find(s => s.id === selectedServer.id) ?? selectedServer : null,
+        [mcpServers, selectedServer]
+    );
+
     // 右侧内容 - 使用 useMemo 避免重复创建（必须在条件返回之前）
     const content = useMemo(() => selectedServer ? (
         <div className="space-y-6">
@@ -548,6 +615,27 @@ const MCPConfig: React.FC = () => {
                             <TooltipContent>编辑MCP</TooltipContent>
                         </Tooltip>
 
+                        <DropdownMenu>
+                            <Tooltip delayDuration={500}>
+                                <TooltipTrigger asChild>
+                                    <DropdownMenuTrigger asChild>
+                                        <Button variant="ghost" size="sm">
+                                            <Share2 className="h-4 w-4" />
+                                        </Button>
+                                    </DropdownMenuTrigger>
+                                </TooltipTrigger>
+                                <TooltipContent>导出MCP配置</TooltipContent>
+                            </Tooltip>
+                            <DropdownMenuContent align="end">
+                                <DropdownMenuItem onClick={() => handleExportServer(selectedServer.id, false)}>
+                                    导出（移除密钥）
+                                </DropdownMenuItem>
+                                <DropdownMenuItem onClick={() => handleExportServer(selectedServer.id, true)}>
+                                    导出（包含密钥）
+                                </DropdownMenuItem>
+                            </DropdownMenuContent>
+                        </DropdownMenu>
+
                         {/* 系统内置工具集不显示删除按钮（is_deletable = false） */}
                         {selectedServer.is_deletable && (
                             <Tooltip delayDuration={500}>
@@ -579,6 +667,30 @@ const MCPConfig: React.FC = () => {
                             {selectedServer.is_long_running ? "是" : "否"}
                         </Badge>
                     </div>
+                    {selectedServer.autostart && (
+                        <div>
+                            <span className="font-medium text-foreground">自动启动:</span>
+                            <Badge
+                                variant={startupStatus?.state === 'failed' ? "destructive" : startupStatus?.state === 'connected' ? "default" : "secondary"}
+                                className="ml-2"
+                                title={startupStatus?.error ?? undefined}
+                            >
+                                {startupStatus?.state === 'connected' ? "已连接" : startupStatus?.state === 'failed' ? "连接失败" : startupStatus?.state === 'connecting' ? "连接中" : "已开启"}
+                            </Badge>
+                        </div>
+                    )}
+                    {selectedServerHealth?.status && (
+                        <div>
+                            <span className="font-medium text-foreground">健康状态:</span>
+                            <Badge
+                                variant={selectedServerHealth.status === 'unhealthy' ? "destructive" : "default"}
+                                className="ml-2"
+                                title={selectedServerHealth.last_healthy_at ? `最近一次正常: ${selectedServerHealth.last_healthy_at}` : undefined}
+                            >
+                                {selectedServerHealth.status === 'unhealthy' ? "不可用" : "正常"}
+                            </Badge>
+                        </div>
+                    )}
                     {selectedServer.timeout && (
                         <div>
                             <span className="font-medium text-foreground">超时时间:</span>
@@ -634,6 +746,7 @@ const MCPConfig: React.FC = () => {
                                                 isExpanded={expandedTools.has(tool.id)}
                                                 onToggleExpansion={toggleToolExpansion}
                                                 onUpdateTool={handleUpdateTool}
+                                                onUpdateToolTimeout={handleUpdateToolTimeout}
                                                 truncateText={truncateText}
                                             />
                                         ))}
@@ -703,7 +816,7 @@ const MCPConfig: React.FC = () => {
             title="选择一个MCP服务器"
             description="从左侧列表中选择一个服务器开始配置"
         />
-    ), [selectedServer, serverTools, serverPrompts, serverResources, expandedTools, isRefreshing, handleToggleServer, handleRefreshServerCapabilities, openEditServerDialog, handleDeleteServer, toggleToolExpansion, handleUpdateTool, handleUpdatePrompt, truncateText]);
+    ), [selectedServer, selectedServerHealth, serverTools, serverPrompts, serverResources, expandedTools, isRefreshing, handleToggleServer, handleRefreshServerCapabilities, openEditServerDialog, handleExportServer, handleDeleteServer, toggleToolExpansion, handleUpdateTool, handleUpdateToolTimeout, handleUpdatePrompt, truncateText]);
 
     // 空状态
     if (mcpServers.length === 0) {
@@ -744,6 +857,7 @@ const MCPConfig: React.FC = () => {
                     isOpen={jsonImportDialogOpen}
                     onClose={closeJSONImportDialog}
                     onImport={handleJSONImportConfirm}
+                    onImportShared={handleSharedImport}
                 />
 
                 {/* 内置工具对话框 - 空状态时也需要渲染 */}
@@ -786,6 +900,7 @@ const MCPConfig: React.FC = () => {
                 isOpen={jsonImportDialogOpen}
                 onClose={closeJSONImportDialog}
                 onImport={handleJSONImportConfirm}
+                onImportShared={handleSharedImport}
             />
 
             {/* 内置工具对话框 */}
@@ -827,6 +942,7 @@ const MCPConfig: React.FC = () => {
                                 is_enabled: editingServer.is_enabled,
                                 is_builtin: editingServer.is_builtin,
                                 proxy_enabled: editingServer.proxy_enabled || false,
+                                autostart: editingServer.autostart || false,
                             };
                             await invoke('update_mcp_server', { id: editingServer.id, request: req });
 
@@ -8,7 +8,7 @@ import { Label } from '../ui/label';
 import { Dialog, DialogContent, DialogFooter, DialogHeader, DialogTitle } from '../ui/dialog';
 import { Accordion, AccordionContent, AccordionItem, AccordionTrigger } from '../ui/accordion';
 import { toast } from 'sonner';
-import { MCPServer, MCPServerRequest, MCP_TRANSPORT_TYPES } from '../../data/MCP';
+import { MCPServer, MCPServerRequest, MCP_TRANSPORT_TYPES, isRemoteTransport } from '../../data/MCP';
 import CustomSelect from '../CustomSelect';
 
 interface MCPServerDialogProps {
@@ -41,6 +41,7 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
         is_long_running: false,
         is_enabled: true,
         proxy_enabled: false,
+        autostart: false,
     });
 
     // UI state
@@ -101,6 +102,7 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
                 is_long_running: editingServer.is_long_running,
                 is_enabled: editingServer.is_enabled,
                 proxy_enabled: editingServer.proxy_enabled || false,
+                autostart: editingServer.autostart || false,
             });
             // initialize header rows from existing headers
             setHeaderRows(parseHeadersToRows(prettyHeaders));
@@ -118,6 +120,7 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
                 is_long_running: false,
                 is_enabled: true,
                 proxy_enabled: false,
+                autostart: false,
             };
 
             // 合并初始配置
@@ -137,9 +140,9 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
         }));
     }, []);
 
-    // Keep formData.headers in sync with headerRows for http
+    // Keep formData.headers in sync with headerRows for remote (http/sse) transports
     useEffect(() => {
-        if (formData.transport_type === 'http') {
+        if (isRemoteTransport(formData.transport_type)) {
             const headersStr = rowsToHeadersString(headerRows);
             if (headersStr !== (formData.headers || '')) {
                 setFormData(prev => ({ ...prev, headers: headersStr }));
@@ -148,9 +151,9 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
         // eslint-disable-next-line react-hooks/exhaustive-deps
     }, [headerRows, formData.transport_type]);
 
-    // When transport type changes away from http, clear header rows sync (but keep stored string)
+    // When transport type changes away from http/sse, clear header rows sync (but keep stored string)
     useEffect(() => {
-        if (formData.transport_type !== 'http') {
+        if (!isRemoteTransport(formData.transport_type)) {
             setHeaderRows([]);
         } else {
             // ensure rows reflect current headers string once upon switch
@@ -182,8 +185,8 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
             return;
         }
 
-        if ((formData.transport_type === 'http') && !formData.url?.trim()) {
-            toast.error('HTTP类型需要提供URL');
+        if (isRemoteTransport(formData.transport_type) && !formData.url?.trim()) {
+            toast.error(`${formData.transport_type.toUpperCase()}类型需要提供URL`);
             return;
         }
 
@@ -285,14 +288,14 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
                             </div>
                         )}
 
-                        {/* HTTP specific fields */}
-                        {(formData.transport_type === 'http') && (
+                        {/* HTTP / SSE specific fields */}
+                        {isRemoteTransport(formData.transport_type) && (
                             <div className="space-y-2">
                                 <Label htmlFor="url">URL *</Label>
                                 <Input
                                     id="url"
                                     type="url"
-                                    placeholder="例如：http://localhost:3000/mcp"
+                                    placeholder={formData.transport_type === 'sse' ? '例如：http://localhost:3000/sse' : '例如：http://localhost:3000/mcp'}
                                     value={formData.url}
                                     onChange={(e) => updateField('url', e.target.value)}
                                 />
@@ -309,6 +312,7 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
                                 value={formData.environment_variables}
                                 onChange={(e) => updateField('environment_variables', e.target.value)}
                             />
+                            <p className="text-xs text-muted-foreground">仅注入到服务器进程，日志中只记录名称；值支持 ${'{'}VAR{'}'} 引用系统环境变量，${'{'}config:功能代码.配置项{'}'} 引用功能配置</p>
                         </div>
                     </div>
 
@@ -341,7 +345,18 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
                                     />
                                 </div>
 
-                                {(formData.transport_type === 'http') && (
+                                <div className="flex items-center justify-between">
+                                    <div>
+                                        <Label>启动时自动连接</Label>
+                                        <p className="text-sm text-muted-foreground mt-1">应用启动时在后台连接并刷新能力，首次调用工具无需等待</p>
+                                    </div>
+                                    <Switch
+                                        checked={formData.autostart || false}
+                                        onCheckedChange={(checked) => updateField('autostart', checked)}
+                                    />
+                                </div>
+
+                                {isRemoteTransport(formData.transport_type) && (
                                     <div className="space-y-2">
                                         <Label>自定义请求头</Label>
                                         <div className="space-y-2">
@@ -378,7 +393,7 @@ const MCPServerDialog: React.FC<MCPServerDialogProps> = ({
                                     </div>
                                 )}
 
-                                {(formData.transport_type === 'http') && (
+                                {isRemoteTransport(formData.transport_type) && (
                                     <div className="flex items-center justify-between">
                                         <div>
                                             <Label>使用网络代理进行请求</Label>
@@ -1,6 +1,7 @@
-import React from "react";
+import React, { useEffect, useState } from "react";
 import { ChevronDown, ChevronRight } from "lucide-react";
 import { Switch } from "../ui/switch";
+import { Input } from "../ui/input";
 import {
     Tooltip,
     TooltipContent,
@@ -19,6 +20,7 @@ interface MCPToolItemProps {
         isEnabled: boolean,
         isAutoRun: boolean,
     ) => void;
+    onUpdateToolTimeout: (toolId: number, timeoutMs: number | null) => void;
     truncateText: (text: string, maxLines?: number) => string;
 }
 
@@ -27,8 +29,27 @@ const MCPToolItem: React.FC<MCPToolItemProps> = ({
     isExpanded,
     onToggleExpansion,
     onUpdateTool,
+    onUpdateToolTimeout,
     truncateText,
 }) => {
+    const [timeoutInput, setTimeoutInput] = useState(tool.timeout_ms?.toString() ?? "");
+    useEffect(() => {
+        setTimeoutInput(tool.timeout_ms?.toString() ?? "");
+    }, [tool.timeout_ms]);
+
+    // 留空表示沿用全局或服务器设置
+    const handleTimeoutBlur = () => {
+        const trimmed = timeoutInput.trim();
+        const timeoutMs = trimmed === "" ? null : parseInt(trimmed, 10);
+        if (timeoutMs !== null && (isNaN(timeoutMs) || timeoutMs <= 0)) {
+            setTimeoutInput(tool.timeout_ms?.toString() ?? "");
+            return;
+        }
+        if (timeoutMs !== (tool.timeout_ms ?? null)) {
+            onUpdateToolTimeout(tool.id, timeoutMs);
+        }
+    };
+
     const hasParameters =
         tool.parameters &&
         tool.parameters !== "{}" &&
@@ -85,6 +106,21 @@ const MCPToolItem: React.FC<MCPToolItemProps> = ({
                     )}
                 </div>
                 <div className="flex items-center gap-6 flex-shrink-0">
+                    <div className="flex items-center gap-2">
+                        <span className="text-sm text-foreground whitespace-nowrap">
+                            超时
+                        </span>
+                        <Input
+                            type="number"
+                            min={1}
+                            className="h-8 w-28"
+                            placeholder="默认"
+                            title="工具执行超时（毫秒），留空沿用全局或服务器设置"
+                            value={timeoutInput}
+                            onChange={(e) => setTimeoutInput(e.target.value)}
+                            onBlur={handleTimeoutBlur}
+                        />
+                    </div>
                     <div className="flex items-center gap-2">
                         <span className="text-sm text-foreground whitespace-nowrap">
                             启用
@@ -1,7 +1,7 @@
 import React, { useState, useCallback, useEffect, useRef } from 'react';
 import { Button } from '../ui/button';
 import { Badge } from '../ui/badge';
-import { Tag, ChevronDown, ChevronUp } from 'lucide-react';
+import { Tag, ChevronDown, ChevronUp, RefreshCw } from 'lucide-react';
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from 'sonner';
 
@@ -20,6 +20,27 @@ interface ModelSelectionResponse {
     missing_models: string[];
 }
 
+// test_llm_provider 的返回结果
+interface ProviderTestResult {
+    success: boolean;
+    latency_ms: number;
+    status: number | null;
+    category:
+        | "auth"
+        | "permission"
+        | "not_found"
+        | "rate_limit"
+        | "server"
+        | "invalid_request"
+        | "network"
+        | "unknown"
+        | null;
+    message: string;
+    error: string | null;
+    probe: "chat" | "models";
+    model_code: string | null;
+}
+
 interface ReadOnlyModelListProps {
     llmProviderId: string;
     tags: string[];
@@ -35,15 +56,17 @@ const ReadOnlyModelList: React.FC<ReadOnlyModelListProps> = ({
 }) => {
     const [isExpanded, setIsExpanded] = useState<boolean>(false);
     const [isFetchingModels, setIsFetchingModels] = useState<boolean>(false);
+    const [isTestingProvider, setIsTestingProvider] = useState<boolean>(false);
     const [shouldShowExpandButton, setShouldShowExpandButton] = useState<boolean>(false);
     const tagsContainerRef = useRef<HTMLDivElement>(null);
 
-    // 获取模型列表
-    const handleFetchModels = useCallback(async () => {
+    // 获取模型列表，默认使用后端缓存，forceRefresh 时重新请求提供商
+    const handleFetchModels = useCallback(async (forceRefresh: boolean = false) => {
         setIsFetchingModels(true);
         try {
             const modelData = await invoke<ModelSelectionResponse>("preview_model_list", { 
-                llmProviderId: parseInt(llmProviderId) 
+                llmProviderId: parseInt(llmProviderId),
+                forceRefresh,
             });
             
             if (onFetchModels) {
@@ -68,6 +91,28 @@ const ReadOnlyModelList: React.FC<ReadOnlyModelListProps> = ({
         }
     }, [llmProviderId, onFetchModels, onTagsChange]);
 
+    // 测试提供商的 API Key 与网络是否可用
+    const handleTestProvider = useCallback(async () => {
+        setIsTestingProvider(true);
+        try {
+            const result = await invoke<ProviderTestResult>("test_llm_provider", {
+                providerId: parseInt(llmProviderId),
+            });
+            if (result.success) {
+                toast.success(`连接成功，耗时 ${result.latency_ms}ms`);
+            } else {
+                const status = result.status ? `（HTTP ${result.status}）` : "";
+                toast.error(`连接失败${status}：${result.message}`, {
+                    description: result.error ?? undefined,
+                });
+            }
+        } catch (e) {
+            toast.error("测试连接失败: " + e);
+        } finally {
+            setIsTestingProvider(false);
+        }
+    }, [llmProviderId]);
+
     // 检测是否需要显示展开按钮
     useEffect(() => {
         if (tags.length > 0 && tagsContainerRef.current) {
@@ -102,12 +147,31 @@ const ReadOnlyModelList: React.FC<ReadOnlyModelListProps> = ({
                         <Button
                             variant="outline"
                             size="sm"
-                            onClick={handleFetchModels}
+                            onClick={handleTestProvider}
+                            disabled={isTestingProvider}
+                            className="h-6 px-2 text-xs hover:bg-muted hover:border-muted-foreground"
+                        >
+                            {isTestingProvider ? "测试中..." : "测试连接"}
+                        </Button>
+                        <Button
+                            variant="outline"
+                            size="sm"
+                            onClick={() => handleFetchModels(false)}
                             disabled={isFetchingModels}
                             className="h-6 px-2 text-xs hover:bg-muted hover:border-muted-foreground"
                         >
                             {isFetchingModels ? "获取中..." : "获取Model列表"}
                         </Button>
+                        <Button
+                            variant="ghost"
+                            size="sm"
+                            onClick={() => handleFetchModels(true)}
+                            disabled={isFetchingModels}
+                            title="忽略缓存，重新从提供商获取模型列表"
+                            className="h-6 px-2 text-xs text-muted-foreground hover:text-foreground hover:bg-muted"
+                        >
+                            <RefreshCw className={`h-3 w-3 ${isFetchingModels ? "animate-spin" : ""}`} />
+                        </Button>
                         {shouldShowExpandButton && (
                             <Button
                                 variant="ghost"
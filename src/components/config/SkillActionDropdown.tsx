@@ -14,6 +14,7 @@ interface SkillActionDropdownProps {
     onOpenFolder: () => void;
     onInstallOfficial?: () => void;
     onShowInstallGuide?: () => void;
+    onManageGitSources?: () => void;
     className?: string;
     variant?: 'default' | 'outline' | 'secondary' | 'ghost' | 'link' | 'destructive';
     size?: 'default' | 'sm' | 'lg' | 'icon';
@@ -26,6 +27,7 @@ const SkillActionDropdown: React.FC<SkillActionDropdownProps> = ({
     onScan,
     onOpenFolder,
     onShowInstallGuide,
+    onManageGitSources,
     className = '',
     variant = 'default',
     size = 'default',
@@ -82,6 +84,17 @@ const SkillActionDropdown: React.FC<SkillActionDropdownProps> = ({
                         </div>
                     </DropdownMenuItem>
                 )}
+                {onManageGitSources && (
+                    <DropdownMenuItem
+                        onClick={onManageGitSources}
+                        className="flex items-center gap-2 cursor-pointer"
+                    >
+                        <div className="flex flex-col">
+                            <span className="font-medium">Git 仓库</span>
+                            <span className="text-xs text-muted-foreground">订阅团队共享的Skills仓库</span>
+                        </div>
+                    </DropdownMenuItem>
+                )}
                 <DropdownMenuItem
                     onClick={onOpenFolder}
                     className="flex items-center gap-2 cursor-pointer"
@@ -0,0 +1,187 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogHeader,
+    DialogTitle,
+} from '../ui/dialog';
+import { Button } from '../ui/button';
+import { Input } from '../ui/input';
+import { Label } from '../ui/label';
+import { GitBranch, Loader2, RefreshCw, Trash2 } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { toast } from 'sonner';
+import { GitSkillSource } from '../../data/Skill';
+
+interface SkillGitSourcesDialogProps {
+    isOpen: boolean;
+    onClose: () => void;
+    /** 仓库同步或删除后回调，用于重新扫描 Skills */
+    onSourcesChanged?: () => void;
+}
+
+const SkillGitSourcesDialog: React.FC<SkillGitSourcesDialogProps> = ({
+    isOpen,
+    onClose,
+    onSourcesChanged
+}) => {
+    const [sources, setSources] = useState<GitSkillSource[]>([]);
+    const [name, setName] = useState('');
+    const [repoUrl, setRepoUrl] = useState('');
+    const [branch, setBranch] = useState('');
+    const [authToken, setAuthToken] = useState('');
+    const [isAdding, setIsAdding] = useState(false);
+    const [refreshingId, setRefreshingId] = useState<number | null>(null);
+
+    const loadSources = useCallback(async () => {
+        try {
+            setSources(await invoke<GitSkillSource[]>('list_git_skill_sources'));
+        } catch (e) {
+            toast.error('加载仓库列表失败: ' + e);
+        }
+    }, []);
+
+    useEffect(() => {
+        if (isOpen) {
+            loadSources();
+        }
+    }, [isOpen, loadSources]);
+
+    const handleAdd = useCallback(async () => {
+        if (!repoUrl.trim()) return;
+        setIsAdding(true);
+        try {
+            const source = await invoke<GitSkillSource>('add_git_skill_source', {
+                name,
+                repoUrl,
+                branch: branch.trim() || null,
+                authToken: authToken.trim() || null,
+            });
+            if (source.last_error) {
+                toast.error('仓库已添加，但克隆失败: ' + source.last_error);
+            } else {
+                toast.success(`已订阅仓库 "${source.name}"`);
+            }
+            setName('');
+            setRepoUrl('');
+            setBranch('');
+            setAuthToken('');
+            await loadSources();
+            onSourcesChanged?.();
+        } catch (e) {
+            toast.error('添加仓库失败: ' + e);
+        } finally {
+            setIsAdding(false);
+        }
+    }, [name, repoUrl, branch, authToken, loadSources, onSourcesChanged]);
+
+    const handleRefresh = useCallback(async (source: GitSkillSource) => {
+        setRefreshingId(source.id);
+        try {
+            await invoke('refresh_skill_source', { id: source.id });
+            toast.success(`仓库 "${source.name}" 已更新`);
+            onSourcesChanged?.();
+        } catch (e) {
+            // 拉取失败时保留上一次成功的内容
+            toast.error(`更新失败，继续使用上一次的内容: ${e}`);
+        } finally {
+            setRefreshingId(null);
+            await loadSources();
+        }
+    }, [loadSources, onSourcesChanged]);
+
+    const handleRemove = useCallback(async (source: GitSkillSource) => {
+        try {
+            await invoke('remove_git_skill_source', { id: source.id });
+            toast.success(`已取消订阅 "${source.name}"`);
+            await loadSources();
+            onSourcesChanged?.();
+        } catch (e) {
+            toast.error('删除仓库失败: ' + e);
+        }
+    }, [loadSources, onSourcesChanged]);
+
+    return (
+        <Dialog open={isOpen} onOpenChange={(open) => !open && onClose()}>
+            <DialogContent className="max-w-2xl">
+                <DialogHeader>
+                    <DialogTitle>Git 仓库 Skills</DialogTitle>
+                    <DialogDescription>
+                        订阅团队共享的 Skills 仓库，仓库中的 SKILL.md 会出现在 Skills 列表中。私有仓库可填写访问令牌。
+                    </DialogDescription>
+                </DialogHeader>
+
+                <div className="space-y-2">
+                    {sources.length === 0 && (
+                        <div className="text-sm text-muted-foreground">暂无订阅的仓库</div>
+                    )}
+                    {sources.map((source) => (
+                        <div key={source.id} className="flex items-start gap-3 rounded-md border p-3">
+                            <GitBranch className="h-4 w-4 mt-1 text-muted-foreground" />
+                            <div className="flex-1 min-w-0">
+                                <div className="font-medium truncate">{source.name}</div>
+                                <div className="text-xs text-muted-foreground truncate">
+                                    {source.repo_url}{source.branch ? ` (${source.branch})` : ''}
+                                </div>
+                                <div className="text-xs text-muted-foreground">
+                                    {source.last_synced_at ? `上次同步：${source.last_synced_at}` : '尚未同步'}
+                                </div>
+                                {source.last_error && (
+                                    <div className="text-xs text-destructive break-all">{source.last_error}</div>
+                                )}
+                            </div>
+                            <Button
+                                variant="ghost"
+                                size="icon"
+                                onClick={() => handleRefresh(source)}
+                                disabled={refreshingId === source.id}
+                            >
+                                <RefreshCw className={`h-4 w-4 ${refreshingId === source.id ? 'animate-spin' : ''}`} />
+                            </Button>
+                            <Button variant="ghost" size="icon" onClick={() => handleRemove(source)}>
+                                <Trash2 className="h-4 w-4" />
+                            </Button>
+                        </div>
+                    ))}
+                </div>
+
+                <div className="grid grid-cols-2 gap-3 pt-2">
+                    <div className="col-span-2 space-y-1">
+                        <Label>仓库地址</Label>
+                        <Input
+                            value={repoUrl}
+                            onChange={(e) => setRepoUrl(e.target.value)}
+                            placeholder="https://github.com/your-team/skills.git"
+                        />
+                    </div>
+                    <div className="space-y-1">
+                        <Label>名称</Label>
+                        <Input value={name} onChange={(e) => setName(e.target.value)} placeholder="团队 Skills" />
+                    </div>
+                    <div className="space-y-1">
+                        <Label>分支</Label>
+                        <Input value={branch} onChange={(e) => setBranch(e.target.value)} placeholder="默认分支" />
+                    </div>
+                    <div className="col-span-2 space-y-1">
+                        <Label>访问令牌（可选）</Label>
+                        <Input
+                            type="password"
+                            value={authToken}
+                            onChange={(e) => setAuthToken(e.target.value)}
+                            placeholder="私有仓库的 Personal Access Token"
+                        />
+                    </div>
+                    <div className="col-span-2 flex justify-end">
+                        <Button onClick={handleAdd} disabled={isAdding || !repoUrl.trim()}>
+                            {isAdding && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+                            添加并克隆
+                        </Button>
+                    </div>
+                </div>
+            </DialogContent>
+        </Dialog>
+    );
+};
+
+export default SkillGitSourcesDialog;
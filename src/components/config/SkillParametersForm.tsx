@@ -0,0 +1,150 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { toast } from 'sonner';
+import { Button } from '../ui/button';
+import { Input } from '../ui/input';
+import { Label } from '../ui/label';
+import { Switch } from '../ui/switch';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '../ui/select';
+import { ScannedSkill, SkillParameter, SkillWithConfig, parseSkillParameterError } from '../../data/Skill';
+
+type ParameterValue = string | number | boolean | null;
+
+interface SkillParametersFormProps {
+    skill: ScannedSkill;
+    assistantId: number;
+    /** 助手当前对该 Skill 的配置，未配置时为 undefined */
+    skillConfig?: SkillWithConfig;
+    onSaved?: () => void;
+}
+
+/** 把输入框里的文本按参数类型转换，无法转换时原样返回，由后端给出类型错误 */
+function toParameterValue(parameter: SkillParameter, raw: string): ParameterValue {
+    if (raw.trim() === '') return null;
+    if (parameter.type === 'number' || parameter.type === 'integer') {
+        const value = Number(raw);
+        return Number.isNaN(value) ? raw : value;
+    }
+    return raw;
+}
+
+const SkillParametersForm: React.FC<SkillParametersFormProps> = ({
+    skill,
+    assistantId,
+    skillConfig,
+    onSaved
+}) => {
+    const [values, setValues] = useState<Record<string, ParameterValue>>({});
+    const [errors, setErrors] = useState<Record<string, string>>({});
+    const [isSaving, setIsSaving] = useState(false);
+
+    useEffect(() => {
+        setValues(skillConfig?.config.parameters ?? {});
+        setErrors({});
+    }, [skill.identifier, skillConfig]);
+
+    const setValue = useCallback((name: string, value: ParameterValue) => {
+        setValues((prev) => ({ ...prev, [name]: value }));
+        setErrors((prev) => {
+            const { [name]: _removed, ...rest } = prev;
+            return rest;
+        });
+    }, []);
+
+    const handleSave = useCallback(async () => {
+        setIsSaving(true);
+        try {
+            await invoke('update_assistant_skill_config', {
+                assistantId,
+                skillIdentifier: skill.identifier,
+                isEnabled: skillConfig?.config.is_enabled ?? false,
+                priority: skillConfig?.config.priority ?? 0,
+                parameters: values,
+            });
+            setErrors({});
+            toast.success('参数已保存');
+            onSaved?.();
+        } catch (e) {
+            const validationError = parseSkillParameterError(e);
+            if (validationError) {
+                setErrors(Object.fromEntries(validationError.errors.map((error) => [error.key, error.message])));
+                toast.error('参数校验失败');
+            } else {
+                toast.error('保存参数失败: ' + e);
+            }
+        } finally {
+            setIsSaving(false);
+        }
+    }, [assistantId, skill.identifier, skillConfig, values, onSaved]);
+
+    const renderInput = (parameter: SkillParameter) => {
+        const value = values[parameter.name];
+        if (parameter.type === 'boolean') {
+            return (
+                <Switch
+                    checked={typeof value === 'boolean' ? value : parameter.default === true}
+                    onCheckedChange={(checked) => setValue(parameter.name, checked)}
+                />
+            );
+        }
+        if (parameter.options.length > 0) {
+            return (
+                <Select
+                    value={typeof value === 'string' ? value : undefined}
+                    onValueChange={(option) => setValue(parameter.name, option)}
+                >
+                    <SelectTrigger>
+                        <SelectValue placeholder={parameter.default != null ? `默认：${parameter.default}` : '请选择'} />
+                    </SelectTrigger>
+                    <SelectContent>
+                        {parameter.options.map((option) => (
+                            <SelectItem key={option} value={option}>{option}</SelectItem>
+                        ))}
+                    </SelectContent>
+                </Select>
+            );
+        }
+        return (
+            <Input
+                type={parameter.type === 'string' ? 'text' : 'number'}
+                value={value == null ? '' : String(value)}
+                placeholder={parameter.default != null ? `默认：${parameter.default}` : ''}
+                onChange={(e) => setValue(parameter.name, toParameterValue(parameter, e.target.value))}
+            />
+        );
+    };
+
+    return (
+        <div className="bg-background rounded-lg border border-border p-6">
+            <h4 className="text-md font-semibold text-foreground mb-4">参数</h4>
+            <div className="space-y-4">
+                {skill.metadata.parameters.map((parameter) => (
+                    <div key={parameter.name} className="space-y-1">
+                        <Label>
+                            {parameter.name}
+                            {parameter.required && <span className="text-destructive ml-1">*</span>}
+                            <span className="ml-2 text-xs text-muted-foreground">{parameter.type}</span>
+                        </Label>
+                        {renderInput(parameter)}
+                        {parameter.description && (
+                            <div className="text-xs text-muted-foreground">{parameter.description}</div>
+                        )}
+                        {errors[parameter.name] && (
+                            <div className="text-xs text-destructive">{errors[parameter.name]}</div>
+                        )}
+                    </div>
+                ))}
+                {Object.entries(errors)
+                    .filter(([key]) => !skill.metadata.parameters.some((parameter) => parameter.name === key))
+                    .map(([key, message]) => (
+                        <div key={key} className="text-xs text-destructive">{message}</div>
+                    ))}
+            </div>
+            <div className="flex justify-end mt-4">
+                <Button onClick={handleSave} disabled={isSaving}>保存参数</Button>
+            </div>
+        </div>
+    );
+};
+
+export default SkillParametersForm;
@@ -1,5 +1,6 @@
 import React, { useCallback, useEffect, useState, useMemo } from 'react';
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Button } from "../ui/button";
 import { Switch } from "../ui/switch";
 import { Badge } from "../ui/badge";
@@ -26,6 +27,8 @@ import {
 
 import SkillActionDropdown from "./SkillActionDropdown";
 import SkillInstallGuideDialog from "./SkillInstallGuideDialog";
+import SkillGitSourcesDialog from "./SkillGitSourcesDialog";
+import SkillParametersForm from "./SkillParametersForm";
 import { useSkillsMcpValidation } from "../../hooks/useSkillsMcpValidation";
 
 interface SkillsManagerProps {
@@ -49,6 +52,7 @@ const SkillsManager: React.FC<SkillsManagerProps> = ({ assistantId }) => {
     const [pendingSkillEnable, setPendingSkillEnable] = useState<{ skill: ScannedSkill; enabled: boolean } | null>(null);
     // 安装指南对话框
     const [installGuideOpen, setInstallGuideOpen] = useState(false);
+    const [gitSourcesOpen, setGitSourcesOpen] = useState(false);
 
     // 删除确认对话框
     const [deleteConfirmOpen, setDeleteConfirmOpen] = useState(false);
@@ -104,6 +108,19 @@ const SkillsManager: React.FC<SkillsManagerProps> = ({ assistantId }) => {
         scanSkills();
     }, []);
 
+    // SKILL.md 在磁盘上被修改后，后端会发出 skills_changed 事件，重新扫描并刷新当前内容
+    useEffect(() => {
+        const unlisten = listen('skills_changed', () => {
+            scanSkills();
+            if (selectedSkill) {
+                loadSkillContent(selectedSkill.identifier);
+            }
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, [scanSkills, selectedSkill, loadSkillContent]);
+
     useEffect(() => {
         if (assistantId) {
             loadAssistantSkills();
@@ -319,6 +336,7 @@ const SkillsManager: React.FC<SkillsManagerProps> = ({ assistantId }) => {
                     onOpenFolder={handleOpenSkillsFolder}
                     onInstallOfficial={handleInstallOfficial}
                     onShowInstallGuide={handleShowInstallGuide}
+                    onManageGitSources={() => setGitSourcesOpen(true)}
                     isScanning={isRefreshing}
                     showIcon={false}
                     variant="outline"
@@ -466,6 +484,16 @@ const SkillsManager: React.FC<SkillsManagerProps> = ({ assistantId }) => {
                 </div>
             </div>
 
+            {/* Per-assistant parameters declared in SKILL.md */}
+            {assistantId && selectedSkill.metadata.parameters?.length > 0 && (
+                <SkillParametersForm
+                    skill={selectedSkill}
+                    assistantId={assistantId}
+                    skillConfig={getSkillConfig(selectedSkill.identifier)}
+                    onSaved={loadAssistantSkills}
+                />
+            )}
+
             {/* Skill content preview */}
             <div className="bg-background rounded-lg border border-border p-6">
                 <h4 className="text-md font-semibold text-foreground mb-4">内容预览</h4>
@@ -503,7 +531,7 @@ const SkillsManager: React.FC<SkillsManagerProps> = ({ assistantId }) => {
             title="选择一个Skill"
             description="从左侧列表中选择一个Skill查看详情"
         />
-    ), [selectedSkill, skillContent, isLoading, assistantId, isSkillEnabled, handleToggleSkill, handleOpenSkillFolder]);
+    ), [selectedSkill, skillContent, isLoading, assistantId, isSkillEnabled, handleToggleSkill, handleOpenSkillFolder, getSkillConfig, loadAssistantSkills]);
 
     // Empty state when no skills found
     if (skills.length === 0 && !isRefreshing) {
@@ -565,6 +593,13 @@ const SkillsManager: React.FC<SkillsManagerProps> = ({ assistantId }) => {
                 onSkillInstalled={() => scanSkills()}
             />
 
+            {/* Git 仓库订阅对话框 */}
+            <SkillGitSourcesDialog
+                isOpen={gitSourcesOpen}
+                onClose={() => setGitSourcesOpen(false)}
+                onSourcesChanged={() => scanSkills()}
+            />
+
             {/* 删除技能确认对话框 */}
             <ConfirmDialog
                 isOpen={deleteConfirmOpen}
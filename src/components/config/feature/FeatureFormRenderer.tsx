@@ -63,6 +63,7 @@ interface FeatureFormRendererProps {
     onSaveDisplay: () => Promise<void>;
     onSaveSummary: () => Promise<void>;
     onSaveNetwork: () => Promise<void>;
+    onSaveDataBackup: () => Promise<void>;
     onSaveShortcuts: () => Promise<void>;
     onSaveExperimental: () => Promise<void>;
 }
@@ -74,6 +75,7 @@ export const FeatureFormRenderer: React.FC<FeatureFormRendererProps> = ({
     onSaveDisplay,
     onSaveSummary,
     onSaveNetwork,
+    onSaveDataBackup,
     onSaveShortcuts,
     onSaveExperimental,
 }) => {
@@ -129,6 +131,7 @@ export const FeatureFormRenderer: React.FC<FeatureFormRendererProps> = ({
             return (
                 <DataFolderConfigForm
                     form={forms.dataFolderForm}
+                    onSave={onSaveDataBackup}
                 />
             );
         case "network_config":
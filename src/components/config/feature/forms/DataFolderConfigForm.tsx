@@ -1,18 +1,133 @@
-import React, { useCallback } from "react";
+import React, { useCallback, useEffect, useState } from "react";
 import { UseFormReturn } from "react-hook-form";
 import { invoke } from "@tauri-apps/api/core";
 import ConfigForm from "@/components/ConfigForm";
+import PasswordDialog from "@/components/PasswordDialog";
 import { toast } from "sonner";
 
+interface DatabaseEncryptionStatus {
+    supported: boolean;
+    enabled: boolean;
+    active: boolean;
+    key_source: "keychain" | "passphrase" | null;
+}
+
+interface BackupInfo {
+    name: string;
+    path: string;
+    created_at: string;
+    size_bytes: number;
+    file_count: number;
+}
+
 interface DataFolderConfigFormProps {
     form: UseFormReturn<any>;
+    onSave: () => Promise<void>;
 }
 
-export const DataFolderConfigForm: React.FC<DataFolderConfigFormProps> = ({ form }) => {
+export const DataFolderConfigForm: React.FC<DataFolderConfigFormProps> = ({ form, onSave }) => {
     const handleOpenDataFolder = useCallback(() => {
         invoke("open_data_folder");
     }, []);
 
+    const [encryptionStatus, setEncryptionStatus] = useState<DatabaseEncryptionStatus | null>(null);
+
+    useEffect(() => {
+        invoke<DatabaseEncryptionStatus>("get_database_encryption_status")
+            .then(setEncryptionStatus)
+            .catch(console.warn);
+    }, []);
+
+    const [passphraseDialogOpen, setPassphraseDialogOpen] = useState(false);
+
+    /** 检查能否启用加密，不能时给出提示 */
+    const canEnableEncryption = useCallback(() => {
+        if (!encryptionStatus?.supported) {
+            toast.info("当前版本未包含 SQLCipher 支持，无法加密本地数据库");
+            return false;
+        }
+        if (encryptionStatus.enabled) {
+            toast.info(encryptionStatus.active ? "本地数据库已加密" : "重启应用后将加密现有数据库");
+            return false;
+        }
+        return true;
+    }, [encryptionStatus]);
+
+    const handleEnableEncryption = useCallback(async () => {
+        if (!canEnableEncryption()) {
+            return;
+        }
+        try {
+            const status = await invoke<DatabaseEncryptionStatus>("enable_database_encryption", {
+                keySource: "keychain",
+            });
+            setEncryptionStatus(status);
+            toast.success("已启用数据库加密，密钥保存在系统钥匙串中，重启应用后加密现有数据");
+        } catch (e) {
+            toast.error("启用数据库加密失败: " + e);
+        }
+    }, [canEnableEncryption]);
+
+    const handleOpenPassphraseDialog = useCallback(() => {
+        if (canEnableEncryption()) {
+            setPassphraseDialogOpen(true);
+        }
+    }, [canEnableEncryption]);
+
+    const handleEnablePassphraseEncryption = useCallback(async (passphrase: string) => {
+        try {
+            const status = await invoke<DatabaseEncryptionStatus>("enable_database_encryption", {
+                keySource: "passphrase",
+                passphrase,
+            });
+            setEncryptionStatus(status);
+        } catch (e) {
+            toast.error("启用数据库加密失败: " + e);
+            throw e;
+        }
+    }, []);
+
+    const [latestBackup, setLatestBackup] = useState<BackupInfo | null>(null);
+    const [isBackingUp, setIsBackingUp] = useState(false);
+
+    const refreshBackups = useCallback(() => {
+        invoke<BackupInfo[]>("list_backups")
+            .then((backups) => setLatestBackup(backups[0] ?? null))
+            .catch(console.warn);
+    }, []);
+
+    useEffect(() => {
+        refreshBackups();
+    }, [refreshBackups]);
+
+    const handleBackupNow = useCallback(async () => {
+        setIsBackingUp(true);
+        try {
+            const backup = await invoke<BackupInfo>("create_backup_now");
+            toast.success(`备份完成：${backup.name}`);
+            refreshBackups();
+        } catch (e) {
+            toast.error("备份失败: " + e);
+        } finally {
+            setIsBackingUp(false);
+        }
+    }, [refreshBackups]);
+
+    const handleSaveBackupConfig = useCallback(async () => {
+        try {
+            await onSave();
+            toast.success("自动备份配置保存成功");
+        } catch (e) {
+            toast.error("保存自动备份配置失败: " + e);
+        }
+    }, [onSave]);
+
+    const encryptionLabel = !encryptionStatus?.enabled
+        ? "启用"
+        : encryptionStatus.active
+            ? "已启用"
+            : "重启后生效";
+
     const handleSyncData = useCallback(() => {
         toast.info("暂未实现，敬请期待");
     }, []);
@@ -27,6 +142,70 @@ export const DataFolderConfigForm: React.FC<DataFolderConfigFormProps> = ({ form
                 onClick: handleOpenDataFolder,
             },
         },
+        {
+            key: "databaseEncryption",
+            config: {
+                type: "button" as const,
+                label: "本地数据库加密",
+                value: encryptionLabel,
+                onClick: handleEnableEncryption,
+            },
+        },
+        {
+            key: "databasePassphraseEncryption",
+            config: {
+                type: "button" as const,
+                label: "使用口令加密",
+                value: encryptionStatus?.key_source === "passphrase" ? encryptionLabel : "设置口令",
+                disabled: !!encryptionStatus?.enabled,
+                onClick: handleOpenPassphraseDialog,
+            },
+        },
+        {
+            key: "backupNow",
+            config: {
+                type: "button" as const,
+                label: latestBackup
+                    ? `立即备份（最近：${new Date(latestBackup.created_at).toLocaleString()}）`
+                    : "立即备份",
+                value: isBackingUp ? "备份中..." : "备份",
+                disabled: isBackingUp,
+                onClick: handleBackupNow,
+            },
+        },
+        {
+            key: "backup_enabled",
+            config: {
+                type: "switch" as const,
+                label: "自动备份",
+                tooltip: "按间隔将数据库快照到备份目录",
+            },
+        },
+        {
+            key: "backup_interval_hours",
+            config: {
+                type: "input" as const,
+                label: "备份间隔（小时）",
+                placeholder: "24",
+            },
+        },
+        {
+            key: "backup_keep_count",
+            config: {
+                type: "input" as const,
+                label: "保留份数",
+                placeholder: "7",
+                description: "超出后删除最早的备份",
+            },
+        },
+        {
+            key: "backup_directory",
+            config: {
+                type: "input" as const,
+                label: "备份目录",
+                placeholder: "默认为数据目录下的 backups 文件夹",
+            },
+        },
         {
             key: "syncData",
             config: {
@@ -39,13 +218,33 @@ export const DataFolderConfigForm: React.FC<DataFolderConfigFormProps> = ({ form
     ];
 
     return (
-        <ConfigForm
-            title="数据目录"
-            description="管理和同步数据文件夹"
-            config={DATA_FOLDER_CONFIG}
-            layout="default"
-            classNames="bottom-space"
-            useFormReturn={form}
-        />
+        <>
+            <ConfigForm
+                title="数据目录"
+                description="管理和同步数据文件夹"
+                config={DATA_FOLDER_CONFIG}
+                layout="default"
+                classNames="bottom-space"
+                useFormReturn={form}
+                onSave={handleSaveBackupConfig}
+            />
+            <PasswordDialog
+                title="本地数据库"
+                isOpen={passphraseDialogOpen}
+                onClose={() => setPassphraseDialogOpen(false)}
+                onConfirm={handleEnablePassphraseEncryption}
+                heading="设置数据库口令"
+                description="使用自己设置的口令加密本地数据库。口令会保存在系统钥匙串中，启动时也可以通过环境变量 AIPP_DB_PASSPHRASE 提供。"
+                tips={[
+                    "请牢记此口令，丢失后无法恢复数据库",
+                    "在其他设备上打开数据库文件时需要同一口令",
+                    "重启应用后加密现有数据",
+                ]}
+                confirmText="启用加密"
+                loadingText="启用中..."
+                successMessage="已启用数据库加密，重启应用后加密现有数据"
+                failureMessage="启用数据库加密失败"
+            />
+        </>
     );
 };
\ No newline at end of file
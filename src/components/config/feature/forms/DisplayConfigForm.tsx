@@ -2,10 +2,12 @@ import React, { useCallback, useEffect, useMemo, useRef, useState } from "react"
 import { UseFormReturn } from "react-hook-form";
 import { isPermissionGranted, requestPermission, sendNotification } from "@tauri-apps/plugin-notification";
 import { emit, listen } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
 import ConfigForm from "@/components/ConfigForm";
 import { toast } from "sonner";
 import { AVAILABLE_CODE_THEMES } from "@/hooks/useCodeTheme";
-import { useSyntectThemes } from "@/hooks/highlight/useSyntectThemes";
+import { SyntectThemeInfo, useSyntectThemes } from "@/hooks/highlight/useSyntectThemes";
 import { pluginRuntime } from "@/services/PluginRuntime";
 
 interface DisplayConfigFormProps {
@@ -15,7 +17,7 @@ interface DisplayConfigFormProps {
 
 export const DisplayConfigForm: React.FC<DisplayConfigFormProps> = ({ form, onSave }) => {
     const previousNotificationValue = useRef<boolean | undefined>(undefined);
-    const { themes, themeInfo } = useSyntectThemes();
+    const { themes, themeInfo, refresh: refreshSyntectThemes } = useSyntectThemes();
     const [pluginThemeOptions, setPluginThemeOptions] = useState<Array<{ value: string; label: string }>>([]);
 
     useEffect(() => {
@@ -73,6 +75,11 @@ export const DisplayConfigForm: React.FC<DisplayConfigFormProps> = ({ form, onSa
         { value: "disabled", label: "关闭" },
     ];
 
+    const editBehaviorOptions = [
+        { value: "truncate", label: "原地修改" },
+        { value: "fork", label: "自动分支到新对话" },
+    ];
+
     const syntectThemeOptions = useMemo(() => {
         if (!themes || themes.length === 0) return null;
         return [...themes]
@@ -110,6 +117,23 @@ export const DisplayConfigForm: React.FC<DisplayConfigFormProps> = ({ form, onSa
         ? syntectThemeOptionsByMode.dark
         : syntectThemeOptions ?? fallbackDarkOptions;
 
+    const handleImportCodeTheme = useCallback(async () => {
+        const selected = await open({
+            multiple: false,
+            filters: [{ name: "TextMate Theme", extensions: ["tmTheme"] }],
+        });
+        if (!selected || Array.isArray(selected)) {
+            return;
+        }
+        try {
+            const imported = await invoke<SyntectThemeInfo>("import_syntect_theme", { path: selected });
+            await refreshSyntectThemes();
+            toast.success(`已导入代码主题：${imported.name}`);
+        } catch (e) {
+            toast.error("导入代码主题失败: " + e);
+        }
+    }, [refreshSyntectThemes]);
+
     const handleSaveDisplayConfig = useCallback(async () => {
         const values = form.getValues();
         const currentNotificationValue = values.notification_on_completion;
@@ -201,6 +225,15 @@ export const DisplayConfigForm: React.FC<DisplayConfigFormProps> = ({ form, onSa
                 options: darkCodeThemeOptions,
             },
         },
+        {
+            key: "importCodeTheme",
+            config: {
+                type: "button" as const,
+                label: "导入代码主题（.tmTheme）",
+                value: "导入",
+                onClick: handleImportCodeTheme,
+            },
+        },
         {
             key: "user_message_markdown_render",
             config: {
@@ -209,6 +242,15 @@ export const DisplayConfigForm: React.FC<DisplayConfigFormProps> = ({ form, onSa
                 options: markdownRenderOptions,
             },
         },
+        {
+            key: "edit_behavior",
+            config: {
+                type: "select" as const,
+                label: "编辑消息方式",
+                options: editBehaviorOptions,
+                tooltip: "编辑消息并重新生成时的默认方式，助手可单独覆盖",
+            },
+        },
         {
             key: "notification_on_completion",
             config: {
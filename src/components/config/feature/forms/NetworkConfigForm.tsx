@@ -126,6 +126,13 @@ export const NetworkConfigForm: React.FC<NetworkConfigFormProps> = ({ form, onSa
         );
     };
 
+    const retryBackoffOptions = [
+        { value: "exponential", label: "指数递增" },
+        { value: "exponential_jitter", label: "指数递增 + 随机抖动" },
+        { value: "linear", label: "线性递增" },
+        { value: "fixed", label: "固定间隔" },
+    ];
+
     const NETWORK_FORM_CONFIG = [
         {
             key: "request_timeout",
@@ -136,6 +143,42 @@ export const NetworkConfigForm: React.FC<NetworkConfigFormProps> = ({ form, onSa
                 description: "思考模型返回较慢，不建议设置过低",
             },
         },
+        {
+            key: "stream_idle_timeout_ms",
+            config: {
+                type: "input" as const,
+                label: "流式空闲超时（毫秒）",
+                placeholder: "0",
+                description: "流式输出中超过该时间未收到新内容视为卡住并自动重试，0 表示不检测（默认）",
+            },
+        },
+        {
+            key: "model_list_cache_ttl_secs",
+            config: {
+                type: "input" as const,
+                label: "模型列表缓存时间（秒）",
+                placeholder: "600",
+                description: "获取提供商模型列表的结果在该时间内复用，0 表示不缓存",
+            },
+        },
+        {
+            key: "mcp_health_check_interval_secs",
+            config: {
+                type: "input" as const,
+                label: "MCP 健康检查间隔（秒）",
+                placeholder: "300",
+                description: "定期检查已启用的远程 MCP 服务器是否可用，最短按 1 分钟执行，0 表示关闭",
+            },
+        },
+        {
+            key: "mcp_tool_timeout_ms",
+            config: {
+                type: "input" as const,
+                label: "MCP 工具执行超时（毫秒）",
+                placeholder: "沿用服务器超时",
+                description: "所有 MCP 工具执行的默认超时，留空则沿用各服务器的请求超时；单个工具可在 MCP 设置中单独覆盖",
+            },
+        },
         {
             key: "retry_attempts",
             config: {
@@ -145,6 +188,34 @@ export const NetworkConfigForm: React.FC<NetworkConfigFormProps> = ({ form, onSa
                 description: "请求失败时的重试次数",
             },
         },
+        {
+            key: "retry_backoff",
+            config: {
+                type: "select" as const,
+                label: "重试退避策略",
+                options: retryBackoffOptions,
+                description: "两次重试之间的等待方式，默认指数递增（2s、4s、8s…）",
+            },
+        },
+        {
+            key: "retry_max_delay_ms",
+            config: {
+                type: "input" as const,
+                label: "最大重试间隔（毫秒）",
+                placeholder: "不限制",
+                description: "单次重试等待时间的上限，留空或 0 表示不限制",
+            },
+        },
+        {
+            key: "bad_request_fallback",
+            config: {
+                type: "input" as const,
+                label: "400 错误降级顺序",
+                placeholder: "remove_tools,strip_extra_params",
+                description:
+                    "重试后仍返回 400 时，按顺序逐步简化请求再试一次：remove_tools 移除工具，strip_extra_params 移除 response_format 等附加参数；留空则关闭",
+            },
+        },
         {
             key: "network_proxy",
             config: {
@@ -20,6 +20,11 @@ export const OtherConfigForm: React.FC<OtherConfigFormProps> = ({ form }) => {
     const [isTogglingAntiLeakage, setIsTogglingAntiLeakage] = useState(false);
     const [continueOnToolErrorEnabled, setContinueOnToolErrorEnabled] = useState(true);
     const [isTogglingContinueOnToolError, setIsTogglingContinueOnToolError] = useState(false);
+    const [isSavingOcr, setIsSavingOcr] = useState(false);
+    const [eagerInitEnabled, setEagerInitEnabled] = useState(false);
+    const [isTogglingEagerInit, setIsTogglingEagerInit] = useState(false);
+    const [launchBehavior, setLaunchBehavior] = useState<string>("ask");
+    const [isSavingLaunchBehavior, setIsSavingLaunchBehavior] = useState(false);
 
     // 加载防泄露模式配置
     useEffect(() => {
@@ -39,6 +44,30 @@ export const OtherConfigForm: React.FC<OtherConfigFormProps> = ({ form }) => {
         }
     }, [featureConfigLoading, getConfigValue, form]);
 
+    useEffect(() => {
+        if (!featureConfigLoading) {
+            const enabled = getConfigValue("eager_init", "enabled") === "true";
+            setEagerInitEnabled(enabled);
+            form.setValue("eager_init_enabled", enabled ? "true" : "false");
+        }
+    }, [featureConfigLoading, getConfigValue, form]);
+
+    useEffect(() => {
+        if (!featureConfigLoading) {
+            form.setValue("ocr_enabled", getConfigValue("attachment_ocr", "ocr_enabled") === "true" ? "true" : "false");
+            form.setValue("ocr_languages", getConfigValue("attachment_ocr", "ocr_languages") || "eng");
+            form.setValue("tesseract_path", getConfigValue("attachment_ocr", "tesseract_path") || "");
+        }
+    }, [featureConfigLoading, getConfigValue, form]);
+
+    useEffect(() => {
+        if (!featureConfigLoading) {
+            const behavior = getConfigValue("launch", "behavior") || "ask";
+            setLaunchBehavior(behavior);
+            form.setValue("launch_behavior", behavior);
+        }
+    }, [featureConfigLoading, getConfigValue, form]);
+
     useEffect(() => {
         const loadSystemState = async () => {
             try {
@@ -108,6 +137,68 @@ export const OtherConfigForm: React.FC<OtherConfigFormProps> = ({ form }) => {
         }
     }, [form, continueOnToolErrorEnabled, saveFeatureConfig]);
 
+    const handleEagerInitChange = useCallback(async (value: string | boolean) => {
+        const checked = value === true || value === "true";
+        setIsTogglingEagerInit(true);
+        try {
+            await saveFeatureConfig("eager_init", { enabled: checked ? "true" : "false" });
+            setEagerInitEnabled(checked);
+            form.setValue("eager_init_enabled", checked ? "true" : "false");
+            toast.success(checked ? "已开启启动预热，下次启动时生效" : "已关闭启动预热");
+        } catch (e) {
+            console.error("[EagerInit] save_feature_config failed:", e);
+            toast.error("设置失败: " + e);
+            form.setValue("eager_init_enabled", eagerInitEnabled ? "true" : "false");
+        } finally {
+            setIsTogglingEagerInit(false);
+        }
+    }, [form, eagerInitEnabled, saveFeatureConfig]);
+
+    // OCR 的三个配置项需要一起保存，保存接口会覆盖整个功能配置
+    const saveOcrConfig = useCallback(async (successMessage?: string) => {
+        const values = form.getValues();
+        const enabled = values.ocr_enabled === true || values.ocr_enabled === "true";
+        setIsSavingOcr(true);
+        try {
+            await saveFeatureConfig("attachment_ocr", {
+                ocr_enabled: enabled ? "true" : "false",
+                ocr_languages: String(values.ocr_languages ?? "").trim() || "eng",
+                tesseract_path: String(values.tesseract_path ?? "").trim(),
+            });
+            if (successMessage) {
+                toast.success(successMessage);
+            }
+        } catch (e) {
+            console.error("[AttachmentOcr] save_feature_config failed:", e);
+            toast.error("设置失败: " + e);
+        } finally {
+            setIsSavingOcr(false);
+        }
+    }, [form, saveFeatureConfig]);
+
+    const handleOcrEnabledChange = useCallback(async (value: string | boolean) => {
+        const checked = value === true || value === "true";
+        form.setValue("ocr_enabled", checked ? "true" : "false");
+        await saveOcrConfig(checked ? "已开启图片文字识别" : "已关闭图片文字识别");
+    }, [form, saveOcrConfig]);
+
+    const handleLaunchBehaviorChange = useCallback(async (value: string | boolean) => {
+        const behavior = String(value);
+        setIsSavingLaunchBehavior(true);
+        try {
+            await saveFeatureConfig("launch", { behavior });
+            setLaunchBehavior(behavior);
+            form.setValue("launch_behavior", behavior);
+            toast.success("启动方式已保存，下次启动时生效");
+        } catch (e) {
+            console.error("[Launch] save_feature_config failed:", e);
+            toast.error("设置失败: " + e);
+            form.setValue("launch_behavior", launchBehavior);
+        } finally {
+            setIsSavingLaunchBehavior(false);
+        }
+    }, [form, launchBehavior, saveFeatureConfig]);
+
     const AUTOSTART_FORM_CONFIG = [
         {
             key: "autostart_enabled",
@@ -119,6 +210,32 @@ export const OtherConfigForm: React.FC<OtherConfigFormProps> = ({ form }) => {
                 disabled: isToggling || systemAutostartEnabled === null,
             },
         },
+        {
+            key: "launch_behavior",
+            config: {
+                type: "select" as const,
+                label: "启动时打开",
+                tooltip: "应用启动时显示的窗口；仅托盘时不显示任何窗口",
+                options: [
+                    { value: "ask", label: "Ask 窗口" },
+                    { value: "chat", label: "Chat 窗口" },
+                    { value: "tray", label: "仅托盘" },
+                    { value: "restore", label: "恢复上次打开的窗口" },
+                ],
+                onChange: handleLaunchBehaviorChange,
+                disabled: isSavingLaunchBehavior || featureConfigLoading,
+            },
+        },
+        {
+            key: "eager_init_enabled",
+            config: {
+                type: "switch" as const,
+                label: "启动预热",
+                tooltip: "启动后在后台为开启了连接保活的模型提供商预先建立连接，缩短首次提问的等待；搜索浏览器的预热在搜索工具的 EAGER_WARMUP 配置中开启",
+                onChange: handleEagerInitChange,
+                disabled: isTogglingEagerInit || featureConfigLoading,
+            },
+        },
         {
             key: "anti_leakage_enabled",
             config: {
@@ -139,6 +256,36 @@ export const OtherConfigForm: React.FC<OtherConfigFormProps> = ({ form }) => {
                 disabled: isTogglingContinueOnToolError || featureConfigLoading,
             },
         },
+        {
+            key: "ocr_enabled",
+            config: {
+                type: "switch" as const,
+                label: "图片附件文字识别",
+                tooltip: "添加图片附件时使用 Tesseract 识别其中的文字，让不支持图片的模型也能读取截图内容",
+                onChange: handleOcrEnabledChange,
+                disabled: isSavingOcr || featureConfigLoading,
+            },
+        },
+        {
+            key: "ocr_languages",
+            config: {
+                type: "input" as const,
+                label: "识别语言",
+                tooltip: "Tesseract 语言代码，多个语言用 + 连接，如 eng+chi_sim",
+                onBlur: () => saveOcrConfig(),
+                disabled: isSavingOcr || featureConfigLoading,
+            },
+        },
+        {
+            key: "tesseract_path",
+            config: {
+                type: "input" as const,
+                label: "Tesseract 路径",
+                tooltip: "留空时从 PATH 中查找 tesseract",
+                onBlur: () => saveOcrConfig(),
+                disabled: isSavingOcr || featureConfigLoading,
+            },
+        },
     ];
 
     if (systemAutostartEnabled === null || featureConfigLoading) {
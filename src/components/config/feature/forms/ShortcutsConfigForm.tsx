@@ -1,5 +1,7 @@
-import React, { useState, useCallback, useMemo } from "react";
+import React, { useState, useCallback, useEffect, useMemo } from "react";
 import { UseFormReturn } from "react-hook-form";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { toast } from "sonner";
 import ConfigForm from "@/components/ConfigForm";
 import { ShortcutRecorder } from "../ShortcutRecorder";
@@ -7,6 +9,7 @@ import {
     SHORTCUT_ACTIONS,
     WINDOW_LABELS,
     APP_SHORTCUT_KEY_PREFIX,
+    type GlobalShortcutStatus,
     type ShortcutWindow,
 } from "@/data/Shortcuts";
 import { formatShortcutDisplay } from "@/hooks/useAppShortcuts";
@@ -28,6 +31,31 @@ export const ShortcutsConfigForm: React.FC<ShortcutsConfigFormProps> = ({ form,
         }
     }, [onSave]);
 
+    // 全局快捷键注册结果，注册失败时在对应行下方提示
+    const [globalStatus, setGlobalStatus] = useState<GlobalShortcutStatus[]>([]);
+
+    useEffect(() => {
+        invoke<GlobalShortcutStatus[]>("get_shortcut_status")
+            .then(setGlobalStatus)
+            .catch(console.warn);
+        const unlistenStatus = listen<GlobalShortcutStatus[]>("shortcut_status_changed", (event) => {
+            setGlobalStatus(event.payload);
+        });
+        const unlistenFailed = listen<GlobalShortcutStatus>("shortcut_registration_failed", (event) => {
+            const { shortcut, error } = event.payload;
+            toast.error(`全局快捷键 ${formatShortcutDisplay(shortcut)} 注册失败: ${error ?? "未知错误"}`);
+        });
+        return () => {
+            unlistenStatus.then((unlisten) => unlisten()).catch(console.warn);
+            unlistenFailed.then((unlisten) => unlisten()).catch(console.warn);
+        };
+    }, []);
+
+    const globalErrorOf = (action: GlobalShortcutStatus["action"]) => {
+        const status = globalStatus.find((s) => s.action === action);
+        return status && !status.registered ? status.error ?? "注册失败" : null;
+    };
+
     // 录入器状态（全局和应用共用一个录入器）
     const [recorderOpen, setRecorderOpen] = useState(false);
     const [editingKey, setEditingKey] = useState<string | null>(null); // form key being edited
@@ -44,8 +72,8 @@ export const ShortcutsConfigForm: React.FC<ShortcutsConfigFormProps> = ({ form,
     const handleShortcutRecorded = useCallback((shortcut: string) => {
         if (!editingKey) return;
         form.setValue(editingKey, shortcut, { shouldDirty: true });
-        // 全局快捷键还需要同步 modifier_key
-        if (editingKey === "shortcut") {
+        // Ask 全局快捷键还需要同步 modifier_key
+        if (editingKey === "shortcut_ask") {
             const modifier = shortcut.split("+").find((t) =>
                 ["Ctrl", "Shift", "Alt", "Super", "Command"].includes(t)
             );
@@ -116,11 +144,12 @@ export const ShortcutsConfigForm: React.FC<ShortcutsConfigFormProps> = ({ form,
     }, []);
 
     // 快捷键行组件
-    const ShortcutRow = ({ label, formKey, defaultValue, actionId }: {
+    const ShortcutRow = ({ label, formKey, defaultValue, actionId, error }: {
         label: string;
         formKey: string;
         defaultValue?: string;
         actionId?: string;
+        error?: string | null;
     }) => {
         const currentValue = form.watch(formKey) || defaultValue || "";
         const isDefault = defaultValue ? currentValue === defaultValue : false;
@@ -128,14 +157,19 @@ export const ShortcutsConfigForm: React.FC<ShortcutsConfigFormProps> = ({ form,
 
         return (
             <div className="flex items-center justify-between py-2 px-3 rounded-md hover:bg-muted/50">
-                <span className="text-sm">{label}</span>
+                <div className="flex flex-col">
+                    <span className="text-sm">{label}</span>
+                    {error && (
+                        <span className="text-xs text-destructive">注册失败：{error}</span>
+                    )}
+                </div>
                 <div className="flex items-center gap-2">
                     {conflicts && (
                         <span className="text-xs text-destructive">冲突</span>
                     )}
                     <div
                         className={`w-40 px-3 py-1 rounded border font-mono text-[13px] text-center cursor-pointer hover:bg-accent transition-colors ${
-                            conflicts ? "border-destructive" : ""
+                            conflicts || error ? "border-destructive" : ""
                         }`}
                         onClick={() => openRecorder(formKey, currentValue)}
                     >
@@ -169,11 +203,17 @@ export const ShortcutsConfigForm: React.FC<ShortcutsConfigFormProps> = ({ form,
                 <div className="space-y-1">
                     <ShortcutRow
                         label="唤起 Ask 窗口"
-                        formKey="shortcut"
+                        formKey="shortcut_ask"
+                        error={globalErrorOf("ask")}
+                    />
+                    <ShortcutRow
+                        label="打开 Chat 窗口"
+                        formKey="shortcut_chat"
+                        error={globalErrorOf("chat")}
                     />
                 </div>
                 <p className="text-xs text-muted-foreground mt-2 px-3">
-                    如果有选中的文本，会自动捕获并填充到输入框
+                    唤起 Ask 时如果有选中的文本，会自动捕获并填充到输入框；两个全局快捷键不能相同
                 </p>
             </div>
 
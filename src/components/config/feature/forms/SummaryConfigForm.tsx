@@ -8,6 +8,7 @@ import { useModels } from "@/hooks/useModels";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
 import { Form, FormItem, FormLabel, FormControl, FormMessage } from "@/components/ui/form";
 import { Textarea } from "@/components/ui/textarea";
+import { Input } from "@/components/ui/input";
 import { Button } from "@/components/ui/button";
 import { Switch } from "@/components/ui/switch";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
@@ -141,6 +142,12 @@ export const SummaryConfigForm: React.FC<SummaryConfigFormProps> = ({
         label: m === -1 ? "所有" : m.toString(),
     }));
 
+    const titleTriggerOptions = [
+        { value: "first_response", label: "第一轮回复后" },
+        { value: "after_turns", label: "指定轮数后" },
+        { value: "manual", label: "仅手动生成" },
+    ];
+
     return (
         <Form {...form}>
             <div className="space-y-4">
@@ -202,6 +209,76 @@ export const SummaryConfigForm: React.FC<SummaryConfigFormProps> = ({
                             )}
                         />
 
+                        <Controller
+                            control={form.control}
+                            name="title_trigger"
+                            render={({ field }) => (
+                                <FormItem>
+                                    <FormLabel>生成时机</FormLabel>
+                                    <FormControl>
+                                        <Select
+                                            disabled={!form.watch("title_summary_enabled")}
+                                            value={field.value}
+                                            onValueChange={field.onChange}
+                                        >
+                                            <SelectTrigger>
+                                                <SelectValue placeholder="选择生成时机" />
+                                            </SelectTrigger>
+                                            <SelectContent>
+                                                {titleTriggerOptions.map((option) => (
+                                                    <SelectItem key={option.value} value={option.value}>
+                                                        {option.label}
+                                                    </SelectItem>
+                                                ))}
+                                            </SelectContent>
+                                        </Select>
+                                    </FormControl>
+                                    <FormMessage />
+                                </FormItem>
+                            )}
+                        />
+
+                        {form.watch("title_trigger") === "after_turns" && (
+                            <Controller
+                                control={form.control}
+                                name="title_trigger_turns"
+                                render={({ field }) => (
+                                    <FormItem>
+                                        <FormLabel>生成轮数</FormLabel>
+                                        <FormControl>
+                                            <Input
+                                                type="number"
+                                                min={1}
+                                                disabled={!form.watch("title_summary_enabled")}
+                                                {...field}
+                                            />
+                                        </FormControl>
+                                        <p className="text-xs text-muted-foreground">
+                                            第 N 轮回复完成后生成标题，适合首轮以工具调用为主的对话
+                                        </p>
+                                        <FormMessage />
+                                    </FormItem>
+                                )}
+                            />
+                        )}
+
+                        <Controller
+                            control={form.control}
+                            name="title_include_tool_results"
+                            render={({ field }) => (
+                                <FormItem className="flex items-center justify-between">
+                                    <FormLabel>包含工具调用结果</FormLabel>
+                                    <FormControl>
+                                        <Switch
+                                            disabled={!form.watch("title_summary_enabled")}
+                                            checked={field.value === true || field.value === "true"}
+                                            onCheckedChange={field.onChange}
+                                        />
+                                    </FormControl>
+                                </FormItem>
+                            )}
+                        />
+
                         <Controller
                             control={form.control}
                             name="title_summary_length"
@@ -303,6 +380,72 @@ export const SummaryConfigForm: React.FC<SummaryConfigFormProps> = ({
                             </FormItem>
                         )}
                     />
+
+                    <Controller
+                        control={form.control}
+                        name="summary_min_messages"
+                        render={({ field }) => (
+                            <FormItem>
+                                <FormLabel>最少消息数</FormLabel>
+                                <FormControl>
+                                    <Input
+                                        type="number"
+                                        min={0}
+                                        disabled={!form.watch("conversation_summary_enabled")}
+                                        {...field}
+                                    />
+                                </FormControl>
+                                <p className="text-xs text-muted-foreground">
+                                    消息数达到该值才自动总结，0 表示不限制
+                                </p>
+                                <FormMessage />
+                            </FormItem>
+                        )}
+                    />
+
+                    <Controller
+                        control={form.control}
+                        name="summary_min_tokens"
+                        render={({ field }) => (
+                            <FormItem>
+                                <FormLabel>最少 Token 数</FormLabel>
+                                <FormControl>
+                                    <Input
+                                        type="number"
+                                        min={0}
+                                        disabled={!form.watch("conversation_summary_enabled")}
+                                        {...field}
+                                    />
+                                </FormControl>
+                                <p className="text-xs text-muted-foreground">
+                                    对话累计 Token 达到该值才自动总结，0 表示不限制
+                                </p>
+                                <FormMessage />
+                            </FormItem>
+                        )}
+                    />
+
+                    <Controller
+                        control={form.control}
+                        name="summary_idle_minutes"
+                        render={({ field }) => (
+                            <FormItem>
+                                <FormLabel>空闲时间（分钟）</FormLabel>
+                                <FormControl>
+                                    <Input
+                                        type="number"
+                                        min={0}
+                                        disabled={!form.watch("conversation_summary_enabled")}
+                                        {...field}
+                                    />
+                                </FormControl>
+                                <p className="text-xs text-muted-foreground">
+                                    最后一条消息超过该时间后才自动总结，0 表示不要求空闲
+                                </p>
+                                <FormMessage />
+                            </FormItem>
+                        )}
+                    />
                 </ConfigSection>
 
                 {/* 记忆总结 */}
@@ -1,7 +1,7 @@
 import React, { memo } from "react";
 import MessageList from "./MessageList";
 import NewChatComponent from "../NewChatComponent";
-import { Message, StreamEvent } from "../../data/Conversation";
+import { Message, StreamEvent, RegenerateMode } from "../../data/Conversation";
 import { AssistantListItem } from "../../data/Assistant";
 import type { InlineInteractionItem } from "../ConversationUI";
 
@@ -19,7 +19,7 @@ export interface ConversationContentProps {
     getGenerationGroupControl: (message: Message) => any;
     handleGenerationVersionChange: (groupId: string, versionIndex: number) => void;
     onCodeRun: (lang: string, inputStr: string) => void;
-    onMessageRegenerate: (messageId: number) => void;
+    onMessageRegenerate: (messageId: number, mode?: RegenerateMode) => void;
     onMessageEdit: (message: Message) => void;
     onMessageFork: (messageId: number) => void;
     onToggleReasoningExpand: (messageId: number) => void;
@@ -30,6 +30,7 @@ export interface ConversationContentProps {
     selectedAssistant: number;
     assistants: AssistantListItem[];
     setSelectedAssistant: (assistantId: number) => void;
+    onStarterSelect?: (starter: string) => void;
 }
 
 const ConversationContent: React.FC<ConversationContentProps> = memo(({
@@ -57,6 +58,7 @@ const ConversationContent: React.FC<ConversationContentProps> = memo(({
     selectedAssistant,
     assistants,
     setSelectedAssistant,
+    onStarterSelect,
 }) => {
     if (conversationId) {
         return (
@@ -92,6 +94,7 @@ const ConversationContent: React.FC<ConversationContentProps> = memo(({
             selectedAssistant={selectedAssistant}
             assistants={assistants}
             setSelectedAssistant={setSelectedAssistant}
+            onStarterSelect={onStarterSelect}
         />
     );
 });
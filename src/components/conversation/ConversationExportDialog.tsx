@@ -14,7 +14,7 @@ import IconButton from "../IconButton";
 import { conversationExportService } from "@/services/conversationExportService";
 import type { ConversationExportOptions } from "@/utils/exportFormatters";
 import type { ExportData } from "@/utils/exportFormatters";
-import { Loader2, FileText, FileImage, File, FileType, Download } from "lucide-react";
+import { Loader2, FileText, FileImage, File, FileType, FileCode, FileJson, Download } from "lucide-react";
 
 interface ConversationExportDialogProps {
     conversationId: string;
@@ -76,7 +76,7 @@ const ConversationExportDialog: React.FC<ConversationExportDialogProps> = ({
 
     // 导出处理函数
     const handleExport = useCallback(
-        async (format: "markdown" | "pdf" | "png" | "word") => {
+        async (format: "markdown" | "pdf" | "png" | "word" | "html" | "json") => {
             if (!exportData) return;
 
             setExporting(format);
@@ -113,6 +113,18 @@ const ConversationExportDialog: React.FC<ConversationExportDialogProps> = ({
                             filename,
                         );
                         break;
+                    case "html":
+                        exportSucceeded = await conversationExportService.exportToHTML(
+                            conversationId,
+                            filename,
+                        );
+                        break;
+                    case "json":
+                        exportSucceeded = await conversationExportService.exportToJSON(
+                            conversationId,
+                            filename,
+                        );
+                        break;
                 }
 
                 if (exportSucceeded) {
@@ -124,7 +136,7 @@ const ConversationExportDialog: React.FC<ConversationExportDialogProps> = ({
                 setExporting(null);
             }
         },
-        [exportData, options, getDefaultFilename],
+        [exportData, options, getDefaultFilename, conversationId],
     );
 
     const isExporting = exporting !== null;
@@ -234,7 +246,7 @@ const ConversationExportDialog: React.FC<ConversationExportDialogProps> = ({
                     {!loading && (
                         <div className="space-y-3 pt-2">
                             <h4 className="text-sm font-medium">导出格式</h4>
-                            <div className="grid grid-cols-4 gap-2">
+                            <div className="grid grid-cols-3 gap-2">
                                 <Button
                                     variant="outline"
                                     onClick={() => handleExport("markdown")}
@@ -290,6 +302,36 @@ const ConversationExportDialog: React.FC<ConversationExportDialogProps> = ({
                                     )}
                                     <span className="text-xs">图片</span>
                                 </Button>
+
+                                <Button
+                                    variant="outline"
+                                    onClick={() => handleExport("html")}
+                                    disabled={isExporting}
+                                    title="完整对话的只读网页，可离线打开分享"
+                                    className="flex flex-col items-center gap-1 h-auto py-3"
+                                >
+                                    {exporting === "html" ? (
+                                        <Loader2 className="h-4 w-4 animate-spin" />
+                                    ) : (
+                                        <FileCode className="h-4 w-4" />
+                                    )}
+                                    <span className="text-xs">HTML</span>
+                                </Button>
+
+                                <Button
+                                    variant="outline"
+                                    onClick={() => handleExport("json")}
+                                    disabled={isExporting}
+                                    title="完整对话数据，可在其他设备上导入"
+                                    className="flex flex-col items-center gap-1 h-auto py-3"
+                                >
+                                    {exporting === "json" ? (
+                                        <Loader2 className="h-4 w-4 animate-spin" />
+                                    ) : (
+                                        <FileJson className="h-4 w-4" />
+                                    )}
+                                    <span className="text-xs">JSON</span>
+                                </Button>
                             </div>
                         </div>
                     )}
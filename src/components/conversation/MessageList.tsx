@@ -1,7 +1,7 @@
 import React, { useMemo } from "react";
 import MessageItem from "../MessageItem";
 import VersionPagination from "../VersionPagination";
-import { Message, StreamEvent } from "../../data/Conversation";
+import { Message, StreamEvent, RegenerateMode } from "../../data/Conversation";
 import type { InlineInteractionItem } from "../ConversationUI";
 
 export interface MessageListProps {
@@ -16,7 +16,7 @@ export interface MessageListProps {
     getGenerationGroupControl: (message: Message) => any;
     handleGenerationVersionChange: (groupId: string, versionIndex: number) => void;
     onCodeRun: (lang: string, inputStr: string) => void;
-    onMessageRegenerate: (messageId: number) => void;
+    onMessageRegenerate: (messageId: number, mode?: RegenerateMode) => void;
     onMessageEdit: (message: Message) => void;
     onMessageFork: (messageId: number) => void;
     onToggleReasoningExpand: (messageId: number) => void;
@@ -98,7 +98,7 @@ const MessageList: React.FC<MessageListProps> = ({
                         message={message}
                         streamEvent={streamEvent}
                         onCodeRun={onCodeRun}
-                        onMessageRegenerate={() => onMessageRegenerate(message.id)}
+                        onMessageRegenerate={(mode) => onMessageRegenerate(message.id, mode)}
                         onMessageEdit={() => onMessageEdit(message)}
                         onMessageFork={() => onMessageFork(message.id)}
                         // Reasoning 展开状态相关 props
@@ -203,6 +203,18 @@ const ErrorMessage: React.FC<ErrorMessageProps> = ({ content, messageId }) => {
                                         <span className="font-medium">{meta.attempts}</span>
                                     </div>
                                 )}
+                                {typeof meta.partial_chars === "number" && (
+                                    <div className="col-span-2">
+                                        <span className="text-red-600/80">部分回复：</span>
+                                        <span className="font-medium">已保留中断前的 {meta.partial_chars} 个字符，可重新生成完整回复</span>
+                                    </div>
+                                )}
+                                {Array.isArray(meta.tried_models) && meta.tried_models.length > 1 && (
+                                    <div className="col-span-2 truncate">
+                                        <span className="text-red-600/80">已尝试模型：</span>
+                                        <span className="font-medium">{meta.tried_models.join(" → ")}</span>
+                                    </div>
+                                )}
                                 {meta.request_id && (
                                     <div className="col-span-2 truncate">
                                         <span className="text-red-600/80">请求ID：</span>
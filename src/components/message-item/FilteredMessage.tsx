@@ -0,0 +1,31 @@
+import React from "react";
+import { ShieldAlert } from "lucide-react";
+
+interface FilteredMessageProps {
+    content: string;
+    messageId?: number;
+}
+
+/**
+ * 提供商内容审核拦截提示，区别于请求错误卡片
+ */
+const FilteredMessage: React.FC<FilteredMessageProps> = ({ content, messageId }) => {
+    return (
+        <div
+            data-message-item
+            data-message-id={messageId}
+            data-message-type="filtered"
+            className="group relative py-4 px-5 rounded-2xl inline-block max-w-[65%] transition-all duration-200 self-start bg-amber-50 text-amber-900 border border-amber-200"
+        >
+            <div className="flex items-start space-x-3">
+                <ShieldAlert className="flex-shrink-0 w-5 h-5 mt-0.5 text-amber-500" />
+                <div className="flex-1">
+                    <div className="text-sm font-medium mb-1">内容已被审核拦截</div>
+                    <div className="text-sm text-amber-800 whitespace-pre-wrap">{content}</div>
+                </div>
+            </div>
+        </div>
+    );
+};
+
+export default FilteredMessage;
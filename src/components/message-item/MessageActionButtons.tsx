@@ -1,5 +1,6 @@
 import React, { useState } from "react";
-import { Edit2, GitBranch, Copy, Check, RefreshCw } from "lucide-react";
+import { Edit2, GitBranch, Copy, Check, RefreshCw, History } from "lucide-react";
+import { RegenerateMode } from "../../data/Conversation";
 import IconButton from "../IconButton";
 import { MessageTokenTooltip } from "../token-statistics";
 import MessageExportDialog from "./MessageExportDialog";
@@ -10,7 +11,9 @@ interface MessageActionButtonsProps {
     copyIconState: "copy" | "ok";
     onCopy: () => void;
     onEdit?: () => void;
-    onRegenerate?: () => void;
+    onRegenerate?: (mode?: RegenerateMode) => void;
+    // 回复发起过工具调用时，可复用已存储的工具结果重新生成
+    hasToolCalls?: boolean;
     onFork?: () => void;
     tokenCount: number;
     inputTokenCount: number;
@@ -27,6 +30,7 @@ const MessageActionButtons: React.FC<MessageActionButtonsProps> = ({
     onCopy,
     onEdit,
     onRegenerate,
+    hasToolCalls = false,
     onFork,
     tokenCount,
     inputTokenCount,
@@ -47,7 +51,13 @@ const MessageActionButtons: React.FC<MessageActionButtonsProps> = ({
                 <IconButton icon={<Edit2 size={16} className="text-icon" />} onClick={onEdit} />
             )}
             {showEditRegenerate && onRegenerate && (
-                <IconButton icon={<RefreshCw size={16} className="text-icon" />} onClick={onRegenerate} />
+                <IconButton icon={<RefreshCw size={16} className="text-icon" />} onClick={() => onRegenerate()} />
+            )}
+            {messageType === "response" && hasToolCalls && onRegenerate && (
+                <IconButton
+                    icon={<History size={16} className="text-icon" />}
+                    onClick={() => onRegenerate("reuse_tool_results")}
+                />
             )}
             {messageType === "response" && onFork && (
                 <IconButton icon={<GitBranch size={16} className="text-icon" />} onClick={onFork} />
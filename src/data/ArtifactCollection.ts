@@ -35,6 +35,26 @@ export interface UpdateArtifactRequest {
     tags?: string;
     db_id?: string;
     assistant_id?: number;
+    /** 代码有变化时会追加一个新版本 */
+    code?: string;
+}
+
+export interface ArtifactVersion {
+    id: number;
+    artifact_id: number;
+    version: number;
+    code: string;
+    created_time: string;
+}
+
+export interface ArtifactVersionDiffLine {
+    kind: 'equal' | 'added' | 'removed';
+    content: string;
+}
+
+export interface ArtifactVersionDetail extends ArtifactVersion {
+    /** 相对上一版本的逐行差异 */
+    diff: ArtifactVersionDiffLine[];
 }
 
 export interface FilteredArtifact extends ArtifactCollectionItem {
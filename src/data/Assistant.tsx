@@ -46,6 +46,17 @@ export interface AssistantPromptParam {
     param_value: string | null;
 }
 
+// lint_assistant_prompt 返回的提示词检查结果，位置为字符偏移，行列从 1 开始
+export interface PromptLintIssue {
+    code: string;
+    severity: "warning" | "info";
+    message: string;
+    start: number;
+    end: number;
+    line: number;
+    column: number;
+}
+
 export interface AssistantMCPConfig {
     id: number;
     assistant_id: number;
@@ -59,6 +70,7 @@ export interface AssistantMCPToolConfig {
     mcp_tool_id: number;
     is_enabled: boolean;
     is_auto_run: boolean;
+    default_arguments?: string | null; // 工具默认参数（JSON 对象），执行前与模型参数合并
 }
 
 export interface AssistantDetail {
@@ -69,4 +81,9 @@ export interface AssistantDetail {
     prompt_params: AssistantPromptParam[];
     mcp_configs: AssistantMCPConfig[];
     mcp_tool_configs: AssistantMCPToolConfig[];
-}
\ No newline at end of file
+    starters?: string[]; // 对话开场建议（原始模板，未解析变量）
+    render_mode?: MessageRenderMode; // 回复渲染模式，由 response_render_mode 配置派生
+    base_assistant_id?: number | null; // 继承的基础助手，通过 set_assistant_base 设置
+}
+
+export type MessageRenderMode = 'markdown' | 'plaintext' | 'code';
\ No newline at end of file
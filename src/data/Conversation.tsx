@@ -4,12 +4,21 @@ export interface Conversation {
     assistant_id: number | null;
     assistant_name: string;
     created_time: Date;
+    tags: string[];
+}
+
+export interface ConversationTag {
+    id: number;
+    name: string;
+    conversation_count: number;
 }
 
 // 新增：用于 get_conversation_with_messages API 的响应结构
 export interface ConversationWithMessages {
     conversation: Conversation;
     messages: Array<Message>;
+    // 传入 limit 分页加载时，是否还有更早的消息
+    has_more: boolean;
 }
 
 export interface Message {
@@ -30,16 +39,38 @@ export interface Message {
     regenerate: Array<Message> | null;
     attachment_list?: Array<any>; // 添加附件列表字段
     tool_calls_json?: string | null; // 添加工具调用 JSON 字段
+    is_pinned?: boolean; // 是否置顶（置顶消息在上下文截断时始终保留）
+    render_mode?: 'plaintext' | 'code' | null; // 渲染模式，为空时按 markdown 渲染
+    is_partial?: boolean; // 是否为生成中途中断的部分回复
     // 性能指标
     first_token_time?: Date | null;
     ttft_ms?: number | null;
     tps?: number | null;
 }
 
+// 重新生成模式：rerun 从头生成（可再次调用工具），reuse_tool_results 复用已存储的工具结果
+export type RegenerateMode = "rerun" | "reuse_tool_results";
+
+// 对话当前生成的状态（get_active_generation_status）
+export interface ActiveGenerationStatus {
+    conversation_id: number;
+    model_id: number;
+    model_code: string;
+    model_name: string;
+    provider_name: string | null;
+    stream: boolean;
+    attempt: number;
+    max_attempts: number;
+    is_fallback: boolean;
+    output_chars: number;
+    output_tokens: number | null;
+    elapsed_ms: number;
+}
+
 // 流式事件数据类型
 export interface StreamEvent {
     message_id: number;
-    message_type: 'reasoning' | 'response' | 'error';
+    message_type: 'reasoning' | 'response' | 'error' | 'filtered';
     content: string;
     is_done: boolean;
     duration_ms?: number; // 后端提供的持续时间
@@ -107,6 +138,41 @@ export interface MCPToolCallUpdateEvent {
     finished_time?: Date;
 }
 
+/**
+ * 生成进度事件（generation_started / reasoning_started / response_started）
+ */
+export interface GenerationProgressEvent {
+    conversation_id: number;
+    generation_group_id: string;
+    occurred_at: string;
+}
+
+/**
+ * 主模型失败后切换到备用模型的事件（model_fallback）
+ */
+export interface ModelFallbackEvent {
+    conversation_id: number;
+    generation_group_id: string;
+    from_model_name: string;
+    model_id: number;
+    model_name: string;
+}
+
+/**
+ * 当前生成所处阶段：已连接等待首个 chunk / 思考中 / 输出回复中
+ */
+export type GenerationStage = "started" | "reasoning" | "response";
+
+/**
+ * 提供商内容审核拦截事件（content_filtered）
+ */
+export interface ContentFilteredEvent {
+    conversation_id: number;
+    message_id: number;
+    reason: string;
+    category?: string | null;
+}
+
 export interface ConversationCancelEvent {
     conversation_id: number;
     cancelled_at: Date;
@@ -233,6 +299,70 @@ export interface MessageTokenStats {
     // 性能指标
     ttft_ms?: number;
     tps?: number;
+    // 按配置的模型单价估算的费用，未配置单价时为 null
+    estimated_cost: number | null;
+}
+
+// ============ 使用情况汇总相关类型 ============
+
+export interface AssistantUsage {
+    assistant_id: number | null;
+    assistant_name: string | null; // 助手已删除时为空
+    conversation_count: number;
+    message_count: number;
+}
+
+export interface ModelUsage {
+    model_name: string;
+    response_count: number;
+    total_tokens: number;
+}
+
+export interface HourlyUsage {
+    hour: number; // 本地时间 0-23
+    message_count: number;
+}
+
+export interface UsageInsights {
+    since: string | null; // 统计窗口起点，null 表示全部历史
+    conversation_count: number;
+    message_count: number;
+    avg_messages_per_conversation: number;
+    top_assistants: AssistantUsage[];
+    top_models: ModelUsage[];
+    hourly_activity: HourlyUsage[];
+    busiest_hour: number | null;
+}
+
+// ============ 用量报表导出相关类型 ============
+
+export type UsageReportGroupBy = "day" | "week" | "month";
+
+export type UsageReportFormat = "csv" | "json";
+
+export interface UsageReportRange {
+    start: string | null; // ISO 时间，null 表示不限
+    end: string | null; // 不包含结束时间
+}
+
+// 模型单价（每 1K token），按模型编码匹配，与模型单价设置一致
+export interface LLMModelPrice {
+    model_code: string;
+    input_price_per_1k: number;
+    output_price_per_1k: number;
+}
+
+// 助手开启发送前确认时 ask_ai 返回的确认信息，需调用 confirm_and_send 继续发送
+export interface SendConfirmation {
+    token: string;
+    assistant_id: number;
+    assistant_name: string;
+    model_name: string;
+    estimated_input_tokens: number;
+    max_output_tokens: number | null;
+    estimated_cost: number | null;
+    tools: string[]; // "server/tool"
+    expires_in_secs: number;
 }
 
 // ============ 对话导出相关类型 ============
@@ -267,6 +397,7 @@ export interface MCPToolCall {
     created_time: string;
     started_time?: string;
     finished_time?: string;
+    timeout_ms?: number | null; // 本次执行生效的超时（毫秒）
 }
 
 export interface ConversationSearchHit {
@@ -2,7 +2,7 @@ export interface MCPServer {
     id: number;
     name: string;
     description: string | null;
-    transport_type: string; // 'stdio' | 'http' | 'builtin'
+    transport_type: string; // 'stdio' | 'sse' | 'http' | 'builtin'
     command: string | null;
     environment_variables: string | null;
     headers?: string | null; // JSON string of custom headers
@@ -14,6 +14,9 @@ export interface MCPServer {
     is_deletable: boolean; // 标识是否可删除（系统初始化的内置工具集不可删除）
     proxy_enabled?: boolean; // 是否使用全局网络代理
     created_time: string;
+    autostart?: boolean; // 应用启动时是否在后台自动连接
+    status?: 'healthy' | 'unhealthy' | null; // 最近一次健康检查结果，未检查时为空
+    last_healthy_at?: string | null; // 最近一次健康检查成功的时间
 }
 
 export interface MCPServerTool {
@@ -24,6 +27,7 @@ export interface MCPServerTool {
     is_enabled: boolean;
     is_auto_run: boolean;
     parameters: string | null; // JSON string of tool parameters
+    timeout_ms?: number | null; // 单独配置的执行超时（毫秒），为空时沿用全局或服务器设置
 }
 
 export interface MCPServerResource {
@@ -57,6 +61,36 @@ export interface MCPServerRequest {
     is_enabled: boolean;
     is_builtin?: boolean; // 可选字段，用于创建内置服务器
     proxy_enabled: boolean; // 是否使用全局网络代理
+    autostart?: boolean; // 应用启动时是否在后台自动连接，未传时保持原值
+}
+
+// 自动启动的服务器在本次运行中的连接状态
+export interface MCPServerStartupStatus {
+    server_id: number;
+    server_name: string;
+    state: 'connecting' | 'connected' | 'failed';
+    error: string | null;
+    updated_time: string;
+}
+
+export interface MCPServerHealthEvent {
+    server_id: number;
+    server_name: string;
+    status: 'healthy' | 'unhealthy';
+    error: string | null;
+    last_healthy_at: string | null;
+}
+
+export interface MCPServerExport {
+    content: string; // 可移植的 JSON 配置
+    secrets_included: boolean;
+    warning?: string;
+}
+
+export interface MCPServerImportResult {
+    server_id: number;
+    name: string;
+    redacted_secrets: string[]; // 导出时被移除的密钥，需要补全
 }
 
 export interface MCPToolConfig {
@@ -76,14 +110,19 @@ export interface MCPTransportConfig {
     };
 }
 
-export type MCPTransportType = 'stdio' | 'http';
+export type MCPTransportType = 'stdio' | 'sse' | 'http';
 
 export const MCP_TRANSPORT_TYPES: { value: MCPTransportType; label: string }[] = [
     { value: 'stdio', label: 'Stdio' },
     { value: 'http', label: 'HTTP' },
+    { value: 'sse', label: 'SSE' },
     // { value: 'builtin', label: '内置工具' }, // Removed builtin transport option
 ];
 
+// 通过 URL 连接的远程传输（需要 URL、请求头与代理设置）
+export const isRemoteTransport = (transportType: string): boolean =>
+    transportType === 'http' || transportType === 'sse';
+
 export interface MCPConnectionStatus {
     server_id: number;
     is_connected: boolean;
@@ -12,6 +12,7 @@ export interface MCPToolCall {
     created_time: string;
     started_time?: string;
     finished_time?: string;
+    timeout_ms?: number | null; // 本次执行生效的超时（毫秒）
 }
 
 export interface CreateMCPToolCallRequest {
@@ -130,3 +130,13 @@ export const WINDOW_LABELS: Record<ShortcutWindow, string> = {
     ask: "Ask 窗口",
     chat: "Chat 窗口",
 };
+
+/**
+ * 全局快捷键注册结果（对应后端 get_shortcut_status / shortcut_status_changed）
+ */
+export interface GlobalShortcutStatus {
+    action: "ask" | "chat";
+    shortcut: string;
+    registered: boolean;
+    error: string | null;
+}
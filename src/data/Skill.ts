@@ -19,6 +19,19 @@ export interface SkillSourceConfig {
   is_builtin: boolean;
 }
 
+/** A git repository subscribed as a skill source (token is never returned) */
+export interface GitSkillSource {
+  id: number;
+  name: string;
+  repo_url: string;
+  branch: string | null;
+  has_auth_token: boolean;
+  last_synced_at: string | null;
+  /** Error from the last failed clone/pull; the previous checkout is kept */
+  last_error: string | null;
+  created_time: string;
+}
+
 /** Metadata extracted from SKILL.md frontmatter */
 export interface SkillMetadata {
   name: string | null;
@@ -27,6 +40,47 @@ export interface SkillMetadata {
   author: string | null;
   tags: string[];
   requires_files: string[];
+  /** Configurable parameters declared in frontmatter */
+  parameters: SkillParameter[];
+}
+
+export type SkillParameterType = 'string' | 'number' | 'integer' | 'boolean';
+
+/** A parameter declared by a skill, configurable per assistant */
+export interface SkillParameter {
+  name: string;
+  type: SkillParameterType;
+  description: string | null;
+  required: boolean;
+  default: string | number | boolean | null;
+  /** Allowed values for string parameters, empty means any value */
+  options: string[];
+}
+
+/** Single parameter validation failure */
+export interface SkillParameterError {
+  key: string;
+  kind: 'unknown_key' | 'type_mismatch' | 'invalid_option' | 'missing_required';
+  expected: SkillParameterType | null;
+  message: string;
+}
+
+/** Structured error returned by update_assistant_skill_config when parameters are invalid */
+export interface SkillParameterValidationError {
+  code: 'SKILL_PARAMETERS_INVALID';
+  skill_identifier: string;
+  errors: SkillParameterError[];
+}
+
+/** Parse a command error into a parameter validation error, if it is one */
+export function parseSkillParameterError(error: unknown): SkillParameterValidationError | null {
+  if (typeof error !== 'string' || !error.startsWith('{')) return null;
+  try {
+    const parsed = JSON.parse(error);
+    return parsed?.code === 'SKILL_PARAMETERS_INVALID' ? parsed : null;
+  } catch {
+    return null;
+  }
 }
 
 /** A scanned skill with metadata */
@@ -70,6 +124,8 @@ export interface AssistantSkillConfig {
   is_enabled: boolean;
   priority: number;
   created_time: string;
+  /** Parameter values, validated against the skill's declared parameters */
+  parameters: Record<string, string | number | boolean | null>;
 }
 
 /** Skill with config and existence status */
@@ -5,6 +5,8 @@ import { AssistantFormConfig } from "@/types/forms";
 import { validateConfig } from "@/utils/validate";
 import AssistantMCPFieldDisplay from "@/components/config/AssistantMCPFieldDisplay";
 import AssistantSkillsFieldDisplay from "@/components/config/AssistantSkillsFieldDisplay";
+import AssistantStartersField from "@/components/config/AssistantStartersField";
+import AssistantBaseField from "@/components/config/AssistantBaseField";
 import { useFeatureConfig } from "@/hooks/feature/useFeatureConfig";
 
 interface UseAssistantFormConfigProps {
@@ -177,6 +179,9 @@ export const useAssistantFormConfig = ({
                         !assistantTypeHideField.includes(config.name) &&
                         !assistantTypeCustomField.find((field) => field.key === config.name) &&
                         config.name !== "reasoning_effort" &&
+                        config.name !== "edit_behavior" &&
+                        config.name !== "context_trim_strategy" &&
+                        config.name !== "context_max_tokens" &&
                         config.name !== "dynamic_mcp_loading_enabled"
                 )
                 .map((config) => ({
@@ -249,6 +254,62 @@ export const useAssistantFormConfig = ({
             });
         }
 
+        // edit_behavior 内置字段：编辑历史消息后是原地重新生成还是自动分支到新对话
+        if (!assistantTypeHideField.includes("edit_behavior")) {
+            const editBehaviorConfig = currentAssistant?.model_configs.find((c) => c.name === "edit_behavior");
+            baseConfigs.push({
+                key: "edit_behavior",
+                config: {
+                    type: "select" as const,
+                    label: "编辑消息方式",
+                    value: editBehaviorConfig?.value ?? "default",
+                    options: [
+                        { value: "default", label: "跟随全局设置" },
+                        { value: "truncate", label: "原地修改" },
+                        { value: "fork", label: "自动分支到新对话" },
+                    ],
+                    tooltip: "编辑消息并重新生成时，自动分支会把编辑点之前的消息复制到新对话中重新生成，原对话保持不变",
+                    onChange: (value: string | boolean) =>
+                        handleConfigChange("edit_behavior", value, "string"),
+                },
+            });
+        }
+
+        // 上下文裁剪：估算 token 超过上限时按策略裁剪较早的消息，上限留空表示不裁剪
+        if (!assistantTypeHideField.includes("context_trim_strategy")) {
+            const trimStrategyConfig = currentAssistant?.model_configs.find((c) => c.name === "context_trim_strategy");
+            const maxTokensConfig = currentAssistant?.model_configs.find((c) => c.name === "context_max_tokens");
+            baseConfigs.push({
+                key: "context_max_tokens",
+                config: {
+                    type: "input" as const,
+                    label: "上下文 Token 上限",
+                    value: maxTokensConfig?.value ?? "",
+                    tooltip: "对话估算 token 超过该值时裁剪较早的消息，避免超出模型上下文窗口；留空不裁剪",
+                    onChange: (value: string | boolean) =>
+                        handleConfigChange("context_max_tokens", value, "number"),
+                    onBlur: (value: string | boolean) =>
+                        handleConfigChange("context_max_tokens", value as string, "number"),
+                },
+            });
+            baseConfigs.push({
+                key: "context_trim_strategy",
+                config: {
+                    type: "select" as const,
+                    label: "上下文裁剪策略",
+                    value: trimStrategyConfig?.value ?? "drop_oldest",
+                    options: [
+                        { value: "drop_oldest", label: "丢弃最早的消息" },
+                        { value: "summarize_oldest", label: "用对话总结替代较早的消息" },
+                        { value: "keep_last_n", label: "只保留最近的消息" },
+                    ],
+                    tooltip: "系统提示词与当前提问始终保留；总结策略使用对话总结功能生成的总结，尚未生成时直接丢弃",
+                    onChange: (value: string | boolean) =>
+                        handleConfigChange("context_trim_strategy", value, "string"),
+                },
+            });
+        }
+
         if (globalDynamicMcpEnabled && !assistantTypeHideField.includes("dynamic_mcp_loading_enabled")) {
             baseConfigs.push({
                 key: "dynamic_mcp_loading_enabled",
@@ -319,6 +380,23 @@ export const useAssistantFormConfig = ({
             });
         }
 
+        if (!assistantTypeHideField.includes("base_assistant")) {
+            baseConfigs.push({
+                key: "base_assistant",
+                config: {
+                    type: "custom" as const,
+                    label: "基础助手",
+                    customRender: () => {
+                        return React.createElement(AssistantBaseField, {
+                            assistantId: currentAssistant?.assistant.id ?? 0,
+                            assistantType: currentAssistant?.assistant.assistant_type ?? 0,
+                            baseAssistantId: currentAssistant?.base_assistant_id ?? null,
+                        });
+                    },
+                },
+            });
+        }
+
         if (!assistantTypeHideField.includes("prompt")) {
             baseConfigs.push({
                 key: "prompt",
@@ -332,6 +410,22 @@ export const useAssistantFormConfig = ({
             });
         }
 
+        if (!assistantTypeHideField.includes("starters")) {
+            baseConfigs.push({
+                key: "starters",
+                config: {
+                    type: "custom" as const,
+                    label: "开场建议",
+                    customRender: () => {
+                        return React.createElement(AssistantStartersField, {
+                            assistantId: currentAssistant?.assistant.id ?? 0,
+                            starters: currentAssistant?.starters ?? [],
+                        });
+                    },
+                },
+            });
+        }
+
         return baseConfigs;
     }, [
         currentAssistant,
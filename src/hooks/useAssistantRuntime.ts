@@ -373,6 +373,7 @@ export function useAssistantRuntime({
                     assistant_id: +assistantId,
                     assistant_name: "", // Will be populated by backend
                     created_time: new Date(),
+                    tags: [],
                 };
                 // Update ref immediately to make it available to subsequent API calls
                 effectiveConversationRef.current = newConversation;
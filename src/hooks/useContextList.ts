@@ -54,6 +54,7 @@ const parseSearchResultItems = (rawResult: string): SearchResultItem[] | undefin
                 snippet: typeof item?.snippet === 'string' ? item.snippet : '',
                 displayUrl: typeof item?.display_url === 'string' ? item.display_url : undefined,
                 rank: typeof item?.rank === 'number' ? item.rank : undefined,
+                relevanceRank: typeof item?.relevance_rank === 'number' ? item.relevance_rank : undefined,
             }))
             .filter((item: SearchResultItem) => item.title && item.url);
     } catch {
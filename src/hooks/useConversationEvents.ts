@@ -9,6 +9,10 @@ import {
     GroupMergeEvent,
     MCPToolCallUpdateEvent,
     ConversationCancelEvent,
+    ContentFilteredEvent,
+    GenerationProgressEvent,
+    GenerationStage,
+    ModelFallbackEvent,
     StreamCompleteEvent,
     ActivityFocusChangeEvent,
     ActivityFocus,
@@ -66,6 +70,9 @@ export function useConversationEvents(options: UseConversationEventsOptions) {
     // 等待回复的用户消息 ID（只有一个）
     const [pendingUserMessageId, setPendingUserMessageId] = useState<number | null>(null);
 
+    // 当前生成阶段，由后端进度事件驱动，可在首条消息创建前显示状态
+    const [generationStage, setGenerationStage] = useState<GenerationStage | null>(null);
+
     // 活动焦点状态 - 由后端统一管理，优先使用这个状态来控制闪亮边框
     const [activityFocus, setActivityFocus] = useState<ActivityFocus>({ focus_type: 'none' });
     const [runtimeState, setRuntimeState] = useState<ConversationRuntimeState | null>(null);
@@ -603,6 +610,33 @@ export function useConversationEvents(options: UseConversationEventsOptions) {
 
                 // 调用外部的消息更新处理函数
                 callbacksRef.current.onMessageUpdate?.(streamEvent);
+            } else if (
+                conversationEvent.type === "generation_started" ||
+                conversationEvent.type === "reasoning_started" ||
+                conversationEvent.type === "response_started"
+            ) {
+                const progressData = conversationEvent.data as GenerationProgressEvent;
+                console.log(`Received ${conversationEvent.type} event:`, progressData);
+                const stage: GenerationStage =
+                    conversationEvent.type === "reasoning_started"
+                        ? "reasoning"
+                        : conversationEvent.type === "response_started"
+                          ? "response"
+                          : "started";
+                setGenerationStage(stage);
+            } else if (conversationEvent.type === "model_fallback") {
+                // 换用备用模型重新生成，回到等待首个 chunk 的状态
+                const fallbackData = conversationEvent.data as ModelFallbackEvent;
+                console.warn(
+                    `Model ${fallbackData.from_model_name} failed, falling back to ${fallbackData.model_name}`,
+                );
+                setGenerationStage("started");
+            } else if (conversationEvent.type === "content_filtered") {
+                // 提供商审核拦截：filtered 消息已通过 message_add/message_update 展示，这里只收尾等待状态
+                const filteredData = conversationEvent.data as ContentFilteredEvent;
+                console.warn("Received content_filtered event:", filteredData);
+                setPendingUserMessageId(null);
+                setGenerationStage(null);
             } else if (conversationEvent.type === "group_merge") {
                 // 处理组合并事件
                 const groupMergeData =
@@ -680,6 +714,7 @@ export function useConversationEvents(options: UseConversationEventsOptions) {
                 // 立即清理所有流式状态，停止显示闪亮边框和思考计时器
                 setStreamingMessages(new Map());
                 setPendingUserMessageId(null);
+                setGenerationStage(null);
                 setStreamingAssistantMessageIds(new Set());
                 setActiveMcpCallIds(new Set());
                 // 保留已完成的 MCP 工具调用状态（搜索结果等），仅移除进行中的
@@ -718,6 +753,7 @@ export function useConversationEvents(options: UseConversationEventsOptions) {
 
                 // 清理流式与闪烁状态（保留流式消息以承载进行中的 MCP 工具卡片）
                 setStreamingAssistantMessageIds(new Set());
+                setGenerationStage(null);
                 setPendingUserMessageId(null);
                 // 保持 MCP 工具调用状态，避免执行中的边框被清空
                 syncRuntimeState(completionData.conversation_id);
@@ -769,6 +805,7 @@ export function useConversationEvents(options: UseConversationEventsOptions) {
             setActiveMcpCallIds(new Set());
             setStreamingAssistantMessageIds(new Set());
             setPendingUserMessageId(null);
+            setGenerationStage(null);
             setActivityFocus({ focus_type: 'none' });
             setRuntimeState(null);
             setShineState(null);
@@ -940,6 +977,7 @@ export function useConversationEvents(options: UseConversationEventsOptions) {
         shiningMcpCallId,
         shineState,
         pendingUserMessageId,
+        generationStage, // 导出当前生成阶段（generation_started / reasoning_started / response_started）
         streamingAssistantMessageIds, // 导出正在流式输出的 assistant 消息状态
         activityFocus, // 导出活动焦点状态（后端驱动）
         runtimeState, // 导出后端语义化运行态（发送按钮等）
@@ -48,10 +48,15 @@ function useConversationManager() {
     return invoke<number>("fork_conversation", { conversationId, messageId });
   }, []);
 
+  const summarizeConversationNow = useCallback(async (conversationId: number): Promise<void> => {
+    await invoke("summarize_conversation_now", { conversationId });
+  }, []);
+
   return {
     deleteConversation,
     listConversations,
-    forkConversation
+    forkConversation,
+    summarizeConversationNow
   };
 }
 
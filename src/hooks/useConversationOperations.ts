@@ -1,8 +1,9 @@
 import { useCallback, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "sonner";
+import { confirm } from "@tauri-apps/plugin-dialog";
 import { throttle } from "lodash";
-import { Message, Conversation, FileInfo } from "../data/Conversation";
+import { Message, Conversation, FileInfo, RegenerateMode, SendConfirmation } from "../data/Conversation";
 import { AssistantListItem } from "../data/Assistant";
 import { extractAssistantFromMessage } from "../utils/assistantMentions";
 import useConversationManager from "./useConversationManager";
@@ -11,6 +12,31 @@ import useConversationManager from "./useConversationManager";
 interface AiResponse {
     conversation_id: number;
     request_prompt_result_with_context: string;
+    confirmation?: SendConfirmation;
+}
+
+// 发送前确认对话框的内容
+const formatSendConfirmation = (confirmation: SendConfirmation): string => {
+    const lines = [
+        `助手：${confirmation.assistant_name}`,
+        `模型：${confirmation.model_name}`,
+        `预估输入：约 ${confirmation.estimated_input_tokens} tokens`,
+    ];
+    if (confirmation.max_output_tokens != null) {
+        lines.push(`输出上限：${confirmation.max_output_tokens} tokens`);
+    }
+    if (confirmation.estimated_cost != null) {
+        lines.push(`预估费用：${confirmation.estimated_cost.toFixed(4)}`);
+    }
+    lines.push(`可用工具：${confirmation.tools.length > 0 ? confirmation.tools.join("、") : "无"}`);
+    return lines.join("\n");
+};
+
+// edit_message 的返回值，forked 为 true 时消息位于新分支对话中
+interface EditMessageResult {
+    conversation_id: number;
+    message_id: number;
+    forked: boolean;
 }
 
 export interface UseConversationOperationsProps {
@@ -38,7 +64,7 @@ export interface UseConversationOperationsReturn {
     handleDeleteConversationSuccess: () => void;
 
     // 消息操作
-    handleMessageRegenerate: (regenerateMessageId: number) => void;
+    handleMessageRegenerate: (regenerateMessageId: number, mode?: RegenerateMode) => void;
     handleMessageEdit: (message: Message) => void;
     handleMessageFork: (messageId: number) => void;
     handleEditSave: (content: string) => void;
@@ -92,7 +118,7 @@ export function useConversationOperations({
 
     // 消息重新生成处理
     const handleMessageRegenerate = useCallback(
-        (regenerateMessageId: number) => {
+        (regenerateMessageId: number, mode?: RegenerateMode) => {
             // 设置AI响应状态
             setAiIsResponsing(true);
 
@@ -101,6 +127,7 @@ export function useConversationOperations({
 
             invoke<AiResponse>("regenerate_ai", {
                 messageId: regenerateMessageId,
+                mode,
             })
                 .then((res) => {
                     console.log("regenerate ai response", res);
@@ -181,12 +208,20 @@ export function useConversationOperations({
         (content: string) => {
             if (!editingMessage) return;
 
-            // 先更新消息内容
-            invoke("update_message_content", {
+            // 先更新消息内容，后端按助手 / 全局配置决定原地修改还是自动分支到新对话
+            invoke<EditMessageResult>("edit_message", {
                 messageId: editingMessage.id,
                 content: content,
             })
-                .then(() => {
+                .then((result) => {
+                    if (result.forked) {
+                        // 在新对话中重新生成，原对话保持不变
+                        onChangeConversationId(result.conversation_id.toString());
+                        handleMessageRegenerate(result.message_id);
+                        toast.success("已在新分支对话中更新消息并开始重新生成");
+                        return;
+                    }
+
                     // 更新本地消息状态
                     setMessages((prevMessages) =>
                         prevMessages.map((msg) =>
@@ -205,7 +240,7 @@ export function useConversationOperations({
                     toast.error("更新消息失败: " + error);
                 });
         },
-        [editingMessage, handleMessageRegenerate, setMessages],
+        [editingMessage, handleMessageRegenerate, onChangeConversationId, setMessages],
     );
 
     // 代码运行处理
@@ -368,8 +403,23 @@ export function useConversationOperations({
                         attachment_list: fileInfoList?.map((i) => i.id),
                     },
                 })
-                    .then((res) => {
+                    .then(async (res) => {
                         console.log("ask ai response", res);
+                        if (res.confirmation) {
+                            const { token } = res.confirmation;
+                            const confirmed = await confirm(formatSendConfirmation(res.confirmation), {
+                                title: "确认发送",
+                                okLabel: "发送",
+                                cancelLabel: "取消",
+                            });
+                            if (!confirmed) {
+                                await invoke("cancel_send_confirmation", { token });
+                                setAiIsResponsing(false);
+                                updateShiningMessages();
+                                return;
+                            }
+                            res = await invoke<AiResponse>("confirm_and_send", { token });
+                        }
                         if (conversationId != String(res.conversation_id)) {
                             onChangeConversationId(String(res.conversation_id));
                         }
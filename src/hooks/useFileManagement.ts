@@ -21,12 +21,18 @@ const useFileManagement = (onFileSelect?: FileSelectCallback) => {
     }, []);
 
     const isSupportedFile = useCallback((file: File) => {
-        return file.type.startsWith("image/") || file.type === "text/plain";
+        return (
+            file.type.startsWith("image/") ||
+            file.type === "text/plain" ||
+            file.type === "application/pdf"
+        );
     }, []);
 
     const getAttachmentType = useCallback((fileType: string) => {
         if (fileType.startsWith("image/")) {
             return AttachmentType.Image;
+        } else if (fileType === "application/pdf") {
+            return AttachmentType.PDF;
         } else if (fileType === "text/plain") {
             return AttachmentType.Text;
         } else {
@@ -74,7 +80,8 @@ const useFileManagement = (onFileSelect?: FileSelectCallback) => {
                         };
                         reader.onerror = reject;
 
-                        if (file.type.startsWith("image/")) {
+                        // PDF 以 data URL 传给后端解析文本
+                        if (file.type.startsWith("image/") || file.type === "application/pdf") {
                             reader.readAsDataURL(file);
                         } else {
                             reader.readAsText(file);
@@ -117,6 +124,8 @@ const useFileManagement = (onFileSelect?: FileSelectCallback) => {
                         const blob = new Blob([contents]);
                         thumbnail = URL.createObjectURL(blob);
                         type = AttachmentType.Image;
+                    } else if (name.match(/\.pdf$/i)) {
+                        type = AttachmentType.PDF;
                     }
 
                     const newFile: FileInfo = {
@@ -6,6 +6,7 @@ import { customUrlTransform } from '@/constants/markdown';
 import { Button } from '@/components/ui/button';
 import { Send, Loader2 } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { toast } from 'sonner';
 import type { InlineInteractionItem } from '@/components/ConversationUI';
 import { getErrorMessage } from '@/utils/error';
 
@@ -367,7 +368,7 @@ const McpToolCallResultsButton: React.FC<{
         } catch (error) {
             console.error('Failed to send tool results:', error);
             const errorMessage = getErrorMessage(error) || '发送结果失败';
-            console.error(errorMessage);
+            toast.error(errorMessage);
         } finally {
             setIsSending(false);
         }
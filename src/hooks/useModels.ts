@@ -8,6 +8,8 @@ export interface ModelForSelect {
     code: string;
     id: number;
     llm_provider_id: number;
+    // 所属提供商最近一次获取远程模型列表的时间（毫秒时间戳），本次运行未获取过时为 null
+    models_fetched_at?: number | null;
 }
 
 export const useModels = (shouldFetch: boolean = true) => {
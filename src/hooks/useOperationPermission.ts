@@ -3,9 +3,20 @@ import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 import {
     OperationPermissionRequest,
+    OperationPermissionBatchRequest,
+    OperationPermissionDecision,
     AcpPermissionRequest,
 } from "@/components/OperationPermissionDialog";
 import { getErrorMessage } from "@/utils/error";
+import { toast } from "sonner";
+
+/** 被命令策略拒绝的 Bash 命令记录 */
+interface DeniedOperationCommand {
+    command: string;
+    reason: string;
+    conversation_id?: number | null;
+    denied_at: number;
+}
 
 interface UseOperationPermissionOptions {
     /** 当前会话 ID，用于过滤只处理当前会话的权限请求 */
@@ -18,6 +29,18 @@ export function useOperationPermission(options: UseOperationPermissionOptions =
     const [, setRequestQueue] = useState<OperationPermissionRequest[]>([]);
     const [isDialogOpen, setIsDialogOpen] = useState(false);
     const [decisionError, setDecisionError] = useState<string | null>(null);
+    const [pendingBatch, setPendingBatch] = useState<OperationPermissionBatchRequest | null>(null);
+    const [, setBatchQueue] = useState<OperationPermissionBatchRequest[]>([]);
+    const [batchDecisionError, setBatchDecisionError] = useState<string | null>(null);
+
+    const shiftNextBatch = useCallback(() => {
+        setBatchQueue((prev) => {
+            const [, ...rest] = prev;
+            setPendingBatch(rest[0] ?? null);
+            setBatchDecisionError(null);
+            return rest;
+        });
+    }, []);
 
     const shiftNextRequest = useCallback(() => {
         setRequestQueue((prev) => {
@@ -66,8 +89,86 @@ export function useOperationPermission(options: UseOperationPermissionOptions =
         };
     }, [conversationId]);
 
+    useEffect(() => {
+        const unsubscribe = listen<OperationPermissionBatchRequest>(
+            "operation-permission-batch-request",
+            (event) => {
+                // 只保留属于当前会话（或未关联会话）的请求
+                const requests = event.payload.requests.filter(
+                    (request) =>
+                        conversationId === undefined ||
+                        request.conversation_id === undefined ||
+                        request.conversation_id === conversationId
+                );
+                if (requests.length === 0) {
+                    return;
+                }
+
+                const batch = { ...event.payload, requests };
+                console.log("Received operation permission batch request:", batch);
+                setBatchQueue((prev) => {
+                    const next = [...prev, batch];
+                    if (next.length === 1) {
+                        setPendingBatch(batch);
+                        setBatchDecisionError(null);
+                    }
+                    return next;
+                });
+            }
+        );
+
+        return () => {
+            unsubscribe.then((f) => f());
+        };
+    }, [conversationId]);
+
+    useEffect(() => {
+        const unsubscribe = listen<DeniedOperationCommand>("operation-command-denied", (event) => {
+            const record = event.payload;
+            if (
+                conversationId !== undefined &&
+                record.conversation_id != null &&
+                record.conversation_id !== conversationId
+            ) {
+                return;
+            }
+
+            console.warn("Operation command denied by policy:", record);
+            toast.error("命令已被拦截", {
+                description: `${record.command}\n${record.reason}`,
+            });
+        });
+
+        return () => {
+            unsubscribe.then((f) => f());
+        };
+    }, [conversationId]);
+
+    const handleBatchDecision = useCallback(
+        async (decisions: Record<string, OperationPermissionDecision>) => {
+            if (!pendingBatch) {
+                return;
+            }
+            try {
+                console.log("Sending batched permission decisions:", decisions);
+                await invoke("confirm_operation_permission_batch", {
+                    decisions: Object.entries(decisions).map(([request_id, decision]) => ({
+                        request_id,
+                        decision,
+                    })),
+                });
+                shiftNextBatch();
+            } catch (error) {
+                const message = getErrorMessage(error) || "提交权限决策失败";
+                console.error("Failed to send batched permission decisions:", message);
+                setBatchDecisionError(message);
+            }
+        },
+        [pendingBatch, shiftNextBatch]
+    );
+
     const handleDecision = useCallback(
-        async (requestId: string, decision: "allow" | "allow_and_save" | "deny") => {
+        async (requestId: string, decision: OperationPermissionDecision) => {
             if (!pendingRequest || pendingRequest.request_id !== requestId) {
                 return;
             }
@@ -93,6 +194,10 @@ export function useOperationPermission(options: UseOperationPermissionOptions =
         isDialogOpen,
         decisionError,
         handleDecision,
+        pendingBatch,
+        isBatchDialogOpen: pendingBatch !== null,
+        batchDecisionError,
+        handleBatchDecision,
     };
 }
 
@@ -0,0 +1,122 @@
+import { useState, useEffect, useCallback, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+
+export interface PreviewConsoleEntry {
+    level: 'log' | 'info' | 'warn' | 'error' | 'uncaught' | 'unhandledrejection' | string;
+    message: string;
+    stack: string | null;
+    timestamp: number;
+}
+
+export interface PreviewConsoleLog {
+    preview_id: string;
+    conversation_id: number | null;
+    entries: PreviewConsoleEntry[];
+    first_error: PreviewConsoleEntry | null;
+    error_count: number;
+    dropped: number;
+    reported_error_count: number;
+}
+
+interface PreviewConsoleMessage {
+    type: 'aipp_preview_console';
+    preview_id: string;
+    entry: PreviewConsoleEntry;
+}
+
+/** 合并上报的间隔，避免日志刷屏时每条都触发一次 IPC 与全量事件 */
+const REPORT_FLUSH_DELAY_MS = 200;
+
+interface UsePreviewConsoleOptions {
+    /** 承载 React/Vue 预览的 iframe */
+    iframeRef: React.RefObject<HTMLIFrameElement | null>;
+    /** 当前预览类型，只有 react/vue 预览会注入桥接脚本 */
+    previewId: string | null;
+    conversationId?: number | null;
+}
+
+interface UsePreviewConsoleReturn {
+    log: PreviewConsoleLog | null;
+    clear: () => Promise<void>;
+}
+
+/**
+ * 预览控制台 Hook
+ *
+ * 接收预览页面桥接脚本通过 postMessage 发来的 console 输出与未捕获异常，
+ * 按批次上报给后端保存（后端再广播 artifact-preview-console 事件），并返回当前日志用于展示
+ */
+export function usePreviewConsole({ iframeRef, previewId, conversationId }: UsePreviewConsoleOptions): UsePreviewConsoleReturn {
+    const [log, setLog] = useState<PreviewConsoleLog | null>(null);
+    const pendingRef = useRef<PreviewConsoleEntry[]>([]);
+
+    // 切换预览时加载已有日志（如预览窗口刷新后）
+    useEffect(() => {
+        setLog(null);
+        if (!previewId) {
+            return;
+        }
+        invoke<PreviewConsoleLog | null>('get_preview_console_logs', { previewId })
+            .then(setLog)
+            .catch(() => setLog(null));
+    }, [previewId]);
+
+    useEffect(() => {
+        if (!previewId) {
+            return;
+        }
+        let flushTimer: ReturnType<typeof setTimeout> | null = null;
+
+        const flush = () => {
+            flushTimer = null;
+            const entries = pendingRef.current;
+            pendingRef.current = [];
+            if (entries.length === 0) {
+                return;
+            }
+            invoke<PreviewConsoleLog>('report_preview_console', {
+                previewId,
+                conversationId: conversationId ?? null,
+                entries,
+            })
+                .then(setLog)
+                .catch((e) => console.warn('[PreviewConsole] 上报预览日志失败:', e));
+        };
+
+        const handleMessage = (event: MessageEvent) => {
+            // 只接收当前预览 iframe 发出的消息
+            if (!iframeRef.current || event.source !== iframeRef.current.contentWindow) {
+                return;
+            }
+            const message = event.data as PreviewConsoleMessage;
+            if (!message || message.type !== 'aipp_preview_console' || message.preview_id !== previewId) {
+                return;
+            }
+            pendingRef.current.push(message.entry);
+            if (!flushTimer) {
+                flushTimer = setTimeout(flush, REPORT_FLUSH_DELAY_MS);
+            }
+        };
+
+        window.addEventListener('message', handleMessage);
+        return () => {
+            window.removeEventListener('message', handleMessage);
+            // 切换预览或卸载前把尚未上报的日志发出去
+            if (flushTimer) {
+                clearTimeout(flushTimer);
+            }
+            flush();
+        };
+    }, [iframeRef, previewId, conversationId]);
+
+    const clear = useCallback(async () => {
+        if (!previewId) {
+            return;
+        }
+        pendingRef.current = [];
+        await invoke('clear_preview_console_logs', { previewId });
+        setLog(null);
+    }, [previewId]);
+
+    return { log, clear };
+}
@@ -395,6 +395,58 @@ export const conversationExportService = {
         }
     },
 
+    /**
+     * 导出为独立 HTML（只读分享）— 由 Rust 后端渲染完整对话并直接写入文件
+     */
+    async exportToHTML(conversationId: string, filename: string): Promise<boolean> {
+        try {
+            const sanitizedName = sanitizeFilename(filename);
+            const path = await save({
+                defaultPath: `${sanitizedName}.html`,
+                filters: [{ name: "HTML", extensions: ["html"] }],
+            });
+            if (!path) {
+                return false;
+            }
+            await invoke("export_conversation_html", {
+                conversationId: parseInt(conversationId),
+                filePath: path,
+            });
+            this.showExportSuccess("HTML", path);
+            return true;
+        } catch (error) {
+            const errorMessage = error instanceof Error ? error.message : String(error);
+            toast.error(`HTML 导出失败: ${errorMessage}`);
+            throw error;
+        }
+    },
+
+    /**
+     * 导出为 JSON 导出包 — 包含完整消息、重新生成分组与附件引用，可在其他设备上导入
+     */
+    async exportToJSON(conversationId: string, filename: string): Promise<boolean> {
+        try {
+            const sanitizedName = sanitizeFilename(filename);
+            const path = await save({
+                defaultPath: `${sanitizedName}.json`,
+                filters: [{ name: "JSON", extensions: ["json"] }],
+            });
+            if (!path) {
+                return false;
+            }
+            await invoke("export_conversation_json", {
+                conversationId: parseInt(conversationId),
+                filePath: path,
+            });
+            this.showExportSuccess("JSON", path);
+            return true;
+        } catch (error) {
+            const errorMessage = error instanceof Error ? error.message : String(error);
+            toast.error(`JSON 导出失败: ${errorMessage}`);
+            throw error;
+        }
+    },
+
     // ---- 单条消息导出 ----
 
     /**
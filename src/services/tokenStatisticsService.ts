@@ -1,7 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
     ConversationTokenStats,
+    LLMModelPrice,
     MessageTokenStats,
+    UsageInsights,
+    UsageReportFormat,
+    UsageReportGroupBy,
+    UsageReportRange,
 } from "@/data/Conversation";
 
 /**
@@ -48,4 +53,46 @@ export const tokenStatisticsService = {
             throw error;
         }
     },
+
+    /**
+     * 获取使用情况汇总（常用助手、模型、活跃时段等）
+     * @param windowDays 统计最近多少天，不传则统计全部历史
+     */
+    async getUsageInsights(windowDays?: number, limit?: number): Promise<UsageInsights> {
+        try {
+            return await invoke<UsageInsights>("get_usage_insights", {
+                windowDays: windowDays ?? null,
+                limit: limit ?? null,
+            });
+        } catch (error) {
+            console.error("Failed to get usage insights:", error);
+            throw error;
+        }
+    },
+
+    /**
+     * 导出用量与估算费用报表到指定文件
+     * @param pricing 模型单价，未传入时使用已保存的模型单价，未配置的模型费用列留空
+     * @returns 导出的行数
+     */
+    async exportUsageReport(
+        filePath: string,
+        range: UsageReportRange,
+        groupBy: UsageReportGroupBy = "day",
+        format: UsageReportFormat = "csv",
+        pricing?: LLMModelPrice[]
+    ): Promise<number> {
+        try {
+            return await invoke<number>("export_usage_report", {
+                range,
+                groupBy,
+                format,
+                filePath,
+                pricing: pricing ?? null,
+            });
+        } catch (error) {
+            console.error("Failed to export usage report:", error);
+            throw error;
+        }
+    },
 };
@@ -322,6 +322,18 @@ interface AssistantRunApi {
 interface AiResponse {
     conversation_id: number;
     request_prompt_result_with_context: string;
+    // 助手开启发送前确认时返回，此时尚未开始生成
+    confirmation?: {
+        token: string;
+        assistant_id: number;
+        assistant_name: string;
+        model_name: string;
+        estimated_input_tokens: number;
+        max_output_tokens: number | null;
+        estimated_cost: number | null;
+        tools: string[];
+        expires_in_secs: number;
+    };
 }
 
 interface CreateConversationResponse {
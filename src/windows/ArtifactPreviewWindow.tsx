@@ -23,6 +23,8 @@ import SaveArtifactDialog from '../components/SaveArtifactDialog';
 import { useTheme } from '../hooks/useTheme';
 import { useArtifactEvents, ArtifactData, EnvironmentCheckData } from '../hooks/useArtifactEvents';
 import { useArtifactBridge } from '../hooks/useArtifactBridge';
+import { usePreviewConsole } from '../hooks/usePreviewConsole';
+import PreviewConsolePanel from '../components/PreviewConsolePanel';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
@@ -113,6 +115,14 @@ export default function ArtifactPreviewWindow() {
         allowedOrigins: ['http://localhost', 'http://127.0.0.1'],
     });
 
+    // ====== 预览控制台 ======
+    // 收集 React/Vue 预览中的 console 输出与未捕获异常
+    const { log: previewConsoleLog, clear: clearPreviewConsole } = usePreviewConsole({
+        iframeRef: previewIframeRef,
+        previewId: previewType === 'react' || previewType === 'vue' ? previewType : null,
+        conversationId: currentConversationId,
+    });
+
     useEffect(() => {
         invoke<AssistantBasicInfo[]>('artifact_get_assistants')
             .then(setAssistants)
@@ -1137,16 +1147,19 @@ export default function ArtifactPreviewWindow() {
                                 />
                             ) : (
                                 /* iframe 预览 - 用于 React 和 Vue */
-                                <iframe
-                                    ref={previewIframeRef}
-                                    src={previewUrl || ''}
-                                    className="flex-1 w-full border-0"
-                                    sandbox="allow-scripts allow-same-origin allow-forms allow-popups"
-                                    onLoad={() => {
-                                    }}
-                                    onError={() => {
-                                    }}
-                                />
+                                <>
+                                    <iframe
+                                        ref={previewIframeRef}
+                                        src={previewUrl || ''}
+                                        className="flex-1 w-full border-0"
+                                        sandbox="allow-scripts allow-same-origin allow-forms allow-popups"
+                                        onLoad={() => {
+                                        }}
+                                        onError={() => {
+                                        }}
+                                    />
+                                    <PreviewConsolePanel log={previewConsoleLog} onClear={clearPreviewConsole} />
+                                </>
                             )}
                         </div>
                     )}
@@ -19,7 +19,10 @@ import { ShineBorder } from "../components/magicui/shine-border";
 import { DEFAULT_SHINE_BORDER_CONFIG } from "@/utils/shineConfig";
 import { useAppShortcuts } from "../hooks/useAppShortcuts";
 import { useOperationPermission } from "../hooks/useOperationPermission";
-import { OperationPermissionDialog } from "../components/OperationPermissionDialog";
+import {
+    OperationPermissionBatchDialog,
+    OperationPermissionDialog,
+} from "../components/OperationPermissionDialog";
 import { pluginRuntime } from "../services/PluginRuntime";
 const appWindow = getCurrentWebviewWindow();
 
@@ -45,7 +48,16 @@ function AskWindow() {
     const [errorMessage, setErrorMessage] = useState<string>("");
     // 闪亮边框状态管理
     const [shouldShowShineBorder, setShouldShowShineBorder] = useState<boolean>(false);
-    const { pendingRequest, isDialogOpen, decisionError, handleDecision } = useOperationPermission({
+    const {
+        pendingRequest,
+        isDialogOpen,
+        decisionError,
+        handleDecision,
+        pendingBatch,
+        isBatchDialogOpen,
+        batchDecisionError,
+        handleBatchDecision,
+    } = useOperationPermission({
         conversationId: conversationId ? parseInt(conversationId, 10) : undefined,
     });
 
@@ -444,6 +456,12 @@ function AskWindow() {
                     onDecision={handleDecision}
                     errorMessage={decisionError}
                 />
+                <OperationPermissionBatchDialog
+                    batch={pendingBatch}
+                    isOpen={isBatchDialogOpen}
+                    errorMessage={batchDecisionError}
+                    onDecision={handleBatchDecision}
+                />
             </div>
         </div>
     );
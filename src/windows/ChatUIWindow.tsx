@@ -10,6 +10,7 @@ import ConversationUI, {
 } from "../components/ConversationUI";
 import {
     AcpPermissionDialog,
+    OperationPermissionBatchDialog,
     OperationPermissionDialog,
 } from "../components/OperationPermissionDialog";
 import {
@@ -55,7 +56,16 @@ function ChatUIWindow() {
     const conversationUIRef = useRef<ConversationUIRef>(null);
 
     // 操作权限对话框
-    const { pendingRequest, isDialogOpen, decisionError, handleDecision } = useOperationPermission({
+    const {
+        pendingRequest,
+        isDialogOpen,
+        decisionError,
+        handleDecision,
+        pendingBatch,
+        isBatchDialogOpen,
+        batchDecisionError,
+        handleBatchDecision,
+    } = useOperationPermission({
         conversationId: selectedConversation ? parseInt(selectedConversation) : undefined,
     });
     const {
@@ -335,6 +345,12 @@ function ChatUIWindow() {
                         errorMessage={decisionError}
                         onDecision={handleDecision}
                     />
+                    <OperationPermissionBatchDialog
+                        batch={pendingBatch}
+                        isOpen={isBatchDialogOpen}
+                        errorMessage={batchDecisionError}
+                        onDecision={handleBatchDecision}
+                    />
                     <AcpPermissionDialog
                         request={pendingAcpRequest}
                         isOpen={isAcpDialogOpen}
@@ -386,6 +402,12 @@ function ChatUIWindow() {
                     errorMessage={decisionError}
                     onDecision={handleDecision}
                 />
+                <OperationPermissionBatchDialog
+                    batch={pendingBatch}
+                    isOpen={isBatchDialogOpen}
+                    errorMessage={batchDecisionError}
+                    onDecision={handleBatchDecision}
+                />
                 <AcpPermissionDialog
                     request={pendingAcpRequest}
                     isOpen={isAcpDialogOpen}
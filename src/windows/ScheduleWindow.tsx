@@ -22,16 +22,23 @@ import { AssistantListItem } from "@/data/Assistant";
 import { useAssistantListListener } from "@/hooks/useAssistantListListener";
 import { Calendar, Clock, Plus, RefreshCw, Trash2, Pencil, Play, Bell, HelpCircle, Square } from "lucide-react";
 
+type ScheduleType = "once" | "interval" | "cron";
+
 interface ScheduledTask {
     id: number;
     name: string;
     isEnabled: boolean;
-    scheduleType: "once" | "interval";
+    scheduleType: ScheduleType;
     intervalValue?: number | null;
     intervalUnit?: string | null;
     startTime?: string | null;
     weekDays?: number[] | null;
     monthDays?: number[] | null;
+    cron?: string | null;
+    timezone?: string | null;
+    maxRetries: number;
+    retryDelaySecs: number;
+    retryAttempt: number;
     runAt?: string | null;
     nextRunAt?: string | null;
     lastRunAt?: string | null;
@@ -61,6 +68,10 @@ interface ScheduledTaskRun {
     errorMessage?: string | null;
     startedTime: string;
     finishedTime?: string | null;
+    scheduledTime?: string | null;
+    scheduledLocalTime?: string | null;
+    timezone?: string | null;
+    attempt: number;
 }
 
 interface ScheduledTaskRunUpdateEvent {
@@ -84,13 +95,17 @@ interface ScheduledTaskToolLogPayload {
 interface ScheduledTaskFormValues {
     name: string;
     is_enabled: boolean;
-    schedule_type: "once" | "interval";
+    schedule_type: ScheduleType;
     run_at: string;
     interval_value: string;
     interval_unit: string;
     start_time: string;
     week_days: number[];
     month_days: number[];
+    cron: string;
+    timezone: string;
+    max_retries: string;
+    retry_delay_secs: string;
     assistant_id: string;
     task_prompt: string;
     notify_prompt: string;
@@ -99,18 +114,39 @@ interface ScheduledTaskFormValues {
 interface ScheduledTaskSavePayload {
     name: string;
     isEnabled: boolean;
-    scheduleType: "once" | "interval";
+    scheduleType: ScheduleType;
     intervalValue: number | null;
     intervalUnit: string | null;
     startTime: string | null;
     weekDays: number[] | null;
     monthDays: number[] | null;
+    cron: string | null;
+    timezone: string | null;
+    maxRetries: number;
+    retryDelaySecs: number;
     runAt: string | null;
     assistantId: number;
     taskPrompt: string;
     notifyPrompt: string;
 }
 
+// 新建任务默认使用系统时区
+const defaultTimezone = Intl.DateTimeFormat().resolvedOptions().timeZone ?? "";
+
+// 后端返回的任务时区本地时间（RFC 3339 带偏移），直接截取墙上时间展示
+const formatScheduledLocalTime = (value: string) => value.slice(0, 16).replace("T", " ");
+
+const formatUtcTime = (value: string) => {
+    const date = new Date(value);
+    return Number.isNaN(date.getTime()) ? value : `${date.toISOString().slice(0, 19).replace("T", " ")} UTC`;
+};
+
+const scheduleTypeLabels: Record<ScheduleType, string> = {
+    once: "单次",
+    interval: "周期",
+    cron: "Cron",
+};
+
 const intervalUnitLabels: Record<string, string> = {
     minute: "分钟",
     hour: "小时",
@@ -138,6 +174,7 @@ const logTypeLabels: Record<string, string> = {
     tool_result: "工具结果",
     loop_done: "执行完成",
     llm_retry: "重试",
+    task_retry: "失败重试",
     timeout: "超时",
     max_rounds: "达到轮次上限",
     cancel_request: "停止请求",
@@ -363,6 +400,10 @@ export default function ScheduleWindow() {
         start_time: "09:00",
         week_days: [1],
         month_days: [1],
+        cron: "0 9 * * 1-5",
+        timezone: defaultTimezone,
+        max_retries: "0",
+        retry_delay_secs: "60",
         assistant_id: "",
         task_prompt: "",
         notify_prompt: "",
@@ -604,6 +645,10 @@ export default function ScheduleWindow() {
             start_time: "09:00",
             week_days: [1],
             month_days: [1],
+            cron: "0 9 * * 1-5",
+            timezone: defaultTimezone,
+            max_retries: "0",
+            retry_delay_secs: "60",
             assistant_id: assistantOptions[0]?.id.toString() ?? "",
             task_prompt: "",
             notify_prompt: "",
@@ -624,6 +669,10 @@ export default function ScheduleWindow() {
                 start_time: task.startTime ?? "09:00",
                 week_days: task.weekDays ?? [1],
                 month_days: task.monthDays ?? [1],
+                cron: task.cron ?? "0 9 * * 1-5",
+                timezone: task.timezone ?? defaultTimezone,
+                max_retries: String(task.maxRetries ?? 0),
+                retry_delay_secs: String(task.retryDelaySecs ?? 60),
                 assistant_id: task.assistantId.toString(),
                 task_prompt: task.taskPrompt,
                 notify_prompt: task.notifyPrompt || "",
@@ -650,6 +699,10 @@ export default function ScheduleWindow() {
                 startTime: formValues.schedule_type === "interval" && needsStartTime ? formValues.start_time : null,
                 weekDays: formValues.schedule_type === "interval" && formValues.interval_unit === "week" ? formValues.week_days : null,
                 monthDays: formValues.schedule_type === "interval" && formValues.interval_unit === "month" ? formValues.month_days : null,
+                cron: formValues.schedule_type === "cron" ? formValues.cron.trim() : null,
+                timezone: formValues.schedule_type !== "once" && formValues.timezone.trim() ? formValues.timezone.trim() : null,
+                maxRetries: Number(formValues.max_retries),
+                retryDelaySecs: Number(formValues.retry_delay_secs),
                 runAt: formValues.schedule_type === "once" ? toServerDatetime(onceRunAtRaw) : null,
                 assistantId: Number(formValues.assistant_id),
                 taskPrompt: formValues.task_prompt.trim(),
@@ -679,6 +732,15 @@ export default function ScheduleWindow() {
             if (payload.scheduleType === "interval" && (!payload.intervalValue || payload.intervalValue <= 0)) {
                 throw new Error("请设置有效的执行周期");
             }
+            if (!Number.isInteger(payload.maxRetries) || payload.maxRetries < 0 || payload.maxRetries > 10) {
+                throw new Error("失败重试次数需要在 0 到 10 之间");
+            }
+            if (payload.maxRetries > 0 && (!Number.isInteger(payload.retryDelaySecs) || payload.retryDelaySecs < 10)) {
+                throw new Error("重试间隔不能少于 10 秒");
+            }
+            if (payload.scheduleType === "cron" && !payload.cron) {
+                throw new Error("请输入 cron 表达式");
+            }
             if (payload.intervalUnit === "week" && (!payload.weekDays || payload.weekDays.length === 0)) {
                 throw new Error("请至少选择一个星期几");
             }
@@ -738,6 +800,10 @@ export default function ScheduleWindow() {
                         startTime: task.startTime ?? null,
                         weekDays: task.weekDays ?? null,
                         monthDays: task.monthDays ?? null,
+                        cron: task.cron ?? null,
+                        timezone: task.timezone ?? null,
+                        maxRetries: task.maxRetries,
+                        retryDelaySecs: task.retryDelaySecs,
                         runAt: task.runAt ? toServerDatetime(toLocalDatetimeInput(task.runAt)) : null,
                         assistantId: task.assistantId,
                         taskPrompt: task.taskPrompt,
@@ -848,6 +914,10 @@ export default function ScheduleWindow() {
         if (selectedTask.scheduleType === "once") {
             return selectedTask.runAt ? `执行时间: ${new Date(selectedTask.runAt).toLocaleString()}` : "未设置时间";
         }
+        if (selectedTask.scheduleType === "cron") {
+            const desc = `按 cron 表达式 ${selectedTask.cron ?? ""} 执行`;
+            return selectedTask.timezone ? `${desc} (${selectedTask.timezone})` : desc;
+        }
         const value = selectedTask.intervalValue ?? 1;
         const unit = intervalUnitLabels[selectedTask.intervalUnit ?? "hour"] ?? selectedTask.intervalUnit ?? "";
         let desc = `每 ${value} ${unit}`;
@@ -864,7 +934,8 @@ export default function ScheduleWindow() {
             desc += ` ${selectedTask.startTime}`;
         }
 
-        return desc + " 执行";
+        desc += " 执行";
+        return selectedTask.timezone ? `${desc} (${selectedTask.timezone})` : desc;
     }, [selectedTask]);
 
     const hasDetailPrompts = useMemo(() => {
@@ -955,7 +1026,7 @@ export default function ScheduleWindow() {
                                                 {task.name}
                                             </div>
                                             <div className={`text-xs mt-0.5 ${subTextClass}`}>
-                                                {task.scheduleType === "once" ? "单次" : "周期"} · {task.isEnabled ? "已启用" : "已停用"}
+                                                {scheduleTypeLabels[task.scheduleType] ?? "周期"} · {task.isEnabled ? "已启用" : "已停用"}
                                             </div>
                                             {task.nextRunAt && (
                                                 <div className={`text-xs mt-1 flex items-center gap-1 ${subTextClass}`}>
@@ -1158,6 +1229,11 @@ export default function ScheduleWindow() {
                                                     <span className="text-xs text-muted-foreground">
                                                         {new Date(run.startedTime).toLocaleString()}
                                                     </span>
+                                                    {run.attempt > 1 && (
+                                                        <span className="text-[11px] text-muted-foreground">
+                                                            第 {run.attempt} 次尝试
+                                                        </span>
+                                                    )}
                                                     {run.notify && (
                                                         <span className="text-[11px] px-2 py-0.5 rounded-full border border-emerald-300/60 text-emerald-600">
                                                             已通知
@@ -1168,6 +1244,13 @@ export default function ScheduleWindow() {
                                                     {run.runId.slice(0, 8)}
                                                 </span>
                                             </div>
+                                            {run.scheduledLocalTime && (
+                                                <div className="text-[11px] text-muted-foreground">
+                                                    计划 {formatScheduledLocalTime(run.scheduledLocalTime)}
+                                                    {run.timezone ? ` (${run.timezone})` : ""} · 实际{" "}
+                                                    {formatUtcTime(run.startedTime)}
+                                                </div>
+                                            )}
                                             <div className="text-[11px] whitespace-pre-wrap break-words text-muted-foreground line-clamp-2">
                                                 {run.errorMessage || run.summary || "无摘要"}
                                             </div>
@@ -1350,7 +1433,7 @@ export default function ScheduleWindow() {
                             <RadioGroup
                                 value={formValues.schedule_type}
                                 onValueChange={(value) =>
-                                    setFormValues((prev) => ({ ...prev, schedule_type: value as "once" | "interval" }))
+                                    setFormValues((prev) => ({ ...prev, schedule_type: value as ScheduleType }))
                                 }
                                 className="flex flex-col gap-3"
                             >
@@ -1522,9 +1605,67 @@ export default function ScheduleWindow() {
                                         )}
                                     </div>
                                 </div>
+                                <div className="flex items-start gap-2">
+                                    <RadioGroupItem value="cron" id="schedule-cron" className="mt-0.5" />
+                                    <div className="flex-1 space-y-1.5">
+                                        <Label htmlFor="schedule-cron" className="text-xs">按 cron 表达式执行</Label>
+                                        <Input
+                                            value={formValues.cron}
+                                            onChange={(e) => setFormValues((prev) => ({ ...prev, cron: e.target.value }))}
+                                            disabled={formValues.schedule_type !== "cron"}
+                                            placeholder="0 9 * * 1-5"
+                                            className="h-8 text-sm font-mono w-48"
+                                        />
+                                        <div className="text-[11px] text-muted-foreground">
+                                            格式为「分 时 日 月 星期」，按任务时区计算，例如 0 9 * * 1-5 表示工作日 9 点
+                                        </div>
+                                    </div>
+                                </div>
                             </RadioGroup>
                         </div>
 
+                        <div className="space-y-1.5">
+                            <Label className="text-xs">失败重试</Label>
+                            <div className="flex items-center gap-2">
+                                <Input
+                                    type="number"
+                                    min={0}
+                                    max={10}
+                                    value={formValues.max_retries}
+                                    onChange={(e) => setFormValues((prev) => ({ ...prev, max_retries: e.target.value }))}
+                                    className="h-8 text-sm w-20"
+                                />
+                                <span className="text-xs text-muted-foreground">次，首次间隔</span>
+                                <Input
+                                    type="number"
+                                    min={10}
+                                    value={formValues.retry_delay_secs}
+                                    onChange={(e) => setFormValues((prev) => ({ ...prev, retry_delay_secs: e.target.value }))}
+                                    disabled={Number(formValues.max_retries) <= 0}
+                                    className="h-8 text-sm w-24"
+                                />
+                                <span className="text-xs text-muted-foreground">秒</span>
+                            </div>
+                            <div className="text-[11px] text-muted-foreground">
+                                执行失败后自动重试，每次重试的间隔翻倍；手动停止的执行不会重试
+                            </div>
+                        </div>
+
+                        {formValues.schedule_type !== "once" && (
+                            <div className="space-y-1.5">
+                                <Label className="text-xs">时区</Label>
+                                <Input
+                                    value={formValues.timezone}
+                                    onChange={(e) => setFormValues((prev) => ({ ...prev, timezone: e.target.value }))}
+                                    placeholder={defaultTimezone || "Asia/Shanghai"}
+                                    className="h-8 text-sm w-56"
+                                />
+                                <div className="text-[11px] text-muted-foreground">
+                                    IANA 时区名，如 Asia/Shanghai、America/New_York；留空使用系统时区。夏令时跳过的时间会顺延到之后第一个有效时刻
+                                </div>
+                            </div>
+                        )}
+
                         <div className="space-y-1.5">
                             <Label className="text-xs">任务指令</Label>
                             <Textarea